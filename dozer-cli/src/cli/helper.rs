@@ -1,3 +1,4 @@
+use crate::cli::secrets::resolve_secrets;
 use crate::config_helper::combine_config;
 use crate::errors::CliError;
 use crate::errors::CliError::{ConfigurationFilePathNotProvided, FailedToFindConfigurationFiles};
@@ -13,11 +14,11 @@ use dozer_types::prettytable::{row, Table};
 use dozer_types::serde_json;
 use dozer_types::tracing::info;
 use dozer_types::{models::config::Config, serde_yaml};
-use handlebars::Handlebars;
+use handlebars::{Context, Handlebars, Helper, HelperResult, Output, RenderContext, RenderError};
 use std::collections::{BTreeMap, HashSet};
 use std::env;
 use std::io::{self, Read};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::runtime::Runtime;
 
 pub async fn init_config(
@@ -119,7 +120,7 @@ async fn load_config(
                     vec![path.to_owned()],
                 ))
             } else {
-                load_config_from_file(config_url_or_paths, read_stdin)
+                load_config_from_file(config_url_or_paths, read_stdin).await
             }
         }
     }
@@ -136,10 +137,10 @@ async fn load_config_from_http_url(
     }
     let response: reqwest::Response = get_request.send().await?.error_for_status()?;
     let contents = response.text().await?;
-    parse_config(&contents)
+    parse_config(&contents).await
 }
 
-pub fn load_config_from_file(
+pub async fn load_config_from_file(
     config_path: Vec<String>,
     read_stdin: bool,
 ) -> Result<(Config, Vec<String>), CliError> {
@@ -168,26 +169,27 @@ pub fn load_config_from_file(
         .collect();
 
     match config_template {
-        Some(template) => Ok((parse_config(&template)?, config_files_with_path)),
+        Some(template) => Ok((parse_config(&template).await?, config_files_with_path)),
         None => Err(FailedToFindConfigurationFiles(config_path.join(", "))),
     }
 }
 
-fn parse_config(config_template: &str) -> Result<Config, CliError> {
-    let mut handlebars = Handlebars::new();
-    handlebars
-        .register_template_string("config", config_template)
-        .map_err(|e| CliError::FailedToParseYaml(Box::new(e)))?;
-
-    let mut data = BTreeMap::new();
-
-    for (key, value) in std::env::vars() {
-        data.insert(key, value);
-    }
-
-    let config_str = handlebars
-        .render("config", &data)
-        .map_err(|e| CliError::FailedToParseYaml(Box::new(e)))?;
+async fn parse_config(config_template: &str) -> Result<Config, CliError> {
+    let data = std::env::vars().collect::<BTreeMap<_, _>>();
+
+    // Resolving secrets may need network access, which a helper can't wait for.
+    // So the first render only collects the secret references, and the second one fills in their values.
+    let references = Arc::new(Mutex::new(vec![]));
+    let collected = references.clone();
+    render_config(config_template, &data, move |reference| {
+        collected.lock().unwrap().push(reference.to_string());
+        String::new()
+    })?;
+    let references = std::mem::take(&mut *references.lock().unwrap());
+    let secrets = resolve_secrets(references).await?;
+    let config_str = render_config(config_template, &data, move |reference| {
+        secrets[reference].clone()
+    })?;
 
     let config: Config = serde_yaml::from_str(&config_str)
         .map_err(|e: serde_yaml::Error| CliError::FailedToParseYaml(Box::new(e)))?;
@@ -195,6 +197,50 @@ fn parse_config(config_template: &str) -> Result<Config, CliError> {
     Ok(config)
 }
 
+/// Renders the config with environment variables as data, and `{{secret "<reference>"}}` replaced by `secret(reference)`.
+///
+/// Secrets are written as double-quoted scalars, so they must be whole values and whatever they contain
+/// can't change the structure of the YAML.
+fn render_config(
+    config_template: &str,
+    data: &BTreeMap<String, String>,
+    secret: impl Fn(&str) -> String + Send + Sync + 'static,
+) -> Result<String, CliError> {
+    let mut handlebars = Handlebars::new();
+    handlebars
+        .register_template_string("config", config_template)
+        .map_err(|e| CliError::FailedToParseYaml(Box::new(e)))?;
+    handlebars.register_helper(
+        "secret",
+        Box::new(
+            move |helper: &Helper,
+                  _: &Handlebars,
+                  _: &Context,
+                  _: &mut RenderContext,
+                  out: &mut dyn Output|
+                  -> HelperResult {
+                let reference = helper
+                    .param(0)
+                    .and_then(|param| param.value().as_str())
+                    .ok_or_else(|| {
+                        RenderError::new(
+                            "`secret` takes a reference like \"file:/run/secrets/password\"",
+                        )
+                    })?;
+                // A JSON string is also a valid YAML double-quoted scalar.
+                let value = serde_json::to_string(&secret(reference))
+                    .expect("serializing a string must succeed");
+                out.write(&value)?;
+                Ok(())
+            },
+        ),
+    );
+
+    handlebars
+        .render("config", data)
+        .map_err(|e| CliError::FailedToParseYaml(Box::new(e)))
+}
+
 /// Convert `config` to JSON, apply JSON pointer overrides, then convert back to `Config`.
 fn apply_overrides(
     config: &Config,
@@ -275,4 +321,36 @@ mod tests {
         .unwrap();
         assert_eq!(config.api.api_security.unwrap(), api_security);
     }
+
+    #[tokio::test]
+    async fn test_parse_config_with_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwt");
+        std::fs::write(&path, "secret1\n").unwrap();
+
+        let config = parse_config(&format!(
+            "version: 1\napp_name: test\napi:\n  api_security: !Jwt {{{{secret \"file:{}\"}}}}\n",
+            path.display()
+        ))
+        .await
+        .unwrap();
+        assert_eq!(
+            config.api.api_security.unwrap(),
+            ApiSecurity::Jwt("secret1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_render_config_quotes_secret() {
+        let secret = "it's # not: a comment\napp_name: injected \"\\";
+        let config_str = render_config(
+            "version: 1\napp_name: test\nsql: {{secret \"file:sql\"}}\n",
+            &BTreeMap::new(),
+            move |_| secret.to_string(),
+        )
+        .unwrap();
+        let config: Config = serde_yaml::from_str(&config_str).unwrap();
+        assert_eq!(config.app_name, "test");
+        assert_eq!(config.sql.unwrap(), secret);
+    }
 }
@@ -124,10 +124,13 @@ pub fn generate_connection(connection_name: &str) -> Connection {
         }
         "S3" | "s3" => {
             let s3_details = S3Details {
-                access_key_id: "<your_access_key_id>".to_owned(),
-                secret_access_key: "<your_secret_access_key>".to_owned(),
+                access_key_id: Some("<your_access_key_id>".to_owned()),
+                secret_access_key: Some("<your_secret_access_key>".to_owned()),
+                session_token: None,
                 region: "<your_region>".to_owned(),
                 bucket_name: "<your_bucket_name>".to_owned(),
+                endpoint: None,
+                virtual_hosted_style: false,
             };
             let s3_config = S3Storage {
                 details: s3_details,
@@ -161,6 +164,9 @@ pub fn generate_connection(connection_name: &str) -> Connection {
                 connection_url: None,
                 schema: None,
                 batch_size: None,
+                snapshot_chunk_size: None,
+                snapshot_parallelism: None,
+                incremental_snapshot: None,
             };
             let connection: Connection = Connection {
                 name: "postgres".to_owned(),
@@ -1,6 +1,7 @@
 pub mod cloud;
 mod helper;
 mod init;
+pub mod secrets;
 pub mod types;
 pub use helper::{
     get_base_dir, init_config, init_dozer, list_sources, load_config_from_file, LOGO,
@@ -0,0 +1,221 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use dozer_types::{
+    serde::{Deserialize, Serialize},
+    serde_json,
+};
+use sha2::Sha256;
+
+use crate::errors::SecretError;
+
+const PASSPHRASE_ENV: &str = "DOZER_KEYRING_PASSPHRASE";
+const PATH_ENV: &str = "DOZER_KEYRING_PATH";
+/// PBKDF2-HMAC-SHA256 iterations for new keyrings, as recommended by OWASP.
+const DEFAULT_ROUNDS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct KeyringFile {
+    salt: String,
+    rounds: u32,
+    secrets: BTreeMap<String, EncryptedSecret>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+struct EncryptedSecret {
+    nonce: String,
+    ciphertext: String,
+}
+
+/// A local file of secrets, each encrypted with AES-256-GCM under a key derived from a passphrase.
+pub struct Keyring {
+    path: PathBuf,
+    cipher: Aes256Gcm,
+    file: KeyringFile,
+}
+
+impl Keyring {
+    /// `DOZER_KEYRING_PATH`, or `~/.dozer/keyring.json`.
+    pub fn default_path() -> PathBuf {
+        env::var(PATH_ENV).map(PathBuf::from).unwrap_or_else(|_| {
+            let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            Path::new(&home_dir).join(".dozer").join("keyring.json")
+        })
+    }
+
+    /// Opens the keyring at [`Keyring::default_path`] with the passphrase in `DOZER_KEYRING_PASSPHRASE`.
+    pub fn open_default() -> Result<Self, SecretError> {
+        let passphrase =
+            env::var(PASSPHRASE_ENV).map_err(|_| SecretError::MissingKeyringPassphrase)?;
+        Self::open(Self::default_path(), &passphrase)
+    }
+
+    /// Opens the keyring at `path`, or an empty one that's created on the first [`Keyring::set`].
+    pub fn open(path: PathBuf, passphrase: &str) -> Result<Self, SecretError> {
+        Self::open_with_rounds(path, passphrase, DEFAULT_ROUNDS)
+    }
+
+    fn open_with_rounds(path: PathBuf, passphrase: &str, rounds: u32) -> Result<Self, SecretError> {
+        let file = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| SecretError::MalformedKeyring(path.clone(), e.to_string()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let mut salt = [0; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                KeyringFile {
+                    salt: hex::encode(salt),
+                    rounds,
+                    secrets: BTreeMap::new(),
+                }
+            }
+            Err(e) => return Err(SecretError::KeyringFileSystem(path, e)),
+        };
+
+        let salt = hex::decode(&file.salt)
+            .map_err(|e| SecretError::MalformedKeyring(path.clone(), e.to_string()))?;
+        let mut key = [0; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), &salt, file.rounds, &mut key);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+
+        let keyring = Self { path, cipher, file };
+        // Fail early on a wrong passphrase, instead of adding secrets that can't be read along with the others.
+        if let Some(name) = keyring.file.secrets.keys().next() {
+            keyring.get(name)?;
+        }
+        Ok(keyring)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.file.secrets.keys().map(String::as_str)
+    }
+
+    pub fn get(&self, name: &str) -> Result<String, SecretError> {
+        let secret = self
+            .file
+            .secrets
+            .get(name)
+            .ok_or_else(|| SecretError::NotInKeyring(name.to_string()))?;
+        let malformed = |e: hex::FromHexError| {
+            SecretError::MalformedKeyring(self.path.clone(), format!("secret {name:?}: {e}"))
+        };
+        let nonce = hex::decode(&secret.nonce).map_err(malformed)?;
+        let ciphertext = hex::decode(&secret.ciphertext).map_err(malformed)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(SecretError::MalformedKeyring(
+                self.path.clone(),
+                format!("secret {name:?} has a nonce of {} bytes", nonce.len()),
+            ));
+        }
+
+        // The name is authenticated with the value, so values can't be swapped between names.
+        let plaintext = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_| SecretError::KeyringDecryption(self.path.clone()))?;
+        String::from_utf8(plaintext).map_err(|e| {
+            SecretError::MalformedKeyring(self.path.clone(), format!("secret {name:?}: {e}"))
+        })
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), SecretError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: value.as_bytes(),
+                    aad: name.as_bytes(),
+                },
+            )
+            .expect("AES-GCM encryption of a secret can't fail");
+        self.file.secrets.insert(
+            name.to_string(),
+            EncryptedSecret {
+                nonce: hex::encode(nonce),
+                ciphertext: hex::encode(ciphertext),
+            },
+        );
+        self.save()
+    }
+
+    /// Returns whether the secret was in the keyring.
+    pub fn remove(&mut self, name: &str) -> Result<bool, SecretError> {
+        if self.file.secrets.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    fn save(&self) -> Result<(), SecretError> {
+        let error = |e| SecretError::KeyringFileSystem(self.path.clone(), e);
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(error)?;
+        }
+        let content =
+            serde_json::to_string_pretty(&self.file).expect("Keyring must be serializable");
+        fs::write(&self.path, content).map_err(error)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600)).map_err(error)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keeps key derivation fast in debug builds.
+    const TEST_ROUNDS: u32 = 1_000;
+
+    #[test]
+    fn test_keyring_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keyring.json");
+
+        let mut keyring =
+            Keyring::open_with_rounds(path.clone(), "passphrase", TEST_ROUNDS).unwrap();
+        keyring.set("pg_password", "hunter2").unwrap();
+        keyring.set("s3_secret", "correct horse").unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("hunter2"));
+
+        let mut keyring = Keyring::open(path.clone(), "passphrase").unwrap();
+        assert_eq!(keyring.get("pg_password").unwrap(), "hunter2");
+        assert_eq!(
+            keyring.names().collect::<Vec<_>>(),
+            vec!["pg_password", "s3_secret"]
+        );
+        assert!(keyring.remove("s3_secret").unwrap());
+        assert!(!keyring.remove("s3_secret").unwrap());
+        assert!(matches!(
+            keyring.get("s3_secret"),
+            Err(SecretError::NotInKeyring(_))
+        ));
+
+        assert!(matches!(
+            Keyring::open(path, "wrong passphrase"),
+            Err(SecretError::KeyringDecryption(_))
+        ));
+    }
+}
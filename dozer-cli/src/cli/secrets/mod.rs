@@ -0,0 +1,179 @@
+//! Secrets the config references as `{{secret "<provider>:<name>"}}` instead of holding them in plain text.
+//!
+//! - `file:<path>` reads a file, like a mounted Docker or Kubernetes secret.
+//! - `keyring:<name>` reads from the encrypted keyring managed with `dozer secret`.
+//! - `vault:<path>#<key>` reads a key of a HashiCorp Vault secret. `path` is the secret's API path,
+//!   e.g. `secret/data/dozer` for the KV version 2 engine. The server is `VAULT_ADDR` and the token `VAULT_TOKEN`.
+//!
+//! A reference expands to a quoted string, so it stands for a whole value, e.g. `password: {{secret "file:pw"}}`.
+//!
+//! Resolved values are registered with [`dozer_types::redact`], so they're redacted from printed configs and logs.
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use dozer_types::redact::register_secret;
+
+use crate::cli::types::SecretCommands;
+use crate::errors::SecretError;
+
+mod keyring;
+mod vault;
+
+pub use keyring::Keyring;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretReference {
+    File(PathBuf),
+    Keyring(String),
+    Vault { path: String, key: String },
+}
+
+impl FromStr for SecretReference {
+    type Err = SecretError;
+
+    fn from_str(reference: &str) -> Result<Self, Self::Err> {
+        let invalid = || SecretError::InvalidReference(reference.to_string());
+        let (provider, name) = reference.split_once(':').ok_or_else(invalid)?;
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        match provider {
+            "file" => Ok(SecretReference::File(name.into())),
+            "keyring" => Ok(SecretReference::Keyring(name.to_string())),
+            "vault" => {
+                let (path, key) = name.rsplit_once('#').ok_or_else(invalid)?;
+                if path.is_empty() || key.is_empty() {
+                    return Err(invalid());
+                }
+                Ok(SecretReference::Vault {
+                    path: path.to_string(),
+                    key: key.to_string(),
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl SecretReference {
+    pub async fn resolve(&self) -> Result<String, SecretError> {
+        match self {
+            SecretReference::File(path) => {
+                let value = tokio::fs::read_to_string(path)
+                    .await
+                    .map_err(|e| SecretError::FileSystem(path.clone(), e))?;
+                // Files written by editors or `echo` end with a newline that isn't part of the secret.
+                Ok(value.trim_end_matches(['\n', '\r']).to_string())
+            }
+            SecretReference::Keyring(name) => Keyring::open_default()?.get(name),
+            SecretReference::Vault { path, key } => vault::read(path, key).await,
+        }
+    }
+}
+
+/// Resolves every reference, registering the values for redaction. The keyring is only opened once.
+pub async fn resolve_secrets(
+    references: Vec<String>,
+) -> Result<HashMap<String, String>, SecretError> {
+    let mut keyring = None;
+    let mut secrets = HashMap::new();
+    for reference in references {
+        if secrets.contains_key(&reference) {
+            continue;
+        }
+        let value = match reference.parse::<SecretReference>()? {
+            SecretReference::Keyring(name) => {
+                if keyring.is_none() {
+                    keyring = Some(Keyring::open_default()?);
+                }
+                keyring
+                    .as_ref()
+                    .expect("keyring was just opened")
+                    .get(&name)?
+            }
+            other => other.resolve().await?,
+        };
+        register_secret(&value);
+        secrets.insert(reference, value);
+    }
+    Ok(secrets)
+}
+
+pub fn run_secret_command(command: &SecretCommands) -> Result<(), SecretError> {
+    let mut keyring = Keyring::open_default()?;
+    match command {
+        SecretCommands::Set { name } => {
+            let mut value = String::new();
+            io::stdin()
+                .lock()
+                .read_line(&mut value)
+                .map_err(|e| SecretError::FileSystem("<stdin>".into(), e))?;
+            keyring.set(name, value.trim_end_matches(['\n', '\r']))?;
+        }
+        SecretCommands::Ls => {
+            for name in keyring.names() {
+                println!("{name}");
+            }
+        }
+        SecretCommands::Rm { name } => {
+            if !keyring.remove(name)? {
+                return Err(SecretError::NotInKeyring(name.clone()));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::{models::ingestion_types::SECRET, redact::redact};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_secret_reference() {
+        assert_eq!(
+            "file:/run/secrets/pg".parse::<SecretReference>().unwrap(),
+            SecretReference::File("/run/secrets/pg".into())
+        );
+        assert_eq!(
+            "keyring:pg_password".parse::<SecretReference>().unwrap(),
+            SecretReference::Keyring("pg_password".to_string())
+        );
+        assert_eq!(
+            "vault:secret/data/dozer#password"
+                .parse::<SecretReference>()
+                .unwrap(),
+            SecretReference::Vault {
+                path: "secret/data/dozer".to_string(),
+                key: "password".to_string()
+            }
+        );
+        for invalid in [
+            "pg_password",
+            "file:",
+            "env:PG_PASSWORD",
+            "vault:secret/data/dozer",
+            "vault:#password",
+        ] {
+            assert!(invalid.parse::<SecretReference>().is_err(), "{invalid}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_file_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pg");
+        std::fs::write(&path, "hunter2\n").unwrap();
+
+        let reference = format!("file:{}", path.display());
+        let secrets = resolve_secrets(vec![reference.clone()]).await.unwrap();
+        assert_eq!(secrets[&reference], "hunter2");
+        assert_eq!(redact("password=hunter2"), format!("password={SECRET}"));
+    }
+}
@@ -0,0 +1,85 @@
+use std::env;
+
+use dozer_types::serde_json::{self, Value};
+
+use crate::errors::SecretError;
+
+/// Address of `vault server -dev`.
+const DEFAULT_VAULT_ADDR: &str = "http://127.0.0.1:8200";
+
+fn address() -> String {
+    env::var("VAULT_ADDR").unwrap_or_else(|_| DEFAULT_VAULT_ADDR.to_string())
+}
+
+/// Reads `key` of the secret at API path `path`.
+pub async fn read(path: &str, key: &str) -> Result<String, SecretError> {
+    let token = env::var("VAULT_TOKEN").map_err(|_| SecretError::MissingVaultToken)?;
+    let url = format!(
+        "{}/v1/{}",
+        address().trim_end_matches('/'),
+        path.trim_start_matches('/')
+    );
+    let vault_error = |e| SecretError::Vault(path.to_string(), e);
+    let text = reqwest::Client::new()
+        .get(url)
+        .header("X-Vault-Token", token)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(vault_error)?
+        .text()
+        .await
+        .map_err(vault_error)?;
+    let response: Value = serde_json::from_str(&text)
+        .map_err(|e| SecretError::MalformedVaultResponse(path.to_string(), e))?;
+
+    // The KV version 2 engine nests the secret in `data.data`, version 1 returns it as `data`.
+    let data = &response["data"];
+    let data = if data["data"].is_object() {
+        &data["data"]
+    } else {
+        data
+    };
+    match &data[key] {
+        Value::Null => Err(SecretError::NotInVault {
+            path: path.to_string(),
+            key: key.to_string(),
+        }),
+        Value::String(value) => Ok(value.clone()),
+        value => Ok(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Needs `vault server -dev`, with `VAULT_ADDR` and `VAULT_TOKEN` set to its address and root token.
+    #[tokio::test]
+    #[ignore]
+    async fn test_vault_dev_server() {
+        let token = env::var("VAULT_TOKEN").unwrap();
+        reqwest::Client::new()
+            .post(format!("{}/v1/secret/data/dozer-test", address()))
+            .header("X-Vault-Token", token)
+            .body(r#"{"data": {"password": "hunter2", "port": 5432}}"#)
+            .send()
+            .await
+            .unwrap()
+            .error_for_status()
+            .unwrap();
+
+        assert_eq!(
+            read("secret/data/dozer-test", "password").await.unwrap(),
+            "hunter2"
+        );
+        assert_eq!(
+            read("secret/data/dozer-test", "port").await.unwrap(),
+            "5432"
+        );
+        assert!(matches!(
+            read("secret/data/dozer-test", "user").await,
+            Err(SecretError::NotInVault { .. })
+        ));
+    }
+}
@@ -54,11 +54,173 @@ pub enum Commands {
     #[command(about = "Build YAML definitions as a dozer pipeline")]
     Build(Build),
     #[command(about = "Run a replication instance with the provided configuration")]
-    Run,
+    Run(Run),
+    #[command(
+        about = "Create, list and describe checkpoints",
+        long_about = "Create named checkpoints of a running pipeline, list and describe existing \
+            checkpoints. Restore one with `dozer run --from-checkpoint <id>`"
+    )]
+    Savepoint(Savepoint),
+    #[command(
+        about = "Inspect connections and preview their data",
+        long_about = "List the configured connections, describe the schemas of their tables, \
+            validate them and sample rows from a table, without building a pipeline"
+    )]
+    Connector(Connector),
+    #[command(
+        about = "Preview the output of a SQL query",
+        long_about = "Run a query against a sample of the configured sources' tables, read \
+            without changing the sources, and print the changes it outputs. Stops after the given \
+            number of changes or seconds. Queries without `INTO` output to a table named `preview`"
+    )]
+    Sql(Sql),
+    #[command(
+        about = "Show the operators a pipeline is made of",
+        long_about = "Print the operators the configured SQL, or the given query, turns into, with \
+            the schema and primary key each outputs. With `--analyze`, keep printing their \
+            throughput, latency and state sizes, read from the metrics of a running `dozer run`, \
+            or of the given query run against a sample of the configured sources"
+    )]
+    Explain(Explain),
+    #[command(
+        about = "Manage the encrypted keyring config secrets can be read from",
+        long_about = "Set, list and remove secrets the config can reference as \
+            `{{secret \"keyring:<name>\"}}`. The keyring is encrypted with the passphrase in \
+            DOZER_KEYRING_PASSPHRASE, and stored at DOZER_KEYRING_PATH or ~/.dozer/keyring.json"
+    )]
+    Secret(Secret),
     #[command(about = "Run UI server")]
     UI(UI),
 }
 
+#[derive(Debug, Args)]
+pub struct Run {
+    #[arg(
+        help = "Restore the checkpoint with this epoch id or savepoint name instead of the latest one",
+        long = "from-checkpoint"
+    )]
+    pub from_checkpoint: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct Savepoint {
+    #[command(subcommand)]
+    pub command: SavepointCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SavepointCommands {
+    #[command(
+        about = "Request a named checkpoint from the running pipeline",
+        long_about = "Request a named checkpoint from the pipeline started by `dozer run` and wait \
+            until it's written. The checkpoint is taken at the next transaction boundary"
+    )]
+    Create {
+        name: String,
+        #[arg(
+            help = "Seconds to wait for the savepoint to be written",
+            long,
+            default_value_t = 60
+        )]
+        timeout: u64,
+    },
+    #[command(about = "List checkpoints of the latest build")]
+    List,
+    #[command(about = "Describe a checkpoint by epoch id or savepoint name")]
+    Describe { id: String },
+}
+
+#[derive(Debug, Args)]
+pub struct Connector {
+    #[command(subcommand)]
+    pub command: ConnectorCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConnectorCommands {
+    #[command(about = "List connections and the sources reading from them")]
+    Ls,
+    #[command(
+        about = "Describe the tables of a connection",
+        long_about = "Print the Dozer schema and CDC type of a table, or of all tables of the \
+            connection if no table is given. Tables can be given as `table` or `schema.table`"
+    )]
+    Describe {
+        connection: String,
+        table: Option<String>,
+    },
+    #[command(
+        about = "Validate connections and the tables sources read from them",
+        long_about = "Validate a connection, or all connections if none is given, and check that \
+            the tables and columns sources read from them exist and have supported types"
+    )]
+    Validate { connection: Option<String> },
+    #[command(
+        about = "Print rows of a table",
+        long_about = "Print rows of a table, read without changing anything in the source. \
+            Supported by Postgres, MySQL and object store connections"
+    )]
+    Sample {
+        connection: String,
+        table: String,
+        #[arg(help = "Number of rows to print", short = 'n', default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct Sql {
+    pub query: String,
+    #[arg(help = "Number of changes to print", short = 'n', default_value_t = 20)]
+    pub limit: usize,
+    #[arg(
+        help = "Rows read from each source table",
+        long,
+        default_value_t = 1000
+    )]
+    pub sample_rows: usize,
+    #[arg(help = "Seconds to run the query for", long, default_value_t = 10)]
+    pub timeout: u64,
+    #[arg(help = "Print one JSON object per change instead of a table", long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct Explain {
+    #[arg(help = "Query to explain instead of the configured SQL")]
+    pub query: Option<String>,
+    #[arg(help = "Print processor statistics until interrupted", long)]
+    pub analyze: bool,
+    #[arg(help = "Seconds between statistics", long, default_value_t = 5)]
+    pub interval: u64,
+    #[arg(
+        help = "Rows read from each source table when analyzing a query",
+        long,
+        default_value_t = 1000
+    )]
+    pub sample_rows: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct Secret {
+    #[command(subcommand)]
+    pub command: SecretCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SecretCommands {
+    #[command(
+        about = "Add or replace a secret",
+        long_about = "Add or replace a secret. The value is read from the first line of stdin, \
+            so it doesn't end up in the shell history"
+    )]
+    Set { name: String },
+    #[command(about = "List the names of the secrets")]
+    Ls,
+    #[command(about = "Remove a secret")]
+    Rm { name: String },
+}
+
 #[derive(Debug, Args)]
 pub struct UI {
     #[command(subcommand)]
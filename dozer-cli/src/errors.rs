@@ -52,6 +52,8 @@ pub enum OrchestrationError {
     GrpcServeFailed(#[source] tonic::transport::Error),
     #[error("Failed to server pgwire: {0}")]
     PGWireServerFailed(#[source] std::io::Error),
+    #[error("Failed to serve health endpoints: {0}")]
+    HealthServeFailed(#[source] std::io::Error),
     #[error("Cache {0} has reached its maximum size. Try to increase `cache_max_map_size` in the config.")]
     CacheFull(String),
     #[error("Internal thread panic: {0}")]
@@ -76,6 +78,10 @@ pub enum OrchestrationError {
     EndpointTableNotFound(String),
     #[error("No endpoints initialized in the config provided")]
     EmptyEndpoints,
+    #[error("Lambda subscribes to endpoint {0:?}, which is not in sinks")]
+    LambdaEndpointNotFound(String),
+    #[error(transparent)]
+    Lambda(#[from] dozer_lambda::Error),
     #[error(transparent)]
     CloudContextError(#[from] CloudContextError),
     #[error("Failed to read organisation name. Error: {0}")]
@@ -90,6 +96,16 @@ pub enum OrchestrationError {
     LockedNoLockFile,
     #[error("Command was aborted")]
     Aborted,
+    #[error("Checkpoint {0} already exists")]
+    SavepointAlreadyExists(String),
+    #[error("Savepoint {0} was not written in time. Is `dozer run` running?")]
+    SavepointTimeout(String),
+    #[error("Table {table:?} not found in connection {connection:?}")]
+    ConnectorTableNotFound { connection: String, table: String },
+    #[error("Connection validation failed")]
+    ConnectorValidationFailed,
+    #[error("Failed to read metrics from {0}: {1}. Is the pipeline running?")]
+    MetricsUnavailable(String, #[source] BoxedError),
 }
 
 #[derive(Error, Debug)]
@@ -120,6 +136,8 @@ pub enum CliError {
     MissingConfigOverride(String),
     #[error("Failed to deserialize config from json: {0}")]
     DeserializeConfigFromJson(#[source] serde_json::Error),
+    #[error(transparent)]
+    Secret(#[from] SecretError),
     // Generic IO error
     #[error(transparent)]
     Io(#[from] std::io::Error),
@@ -155,6 +173,32 @@ pub enum CloudError {
     LockfileNotFound,
 }
 
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error("Invalid secret reference {0:?}. Expected `file:<path>`, `keyring:<name>` or `vault:<path>#<key>`")]
+    InvalidReference(String),
+    #[error("Failed to read secret file {0:?}: {1}")]
+    FileSystem(PathBuf, #[source] std::io::Error),
+    #[error("DOZER_KEYRING_PASSPHRASE must be set to use the keyring")]
+    MissingKeyringPassphrase,
+    #[error("Failed to read or write keyring {0:?}: {1}")]
+    KeyringFileSystem(PathBuf, #[source] std::io::Error),
+    #[error("Keyring {0:?} is malformed: {1}")]
+    MalformedKeyring(PathBuf, String),
+    #[error("Failed to decrypt keyring {0:?}. Is DOZER_KEYRING_PASSPHRASE right?")]
+    KeyringDecryption(PathBuf),
+    #[error("Secret {0:?} is not in the keyring")]
+    NotInKeyring(String),
+    #[error("VAULT_TOKEN must be set to read secrets from Vault")]
+    MissingVaultToken,
+    #[error("Failed to read {0:?} from Vault: {1}")]
+    Vault(String, #[source] reqwest::Error),
+    #[error("Vault returned a malformed response for {0:?}: {1}")]
+    MalformedVaultResponse(String, #[source] serde_json::Error),
+    #[error("Vault secret {path:?} has no key {key:?}")]
+    NotInVault { path: String, key: String },
+}
+
 #[derive(Debug, Error)]
 pub enum ConfigCombineError {
     #[error("Failed to parse yaml file {0}: {1}")]
@@ -210,6 +254,11 @@ pub enum BuildError {
     FailedToLoadExistingContract(#[source] serde_json::Error),
     #[error("Serde json error: {0}")]
     SerdeJson(#[source] serde_json::Error),
+    #[error(
+        "Sinks {0:?} would receive data they already have again, because the sources feeding them must be re-snapshotted. \
+        Write changed queries to new sinks, or start over with a force build: `dozer build --force`."
+    )]
+    MigrationDuplicatesSinks(Vec<String>),
 }
 
 #[derive(Debug, Error)]
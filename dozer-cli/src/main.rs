@@ -1,7 +1,10 @@
 use clap::Parser;
 use dozer_cli::cli::init_config;
 use dozer_cli::cli::init_dozer;
-use dozer_cli::cli::types::{Cli, Commands, UICommands};
+use dozer_cli::cli::secrets::run_secret_command;
+use dozer_cli::cli::types::{
+    Cli, Commands, ConnectorCommands, Explain, SavepointCommands, UICommands,
+};
 use dozer_cli::errors::{CliError, CloudError, OrchestrationError};
 use dozer_cli::ui;
 use dozer_cli::ui::app::AppUIError;
@@ -14,6 +17,7 @@ use dozer_types::tracing::{error, error_span, info};
 use futures::TryFutureExt;
 use std::process;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::runtime::Runtime;
 
 fn main() {
@@ -45,7 +49,16 @@ fn run() -> Result<(), OrchestrationError> {
         .map(|(c, _)| c.cloud.app_id.as_deref().unwrap_or(&c.app_name))
         .ok();
 
-    let telemetry_config = if matches!(cli.cmd, Commands::Run) {
+    // `dozer explain --analyze` reads the metrics of the query it runs.
+    let telemetry_config = if matches!(
+        cli.cmd,
+        Commands::Run(_)
+            | Commands::Explain(Explain {
+                query: Some(_),
+                analyze: true,
+                ..
+            })
+    ) {
         TelemetryConfig {
             trace: None,
             metrics: Some(TelemetryMetricsConfig::Prometheus),
@@ -59,6 +72,12 @@ fn run() -> Result<(), OrchestrationError> {
 
     let _telemetry = runtime.block_on(async { Telemetry::new(app_id, &telemetry_config) });
 
+    // managing secrets does not require config to be loaded
+    if let Commands::Secret(secret) = &cli.cmd {
+        run_secret_command(&secret.command).map_err(CliError::Secret)?;
+        return Ok(());
+    }
+
     // running UI does not require config to be loaded
     if let Commands::UI(run) = &cli.cmd {
         if let Some(UICommands::Update) = run.command {
@@ -89,9 +108,54 @@ fn run() -> Result<(), OrchestrationError> {
 
     // run individual servers
     (match cli.cmd {
-        Commands::Run => dozer
-            .runtime
-            .block_on(dozer.run_apps(shutdown_receiver, None)),
+        Commands::Run(run) => {
+            dozer
+                .runtime
+                .block_on(dozer.run_apps(shutdown_receiver, None, run.from_checkpoint))
+        }
+        Commands::Savepoint(savepoint) => match savepoint.command {
+            SavepointCommands::Create { name, timeout } => dozer
+                .runtime
+                .block_on(dozer.create_savepoint(name, Duration::from_secs(timeout))),
+            SavepointCommands::List => dozer.runtime.block_on(dozer.list_checkpoints()),
+            SavepointCommands::Describe { id } => {
+                dozer.runtime.block_on(dozer.describe_checkpoint(id))
+            }
+        },
+        Commands::Connector(connector) => match connector.command {
+            ConnectorCommands::Ls => {
+                dozer.list_connections();
+                Ok(())
+            }
+            ConnectorCommands::Describe { connection, table } => dozer
+                .runtime
+                .block_on(dozer.describe_connector(connection, table)),
+            ConnectorCommands::Validate { connection } => dozer
+                .runtime
+                .block_on(dozer.validate_connectors(connection)),
+            ConnectorCommands::Sample {
+                connection,
+                table,
+                limit,
+            } => dozer
+                .runtime
+                .block_on(dozer.sample_connector(connection, table, limit)),
+        },
+        Commands::Sql(sql) => dozer.runtime.block_on(dozer.preview_sql(
+            sql.query,
+            sql.limit,
+            sql.sample_rows,
+            Duration::from_secs(sql.timeout),
+            sql.json,
+            shutdown_receiver,
+        )),
+        Commands::Explain(explain) => dozer.runtime.block_on(dozer.explain(
+            explain.query,
+            explain.analyze,
+            Duration::from_secs(explain.interval),
+            explain.sample_rows,
+            shutdown_receiver,
+        )),
         Commands::Build(build) => {
             let force = build.force.is_some();
 
@@ -100,7 +164,7 @@ fn run() -> Result<(), OrchestrationError> {
                 .block_on(dozer.build(force, shutdown_receiver, build.locked))
         }
         Commands::Clean => dozer.clean(),
-        Commands::UI(_) => {
+        Commands::UI(_) | Commands::Secret(_) => {
             panic!("This should not happen as it is handled earlier");
         }
     })
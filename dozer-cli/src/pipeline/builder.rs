@@ -8,6 +8,8 @@ use dozer_core::app::PipelineEntryPoint;
 use dozer_core::node::SinkFactory;
 use dozer_core::shutdown::ShutdownReceiver;
 use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_ingestion::MemoryBudget;
+use dozer_lambda::LambdaSinkFactory;
 use dozer_log::replication::Log;
 use dozer_sql::builder::statement_to_pipeline;
 use dozer_sql::builder::{OutputNodeInfo, QueryContext};
@@ -22,9 +24,11 @@ use dozer_types::models::source::Source;
 use dozer_types::models::udf_config::UdfConfig;
 use std::hash::Hash;
 use tokio::runtime::Runtime;
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::Mutex;
 
 use crate::pipeline::dummy_sink::DummySinkFactory;
+use crate::pipeline::preview_sink::{PreviewOperation, PreviewSinkFactory};
 use crate::pipeline::LogSinkFactory;
 use dozer_sink_aerospike::AerospikeSinkFactory;
 use dozer_sink_clickhouse::ClickhouseSinkFactory;
@@ -59,11 +63,29 @@ pub struct EndpointLog {
 
 #[derive(Debug)]
 pub enum EndpointLogKind {
-    Api { log: Arc<Mutex<Log>> },
+    Api {
+        log: Arc<Mutex<Log>>,
+    },
     Dummy,
-    Aerospike { config: AerospikeSinkConfig },
-    Clickhouse { config: ClickhouseSinkConfig },
-    Oracle { config: OracleSinkConfig },
+    Aerospike {
+        config: AerospikeSinkConfig,
+    },
+    Clickhouse {
+        config: ClickhouseSinkConfig,
+    },
+    Oracle {
+        config: OracleSinkConfig,
+    },
+    Preview {
+        sender: UnboundedSender<PreviewOperation>,
+    },
+}
+
+/// A lambda attached to the table of the endpoint it subscribes to.
+#[derive(Debug)]
+pub struct EndpointLambda {
+    pub endpoint: String,
+    pub factory: LambdaSinkFactory,
 }
 
 pub struct PipelineBuilder<'a> {
@@ -71,9 +93,13 @@ pub struct PipelineBuilder<'a> {
     sources: &'a [Source],
     sql: Option<&'a str>,
     endpoint_logs: Vec<EndpointLog>,
+    lambdas: Vec<EndpointLambda>,
     labels: LabelsAndProgress,
     flags: Flags,
     udfs: &'a [UdfConfig],
+    default_output_table: Option<String>,
+    memory_budget: Option<MemoryBudget>,
+    sample_rows: Option<usize>,
 }
 
 impl<'a> PipelineBuilder<'a> {
@@ -91,12 +117,39 @@ impl<'a> PipelineBuilder<'a> {
             sources,
             sql,
             endpoint_logs,
+            lambdas: vec![],
             labels,
             flags,
             udfs,
+            default_output_table: None,
+            memory_budget: None,
+            sample_rows: None,
         }
     }
 
+    pub fn with_lambdas(mut self, lambdas: Vec<EndpointLambda>) -> Self {
+        self.lambdas = lambdas;
+        self
+    }
+
+    /// Queries without an `INTO` clause output to this table instead of failing to build.
+    pub fn with_default_output_table(mut self, table_name: String) -> Self {
+        self.default_output_table = Some(table_name);
+        self
+    }
+
+    /// Bytes of ingested records that may wait to enter the pipeline, across all sources.
+    pub fn with_memory_budget(mut self, memory_budget: Option<u64>) -> Self {
+        self.memory_budget = memory_budget.map(MemoryBudget::new);
+        self
+    }
+
+    /// Sources read this many rows of each table, leaving the source unchanged, then stop.
+    pub fn with_sample_rows(mut self, sample_rows: usize) -> Self {
+        self.sample_rows = Some(sample_rows);
+        self
+    }
+
     // Based on used_sources, map it to the connection name and create sources
     // For not breaking current functionality, current format is to be still supported.
     pub async fn get_grouped_tables(
@@ -158,9 +211,14 @@ impl<'a> PipelineBuilder<'a> {
         let mut transformed_sources = vec![];
 
         if let Some(sql) = &self.sql {
-            let query_context =
-                statement_to_pipeline(sql, &mut pipeline, None, self.udfs.to_vec(), runtime)
-                    .map_err(OrchestrationError::PipelineError)?;
+            let query_context = statement_to_pipeline(
+                sql,
+                &mut pipeline,
+                self.default_output_table.clone(),
+                self.udfs.to_vec(),
+                runtime,
+            )
+            .map_err(OrchestrationError::PipelineError)?;
 
             query_ctx = Some(query_context.clone());
 
@@ -227,7 +285,7 @@ impl<'a> PipelineBuilder<'a> {
             let query_context = statement_to_pipeline(
                 sql,
                 &mut pipeline,
-                None,
+                self.default_output_table.clone(),
                 self.udfs.to_vec(),
                 runtime.clone(),
             )
@@ -304,35 +362,40 @@ impl<'a> PipelineBuilder<'a> {
                         table: endpoint_log.table_name.clone(),
                     })
                 }
+                EndpointLogKind::Preview { sender } => Box::new(PreviewSinkFactory::new(
+                    endpoint_log.table_name.clone(),
+                    sender,
+                )),
             };
 
-            match table_info {
-                OutputTableInfo::Transformed(table_info) => {
-                    pipeline.add_sink(snk_factory, &endpoint_log.table_name, None);
+            add_sink(
+                &mut pipeline,
+                snk_factory,
+                &endpoint_log.table_name,
+                table_info,
+            );
+        }
 
-                    pipeline.connect_nodes(
-                        &table_info.node,
-                        table_info.port,
-                        &endpoint_log.table_name,
-                        DEFAULT_PORT_HANDLE,
-                    );
-                }
-                OutputTableInfo::Original(table_info) => {
-                    pipeline.add_sink(
-                        snk_factory,
-                        &endpoint_log.table_name,
-                        Some(PipelineEntryPoint::new(
-                            table_info.table_name.clone(),
-                            DEFAULT_PORT_HANDLE,
-                        )),
-                    );
-                }
-            }
+        for (index, lambda) in self.lambdas.into_iter().enumerate() {
+            let table_info = available_output_tables
+                .get(&lambda.endpoint)
+                .ok_or_else(|| {
+                    OrchestrationError::LambdaEndpointNotFound(lambda.endpoint.clone())
+                })?;
+            let node_name = format!("lambda-{}-{index}", lambda.endpoint);
+            add_sink(
+                &mut pipeline,
+                Box::new(lambda.factory),
+                &node_name,
+                table_info,
+            );
         }
 
         pipelines.push(pipeline);
 
-        let source_builder = SourceBuilder::new(grouped_connections, self.labels);
+        let source_builder = SourceBuilder::new(grouped_connections, self.labels)
+            .with_memory_budget(self.memory_budget)
+            .with_sample_rows(self.sample_rows);
         let asm = source_builder
             .build_source_manager(runtime, shutdown)
             .await?;
@@ -348,6 +411,31 @@ impl<'a> PipelineBuilder<'a> {
     }
 }
 
+fn add_sink(
+    pipeline: &mut AppPipeline,
+    factory: Box<dyn SinkFactory>,
+    name: &str,
+    table_info: &OutputTableInfo,
+) {
+    match table_info {
+        OutputTableInfo::Transformed(table_info) => {
+            pipeline.add_sink(factory, name, None);
+
+            pipeline.connect_nodes(&table_info.node, table_info.port, name, DEFAULT_PORT_HANDLE);
+        }
+        OutputTableInfo::Original(table_info) => {
+            pipeline.add_sink(
+                factory,
+                name,
+                Some(PipelineEntryPoint::new(
+                    table_info.table_name.clone(),
+                    DEFAULT_PORT_HANDLE,
+                )),
+            );
+        }
+    }
+}
+
 fn dedup<T: Eq + Hash + Clone>(v: &mut Vec<T>) {
     let mut uniques = HashSet::new();
     v.retain(|e| uniques.insert(e.clone()));
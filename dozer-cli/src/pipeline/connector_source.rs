@@ -1,27 +1,33 @@
 use dozer_core::node::{OutputPortDef, OutputPortType, PortHandle, Source, SourceFactory};
 use dozer_core::shutdown::ShutdownReceiver;
+use dozer_ingestion::{estimated_size, IngestionConfig, Ingestor, MemoryBudget};
 use dozer_ingestion::{
     get_connector, CdcType, Connector, IngestionIterator, TableIdentifier, TableInfo,
 };
-use dozer_ingestion::{IngestionConfig, Ingestor};
-use dozer_tracing::LabelsAndProgress;
+use dozer_tracing::{Labels, LabelsAndProgress};
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::log::{error, info};
 use dozer_types::models::connection::Connection;
-use dozer_types::models::ingestion_types::IngestionMessage;
-use dozer_types::node::OpIdentifier;
+use dozer_types::models::ingestion_types::{IngestionMessage, TransactionInfo};
+use dozer_types::models::source::RateLimit;
+use dozer_types::node::SourcePosition;
 use dozer_types::thiserror::{self, Error};
 use dozer_types::tracing::{span, Level};
 use dozer_types::types::{Operation, Schema, SourceDefinition};
 use futures::stream::{AbortHandle, Abortable, Aborted};
-use metrics::counter;
-use metrics::describe_counter;
+use metrics::{counter, describe_counter, describe_histogram, histogram};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::runtime::Runtime;
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::mpsc::Sender;
+use tokio::sync::watch;
 use tonic::async_trait;
 
+use super::rate_limiter::RateLimiter;
+
 #[derive(Debug)]
 struct Table {
     schema_name: Option<String>,
@@ -30,6 +36,7 @@ struct Table {
     schema: Schema,
     cdc_type: CdcType,
     port: PortHandle,
+    rate_limit: Option<RateLimit>,
 }
 
 #[derive(Debug, Error)]
@@ -40,15 +47,21 @@ pub enum ConnectorSourceFactoryError {
     PortNotFoundInSource(PortHandle),
     #[error("Schema not initialized")]
     SchemaNotInitialized,
+    #[error("Connection {0} can't be sampled without changing the source")]
+    SampleUnsupported(String),
 }
 
 #[derive(Debug)]
 pub struct ConnectorSourceFactory {
     connection: Connection,
+    /// Connector used to read schemas and order source positions.
+    connector: Box<dyn Connector>,
     runtime: Arc<Runtime>,
     tables: Vec<Table>,
     labels: LabelsAndProgress,
     shutdown: ShutdownReceiver,
+    memory_budget: Option<MemoryBudget>,
+    sample_rows: Option<usize>,
 }
 
 fn map_replication_type_to_output_port_type(_typ: &CdcType) -> OutputPortType {
@@ -105,6 +118,7 @@ impl ConnectorSourceFactory {
                 schema,
                 cdc_type,
                 port,
+                rate_limit: None,
             };
 
             tables.push(table);
@@ -112,12 +126,35 @@ impl ConnectorSourceFactory {
 
         Ok(Self {
             connection,
+            connector,
             runtime,
             tables,
             labels,
             shutdown,
+            memory_budget: None,
+            sample_rows: None,
         })
     }
+
+    /// Rate limits of tables, by port.
+    pub fn with_rate_limits(mut self, mut rate_limits: HashMap<PortHandle, RateLimit>) -> Self {
+        for table in &mut self.tables {
+            table.rate_limit = rate_limits.remove(&table.port);
+        }
+        self
+    }
+
+    pub fn with_memory_budget(mut self, memory_budget: Option<MemoryBudget>) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    /// Ingests up to this many rows of each table as a snapshot and stops, instead of replicating.
+    /// Sampling leaves the source unchanged, see `Connector::sample`.
+    pub fn with_sample_rows(mut self, sample_rows: Option<usize>) -> Self {
+        self.sample_rows = sample_rows;
+        self
+    }
 }
 
 impl SourceFactory for ConnectorSourceFactory {
@@ -182,30 +219,44 @@ impl SourceFactory for ConnectorSourceFactory {
             })
             .collect();
         let ports = self.tables.iter().map(|table| table.port).collect();
+        let rate_limits = self
+            .tables
+            .iter()
+            .map(|table| table.rate_limit.clone())
+            .collect();
 
         let connector = get_connector(self.runtime.clone(), self.connection.clone(), state)?;
 
         Ok(Box::new(ConnectorSource {
             tables,
             ports,
+            rate_limits,
             connector,
             connection_name: self.connection.name.clone(),
             labels: self.labels.clone(),
             shutdown: self.shutdown.clone(),
-            ingestion_config: IngestionConfig::default(),
+            ingestion_config: IngestionConfig::default()
+                .with_memory_budget(self.memory_budget.clone()),
+            sample_rows: self.sample_rows,
         }))
     }
+
+    fn compare_positions(&self, a: &SourcePosition, b: &SourcePosition) -> Option<Ordering> {
+        self.connector.compare_positions(a, b)
+    }
 }
 
 #[derive(Debug)]
 pub struct ConnectorSource {
     tables: Vec<TableInfo>,
     ports: Vec<PortHandle>,
+    rate_limits: Vec<Option<RateLimit>>,
     connector: Box<dyn Connector>,
     connection_name: String,
     labels: LabelsAndProgress,
     shutdown: ShutdownReceiver,
     ingestion_config: IngestionConfig,
+    sample_rows: Option<usize>,
 }
 
 #[async_trait]
@@ -214,15 +265,23 @@ impl Source for ConnectorSource {
         self.connector.serialize_state().await
     }
 
+    fn set_checkpointed_position(&mut self, position: watch::Receiver<Option<SourcePosition>>) {
+        self.ingestion_config = self
+            .ingestion_config
+            .clone()
+            .with_checkpointed_position(position);
+    }
+
     async fn start(
         &mut self,
         sender: Sender<(PortHandle, IngestionMessage)>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         let (ingestor, iterator) = Ingestor::initialize_channel(self.ingestion_config.clone());
         let connection_name = self.connection_name.clone();
         let tables = self.tables.clone();
         let ports = self.ports.clone();
+        let rate_limits = self.rate_limits.clone();
         let labels = self.labels.clone();
         let handle = tokio::spawn(forward_message_to_pipeline(
             iterator,
@@ -230,6 +289,7 @@ impl Source for ConnectorSource {
             connection_name,
             tables,
             ports,
+            rate_limits,
             labels,
         ));
 
@@ -245,12 +305,29 @@ impl Source for ConnectorSource {
             abort_handle.abort();
             eprintln!("Aborted connector {}", name);
         });
-        let result = Abortable::new(
-            self.connector
-                .start(&ingestor, self.tables.clone(), last_checkpoint),
-            abort_registration,
-        )
-        .await;
+        let result = match self.sample_rows {
+            Some(limit) => {
+                Abortable::new(
+                    ingest_sample(
+                        &mut *self.connector,
+                        &ingestor,
+                        &self.connection_name,
+                        self.tables.clone(),
+                        limit,
+                    ),
+                    abort_registration,
+                )
+                .await
+            }
+            None => {
+                Abortable::new(
+                    self.connector
+                        .start(&ingestor, self.tables.clone(), last_checkpoint),
+                    abort_registration,
+                )
+                .await
+            }
+        };
         match result {
             Ok(Ok(())) => {}
             Ok(Err(e)) => return Err(e),
@@ -268,7 +345,47 @@ impl Source for ConnectorSource {
     }
 }
 
+/// Ingests a sample of each table as the snapshot.
+async fn ingest_sample(
+    connector: &mut dyn Connector,
+    ingestor: &Ingestor,
+    connection_name: &str,
+    tables: Vec<TableInfo>,
+    limit: usize,
+) -> Result<(), BoxedError> {
+    let samples = connector.sample(tables, limit).await?.ok_or_else(|| {
+        ConnectorSourceFactoryError::SampleUnsupported(connection_name.to_string())
+    })?;
+
+    ingestor
+        .handle_message(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingStarted,
+        ))
+        .await?;
+    for (table_index, records) in samples.into_iter().enumerate() {
+        if records.is_empty() {
+            continue;
+        }
+        ingestor
+            .handle_message(IngestionMessage::OperationEvent {
+                table_index,
+                op: Operation::BatchInsert { new: records },
+                id: None,
+                source_time: None,
+            })
+            .await?;
+    }
+    ingestor
+        .handle_message(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingDone { id: None },
+        ))
+        .await?;
+    Ok(())
+}
+
 const SOURCE_OPERATION_COUNTER_NAME: &str = "source_operation";
+const SOURCE_PAUSE_HISTOGRAM_NAME: &str = "source_pause_seconds";
+const PAUSE_REASON_LABEL: &str = "reason";
 
 async fn forward_message_to_pipeline(
     mut iterator: IngestionIterator,
@@ -276,6 +393,7 @@ async fn forward_message_to_pipeline(
     connection_name: String,
     tables: Vec<TableInfo>,
     ports: Vec<PortHandle>,
+    rate_limits: Vec<Option<RateLimit>>,
     labels: LabelsAndProgress,
 ) {
     let mut bars = vec![];
@@ -288,9 +406,23 @@ async fn forward_message_to_pipeline(
         SOURCE_OPERATION_COUNTER_NAME,
         "Number of operation processed by source"
     );
+    describe_histogram!(
+        SOURCE_PAUSE_HISTOGRAM_NAME,
+        "Time a source was paused by its rate limit or a full pipeline"
+    );
+
+    let now = Instant::now();
+    let mut rate_limiters = rate_limits
+        .iter()
+        .map(|limit| {
+            limit
+                .as_ref()
+                .and_then(|limit| RateLimiter::new(limit, now))
+        })
+        .collect::<Vec<_>>();
 
     let mut counter = vec![(0u64, 0u64); tables.len()];
-    while let Some(message) = iterator.receiver.recv().await {
+    while let Some(message) = iterator.recv().await {
         let span = span!(Level::TRACE, "pipeline_source_start", connection_name);
         let _enter = span.enter();
 
@@ -302,9 +434,10 @@ async fn forward_message_to_pipeline(
                 let table_name = &tables[*table_index].name;
 
                 // Update metrics
-                let mut labels = labels.labels().clone();
-                labels.push("connection", connection_name.clone());
-                labels.push("table", table_name.clone());
+                let mut table_labels = labels.labels().clone();
+                table_labels.push("connection", connection_name.clone());
+                table_labels.push("table", table_name.clone());
+                let mut labels = table_labels.clone();
                 const OPERATION_TYPE_LABEL: &str = "operation_type";
                 match op {
                     Operation::Delete { .. } => {
@@ -339,17 +472,53 @@ async fn forward_message_to_pipeline(
                     bars[*table_index].set_position(counter.0);
                 }
 
+                // Hold back the message while the table is over its rate limit. This pauses the whole connection,
+                // as later messages can't overtake it: a commit covers the operations of every table before it.
+                if let Some(rate_limiter) = &mut rate_limiters[*table_index] {
+                    let delay =
+                        rate_limiter.take(counter_number, estimated_size(&message), Instant::now());
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                        let mut labels = table_labels.clone();
+                        labels.push(PAUSE_REASON_LABEL, "rate_limit");
+                        histogram!(SOURCE_PAUSE_HISTOGRAM_NAME, delay, labels);
+                    }
+                }
+
                 // Send message to the pipeline
-                if sender.send((port, message)).await.is_err() {
+                if !send_to_pipeline(&sender, (port, message), table_labels).await {
                     break;
                 }
             }
             IngestionMessage::TransactionInfo(_) => {
                 // For transaction level messages, we can send to any port.
-                if sender.send((ports[0], message)).await.is_err() {
+                let mut labels = labels.labels().clone();
+                labels.push("connection", connection_name.clone());
+                if !send_to_pipeline(&sender, (ports[0], message), labels).await {
                     break;
                 }
             }
         }
     }
 }
+
+/// Sends `item`, recording how long the source was paused if the pipeline is full. Returns if it's sent.
+async fn send_to_pipeline(
+    sender: &Sender<(PortHandle, IngestionMessage)>,
+    item: (PortHandle, IngestionMessage),
+    mut labels: Labels,
+) -> bool {
+    let item = match sender.try_send(item) {
+        Ok(()) => return true,
+        Err(TrySendError::Closed(_)) => return false,
+        Err(TrySendError::Full(item)) => item,
+    };
+
+    let start = Instant::now();
+    if sender.send(item).await.is_err() {
+        return false;
+    }
+    labels.push(PAUSE_REASON_LABEL, "backpressure");
+    histogram!(SOURCE_PAUSE_HISTOGRAM_NAME, start.elapsed(), labels);
+    true
+}
@@ -10,7 +10,7 @@ use dozer_types::{
     chrono::Local,
     errors::internal::BoxedError,
     log::{info, warn},
-    node::OpIdentifier,
+    node::SourcePosition,
     types::{FieldType, Operation, Schema, TableOperation},
 };
 
@@ -53,6 +53,10 @@ impl SinkFactory for DummySinkFactory {
     fn type_name(&self) -> String {
         "dummy".to_string()
     }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Debug)]
@@ -113,7 +117,7 @@ impl Sink for DummySink {
     fn on_source_snapshotting_done(
         &mut self,
         connection_name: String,
-        _id: Option<OpIdentifier>,
+        _id: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         if let Some(started_instant) = self.snapshotting_started_instant.remove(&connection_name) {
             info!(
@@ -138,7 +142,7 @@ impl Sink for DummySink {
         Ok(None)
     }
 
-    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
         Ok(None)
     }
 }
@@ -11,7 +11,7 @@ use dozer_log::{
 };
 use dozer_tracing::LabelsAndProgress;
 use dozer_types::types::Schema;
-use dozer_types::{errors::internal::BoxedError, node::OpIdentifier};
+use dozer_types::{errors::internal::BoxedError, node::SourcePosition};
 use dozer_types::{indicatif::ProgressBar, types::TableOperation};
 use tokio::{runtime::Runtime, sync::Mutex};
 
@@ -135,7 +135,7 @@ impl Sink for LogSink {
     fn on_source_snapshotting_done(
         &mut self,
         connection_name: String,
-        _id: Option<OpIdentifier>,
+        _id: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         let end = self
             .runtime
@@ -153,7 +153,7 @@ impl Sink for LogSink {
         Ok(None)
     }
 
-    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
         Ok(None)
     }
 }
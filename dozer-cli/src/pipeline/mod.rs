@@ -2,10 +2,13 @@ mod builder;
 pub mod connector_source;
 mod dummy_sink;
 mod log_sink;
+mod preview_sink;
+mod rate_limiter;
 pub mod source_builder;
 
-pub use builder::{EndpointLog, EndpointLogKind, PipelineBuilder};
+pub use builder::{EndpointLambda, EndpointLog, EndpointLogKind, PipelineBuilder};
 pub use log_sink::{LogSink, LogSinkFactory};
+pub use preview_sink::PreviewOperation;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,132 @@
+use std::{collections::HashMap, sync::Arc};
+
+use dozer_core::{
+    epoch::Epoch,
+    node::{PortHandle, Sink, SinkFactory},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_log::storage::Queue;
+use dozer_types::{
+    errors::internal::BoxedError,
+    node::SourcePosition,
+    types::{Operation, Schema, TableOperation},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::async_trait::async_trait;
+
+/// An operation received by an output table of `dozer sql`.
+#[derive(Debug, Clone)]
+pub struct PreviewOperation {
+    pub table: String,
+    pub schema: Arc<Schema>,
+    pub op: Operation,
+}
+
+#[derive(Debug)]
+pub struct PreviewSinkFactory {
+    table: String,
+    sender: UnboundedSender<PreviewOperation>,
+}
+
+impl PreviewSinkFactory {
+    pub fn new(table: String, sender: UnboundedSender<PreviewOperation>) -> Self {
+        Self { table, sender }
+    }
+}
+
+#[async_trait]
+impl SinkFactory for PreviewSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(&self, _input_schemas: HashMap<PortHandle, Schema>) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    async fn build(
+        &self,
+        mut input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, BoxedError> {
+        let schema = input_schemas
+            .remove(&DEFAULT_PORT_HANDLE)
+            .expect("preview sink must have an input schema");
+        Ok(Box::new(PreviewSink {
+            table: self.table.clone(),
+            schema: Arc::new(schema),
+            sender: self.sender.clone(),
+        }))
+    }
+
+    fn type_name(&self) -> String {
+        "preview".to_string()
+    }
+}
+
+#[derive(Debug)]
+struct PreviewSink {
+    table: String,
+    schema: Arc<Schema>,
+    sender: UnboundedSender<PreviewOperation>,
+}
+
+impl PreviewSink {
+    fn send(&self, op: Operation) {
+        // The preview stops listening once it has enough operations, while the pipeline is shutting down.
+        let _ = self.sender.send(PreviewOperation {
+            table: self.table.clone(),
+            schema: self.schema.clone(),
+            op,
+        });
+    }
+}
+
+impl Sink for PreviewSink {
+    fn process(&mut self, op: TableOperation) -> Result<(), BoxedError> {
+        match op.op {
+            Operation::BatchInsert { new } => {
+                for new in new {
+                    self.send(Operation::Insert { new });
+                }
+            }
+            op => self.send(op),
+        }
+        Ok(())
+    }
+
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn persist(&mut self, _epoch: &Epoch, _queue: &Queue) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_started(
+        &mut self,
+        _connection_name: String,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(
+        &mut self,
+        _connection_name: String,
+        _id: Option<SourcePosition>,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn set_source_state(&mut self, _source_state: &[u8]) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+        Ok(None)
+    }
+
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
+        Ok(None)
+    }
+}
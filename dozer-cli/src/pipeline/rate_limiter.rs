@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+use dozer_types::models::source::RateLimit;
+
+/// Token buckets holding up to a second worth of rows and bytes of a source.
+#[derive(Debug)]
+pub struct RateLimiter {
+    rows: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+}
+
+impl RateLimiter {
+    /// Returns `None` if `limit` doesn't limit anything.
+    pub fn new(limit: &RateLimit, now: Instant) -> Option<Self> {
+        let bucket = |rate: Option<u64>| {
+            rate.filter(|rate| *rate > 0)
+                .map(|rate| TokenBucket::new(rate as f64, now))
+        };
+        let rows = bucket(limit.max_rows_per_sec);
+        let bytes = bucket(limit.max_bytes_per_sec);
+        (rows.is_some() || bytes.is_some()).then_some(Self { rows, bytes })
+    }
+
+    /// Takes `rows` and `bytes` from the buckets, returning how long to wait before sending them.
+    pub fn take(&mut self, rows: u64, bytes: u64, now: Instant) -> Duration {
+        let rows = self
+            .rows
+            .as_mut()
+            .map_or(Duration::ZERO, |bucket| bucket.take(rows as f64, now));
+        let bytes = self
+            .bytes
+            .as_mut()
+            .map_or(Duration::ZERO, |bucket| bucket.take(bytes as f64, now));
+        rows.max(bytes)
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, now: Instant) -> Self {
+        Self {
+            rate,
+            tokens: rate,
+            last_refill: now,
+        }
+    }
+
+    /// Tokens can go negative, so a batch larger than the bucket is let through after waiting for all of it.
+    fn take(&mut self, amount: f64, now: Instant) -> Duration {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+
+        self.tokens -= amount;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let start = Instant::now();
+        let limit = RateLimit {
+            max_rows_per_sec: Some(100),
+            max_bytes_per_sec: Some(1000),
+        };
+        let mut limiter = RateLimiter::new(&limit, start).unwrap();
+
+        // A second worth of rows is let through at once.
+        assert_eq!(limiter.take(100, 100, start), Duration::ZERO);
+        assert_eq!(limiter.take(50, 100, start), Duration::from_millis(500));
+        // Bytes are the tighter limit here.
+        let later = start + Duration::from_secs(2);
+        assert_eq!(limiter.take(10, 1500, later), Duration::from_millis(500));
+
+        assert!(RateLimiter::new(&RateLimit::default(), start).is_none());
+        let unlimited = RateLimit {
+            max_rows_per_sec: Some(0),
+            max_bytes_per_sec: None,
+        };
+        assert!(RateLimiter::new(&unlimited, start).is_none());
+    }
+}
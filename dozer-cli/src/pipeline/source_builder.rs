@@ -2,7 +2,7 @@ use crate::pipeline::connector_source::ConnectorSourceFactory;
 use crate::OrchestrationError;
 use dozer_core::appsource::{AppSourceManager, AppSourceMappings};
 use dozer_core::shutdown::ShutdownReceiver;
-use dozer_ingestion::TableInfo;
+use dozer_ingestion::{MemoryBudget, TableInfo};
 
 use dozer_tracing::LabelsAndProgress;
 use dozer_types::models::connection::Connection;
@@ -14,6 +14,8 @@ use tokio::runtime::Runtime;
 pub struct SourceBuilder {
     grouped_connections: HashMap<Connection, Vec<Source>>,
     labels: LabelsAndProgress,
+    memory_budget: Option<MemoryBudget>,
+    sample_rows: Option<usize>,
 }
 
 const SOURCE_PORTS_RANGE_START: u16 = 1000;
@@ -26,9 +28,22 @@ impl SourceBuilder {
         Self {
             grouped_connections,
             labels,
+            memory_budget: None,
+            sample_rows: None,
         }
     }
 
+    /// Shared by the connectors of all sources.
+    pub fn with_memory_budget(mut self, memory_budget: Option<MemoryBudget>) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    pub fn with_sample_rows(mut self, sample_rows: Option<usize>) -> Self {
+        self.sample_rows = sample_rows;
+        self
+    }
+
     pub fn get_ports(&self) -> HashMap<(&str, &str), u16> {
         let mut port: u16 = SOURCE_PORTS_RANGE_START;
 
@@ -54,8 +69,12 @@ impl SourceBuilder {
         for (connection, sources_group) in &self.grouped_connections {
             let mut ports = HashMap::new();
             let mut table_and_ports = vec![];
+            let mut rate_limits = HashMap::new();
             for source in sources_group {
                 ports.insert(source.name.clone(), port);
+                if let Some(rate_limit) = &source.rate_limit {
+                    rate_limits.insert(port, rate_limit.clone());
+                }
 
                 table_and_ports.push((
                     TableInfo {
@@ -76,7 +95,10 @@ impl SourceBuilder {
                 self.labels.clone(),
                 shutdown.clone(),
             )
-            .await?;
+            .await?
+            .with_rate_limits(rate_limits)
+            .with_memory_budget(self.memory_budget.clone())
+            .with_sample_rows(self.sample_rows);
 
             asm.add(
                 Box::new(source_factory),
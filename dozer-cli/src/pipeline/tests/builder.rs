@@ -37,6 +37,7 @@ fn get_default_config() -> Config {
                 connection: grpc_conn.name.clone(),
                 schema: None,
                 refresh_config: Default::default(),
+                rate_limit: None,
             },
             Source {
                 name: "grpc_conn_customers".to_string(),
@@ -45,6 +46,7 @@ fn get_default_config() -> Config {
                 connection: grpc_conn.name,
                 schema: None,
                 refresh_config: Default::default(),
+                rate_limit: None,
             },
         ],
         ..Default::default()
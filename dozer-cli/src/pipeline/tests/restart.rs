@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::future::pending;
+use std::sync::{Arc, Mutex};
+
+use dozer_core::app::{App, AppPipeline, PipelineEntryPoint};
+use dozer_core::appsource::{AppSourceManager, AppSourceMappings};
+use dozer_core::checkpoint::create_checkpoint_for_test;
+use dozer_core::dozer_log::storage::Queue;
+use dozer_core::epoch::Epoch;
+use dozer_core::executor::{DagExecutor, ExecutorOptions};
+use dozer_core::node::{PortHandle, Sink, SinkFactory};
+use dozer_core::shutdown;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_ingestion::TableInfo;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::models::connection::{Connection, ConnectionConfig};
+use dozer_types::models::ingestion_types::JavaScriptConfig;
+use dozer_types::node::SourcePosition;
+use dozer_types::serde_json::json;
+use dozer_types::types::{Field, Operation, Schema, TableOperation};
+use tempdir::TempDir;
+use tonic::async_trait;
+
+use crate::pipeline::connector_source::ConnectorSourceFactory;
+
+/// Ingests 1 to 5 into `numbers`, resuming after the cursor it's passed.
+const MODULE: &str = r#"
+export function tables() {
+    return [{ name: "numbers", columns: [{ name: "n", type: "int" }], primary_key: ["n"] }];
+}
+
+export default async function (cursor) {
+    for (let n = (cursor ?? 0) + 1; n <= 5; n++) {
+        await Deno[Deno.internal].core.ops.ingest({ typ: "Insert", new_val: { n }, cursor: n });
+    }
+}
+"#;
+
+/// A sink that was checkpointed at `cursor`, and collects the numbers it receives.
+#[derive(Debug)]
+struct CheckpointedSinkFactory {
+    cursor: i64,
+    numbers: Arc<Mutex<Vec<i64>>>,
+}
+
+#[async_trait]
+impl SinkFactory for CheckpointedSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(&self, _input_schemas: HashMap<PortHandle, Schema>) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    async fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, BoxedError> {
+        Ok(Box::new(CheckpointedSink {
+            cursor: self.cursor,
+            numbers: self.numbers.clone(),
+        }))
+    }
+
+    fn type_name(&self) -> String {
+        "checkpointed".to_string()
+    }
+}
+
+#[derive(Debug)]
+struct CheckpointedSink {
+    cursor: i64,
+    numbers: Arc<Mutex<Vec<i64>>>,
+}
+
+impl Sink for CheckpointedSink {
+    fn process(&mut self, op: TableOperation) -> Result<(), BoxedError> {
+        let Operation::Insert { new } = op.op else {
+            panic!("expected insert, got {:?}", op.op);
+        };
+        let Field::Int(n) = new.values[0] else {
+            panic!("expected int, got {:?}", new.values[0]);
+        };
+        self.numbers.lock().unwrap().push(n);
+        Ok(())
+    }
+
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn persist(&mut self, _epoch: &Epoch, _queue: &Queue) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_started(
+        &mut self,
+        _connection_name: String,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(
+        &mut self,
+        _connection_name: String,
+        _id: Option<SourcePosition>,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn set_source_state(&mut self, _source_state: &[u8]) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+        Ok(None)
+    }
+
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
+        Ok(Some(SourcePosition::encode(&json!(self.cursor))?))
+    }
+}
+
+#[test]
+fn javascript_source_restarts_from_oldest_sink_cursor() {
+    let temp_dir = TempDir::new("javascript_restart").unwrap();
+    let module_path = temp_dir.path().join("numbers.js");
+    std::fs::write(&module_path, MODULE).unwrap();
+
+    let runtime = Arc::new(
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap(),
+    );
+    let (_sender, shutdown_receiver) = shutdown::new(&runtime);
+
+    let connection = Connection {
+        config: ConnectionConfig::JavaScript(JavaScriptConfig {
+            bootstrap_path: Some(module_path.to_str().unwrap().to_string()),
+        }),
+        name: "js".to_string(),
+    };
+    let table = TableInfo {
+        schema: None,
+        name: "numbers".to_string(),
+        column_names: vec![],
+    };
+    let source = runtime
+        .block_on(ConnectorSourceFactory::new(
+            vec![(table, DEFAULT_PORT_HANDLE)],
+            connection,
+            runtime.clone(),
+            Default::default(),
+            shutdown_receiver,
+        ))
+        .unwrap();
+
+    let mut asm = AppSourceManager::new();
+    asm.add(
+        Box::new(source),
+        AppSourceMappings::new(
+            "js".to_string(),
+            [("numbers".to_string(), DEFAULT_PORT_HANDLE)]
+                .into_iter()
+                .collect(),
+        ),
+    )
+    .unwrap();
+
+    let mut pipeline = AppPipeline::new_with_default_flags();
+    let mut sinks = vec![];
+    for (id, cursor) in [("ahead", 4), ("behind", 2)] {
+        let numbers = Arc::new(Mutex::new(vec![]));
+        pipeline.add_sink(
+            Box::new(CheckpointedSinkFactory {
+                cursor,
+                numbers: numbers.clone(),
+            }),
+            id,
+            Some(PipelineEntryPoint::new(
+                "numbers".to_string(),
+                DEFAULT_PORT_HANDLE,
+            )),
+        );
+        sinks.push(numbers);
+    }
+
+    let mut app = App::new(asm);
+    app.add_pipeline(pipeline);
+    let dag = app.into_dag().unwrap();
+
+    let runtime_clone = runtime.clone();
+    let handle = runtime.block_on(async move {
+        let (_temp_dir, checkpoint) = create_checkpoint_for_test().await;
+        // Sinks at different cursors must not conflict, and the source resumes after the older one.
+        DagExecutor::new(dag, checkpoint, ExecutorOptions::default())
+            .await
+            .unwrap()
+            .start(pending::<()>(), Default::default(), runtime_clone)
+            .await
+            .unwrap()
+    });
+    handle.join().unwrap();
+
+    for numbers in sinks {
+        assert_eq!(*numbers.lock().unwrap(), vec![3, 4, 5]);
+    }
+}
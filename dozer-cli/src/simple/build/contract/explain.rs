@@ -0,0 +1,221 @@
+use std::{collections::HashSet, fmt::Write};
+
+use dozer_core::{
+    daggy::NodeIndex,
+    petgraph::{
+        visit::{EdgeRef, IntoNodeReferences},
+        Direction,
+    },
+};
+use dozer_types::{node::NodeHandle, types::Schema};
+
+use super::{Contract, NodeKind};
+
+impl Contract {
+    /// Renders the operators feeding each sink as a tree, with the schema and primary key every operator outputs.
+    pub fn explain(&self) -> String {
+        let mut output = String::new();
+        let mut explained = HashSet::new();
+        for (node_index, node) in self.pipeline.0.node_references() {
+            if let NodeKind::Sink { typ, .. } = &node.kind {
+                writeln!(output, "Sink: {} ({typ})", node.handle.id).unwrap();
+                self.explain_inputs(node_index, "", &mut explained, &mut output);
+            }
+        }
+        output
+    }
+
+    /// Handles and types of the processors, in the order they were added to the pipeline.
+    pub fn processors(&self) -> Vec<(&NodeHandle, &str)> {
+        self.pipeline
+            .0
+            .node_references()
+            .filter_map(|(_, node)| match &node.kind {
+                NodeKind::Processor { typ, .. } => Some((&node.handle, typ.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn explain_inputs(
+        &self,
+        node_index: NodeIndex,
+        prefix: &str,
+        explained: &mut HashSet<NodeIndex>,
+        output: &mut String,
+    ) {
+        let graph = self.pipeline.0.graph();
+        let mut edges = graph
+            .edges_directed(node_index, Direction::Incoming)
+            .collect::<Vec<_>>();
+        edges.sort_by_key(|edge| edge.weight().to_port);
+
+        for (index, edge) in edges.iter().enumerate() {
+            let (branch, indent) = if index + 1 == edges.len() {
+                ("└─ ", "   ")
+            } else {
+                ("├─ ", "│  ")
+            };
+            let child_prefix = format!("{prefix}{indent}");
+            let input = &graph[edge.source()];
+            match &input.kind {
+                NodeKind::Source { typ, port_names } => {
+                    writeln!(
+                        output,
+                        "{prefix}{branch}Source: {}.{} ({typ})",
+                        input.handle.id,
+                        port_names[&edge.weight().from_port]
+                    )
+                    .unwrap();
+                    write_schema(output, &child_prefix, &edge.weight().schema);
+                }
+                NodeKind::Processor { typ, definition } => {
+                    // A processor feeding several others is only expanded the first time.
+                    if !explained.insert(edge.source()) {
+                        writeln!(
+                            output,
+                            "{prefix}{branch}Processor: {} ({typ}), see above",
+                            input.handle.id
+                        )
+                        .unwrap();
+                        continue;
+                    }
+                    writeln!(
+                        output,
+                        "{prefix}{branch}Processor: {} ({typ})",
+                        input.handle.id
+                    )
+                    .unwrap();
+                    if let Some(definition) = definition {
+                        writeln!(output, "{child_prefix}Definition: {definition}").unwrap();
+                    }
+                    write_schema(output, &child_prefix, &edge.weight().schema);
+                    self.explain_inputs(edge.source(), &child_prefix, explained, output);
+                }
+                NodeKind::Sink { .. } => unreachable!("Sinks don't have outputs"),
+            }
+        }
+    }
+}
+
+fn write_schema(output: &mut String, prefix: &str, schema: &Schema) {
+    let fields = schema
+        .fields
+        .iter()
+        .map(|field| {
+            let nullable = if field.nullable { " NULL" } else { "" };
+            format!("{} {:?}{nullable}", field.name, field.typ)
+        })
+        .collect::<Vec<_>>();
+    writeln!(output, "{prefix}Schema: {}", fields.join(", ")).unwrap();
+
+    let primary_key = if schema.primary_index.is_empty() {
+        "none".to_string()
+    } else {
+        let key = schema
+            .primary_index
+            .iter()
+            .map(|index| schema.fields[*index].name.as_str())
+            .collect::<Vec<_>>();
+        format!("({})", key.join(", "))
+    };
+    writeln!(output, "{prefix}Primary key: {primary_key}").unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use dozer_core::daggy::Dag;
+    use dozer_types::types::{FieldDefinition, FieldType, SourceDefinition};
+
+    use super::super::{EdgeType, NodeType, PipelineContract};
+    use super::*;
+
+    fn schema(fields: &[&str]) -> Schema {
+        let mut schema = Schema::default();
+        for (index, name) in fields.iter().enumerate() {
+            schema.field(
+                FieldDefinition::new(
+                    name.to_string(),
+                    FieldType::Int,
+                    index > 0,
+                    SourceDefinition::Dynamic,
+                ),
+                index == 0,
+            );
+        }
+        schema
+    }
+
+    #[test]
+    fn test_explain_join() {
+        let mut dag = Dag::new();
+        let mut add_node = |id: &str, kind| {
+            dag.add_node(NodeType {
+                handle: NodeHandle::new(None, id.to_string()),
+                kind,
+            })
+        };
+        let source = add_node(
+            "pg",
+            NodeKind::Source {
+                typ: "Postgres".to_string(),
+                port_names: [(0, "users".to_string()), (1, "orders".to_string())]
+                    .into_iter()
+                    .collect(),
+            },
+        );
+        let join = add_node(
+            "join--0",
+            NodeKind::Processor {
+                typ: "Join".to_string(),
+                definition: Some("users JOIN orders".to_string()),
+            },
+        );
+        let sink = add_node(
+            "user_orders",
+            NodeKind::Sink {
+                typ: "Dummy".to_string(),
+                idempotent: true,
+            },
+        );
+        let mut add_edge = |from, from_port, to, to_port, fields: &[&str]| {
+            dag.add_edge(
+                from,
+                to,
+                EdgeType {
+                    from_port,
+                    to_port,
+                    schema: schema(fields),
+                },
+            )
+            .unwrap();
+        };
+        add_edge(source, 1, join, 1, &["order_id", "user_id"]);
+        add_edge(source, 0, join, 0, &["id"]);
+        add_edge(join, 0, sink, 0, &["id", "order_id"]);
+
+        let contract = Contract {
+            version: 1,
+            pipeline: PipelineContract(dag),
+            endpoints: BTreeMap::new(),
+        };
+        assert_eq!(
+            contract.explain(),
+            "\
+Sink: user_orders (Dummy)
+└─ Processor: join--0 (Join)
+   Definition: users JOIN orders
+   Schema: id Int, order_id Int NULL
+   Primary key: (id)
+   ├─ Source: pg.users (Postgres)
+   │  Schema: id Int
+   │  Primary key: (id)
+   └─ Source: pg.orders (Postgres)
+      Schema: order_id Int, user_id Int NULL
+      Primary key: (order_id)
+"
+        );
+    }
+}
@@ -0,0 +1,526 @@
+use std::collections::{HashMap, HashSet};
+
+use dozer_core::{
+    checkpoint::{ReusedProcessor, StateMigration},
+    daggy::{self, NodeIndex},
+    node::PortHandle,
+    petgraph::{
+        algo::toposort,
+        visit::{EdgeRef, IntoNodeReferences},
+        Direction,
+    },
+};
+use dozer_log::camino::{Utf8Path, Utf8PathBuf};
+use dozer_types::{
+    node::NodeHandle,
+    prettytable::{row, Table},
+    serde_json,
+    types::Schema,
+};
+
+use crate::errors::BuildError;
+
+use super::{serde_json_to_path, Contract, EdgeType, NodeKind, NodeType};
+
+/// What happens to a node of the new pipeline when it starts from a checkpoint of the previous one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeMigration {
+    /// Sources and sinks continue from their positions. Processors reuse the state checkpointed under `previous_handle`.
+    Reuse { previous_handle: NodeHandle },
+    /// A new node, or a processor whose definition or inputs changed. Processors start with empty state.
+    Changed,
+    /// The source restarts from the beginning, so processors downstream of it are rebuilt and sinks receive the data again.
+    ///
+    /// This includes unchanged processors on branches other than the changed one, which would otherwise see the data twice.
+    Resnapshot,
+}
+
+/// The difference between the pipeline in the lockfile and a new build of it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MigrationPlan {
+    pub sources: Vec<(NodeHandle, NodeMigration)>,
+    /// In topological order.
+    pub processors: Vec<(NodeHandle, NodeMigration)>,
+    pub sinks: Vec<(NodeHandle, NodeMigration)>,
+    /// Nodes of the previous pipeline that have no counterpart in the new one.
+    pub removed: Vec<NodeHandle>,
+    /// Kept sinks that would receive their data again because their sources are re-snapshotted, and aren't idempotent.
+    pub duplicated_sinks: Vec<NodeHandle>,
+}
+
+type Dag = daggy::Dag<NodeType, EdgeType>;
+
+impl MigrationPlan {
+    /// Processor handles are assigned positionally, so processors are matched by definition and inputs instead of handle,
+    /// following the new pipeline in topological order.
+    pub fn new(previous: &Contract, current: &Contract) -> Self {
+        let previous = &previous.pipeline.0;
+        let current = &current.pipeline.0;
+        let order = toposort(current.graph(), None).expect("pipeline must be a DAG");
+
+        // Current node to previous node.
+        let mut matches = HashMap::<NodeIndex, NodeIndex>::new();
+        let mut matched_previous = HashSet::<NodeIndex>::new();
+        for &index in &order {
+            let node = &current[index];
+            let candidate =
+                previous
+                    .graph()
+                    .node_references()
+                    .find(|(previous_index, previous_node)| {
+                        !matched_previous.contains(previous_index)
+                            && match (&node.kind, &previous_node.kind) {
+                                (
+                                    NodeKind::Source { typ, .. },
+                                    NodeKind::Source {
+                                        typ: previous_typ, ..
+                                    },
+                                ) => node.handle == previous_node.handle && typ == previous_typ,
+                                (
+                                    NodeKind::Processor { typ, definition },
+                                    NodeKind::Processor {
+                                        typ: previous_typ,
+                                        definition: previous_definition,
+                                    },
+                                ) => {
+                                    definition.is_some()
+                                        && typ == previous_typ
+                                        && definition == previous_definition
+                                        && same_inputs(
+                                            current,
+                                            index,
+                                            previous,
+                                            *previous_index,
+                                            &matches,
+                                        )
+                                }
+                                (NodeKind::Sink { .. }, NodeKind::Sink { .. }) => {
+                                    node.handle == previous_node.handle
+                                }
+                                _ => false,
+                            }
+                    });
+            if let Some((previous_index, _)) = candidate {
+                matches.insert(index, previous_index);
+                matched_previous.insert(previous_index);
+            }
+        }
+
+        // Changed processors and new sinks are filled from the beginning of their sources' streams,
+        // which every other node reading from those sources then sees again.
+        let resnapshot_sources = order
+            .iter()
+            .filter(|&&index| {
+                !matches.contains_key(&index)
+                    && !matches!(current[index].kind, NodeKind::Source { .. })
+            })
+            .flat_map(|index| ancestor_sources(current, *index))
+            .filter(|source| matches.contains_key(source))
+            .collect::<HashSet<_>>();
+
+        let mut plan = MigrationPlan::default();
+        for index in order {
+            let node = &current[index];
+            let migration = match matches.get(&index) {
+                None => NodeMigration::Changed,
+                Some(_)
+                    if ancestor_sources(current, index)
+                        .iter()
+                        .any(|source| resnapshot_sources.contains(source)) =>
+                {
+                    NodeMigration::Resnapshot
+                }
+                Some(previous_index) => NodeMigration::Reuse {
+                    previous_handle: previous[*previous_index].handle.clone(),
+                },
+            };
+            let nodes = match node.kind {
+                NodeKind::Source { .. } => &mut plan.sources,
+                NodeKind::Processor { .. } => &mut plan.processors,
+                NodeKind::Sink { idempotent, .. } => {
+                    if migration == NodeMigration::Resnapshot && !idempotent {
+                        plan.duplicated_sinks.push(node.handle.clone());
+                    }
+                    &mut plan.sinks
+                }
+            };
+            nodes.push((node.handle.clone(), migration));
+        }
+        plan.removed = previous
+            .graph()
+            .node_references()
+            .filter(|(index, _)| !matched_previous.contains(index))
+            .map(|(_, node)| node.handle.clone())
+            .collect();
+        plan
+    }
+
+    /// Whether every source resumes and every processor keeps its state.
+    pub fn keeps_all_state(&self) -> bool {
+        self.sources
+            .iter()
+            .chain(&self.processors)
+            .all(|(_, migration)| matches!(migration, NodeMigration::Reuse { .. }))
+    }
+
+    /// Processors that are unchanged but rebuilt, because a source they read from is re-snapshotted.
+    pub fn rebuilt_unchanged_processors(&self) -> Vec<&NodeHandle> {
+        self.processors
+            .iter()
+            .filter(|(_, migration)| migration == &NodeMigration::Resnapshot)
+            .map(|(handle, _)| handle)
+            .collect()
+    }
+
+    pub fn state_migration(&self, last_epoch_id: u64) -> StateMigration {
+        StateMigration {
+            last_epoch_id,
+            reused_processors: self
+                .processors
+                .iter()
+                .filter_map(|(handle, migration)| match migration {
+                    NodeMigration::Reuse { previous_handle } => Some(ReusedProcessor {
+                        handle: handle.clone(),
+                        previous_handle: previous_handle.clone(),
+                    }),
+                    _ => None,
+                })
+                .collect(),
+            resnapshot_sources: self
+                .sources
+                .iter()
+                .filter(|(_, migration)| migration == &NodeMigration::Resnapshot)
+                .map(|(handle, _)| handle.clone())
+                .collect(),
+        }
+    }
+
+    pub fn print(&self) {
+        let mut table = Table::new();
+        table.add_row(row!["Node", "Kind", "Migration"]);
+        for (kind, nodes) in [
+            ("source", &self.sources),
+            ("processor", &self.processors),
+            ("sink", &self.sinks),
+        ] {
+            for (handle, migration) in nodes {
+                let migration = match (kind, migration) {
+                    ("processor", NodeMigration::Reuse { previous_handle }) => {
+                        if previous_handle == handle {
+                            "reuse state".to_string()
+                        } else {
+                            format!("reuse state of {previous_handle}")
+                        }
+                    }
+                    (_, NodeMigration::Reuse { .. }) => "resume".to_string(),
+                    ("processor", NodeMigration::Changed) => "rebuild (changed)".to_string(),
+                    (_, NodeMigration::Changed) => "new".to_string(),
+                    ("source", NodeMigration::Resnapshot) => "re-snapshot".to_string(),
+                    ("processor", NodeMigration::Resnapshot) => {
+                        "rebuild (input re-snapshotted)".to_string()
+                    }
+                    (_, NodeMigration::Resnapshot) => {
+                        "receive again (input re-snapshotted)".to_string()
+                    }
+                };
+                table.add_row(row![handle, kind, migration]);
+            }
+        }
+        for handle in &self.removed {
+            table.add_row(row![handle, "", "removed"]);
+        }
+        table.printstd();
+    }
+}
+
+/// Inputs are the same if they come from the same ports of matched nodes with the same schemas.
+fn same_inputs(
+    current: &Dag,
+    index: NodeIndex,
+    previous: &Dag,
+    previous_index: NodeIndex,
+    matches: &HashMap<NodeIndex, NodeIndex>,
+) -> bool {
+    let Some(inputs) = inputs(current, index, |from| matches.get(&from).copied()) else {
+        return false;
+    };
+    let Some(previous_inputs) = inputs_of(previous, previous_index) else {
+        return false;
+    };
+    inputs.len() == previous_inputs.len()
+        && inputs.iter().all(|input| previous_inputs.contains(input))
+}
+
+type Input<'a> = (NodeIndex, String, PortHandle, &'a Schema);
+
+fn inputs_of(dag: &Dag, index: NodeIndex) -> Option<Vec<Input>> {
+    inputs(dag, index, Some)
+}
+
+/// Source ports are identified by name, because their port handles depend on the order tables are listed in.
+fn inputs(
+    dag: &Dag,
+    index: NodeIndex,
+    map_node: impl Fn(NodeIndex) -> Option<NodeIndex>,
+) -> Option<Vec<Input>> {
+    dag.graph()
+        .edges_directed(index, Direction::Incoming)
+        .map(|edge| {
+            let from = edge.source();
+            let port = match &dag[from].kind {
+                NodeKind::Source { port_names, .. } => {
+                    port_names.get(&edge.weight().from_port)?.clone()
+                }
+                _ => edge.weight().from_port.to_string(),
+            };
+            Some((
+                map_node(from)?,
+                port,
+                edge.weight().to_port,
+                &edge.weight().schema,
+            ))
+        })
+        .collect()
+}
+
+fn ancestor_sources(dag: &Dag, index: NodeIndex) -> HashSet<NodeIndex> {
+    let mut sources = HashSet::new();
+    let mut stack = vec![index];
+    let mut visited = HashSet::new();
+    while let Some(index) = stack.pop() {
+        if !visited.insert(index) {
+            continue;
+        }
+        if let NodeKind::Source { .. } = dag[index].kind {
+            sources.insert(index);
+        }
+        stack.extend(
+            dag.graph()
+                .edges_directed(index, Direction::Incoming)
+                .map(|edge| edge.source()),
+        );
+    }
+    sources
+}
+
+/// Written by `dozer build` next to the checkpoints the migration applies to, and read by `dozer run`.
+fn state_migration_path(data_dir: &Utf8Path) -> Utf8PathBuf {
+    data_dir.join("state_migration.json")
+}
+
+pub fn read_state_migration(data_dir: &Utf8Path) -> Result<Option<StateMigration>, BuildError> {
+    let path = state_migration_path(data_dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let file =
+        std::fs::File::open(&path).map_err(|e| BuildError::FileSystem(path.clone().into(), e))?;
+    serde_json::from_reader(file)
+        .map(Some)
+        .map_err(BuildError::SerdeJson)
+}
+
+pub fn write_state_migration(
+    data_dir: &Utf8Path,
+    state_migration: &StateMigration,
+) -> Result<(), BuildError> {
+    serde_json_to_path(state_migration_path(data_dir), state_migration)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use dozer_types::types::{FieldDefinition, FieldType, SourceDefinition};
+
+    use super::super::PipelineContract;
+    use super::*;
+
+    fn schema() -> Schema {
+        let mut schema = Schema::default();
+        schema.field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        );
+        schema
+    }
+
+    fn handle(id: &str) -> NodeHandle {
+        NodeHandle::new(None, id.to_string())
+    }
+
+    /// Two sources, each feeding a chain of processors into a sink.
+    fn contract(users_definition: &str, users_processor: &str) -> Contract {
+        let mut dag = Dag::new();
+        let edge = |dag: &mut Dag, from, to| {
+            dag.add_edge(
+                from,
+                to,
+                EdgeType {
+                    from_port: 0,
+                    to_port: 0,
+                    schema: schema(),
+                },
+            )
+            .unwrap();
+        };
+        let chain = |dag: &mut Dag, source: &str, processor: &str, definition: &str, sink: &str| {
+            let source = dag.add_node(NodeType {
+                handle: handle(source),
+                kind: NodeKind::Source {
+                    typ: "Postgres".to_string(),
+                    port_names: [(0, source.to_string())].into_iter().collect(),
+                },
+            });
+            let processor = dag.add_node(NodeType {
+                handle: handle(processor),
+                kind: NodeKind::Processor {
+                    typ: "Selection".to_string(),
+                    definition: Some(definition.to_string()),
+                },
+            });
+            let sink = dag.add_node(NodeType {
+                handle: handle(sink),
+                kind: NodeKind::Sink {
+                    typ: "Log".to_string(),
+                    idempotent: false,
+                },
+            });
+            edge(dag, source, processor);
+            edge(dag, processor, sink);
+        };
+        chain(
+            &mut dag,
+            "users",
+            users_processor,
+            users_definition,
+            "active_users",
+        );
+        chain(&mut dag, "orders", "select--1", "amount > 0", "paid_orders");
+        Contract {
+            version: 1,
+            pipeline: PipelineContract(dag),
+            endpoints: BTreeMap::new(),
+        }
+    }
+
+    fn migration_of<'a>(nodes: &'a [(NodeHandle, NodeMigration)], id: &str) -> &'a NodeMigration {
+        &nodes.iter().find(|(handle, _)| handle.id == id).unwrap().1
+    }
+
+    #[test]
+    fn test_renamed_processor_reuses_state() {
+        let previous = contract("active", "select--0");
+        let current = contract("active", "select--2");
+        let plan = MigrationPlan::new(&previous, &current);
+        assert!(plan.keeps_all_state());
+        assert_eq!(
+            migration_of(&plan.processors, "select--2"),
+            &NodeMigration::Reuse {
+                previous_handle: handle("select--0")
+            }
+        );
+        assert!(plan.removed.is_empty());
+        assert!(plan.duplicated_sinks.is_empty());
+    }
+
+    #[test]
+    fn test_changed_processor_resnapshots_its_sources_only() {
+        let previous = contract("active", "select--0");
+        let current = contract("NOT active", "select--0");
+        let plan = MigrationPlan::new(&previous, &current);
+        assert!(!plan.keeps_all_state());
+        assert_eq!(
+            migration_of(&plan.sources, "users"),
+            &NodeMigration::Resnapshot
+        );
+        assert_eq!(
+            migration_of(&plan.processors, "select--0"),
+            &NodeMigration::Changed
+        );
+        assert_eq!(
+            migration_of(&plan.sinks, "active_users"),
+            &NodeMigration::Resnapshot
+        );
+        assert_eq!(
+            migration_of(&plan.processors, "select--1"),
+            &NodeMigration::Reuse {
+                previous_handle: handle("select--1")
+            }
+        );
+        assert_eq!(plan.removed, vec![handle("select--0")]);
+        assert_eq!(plan.duplicated_sinks, vec![handle("active_users")]);
+
+        let state_migration = plan.state_migration(7);
+        assert_eq!(state_migration.resnapshot_sources, vec![handle("users")]);
+        assert_eq!(state_migration.reused_processors.len(), 1);
+        assert!(plan.rebuilt_unchanged_processors().is_empty());
+    }
+
+    /// Adds a processor reading from `source`, next to the one `contract` already has.
+    fn add_branch(contract: &mut Contract, source: &str, processor: &str, sink: &str) {
+        let dag = &mut contract.pipeline.0;
+        let source = dag
+            .graph()
+            .node_references()
+            .find(|(_, node)| node.handle.id == source)
+            .unwrap()
+            .0;
+        let processor = dag.add_node(NodeType {
+            handle: handle(processor),
+            kind: NodeKind::Processor {
+                typ: "Selection".to_string(),
+                definition: Some("id > 0".to_string()),
+            },
+        });
+        let sink = dag.add_node(NodeType {
+            handle: handle(sink),
+            kind: NodeKind::Sink {
+                typ: "Log".to_string(),
+                idempotent: true,
+            },
+        });
+        for (from, to) in [(source, processor), (processor, sink)] {
+            dag.add_edge(
+                from,
+                to,
+                EdgeType {
+                    from_port: 0,
+                    to_port: 0,
+                    schema: schema(),
+                },
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_unchanged_processor_sharing_resnapshotted_source_is_rebuilt() {
+        let mut previous = contract("active", "select--0");
+        add_branch(&mut previous, "users", "select--2", "positive_users");
+        let mut current = contract("NOT active", "select--0");
+        add_branch(&mut current, "users", "select--2", "positive_users");
+        let plan = MigrationPlan::new(&previous, &current);
+
+        assert_eq!(
+            migration_of(&plan.processors, "select--2"),
+            &NodeMigration::Resnapshot
+        );
+        assert_eq!(
+            plan.rebuilt_unchanged_processors(),
+            vec![&handle("select--2")]
+        );
+        // The other source's branch keeps its state.
+        assert_eq!(
+            migration_of(&plan.processors, "select--1"),
+            &NodeMigration::Reuse {
+                previous_handle: handle("select--1")
+            }
+        );
+    }
+}
@@ -46,9 +46,15 @@ pub enum NodeKind {
     },
     Processor {
         typ: String,
+        /// Lockfiles written before definitions were recorded have none, so their processors never match.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        definition: Option<String>,
     },
     Sink {
         typ: String,
+        /// Whether the sink can receive the same data again, e.g. when a source is re-snapshotted.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        idempotent: bool,
     },
 }
 
@@ -147,9 +153,11 @@ impl Contract {
                     }
                     dozer_core::NodeKind::Processor(processor) => NodeKind::Processor {
                         typ: processor.type_name(),
+                        definition: processor.definition(),
                     },
                     dozer_core::NodeKind::Sink(sink) => NodeKind::Sink {
                         typ: sink.type_name(),
+                        idempotent: sink.is_idempotent(),
                     },
                 };
                 NodeType { handle, kind }
@@ -178,8 +186,12 @@ impl Contract {
     }
 }
 
+mod explain;
+mod migration;
 mod service;
 
+pub use migration::{read_state_migration, write_state_migration, MigrationPlan};
+
 /// Sink's `NodeHandle::id` must be `table_name`.
 fn find_sink(dag: &DagSchemas, endpoint_table_name: &str) -> Option<NodeIndex> {
     dag.graph()
@@ -253,10 +265,16 @@ impl PartialEq<PipelineContract> for PipelineContract {
                             }
                         }
                         (
-                            NodeKind::Processor { typ: left_typ },
-                            NodeKind::Processor { typ: right_typ },
-                        ) => left_typ == right_typ,
-                        (NodeKind::Sink { typ: _ }, NodeKind::Sink { typ: _ }) => true,
+                            NodeKind::Processor {
+                                typ: left_typ,
+                                definition: left_definition,
+                            },
+                            NodeKind::Processor {
+                                typ: right_typ,
+                                definition: right_definition,
+                            },
+                        ) => left_typ == right_typ && left_definition == right_definition,
+                        (NodeKind::Sink { .. }, NodeKind::Sink { .. }) => true,
                         _ => false,
                     }
             },
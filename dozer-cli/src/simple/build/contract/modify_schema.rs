@@ -56,7 +56,11 @@ fn generate_secondary_indexes(
             }
 
             // Skip creating indexes
-            FieldType::Text | FieldType::Binary | FieldType::Json => (),
+            FieldType::Text
+            | FieldType::Binary
+            | FieldType::Json
+            | FieldType::List(_)
+            | FieldType::Struct(_) => (),
         }
     }
 
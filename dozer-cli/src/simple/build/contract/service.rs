@@ -104,7 +104,7 @@ impl Contract {
                         }
                     }
                 }
-                NodeKind::Processor { typ } => {
+                NodeKind::Processor { typ, .. } => {
                     // Create processor ui node. Schema comes from the outgoing edge.
                     let mut edges = self
                         .pipeline
@@ -126,7 +126,7 @@ impl Contract {
                     });
                     pipeline_node_index_to_ui_node_index.insert(node_index, processor_node_index);
                 }
-                NodeKind::Sink { typ } => {
+                NodeKind::Sink { typ, .. } => {
                     // Create sink ui node. Schema comes from endpoint.
                     let schema = self.endpoints[&node.handle.id].schema.clone();
                     let sink_node_index = ui_graph.add_node(UiNodeType {
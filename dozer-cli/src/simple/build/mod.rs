@@ -1,2 +1,4 @@
 mod contract;
-pub use contract::{Contract, PipelineContract};
+pub use contract::{
+    read_state_migration, write_state_migration, Contract, MigrationPlan, PipelineContract,
+};
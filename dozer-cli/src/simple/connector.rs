@@ -0,0 +1,175 @@
+use dozer_ingestion::{Connector, SourceSchema, TableIdentifier, TableInfo};
+use dozer_types::{
+    errors::internal::BoxedError,
+    models::{connection::Connection, source::Source},
+    prettytable::{row, Cell, Row, Table},
+    types::{Record, Schema},
+};
+
+use crate::console_helper::{get_colored_text, GREEN, RED};
+use crate::errors::OrchestrationError;
+use crate::pipeline::connector_source::ConnectorSourceFactoryError;
+
+fn display_table_name(schema: Option<&str>, name: &str) -> String {
+    schema.map_or(name.to_string(), |schema| format!("{schema}.{name}"))
+}
+
+/// Tables can be referred to as `table` or `schema.table`.
+pub fn find_table(tables: Vec<TableIdentifier>, name: &str) -> Option<TableIdentifier> {
+    tables.into_iter().find(|table| {
+        table.name == name || display_table_name(table.schema.as_deref(), &table.name) == name
+    })
+}
+
+pub fn print_connections(connections: &[Connection], sources: &[Source]) {
+    let mut table = Table::new();
+    table.add_row(row!["Connection", "Type", "Sources"]);
+    for connection in connections {
+        let sources = sources
+            .iter()
+            .filter(|source| source.connection == connection.name)
+            .map(|source| {
+                format!(
+                    "{} ({})",
+                    source.name,
+                    display_table_name(source.schema.as_deref(), &source.table_name)
+                )
+            })
+            .collect::<Vec<_>>();
+        table.add_row(row![
+            connection.name,
+            connection.config.get_type_name(),
+            sources.join("\n")
+        ]);
+    }
+    table.printstd();
+}
+
+pub fn print_schemas(table_infos: &[TableInfo], schemas: &[SourceSchema]) {
+    for (table_info, schema) in table_infos.iter().zip(schemas) {
+        println!(
+            "{} (CDC: {:?})",
+            display_table_name(table_info.schema.as_deref(), &table_info.name),
+            schema.cdc_type
+        );
+        schema.schema.print().printstd();
+    }
+}
+
+/// Runs the same checks as building a pipeline, printing a line per check. Returns whether all passed.
+pub async fn validate_connection(
+    connector: &mut dyn Connector,
+    connection: &Connection,
+    sources: &[Source],
+) -> bool {
+    let report = |check: &str, result: &Result<(), BoxedError>| match result {
+        Ok(()) => println!(
+            "[{}][{}] {check}",
+            connection.name,
+            get_colored_text("✓", GREEN)
+        ),
+        Err(e) => println!(
+            "[{}][{}] {check}: {e}",
+            connection.name,
+            get_colored_text("X", RED)
+        ),
+    };
+
+    let result = connector.validate_connection().await;
+    report("Connection", &result);
+    if result.is_err() {
+        return false;
+    }
+
+    let sources = sources
+        .iter()
+        .filter(|source| source.connection == connection.name)
+        .collect::<Vec<_>>();
+    if sources.is_empty() {
+        return true;
+    }
+    let tables = sources
+        .iter()
+        .map(|source| TableIdentifier::new(source.schema.clone(), source.table_name.clone()))
+        .collect::<Vec<_>>();
+    let result = connector.validate_tables(&tables).await;
+    report("Tables", &result);
+    if result.is_err() {
+        return false;
+    }
+
+    let table_infos = match connector.list_columns(tables).await {
+        Ok(table_infos) => table_infos,
+        Err(e) => {
+            report("Columns", &Err(e));
+            return false;
+        }
+    };
+    // Only the columns a source selects need to be supported.
+    let table_infos = table_infos
+        .into_iter()
+        .zip(&sources)
+        .map(|(mut table_info, source)| {
+            if !source.columns.is_empty() {
+                table_info.column_names = source.columns.clone();
+            }
+            table_info
+        })
+        .collect::<Vec<_>>();
+    let schemas = match connector.get_schemas(&table_infos).await {
+        Ok(schemas) => schemas,
+        Err(e) => {
+            report("Schemas", &Err(e));
+            return false;
+        }
+    };
+    let mut valid = true;
+    for (source, schema) in sources.iter().zip(schemas) {
+        let result = schema.map(|_| ());
+        valid &= result.is_ok();
+        report(
+            &format!(
+                "Schema of {}",
+                display_table_name(source.schema.as_deref(), &source.table_name)
+            ),
+            &result,
+        );
+    }
+    valid
+}
+
+/// Reads up to `limit` rows of the table, without changing anything in the source.
+pub async fn sample_table(
+    mut connector: Box<dyn Connector>,
+    connection: &str,
+    table_info: TableInfo,
+    limit: usize,
+) -> Result<Vec<Record>, OrchestrationError> {
+    let samples = connector
+        .sample(vec![table_info], limit)
+        .await
+        .map_err(ConnectorSourceFactoryError::Connector)?
+        .ok_or_else(|| ConnectorSourceFactoryError::SampleUnsupported(connection.to_string()))?;
+    Ok(samples.into_iter().next().unwrap_or_default())
+}
+
+pub fn print_records(schema: &Schema, records: &[Record]) {
+    let mut table = Table::new();
+    table.set_titles(Row::new(
+        schema
+            .fields
+            .iter()
+            .map(|field| Cell::new(&field.name))
+            .collect(),
+    ));
+    for record in records {
+        table.add_row(Row::new(
+            record
+                .values
+                .iter()
+                .map(|value| Cell::new(&value.to_string()))
+                .collect(),
+        ));
+    }
+    table.printstd();
+}
@@ -1,11 +1,14 @@
 use dozer_core::checkpoint::{CheckpointOptions, OptionCheckpoint};
 use dozer_core::shutdown::ShutdownReceiver;
+use dozer_lambda::{LambdaSinkFactory, LambdaState};
+use dozer_log::camino::Utf8Path;
 use dozer_log::home_dir::HomeDir;
 use dozer_tracing::LabelsAndProgress;
 use dozer_types::models::endpoint::{
     AerospikeSinkConfig, ClickhouseSinkConfig, Endpoint, EndpointKind, OracleSinkConfig,
 };
 use dozer_types::models::flags::Flags;
+use dozer_types::models::lambda_config::LambdaConfig;
 use tokio::runtime::Runtime;
 
 use std::sync::Arc;
@@ -13,12 +16,13 @@ use std::sync::Arc;
 use dozer_types::models::source::Source;
 use dozer_types::models::udf_config::UdfConfig;
 
-use crate::pipeline::{EndpointLog, EndpointLogKind, PipelineBuilder};
+use crate::pipeline::{EndpointLambda, EndpointLog, EndpointLogKind, PipelineBuilder};
 use dozer_core::executor::{DagExecutor, ExecutorOptions};
 
 use dozer_types::models::connection::Connection;
 
 use crate::errors::OrchestrationError;
+use crate::simple::build::read_state_migration;
 
 pub struct Executor<'a> {
     connections: &'a [Connection],
@@ -28,6 +32,7 @@ pub struct Executor<'a> {
     endpoints: Vec<ExecutorEndpoint>,
     labels: LabelsAndProgress,
     udfs: &'a [UdfConfig],
+    lambdas: &'a [LambdaConfig],
 }
 
 #[derive(Debug)]
@@ -54,9 +59,10 @@ impl<'a> Executor<'a> {
         sources: &'a [Source],
         sql: Option<&'a str>,
         endpoints: &'a [Endpoint],
-        checkpoint_options: CheckpointOptions,
+        mut checkpoint_options: CheckpointOptions,
         labels: LabelsAndProgress,
         udfs: &'a [UdfConfig],
+        lambdas: &'a [LambdaConfig],
     ) -> Result<Executor<'a>, OrchestrationError> {
         // Find the build path.
         let build_path = home_dir
@@ -64,7 +70,8 @@ impl<'a> Executor<'a> {
             .map_err(|(path, error)| OrchestrationError::FileSystem(path.into(), error))?
             .ok_or(OrchestrationError::NoBuildFound)?;
 
-        // Load pipeline checkpoint.
+        // Load pipeline checkpoint, mapping it to the current build if `dozer build` planned a migration.
+        checkpoint_options.state_migration = read_state_migration(&build_path.data_dir)?;
         let checkpoint =
             OptionCheckpoint::new(build_path.data_dir.to_string(), checkpoint_options).await?;
 
@@ -97,6 +104,7 @@ impl<'a> Executor<'a> {
             endpoints: executor_endpoints,
             labels,
             udfs,
+            lambdas,
         })
     }
 
@@ -106,7 +114,27 @@ impl<'a> Executor<'a> {
         executor_options: ExecutorOptions,
         shutdown: ShutdownReceiver,
         flags: Flags,
+        memory_budget: Option<u64>,
     ) -> Result<DagExecutor, OrchestrationError> {
+        let mut lambdas = vec![];
+        for lambda in self.lambdas {
+            let LambdaConfig::JavaScript(lambda) = lambda;
+            let checkpoint_key =
+                lambda_checkpoint_key(self.checkpoint.prefix(), &lambda.endpoint, &lambda.module);
+            let state =
+                LambdaState::load(self.checkpoint.storage(), checkpoint_key.clone()).await?;
+            lambdas.push(EndpointLambda {
+                endpoint: lambda.endpoint.clone(),
+                factory: LambdaSinkFactory::new(
+                    runtime.clone(),
+                    lambda.endpoint.clone(),
+                    lambda.module.clone(),
+                    checkpoint_key,
+                    state,
+                ),
+            });
+        }
+
         let builder = PipelineBuilder::new(
             self.connections,
             self.sources,
@@ -135,7 +163,9 @@ impl<'a> Executor<'a> {
             self.labels.clone(),
             flags,
             self.udfs,
-        );
+        )
+        .with_lambdas(lambdas)
+        .with_memory_budget(memory_budget);
 
         let dag = builder.build(runtime, shutdown).await?;
         let exec = DagExecutor::new(dag, self.checkpoint, executor_options).await?;
@@ -144,6 +174,14 @@ impl<'a> Executor<'a> {
     }
 }
 
+/// Lambdas are keyed by endpoint and module, so reordering them in the config keeps their checkpoints.
+fn lambda_checkpoint_key(checkpoint_prefix: &str, endpoint: &str, module: &str) -> String {
+    AsRef::<Utf8Path>::as_ref(checkpoint_prefix)
+        .join("lambdas")
+        .join(format!("{endpoint}-{}", module.replace(['/', '\\'], "_")))
+        .into_string()
+}
+
 pub fn run_dag_executor(
     runtime: &Arc<Runtime>,
     dag_executor: DagExecutor,
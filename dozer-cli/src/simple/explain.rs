@@ -0,0 +1,135 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use dozer_core::executor::{PROCESSOR_LATENCY_HISTOGRAM_NAME, PROCESSOR_OPERATION_COUNTER_NAME};
+use dozer_types::{
+    errors::internal::BoxedError,
+    node::NodeHandle,
+    prettytable::{row, Table},
+};
+use prometheus_parse::Value;
+
+use crate::errors::OrchestrationError;
+
+/// Where the Prometheus exporter installed by telemetry serves metrics.
+pub const METRICS_ENDPOINT: &str = "http://localhost:9000/metrics";
+
+/// What one scrape of the metrics endpoint reports for a processor, summed over its partitions.
+#[derive(Debug, Default)]
+struct ProcessorMetrics {
+    operations: f64,
+    latency_p50: Option<f64>,
+    latency_p99: Option<f64>,
+    /// Gauges the processor reports, like the join's lookup table sizes.
+    state: BTreeMap<String, f64>,
+}
+
+#[derive(Debug)]
+pub struct MetricsSnapshot {
+    time: Instant,
+    processors: HashMap<String, ProcessorMetrics>,
+}
+
+pub async fn scrape_processor_metrics() -> Result<MetricsSnapshot, OrchestrationError> {
+    let unavailable =
+        |e: BoxedError| OrchestrationError::MetricsUnavailable(METRICS_ENDPOINT.to_string(), e);
+    let text = reqwest::get(METRICS_ENDPOINT)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| unavailable(e.into()))?
+        .text()
+        .await
+        .map_err(|e| unavailable(e.into()))?;
+    let time = Instant::now();
+
+    let lines = text.lines().map(|line| Ok(line.to_string()));
+    let scrape = prometheus_parse::Scrape::parse(lines).map_err(|e| unavailable(e.into()))?;
+
+    let mut processors = HashMap::<String, ProcessorMetrics>::new();
+    for sample in scrape.samples {
+        let Some(pid) = sample.labels.get("pid") else {
+            continue;
+        };
+        let metrics = processors.entry(pid.to_string()).or_default();
+        match sample.value {
+            Value::Counter(count) | Value::Untyped(count)
+                if sample.metric == PROCESSOR_OPERATION_COUNTER_NAME =>
+            {
+                metrics.operations += count
+            }
+            Value::Summary(quantiles) if sample.metric == PROCESSOR_LATENCY_HISTOGRAM_NAME => {
+                // Partitions are as slow as the slowest one.
+                for quantile in quantiles {
+                    let latency = if quantile.quantile == 0.5 {
+                        &mut metrics.latency_p50
+                    } else if quantile.quantile == 0.99 {
+                        &mut metrics.latency_p99
+                    } else {
+                        continue;
+                    };
+                    *latency = Some(latency.map_or(quantile.count, |l| l.max(quantile.count)));
+                }
+            }
+            Value::Gauge(value) => *metrics.state.entry(sample.metric).or_default() += value,
+            _ => {}
+        }
+    }
+
+    Ok(MetricsSnapshot { time, processors })
+}
+
+fn format_latency(latency: Option<f64>) -> String {
+    latency.map_or("-".to_string(), |latency| {
+        format!("{:?}", Duration::from_secs_f64(latency))
+    })
+}
+
+/// Prints a row per processor. Rates are computed against `previous`, so they're missing on the first snapshot.
+pub fn print_processor_metrics(
+    processors: &[(&NodeHandle, &str)],
+    current: &MetricsSnapshot,
+    previous: Option<&MetricsSnapshot>,
+) {
+    let mut table = Table::new();
+    table.add_row(row![
+        "Processor",
+        "Type",
+        "Records",
+        "Records/s",
+        "Latency p50",
+        "Latency p99",
+        "State"
+    ]);
+    let empty = ProcessorMetrics::default();
+    for (handle, typ) in processors {
+        let metrics = current.processors.get(&handle.id).unwrap_or(&empty);
+        let rate = previous.map_or("-".to_string(), |previous| {
+            let previous_operations = previous
+                .processors
+                .get(&handle.id)
+                .map_or(0.0, |metrics| metrics.operations);
+            let elapsed = current.time.duration_since(previous.time).as_secs_f64();
+            format!(
+                "{:.1}",
+                (metrics.operations - previous_operations) / elapsed
+            )
+        });
+        let state = metrics
+            .state
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect::<Vec<_>>();
+        table.add_row(row![
+            handle.id,
+            typ,
+            metrics.operations,
+            rate,
+            format_latency(metrics.latency_p50),
+            format_latency(metrics.latency_p99),
+            state.join("\n")
+        ]);
+    }
+    table.printstd();
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use actix_web::{dev::Server, web, App, HttpResponse, HttpServer};
+use dozer_core::executor::PipelineStatus;
+use dozer_types::{
+    log::info,
+    models::api_config::{
+        default_health_port, default_host, default_max_sink_lag_seconds, HealthOptions,
+    },
+};
+
+use crate::errors::OrchestrationError;
+
+use super::explain::METRICS_ENDPOINT;
+
+struct HealthState {
+    status: PipelineStatus,
+    max_sink_lag: Duration,
+}
+
+/// Serves the liveness, readiness, status and metrics endpoints of `dozer run`. Returns `None` if they're disabled.
+pub fn start_health_server(
+    options: &HealthOptions,
+    status: PipelineStatus,
+) -> Result<Option<Server>, OrchestrationError> {
+    if options.enabled == Some(false) {
+        return Ok(None);
+    }
+
+    let host = options.host.clone().unwrap_or_else(default_host);
+    let port = options.port.unwrap_or_else(default_health_port);
+    let state = web::Data::new(HealthState {
+        status,
+        max_sink_lag: Duration::from_secs(
+            options
+                .max_sink_lag_seconds
+                .unwrap_or_else(default_max_sink_lag_seconds),
+        ),
+    });
+
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(state.clone())
+            .route("/health", web::get().to(health))
+            .route("/ready", web::get().to(ready))
+            .route("/status", web::get().to(status))
+            .route("/metrics", web::get().to(metrics))
+    })
+    .workers(1)
+    .disable_signals()
+    .bind((host.as_str(), port))
+    .map_err(OrchestrationError::HealthServeFailed)?
+    .run();
+
+    info!("Serving health endpoints on {host}:{port}");
+    Ok(Some(server))
+}
+
+/// The process is alive as long as it answers.
+async fn health() -> HttpResponse {
+    HttpResponse::Ok().body("OK")
+}
+
+async fn ready(state: web::Data<HealthState>) -> HttpResponse {
+    let report = state.status.report();
+    if report.is_ready(state.max_sink_lag) {
+        HttpResponse::Ok().json(report)
+    } else {
+        HttpResponse::ServiceUnavailable().json(report)
+    }
+}
+
+async fn status(state: web::Data<HealthState>) -> HttpResponse {
+    HttpResponse::Ok().json(state.status.report())
+}
+
+/// Passes on what the Prometheus exporter installed by telemetry serves, so one port covers probes and scraping.
+async fn metrics() -> HttpResponse {
+    let response = match reqwest::get(METRICS_ENDPOINT).await {
+        Ok(response) => response,
+        Err(e) => return HttpResponse::ServiceUnavailable().body(e.to_string()),
+    };
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("text/plain")
+        .to_string();
+    match response.text().await {
+        Ok(text) => HttpResponse::Ok().content_type(content_type).body(text),
+        Err(e) => HttpResponse::ServiceUnavailable().body(e.to_string()),
+    }
+}
@@ -5,6 +5,7 @@ use dozer_types::log::info;
 use dozer_types::models::config::default_home_dir;
 use dozer_types::models::config::Config;
 use dozer_types::models::endpoint::Endpoint;
+use dozer_types::models::lambda_config::LambdaConfig;
 
 pub fn validate_config(config: &Config) -> Result<(), OrchestrationError> {
     info!(
@@ -15,6 +16,7 @@ pub fn validate_config(config: &Config) -> Result<(), OrchestrationError> {
         )
     );
     validate_endpoints(&config.sinks)?;
+    validate_lambdas(&config.lambdas, &config.sinks)?;
 
     Ok(())
 }
@@ -26,3 +28,22 @@ pub fn validate_endpoints(endpoints: &[Endpoint]) -> Result<(), OrchestrationErr
 
     Ok(())
 }
+
+pub fn validate_lambdas(
+    lambdas: &[LambdaConfig],
+    endpoints: &[Endpoint],
+) -> Result<(), OrchestrationError> {
+    for lambda in lambdas {
+        let LambdaConfig::JavaScript(lambda) = lambda;
+        if !endpoints
+            .iter()
+            .any(|endpoint| endpoint.table_name == lambda.endpoint)
+        {
+            return Err(OrchestrationError::LambdaEndpointNotFound(
+                lambda.endpoint.clone(),
+            ));
+        }
+    }
+
+    Ok(())
+}
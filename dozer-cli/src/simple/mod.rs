@@ -2,5 +2,10 @@ mod executor;
 pub mod orchestrator;
 pub use orchestrator::SimpleOrchestrator;
 mod build;
+mod connector;
+mod explain;
+mod health;
+mod preview;
+mod savepoint;
 pub use build::{Contract, PipelineContract};
 pub mod helper;
@@ -1,18 +1,31 @@
+use super::connector::{
+    find_table, print_connections, print_records, print_schemas, sample_table, validate_connection,
+};
 use super::executor::{run_dag_executor, Executor};
+use super::explain::{print_processor_metrics, scrape_processor_metrics};
+use super::health::start_health_server;
+use super::preview::{print_changelog, print_changelog_json};
+use super::savepoint::{
+    print_checkpoint, print_checkpoints, watch_savepoint_requests, watch_savepoint_signal,
+    write_savepoint_request,
+};
 use super::Contract;
 use crate::errors::{BuildError, OrchestrationError};
 use crate::pipeline::connector_source::ConnectorSourceFactoryError;
 use crate::pipeline::{EndpointLog, EndpointLogKind, PipelineBuilder};
-use crate::simple::build;
+use crate::simple::build::{self, read_state_migration, write_state_migration, MigrationPlan};
 use crate::simple::helper::validate_config;
 use crate::utils::{get_checkpoint_options, get_executor_options};
 
 use crate::flatten_join_handle;
 use dozer_core::app::AppPipeline;
+use dozer_core::checkpoint::{validate_savepoint_name, OptionCheckpoint};
 use dozer_core::dag_schemas::DagSchemas;
-use dozer_core::shutdown::ShutdownReceiver;
-use dozer_log::camino::Utf8PathBuf;
-use dozer_log::home_dir::{BuildId, HomeDir};
+use dozer_core::executor::{DagExecutor, DagExecutorJoinHandle};
+use dozer_core::shutdown::{self, ShutdownReceiver, ShutdownSender};
+use dozer_core::Dag;
+use dozer_log::camino::{Utf8Path, Utf8PathBuf};
+use dozer_log::home_dir::{BuildId, BuildPath, HomeDir};
 use dozer_tracing::LabelsAndProgress;
 use dozer_types::constants::LOCK_FILE;
 use dozer_types::models::endpoint::EndpointKind;
@@ -24,20 +37,45 @@ use crate::console_helper::GREEN;
 use crate::console_helper::PURPLE;
 use crate::console_helper::RED;
 use dozer_core::errors::ExecutionError;
-use dozer_ingestion::{get_connector, SourceSchema, TableInfo};
+use dozer_ingestion::{get_connector, Connector, SourceSchema, TableInfo};
 use dozer_sql::builder::statement_to_pipeline;
 use dozer_sql::errors::PipelineError;
-use dozer_types::log::info;
+use dozer_types::log::{info, warn};
 use dozer_types::models::config::{default_cache_dir, default_home_dir, Config};
+use dozer_types::models::connection::Connection;
 use dozer_types::tracing::error;
 use futures::stream::FuturesUnordered;
 use futures::{FutureExt, StreamExt};
 use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::future::Future;
 
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempdir::TempDir;
 use tokio::runtime::Runtime;
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc, oneshot};
+
+/// Output table of `dozer sql` queries without an `INTO` clause.
+const PREVIEW_TABLE: &str = "preview";
+
+/// A pipeline run by `dozer sql` or `dozer explain --analyze`. Its checkpoint is deleted once it stops.
+struct TemporaryPipeline {
+    shutdown_sender: ShutdownSender,
+    join_handle: DagExecutorJoinHandle,
+    _checkpoint_dir: TempDir,
+}
+
+impl TemporaryPipeline {
+    async fn stop(self) -> Result<(), OrchestrationError> {
+        self.shutdown_sender.shutdown();
+        let join_handle = self.join_handle;
+        tokio::task::spawn_blocking(move || join_handle.join())
+            .await
+            .map_err(OrchestrationError::JoinError)??;
+        Ok(())
+    }
+}
 
 #[derive(Clone)]
 pub struct SimpleOrchestrator {
@@ -84,10 +122,28 @@ impl SimpleOrchestrator {
         lockfile_path(self.base_directory.clone())
     }
 
+    fn latest_build_path(&self) -> Result<BuildPath, OrchestrationError> {
+        HomeDir::new(self.home_dir(), self.cache_dir())
+            .find_latest_build_path()
+            .map_err(|(path, error)| OrchestrationError::FileSystem(path.into(), error))?
+            .ok_or(OrchestrationError::NoBuildFound)
+    }
+
+    async fn load_checkpoints(&self) -> Result<OptionCheckpoint, OrchestrationError> {
+        let build_path = self.latest_build_path()?;
+        Ok(OptionCheckpoint::new(
+            build_path.data_dir.to_string(),
+            get_checkpoint_options(&self.config, None),
+        )
+        .await?)
+    }
+
+    /// `from_checkpoint` is an epoch id or savepoint name. The latest checkpoint is restored if it's `None`.
     pub async fn run_apps(
         &self,
         shutdown: ShutdownReceiver,
         api_notifier: Option<oneshot::Sender<()>>,
+        from_checkpoint: Option<String>,
     ) -> Result<(), OrchestrationError> {
         let home_dir = HomeDir::new(self.home_dir(), self.cache_dir());
         let executor = Executor::new(
@@ -96,9 +152,10 @@ impl SimpleOrchestrator {
             &self.config.sources,
             self.config.sql.as_deref(),
             &self.config.sinks,
-            get_checkpoint_options(&self.config),
+            get_checkpoint_options(&self.config, from_checkpoint),
             self.labels.clone(),
             &self.config.udfs,
+            &self.config.lambdas,
         )
         .await?;
         let dag_executor = executor
@@ -107,6 +164,7 @@ impl SimpleOrchestrator {
                 get_executor_options(&self.config),
                 shutdown.clone(),
                 self.config.flags.clone(),
+                self.config.app.memory_budget,
             )
             .await?;
 
@@ -114,6 +172,21 @@ impl SimpleOrchestrator {
             api_notifier.send(()).expect("Failed to notify API server");
         }
 
+        let health_server = start_health_server(&self.config.api.health, dag_executor.status())?
+            .map(|server| {
+                let handle = server.handle();
+                tokio::spawn(server);
+                handle
+            });
+        let data_dir = self.latest_build_path()?.data_dir;
+        let savepoint_watchers = [
+            tokio::spawn(watch_savepoint_requests(
+                data_dir,
+                dag_executor.savepoint_requests(),
+            )),
+            tokio::spawn(watch_savepoint_signal(dag_executor.savepoint_requests())),
+        ];
+
         let labels = self.labels.clone();
         let runtime_clone = self.runtime.clone();
         let shutdown_clone = shutdown.clone();
@@ -124,9 +197,72 @@ impl SimpleOrchestrator {
         let mut futures = FuturesUnordered::new();
         futures.push(flatten_join_handle(pipeline_future).boxed());
 
-        while let Some(result) = futures.next().await {
-            result?;
+        let result = async {
+            while let Some(result) = futures.next().await {
+                result?;
+            }
+            Ok::<_, OrchestrationError>(())
+        }
+        .await;
+        for watcher in savepoint_watchers {
+            watcher.abort();
         }
+        if let Some(health_server) = health_server {
+            health_server.stop(true).await;
+        }
+        result
+    }
+
+    /// Asks the running pipeline to write a checkpoint named `name` and waits until it's written.
+    pub async fn create_savepoint(
+        &self,
+        name: String,
+        timeout: Duration,
+    ) -> Result<(), OrchestrationError> {
+        validate_savepoint_name(&name)?;
+        if self
+            .load_checkpoints()
+            .await?
+            .find_checkpoint(&name)
+            .is_some()
+        {
+            return Err(OrchestrationError::SavepointAlreadyExists(name));
+        }
+
+        write_savepoint_request(&self.latest_build_path()?.data_dir, &name)?;
+        info!("Requested savepoint {name}, waiting for the pipeline to write it");
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            if let Some(checkpoint) = self.load_checkpoints().await?.find_checkpoint(&name) {
+                info!(
+                    "Savepoint {name} written at epoch {}. Restore it with `dozer run --from-checkpoint {name}`",
+                    checkpoint.epoch_id
+                );
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(OrchestrationError::SavepointTimeout(name));
+            }
+        }
+    }
+
+    pub async fn list_checkpoints(&self) -> Result<(), OrchestrationError> {
+        print_checkpoints(self.load_checkpoints().await?.checkpoints());
+        Ok(())
+    }
+
+    pub async fn describe_checkpoint(&self, id: String) -> Result<(), OrchestrationError> {
+        let checkpoints = self.load_checkpoints().await?;
+        let checkpoint = checkpoints
+            .find_checkpoint(&id)
+            .ok_or(ExecutionError::CheckpointNotFound(id))?;
+        let objects = checkpoints
+            .list_checkpoint_objects(checkpoint)
+            .await
+            .map_err(ExecutionError::ObjectStorage)?;
+        print_checkpoint(checkpoint, &objects);
         Ok(())
     }
 
@@ -155,6 +291,333 @@ impl SimpleOrchestrator {
         Ok(schema_map)
     }
 
+    fn find_connection(&self, name: &str) -> Result<&Connection, OrchestrationError> {
+        self.config
+            .connections
+            .iter()
+            .find(|connection| connection.name == name)
+            .ok_or_else(|| OrchestrationError::ConnectionNotFound(name.to_string()))
+    }
+
+    fn connect(&self, connection: &Connection) -> Result<Box<dyn Connector>, OrchestrationError> {
+        // We're not going to resume ingestion, so passing `None` as state here is OK.
+        Ok(
+            get_connector(self.runtime.clone(), connection.clone(), None)
+                .map_err(|e| ConnectorSourceFactoryError::Connector(e.into()))?,
+        )
+    }
+
+    /// Lists the tables `table_name` may refer to with their columns and schemas.
+    async fn describe_tables(
+        &self,
+        connector: &mut dyn Connector,
+        connection: &str,
+        table_name: Option<&str>,
+    ) -> Result<(Vec<TableInfo>, Vec<SourceSchema>), OrchestrationError> {
+        let Some(table_name) = table_name else {
+            return Ok(connector
+                .list_all_schemas()
+                .await
+                .map_err(ConnectorSourceFactoryError::Connector)?);
+        };
+        let tables = connector
+            .list_tables()
+            .await
+            .map_err(ConnectorSourceFactoryError::Connector)?;
+        let table = find_table(tables, table_name).ok_or_else(|| {
+            OrchestrationError::ConnectorTableNotFound {
+                connection: connection.to_string(),
+                table: table_name.to_string(),
+            }
+        })?;
+        let table_infos = connector
+            .list_columns(vec![table])
+            .await
+            .map_err(ConnectorSourceFactoryError::Connector)?;
+        let schemas = connector
+            .get_schemas(&table_infos)
+            .await
+            .map_err(ConnectorSourceFactoryError::Connector)?
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ConnectorSourceFactoryError::Connector)?;
+        Ok((table_infos, schemas))
+    }
+
+    pub fn list_connections(&self) {
+        print_connections(&self.config.connections, &self.config.sources);
+    }
+
+    pub async fn describe_connector(
+        &self,
+        connection: String,
+        table: Option<String>,
+    ) -> Result<(), OrchestrationError> {
+        let mut connector = self.connect(self.find_connection(&connection)?)?;
+        let (table_infos, schemas) = self
+            .describe_tables(&mut *connector, &connection, table.as_deref())
+            .await?;
+        print_schemas(&table_infos, &schemas);
+        Ok(())
+    }
+
+    /// Validates `connection`, or all connections if `None`, and the tables sources read from them.
+    pub async fn validate_connectors(
+        &self,
+        connection: Option<String>,
+    ) -> Result<(), OrchestrationError> {
+        let connections = match &connection {
+            Some(name) => vec![self.find_connection(name)?],
+            None => self.config.connections.iter().collect(),
+        };
+        let mut valid = true;
+        for connection in connections {
+            valid &= match self.connect(connection) {
+                Ok(mut connector) => {
+                    validate_connection(&mut *connector, connection, &self.config.sources).await
+                }
+                Err(e) => {
+                    println!(
+                        "[{}][{}] Connector: {e}",
+                        connection.name,
+                        get_colored_text("X", RED)
+                    );
+                    false
+                }
+            };
+        }
+        if valid {
+            Ok(())
+        } else {
+            Err(OrchestrationError::ConnectorValidationFailed)
+        }
+    }
+
+    pub async fn sample_connector(
+        &self,
+        connection: String,
+        table: String,
+        limit: usize,
+    ) -> Result<(), OrchestrationError> {
+        let mut connector = self.connect(self.find_connection(&connection)?)?;
+        let (mut table_infos, schemas) = self
+            .describe_tables(&mut *connector, &connection, Some(&table))
+            .await?;
+        let (Some(table_info), Some(schema)) = (table_infos.pop(), schemas.first()) else {
+            return Err(OrchestrationError::ConnectorTableNotFound { connection, table });
+        };
+        let records = sample_table(connector, &connection, table_info, limit).await?;
+        print_records(&schema.schema, &records);
+        Ok(())
+    }
+
+    /// Runs `query` with empty state against `sample_rows` rows of each source table, until it outputs `limit` operations or `timeout` passes.
+    ///
+    /// Sources are sampled, so that previewing doesn't change them, e.g. by creating replication slots.
+    pub async fn preview_sql(
+        &self,
+        query: String,
+        limit: usize,
+        sample_rows: usize,
+        timeout: Duration,
+        json: bool,
+        shutdown: ShutdownReceiver,
+    ) -> Result<(), OrchestrationError> {
+        let query = query.as_str();
+        let builder = |endpoint_logs| {
+            PipelineBuilder::new(
+                &self.config.connections,
+                &self.config.sources,
+                Some(query),
+                endpoint_logs,
+                self.labels.clone(),
+                self.config.flags.clone(),
+                &self.config.udfs,
+            )
+            .with_default_output_table(PREVIEW_TABLE.to_string())
+            .with_sample_rows(sample_rows)
+        };
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let endpoint_logs = builder(vec![])
+            .calculate_sources(self.runtime.clone())?
+            .transformed_sources
+            .into_iter()
+            .map(|table_name| EndpointLog {
+                table_name,
+                kind: EndpointLogKind::Preview {
+                    sender: sender.clone(),
+                },
+            })
+            .collect();
+        drop(sender);
+
+        let pipeline = self
+            .start_temporary_pipeline("dozer-sql-preview", |shutdown| {
+                builder(endpoint_logs).build(&self.runtime, shutdown)
+            })
+            .await?;
+
+        let mut changelog = vec![];
+        let deadline = tokio::time::sleep(timeout);
+        tokio::pin!(deadline);
+        let interrupted = shutdown.create_shutdown_future();
+        tokio::pin!(interrupted);
+        while changelog.len() < limit {
+            tokio::select! {
+                operation = receiver.recv() => match operation {
+                    Some(operation) => changelog.push(operation),
+                    // The pipeline stopped.
+                    None => break,
+                },
+                _ = &mut deadline => break,
+                _ = &mut interrupted => break,
+            }
+        }
+        drop(receiver);
+        pipeline.stop().await?;
+
+        if json {
+            print_changelog_json(&changelog);
+        } else {
+            print_changelog(&changelog);
+        }
+        Ok(())
+    }
+
+    /// Prints the operators the configured SQL, or `query`, is made of.
+    ///
+    /// With `analyze`, then prints their statistics every `interval` until interrupted.
+    /// The statistics come from the metrics of `dozer run`, or of `query` run against `sample_rows` rows of each source table.
+    pub async fn explain(
+        &self,
+        query: Option<String>,
+        analyze: bool,
+        interval: Duration,
+        sample_rows: usize,
+        shutdown: ShutdownReceiver,
+    ) -> Result<(), OrchestrationError> {
+        let builder = |endpoint_logs| {
+            let builder = PipelineBuilder::new(
+                &self.config.connections,
+                &self.config.sources,
+                query.as_deref().or(self.config.sql.as_deref()),
+                endpoint_logs,
+                self.labels.clone(),
+                self.config.flags.clone(),
+                &self.config.udfs,
+            );
+            if query.is_some() {
+                builder
+                    .with_default_output_table(PREVIEW_TABLE.to_string())
+                    .with_sample_rows(sample_rows)
+            } else {
+                builder
+            }
+        };
+        let output_tables = if query.is_some() {
+            builder(vec![])
+                .calculate_sources(self.runtime.clone())?
+                .transformed_sources
+        } else {
+            self.config
+                .sinks
+                .iter()
+                .map(|sink| sink.table_name.clone())
+                .collect()
+        };
+        let endpoint_logs = || {
+            output_tables
+                .iter()
+                .map(|table_name| EndpointLog {
+                    table_name: table_name.clone(),
+                    kind: EndpointLogKind::Dummy,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let dag = builder(endpoint_logs())
+            .build(&self.runtime, shutdown.clone())
+            .await?;
+        let contract = Contract::new(
+            self.config.version as usize,
+            &DagSchemas::new(dag).await?,
+            &self.config.connections,
+            &[],
+            false,
+            false,
+        )?;
+        print!("{}", contract.explain());
+        if !analyze {
+            return Ok(());
+        }
+
+        let pipeline = if query.is_some() {
+            Some(
+                self.start_temporary_pipeline("dozer-explain", |shutdown| {
+                    builder(endpoint_logs()).build(&self.runtime, shutdown)
+                })
+                .await?,
+            )
+        } else {
+            None
+        };
+
+        let processors = contract.processors();
+        let mut previous = None;
+        let mut ticks = tokio::time::interval(interval);
+        let interrupted = shutdown.create_shutdown_future();
+        tokio::pin!(interrupted);
+        let result = loop {
+            tokio::select! {
+                _ = ticks.tick() => {}
+                _ = &mut interrupted => break Ok(()),
+            }
+            let current = match scrape_processor_metrics().await {
+                Ok(current) => current,
+                Err(e) => break Err(e),
+            };
+            println!();
+            print_processor_metrics(&processors, &current, previous.as_ref());
+            previous = Some(current);
+        };
+
+        if let Some(pipeline) = pipeline {
+            pipeline.stop().await?;
+        }
+        result
+    }
+
+    /// Runs the DAG `build` returns from empty state, with a checkpoint that's deleted when the pipeline stops.
+    async fn start_temporary_pipeline<F: Future<Output = Result<Dag, OrchestrationError>>>(
+        &self,
+        name: &str,
+        build: impl FnOnce(ShutdownReceiver) -> F,
+    ) -> Result<TemporaryPipeline, OrchestrationError> {
+        let (shutdown_sender, shutdown) = shutdown::new(&self.runtime);
+        let dag = build(shutdown.clone()).await?;
+        let checkpoint_dir =
+            TempDir::new(name).map_err(|e| OrchestrationError::FileSystem(name.into(), e))?;
+        let checkpoint = OptionCheckpoint::new(
+            checkpoint_dir.path().to_string_lossy().to_string(),
+            Default::default(),
+        )
+        .await?;
+        let join_handle = DagExecutor::new(dag, checkpoint, Default::default())
+            .await?
+            .start(
+                Box::pin(shutdown.create_shutdown_future()),
+                self.labels.clone(),
+                self.runtime.clone(),
+            )
+            .await?;
+        Ok(TemporaryPipeline {
+            shutdown_sender,
+            join_handle,
+            _checkpoint_dir: checkpoint_dir,
+        })
+    }
+
     pub async fn build(
         &self,
         force: bool,
@@ -239,15 +702,74 @@ impl SimpleOrchestrator {
             }
         }
 
-        home_dir
+        let build_path = home_dir
             .create_build_dir_all(BuildId::first())
             .map_err(|(path, error)| BuildError::FileSystem(path.into(), error))?;
 
+        if let Some(previous_contract) = Contract::deserialize(contract_path.as_std_path())
+            .ok()
+            .filter(|previous_contract| previous_contract != &contract)
+        {
+            self.migrate_state(&previous_contract, &contract, &build_path.data_dir)
+                .await?;
+        }
+
         contract.serialize(contract_path.as_std_path())?;
 
         Ok(())
     }
 
+    /// Reports how the checkpointed state carries over to the new build, and records it for `dozer run`.
+    async fn migrate_state(
+        &self,
+        previous_contract: &Contract,
+        contract: &Contract,
+        data_dir: &Utf8Path,
+    ) -> Result<(), OrchestrationError> {
+        let checkpoint = OptionCheckpoint::new(
+            data_dir.to_string(),
+            get_checkpoint_options(&self.config, None),
+        )
+        .await?;
+        let Some(last_epoch_id) = checkpoint.last_epoch_id() else {
+            return Ok(());
+        };
+
+        let plan = MigrationPlan::new(previous_contract, contract);
+        info!("Pipeline changed since checkpoint {last_epoch_id}, state migration plan:");
+        plan.print();
+        if !plan.duplicated_sinks.is_empty() {
+            let sinks = plan
+                .duplicated_sinks
+                .into_iter()
+                .map(|handle| handle.id)
+                .collect();
+            return Err(BuildError::MigrationDuplicatesSinks(sinks).into());
+        }
+        if !plan.keeps_all_state() {
+            warn!("Re-snapshotted sources are read from the beginning, and their sinks receive the data again");
+        }
+        let rebuilt = plan.rebuilt_unchanged_processors();
+        if !rebuilt.is_empty() {
+            let rebuilt = rebuilt
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            warn!("Processors {rebuilt} are unchanged but rebuilt, because they read from a re-snapshotted source and would otherwise receive its data twice");
+        }
+
+        let mut state_migration = plan.state_migration(last_epoch_id);
+        // The pipeline was built before without running, so the checkpoint is still keyed by the build before that.
+        if let Some(previous_migration) = read_state_migration(data_dir)? {
+            if previous_migration.last_epoch_id == last_epoch_id {
+                state_migration = previous_migration.then(state_migration);
+            }
+        }
+        write_state_migration(data_dir, &state_migration)?;
+        Ok(())
+    }
+
     // Cleaning the entire folder as there will be inconsistencies
     // between pipeline, cache and generated proto files.
     pub fn clean(&self) -> Result<(), OrchestrationError> {
@@ -277,8 +799,12 @@ impl SimpleOrchestrator {
 
         let dozer_pipeline = self.clone();
         let pipeline_shutdown = shutdown.clone();
-        let pipeline_future =
-            async move { dozer_pipeline.run_apps(pipeline_shutdown, Some(tx)).await }.boxed();
+        let pipeline_future = async move {
+            dozer_pipeline
+                .run_apps(pipeline_shutdown, Some(tx), None)
+                .await
+        }
+        .boxed();
 
         match select(rx, pipeline_future).await {
             Either::Left((result, pipeline_future)) => {
@@ -0,0 +1,83 @@
+use dozer_types::{
+    json_types::{field_to_json_value, JsonObject, JsonValue},
+    prettytable::{row, Table},
+    serde_json,
+    types::{Operation, Record, Schema},
+};
+
+use crate::pipeline::PreviewOperation;
+
+fn op_name(op: &Operation) -> &'static str {
+    match op {
+        Operation::Insert { .. } => "insert",
+        Operation::Delete { .. } => "delete",
+        Operation::Update { .. } => "update",
+        Operation::BatchInsert { .. } => "batch_insert",
+    }
+}
+
+fn before_and_after(op: &Operation) -> (Option<&Record>, Option<&Record>) {
+    match op {
+        Operation::Insert { new } => (None, Some(new)),
+        Operation::Delete { old } => (Some(old), None),
+        Operation::Update { old, new } => (Some(old), Some(new)),
+        Operation::BatchInsert { .. } => unreachable!("preview sink splits batch inserts"),
+    }
+}
+
+fn format_record(schema: &Schema, record: &Record) -> String {
+    schema
+        .fields
+        .iter()
+        .zip(&record.values)
+        .map(|(field, value)| format!("{}: {value}", field.name))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn record_to_json(schema: &Schema, record: &Record) -> JsonValue {
+    let mut object = JsonObject::new();
+    for (field, value) in schema.fields.iter().zip(&record.values) {
+        object.insert(field.name.as_str(), field_to_json_value(value.clone()));
+    }
+    object.into()
+}
+
+pub fn print_changelog(changelog: &[PreviewOperation]) {
+    let mut table = Table::new();
+    table.add_row(row!["Table", "Op", "Before", "After"]);
+    for operation in changelog {
+        let (before, after) = before_and_after(&operation.op);
+        let format = |record: Option<&Record>| {
+            record.map_or(String::new(), |record| {
+                format_record(&operation.schema, record)
+            })
+        };
+        table.add_row(row![
+            operation.table,
+            op_name(&operation.op),
+            format(before),
+            format(after)
+        ]);
+    }
+    table.printstd();
+}
+
+/// Prints one JSON object per line, so the output can be piped into line-oriented tools.
+pub fn print_changelog_json(changelog: &[PreviewOperation]) {
+    for operation in changelog {
+        let (before, after) = before_and_after(&operation.op);
+        let to_json = |record: Option<&Record>| {
+            record.map_or(JsonValue::NULL, |record| {
+                record_to_json(&operation.schema, record)
+            })
+        };
+        let mut line = JsonObject::new();
+        line.insert("table", operation.table.as_str());
+        line.insert("op", op_name(&operation.op));
+        line.insert("before", to_json(before));
+        line.insert("after", to_json(after));
+        let line = serde_json::to_string(&line).expect("JSON values must serialize");
+        println!("{line}");
+    }
+}
@@ -0,0 +1,123 @@
+use std::time::{Duration, SystemTime};
+
+use dozer_core::{checkpoint::CheckpointDescription, epoch::SavepointRequests};
+use dozer_log::{
+    camino::{Utf8Path, Utf8PathBuf},
+    storage::ListedObject,
+};
+use dozer_types::{
+    chrono::{DateTime, Utc},
+    log::error,
+    node::SourceState,
+    prettytable::{row, Table},
+};
+
+use crate::errors::OrchestrationError;
+
+/// `dozer savepoint create` drops a file named after the savepoint here, which the running pipeline picks up.
+fn savepoint_requests_dir(data_dir: &Utf8Path) -> Utf8PathBuf {
+    data_dir.join("savepoint_requests")
+}
+
+pub fn write_savepoint_request(data_dir: &Utf8Path, name: &str) -> Result<(), OrchestrationError> {
+    let dir = savepoint_requests_dir(data_dir);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| OrchestrationError::FileSystem(dir.clone().into(), e))?;
+    let path = dir.join(name);
+    std::fs::write(&path, []).map_err(|e| OrchestrationError::FileSystem(path.into(), e))
+}
+
+pub async fn watch_savepoint_requests(data_dir: Utf8PathBuf, requests: SavepointRequests) {
+    let dir = savepoint_requests_dir(&data_dir);
+    let mut interval = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        interval.tick().await;
+        let Ok(entries) = dir.read_dir_utf8() else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if let Err(e) = std::fs::remove_file(entry.path()) {
+                error!("Failed to remove savepoint request {}: {e}", entry.path());
+                continue;
+            }
+            requests.request(entry.file_name().to_string());
+        }
+    }
+}
+
+/// `SIGUSR1` requests a savepoint named after the current time.
+#[cfg(unix)]
+pub async fn watch_savepoint_signal(requests: SavepointRequests) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut signals = match signal(SignalKind::user_defined1()) {
+        Ok(signals) => signals,
+        Err(e) => {
+            error!("Failed to listen for savepoint signal: {e}");
+            return;
+        }
+    };
+    while signals.recv().await.is_some() {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        requests.request(format!("signal-{}", now.format("%Y%m%dT%H%M%S")));
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn watch_savepoint_signal(_requests: SavepointRequests) {
+    std::future::pending().await
+}
+
+pub fn print_checkpoints(checkpoints: &[CheckpointDescription]) {
+    let mut table = Table::new();
+    table.add_row(row!["Epoch", "Savepoint", "Created at", "Sources"]);
+    for checkpoint in checkpoints {
+        table.add_row(row![
+            checkpoint.epoch_id,
+            checkpoint.savepoint.as_deref().unwrap_or("-"),
+            format_time(checkpoint.created_at),
+            checkpoint.source_states.len()
+        ]);
+    }
+    table.printstd();
+}
+
+pub fn print_checkpoint(checkpoint: &CheckpointDescription, objects: &[ListedObject]) {
+    let mut table = Table::new();
+    table.add_row(row!["Epoch", checkpoint.epoch_id]);
+    table.add_row(row![
+        "Savepoint",
+        checkpoint.savepoint.as_deref().unwrap_or("-")
+    ]);
+    table.add_row(row!["Created at", format_time(checkpoint.created_at)]);
+    table.add_row(row!["State prefix", checkpoint.processor_prefix]);
+    table.printstd();
+
+    let mut sources = checkpoint.source_states.iter().collect::<Vec<_>>();
+    sources.sort_by_key(|(handle, _)| handle.to_string());
+    let mut table = Table::new();
+    table.add_row(row!["Source", "State"]);
+    for (handle, state) in sources {
+        let state = match state {
+            SourceState::NotStarted => "not started",
+            SourceState::NonRestartable => "not restartable",
+            SourceState::RestartableFromOpId(_) | SourceState::Restartable(_) => "restartable",
+        };
+        table.add_row(row![handle, state]);
+    }
+    table.printstd();
+
+    let mut table = Table::new();
+    table.add_row(row!["Node state", "Written at"]);
+    for object in objects {
+        let name = AsRef::<Utf8Path>::as_ref(&object.key)
+            .strip_prefix(&checkpoint.processor_prefix)
+            .map_or(object.key.as_str(), |name| name.as_str());
+        table.add_row(row![name, format_time(object.last_modified)]);
+    }
+    table.printstd();
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339()
+}
@@ -9,7 +9,7 @@ use dozer_tracing::{Labels, LabelsAndProgress};
 use dozer_types::{
     grpc_types::{
         app_ui::{AppUi, AppUiResponse, BuildResponse, BuildStatus, ConnectResponse, RunRequest},
-        contract::DotResponse,
+        contract::{DotResponse, ExplainResponse},
         types::SchemasResponse,
     },
     log::info,
@@ -239,6 +239,16 @@ impl AppUIState {
         })
     }
 
+    pub async fn explain(&self) -> Result<ExplainResponse, AppUIError> {
+        self.create_contract_if_missing().await?;
+        let dozer = self.dozer.read().await;
+        let contract = get_contract(&dozer)?;
+
+        Ok(ExplainResponse {
+            plan: contract.explain(),
+        })
+    }
+
     pub async fn run(&self, request: RunRequest) -> Result<Labels, AppUIError> {
         let dozer = self.dozer.read().await;
         let dozer = &dozer.as_ref().ok_or(AppUIError::NotInitialized)?.dozer;
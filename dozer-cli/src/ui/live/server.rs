@@ -2,7 +2,7 @@ use dozer_types::{
     grpc_types::{
         contract::{
             contract_service_server::{ContractService, ContractServiceServer},
-            CommonRequest, DotResponse, SourcesRequest,
+            CommonRequest, DotResponse, ExplainResponse, SourcesRequest,
         },
         live::{
             code_service_server::{CodeService, CodeServiceServer},
@@ -70,6 +70,19 @@ impl ContractService for ContractServer {
         }
     }
 
+    async fn explain(
+        &self,
+        _request: Request<CommonRequest>,
+    ) -> Result<Response<ExplainResponse>, Status> {
+        let state = self.state.clone();
+        let res = state.explain().await;
+
+        match res {
+            Ok(res) => Ok(Response::new(res)),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+
     async fn get_graph_schemas(
         &self,
         _request: Request<CommonRequest>,
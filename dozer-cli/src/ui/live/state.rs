@@ -8,7 +8,7 @@ use dozer_log::camino::Utf8Path;
 use dozer_tracing::{Labels, LabelsAndProgress};
 use dozer_types::{
     grpc_types::{
-        contract::DotResponse,
+        contract::{DotResponse, ExplainResponse},
         live::{BuildResponse, BuildStatus, ConnectResponse, LiveApp, LiveResponse, RunRequest},
         types::SchemasResponse,
     },
@@ -243,6 +243,16 @@ impl LiveState {
         })
     }
 
+    pub async fn explain(&self) -> Result<ExplainResponse, LiveError> {
+        self.create_contract_if_missing().await?;
+        let dozer = self.dozer.read().await;
+        let contract = get_contract(&dozer)?;
+
+        Ok(ExplainResponse {
+            plan: contract.explain(),
+        })
+    }
+
     pub async fn run(&self, request: RunRequest) -> Result<Labels, LiveError> {
         let dozer = self.dozer.read().await;
         let dozer = &dozer.as_ref().ok_or(LiveError::NotInitialized)?.dozer;
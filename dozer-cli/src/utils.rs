@@ -1,13 +1,17 @@
 use dozer_core::{
     checkpoint::{CheckpointFactoryOptions, CheckpointOptions},
+    epoch::EpochManagerOptions,
     executor::ExecutorOptions,
 };
 use dozer_types::models::{
     app_config::{
         default_app_buffer_size, default_commit_size, default_commit_timeout,
-        default_error_threshold, default_persist_queue_capacity,
+        default_error_threshold, default_max_interval_before_persist_in_seconds,
+        default_max_num_records_before_persist, default_parallelism,
+        default_persist_queue_capacity,
     },
     config::Config,
+    flags::default_enable_app_checkpoints,
 };
 use std::time::Duration;
 
@@ -38,10 +42,12 @@ fn get_error_threshold(config: &Config) -> u32 {
         .unwrap_or_else(default_error_threshold)
 }
 
-pub fn get_checkpoint_options(config: &Config) -> CheckpointOptions {
+pub fn get_checkpoint_options(config: &Config, restore_from: Option<String>) -> CheckpointOptions {
     let app = &config.app;
     CheckpointOptions {
         data_storage: app.data_storage.clone(),
+        restore_from,
+        state_migration: None,
     }
 }
 
@@ -55,6 +61,24 @@ fn get_checkpoint_factory_options(config: &Config) -> CheckpointFactoryOptions {
     }
 }
 
+fn get_epoch_manager_options(config: &Config) -> EpochManagerOptions {
+    EpochManagerOptions {
+        max_num_records_before_persist: config
+            .app
+            .max_num_records_before_persist
+            .unwrap_or_else(default_max_num_records_before_persist)
+            as usize,
+        max_interval_before_persist_in_seconds: config
+            .app
+            .max_interval_before_persist_in_seconds
+            .unwrap_or_else(default_max_interval_before_persist_in_seconds),
+        enable_app_checkpoints: config
+            .flags
+            .enable_app_checkpoints
+            .unwrap_or_else(default_enable_app_checkpoints),
+    }
+}
+
 pub fn get_executor_options(config: &Config) -> ExecutorOptions {
     ExecutorOptions {
         commit_sz: get_commit_size(config),
@@ -62,5 +86,13 @@ pub fn get_executor_options(config: &Config) -> ExecutorOptions {
         commit_time_threshold: get_commit_time_threshold(config),
         error_threshold: Some(get_error_threshold(config)),
         checkpoint_factory_options: get_checkpoint_factory_options(config),
+        epoch_manager_options: get_epoch_manager_options(config),
+        parallelism: config.app.parallelism.unwrap_or_else(default_parallelism) as usize,
+        processor_parallelism: config
+            .app
+            .processor_parallelism
+            .iter()
+            .map(|(id, parallelism)| (id.clone(), *parallelism as usize))
+            .collect(),
     }
 }
@@ -1,22 +1,21 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    cmp::Ordering,
+    collections::{hash_map::Entry, HashMap, HashSet},
     fmt::Debug,
 };
 
-use daggy::{
-    petgraph::visit::{IntoNodeIdentifiers, IntoNodeReferences},
-    NodeIndex,
-};
+use daggy::{petgraph::visit::IntoNodeIdentifiers, NodeIndex};
 use dozer_types::{
     log::warn,
-    node::{NodeHandle, OpIdentifier},
+    node::{NodeHandle, SourcePosition},
 };
 
 use crate::{
-    checkpoint::OptionCheckpoint,
+    checkpoint::{OptionCheckpoint, ReusedProcessor, StateMigration},
     dag_schemas::{DagHaveSchemas, DagSchemas, EdgeType},
     errors::ExecutionError,
-    node::{Processor, Sink, SinkFactory, Source},
+    executor::ExecutorOptions,
+    node::{Partitioner, Processor, Sink, SinkFactory, Source, SourceFactory},
     NodeKind as DagNodeKind,
 };
 
@@ -34,12 +33,27 @@ pub struct NodeType {
 pub enum NodeKind {
     Source {
         source: Box<dyn Source>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     },
     Processor(Box<dyn Processor>),
+    /// A processor running as several instances, each owning the keys the partitioner assigns to it.
+    PartitionedProcessor {
+        partitioner: Box<dyn Partitioner>,
+        processors: Vec<Box<dyn Processor>>,
+    },
     Sink(Box<dyn Sink>),
 }
 
+/// The handle a processor instance checkpoints its state under.
+///
+/// The number of partitions is part of the handle, because keys are reassigned when it changes.
+pub fn partition_handle(handle: &NodeHandle, index: usize, count: usize) -> NodeHandle {
+    NodeHandle::new(
+        handle.ns,
+        format!("{}-partition-{index}-of-{count}", handle.id),
+    )
+}
+
 /// Builder DAG builds all the sources, processors and sinks.
 /// It also asks each source if its possible to start from the given checkpoint.
 /// If not possible, it resets metadata and updates the checkpoint.
@@ -50,8 +64,9 @@ pub struct BuilderDag {
 
 impl BuilderDag {
     pub async fn new(
-        checkpoint: &OptionCheckpoint,
+        checkpoint: &mut OptionCheckpoint,
         dag_schemas: DagSchemas,
+        options: &ExecutorOptions,
     ) -> Result<Self, ExecutionError> {
         // Collect input output schemas.
         let mut input_schemas = HashMap::new();
@@ -61,15 +76,6 @@ impl BuilderDag {
             output_schemas.insert(node_index, dag_schemas.get_node_output_schemas(node_index));
         }
 
-        // Load processor checkpoint data.
-        let mut checkpoint_data = HashMap::new();
-        for (node_index, node) in dag_schemas.graph().node_references() {
-            if let DagNodeKind::Processor(_) = &node.kind {
-                let processor_data = checkpoint.load_processor_data(&node.handle).await?;
-                checkpoint_data.insert(node_index, processor_data);
-            }
-        }
-
         // Collect sources that may affect a node.
         let mut affecting_sources = dag_schemas
             .graph()
@@ -84,10 +90,100 @@ impl BuilderDag {
             .map(|node| Some(node.weight))
             .collect::<Vec<_>>();
 
+        // Collect partitioners of processors that run as several instances.
+        let mut partitioners = HashMap::new();
+        for (node_index, node) in nodes.iter().enumerate() {
+            let Some(super::NodeType {
+                handle,
+                kind: DagNodeKind::Processor(processor),
+            }) = node
+            else {
+                continue;
+            };
+            if options.parallelism_of(handle) <= 1 {
+                continue;
+            }
+            let node_index = NodeIndex::new(node_index);
+            let partitioner = processor
+                .partitioner(&input_schemas[&node_index])
+                .await
+                .map_err(ExecutionError::Factory)?;
+            match partitioner {
+                Some(partitioner) => {
+                    partitioners.insert(node_index, partitioner);
+                }
+                None => warn!(
+                    "Processor {handle} cannot be partitioned, running it as a single instance"
+                ),
+            }
+        }
+
+        // State checkpointed by a different number of instances cannot be loaded, so rebuild it from the sources.
+        if let Some(last_epoch_id) = checkpoint.last_epoch_id() {
+            let mut rebuilt_sources = HashSet::new();
+            for (node_index, node) in nodes.iter().enumerate() {
+                let Some(super::NodeType {
+                    handle,
+                    kind: DagNodeKind::Processor(_),
+                }) = node
+                else {
+                    continue;
+                };
+                let Some(state_handle) = checkpoint.processor_state_handle(handle) else {
+                    continue;
+                };
+                let state_handles = if partitioners.contains_key(&NodeIndex::new(node_index)) {
+                    let parallelism = options.parallelism_of(handle);
+                    (0..parallelism)
+                        .map(|index| partition_handle(&state_handle, index, parallelism))
+                        .collect()
+                } else {
+                    vec![state_handle]
+                };
+                if !state_handles
+                    .iter()
+                    .all(|state_handle| checkpoint.has_processor_data(state_handle))
+                {
+                    warn!(
+                        "Processor {handle} was checkpointed with a different parallelism, re-snapshotting sources {:?}",
+                        affecting_sources[node_index]
+                    );
+                    rebuilt_sources.extend(affecting_sources[node_index].iter().cloned());
+                }
+            }
+
+            if !rebuilt_sources.is_empty() {
+                let reused_processors = nodes
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(node_index, node)| {
+                        let super::NodeType {
+                            handle,
+                            kind: DagNodeKind::Processor(_),
+                        } = node.as_ref()?
+                        else {
+                            return None;
+                        };
+                        affecting_sources[node_index]
+                            .is_disjoint(&rebuilt_sources)
+                            .then(|| ReusedProcessor {
+                                handle: handle.clone(),
+                                previous_handle: handle.clone(),
+                            })
+                    })
+                    .collect();
+                checkpoint.add_state_migration(StateMigration {
+                    last_epoch_id,
+                    reused_processors,
+                    resnapshot_sources: rebuilt_sources.into_iter().collect(),
+                });
+            }
+        }
+
         // Build the sinks and load checkpoint.
         let mut graph = daggy::Dag::new();
         let mut source_states = HashMap::new();
-        let mut source_op_ids = HashMap::new();
+        let mut source_positions = HashMap::<NodeHandle, Vec<SourcePosition>>::new();
         let mut source_id_to_sinks = HashMap::<NodeHandle, Vec<NodeIndex>>::new();
         let mut node_index_map: HashMap<NodeIndex, NodeIndex> = HashMap::new();
         for (node_index, node) in nodes.iter_mut().enumerate() {
@@ -110,10 +206,10 @@ impl BuilderDag {
 
                 let state = sink.get_source_state().map_err(ExecutionError::Sink)?;
                 if let Some(state) = state {
-                    match source_states.entry(handle.clone()) {
+                    match source_states.entry(source.clone()) {
                         Entry::Occupied(entry) => {
                             if entry.get() != &state {
-                                return Err(ExecutionError::SourceStateConflict(handle));
+                                return Err(ExecutionError::SourceStateConflict(source));
                             }
                         }
                         Entry::Vacant(entry) => {
@@ -124,14 +220,10 @@ impl BuilderDag {
 
                 let op_id = sink.get_latest_op_id().map_err(ExecutionError::Sink)?;
                 if let Some(op_id) = op_id {
-                    match source_op_ids.entry(handle.clone()) {
-                        Entry::Occupied(mut entry) => {
-                            *entry.get_mut() = op_id.min(*entry.get());
-                        }
-                        Entry::Vacant(entry) => {
-                            entry.insert(op_id);
-                        }
-                    }
+                    source_positions
+                        .entry(source.clone())
+                        .or_default()
+                        .push(op_id);
                 }
 
                 let new_node_index = graph.add_node(NodeType {
@@ -154,12 +246,25 @@ impl BuilderDag {
             let node_index = NodeIndex::new(node_index);
             let node = match node.kind {
                 DagNodeKind::Source(source) => {
+                    let resnapshot = checkpoint.is_resnapshotting(&node.handle);
+                    let mut state = source_states.remove(&node.handle);
+                    let sink_positions = source_positions.remove(&node.handle).unwrap_or_default();
+                    let mut last_checkpoint = if checkpoint.is_chosen() {
+                        // Processor states are restored from the chosen checkpoint, so replay everything after it.
+                        checkpoint.get_source_state(&node.handle)?
+                    } else {
+                        oldest_position(source.as_ref(), &node.handle, sink_positions)?
+                    };
+                    if resnapshot {
+                        state = None;
+                        last_checkpoint = None;
+                    }
                     let source = source
                         .build(
                             output_schemas
                                 .remove(&node_index)
                                 .expect("we collected all output schemas"),
-                            source_states.remove(&node.handle),
+                            state,
                         )
                         .map_err(ExecutionError::Factory)?;
 
@@ -177,7 +282,6 @@ impl BuilderDag {
                             .map_err(ExecutionError::Sink)?;
                     }
 
-                    let last_checkpoint = source_op_ids.remove(&node.handle);
                     NodeType {
                         handle: node.handle,
                         kind: NodeKind::Source {
@@ -187,24 +291,65 @@ impl BuilderDag {
                     }
                 }
                 DagNodeKind::Processor(processor) => {
-                    let processor = processor
-                        .build(
-                            input_schemas
-                                .remove(&node_index)
-                                .expect("we collected all input schemas"),
-                            output_schemas
-                                .remove(&node_index)
-                                .expect("we collected all output schemas"),
-                            // checkpoint.record_store(),
-                            checkpoint_data
-                                .remove(&node_index)
-                                .expect("we collected all processor checkpoint data"),
+                    let node_input_schemas = input_schemas
+                        .remove(&node_index)
+                        .expect("we collected all input schemas");
+                    let node_output_schemas = output_schemas
+                        .remove(&node_index)
+                        .expect("we collected all output schemas");
+
+                    let parallelism = options.parallelism_of(&node.handle);
+                    let partitioner = partitioners.remove(&node_index);
+
+                    // Processors that are new or changed since the checkpoint was written start empty.
+                    let state_handle = checkpoint.processor_state_handle(&node.handle);
+                    let kind = if let Some(partitioner) = partitioner {
+                        let mut processors = Vec::with_capacity(parallelism);
+                        for index in 0..parallelism {
+                            let checkpoint_data = match &state_handle {
+                                Some(state_handle) => {
+                                    checkpoint
+                                        .load_processor_data(&partition_handle(
+                                            state_handle,
+                                            index,
+                                            parallelism,
+                                        ))
+                                        .await?
+                                }
+                                None => None,
+                            };
+                            processors.push(
+                                processor
+                                    .build(
+                                        node_input_schemas.clone(),
+                                        node_output_schemas.clone(),
+                                        checkpoint_data,
+                                    )
+                                    .await
+                                    .map_err(ExecutionError::Factory)?,
+                            );
+                        }
+                        NodeKind::PartitionedProcessor {
+                            partitioner,
+                            processors,
+                        }
+                    } else {
+                        let checkpoint_data = match &state_handle {
+                            Some(state_handle) => {
+                                checkpoint.load_processor_data(state_handle).await?
+                            }
+                            None => None,
+                        };
+                        NodeKind::Processor(
+                            processor
+                                .build(node_input_schemas, node_output_schemas, checkpoint_data)
+                                .await
+                                .map_err(ExecutionError::Factory)?,
                         )
-                        .await
-                        .map_err(ExecutionError::Factory)?;
+                    };
                     NodeType {
                         handle: node.handle,
-                        kind: NodeKind::Processor(processor),
+                        kind,
                     }
                 }
                 DagNodeKind::Sink(_) => unreachable!(),
@@ -236,6 +381,28 @@ impl BuilderDag {
     }
 }
 
+/// The position a source restarts from, so that no sink misses an operation.
+fn oldest_position(
+    source: &dyn SourceFactory,
+    handle: &NodeHandle,
+    positions: Vec<SourcePosition>,
+) -> Result<Option<SourcePosition>, ExecutionError> {
+    let mut oldest: Option<SourcePosition> = None;
+    for position in positions {
+        let Some(current) = &oldest else {
+            oldest = Some(position);
+            continue;
+        };
+        match source.compare_positions(&position, current) {
+            Some(Ordering::Less) => oldest = Some(position),
+            Some(_) => (),
+            None if &position == current => (),
+            None => return Err(ExecutionError::SourceStateConflict(handle.clone())),
+        }
+    }
+    Ok(oldest)
+}
+
 fn take_sink(node: &mut Option<super::NodeType>) -> Option<(NodeHandle, Box<dyn SinkFactory>)> {
     let super::NodeType { handle, kind } = node.take()?;
     if let super::NodeKind::Sink(sink) = kind {
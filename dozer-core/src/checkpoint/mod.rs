@@ -1,19 +1,27 @@
 use dozer_log::{
-    camino::Utf8Path,
-    reader::{list_record_store_slices, processor_prefix},
+    camino::{Utf8Path, Utf8PathBuf},
+    reader::{list_record_store_slices, processor_prefix, record_store_key},
     replication::create_data_storage,
-    storage::{self, Object, Queue, Storage},
-    tokio::task::JoinHandle,
+    storage::{self, ListedObject, Object, Queue, Storage},
+    tokio::{
+        sync::{mpsc, oneshot, watch},
+        task::JoinHandle,
+    },
 };
 use dozer_types::types::Field;
 use dozer_types::{
     bincode,
-    log::info,
+    log::{error, info},
     models::app_config::DataStorage,
-    node::{NodeHandle, OpIdentifier, SourceState, SourceStates},
+    node::{NodeHandle, SourcePosition, SourceState, SourceStates},
+    serde::{Deserialize, Serialize},
     tonic::codegen::tokio_stream::StreamExt,
 };
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::SystemTime,
+};
 use tempdir::TempDir;
 
 use crate::errors::ExecutionError;
@@ -22,6 +30,10 @@ use crate::errors::ExecutionError;
 pub struct CheckpointFactory {
     queue: Queue,
     prefix: String,
+    /// Record store slice uploads, with the source states of their checkpoints.
+    uploads: mpsc::UnboundedSender<(oneshot::Receiver<String>, Arc<SourceStates>)>,
+    /// Source states of the last checkpoint that has been fully persisted.
+    checkpointed: watch::Receiver<Arc<SourceStates>>,
 }
 
 #[derive(Debug, Clone)]
@@ -37,23 +49,98 @@ impl Default for CheckpointFactoryOptions {
     }
 }
 
+/// A complete checkpoint found in the checkpoint storage.
 #[derive(Debug, Clone)]
-struct Checkpoint {
-    processor_prefix: String,
-    epoch_id: u64,
-    source_states: SourceStates,
+pub struct CheckpointDescription {
+    pub epoch_id: u64,
+    /// Name of the savepoint that requested this checkpoint, if any.
+    pub savepoint: Option<String>,
+    pub created_at: SystemTime,
+    pub source_states: SourceStates,
+    pub processor_prefix: String,
+}
+
+impl CheckpointDescription {
+    /// Checkpoints can be referred to by epoch id or savepoint name.
+    pub fn matches(&self, id: &str) -> bool {
+        self.savepoint.as_deref() == Some(id) || id.parse::<u64>().ok() == Some(self.epoch_id)
+    }
+}
+
+/// How the checkpoints of a previous pipeline build map to the nodes of the current one.
+///
+/// Processor state is keyed by node handle, and handles of SQL processors are assigned positionally,
+/// so an unchanged processor can have a different handle after the SQL changes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct StateMigration {
+    /// The latest checkpoint written by the previous build. Later checkpoints are written by the current build.
+    pub last_epoch_id: u64,
+    /// Processors whose state is reused, with the handle the previous build checkpointed the state under.
+    /// Processors not listed here start with empty state.
+    pub reused_processors: Vec<ReusedProcessor>,
+    /// Sources that restart from the beginning, so that processors with empty state are rebuilt from their inputs.
+    pub resnapshot_sources: Vec<NodeHandle>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct ReusedProcessor {
+    pub handle: NodeHandle,
+    pub previous_handle: NodeHandle,
+}
+
+impl StateMigration {
+    /// Applies `next` on top of this migration, for when the pipeline is built again before a new checkpoint is written.
+    pub fn then(self, next: StateMigration) -> StateMigration {
+        let reused_processors = next
+            .reused_processors
+            .into_iter()
+            .filter_map(|processor| {
+                self.reused_processors
+                    .iter()
+                    .find(|previous| previous.handle == processor.previous_handle)
+                    .map(|previous| ReusedProcessor {
+                        handle: processor.handle,
+                        previous_handle: previous.previous_handle.clone(),
+                    })
+            })
+            .collect();
+        let mut resnapshot_sources = self.resnapshot_sources;
+        for source in next.resnapshot_sources {
+            if !resnapshot_sources.contains(&source) {
+                resnapshot_sources.push(source);
+            }
+        }
+        StateMigration {
+            last_epoch_id: next.last_epoch_id,
+            reused_processors,
+            resnapshot_sources,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct OptionCheckpoint {
     storage: Box<dyn Storage>,
     prefix: String,
-    checkpoint: Option<Checkpoint>,
+    checkpoint: Option<CheckpointDescription>,
+    /// All complete checkpoints, ordered by epoch id.
+    checkpoints: Vec<CheckpointDescription>,
+    /// Whether `checkpoint` was chosen by the user instead of being the latest one.
+    chosen: bool,
+    /// Set if the restored checkpoint was written by a previous pipeline build.
+    state_migration: Option<StateMigration>,
+    /// Keys of the processor and record writer states stored in `checkpoint`.
+    processor_keys: HashSet<String>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct CheckpointOptions {
     pub data_storage: DataStorage,
+    /// Epoch id or savepoint name of the checkpoint to restore. The latest checkpoint is restored if `None`.
+    pub restore_from: Option<String>,
+    pub state_migration: Option<StateMigration>,
 }
 
 impl OptionCheckpoint {
@@ -63,21 +150,119 @@ impl OptionCheckpoint {
     ) -> Result<Self, ExecutionError> {
         let (storage, prefix) =
             create_data_storage(options.data_storage, checkpoint_dir.to_string()).await?;
-        let checkpoint = read_record_store_slices(&*storage, &prefix).await?;
+        let checkpoints = read_checkpoints(&*storage, &prefix).await?;
+        let checkpoint = match &options.restore_from {
+            Some(id) => Some(
+                checkpoints
+                    .iter()
+                    .find(|checkpoint| checkpoint.matches(id))
+                    .cloned()
+                    .ok_or_else(|| ExecutionError::CheckpointNotFound(id.clone()))?,
+            ),
+            None => checkpoints.last().cloned(),
+        };
         if let Some(checkpoint) = &checkpoint {
             info!(
                 "Restored record store from epoch id {}, processor states are stored in {}",
                 checkpoint.epoch_id, checkpoint.processor_prefix
             );
         }
+        let state_migration = options.state_migration.filter(|migration| {
+            checkpoint
+                .as_ref()
+                .is_some_and(|checkpoint| checkpoint.epoch_id <= migration.last_epoch_id)
+        });
+        if let Some(migration) = &state_migration {
+            info!(
+                "Migrating state of {} processors from the previous build, re-snapshotting sources {:?}",
+                migration.reused_processors.len(),
+                migration.resnapshot_sources
+            );
+        }
+
+        let processor_keys = match &checkpoint {
+            Some(checkpoint) => list_all_objects(&*storage, checkpoint.processor_prefix.clone())
+                .await?
+                .into_iter()
+                .map(|object| object.key)
+                .collect(),
+            None => HashSet::new(),
+        };
 
         Ok(Self {
             storage,
             prefix,
             checkpoint,
+            checkpoints,
+            chosen: options.restore_from.is_some(),
+            state_migration,
+            processor_keys,
+        })
+    }
+
+    /// The handle the restored checkpoint stores `handle`'s state under, or `None` if the processor must start with empty state.
+    pub fn processor_state_handle(&self, handle: &NodeHandle) -> Option<NodeHandle> {
+        match &self.state_migration {
+            Some(migration) => migration
+                .reused_processors
+                .iter()
+                .find(|processor| &processor.handle == handle)
+                .map(|processor| processor.previous_handle.clone()),
+            None => Some(handle.clone()),
+        }
+    }
+
+    /// Whether the restored checkpoint stores state under `handle`.
+    pub fn has_processor_data(&self, handle: &NodeHandle) -> bool {
+        self.checkpoint.as_ref().is_some_and(|checkpoint| {
+            self.processor_keys
+                .contains(&processor_key(&checkpoint.processor_prefix, handle))
         })
     }
 
+    /// Applies `migration` on top of the one the checkpoint was restored with, if any.
+    pub fn add_state_migration(&mut self, migration: StateMigration) {
+        info!(
+            "Migrating state of {} processors, re-snapshotting sources {:?}",
+            migration.reused_processors.len(),
+            migration.resnapshot_sources
+        );
+        self.state_migration = Some(match self.state_migration.take() {
+            Some(previous) => previous.then(migration),
+            None => migration,
+        });
+    }
+
+    /// Whether `source` must ignore its checkpointed position and state and start from the beginning.
+    pub fn is_resnapshotting(&self, source: &NodeHandle) -> bool {
+        self.state_migration
+            .as_ref()
+            .is_some_and(|migration| migration.resnapshot_sources.contains(source))
+    }
+
+    /// Whether the restored checkpoint was chosen with `restore_from`. Sources must then restart from its positions rather than their sinks', which may be newer.
+    pub fn is_chosen(&self) -> bool {
+        self.chosen
+    }
+
+    pub fn checkpoints(&self) -> &[CheckpointDescription] {
+        &self.checkpoints
+    }
+
+    pub fn find_checkpoint(&self, id: &str) -> Option<&CheckpointDescription> {
+        self.checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.matches(id))
+    }
+
+    /// Lists the processor and record writer states stored in `checkpoint`.
+    pub async fn list_checkpoint_objects(
+        &self,
+        checkpoint: &CheckpointDescription,
+    ) -> Result<Vec<ListedObject>, storage::Error> {
+        list_all_objects(&*self.storage, checkpoint.processor_prefix.clone()).await
+    }
+
     pub fn storage(&self) -> &dyn Storage {
         &*self.storage
     }
@@ -92,8 +277,11 @@ impl OptionCheckpoint {
             .map(|checkpoint| checkpoint.epoch_id)
     }
 
+    /// Epochs continue after the latest checkpoint, even when an older one is restored, so existing checkpoints are never overwritten.
     pub fn next_epoch_id(&self) -> u64 {
-        self.last_epoch_id().map_or(0, |id| id + 1)
+        self.checkpoints
+            .last()
+            .map_or(0, |checkpoint| checkpoint.epoch_id + 1)
     }
 
     /// Returns the checkpointed source state, if any of the table is marked as non-restartable, returns `Err`.
@@ -101,7 +289,7 @@ impl OptionCheckpoint {
     pub fn get_source_state(
         &self,
         node_handle: &NodeHandle,
-    ) -> Result<Option<OpIdentifier>, ExecutionError> {
+    ) -> Result<Option<SourcePosition>, ExecutionError> {
         let Some(checkpoint) = self.checkpoint.as_ref() else {
             return Ok(None);
         };
@@ -114,7 +302,9 @@ impl OptionCheckpoint {
             SourceState::NonRestartable => {
                 Err(ExecutionError::SourceCannotRestart(node_handle.clone()))
             }
-            SourceState::Restartable(id) => Ok(Some(*id)),
+            SourceState::RestartableFromOpId(_) | SourceState::Restartable(_) => {
+                Ok(state.position())
+            }
         }
     }
 
@@ -136,6 +326,9 @@ impl OptionCheckpoint {
         node_handle: &NodeHandle,
         port_name: &str,
     ) -> Result<Option<Vec<u8>>, storage::Error> {
+        if self.is_resnapshotting(node_handle) {
+            return Ok(None);
+        }
         if let Some(checkpoint) = &self.checkpoint {
             let key = record_writer_key(&checkpoint.processor_prefix, node_handle, port_name);
             info!("Loading record writer {node_handle}-{port_name} checkpoint from {key}");
@@ -154,10 +347,24 @@ impl CheckpointFactory {
     ) -> Result<(Self, JoinHandle<()>), ExecutionError> {
         let (queue, worker) = Queue::new(checkpoint.storage, options.persist_queue_capacity);
 
+        // A checkpoint is persisted once its record store slice is uploaded, as it's uploaded last.
+        let (uploads, mut uploads_receiver) =
+            mpsc::unbounded_channel::<(oneshot::Receiver<String>, Arc<SourceStates>)>();
+        let (checkpointed_sender, checkpointed) = watch::channel(Arc::new(SourceStates::new()));
+        tokio::spawn(async move {
+            while let Some((upload, source_states)) = uploads_receiver.recv().await {
+                if upload.await.is_ok() {
+                    checkpointed_sender.send_replace(source_states);
+                }
+            }
+        });
+
         Ok((
             Self {
                 queue,
                 prefix: checkpoint.prefix,
+                uploads,
+                checkpointed,
             },
             worker,
         ))
@@ -166,6 +373,11 @@ impl CheckpointFactory {
     pub fn queue(&self) -> &Queue {
         &self.queue
     }
+
+    /// Source states of the last persisted checkpoint, updated whenever another one is persisted.
+    pub fn checkpointed_source_states(&self) -> watch::Receiver<Arc<SourceStates>> {
+        self.checkpointed.clone()
+    }
 }
 
 #[derive(Debug)]
@@ -177,9 +389,17 @@ struct RecordStoreSlice {
     data: Vec<u8>,
 }
 
+#[derive(Debug, bincode::Encode, bincode::Decode)]
+struct SavepointMeta {
+    epoch_id: u64,
+}
+
 #[derive(Debug)]
 pub struct CheckpointWriter {
     factory: Arc<CheckpointFactory>,
+    epoch_id: u64,
+    source_states: Arc<SourceStates>,
+    savepoint: Option<String>,
     processor_prefix: String,
 }
 
@@ -195,15 +415,50 @@ fn record_writer_key(processor_prefix: &str, node_handle: &NodeHandle, port_name
         .into_string()
 }
 
+fn savepoint_prefix(factory_prefix: &str) -> Utf8PathBuf {
+    AsRef::<Utf8Path>::as_ref(factory_prefix).join("savepoints")
+}
+
+fn savepoint_key(factory_prefix: &str, name: &str) -> String {
+    savepoint_prefix(factory_prefix).join(name).into_string()
+}
+
+/// Savepoint names are used as object keys and must not be confused with epoch ids.
+pub fn validate_savepoint_name(name: &str) -> Result<(), ExecutionError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !name.chars().all(|c| c.is_ascii_digit())
+        && !name.starts_with('.');
+    if valid {
+        Ok(())
+    } else {
+        Err(ExecutionError::InvalidSavepointName(name.to_string()))
+    }
+}
+
 impl CheckpointWriter {
-    pub fn new(factory: Arc<CheckpointFactory>, epoch_id: u64) -> Self {
+    pub fn new(
+        factory: Arc<CheckpointFactory>,
+        epoch_id: u64,
+        source_states: Arc<SourceStates>,
+        savepoint: Option<String>,
+    ) -> Self {
         let processor_prefix = processor_prefix(&factory.prefix, epoch_id).into();
         Self {
             factory,
+            epoch_id,
+            source_states,
+            savepoint,
             processor_prefix,
         }
     }
 
+    pub fn savepoint(&self) -> Option<&str> {
+        self.savepoint.as_deref()
+    }
+
     pub fn queue(&self) -> &Queue {
         &self.factory.queue
     }
@@ -229,17 +484,92 @@ impl CheckpointWriter {
 }
 
 impl Drop for CheckpointWriter {
-    fn drop(&mut self) {}
+    /// The writer is dropped after every node has queued its state, so the record store slice, whose presence marks the checkpoint as complete, is uploaded last.
+    fn drop(&mut self) {
+        let record_store_slice = RecordStoreSlice {
+            source_states: (*self.source_states).clone(),
+            data: vec![],
+        };
+        let data = bincode::encode_to_vec(&record_store_slice, bincode::config::legacy())
+            .expect("Record store slice must be serializable");
+        let key = record_store_key(&self.factory.prefix, self.epoch_id).into_string();
+        let Ok(upload) = self.factory.queue.upload_object(key, data) else {
+            error!(
+                "Failed to persist checkpoint of epoch {}: checkpoint writer thread quit",
+                self.epoch_id
+            );
+            return;
+        };
+        // No one is waiting for checkpoints if the factory's notifier quit.
+        let _ = self
+            .factory
+            .uploads
+            .send((upload, self.source_states.clone()));
+
+        if let Some(name) = &self.savepoint {
+            let meta = SavepointMeta {
+                epoch_id: self.epoch_id,
+            };
+            let data = bincode::encode_to_vec(&meta, bincode::config::legacy())
+                .expect("Savepoint meta must be serializable");
+            let key = savepoint_key(&self.factory.prefix, name);
+            if self.factory.queue.upload_object(key, data).is_err() {
+                error!("Failed to persist savepoint {name}: checkpoint writer thread quit");
+            } else {
+                info!("Created savepoint {name} at epoch {}", self.epoch_id);
+            }
+        }
+    }
 }
 
-async fn read_record_store_slices(
+async fn list_all_objects(
+    storage: &dyn Storage,
+    prefix: String,
+) -> Result<Vec<ListedObject>, storage::Error> {
+    let mut objects = vec![];
+    let mut continuation_token = None;
+    loop {
+        let output = storage
+            .list_objects(prefix.clone(), continuation_token)
+            .await?;
+        objects.extend(output.objects);
+        continuation_token = output.continuation_token;
+        if continuation_token.is_none() {
+            return Ok(objects);
+        }
+    }
+}
+
+async fn read_savepoints(
     storage: &dyn Storage,
     factory_prefix: &str,
-) -> Result<Option<Checkpoint>, ExecutionError> {
+) -> Result<HashMap<u64, String>, ExecutionError> {
+    let prefix = savepoint_prefix(factory_prefix);
+    let mut savepoints = HashMap::new();
+    for object in list_all_objects(storage, prefix.to_string()).await? {
+        let Ok(name) = AsRef::<Utf8Path>::as_ref(&object.key).strip_prefix(&prefix) else {
+            continue;
+        };
+        let name = name.to_string();
+        let data = storage.download_object(object.key).await?;
+        let meta: SavepointMeta = bincode::decode_from_slice(&data, bincode::config::legacy())
+            .map_err(ExecutionError::CorruptedCheckpoint)?
+            .0;
+        savepoints.insert(meta.epoch_id, name);
+    }
+    Ok(savepoints)
+}
+
+async fn read_checkpoints(
+    storage: &dyn Storage,
+    factory_prefix: &str,
+) -> Result<Vec<CheckpointDescription>, ExecutionError> {
+    let mut savepoints = read_savepoints(storage, factory_prefix).await?;
+
     let stream = list_record_store_slices(storage, factory_prefix);
     let mut stream = std::pin::pin!(stream);
 
-    let mut last_checkpoint: Option<Checkpoint> = None;
+    let mut checkpoints = vec![];
     while let Some(meta) = stream.next().await {
         let meta = meta?;
         info!("Loading {}", meta.key);
@@ -248,14 +578,17 @@ async fn read_record_store_slices(
             bincode::decode_from_slice(&data, bincode::config::legacy())
                 .map_err(ExecutionError::CorruptedCheckpoint)?
                 .0;
-        last_checkpoint = Some(Checkpoint {
+        checkpoints.push(CheckpointDescription {
             epoch_id: meta.epoch_id,
+            savepoint: savepoints.remove(&meta.epoch_id),
+            created_at: meta.last_modified,
             source_states: record_store_slice.source_states,
             processor_prefix: meta.processor_prefix.into(),
         });
     }
+    checkpoints.sort_by_key(|checkpoint| checkpoint.epoch_id);
 
-    Ok(last_checkpoint)
+    Ok(checkpoints)
 }
 
 /// This is only meant to be used in tests.
@@ -290,9 +623,17 @@ pub async fn create_checkpoint_factory_for_test(
     )]
     .into_iter()
     .collect();
-    std::thread::spawn(move || drop(CheckpointWriter::new(factory, epoch_id)))
-        .join()
-        .unwrap();
+    let writer_source_states = Arc::new(source_states.clone());
+    std::thread::spawn(move || {
+        drop(CheckpointWriter::new(
+            factory,
+            epoch_id,
+            writer_source_states,
+            None,
+        ))
+    })
+    .join()
+    .unwrap();
     handle.await.unwrap();
 
     // Create a new factory that loads from the checkpoint.
@@ -309,3 +650,112 @@ pub async fn create_checkpoint_factory_for_test(
 }
 
 pub mod serialize;
+
+#[cfg(test)]
+mod tests {
+    use dozer_log::tokio;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_restore_from_savepoint() {
+        let temp_dir = TempDir::new("test_restore_from_savepoint").unwrap();
+        let checkpoint_dir = temp_dir.path().to_str().unwrap().to_string();
+        let checkpoint = OptionCheckpoint::new(checkpoint_dir.clone(), Default::default())
+            .await
+            .unwrap();
+        let (factory, handle) = CheckpointFactory::new(checkpoint, Default::default())
+            .await
+            .unwrap();
+        let factory = Arc::new(factory);
+        std::thread::spawn(move || {
+            for (epoch_id, savepoint) in [(1, Some("before-upgrade")), (2, None)] {
+                drop(CheckpointWriter::new(
+                    factory.clone(),
+                    epoch_id,
+                    Default::default(),
+                    savepoint.map(ToString::to_string),
+                ));
+            }
+        })
+        .join()
+        .unwrap();
+        handle.await.unwrap();
+
+        let checkpoint = OptionCheckpoint::new(checkpoint_dir.clone(), Default::default())
+            .await
+            .unwrap();
+        assert_eq!(checkpoint.checkpoints().len(), 2);
+        assert_eq!(checkpoint.last_epoch_id(), Some(2));
+        assert_eq!(
+            checkpoint
+                .find_checkpoint("1")
+                .unwrap()
+                .savepoint
+                .as_deref(),
+            Some("before-upgrade")
+        );
+
+        let options = CheckpointOptions {
+            restore_from: Some("before-upgrade".to_string()),
+            ..Default::default()
+        };
+        let checkpoint = OptionCheckpoint::new(checkpoint_dir.clone(), options)
+            .await
+            .unwrap();
+        assert_eq!(checkpoint.last_epoch_id(), Some(1));
+        assert_eq!(checkpoint.next_epoch_id(), 3);
+
+        let options = CheckpointOptions {
+            restore_from: Some("missing".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            OptionCheckpoint::new(checkpoint_dir, options).await,
+            Err(ExecutionError::CheckpointNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_savepoint_name() {
+        assert!(validate_savepoint_name("before-upgrade_1.2").is_ok());
+        assert!(validate_savepoint_name("").is_err());
+        assert!(validate_savepoint_name("42").is_err());
+        assert!(validate_savepoint_name("a/b").is_err());
+        assert!(validate_savepoint_name(".hidden").is_err());
+    }
+
+    #[test]
+    fn test_compose_state_migrations() {
+        let handle = |id: &str| NodeHandle::new(None, id.to_string());
+        let reused = |handle: NodeHandle, previous_handle: NodeHandle| ReusedProcessor {
+            handle,
+            previous_handle,
+        };
+        let first = StateMigration {
+            last_epoch_id: 3,
+            reused_processors: vec![
+                reused(handle("agg--2"), handle("agg--1")),
+                reused(handle("from--2"), handle("from--1")),
+            ],
+            resnapshot_sources: vec![handle("users")],
+        };
+        let second = StateMigration {
+            last_epoch_id: 3,
+            reused_processors: vec![
+                reused(handle("agg--3"), handle("agg--2")),
+                // Rebuilt by the first migration, so there's no state to reuse.
+                reused(handle("join--3"), handle("join--2")),
+            ],
+            resnapshot_sources: vec![handle("users"), handle("orders")],
+        };
+        assert_eq!(
+            first.then(second),
+            StateMigration {
+                last_epoch_id: 3,
+                reused_processors: vec![reused(handle("agg--3"), handle("agg--1"))],
+                resnapshot_sources: vec![handle("users"), handle("orders")],
+            }
+        );
+    }
+}
@@ -1,7 +1,10 @@
+use dozer_log::tokio::sync::Notify;
 use dozer_types::log::info;
 use dozer_types::node::{NodeHandle, SourceState, SourceStates};
 use dozer_types::parking_lot::Mutex;
+use std::collections::VecDeque;
 use std::ops::DerefMut;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Barrier};
 use std::thread::sleep;
 use std::time::{Duration, SystemTime};
@@ -42,6 +45,8 @@ enum EpochManagerStateKind {
         epoch_id: u64,
         /// Collected source states.
         source_states: Arc<SourceStates>,
+        /// The checkpoint writer shared by all sources, if this epoch is checkpointed.
+        checkpoint_writer: Option<Arc<CheckpointWriter>>,
         /// Instant when the epoch was closed.
         instant: SystemTime,
         /// Number of sources that have confirmed the epoch close.
@@ -104,11 +109,42 @@ impl Default for EpochManagerOptions {
     }
 }
 
+/// Named checkpoints requested while the pipeline is running. A savepoint is taken at the first epoch that can be checkpointed after the request.
+#[derive(Debug, Clone, Default)]
+pub struct SavepointRequests {
+    names: Arc<Mutex<VecDeque<String>>>,
+    notify: Arc<Notify>,
+}
+
+impl SavepointRequests {
+    pub fn request(&self, name: String) {
+        info!("Savepoint {name} requested");
+        self.names.lock().push_back(name);
+        self.notify.notify_one();
+    }
+
+    /// Resolves when a savepoint is requested, so idle sources can close an epoch for it.
+    pub async fn requested(&self) {
+        self.notify.notified().await
+    }
+
+    fn is_pending(&self) -> bool {
+        !self.names.lock().is_empty()
+    }
+
+    fn pop(&self) -> Option<String> {
+        self.names.lock().pop_front()
+    }
+}
+
 #[derive(Debug)]
 pub struct EpochManager {
     num_sources: usize,
     checkpoint_factory: Arc<CheckpointFactory>,
     options: EpochManagerOptions,
+    savepoint_requests: SavepointRequests,
+    /// Number of records ingested since the epoch manager started.
+    num_records: AtomicUsize,
     state: Mutex<EpochManagerState>,
 }
 
@@ -127,6 +163,7 @@ impl EpochManager {
         epoch_id: u64,
         checkpoint_factory: Arc<CheckpointFactory>,
         options: EpochManagerOptions,
+        savepoint_requests: SavepointRequests,
     ) -> Self {
         debug_assert!(num_sources > 0);
         let next_record_index_to_persist = 0;
@@ -134,6 +171,8 @@ impl EpochManager {
             num_sources,
             checkpoint_factory,
             options,
+            savepoint_requests,
+            num_records: AtomicUsize::new(0),
             state: Mutex::new(EpochManagerState {
                 kind: EpochManagerStateKind::new_closing(epoch_id, num_sources),
                 next_record_index_to_persist,
@@ -146,20 +185,26 @@ impl EpochManager {
         self.state.lock().kind.epoch_id()
     }
 
+    pub fn add_records(&self, num_records: usize) {
+        self.num_records.fetch_add(num_records, Ordering::Relaxed);
+    }
+
     /// Waits for the epoch to close until all sources do so.
     ///
     /// Returns whether the participant should terminate, the epoch id if the source should commit, and the instant when the decision was made.
     ///
     /// # Arguments
     ///
+    /// - `source_states`: States of the sources this participant runs.
     /// - `request_termination`: Whether the source wants to terminate. The `EpochManager` checks if all sources want to terminate and returns `true` if so.
     /// - `request_commit`: Whether the source wants to commit. The `EpochManager` checks if any source wants to commit and returns `Some` if so.
     pub fn wait_for_epoch_close(
         &self,
-        source_state: (NodeHandle, SourceState),
+        source_states: impl IntoIterator<Item = (NodeHandle, SourceState)>,
         request_termination: bool,
         request_commit: bool,
     ) -> ClosedEpoch {
+        let mut new_source_states = Some(source_states);
         let barrier = loop {
             let mut state = self.state.lock();
             match &mut state.kind {
@@ -175,7 +220,11 @@ impl EpochManager {
                     // If anyone wants to commit, we commit.
                     *should_commit = *should_commit || request_commit;
                     // Collect source states.
-                    source_states.insert(source_state.0, source_state.1);
+                    source_states.extend(
+                        new_source_states
+                            .take()
+                            .expect("We only collect source states once"),
+                    );
                     break barrier.clone();
                 }
                 EpochManagerStateKind::Closed { .. } => {
@@ -200,10 +249,12 @@ impl EpochManager {
         } = &mut state.kind
         {
             let instant = SystemTime::now();
+            let savepoint_requested = self.savepoint_requests.is_pending();
             let action = if *should_commit {
-                let num_records = 0;
-                if num_records - state.next_record_index_to_persist
-                    >= self.options.max_num_records_before_persist
+                let num_records = self.num_records.load(Ordering::Relaxed);
+                if savepoint_requested
+                    || num_records - state.next_record_index_to_persist
+                        >= self.options.max_num_records_before_persist
                     || instant
                         .duration_since(state.last_persisted_epoch_decision_instant)
                         .unwrap_or(Duration::from_secs(0))
@@ -223,11 +274,30 @@ impl EpochManager {
                 Action::Nothing
             };
 
+            let source_states = Arc::new(std::mem::take(source_states));
+            let checkpoint_writer = if action.should_persist() && is_restartable(&source_states) {
+                let savepoint = self.savepoint_requests.pop();
+                (savepoint.is_some() || self.options.enable_app_checkpoints).then(|| {
+                    Arc::new(CheckpointWriter::new(
+                        self.checkpoint_factory.clone(),
+                        *epoch_id,
+                        source_states.clone(),
+                        savepoint,
+                    ))
+                })
+            } else {
+                if savepoint_requested && action.should_persist() {
+                    info!("Deferring savepoint, some sources cannot restart from epoch {epoch_id}");
+                }
+                None
+            };
+
             state.kind = EpochManagerStateKind::Closed {
                 terminating: *should_terminate,
                 action,
                 epoch_id: *epoch_id,
-                source_states: Arc::new(std::mem::take(source_states)),
+                source_states,
+                checkpoint_writer,
                 instant,
                 num_source_confirmations: 0,
             };
@@ -239,25 +309,17 @@ impl EpochManager {
                 action,
                 epoch_id,
                 source_states,
+                checkpoint_writer,
                 instant,
                 num_source_confirmations,
             } => {
                 let common_info = action.should_commit().then(|| {
-                    let checkpoint_writer = (action.should_persist()
-                        && self.options.enable_app_checkpoints
-                        && is_restartable(source_states))
-                    .then(|| {
-                        Arc::new(CheckpointWriter::new(
-                            self.checkpoint_factory.clone(),
-                            *epoch_id,
-                        ))
-                    });
                     let sink_persist_queue = action
                         .should_persist()
                         .then(|| self.checkpoint_factory.queue().clone());
                     EpochCommonInfo {
                         id: *epoch_id,
-                        checkpoint_writer,
+                        checkpoint_writer: checkpoint_writer.clone(),
                         sink_persist_queue,
                         source_states: source_states.clone(),
                     }
@@ -316,7 +378,13 @@ mod tests {
     ) -> (TempDir, EpochManager) {
         let (temp_dir, checkpoint_factory, _) = create_checkpoint_factory_for_test().await;
 
-        let epoch_manager = EpochManager::new(num_sources, 0, checkpoint_factory, options);
+        let epoch_manager = EpochManager::new(
+            num_sources,
+            0,
+            checkpoint_factory,
+            options,
+            Default::default(),
+        );
 
         (temp_dir, epoch_manager)
     }
@@ -332,7 +400,7 @@ mod tests {
                 .map(|index| {
                     scope.spawn(move || {
                         epoch_manager.wait_for_epoch_close(
-                            source_state_gen(index),
+                            [source_state_gen(index)],
                             termination_gen(index),
                             commit_gen(index),
                         )
@@ -433,20 +501,21 @@ mod tests {
         let source_state = generate_source_state(0);
         std::thread::spawn(move || {
             // No record, no persist.
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            let epoch = epoch_manager.wait_for_epoch_close([source_state.clone()], false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_none());
             assert!(common_info.sink_persist_queue.is_none());
 
             // One record, persist.
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            epoch_manager.add_records(1);
+            let epoch = epoch_manager.wait_for_epoch_close([source_state.clone()], false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_some());
             assert!(common_info.sink_persist_queue.is_some());
 
             // Time passes, persist.
             std::thread::sleep(Duration::from_secs(1));
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            let epoch = epoch_manager.wait_for_epoch_close([source_state.clone()], false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_some());
             assert!(common_info.sink_persist_queue.is_some());
@@ -468,20 +537,21 @@ mod tests {
         let source_state = generate_source_state(0);
         std::thread::spawn(move || {
             // No record, no persist.
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            let epoch = epoch_manager.wait_for_epoch_close([source_state.clone()], false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_none());
             assert!(common_info.sink_persist_queue.is_none());
 
             // One record, persist.
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            epoch_manager.add_records(1);
+            let epoch = epoch_manager.wait_for_epoch_close([source_state.clone()], false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_none());
             assert!(common_info.sink_persist_queue.is_some());
 
             // Time passes, persist.
             std::thread::sleep(Duration::from_secs(1));
-            let epoch = epoch_manager.wait_for_epoch_close(source_state.clone(), false, true);
+            let epoch = epoch_manager.wait_for_epoch_close([source_state.clone()], false, true);
             let common_info = epoch.common_info.unwrap();
             assert!(common_info.checkpoint_writer.is_none());
             assert!(common_info.sink_persist_queue.is_some());
@@ -489,4 +559,33 @@ mod tests {
         .join()
         .unwrap();
     }
+
+    #[tokio::test]
+    async fn test_epoch_manager_savepoint() {
+        let (_temp_dir, epoch_manager) = create_epoch_manager(1, Default::default()).await;
+        let savepoint_requests = epoch_manager.savepoint_requests.clone();
+
+        std::thread::spawn(move || {
+            // Checkpoints are disabled, but a requested savepoint is still taken.
+            savepoint_requests.request("before-upgrade".to_string());
+            let epoch = epoch_manager.wait_for_epoch_close(
+                [(
+                    NodeHandle::new(Some(0), "0".to_string()),
+                    SourceState::NonRestartable,
+                )],
+                false,
+                true,
+            );
+            assert!(epoch.common_info.unwrap().checkpoint_writer.is_none());
+
+            let epoch = epoch_manager.wait_for_epoch_close([generate_source_state(0)], false, true);
+            let checkpoint_writer = epoch.common_info.unwrap().checkpoint_writer.unwrap();
+            assert_eq!(checkpoint_writer.savepoint(), Some("before-upgrade"));
+
+            let epoch = epoch_manager.wait_for_epoch_close([generate_source_state(0)], false, true);
+            assert!(epoch.common_info.unwrap().checkpoint_writer.is_none());
+        })
+        .join()
+        .unwrap();
+    }
 }
@@ -1,7 +1,7 @@
-use std::{sync::Arc, time::SystemTime};
+use std::{collections::HashMap, sync::Arc, time::SystemTime};
 
 use dozer_log::storage::Queue;
-use dozer_types::node::SourceStates;
+use dozer_types::node::{NodeHandle, SourceStates};
 
 #[derive(Clone, Debug)]
 pub struct EpochCommonInfo {
@@ -11,10 +11,20 @@ pub struct EpochCommonInfo {
     pub source_states: Arc<SourceStates>,
 }
 
+/// How much of a source the pipeline had received when an epoch was closed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SourceProgress {
+    /// Operations received since the pipeline started.
+    pub operations: u64,
+    /// When the source committed the latest operation received, if its connector reports it.
+    pub source_time: Option<SystemTime>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Epoch {
     pub common_info: EpochCommonInfo,
     pub decision_instant: SystemTime,
+    pub source_progress: Arc<HashMap<NodeHandle, SourceProgress>>,
 }
 
 impl Epoch {
@@ -33,6 +43,7 @@ impl Epoch {
                 source_states,
             },
             decision_instant,
+            source_progress: Default::default(),
         }
     }
 
@@ -40,8 +51,21 @@ impl Epoch {
         Self {
             common_info,
             decision_instant,
+            source_progress: Default::default(),
         }
     }
+
+    pub fn with_source_progress(
+        mut self,
+        source_progress: HashMap<NodeHandle, SourceProgress>,
+    ) -> Self {
+        self.source_progress = Arc::new(source_progress);
+        self
+    }
 }
 
 use crate::checkpoint::CheckpointWriter;
+
+mod manager;
+
+pub use manager::{ClosedEpoch, EpochManager, EpochManagerOptions, SavepointRequests};
@@ -27,6 +27,10 @@ impl ErrorManager {
         }
     }
 
+    pub fn count(&self) -> u32 {
+        self.count.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
     pub fn report(&self, error: BoxedError) {
         let err_span = error_span!("reported error", error = true, e = error);
         let _error_guard = err_span.enter();
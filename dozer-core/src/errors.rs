@@ -56,6 +56,10 @@ pub enum ExecutionError {
     FailedToCreateCheckpoint(BoxedError),
     #[error("Failed to serialize record writer: {0}")]
     SerializeRecordWriter(#[source] SerializationError),
+    #[error("Checkpoint {0} not found")]
+    CheckpointNotFound(String),
+    #[error("Invalid savepoint name {0:?}. Names may contain letters, digits, '-', '_' and '.', and must not be a number")]
+    InvalidSavepointName(String),
 }
 
 impl<T> From<crossbeam::channel::SendError<T>> for ExecutionError {
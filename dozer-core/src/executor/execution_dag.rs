@@ -1,5 +1,5 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     fmt::Debug,
     sync::Arc,
 };
@@ -61,7 +61,7 @@ pub struct ExecutionDag {
 impl ExecutionDag {
     pub async fn new(
         builder_dag: BuilderDag,
-        checkpoint: OptionCheckpoint,
+        checkpoint: &OptionCheckpoint,
         labels: LabelsAndProgress,
         channel_buffer_sz: usize,
         error_threshold: Option<u32>,
@@ -236,6 +236,23 @@ impl ExecutionDag {
         record_writers
     }
 
+    /// Names of the output ports that have record writers, which key the record writers in checkpoints.
+    pub fn collect_record_writer_port_names(
+        &self,
+        node_index: daggy::NodeIndex,
+    ) -> HashMap<PortHandle, String> {
+        self.graph
+            .edges(node_index)
+            .filter_map(|edge| match &edge.weight().edge_kind {
+                EdgeKind::FromSource {
+                    port_type: OutputPortType::StatefulWithPrimaryKeyLookup,
+                    port_name,
+                } => Some((edge.weight().output_port, port_name.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn collect_receivers(
         &self,
         node_index: daggy::NodeIndex,
@@ -254,4 +271,24 @@ impl ExecutionDag {
         }
         handles_and_receivers.into_values().unzip()
     }
+
+    /// Handles of the sources whose data reaches this node, directly or through processors.
+    pub fn collect_upstream_sources(&self, node_index: daggy::NodeIndex) -> Vec<NodeHandle> {
+        let mut visited = HashSet::from([node_index]);
+        let mut to_visit = vec![node_index];
+        let mut sources = vec![];
+        while let Some(node_index) = to_visit.pop() {
+            for edge in self.graph.edges_directed(node_index, Direction::Incoming) {
+                if !visited.insert(edge.source()) {
+                    continue;
+                }
+                if matches!(edge.weight().edge_kind, EdgeKind::FromSource { .. }) {
+                    sources.push(self.graph[edge.source()].handle.clone());
+                } else {
+                    to_visit.push(edge.source());
+                }
+            }
+        }
+        sources
+    }
 }
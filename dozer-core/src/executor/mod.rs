@@ -1,14 +1,17 @@
 use crate::builder_dag::{BuilderDag, NodeKind};
-use crate::checkpoint::{CheckpointFactoryOptions, OptionCheckpoint};
+use crate::checkpoint::{CheckpointFactory, CheckpointFactoryOptions, OptionCheckpoint};
 use crate::dag_schemas::DagSchemas;
+use crate::epoch::{EpochManagerOptions, SavepointRequests};
 use crate::errors::ExecutionError;
 use crate::Dag;
 
 use daggy::petgraph::visit::IntoNodeIdentifiers;
+use dozer_types::node::NodeHandle;
 
 use dozer_log::tokio::runtime::Runtime;
 use dozer_tracing::LabelsAndProgress;
 use futures::Future;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 use std::thread::JoinHandle;
@@ -22,6 +25,21 @@ pub struct ExecutorOptions {
     pub commit_time_threshold: Duration,
     pub error_threshold: Option<u32>,
     pub checkpoint_factory_options: CheckpointFactoryOptions,
+    pub epoch_manager_options: EpochManagerOptions,
+    /// Number of instances of each processor that can partition its input by key.
+    pub parallelism: usize,
+    /// Parallelism of individual processors, by processor id.
+    pub processor_parallelism: HashMap<String, usize>,
+}
+
+impl ExecutorOptions {
+    pub fn parallelism_of(&self, handle: &NodeHandle) -> usize {
+        self.processor_parallelism
+            .get(&handle.id)
+            .copied()
+            .unwrap_or(self.parallelism)
+            .max(1)
+    }
 }
 
 impl Default for ExecutorOptions {
@@ -32,6 +50,9 @@ impl Default for ExecutorOptions {
             commit_time_threshold: Duration::from_millis(50),
             error_threshold: Some(0),
             checkpoint_factory_options: Default::default(),
+            epoch_manager_options: Default::default(),
+            parallelism: 1,
+            processor_parallelism: HashMap::new(),
         }
     }
 }
@@ -39,14 +60,21 @@ impl Default for ExecutorOptions {
 mod execution_dag;
 mod name;
 mod node;
+mod partitioned_processor_node;
 mod processor_node;
 mod receiver_loop;
 mod sink_node;
 mod source_node;
+mod status;
 
 use node::Node;
+use partitioned_processor_node::PartitionedProcessorNode;
 use processor_node::ProcessorNode;
+pub use processor_node::{PROCESSOR_LATENCY_HISTOGRAM_NAME, PROCESSOR_OPERATION_COUNTER_NAME};
 use sink_node::SinkNode;
+pub use status::{
+    CheckpointStatus, PipelineStatus, PipelineStatusReport, SinkStatus, SourcePhase, SourceStatus,
+};
 
 use self::execution_dag::ExecutionDag;
 use self::source_node::{create_source_node, SourceNode};
@@ -55,29 +83,44 @@ pub struct DagExecutor {
     builder_dag: BuilderDag,
     checkpoint: OptionCheckpoint,
     options: ExecutorOptions,
+    savepoint_requests: SavepointRequests,
+    status: PipelineStatus,
 }
 
 pub struct DagExecutorJoinHandle {
     join_handles: Vec<JoinHandle<Result<(), ExecutionError>>>,
+    checkpoint_worker: dozer_log::tokio::task::JoinHandle<()>,
 }
 
 impl DagExecutor {
     pub async fn new(
         dag: Dag,
-        checkpoint: OptionCheckpoint,
+        mut checkpoint: OptionCheckpoint,
         options: ExecutorOptions,
     ) -> Result<Self, ExecutionError> {
         let dag_schemas = DagSchemas::new(dag).await?;
 
-        let builder_dag = BuilderDag::new(&checkpoint, dag_schemas).await?;
+        let builder_dag = BuilderDag::new(&mut checkpoint, dag_schemas, &options).await?;
 
         Ok(Self {
             builder_dag,
             checkpoint,
             options,
+            savepoint_requests: Default::default(),
+            status: Default::default(),
         })
     }
 
+    /// Savepoints requested through the returned handle are taken once the executor is started.
+    pub fn savepoint_requests(&self) -> SavepointRequests {
+        self.savepoint_requests.clone()
+    }
+
+    /// The returned handle reports on the pipeline once the executor is started.
+    pub fn status(&self) -> PipelineStatus {
+        self.status.clone()
+    }
+
     pub async fn validate<T: Clone + Debug>(dag: Dag) -> Result<(), ExecutionError> {
         DagSchemas::new(dag).await?;
         Ok(())
@@ -92,17 +135,32 @@ impl DagExecutor {
         // Construct execution dag.
         let mut execution_dag = ExecutionDag::new(
             self.builder_dag,
-            self.checkpoint,
+            &self.checkpoint,
             labels,
             self.options.channel_buffer_sz,
             self.options.error_threshold,
         )
         .await?;
+        self.status.start(execution_dag.error_manager().clone());
         let node_indexes = execution_dag.graph().node_identifiers().collect::<Vec<_>>();
 
+        let (checkpoint_factory, checkpoint_worker) = CheckpointFactory::new(
+            self.checkpoint,
+            self.options.checkpoint_factory_options.clone(),
+        )
+        .await?;
+
         // Start the threads.
-        let source_node =
-            create_source_node(&mut execution_dag, &self.options, shutdown, runtime.clone()).await;
+        let source_node = create_source_node(
+            &mut execution_dag,
+            &self.options,
+            Arc::new(checkpoint_factory),
+            self.savepoint_requests,
+            self.status.clone(),
+            shutdown,
+            runtime.clone(),
+        )
+        .await;
         let mut join_handles = vec![start_source(source_node)?];
         for node_index in node_indexes {
             let Some(node) = execution_dag.graph()[node_index].kind.as_ref() else {
@@ -114,14 +172,26 @@ impl DagExecutor {
                     let processor_node = ProcessorNode::new(&mut execution_dag, node_index).await;
                     join_handles.push(start_processor(processor_node)?);
                 }
+                NodeKind::PartitionedProcessor { .. } => {
+                    let processor_node = PartitionedProcessorNode::new(
+                        &mut execution_dag,
+                        node_index,
+                        self.options.channel_buffer_sz,
+                    )
+                    .await;
+                    join_handles.extend(start_partitioned_processor(processor_node)?);
+                }
                 NodeKind::Sink(_) => {
-                    let sink_node = SinkNode::new(&mut execution_dag, node_index);
+                    let sink_node = SinkNode::new(&mut execution_dag, node_index, &self.status);
                     join_handles.push(start_sink(sink_node)?);
                 }
             }
         }
 
-        Ok(DagExecutorJoinHandle { join_handles })
+        Ok(DagExecutorJoinHandle {
+            join_handles,
+            checkpoint_worker,
+        })
     }
 }
 
@@ -142,6 +212,9 @@ impl DagExecutorJoinHandle {
             handle.join().unwrap()?;
 
             if self.join_handles.is_empty() {
+                // All nodes have released the checkpoint queue, wait for pending checkpoints to be uploaded.
+                futures::executor::block_on(self.checkpoint_worker)
+                    .map_err(|_| ExecutionError::CheckpointWriterThreadPanicked)?;
                 return Ok(());
             }
         }
@@ -183,6 +256,35 @@ fn start_processor(
         .map_err(ExecutionError::CannotSpawnWorkerThread)
 }
 
+fn start_partitioned_processor(
+    processor: PartitionedProcessorNode,
+) -> Result<Vec<JoinHandle<Result<(), ExecutionError>>>, ExecutionError> {
+    let handle = processor.handle().to_string();
+    let PartitionedProcessorNode {
+        router,
+        instances,
+        merger,
+    } = processor;
+
+    let mut join_handles = Vec::with_capacity(instances.len() + 2);
+    join_handles.push(
+        Builder::new()
+            .name(format!("{handle}-router"))
+            .spawn(move || router.run())
+            .map_err(ExecutionError::CannotSpawnWorkerThread)?,
+    );
+    for instance in instances {
+        join_handles.push(start_processor(instance)?);
+    }
+    join_handles.push(
+        Builder::new()
+            .name(format!("{handle}-merger"))
+            .spawn(move || merger.run())
+            .map_err(ExecutionError::CannotSpawnWorkerThread)?,
+    );
+    Ok(join_handles)
+}
+
 fn start_sink(sink: SinkNode) -> Result<JoinHandle<Result<(), ExecutionError>>, ExecutionError> {
     Builder::new()
         .name(sink.handle().to_string())
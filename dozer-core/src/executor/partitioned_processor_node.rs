@@ -0,0 +1,584 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::mem::swap;
+use std::sync::Arc;
+
+use crossbeam::channel::{bounded, Receiver, Sender};
+use daggy::NodeIndex;
+use dozer_types::errors::internal::BoxedError;
+use dozer_types::node::{NodeHandle, SourcePosition};
+use dozer_types::types::{Field, Operation, PortHandle, Record, TableOperation};
+
+use crate::builder_dag::{partition_handle, NodeKind};
+use crate::epoch::Epoch;
+use crate::error_manager::ErrorManager;
+use crate::errors::ExecutionError;
+use crate::executor_operation::ExecutorOperation;
+use crate::forwarder::{ChannelManager, SenderWithPortMapping};
+use crate::node::Partitioner;
+
+use super::{
+    execution_dag::ExecutionDag, name::Name, processor_node::ProcessorNode,
+    receiver_loop::ReceiverLoop,
+};
+
+/// A processor running as several instances.
+///
+/// The router hashes the key of every input record to pick the instance that handles it,
+/// and broadcasts everything else. The merger forwards the instances' output downstream,
+/// and forwards a commit once every instance has committed the epoch, so downstream nodes
+/// and checkpoints see the same epochs as with a single instance.
+///
+/// An update that moves a key to another instance is split into a delete and an insert.
+/// Both outputs may have the same primary key, so the router sends the insert only after
+/// the merger has acknowledged a barrier sent behind the delete.
+#[derive(Debug)]
+pub struct PartitionedProcessorNode {
+    pub router: PartitionRouter,
+    pub instances: Vec<ProcessorNode>,
+    pub merger: PartitionMerger,
+}
+
+impl PartitionedProcessorNode {
+    pub async fn new(
+        dag: &mut ExecutionDag,
+        node_index: NodeIndex,
+        channel_buffer_sz: usize,
+    ) -> Self {
+        let node = dag.node_weight_mut(node_index);
+        let Some(kind) = node.kind.take() else {
+            panic!("Must pass in a node")
+        };
+        let node_handle = node.handle.clone();
+        let NodeKind::PartitionedProcessor {
+            partitioner,
+            processors,
+        } = kind
+        else {
+            panic!("Must pass in a partitioned processor node");
+        };
+
+        let (node_handles, receivers) = dag.collect_receivers(node_index);
+        let senders = dag.collect_senders(node_index);
+        let record_writers = dag.collect_record_writers(node_index).await;
+        let output_ports = senders
+            .iter()
+            .flat_map(|sender| sender.port_mapping.keys().copied())
+            .collect::<HashSet<PortHandle>>();
+
+        let initial_epoch_id = dag.initial_epoch_id();
+        let error_manager = dag.error_manager().clone();
+        let count = processors.len();
+
+        let (barrier_sender, barrier_receiver) = bounded(1);
+        let mut router_senders = Vec::with_capacity(count);
+        let mut merger_receivers = Vec::with_capacity(count);
+        let mut instances = Vec::with_capacity(count);
+        for (index, processor) in processors.into_iter().enumerate() {
+            let instance_handle = partition_handle(&node_handle, index, count);
+            let (router_sender, instance_receiver) = bounded(channel_buffer_sz);
+            let (instance_sender, merger_receiver) = bounded(channel_buffer_sz);
+            router_senders.push(router_sender);
+            merger_receivers.push(merger_receiver);
+
+            let port_mapping = output_ports
+                .iter()
+                .map(|port| (*port, vec![*port]))
+                .collect();
+            let channel_manager = ChannelManager::new(
+                instance_handle.clone(),
+                HashMap::new(),
+                vec![SenderWithPortMapping {
+                    sender: instance_sender,
+                    port_mapping,
+                }],
+                error_manager.clone(),
+            );
+            // Instances report under the processor's `pid`, so their metrics add up to the processor's.
+            let mut labels = dag.labels().labels().clone();
+            labels.push("pid", node_handle.id.clone());
+            labels.push("partition", index.to_string());
+            instances.push(ProcessorNode::from_parts(
+                instance_handle,
+                initial_epoch_id,
+                vec![node_handle.clone()],
+                vec![instance_receiver],
+                processor,
+                channel_manager,
+                error_manager.clone(),
+                labels,
+            ));
+        }
+
+        let router = PartitionRouter {
+            node_handle: node_handle.clone(),
+            initial_epoch_id,
+            node_handles,
+            receivers,
+            partitioner,
+            senders: router_senders,
+            barrier_acks: barrier_receiver,
+            next_barrier: 0,
+            error_manager: error_manager.clone(),
+        };
+        let merger = PartitionMerger {
+            node_handle: node_handle.clone(),
+            initial_epoch_id,
+            receivers: merger_receivers,
+            channel_manager: ChannelManager::new(
+                node_handle,
+                record_writers,
+                senders,
+                error_manager,
+            ),
+            pending_notifications: HashMap::new(),
+            barrier_acks: barrier_sender,
+        };
+
+        Self {
+            router,
+            instances,
+            merger,
+        }
+    }
+
+    pub fn handle(&self) -> &NodeHandle {
+        &self.router.node_handle
+    }
+}
+
+/// FNV-1a over the length-prefixed encodings of `key`.
+///
+/// Partition state is checkpointed per instance, so a key must map to the same instance across
+/// restarts and toolchain upgrades. `DefaultHasher` gives no such guarantee.
+fn partition_hash(key: &[Field]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET_BASIS;
+    for field in key {
+        let encoded = field.encode();
+        for byte in (encoded.len() as u64).to_le_bytes().iter().chain(&encoded) {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+#[derive(Debug)]
+pub struct PartitionRouter {
+    node_handle: NodeHandle,
+    initial_epoch_id: u64,
+    node_handles: Vec<NodeHandle>,
+    receivers: Vec<Receiver<ExecutorOperation>>,
+    partitioner: Box<dyn Partitioner>,
+    senders: Vec<Sender<ExecutorOperation>>,
+    /// Barriers the merger has acknowledged.
+    barrier_acks: Receiver<u64>,
+    next_barrier: u64,
+    error_manager: Arc<ErrorManager>,
+}
+
+/// Where the router sends an input operation.
+#[derive(Debug)]
+enum Routing {
+    /// Operations for different instances, whose output may be forwarded in any order.
+    Unordered(Vec<(usize, TableOperation)>),
+    /// An update whose key moved to another instance. The delete's output must be forwarded before the insert's.
+    Moved {
+        delete: (usize, TableOperation),
+        insert: (usize, TableOperation),
+    },
+}
+
+impl PartitionRouter {
+    fn partition_of(&mut self, port: PortHandle, record: &Record) -> Result<usize, BoxedError> {
+        let key = self.partitioner.partition_key(port, record)?;
+        Ok((partition_hash(&key) % self.senders.len() as u64) as usize)
+    }
+
+    fn route(&mut self, op: TableOperation) -> Result<Routing, BoxedError> {
+        let TableOperation { id, op, port } = op;
+        let table_op = |op| TableOperation {
+            id: id.clone(),
+            op,
+            port,
+        };
+        let routed = match op {
+            Operation::Insert { new } => {
+                vec![(self.partition_of(port, &new)?, Operation::Insert { new })]
+            }
+            Operation::Delete { old } => {
+                vec![(self.partition_of(port, &old)?, Operation::Delete { old })]
+            }
+            Operation::Update { old, new } => {
+                let old_partition = self.partition_of(port, &old)?;
+                let new_partition = self.partition_of(port, &new)?;
+                if old_partition != new_partition {
+                    // The key moved to another instance, which must see it as a new record.
+                    return Ok(Routing::Moved {
+                        delete: (old_partition, table_op(Operation::Delete { old })),
+                        insert: (new_partition, table_op(Operation::Insert { new })),
+                    });
+                }
+                vec![(old_partition, Operation::Update { old, new })]
+            }
+            Operation::BatchInsert { new } => {
+                let mut batches = vec![vec![]; self.senders.len()];
+                for record in new {
+                    batches[self.partition_of(port, &record)?].push(record);
+                }
+                batches
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, batch)| !batch.is_empty())
+                    .map(|(partition, new)| (partition, Operation::BatchInsert { new }))
+                    .collect()
+            }
+        };
+        Ok(Routing::Unordered(
+            routed
+                .into_iter()
+                .map(|(partition, op)| (partition, table_op(op)))
+                .collect(),
+        ))
+    }
+
+    /// Sends a barrier behind the operations sent to `partition`, and waits until the merger has forwarded their output.
+    fn drain(&mut self, partition: usize) -> Result<(), ExecutionError> {
+        let id = self.next_barrier;
+        self.next_barrier += 1;
+        self.senders[partition].send(ExecutorOperation::Barrier { id })?;
+        let acknowledged = self
+            .barrier_acks
+            .recv()
+            .map_err(|_| ExecutionError::CannotReceiveFromChannel)?;
+        debug_assert_eq!(acknowledged, id);
+        Ok(())
+    }
+
+    fn broadcast(&self, op: ExecutorOperation) -> Result<(), ExecutionError> {
+        for sender in &self.senders {
+            sender.send(op.clone())?;
+        }
+        Ok(())
+    }
+}
+
+impl Name for PartitionRouter {
+    fn name(&self) -> Cow<str> {
+        Cow::Owned(format!("{}-router", self.node_handle))
+    }
+}
+
+impl ReceiverLoop for PartitionRouter {
+    fn initial_epoch_id(&self) -> u64 {
+        self.initial_epoch_id
+    }
+
+    fn receivers(&mut self) -> Vec<Receiver<ExecutorOperation>> {
+        let mut result = vec![];
+        swap(&mut self.receivers, &mut result);
+        result
+    }
+
+    fn receiver_name(&self, index: usize) -> Cow<str> {
+        Cow::Owned(self.node_handles[index].to_string())
+    }
+
+    fn on_op(&mut self, _index: usize, op: TableOperation) -> Result<(), ExecutionError> {
+        match self.route(op) {
+            Ok(Routing::Unordered(routed)) => {
+                for (partition, op) in routed {
+                    self.senders[partition].send(ExecutorOperation::Op { op })?;
+                }
+            }
+            Ok(Routing::Moved {
+                delete: (delete_partition, delete),
+                insert: (insert_partition, insert),
+            }) => {
+                self.senders[delete_partition].send(ExecutorOperation::Op { op: delete })?;
+                self.drain(delete_partition)?;
+                self.senders[insert_partition].send(ExecutorOperation::Op { op: insert })?;
+            }
+            Err(e) => self.error_manager.report(e),
+        }
+        Ok(())
+    }
+
+    fn on_commit(&mut self, epoch: Epoch) -> Result<(), ExecutionError> {
+        self.broadcast(ExecutorOperation::Commit { epoch })
+    }
+
+    fn on_terminate(&mut self) -> Result<(), ExecutionError> {
+        self.broadcast(ExecutorOperation::Terminate)
+    }
+
+    fn on_snapshotting_started(&mut self, connection_name: String) -> Result<(), ExecutionError> {
+        self.broadcast(ExecutorOperation::SnapshottingStarted { connection_name })
+    }
+
+    fn on_snapshotting_done(
+        &mut self,
+        connection_name: String,
+        id: Option<SourcePosition>,
+    ) -> Result<(), ExecutionError> {
+        self.broadcast(ExecutorOperation::SnapshottingDone {
+            connection_name,
+            id,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Notification {
+    SnapshottingStarted(String),
+    SnapshottingDone(String, Option<SourcePosition>),
+}
+
+#[derive(Debug)]
+pub struct PartitionMerger {
+    node_handle: NodeHandle,
+    initial_epoch_id: u64,
+    receivers: Vec<Receiver<ExecutorOperation>>,
+    channel_manager: ChannelManager,
+    /// Notifications every instance forwards, and how many instances have forwarded them so far.
+    pending_notifications: HashMap<Notification, usize>,
+    barrier_acks: Sender<u64>,
+}
+
+impl PartitionMerger {
+    /// Returns if all instances have forwarded `notification`.
+    fn received_from_all(&mut self, notification: Notification) -> bool {
+        let count = self
+            .pending_notifications
+            .entry(notification.clone())
+            .or_default();
+        *count += 1;
+        if *count == self.receivers.len().max(1) {
+            self.pending_notifications.remove(&notification);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Name for PartitionMerger {
+    fn name(&self) -> Cow<str> {
+        Cow::Owned(format!("{}-merger", self.node_handle))
+    }
+}
+
+impl ReceiverLoop for PartitionMerger {
+    fn initial_epoch_id(&self) -> u64 {
+        self.initial_epoch_id
+    }
+
+    fn receivers(&mut self) -> Vec<Receiver<ExecutorOperation>> {
+        self.receivers.clone()
+    }
+
+    fn receiver_name(&self, index: usize) -> Cow<str> {
+        Cow::Owned(format!("{}-partition-{index}", self.node_handle))
+    }
+
+    fn on_op(&mut self, _index: usize, op: TableOperation) -> Result<(), ExecutionError> {
+        self.channel_manager.send_op(op)
+    }
+
+    fn on_commit(&mut self, epoch: Epoch) -> Result<(), ExecutionError> {
+        self.channel_manager.send_commit(epoch)
+    }
+
+    fn on_terminate(&mut self) -> Result<(), ExecutionError> {
+        self.channel_manager.send_terminate()
+    }
+
+    fn on_snapshotting_started(&mut self, connection_name: String) -> Result<(), ExecutionError> {
+        if self.received_from_all(Notification::SnapshottingStarted(connection_name.clone())) {
+            self.channel_manager
+                .send_snapshotting_started(connection_name)?;
+        }
+        Ok(())
+    }
+
+    fn on_snapshotting_done(
+        &mut self,
+        connection_name: String,
+        id: Option<SourcePosition>,
+    ) -> Result<(), ExecutionError> {
+        if self.received_from_all(Notification::SnapshottingDone(
+            connection_name.clone(),
+            id.clone(),
+        )) {
+            self.channel_manager
+                .send_snapshotting_done(connection_name, id)?;
+        }
+        Ok(())
+    }
+
+    fn on_barrier(&mut self, id: u64) -> Result<(), ExecutionError> {
+        // Everything the instance sent before the barrier has been forwarded.
+        self.barrier_acks.send(id)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct FirstFieldPartitioner;
+
+    impl Partitioner for FirstFieldPartitioner {
+        fn partition_key(
+            &mut self,
+            _port: PortHandle,
+            record: &Record,
+        ) -> Result<Vec<Field>, BoxedError> {
+            Ok(vec![record.values[0].clone()])
+        }
+    }
+
+    /// Returns the router, the instances' receivers, and the sender the merger acknowledges barriers with.
+    fn router(
+        count: usize,
+    ) -> (
+        PartitionRouter,
+        Vec<Receiver<ExecutorOperation>>,
+        Sender<u64>,
+    ) {
+        let (senders, receivers) = (0..count).map(|_| crossbeam::channel::unbounded()).unzip();
+        let (barrier_sender, barrier_receiver) = crossbeam::channel::unbounded();
+        (
+            PartitionRouter {
+                node_handle: NodeHandle::new(None, "processor".to_string()),
+                initial_epoch_id: 0,
+                node_handles: vec![],
+                receivers: vec![],
+                partitioner: Box::new(FirstFieldPartitioner),
+                senders,
+                barrier_acks: barrier_receiver,
+                next_barrier: 0,
+                error_manager: Arc::new(ErrorManager::new_unlimited()),
+            },
+            receivers,
+            barrier_sender,
+        )
+    }
+
+    fn record(key: i64, value: i64) -> Record {
+        Record::new(vec![Field::Int(key), Field::Int(value)])
+    }
+
+    fn received(receiver: &Receiver<ExecutorOperation>) -> Vec<Operation> {
+        receiver
+            .try_iter()
+            .filter_map(|op| match op {
+                ExecutorOperation::Op { op } => Some(op.op),
+                ExecutorOperation::Barrier { .. } => None,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn partition_hash_is_stable() {
+        assert_eq!(partition_hash(&[]), 0xcbf29ce484222325);
+        assert_eq!(partition_hash(&[Field::Int(1)]), 0x9cb1d485f517029d);
+    }
+
+    #[test]
+    fn router_sends_equal_keys_to_the_same_instance() {
+        let (mut router, receivers, _) = router(4);
+        for value in 0..10 {
+            router
+                .on_op(
+                    0,
+                    TableOperation::without_id(
+                        Operation::Insert {
+                            new: record(7, value),
+                        },
+                        0,
+                    ),
+                )
+                .unwrap();
+        }
+        let counts = receivers
+            .iter()
+            .map(|receiver| received(receiver).len())
+            .collect::<Vec<_>>();
+        assert_eq!(counts.iter().sum::<usize>(), 10);
+        assert!(counts.contains(&10));
+    }
+
+    #[test]
+    fn router_splits_updates_that_change_partition() {
+        let (mut router, receivers, barrier_acks) = router(2);
+        let (old, new) = (0..)
+            .map(|key| (record(0, 0), record(key, 0)))
+            .find(|(old, new)| {
+                router.partition_of(0, old).unwrap() != router.partition_of(0, new).unwrap()
+            })
+            .unwrap();
+        let (old_partition, new_partition) = (
+            router.partition_of(0, &old).unwrap(),
+            router.partition_of(0, &new).unwrap(),
+        );
+        // The merger has forwarded the delete's output.
+        barrier_acks.send(0).unwrap();
+        router
+            .on_op(
+                0,
+                TableOperation::without_id(
+                    Operation::Update {
+                        old: old.clone(),
+                        new: new.clone(),
+                    },
+                    0,
+                ),
+            )
+            .unwrap();
+
+        // The barrier follows the delete.
+        let old_ops = receivers[old_partition].try_iter().collect::<Vec<_>>();
+        assert!(matches!(
+            old_ops.as_slice(),
+            [
+                ExecutorOperation::Op { op: TableOperation { op: Operation::Delete { old: deleted }, .. } },
+                ExecutorOperation::Barrier { id: 0 },
+            ] if *deleted == old
+        ));
+        assert_eq!(
+            received(&receivers[new_partition]),
+            vec![Operation::Insert { new }]
+        );
+    }
+
+    #[test]
+    fn router_splits_batches_by_partition() {
+        let (mut router, receivers, _) = router(3);
+        let new = (0..30).map(|key| record(key, key)).collect::<Vec<_>>();
+        router
+            .on_op(
+                0,
+                TableOperation::without_id(Operation::BatchInsert { new: new.clone() }, 0),
+            )
+            .unwrap();
+
+        let mut records = vec![];
+        for receiver in &receivers {
+            for op in received(receiver) {
+                let Operation::BatchInsert { new } = op else {
+                    panic!("expected a batch insert");
+                };
+                records.extend(new);
+            }
+        }
+        records.sort_by_key(|record| record.values[0].clone());
+        assert_eq!(records, new);
+    }
+}
@@ -1,10 +1,15 @@
 use std::sync::Arc;
+use std::time::Instant;
 use std::{borrow::Cow, mem::swap};
 
 use crossbeam::channel::Receiver;
 use daggy::NodeIndex;
-use dozer_types::node::{NodeHandle, OpIdentifier};
-use dozer_types::types::TableOperation;
+use dozer_tracing::Labels;
+use dozer_types::node::{NodeHandle, SourcePosition};
+use dozer_types::types::{Operation, TableOperation};
+use metrics::{
+    describe_counter, describe_histogram, register_counter, register_histogram, Counter, Histogram,
+};
 
 use crate::epoch::Epoch;
 use crate::error_manager::ErrorManager;
@@ -32,6 +37,40 @@ pub struct ProcessorNode {
     channel_manager: ChannelManager,
     /// The error manager, for reporting non-fatal errors.
     error_manager: Arc<ErrorManager>,
+    /// The metrics, identifying the processor by `pid`.
+    metrics: ProcessorMetrics,
+}
+
+pub const PROCESSOR_OPERATION_COUNTER_NAME: &str = "processor_operation";
+pub const PROCESSOR_LATENCY_HISTOGRAM_NAME: &str = "processor_latency";
+
+/// Metric handles, registered once so that recording doesn't look them up by labels per operation.
+struct ProcessorMetrics {
+    latency: Histogram,
+    operations: Counter,
+}
+
+impl ProcessorMetrics {
+    fn new(labels: Labels) -> Self {
+        describe_counter!(
+            PROCESSOR_OPERATION_COUNTER_NAME,
+            "Number of records received by the processor"
+        );
+        describe_histogram!(
+            PROCESSOR_LATENCY_HISTOGRAM_NAME,
+            "Time the processor took to process an operation in seconds"
+        );
+        Self {
+            latency: register_histogram!(PROCESSOR_LATENCY_HISTOGRAM_NAME, labels.clone()),
+            operations: register_counter!(PROCESSOR_OPERATION_COUNTER_NAME, labels),
+        }
+    }
+}
+
+impl std::fmt::Debug for ProcessorMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessorMetrics").finish_non_exhaustive()
+    }
 }
 
 impl ProcessorNode {
@@ -57,6 +96,9 @@ impl ProcessorNode {
             dag.error_manager().clone(),
         );
 
+        let mut labels = dag.labels().labels().clone();
+        labels.push("pid", node_handle.id.clone());
+
         Self {
             node_handle,
             initial_epoch_id: dag.initial_epoch_id(),
@@ -65,6 +107,31 @@ impl ProcessorNode {
             processor,
             channel_manager,
             error_manager: dag.error_manager().clone(),
+            metrics: ProcessorMetrics::new(labels),
+        }
+    }
+
+    /// Creates an instance of a partitioned processor, which receives its input from the partition router.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_parts(
+        node_handle: NodeHandle,
+        initial_epoch_id: u64,
+        node_handles: Vec<NodeHandle>,
+        receivers: Vec<Receiver<ExecutorOperation>>,
+        processor: Box<dyn Processor>,
+        channel_manager: ChannelManager,
+        error_manager: Arc<ErrorManager>,
+        labels: Labels,
+    ) -> Self {
+        Self {
+            node_handle,
+            initial_epoch_id,
+            node_handles,
+            receivers,
+            processor,
+            channel_manager,
+            error_manager,
+            metrics: ProcessorMetrics::new(labels),
         }
     }
 
@@ -95,9 +162,18 @@ impl ReceiverLoop for ProcessorNode {
     }
 
     fn on_op(&mut self, _index: usize, op: TableOperation) -> Result<(), ExecutionError> {
+        let count = match &op.op {
+            Operation::BatchInsert { new } => new.len() as u64,
+            _ => 1,
+        };
+
+        let start = Instant::now();
         if let Err(e) = self.processor.process(op, &mut self.channel_manager) {
             self.error_manager.report(e);
         }
+
+        self.metrics.latency.record(start.elapsed());
+        self.metrics.operations.increment(count);
         Ok(())
     }
 
@@ -128,9 +204,14 @@ impl ReceiverLoop for ProcessorNode {
     fn on_snapshotting_done(
         &mut self,
         connection_name: String,
-        id: Option<OpIdentifier>,
+        id: Option<SourcePosition>,
     ) -> Result<(), ExecutionError> {
         self.channel_manager
             .send_snapshotting_done(connection_name, id)
     }
+
+    fn on_barrier(&mut self, id: u64) -> Result<(), ExecutionError> {
+        self.channel_manager
+            .send_non_op(ExecutorOperation::Barrier { id })
+    }
 }
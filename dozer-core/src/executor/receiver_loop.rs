@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
 use crossbeam::channel::{Receiver, Select};
-use dozer_types::{log::debug, node::OpIdentifier, types::TableOperation};
+use dozer_types::{log::debug, node::SourcePosition, types::TableOperation};
 
 use crate::{epoch::Epoch, errors::ExecutionError, executor_operation::ExecutorOperation};
 
@@ -29,8 +29,12 @@ pub trait ReceiverLoop: Name {
     fn on_snapshotting_done(
         &mut self,
         connection_name: String,
-        id: Option<OpIdentifier>,
+        id: Option<SourcePosition>,
     ) -> Result<(), ExecutionError>;
+    /// Responds to `Barrier`. Only the instances of a partitioned processor and their merger receive barriers.
+    fn on_barrier(&mut self, id: u64) -> Result<(), ExecutionError> {
+        unreachable!("[{}] Unexpected barrier {id}", self.name())
+    }
 
     /// The loop implementation, calls [`on_op`], [`on_commit`] and [`on_terminate`] at appropriate times.
     fn receiver_loop(&mut self, initial_epoch_id: u64) -> Result<(), ExecutionError> {
@@ -90,6 +94,9 @@ pub trait ReceiverLoop: Name {
                 } => {
                     self.on_snapshotting_done(connection_name, id)?;
                 }
+                ExecutorOperation::Barrier { id } => {
+                    self.on_barrier(id)?;
+                }
             }
         }
     }
@@ -122,7 +129,7 @@ mod tests {
         ops: Vec<(usize, TableOperation)>,
         commits: Vec<Epoch>,
         snapshotting_started: Vec<String>,
-        snapshotting_done: Vec<(String, Option<OpIdentifier>)>,
+        snapshotting_done: Vec<(String, Option<SourcePosition>)>,
         num_terminations: usize,
     }
 
@@ -173,7 +180,7 @@ mod tests {
         fn on_snapshotting_done(
             &mut self,
             connection_name: String,
-            state: Option<OpIdentifier>,
+            state: Option<SourcePosition>,
         ) -> Result<(), ExecutionError> {
             self.snapshotting_done.push((connection_name, state));
             Ok(())
@@ -2,11 +2,11 @@ use crossbeam::channel::Receiver;
 use daggy::NodeIndex;
 use dozer_tracing::LabelsAndProgress;
 use dozer_types::{
-    node::{NodeHandle, OpIdentifier},
+    node::{NodeHandle, SourcePosition},
     types::{Operation, TableOperation},
 };
 use metrics::{counter, describe_counter, describe_gauge, gauge};
-use std::{borrow::Cow, mem::swap, sync::Arc, usize};
+use std::{borrow::Cow, mem::swap, sync::Arc, time::SystemTime, usize};
 
 use crate::{
     builder_dag::NodeKind, epoch::Epoch, error_manager::ErrorManager, errors::ExecutionError,
@@ -14,6 +14,7 @@ use crate::{
 };
 
 use super::execution_dag::ExecutionDag;
+use super::status::PipelineStatus;
 use super::{name::Name, receiver_loop::ReceiverLoop};
 
 /// A sink in the execution DAG.
@@ -25,6 +26,8 @@ pub struct SinkNode {
     initial_epoch_id: u64,
     /// Input node handles.
     node_handles: Vec<NodeHandle>,
+    /// The sources this sink receives data from, directly or through processors.
+    sources: Vec<NodeHandle>,
     /// Input data channels.
     receivers: Vec<Receiver<ExecutorOperation>>,
     /// The sink.
@@ -33,13 +36,19 @@ pub struct SinkNode {
     error_manager: Arc<ErrorManager>,
     /// The metrics labels.
     labels: LabelsAndProgress,
+    /// Where the sink reports its commits.
+    status: PipelineStatus,
+    /// The sink's index in `status`.
+    status_index: usize,
 }
 
 const SINK_OPERATION_COUNTER_NAME: &str = "sink_operation";
 const PIPELINE_LATENCY_GAUGE_NAME: &str = "pipeline_latency";
+const REPLICATION_LAG_GAUGE_NAME: &str = "replication_lag";
+const RECORDS_BEHIND_GAUGE_NAME: &str = "records_behind";
 
 impl SinkNode {
-    pub fn new(dag: &mut ExecutionDag, node_index: NodeIndex) -> Self {
+    pub fn new(dag: &mut ExecutionDag, node_index: NodeIndex, status: &PipelineStatus) -> Self {
         let node = dag.node_weight_mut(node_index);
         let Some(kind) = node.kind.take() else {
             panic!("Must pass in a node")
@@ -50,6 +59,8 @@ impl SinkNode {
         };
 
         let (node_handles, receivers) = dag.collect_receivers(node_index);
+        let sources = dag.collect_upstream_sources(node_index);
+        let status_index = status.add_sink(node_handle.id.clone());
 
         describe_counter!(
             SINK_OPERATION_COUNTER_NAME,
@@ -59,21 +70,66 @@ impl SinkNode {
             PIPELINE_LATENCY_GAUGE_NAME,
             "The pipeline processing latency in seconds"
         );
+        describe_gauge!(
+            REPLICATION_LAG_GAUGE_NAME,
+            "Seconds between the source committing the latest change and the sink committing it"
+        );
+        describe_gauge!(
+            RECORDS_BEHIND_GAUGE_NAME,
+            "Number of records received from the source but not yet committed by the sink"
+        );
 
         Self {
             node_handle,
             initial_epoch_id: dag.initial_epoch_id(),
             node_handles,
+            sources,
             receivers,
             sink,
             error_manager: dag.error_manager().clone(),
             labels: dag.labels().clone(),
+            status: status.clone(),
+            status_index,
         }
     }
 
     pub fn handle(&self) -> &NodeHandle {
         &self.node_handle
     }
+
+    /// Reports how far the committed `epoch` is behind each upstream source.
+    fn report_source_lag(&self, epoch: &Epoch) {
+        let now = SystemTime::now();
+        for source in &self.sources {
+            let Some(progress) = epoch.source_progress.get(source) else {
+                continue;
+            };
+            let mut labels = self.labels.labels().clone();
+            labels.push("source", source.id.clone());
+            labels.push("endpoint", self.node_handle.id.clone());
+
+            let behind = self
+                .status
+                .source_operations(&source.id)
+                .map(|operations| operations.saturating_sub(progress.operations));
+            // Once the sink has committed everything the source sent, it's not lagging, however long ago the
+            // latest change happened.
+            let lag = if behind == Some(0) {
+                Some(0.0)
+            } else {
+                progress
+                    .source_time
+                    .and_then(|source_time| now.duration_since(source_time).ok())
+                    .map(|lag| lag.as_secs_f64())
+            };
+            if let Some(lag) = lag {
+                gauge!(REPLICATION_LAG_GAUGE_NAME, lag, labels.clone());
+            }
+            if let Some(behind) = behind {
+                gauge!(RECORDS_BEHIND_GAUGE_NAME, behind as f64, labels);
+            }
+        }
+    }
 }
 
 impl Name for SinkNode {
@@ -135,11 +191,18 @@ impl ReceiverLoop for SinkNode {
             self.error_manager.report(e);
         }
 
+        self.status.commit_sink(
+            self.status_index,
+            epoch.common_info.id,
+            epoch.decision_instant,
+        );
+
         if let Ok(duration) = epoch.decision_instant.elapsed() {
             let mut labels = self.labels.labels().clone();
             labels.push("endpoint", self.node_handle.id.clone());
             gauge!(PIPELINE_LATENCY_GAUGE_NAME, duration.as_secs_f64(), labels);
         }
+        self.report_source_lag(&epoch);
 
         if let Some(queue) = epoch.common_info.sink_persist_queue.as_ref() {
             if let Err(e) = self.sink.persist(&epoch, queue) {
@@ -164,7 +227,7 @@ impl ReceiverLoop for SinkNode {
     fn on_snapshotting_done(
         &mut self,
         connection_name: String,
-        id: Option<OpIdentifier>,
+        id: Option<SourcePosition>,
     ) -> Result<(), ExecutionError> {
         if let Err(e) = self.sink.on_source_snapshotting_done(connection_name, id) {
             self.error_manager.report(e);
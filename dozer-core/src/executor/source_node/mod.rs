@@ -1,26 +1,40 @@
-use std::{fmt::Debug, future::Future, pin::pin, sync::Arc, time::SystemTime};
+use std::{collections::HashMap, fmt::Debug, future::Future, pin::pin, sync::Arc};
 
 use daggy::petgraph::visit::IntoNodeIdentifiers;
 use dozer_log::tokio::{
     runtime::Runtime,
-    sync::mpsc::{channel, Receiver, Sender},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        watch,
+    },
 };
+use dozer_tracing::LabelsAndProgress;
 use dozer_types::{
-    log::debug, models::ingestion_types::TransactionInfo, node::OpIdentifier, types::TableOperation,
+    log::debug,
+    models::ingestion_types::TransactionInfo,
+    node::{NodeHandle, SourcePosition},
+    types::{Operation, TableOperation},
 };
 use dozer_types::{models::ingestion_types::IngestionMessage, node::SourceState};
 use futures::{future::Either, StreamExt};
+use metrics::{describe_gauge, gauge};
 
 use crate::{
     builder_dag::NodeKind,
-    epoch::Epoch,
+    checkpoint::CheckpointFactory,
+    epoch::{Epoch, EpochManager, SavepointRequests, SourceProgress},
     errors::ExecutionError,
     executor_operation::ExecutorOperation,
     forwarder::ChannelManager,
     node::{PortHandle, Source},
 };
 
-use super::{execution_dag::ExecutionDag, node::Node, ExecutorOptions};
+use super::{
+    execution_dag::ExecutionDag,
+    node::Node,
+    status::{PipelineStatus, SourcePhase},
+    ExecutorOptions,
+};
 
 /// The source operation collector.
 #[derive(Debug)]
@@ -31,8 +45,14 @@ pub struct SourceNode<F> {
     source_runners: Vec<SourceRunner>,
     /// Receivers from sources.
     receivers: Vec<Receiver<(PortHandle, IngestionMessage)>>,
-    /// The current epoch id.
-    epoch_id: u64,
+    /// Decides which epochs are checkpointed.
+    epoch_manager: EpochManager,
+    /// Savepoint requests wake up the source node even if no source is sending data.
+    savepoint_requests: SavepointRequests,
+    /// Where sources and epochs are reported. Sources have the same indices as in `sources`.
+    status: PipelineStatus,
+    /// The metrics labels.
+    labels: LabelsAndProgress,
     /// The shutdown future.
     shutdown: F,
     /// The runtime to run the source in.
@@ -56,15 +76,33 @@ impl<F: Future + Unpin> Node for SourceNode<F> {
         loop {
             let next = stream.next();
             let next = pin!(next);
-            match self
-                .runtime
-                .block_on(futures::future::select(self.shutdown, next))
-            {
+            let savepoint_requested = self.savepoint_requests.requested();
+            let savepoint_requested = pin!(savepoint_requested);
+            match self.runtime.block_on(futures::future::select(
+                self.shutdown,
+                futures::future::select(next, savepoint_requested),
+            )) {
                 Either::Left((_, _)) => {
                     send_to_all_nodes(&self.sources, ExecutorOperation::Terminate)?;
                     return Ok(());
                 }
-                Either::Right((next, shutdown)) => {
+                Either::Right((Either::Right(_), shutdown)) => {
+                    self.shutdown = shutdown;
+                    // Only checkpoint between transactions. Otherwise the savepoint stays pending until the next commit.
+                    if self
+                        .sources
+                        .iter()
+                        .all(|source| source.state != SourceState::NonRestartable)
+                    {
+                        close_epoch(
+                            &self.sources,
+                            &self.epoch_manager,
+                            &self.status,
+                            &self.labels,
+                        )?;
+                    }
+                }
+                Either::Right((Either::Left((next, _)), shutdown)) => {
                     let next = next.expect("We return just when the stream ends");
                     self.shutdown = shutdown;
                     let index = next.0;
@@ -91,8 +129,23 @@ impl<F: Future + Unpin> Node for SourceNode<F> {
                     };
                     let source = &mut self.sources[index];
                     match message {
-                        IngestionMessage::OperationEvent { op, id, .. } => {
+                        IngestionMessage::OperationEvent {
+                            op,
+                            id,
+                            source_time,
+                            ..
+                        } => {
                             source.state = SourceState::NonRestartable;
+                            self.epoch_manager.add_records(1);
+                            let operations = match &op {
+                                Operation::BatchInsert { new } => new.len() as u64,
+                                _ => 1,
+                            };
+                            source.progress.operations += operations;
+                            if source_time.is_some() {
+                                source.progress.source_time = source_time;
+                            }
+                            self.status.add_operations(index, operations);
                             source
                                 .channel_manager
                                 .send_op(TableOperation { op, id, port })?;
@@ -105,41 +158,39 @@ impl<F: Future + Unpin> Node for SourceNode<F> {
                                     source.state = SourceState::NonRestartable;
                                 }
 
-                                let source_states = Arc::new(
-                                    self.sources
-                                        .iter()
-                                        .map(|source| {
-                                            (
-                                                source.channel_manager.owner().clone(),
-                                                source.state.clone(),
-                                            )
-                                        })
-                                        .collect(),
-                                );
-                                let epoch = Epoch::new(
-                                    self.epoch_id,
-                                    source_states,
-                                    None,
-                                    None,
-                                    SystemTime::now(),
-                                );
-                                send_to_all_nodes(
+                                close_epoch(
                                     &self.sources,
-                                    ExecutorOperation::Commit { epoch },
+                                    &self.epoch_manager,
+                                    &self.status,
+                                    &self.labels,
                                 )?;
-                                self.epoch_id += 1;
                             }
                             TransactionInfo::SnapshottingStarted => {
+                                source.snapshotting = true;
+                                self.status
+                                    .set_source_phase(index, SourcePhase::Snapshotting);
                                 source.channel_manager.send_snapshotting_started(
                                     source.channel_manager.owner().id.clone(),
                                 )?;
                             }
+                            TransactionInfo::SnapshotSize { rows, .. } => {
+                                self.status.add_snapshot_size(index, rows);
+                                report_snapshot_progress(source, &self.status, index, &self.labels);
+                            }
                             TransactionInfo::SnapshottingDone { id } => {
+                                self.status
+                                    .set_source_phase(index, SourcePhase::Replicating);
+                                source.snapshotting = false;
+                                report_snapshot_progress(source, &self.status, index, &self.labels);
                                 source.channel_manager.send_snapshotting_done(
                                     source.channel_manager.owner().id.clone(),
                                     id,
                                 )?;
                             }
+                            TransactionInfo::SnapshottingSkipped => {
+                                self.status
+                                    .set_source_phase(index, SourcePhase::Replicating);
+                            }
                         },
                     }
                 }
@@ -151,16 +202,80 @@ impl<F: Future + Unpin> Node for SourceNode<F> {
 #[derive(Debug)]
 struct RunningSource {
     channel_manager: ChannelManager,
+    /// Names of the output ports with record writers.
+    record_writer_port_names: HashMap<PortHandle, String>,
     state: SourceState,
+    snapshotting: bool,
+    progress: SourceProgress,
 }
 
 #[derive(Debug)]
 struct SourceRunner {
     source: Box<dyn Source>,
-    last_checkpoint: Option<OpIdentifier>,
+    last_checkpoint: Option<SourcePosition>,
     sender: Sender<(PortHandle, IngestionMessage)>,
 }
 
+/// Commits the current epoch, writing the sources' record writers if the epoch is checkpointed.
+///
+/// Snapshot progress is reported here rather than per operation, so it's updated once per epoch.
+fn close_epoch(
+    sources: &[RunningSource],
+    epoch_manager: &EpochManager,
+    status: &PipelineStatus,
+    labels: &LabelsAndProgress,
+) -> Result<(), ExecutionError> {
+    let source_states = sources
+        .iter()
+        .map(|source| (source.channel_manager.owner().clone(), source.state.clone()));
+    let closed_epoch = epoch_manager.wait_for_epoch_close(source_states, false, true);
+    let common_info = closed_epoch
+        .common_info
+        .expect("We requested commit so the epoch must be committed");
+
+    if let Some(checkpoint_writer) = &common_info.checkpoint_writer {
+        for source in sources {
+            source
+                .channel_manager
+                .serialize_record_writers(checkpoint_writer, &source.record_writer_port_names)?;
+        }
+    }
+
+    for (index, source) in sources.iter().enumerate() {
+        if source.snapshotting {
+            report_snapshot_progress(source, status, index, labels);
+        }
+    }
+
+    status.close_epoch(
+        common_info.id,
+        closed_epoch.decision_instant,
+        common_info.checkpoint_writer.is_some(),
+    );
+    let source_progress = sources
+        .iter()
+        .map(|source| (source.channel_manager.owner().clone(), source.progress))
+        .collect();
+    let epoch = Epoch::from(common_info, closed_epoch.decision_instant)
+        .with_source_progress(source_progress);
+    send_to_all_nodes(sources, ExecutorOperation::Commit { epoch })
+}
+
+const SNAPSHOT_PROGRESS_GAUGE_NAME: &str = "snapshot_progress";
+
+fn report_snapshot_progress(
+    source: &RunningSource,
+    status: &PipelineStatus,
+    index: usize,
+    labels: &LabelsAndProgress,
+) {
+    if let Some(progress) = status.snapshot_progress(index) {
+        let mut labels = labels.labels().clone();
+        labels.push("source", source.channel_manager.owner().id.clone());
+        gauge!(SNAPSHOT_PROGRESS_GAUGE_NAME, progress, labels);
+    }
+}
+
 /// Returns if the operation is sent successfully.
 fn send_to_all_nodes(
     sources: &[RunningSource],
@@ -175,6 +290,9 @@ fn send_to_all_nodes(
 pub async fn create_source_node<F>(
     dag: &mut ExecutionDag,
     options: &ExecutorOptions,
+    checkpoint_factory: Arc<CheckpointFactory>,
+    savepoint_requests: SavepointRequests,
+    status: PipelineStatus,
     shutdown: F,
     runtime: Arc<Runtime>,
 ) -> SourceNode<F> {
@@ -193,15 +311,22 @@ pub async fn create_source_node<F>(
         }
         let node = dag.node_weight_mut(node_index);
         let node_handle = node.handle.clone();
+        status.add_source(node_handle.id.clone());
         let NodeKind::Source {
-            source,
+            mut source,
             last_checkpoint,
         } = node.kind.take().unwrap()
         else {
             continue;
         };
+        source.set_checkpointed_position(checkpointed_position(
+            &checkpoint_factory,
+            node_handle.clone(),
+            &runtime,
+        ));
 
         let senders = dag.collect_senders(node_index);
+        let record_writer_port_names = dag.collect_record_writer_port_names(node_index);
         let record_writers = dag.collect_record_writers(node_index).await;
         let channel_manager = ChannelManager::new(
             node_handle,
@@ -211,7 +336,10 @@ pub async fn create_source_node<F>(
         );
         sources.push(RunningSource {
             channel_manager,
+            record_writer_port_names,
             state: SourceState::NotStarted,
+            snapshotting: false,
+            progress: SourceProgress::default(),
         });
 
         let (sender, receiver) = channel(options.channel_buffer_sz);
@@ -224,14 +352,54 @@ pub async fn create_source_node<F>(
         receivers.push(receiver);
     }
 
+    describe_gauge!(
+        SNAPSHOT_PROGRESS_GAUGE_NAME,
+        "Percentage of the source's estimated snapshot size sent"
+    );
+
+    // All sources are collected by this node, which is the only participant closing epochs.
+    let epoch_manager = EpochManager::new(
+        1,
+        dag.initial_epoch_id(),
+        checkpoint_factory,
+        options.epoch_manager_options.clone(),
+        savepoint_requests.clone(),
+    );
+
     SourceNode {
         sources,
         source_runners,
         receivers,
-        epoch_id: dag.initial_epoch_id(),
+        epoch_manager,
+        savepoint_requests,
+        status,
+        labels: dag.labels().clone(),
         shutdown,
         runtime,
     }
 }
 
+/// Follows the position of the source `handle` in persisted checkpoints.
+fn checkpointed_position(
+    checkpoint_factory: &CheckpointFactory,
+    handle: NodeHandle,
+    runtime: &Runtime,
+) -> watch::Receiver<Option<SourcePosition>> {
+    let mut source_states = checkpoint_factory.checkpointed_source_states();
+    let (sender, receiver) = watch::channel(None);
+    runtime.spawn(async move {
+        while source_states.changed().await.is_ok() {
+            let position = source_states
+                .borrow_and_update()
+                .get(&handle)
+                .and_then(SourceState::position);
+            if position.is_some() && sender.send(position).is_err() {
+                // The source doesn't follow checkpoints.
+                break;
+            }
+        }
+    });
+    receiver
+}
+
 mod stream;
@@ -0,0 +1,293 @@
+use std::{
+    sync::{Arc, OnceLock},
+    time::{Duration, SystemTime},
+};
+
+use dozer_types::{
+    chrono::{DateTime, Utc},
+    parking_lot::Mutex,
+    serde::Serialize,
+};
+
+use crate::error_manager::ErrorManager;
+
+/// The state of a running pipeline, updated by the source and sink nodes.
+#[derive(Debug, Clone, Default)]
+pub struct PipelineStatus {
+    inner: Arc<Mutex<Inner>>,
+    /// Set when the executor starts.
+    error_manager: Arc<OnceLock<Arc<ErrorManager>>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    sources: Vec<(String, SourceInner)>,
+    sinks: Vec<(String, Option<CommittedEpoch>)>,
+    first_epoch: Option<SystemTime>,
+    latest_epoch: Option<u64>,
+    last_checkpoint: Option<CheckpointStatus>,
+}
+
+#[derive(Debug, Default)]
+struct SourceInner {
+    phase: SourcePhase,
+    operations: u64,
+    /// Rows the connector estimated the snapshot sends.
+    snapshot_size: u64,
+    snapshot_operations: u64,
+}
+
+impl SourceInner {
+    fn snapshot_progress(&self) -> Option<f64> {
+        if self.snapshot_size == 0 {
+            return None;
+        }
+        match self.phase {
+            SourcePhase::Starting => None,
+            SourcePhase::Snapshotting => Some(
+                (self.snapshot_operations as f64 / self.snapshot_size as f64 * 100.0).min(100.0),
+            ),
+            SourcePhase::Replicating => Some(100.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CommittedEpoch {
+    id: u64,
+    decision_instant: SystemTime,
+    latency: Duration,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(crate = "dozer_types::serde", rename_all = "snake_case")]
+pub enum SourcePhase {
+    /// The source hasn't reported a snapshot, a skipped snapshot or any change yet.
+    #[default]
+    Starting,
+    Snapshotting,
+    Replicating,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct SourceStatus {
+    pub name: String,
+    pub phase: SourcePhase,
+    pub operations: u64,
+    /// Percentage of the snapshot sent, if the connector estimated its size.
+    pub snapshot_progress: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct SinkStatus {
+    pub name: String,
+    pub epoch_id: Option<u64>,
+    /// How far the sink is behind the sources. It's the latency of its last commit if it has committed the latest epoch.
+    pub lag_seconds: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct CheckpointStatus {
+    pub epoch_id: u64,
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "dozer_types::serde")]
+pub struct PipelineStatusReport {
+    /// Whether the executor has started. Sources and sinks are only known after that.
+    pub started: bool,
+    pub sources: Vec<SourceStatus>,
+    pub sinks: Vec<SinkStatus>,
+    /// The latest epoch closed by the sources.
+    pub epoch_id: Option<u64>,
+    pub last_checkpoint: Option<CheckpointStatus>,
+    /// Errors reported by processors and sinks, which don't stop the pipeline below the error threshold.
+    pub errors: u32,
+}
+
+impl PipelineStatusReport {
+    /// The pipeline has started, every source is replicating and every sink is within `max_sink_lag` of the sources.
+    ///
+    /// A source replicates once its snapshot is done, or once it reports it has no snapshot to send.
+    pub fn is_ready(&self, max_sink_lag: Duration) -> bool {
+        self.started
+            && self
+                .sources
+                .iter()
+                .all(|source| source.phase == SourcePhase::Replicating)
+            && self
+                .sinks
+                .iter()
+                .all(|sink| sink.lag_seconds <= max_sink_lag.as_secs_f64())
+    }
+}
+
+impl PipelineStatus {
+    pub fn report(&self) -> PipelineStatusReport {
+        let inner = self.inner.lock();
+        let now = SystemTime::now();
+        let since = |instant: SystemTime| {
+            now.duration_since(instant)
+                .unwrap_or_default()
+                .as_secs_f64()
+        };
+
+        let sources = inner
+            .sources
+            .iter()
+            .map(|(name, source)| SourceStatus {
+                name: name.clone(),
+                phase: source.phase,
+                operations: source.operations,
+                snapshot_progress: source.snapshot_progress(),
+            })
+            .collect();
+        let sinks = inner
+            .sinks
+            .iter()
+            .map(|(name, committed)| {
+                let lag_seconds = match (committed, inner.latest_epoch) {
+                    (_, None) => 0.0,
+                    (Some(committed), Some(latest)) if committed.id >= latest => {
+                        committed.latency.as_secs_f64()
+                    }
+                    (Some(committed), Some(_)) => since(committed.decision_instant),
+                    (None, Some(_)) => since(inner.first_epoch.expect("An epoch was closed")),
+                };
+                SinkStatus {
+                    name: name.clone(),
+                    epoch_id: committed.map(|committed| committed.id),
+                    lag_seconds,
+                }
+            })
+            .collect();
+
+        let error_manager = self.error_manager.get();
+        PipelineStatusReport {
+            started: error_manager.is_some(),
+            sources,
+            sinks,
+            epoch_id: inner.latest_epoch,
+            last_checkpoint: inner.last_checkpoint,
+            errors: error_manager.map_or(0, |error_manager| error_manager.count()),
+        }
+    }
+
+    pub(crate) fn start(&self, error_manager: Arc<ErrorManager>) {
+        let _ = self.error_manager.set(error_manager);
+    }
+
+    /// Returns the index to update the source with.
+    pub(crate) fn add_source(&self, name: String) -> usize {
+        let mut inner = self.inner.lock();
+        inner.sources.push((name, SourceInner::default()));
+        inner.sources.len() - 1
+    }
+
+    pub(crate) fn add_operations(&self, source: usize, operations: u64) {
+        let source = &mut self.inner.lock().sources[source].1;
+        source.operations += operations;
+        match source.phase {
+            SourcePhase::Starting => source.phase = SourcePhase::Replicating,
+            SourcePhase::Snapshotting => source.snapshot_operations += operations,
+            SourcePhase::Replicating => {}
+        }
+    }
+
+    /// Operations received from the source named `name` so far.
+    pub(crate) fn source_operations(&self, name: &str) -> Option<u64> {
+        self.inner
+            .lock()
+            .sources
+            .iter()
+            .find(|(source_name, _)| source_name == name)
+            .map(|(_, source)| source.operations)
+    }
+
+    pub(crate) fn set_source_phase(&self, source: usize, phase: SourcePhase) {
+        self.inner.lock().sources[source].1.phase = phase;
+    }
+
+    pub(crate) fn add_snapshot_size(&self, source: usize, rows: u64) {
+        self.inner.lock().sources[source].1.snapshot_size += rows;
+    }
+
+    pub(crate) fn snapshot_progress(&self, source: usize) -> Option<f64> {
+        self.inner.lock().sources[source].1.snapshot_progress()
+    }
+
+    pub(crate) fn close_epoch(&self, id: u64, decision_instant: SystemTime, checkpointed: bool) {
+        let mut inner = self.inner.lock();
+        inner.first_epoch.get_or_insert(decision_instant);
+        inner.latest_epoch = Some(id);
+        if checkpointed {
+            inner.last_checkpoint = Some(CheckpointStatus {
+                epoch_id: id,
+                time: decision_instant.into(),
+            });
+        }
+    }
+
+    /// Returns the index to update the sink with.
+    pub(crate) fn add_sink(&self, name: String) -> usize {
+        let mut inner = self.inner.lock();
+        inner.sinks.push((name, None));
+        inner.sinks.len() - 1
+    }
+
+    pub(crate) fn commit_sink(&self, sink: usize, id: u64, decision_instant: SystemTime) {
+        let latency = decision_instant.elapsed().unwrap_or_default();
+        self.inner.lock().sinks[sink].1 = Some(CommittedEpoch {
+            id,
+            decision_instant,
+            latency,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_status_readiness() {
+        let max_lag = Duration::from_secs(10);
+        let status = PipelineStatus::default();
+        assert!(!status.report().is_ready(max_lag));
+        status.start(Arc::new(ErrorManager::new_threshold(0)));
+        let source = status.add_source("postgres".to_string());
+        let sink = status.add_sink("users".to_string());
+        // The source hasn't said whether it snapshots yet.
+        assert!(!status.report().is_ready(max_lag));
+
+        status.set_source_phase(source, SourcePhase::Snapshotting);
+        status.add_snapshot_size(source, 400);
+        status.add_operations(source, 100);
+        assert_eq!(status.snapshot_progress(source), Some(25.0));
+        assert!(!status.report().is_ready(max_lag));
+        status.set_source_phase(source, SourcePhase::Replicating);
+        assert_eq!(status.snapshot_progress(source), Some(100.0));
+
+        // The sink hasn't committed an epoch closed a minute ago.
+        let a_minute_ago = SystemTime::now() - Duration::from_secs(60);
+        status.close_epoch(1, a_minute_ago, true);
+        let report = status.report();
+        assert!(report.sinks[0].lag_seconds >= 60.0);
+        assert!(!report.is_ready(max_lag));
+
+        // It has now, and the sources haven't moved on since.
+        let now = SystemTime::now();
+        status.close_epoch(2, now, false);
+        status.commit_sink(sink, 2, now);
+        let report = status.report();
+        assert_eq!(report.epoch_id, Some(2));
+        assert_eq!(report.last_checkpoint.unwrap().epoch_id, 1);
+        assert_eq!(report.sources[0].operations, 100);
+        assert_eq!(status.source_operations("postgres"), Some(100));
+        assert!(report.is_ready(max_lag));
+    }
+}
@@ -1,4 +1,4 @@
-use dozer_types::{node::OpIdentifier, types::TableOperation};
+use dozer_types::{node::SourcePosition, types::TableOperation};
 
 use crate::epoch::Epoch;
 
@@ -16,6 +16,11 @@ pub enum ExecutorOperation {
     },
     SnapshottingDone {
         connection_name: String,
-        id: Option<OpIdentifier>,
+        id: Option<SourcePosition>,
+    },
+    /// Passed through an instance of a partitioned processor. The merger acknowledges it to the router
+    /// once the instance's output before it has been forwarded.
+    Barrier {
+        id: u64,
     },
 }
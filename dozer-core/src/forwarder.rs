@@ -1,4 +1,5 @@
 use crate::channels::ProcessorChannelForwarder;
+use crate::checkpoint::CheckpointWriter;
 use crate::epoch::Epoch;
 use crate::error_manager::ErrorManager;
 use crate::errors::ExecutionError;
@@ -8,7 +9,7 @@ use crate::record_store::RecordWriter;
 
 use crossbeam::channel::Sender;
 use dozer_types::log::debug;
-use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::node::{NodeHandle, SourcePosition};
 use dozer_types::types::TableOperation;
 use std::collections::HashMap;
 use std::ops::Deref;
@@ -96,7 +97,7 @@ impl ChannelManager {
     pub fn send_snapshotting_done(
         &self,
         connection_name: String,
-        id: Option<OpIdentifier>,
+        id: Option<SourcePosition>,
     ) -> Result<(), ExecutionError> {
         self.send_non_op(ExecutorOperation::SnapshottingDone {
             connection_name,
@@ -119,6 +120,24 @@ impl ChannelManager {
         &self.owner
     }
 
+    /// Writes the record writers' state to the checkpoint. `port_names` keys the record writers in the checkpoint.
+    pub fn serialize_record_writers(
+        &self,
+        checkpoint_writer: &CheckpointWriter,
+        port_names: &HashMap<PortHandle, String>,
+    ) -> Result<(), ExecutionError> {
+        for (port, record_writer) in &self.record_writers {
+            let Some(port_name) = port_names.get(port) else {
+                continue;
+            };
+            let object = checkpoint_writer.create_record_writer_object(&self.owner, port_name)?;
+            record_writer
+                .serialize(object)
+                .map_err(ExecutionError::SerializeRecordWriter)?;
+        }
+        Ok(())
+    }
+
     pub fn new(
         owner: NodeHandle,
         record_writers: HashMap<PortHandle, Box<dyn RecordWriter>>,
@@ -2,13 +2,14 @@ use crate::channels::ProcessorChannelForwarder;
 use crate::epoch::Epoch;
 
 use dozer_log::storage::{Object, Queue};
-use dozer_log::tokio::sync::mpsc::Sender;
+use dozer_log::tokio::sync::{mpsc::Sender, watch};
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::models::ingestion_types::IngestionMessage;
-use dozer_types::node::OpIdentifier;
+use dozer_types::node::{OpIdentifier, SourcePosition};
 use dozer_types::serde::{Deserialize, Serialize};
 use dozer_types::tonic::async_trait;
-use dozer_types::types::{Schema, TableOperation};
+use dozer_types::types::{Field, Record, Schema, TableOperation};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
 
@@ -53,6 +54,14 @@ pub trait SourceFactory: Send + Sync + Debug {
         output_schemas: HashMap<PortHandle, Schema>,
         state: Option<Vec<u8>>,
     ) -> Result<Box<dyn Source>, BoxedError>;
+
+    /// Orders two positions of this source, so that a source feeding several sinks restarts from the oldest one.
+    /// `None` if they can't be ordered. The default orders positions converted from `OpIdentifier`s.
+    fn compare_positions(&self, a: &SourcePosition, b: &SourcePosition) -> Option<Ordering> {
+        let a = OpIdentifier::try_from(a).ok()?;
+        let b = OpIdentifier::try_from(b).ok()?;
+        Some(a.cmp(&b))
+    }
 }
 
 #[async_trait]
@@ -62,8 +71,12 @@ pub trait Source: Send + Sync + Debug {
     async fn start(
         &mut self,
         sender: Sender<(PortHandle, IngestionMessage)>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError>;
+
+    /// Called before `start` with the last position of this source that the pipeline has checkpointed, which changes as
+    /// checkpoints are persisted. Sources that clean up ingested data once it's durable keep it.
+    fn set_checkpointed_position(&mut self, _position: watch::Receiver<Option<SourcePosition>>) {}
 }
 
 #[async_trait]
@@ -83,6 +96,32 @@ pub trait ProcessorFactory: Send + Sync + Debug {
     ) -> Result<Box<dyn Processor>, BoxedError>;
     fn type_name(&self) -> String;
     fn id(&self) -> String;
+
+    /// What the processor computes from its inputs, e.g. the SQL it was built from.
+    /// Processors with equal type name and definition on the same inputs are interchangeable, so a new build of the pipeline can reuse their state.
+    /// `None` if the processor can't tell.
+    fn definition(&self) -> Option<String> {
+        None
+    }
+
+    /// Returns a partitioner if the processor only keeps state per key, so that it can run as
+    /// several instances that each own a subset of the keys.
+    async fn partitioner(
+        &self,
+        _input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<Option<Box<dyn Partitioner>>, BoxedError> {
+        Ok(None)
+    }
+}
+
+/// Computes the key that decides which processor instance handles a record.
+/// Records with equal keys, on any input port, must be handled by the same instance.
+pub trait Partitioner: Send + Sync + Debug {
+    fn partition_key(
+        &mut self,
+        port: PortHandle,
+        record: &Record,
+    ) -> Result<Vec<Field>, BoxedError>;
 }
 
 pub trait Processor: Send + Sync + Debug {
@@ -104,6 +143,11 @@ pub trait SinkFactory: Send + Sync + Debug {
         input_schemas: HashMap<PortHandle, Schema>,
     ) -> Result<Box<dyn Sink>, BoxedError>;
     fn type_name(&self) -> String;
+
+    /// Whether receiving operations again leaves the sink's output unchanged, e.g. because it writes records by primary key.
+    fn is_idempotent(&self) -> bool {
+        false
+    }
 }
 
 pub trait Sink: Send + Sync + Debug {
@@ -116,11 +160,11 @@ pub trait Sink: Send + Sync + Debug {
     fn on_source_snapshotting_done(
         &mut self,
         connection_name: String,
-        id: Option<OpIdentifier>,
+        id: Option<SourcePosition>,
     ) -> Result<(), BoxedError>;
 
     // Pipeline state management.
     fn set_source_state(&mut self, source_state: &[u8]) -> Result<(), BoxedError>;
     fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError>;
-    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError>;
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError>;
 }
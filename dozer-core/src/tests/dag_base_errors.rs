@@ -12,7 +12,7 @@ use dozer_log::storage::{Object, Queue};
 use dozer_log::tokio::sync::mpsc::Sender;
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::models::ingestion_types::{IngestionMessage, TransactionInfo};
-use dozer_types::node::{NodeHandle, OpIdentifier};
+use dozer_types::node::{NodeHandle, OpIdentifier, SourcePosition};
 use dozer_types::tonic::async_trait;
 use dozer_types::types::{
     Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition, TableOperation,
@@ -336,7 +336,7 @@ impl Source for ErrGeneratorSource {
     async fn start(
         &mut self,
         sender: Sender<(PortHandle, IngestionMessage)>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         for n in 1..(self.count + 1) {
             if n == self.err_at {
@@ -354,7 +354,8 @@ impl Source for ErrGeneratorSource {
                                 Field::String(format!("value_{n}")),
                             ]),
                         },
-                        id: Some(OpIdentifier::new(0, n)),
+                        id: Some(OpIdentifier::new(0, n).into()),
+                        source_time: None,
                     },
                 ))
                 .await?;
@@ -362,7 +363,7 @@ impl Source for ErrGeneratorSource {
                 .send((
                     GENERATOR_SOURCE_OUTPUT_PORT,
                     IngestionMessage::TransactionInfo(TransactionInfo::Commit {
-                        id: Some(OpIdentifier::new(0, n)),
+                        id: Some(OpIdentifier::new(0, n).into()),
                     }),
                 ))
                 .await?;
@@ -483,7 +484,7 @@ impl Sink for ErrSink {
     fn on_source_snapshotting_done(
         &mut self,
         _connection_name: String,
-        _id: Option<OpIdentifier>,
+        _id: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         Ok(())
     }
@@ -496,7 +497,7 @@ impl Sink for ErrSink {
         Ok(None)
     }
 
-    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
         Ok(None)
     }
 }
@@ -1,9 +1,13 @@
+use crate::builder_dag::partition_handle;
 use crate::channels::ProcessorChannelForwarder;
-use crate::checkpoint::create_checkpoint_for_test;
+use crate::checkpoint::{
+    create_checkpoint_for_test, CheckpointFactory, CheckpointOptions, CheckpointWriter,
+    OptionCheckpoint,
+};
 use crate::epoch::Epoch;
-use crate::executor::DagExecutor;
-use crate::node::{PortHandle, Processor, ProcessorFactory};
-use crate::tests::sinks::{CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
+use crate::executor::{DagExecutor, ExecutorOptions};
+use crate::node::{Partitioner, PortHandle, Processor, ProcessorFactory};
+use crate::tests::sinks::{CollectingSinkFactory, CountingSinkFactory, COUNTING_SINK_INPUT_PORT};
 use crate::tests::sources::{
     DualPortGeneratorSourceFactory, GeneratorSourceFactory,
     DUAL_PORT_GENERATOR_SOURCE_OUTPUT_PORT_1, DUAL_PORT_GENERATOR_SOURCE_OUTPUT_PORT_2,
@@ -13,15 +17,17 @@ use crate::{Dag, Endpoint, DEFAULT_PORT_HANDLE};
 use dozer_log::storage::Object;
 use dozer_log::tokio::sync::oneshot;
 use dozer_types::errors::internal::BoxedError;
-use dozer_types::node::NodeHandle;
+use dozer_types::node::{NodeHandle, OpIdentifier, SourceState};
 use dozer_types::tonic::async_trait;
-use dozer_types::types::{Schema, TableOperation};
+use dozer_types::types::{Field, Record, Schema, TableOperation};
 
+use futures::future::pending;
 use std::collections::HashMap;
 use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
+use tempdir::TempDir;
 
 use super::{create_test_runtime, run_dag};
 
@@ -175,6 +181,333 @@ fn test_run_dag_and_stop() {
     join_handle.join().unwrap();
 }
 
+#[test]
+fn test_run_dag_from_savepoint() {
+    let count: usize = 10;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+    let records = Arc::new(Mutex::new(vec![]));
+
+    let source_handle = NodeHandle::new(Some(1), 1.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 2.to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Box::new(GeneratorSourceFactory::new(
+            count as u64,
+            latch.clone(),
+            false,
+        )),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Box::new(CollectingSinkFactory::new(count, latch, records.clone())),
+    );
+    dag.connect(
+        Endpoint::new(source_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let runtime = create_test_runtime();
+    let runtime_clone = runtime.clone();
+    let temp_dir = TempDir::new("test_run_dag_from_savepoint").unwrap();
+    let checkpoint_dir = temp_dir.path().to_str().unwrap().to_string();
+    let join_handle = runtime.block_on(async move {
+        // Write a savepoint at the 10th operation and a newer checkpoint at the 20th.
+        let checkpoint = OptionCheckpoint::new(checkpoint_dir.clone(), Default::default())
+            .await
+            .unwrap();
+        let (factory, handle) = CheckpointFactory::new(checkpoint, Default::default())
+            .await
+            .unwrap();
+        let factory = Arc::new(factory);
+        thread::spawn(move || {
+            for (epoch_id, seq_in_tx, savepoint) in [(1, 9, Some("before-upgrade")), (2, 19, None)]
+            {
+                let source_states = [(
+                    source_handle.clone(),
+                    SourceState::Restartable(OpIdentifier::new(0, seq_in_tx).into()),
+                )]
+                .into_iter()
+                .collect();
+                drop(CheckpointWriter::new(
+                    factory.clone(),
+                    epoch_id,
+                    Arc::new(source_states),
+                    savepoint.map(ToString::to_string),
+                ));
+            }
+        })
+        .join()
+        .unwrap();
+        handle.await.unwrap();
+
+        let options = CheckpointOptions {
+            restore_from: Some("before-upgrade".to_string()),
+            ..Default::default()
+        };
+        let checkpoint = OptionCheckpoint::new(checkpoint_dir, options)
+            .await
+            .unwrap();
+        DagExecutor::new(dag, checkpoint, Default::default())
+            .await
+            .unwrap()
+            .start(pending::<()>(), Default::default(), runtime_clone)
+            .await
+            .unwrap()
+    });
+    join_handle.join().unwrap();
+
+    let records = records.lock().unwrap();
+    assert_eq!(records.len(), count);
+    assert_eq!(
+        records[0].values[0],
+        Field::String("key_10".to_string()),
+        "the source must restart after the savepoint's position"
+    );
+}
+
+#[derive(Debug)]
+struct PartitionedNoopProcessorFactory {}
+
+#[async_trait]
+impl ProcessorFactory for PartitionedNoopProcessorFactory {
+    fn type_name(&self) -> String {
+        "PartitionedNoop".to_owned()
+    }
+
+    async fn get_output_schema(
+        &self,
+        _output_port: &PortHandle,
+        input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<Schema, BoxedError> {
+        Ok(input_schemas.get(&DEFAULT_PORT_HANDLE).unwrap().clone())
+    }
+
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn get_output_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    async fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+        _output_schemas: HashMap<PortHandle, Schema>,
+        _checkpoint_data: Option<Vec<u8>>,
+    ) -> Result<Box<dyn Processor>, BoxedError> {
+        Ok(Box::new(NoopProcessor {}))
+    }
+
+    fn id(&self) -> String {
+        "PartitionedNoop".to_owned()
+    }
+
+    async fn partitioner(
+        &self,
+        _input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<Option<Box<dyn Partitioner>>, BoxedError> {
+        Ok(Some(Box::new(FirstFieldPartitioner)))
+    }
+}
+
+#[derive(Debug)]
+struct FirstFieldPartitioner;
+
+impl Partitioner for FirstFieldPartitioner {
+    fn partition_key(
+        &mut self,
+        _port: PortHandle,
+        record: &Record,
+    ) -> Result<Vec<Field>, BoxedError> {
+        Ok(vec![record.values[0].clone()])
+    }
+}
+
+#[test]
+fn test_run_dag_partitioned_processor() {
+    let count: u64 = 10_000;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+
+    let source_handle = NodeHandle::new(None, 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 2.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Box::new(GeneratorSourceFactory::new(count, latch.clone(), true)),
+    );
+    dag.add_processor(
+        proc_handle.clone(),
+        Box::new(PartitionedNoopProcessorFactory {}),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Box::new(CountingSinkFactory::new(count, latch)),
+    );
+
+    dag.connect(
+        Endpoint::new(source_handle, GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+
+    dag.connect(
+        Endpoint::new(proc_handle, DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let runtime = create_test_runtime();
+    let runtime_clone = runtime.clone();
+    let options = ExecutorOptions {
+        parallelism: 4,
+        ..Default::default()
+    };
+    let join_handle = runtime
+        .block_on(async move {
+            let (_temp_dir, checkpoint) = create_checkpoint_for_test().await;
+            DagExecutor::new(dag, checkpoint, options)
+                .await?
+                .start(pending::<()>(), Default::default(), runtime_clone)
+                .await
+        })
+        .unwrap();
+    join_handle.join().unwrap();
+}
+
+/// Restores a checkpoint written by `checkpointed_parallelism` instances of a partitioned processor
+/// and runs it with `parallelism` instances, returning the records the sink received.
+fn run_partitioned_dag_from_checkpoint(
+    checkpointed_parallelism: usize,
+    parallelism: usize,
+) -> Vec<Record> {
+    let count: usize = 10;
+
+    let mut dag = Dag::new();
+    let latch = Arc::new(AtomicBool::new(true));
+    let records = Arc::new(Mutex::new(vec![]));
+
+    let source_handle = NodeHandle::new(None, 1.to_string());
+    let proc_handle = NodeHandle::new(Some(1), 2.to_string());
+    let sink_handle = NodeHandle::new(Some(1), 3.to_string());
+
+    dag.add_source(
+        source_handle.clone(),
+        Box::new(GeneratorSourceFactory::new(
+            count as u64,
+            latch.clone(),
+            false,
+        )),
+    );
+    dag.add_processor(
+        proc_handle.clone(),
+        Box::new(PartitionedNoopProcessorFactory {}),
+    );
+    dag.add_sink(
+        sink_handle.clone(),
+        Box::new(CollectingSinkFactory::new(count, latch, records.clone())),
+    );
+    dag.connect(
+        Endpoint::new(source_handle.clone(), GENERATOR_SOURCE_OUTPUT_PORT),
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+    )
+    .unwrap();
+    dag.connect(
+        Endpoint::new(proc_handle.clone(), DEFAULT_PORT_HANDLE),
+        Endpoint::new(sink_handle, COUNTING_SINK_INPUT_PORT),
+    )
+    .unwrap();
+
+    let runtime = create_test_runtime();
+    let runtime_clone = runtime.clone();
+    let temp_dir = TempDir::new("run_partitioned_dag_from_checkpoint").unwrap();
+    let checkpoint_dir = temp_dir.path().to_str().unwrap().to_string();
+    let options = ExecutorOptions {
+        parallelism,
+        ..Default::default()
+    };
+    let join_handle = runtime.block_on(async move {
+        // Write a checkpoint at the 10th operation, with the state of every processor instance.
+        let checkpoint = OptionCheckpoint::new(checkpoint_dir.clone(), Default::default())
+            .await
+            .unwrap();
+        let (factory, handle) = CheckpointFactory::new(checkpoint, Default::default())
+            .await
+            .unwrap();
+        let factory = Arc::new(factory);
+        thread::spawn(move || {
+            let source_states = [(
+                source_handle,
+                SourceState::Restartable(OpIdentifier::new(0, 9).into()),
+            )]
+            .into_iter()
+            .collect();
+            let writer = CheckpointWriter::new(factory, 1, Arc::new(source_states), None);
+            for index in 0..checkpointed_parallelism {
+                drop(
+                    writer
+                        .create_processor_object(&partition_handle(
+                            &proc_handle,
+                            index,
+                            checkpointed_parallelism,
+                        ))
+                        .unwrap(),
+                );
+            }
+        })
+        .join()
+        .unwrap();
+        handle.await.unwrap();
+
+        let checkpoint_options = CheckpointOptions {
+            restore_from: Some("1".to_string()),
+            ..Default::default()
+        };
+        let checkpoint = OptionCheckpoint::new(checkpoint_dir, checkpoint_options)
+            .await
+            .unwrap();
+        DagExecutor::new(dag, checkpoint, options)
+            .await
+            .unwrap()
+            .start(pending::<()>(), Default::default(), runtime_clone)
+            .await
+            .unwrap()
+    });
+    join_handle.join().unwrap();
+
+    let records = records.lock().unwrap();
+    records.clone()
+}
+
+#[test]
+fn test_run_dag_partitioned_processor_from_checkpoint() {
+    let records = run_partitioned_dag_from_checkpoint(2, 2);
+    assert!(
+        records
+            .iter()
+            .all(|record| record.values[0] != Field::String("key_0".to_string())),
+        "the source must restart after the checkpoint's position"
+    );
+}
+
+#[test]
+fn test_run_dag_partitioned_processor_after_parallelism_change() {
+    let records = run_partitioned_dag_from_checkpoint(2, 4);
+    assert!(
+        records
+            .iter()
+            .any(|record| record.values[0] == Field::String("key_0".to_string())),
+        "the source must re-snapshot to rebuild the state of the new instances"
+    );
+}
+
 #[derive(Debug)]
 pub(crate) struct NoopJoinProcessorFactory {}
 
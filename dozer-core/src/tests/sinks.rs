@@ -3,15 +3,15 @@ use crate::node::{PortHandle, Sink, SinkFactory};
 use crate::DEFAULT_PORT_HANDLE;
 use dozer_log::storage::Queue;
 use dozer_types::errors::internal::BoxedError;
-use dozer_types::node::OpIdentifier;
-use dozer_types::types::{Schema, TableOperation};
+use dozer_types::node::SourcePosition;
+use dozer_types::types::{Operation, Record, Schema, TableOperation};
 
 use dozer_types::log::debug;
 use std::collections::HashMap;
 
 use dozer_types::tonic::async_trait;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 pub(crate) const COUNTING_SINK_INPUT_PORT: PortHandle = 90;
 
@@ -93,7 +93,7 @@ impl Sink for CountingSink {
     fn on_source_snapshotting_done(
         &mut self,
         _connection_name: String,
-        _id: Option<OpIdentifier>,
+        _id: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         Ok(())
     }
@@ -106,7 +106,7 @@ impl Sink for CountingSink {
         Ok(None)
     }
 
-    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
         Ok(None)
     }
 }
@@ -160,3 +160,106 @@ impl SinkFactory for NoInputPortSinkFactory {
         "no_input_port".to_string()
     }
 }
+
+/// Collects inserted records, and stops the sources after `expected` of them.
+#[derive(Debug)]
+pub(crate) struct CollectingSinkFactory {
+    expected: usize,
+    running: Arc<AtomicBool>,
+    records: Arc<Mutex<Vec<Record>>>,
+}
+
+impl CollectingSinkFactory {
+    pub fn new(
+        expected: usize,
+        barrier: Arc<AtomicBool>,
+        records: Arc<Mutex<Vec<Record>>>,
+    ) -> Self {
+        Self {
+            expected,
+            running: barrier,
+            records,
+        }
+    }
+}
+
+#[async_trait]
+impl SinkFactory for CollectingSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![COUNTING_SINK_INPUT_PORT]
+    }
+
+    fn prepare(&self, _input_schemas: HashMap<PortHandle, Schema>) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    async fn build(
+        &self,
+        _input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, BoxedError> {
+        Ok(Box::new(CollectingSink {
+            expected: self.expected,
+            running: self.running.clone(),
+            records: self.records.clone(),
+        }))
+    }
+
+    fn type_name(&self) -> String {
+        "collecting".to_string()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CollectingSink {
+    expected: usize,
+    running: Arc<AtomicBool>,
+    records: Arc<Mutex<Vec<Record>>>,
+}
+
+impl Sink for CollectingSink {
+    fn commit(&mut self, _epoch_details: &Epoch) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn process(&mut self, op: TableOperation) -> Result<(), BoxedError> {
+        if let Operation::Insert { new } = op.op {
+            let mut records = self.records.lock().unwrap();
+            records.push(new);
+            if records.len() == self.expected {
+                self.running.store(false, Ordering::Relaxed);
+            }
+        }
+        Ok(())
+    }
+
+    fn persist(&mut self, _epoch: &Epoch, _queue: &Queue) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_started(
+        &mut self,
+        _connection_name: String,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(
+        &mut self,
+        _connection_name: String,
+        _id: Option<SourcePosition>,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn set_source_state(&mut self, _source_state: &[u8]) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+        Ok(None)
+    }
+
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
+        Ok(None)
+    }
+}
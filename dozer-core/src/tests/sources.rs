@@ -3,7 +3,7 @@ use crate::DEFAULT_PORT_HANDLE;
 use dozer_log::tokio::{self, sync::mpsc::Sender};
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::models::ingestion_types::{IngestionMessage, TransactionInfo};
-use dozer_types::node::OpIdentifier;
+use dozer_types::node::{OpIdentifier, SourcePosition};
 use dozer_types::tonic::async_trait;
 use dozer_types::types::{
     Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
@@ -100,9 +100,12 @@ impl Source for GeneratorSource {
     async fn start(
         &mut self,
         sender: Sender<(PortHandle, IngestionMessage)>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         let start = last_checkpoint
+            .as_ref()
+            .map(OpIdentifier::try_from)
+            .transpose()?
             .map(|checkpoint| checkpoint.seq_in_tx + 1)
             .unwrap_or(0);
         for n in start..(start + self.count) {
@@ -117,7 +120,8 @@ impl Source for GeneratorSource {
                                 Field::String(format!("value_{n}")),
                             ]),
                         },
-                        id: Some(OpIdentifier::new(0, n)),
+                        id: Some(OpIdentifier::new(0, n).into()),
+                        source_time: None,
                     },
                 ))
                 .await?;
@@ -125,7 +129,7 @@ impl Source for GeneratorSource {
                 .send((
                     GENERATOR_SOURCE_OUTPUT_PORT,
                     IngestionMessage::TransactionInfo(TransactionInfo::Commit {
-                        id: Some(OpIdentifier::new(0, n)),
+                        id: Some(OpIdentifier::new(0, n).into()),
                     }),
                 ))
                 .await?;
@@ -242,7 +246,7 @@ impl Source for DualPortGeneratorSource {
     async fn start(
         &mut self,
         sender: Sender<(PortHandle, IngestionMessage)>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         for n in 1..(self.count + 1) {
             sender
@@ -256,7 +260,8 @@ impl Source for DualPortGeneratorSource {
                                 Field::String(format!("value_{n}")),
                             ]),
                         },
-                        id: Some(OpIdentifier::new(0, n)),
+                        id: Some(OpIdentifier::new(0, n).into()),
+                        source_time: None,
                     },
                 ))
                 .await?;
@@ -271,7 +276,8 @@ impl Source for DualPortGeneratorSource {
                                 Field::String(format!("value_{n}")),
                             ]),
                         },
-                        id: Some(OpIdentifier::new(0, n)),
+                        id: Some(OpIdentifier::new(0, n).into()),
+                        source_time: None,
                     },
                 ))
                 .await?;
@@ -279,7 +285,7 @@ impl Source for DualPortGeneratorSource {
                 .send((
                     DUAL_PORT_GENERATOR_SOURCE_OUTPUT_PORT_1,
                     IngestionMessage::TransactionInfo(TransactionInfo::Commit {
-                        id: Some(OpIdentifier::new(0, n)),
+                        id: Some(OpIdentifier::new(0, n).into()),
                     }),
                 ))
                 .await?;
@@ -39,17 +39,31 @@ pub enum Error {
     EvaluateModule(String, #[source] AnyError),
     #[error("failed to get namespace of module {0}: {1}")]
     GetModuleNamespace(String, #[source] AnyError),
-    #[error("module {0} has no default export")]
-    ModuleNoDefaultExport(String),
-    #[error("module {0} default export is not a function: {1}")]
-    ModuleDefaultExportNotFunction(String, #[source] v8::DataError),
+    #[error("module {0} has no export {1}")]
+    ModuleNoExport(String, String),
+    #[error("module {0} export {1} is not a function: {2}")]
+    ModuleExportNotFunction(String, String, #[source] v8::DataError),
 }
 
 impl Runtime {
-    /// Returns `Runtime` and the ids of the exported functions.
+    /// Returns `Runtime` and the ids of the default exports of `modules`.
     pub async fn new<T: (FnOnce() -> Extension) + Send + 'static>(
         modules: Vec<String>,
         extension_generators: Vec<T>,
+    ) -> Result<(Self, Vec<NonZeroI32>), Error> {
+        let functions = modules
+            .into_iter()
+            .map(|module| (module, "default".to_string()))
+            .collect();
+        Self::with_exports(functions, extension_generators).await
+    }
+
+    /// Returns `Runtime` and the ids of the functions, given as `(module, export name)` pairs.
+    ///
+    /// The ids are in the same order as `functions`. A module is only loaded once.
+    pub async fn with_exports<T: (FnOnce() -> Extension) + Send + 'static>(
+        functions: Vec<(String, String)>,
+        extension_generators: Vec<T>,
     ) -> Result<(Self, Vec<NonZeroI32>), Error> {
         let (init_sender, init_receiver) = oneshot::channel();
         let (work_sender, work_receiver) = mpsc::channel(10);
@@ -58,17 +72,14 @@ impl Runtime {
                 .into_iter()
                 .map(|generate| generate())
                 .collect::<Vec<_>>();
-            let mut worker = match Worker::new(modules, extensions) {
-                Ok(worker) => worker,
+            let (mut worker, ids) = match Worker::new(functions, extensions) {
+                Ok(result) => result,
                 Err(e) => {
                     let _ = init_sender.send(Err(e));
                     return;
                 }
             };
-            if init_sender
-                .send(Ok(worker.functions.keys().cloned().collect()))
-                .is_err()
-            {
+            if init_sender.send(Ok(ids)).is_err() {
                 return;
             }
             worker.run(work_receiver)
@@ -130,24 +141,39 @@ struct Worker {
 }
 
 impl Worker {
-    fn new(modules: Vec<String>, extensions: Vec<Extension>) -> Result<Self, Error> {
+    fn new(
+        exports: Vec<(String, String)>,
+        extensions: Vec<Extension>,
+    ) -> Result<(Self, Vec<NonZeroI32>), Error> {
         let tokio_runtime = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .map_err(Error::CreateJsRuntime)?;
         let mut js_runtime = js_runtime::new(extensions).map_err(Error::CreateJsRuntime)?;
 
-        let mut functions = HashMap::with_capacity(modules.len());
-        for module in modules {
-            let (id, fun) = tokio_runtime.block_on(Self::load_function(&mut js_runtime, module))?;
+        let mut namespaces = HashMap::new();
+        let mut functions = HashMap::with_capacity(exports.len());
+        let mut ids = Vec::with_capacity(exports.len());
+        for (module, export) in exports {
+            if !namespaces.contains_key(&module) {
+                let namespace =
+                    tokio_runtime.block_on(Self::load_module(&mut js_runtime, &module))?;
+                namespaces.insert(module.clone(), namespace);
+            }
+            let (id, fun) =
+                Self::get_function(&mut js_runtime, &namespaces[&module], &module, export)?;
             functions.insert(id, fun);
+            ids.push(id);
         }
 
-        Ok(Self {
-            tokio_runtime,
-            js_runtime,
-            functions,
-        })
+        Ok((
+            Self {
+                tokio_runtime,
+                js_runtime,
+                functions,
+            },
+            ids,
+        ))
     }
 
     async fn call_function(
@@ -197,10 +223,12 @@ impl Worker {
             }
         })
     }
-    async fn load_function(
+
+    async fn load_module(
         runtime: &mut JsRuntime,
-        module: String,
-    ) -> Result<(NonZeroI32, Global<Function>), Error> {
+        module: &str,
+    ) -> Result<Global<v8::Object>, Error> {
+        let module = module.to_string();
         let path = canonicalize(&module).map_err(|e| Error::CanonicalizePath(module.clone(), e))?;
         let module_specifier =
             ModuleSpecifier::from_file_path(path).expect("we just canonicalized it");
@@ -212,20 +240,27 @@ impl Worker {
         js_runtime::evaluate_module(runtime, module_id)
             .await
             .map_err(|e| Error::EvaluateModule(module.clone(), e))?;
-        let namespace = runtime
+        runtime
             .get_module_namespace(module_id)
-            .map_err(|e| Error::GetModuleNamespace(module.clone(), e))?;
+            .map_err(|e| Error::GetModuleNamespace(module, e))
+    }
+
+    fn get_function(
+        runtime: &mut JsRuntime,
+        namespace: &Global<v8::Object>,
+        module: &str,
+        export: String,
+    ) -> Result<(NonZeroI32, Global<Function>), Error> {
         let scope = &mut runtime.handle_scope();
         let namespace = v8::Local::new(scope, namespace);
-        let default_key = v8::String::new_external_onebyte_static(scope, b"default")
-            .unwrap()
-            .into();
-        let default_export = namespace
-            .get(scope, default_key)
-            .ok_or_else(|| Error::ModuleNoDefaultExport(module.clone()))?;
-        let function: Local<Function> = default_export
+        let key = v8::String::new(scope, &export).unwrap().into();
+        let value = namespace
+            .get(scope, key)
+            .filter(|value| !value.is_undefined())
+            .ok_or_else(|| Error::ModuleNoExport(module.to_string(), export.clone()))?;
+        let function: Local<Function> = value
             .try_into()
-            .map_err(|e| Error::ModuleDefaultExportNotFunction(module.clone(), e))?;
+            .map_err(|e| Error::ModuleExportNotFunction(module.to_string(), export, e))?;
         let id = function.get_identity_hash();
         Ok((id, Global::new(scope, function)))
     }
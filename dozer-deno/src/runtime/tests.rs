@@ -41,3 +41,37 @@ async fn test_async_function_call_exception() {
         .unwrap_err();
     assert!(error.to_string().starts_with("SyntaxError: "));
 }
+
+#[tokio::test]
+async fn test_named_exports() {
+    let module = "src/runtime/named_exports.js".to_string();
+    let (mut runtime, functions) = Runtime::with_exports::<fn() -> Extension>(
+        vec![
+            (module.clone(), "negate".to_string()),
+            (module.clone(), "add".to_string()),
+        ],
+        vec![],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        runtime
+            .call_function(functions[0], vec![json!(2.0)])
+            .await
+            .unwrap(),
+        json!(-2.0)
+    );
+    assert_eq!(
+        runtime
+            .call_function(functions[1], vec![json!(1.0), json!(2.0)])
+            .await
+            .unwrap(),
+        json!(3.0)
+    );
+
+    let error =
+        Runtime::with_exports::<fn() -> Extension>(vec![(module, "sub".to_string())], vec![])
+            .await
+            .unwrap_err();
+    assert!(matches!(error, Error::ModuleNoExport(_, export) if export == "sub"));
+}
@@ -4,7 +4,7 @@ use dozer_ingestion_connector::dozer_types::models::connection::AerospikeConnect
 use dozer_ingestion_connector::dozer_types::models::ingestion_types::{
     IngestionMessage, TransactionInfo,
 };
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
+use dozer_ingestion_connector::dozer_types::node::SourcePosition;
 use dozer_ingestion_connector::dozer_types::types::Operation::Insert;
 use dozer_ingestion_connector::dozer_types::types::{Field, FieldDefinition, FieldType, Schema};
 use dozer_ingestion_connector::{
@@ -361,7 +361,7 @@ impl Connector for AerospikeConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         let mapped_schema = self.get_schemas(&tables).await?;
         ingestor
@@ -385,7 +385,7 @@ impl Connector for AerospikeConnector {
                     .fields
                     .iter()
                     .enumerate()
-                    .map(|(i, field)| (field.name.clone(), (i, field.typ)))
+                    .map(|(i, field)| (field.name.clone(), (i, field.typ.clone())))
                     .collect();
 
                 (
@@ -435,7 +435,7 @@ async fn map_events(
         for bin in event.bins {
             if let Some((i, typ)) = columns_map.get(bin.name.as_str()) {
                 fields[*i] = match bin.value {
-                    Some(value) => map_value_to_field(bin.r#type.as_str(), value, *typ)?,
+                    Some(value) => map_value_to_field(bin.r#type.as_str(), value, typ.clone())?,
                     None => Field::Null,
                 };
             }
@@ -448,6 +448,7 @@ async fn map_events(
                     new: dozer_types::types::Record::new(fields),
                 },
                 id: None,
+                source_time: None,
             },
             IngestionMessage::TransactionInfo(TransactionInfo::Commit { id: None }),
         ]))
@@ -1,39 +1,84 @@
 use dozer_types::models::ingestion_types::IngestionMessage;
+use dozer_types::node::SourcePosition;
 use std::{error::Error, fmt::Display, time::Duration};
 use tokio::{
-    sync::mpsc::{channel, Receiver, Sender},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        watch,
+    },
     time::timeout,
 };
 
+use crate::memory_budget::{estimated_size, MemoryBudget};
+
 #[derive(Debug, Clone)]
 pub struct IngestionConfig {
     forwarder_channel_cap: usize,
+    memory_budget: Option<MemoryBudget>,
+    checkpointed_position: watch::Receiver<Option<SourcePosition>>,
 }
 
 impl Default for IngestionConfig {
     fn default() -> Self {
         Self {
             forwarder_channel_cap: 100000,
+            memory_budget: None,
+            // Nothing is ever checkpointed.
+            checkpointed_position: watch::channel(None).1,
         }
     }
 }
 
+impl IngestionConfig {
+    /// Bounds the channel by the estimated size of its messages too.
+    pub fn with_memory_budget(mut self, memory_budget: Option<MemoryBudget>) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    /// Follows the last position the pipeline has checkpointed.
+    pub fn with_checkpointed_position(
+        mut self,
+        checkpointed_position: watch::Receiver<Option<SourcePosition>>,
+    ) -> Self {
+        self.checkpointed_position = checkpointed_position;
+        self
+    }
+}
+
 #[derive(Debug)]
 /// `IngestionIterator` is the receiver side of a spsc channel. The sender side is `Ingestor`.
+///
+/// With a memory budget, messages must be received with `next`, `recv` or `next_timeout` so their budget is released.
 pub struct IngestionIterator {
     pub receiver: Receiver<IngestionMessage>,
+    memory_budget: Option<MemoryBudget>,
 }
 
 impl Iterator for IngestionIterator {
     type Item = IngestionMessage;
     fn next(&mut self) -> Option<Self::Item> {
-        self.receiver.blocking_recv()
+        let message = self.receiver.blocking_recv();
+        self.release(message.as_ref());
+        message
     }
 }
 
 impl IngestionIterator {
+    pub async fn recv(&mut self) -> Option<IngestionMessage> {
+        let message = self.receiver.recv().await;
+        self.release(message.as_ref());
+        message
+    }
+
     pub async fn next_timeout(&mut self, duration: Duration) -> Option<IngestionMessage> {
-        timeout(duration, self.receiver.recv()).await.ok().flatten()
+        timeout(duration, self.recv()).await.ok().flatten()
+    }
+
+    fn release(&self, message: Option<&IngestionMessage>) {
+        if let (Some(memory_budget), Some(message)) = (&self.memory_budget, message) {
+            memory_budget.release(estimated_size(message));
+        }
     }
 }
 
@@ -43,6 +88,8 @@ impl IngestionIterator {
 /// `IngestionMessage` is the message type that is sent over the channel.
 pub struct Ingestor {
     pub sender: Sender<IngestionMessage>,
+    memory_budget: Option<MemoryBudget>,
+    checkpointed_position: watch::Receiver<Option<SourcePosition>>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -59,17 +106,36 @@ impl Error for SendError {}
 impl Ingestor {
     pub fn initialize_channel(config: IngestionConfig) -> (Ingestor, IngestionIterator) {
         let (sender, receiver) = channel(config.forwarder_channel_cap);
-        let ingestor = Self { sender };
+        let ingestor = Self {
+            sender,
+            memory_budget: config.memory_budget.clone(),
+            checkpointed_position: config.checkpointed_position,
+        };
 
-        let iterator = IngestionIterator { receiver };
+        let iterator = IngestionIterator {
+            receiver,
+            memory_budget: config.memory_budget,
+        };
         (ingestor, iterator)
     }
 
     pub async fn handle_message(&self, message: IngestionMessage) -> Result<(), SendError> {
+        if let Some(memory_budget) = &self.memory_budget {
+            memory_budget.acquire(estimated_size(&message)).await;
+        }
         self.sender.send(message).await.map_err(|_| SendError)
     }
 
+    /// The last position sent by this ingestor that the pipeline has checkpointed, which changes as checkpoints are persisted.
+    /// Connectors may only remove ingested data from the source once a checkpointed position covers it.
+    pub fn checkpointed_position(&self) -> watch::Receiver<Option<SourcePosition>> {
+        self.checkpointed_position.clone()
+    }
+
     pub fn blocking_handle_message(&self, message: IngestionMessage) -> Result<(), SendError> {
+        if let Some(memory_budget) = &self.memory_budget {
+            memory_budget.blocking_acquire(estimated_size(&message));
+        }
         self.sender.blocking_send(message).map_err(|_| SendError)
     }
 }
@@ -83,7 +149,11 @@ mod tests {
     #[tokio::test]
     async fn test_message_handle() {
         let (sender, mut rx) = tokio::sync::mpsc::channel(10);
-        let ingestor = Ingestor { sender };
+        let ingestor = Ingestor {
+            sender,
+            memory_budget: None,
+            checkpointed_position: tokio::sync::watch::channel(None).1,
+        };
 
         // Expected seq no - 2
         let operation = Operation::Insert {
@@ -100,6 +170,7 @@ mod tests {
                 table_index: 0,
                 op: operation.clone(),
                 id: None,
+                source_time: None,
             })
             .await
             .unwrap();
@@ -108,6 +179,7 @@ mod tests {
                 table_index: 0,
                 op: operation2.clone(),
                 id: None,
+                source_time: None,
             })
             .await
             .unwrap();
@@ -126,7 +198,8 @@ mod tests {
                 IngestionMessage::OperationEvent {
                     table_index: 0,
                     op,
-                    id: None
+                    id: None,
+                    source_time: None,
                 },
                 msg
             );
@@ -1,18 +1,21 @@
+use std::cmp::Ordering;
 use std::fmt::Debug;
 
 use dozer_types::errors::internal::BoxedError;
-use dozer_types::node::OpIdentifier;
+use dozer_types::node::{OpIdentifier, SourcePosition};
 use dozer_types::serde;
 use dozer_types::serde::{Deserialize, Serialize};
 pub use dozer_types::tonic::async_trait;
-use dozer_types::types::{FieldType, Schema};
+use dozer_types::types::{FieldType, Record, Schema};
 
 mod ingestor;
+mod memory_budget;
 pub mod schema_parser;
 pub mod test_util;
 pub mod utils;
 
 pub use ingestor::{IngestionConfig, IngestionIterator, Ingestor};
+pub use memory_budget::{estimated_size, MemoryBudget};
 
 pub use dozer_types;
 pub use futures;
@@ -107,8 +110,28 @@ pub trait Connector: Send + Sync + Debug {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError>;
+
+    /// Reads up to `limit` rows of each of `tables`, for previews.
+    ///
+    /// Unlike `start`, this must not change anything in the source, like creating publications or replication slots, locking tables or deleting ingested files.
+    /// Returns `None` if the connector can't read without such side effects.
+    async fn sample(
+        &mut self,
+        _tables: Vec<TableInfo>,
+        _limit: usize,
+    ) -> Result<Option<Vec<Vec<Record>>>, BoxedError> {
+        Ok(None)
+    }
+
+    /// Orders two positions this connector sent, so that a source feeding several sinks restarts from the oldest one.
+    /// `None` if they can't be ordered. The default orders positions converted from `OpIdentifier`s.
+    fn compare_positions(&self, a: &SourcePosition, b: &SourcePosition) -> Option<Ordering> {
+        let a = OpIdentifier::try_from(a).ok()?;
+        let b = OpIdentifier::try_from(b).ok()?;
+        Some(a.cmp(&b))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -0,0 +1,147 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use dozer_types::models::ingestion_types::IngestionMessage;
+use dozer_types::types::{Operation, Record};
+use metrics::{describe_gauge, describe_histogram, gauge, histogram};
+use tokio::sync::Notify;
+
+const MEMORY_BUDGET_USED_GAUGE_NAME: &str = "ingestion_memory_budget_used_bytes";
+const MEMORY_BUDGET_WAIT_HISTOGRAM_NAME: &str = "ingestion_memory_budget_wait_seconds";
+
+/// Bytes of ingested messages that may be buffered between the connectors and the pipeline, shared by all sources.
+///
+/// Connectors wait for budget before sending a message, so they pause while the pipeline falls behind.
+/// Sizes are estimated from the records' encoded size.
+#[derive(Debug, Clone)]
+pub struct MemoryBudget {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    capacity: u64,
+    used: Mutex<u64>,
+    released: Notify,
+}
+
+impl MemoryBudget {
+    pub fn new(capacity: u64) -> Self {
+        describe_gauge!(
+            MEMORY_BUDGET_USED_GAUGE_NAME,
+            "Estimated bytes of ingested messages waiting to enter the pipeline"
+        );
+        describe_histogram!(
+            MEMORY_BUDGET_WAIT_HISTOGRAM_NAME,
+            "Time connectors waited for the memory budget"
+        );
+        Self {
+            inner: Arc::new(Inner {
+                capacity,
+                used: Mutex::new(0),
+                released: Notify::new(),
+            }),
+        }
+    }
+
+    pub fn used(&self) -> u64 {
+        *self.inner.used.lock().unwrap()
+    }
+
+    /// Waits until `bytes` fit in the budget and takes them. A message larger than the whole budget takes all of it.
+    pub async fn acquire(&self, bytes: u64) {
+        let bytes = bytes.min(self.inner.capacity);
+        let mut wait_start = None;
+        loop {
+            // Created before checking, so a release in between isn't missed.
+            let released = self.inner.released.notified();
+            if self.try_acquire(bytes) {
+                if let Some(wait_start) = wait_start {
+                    histogram!(
+                        MEMORY_BUDGET_WAIT_HISTOGRAM_NAME,
+                        Instant::now().duration_since(wait_start)
+                    );
+                }
+                return;
+            }
+            wait_start.get_or_insert_with(Instant::now);
+            released.await;
+        }
+    }
+
+    /// For connectors sending from outside the runtime.
+    pub fn blocking_acquire(&self, bytes: u64) {
+        futures::executor::block_on(self.acquire(bytes))
+    }
+
+    pub fn release(&self, bytes: u64) {
+        let bytes = bytes.min(self.inner.capacity);
+        let mut used = self.inner.used.lock().unwrap();
+        *used = used.saturating_sub(bytes);
+        gauge!(MEMORY_BUDGET_USED_GAUGE_NAME, *used as f64);
+        drop(used);
+        self.inner.released.notify_waiters();
+    }
+
+    fn try_acquire(&self, bytes: u64) -> bool {
+        let mut used = self.inner.used.lock().unwrap();
+        if *used + bytes > self.inner.capacity {
+            return false;
+        }
+        *used += bytes;
+        gauge!(MEMORY_BUDGET_USED_GAUGE_NAME, *used as f64);
+        true
+    }
+}
+
+/// Estimated bytes a message holds, counting its records' encoded size.
+pub fn estimated_size(message: &IngestionMessage) -> u64 {
+    fn record_size(record: &Record) -> u64 {
+        record
+            .values
+            .iter()
+            .map(|field| field.encoding_len() as u64)
+            .sum()
+    }
+
+    match message {
+        IngestionMessage::OperationEvent { op, .. } => match op {
+            Operation::Delete { old } => record_size(old),
+            Operation::Insert { new } => record_size(new),
+            Operation::Update { old, new } => record_size(old) + record_size(new),
+            Operation::BatchInsert { new } => new.iter().map(record_size).sum(),
+        },
+        IngestionMessage::TransactionInfo(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_budget_waits_for_release() {
+        let budget = MemoryBudget::new(100);
+        budget.acquire(60).await;
+        budget.acquire(40).await;
+        assert_eq!(budget.used(), 100);
+
+        let waiting = tokio::spawn({
+            let budget = budget.clone();
+            async move { budget.acquire(50).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        budget.release(60);
+        waiting.await.unwrap();
+        assert_eq!(budget.used(), 90);
+
+        // Larger than the whole budget, so it waits for everything to be released.
+        budget.release(90);
+        budget.acquire(1000).await;
+        assert_eq!(budget.used(), 100);
+    }
+}
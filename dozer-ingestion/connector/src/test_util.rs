@@ -4,6 +4,7 @@ use dozer_types::{
     constants::DEFAULT_CONFIG_PATH,
     log::error,
     models::{config::Config, connection::ConnectionConfig},
+    node::SourcePosition,
 };
 use futures::stream::{AbortHandle, Abortable};
 use tokio::runtime::Runtime;
@@ -20,16 +21,28 @@ pub fn create_test_runtime() -> Arc<Runtime> {
 }
 
 pub fn spawn_connector(
+    runtime: Arc<Runtime>,
+    connector: impl Connector + 'static,
+    tables: Vec<TableInfo>,
+) -> (IngestionIterator, AbortHandle) {
+    spawn_connector_from_checkpoint(runtime, connector, tables, None)
+}
+
+pub fn spawn_connector_from_checkpoint(
     runtime: Arc<Runtime>,
     mut connector: impl Connector + 'static,
     tables: Vec<TableInfo>,
+    last_checkpoint: Option<SourcePosition>,
 ) -> (IngestionIterator, AbortHandle) {
     let (ingestor, iterator) = Ingestor::initialize_channel(Default::default());
     let (abort_handle, abort_registration) = AbortHandle::new_pair();
     runtime.clone().spawn_blocking(move || {
         runtime.block_on(async move {
-            if let Ok(Err(e)) =
-                Abortable::new(connector.start(&ingestor, tables, None), abort_registration).await
+            if let Ok(Err(e)) = Abortable::new(
+                connector.start(&ingestor, tables, last_checkpoint),
+                abort_registration,
+            )
+            .await
             {
                 error!("Connector `start` returned error: {e}")
             }
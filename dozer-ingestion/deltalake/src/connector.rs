@@ -3,7 +3,9 @@ use crate::schema_helper::SchemaHelper;
 use dozer_ingestion_connector::{
     async_trait,
     dozer_types::{
-        errors::internal::BoxedError, models::ingestion_types::DeltaLakeConfig, node::OpIdentifier,
+        errors::internal::BoxedError,
+        models::ingestion_types::{DeltaLakeConfig, IngestionMessage, TransactionInfo},
+        node::SourcePosition,
         types::FieldType,
     },
     utils::{ListOrFilterColumns, TableNotFound},
@@ -117,9 +119,19 @@ impl Connector for DeltaLakeConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         assert!(last_checkpoint.is_none());
+        // Tables are read once as operations, there's no separate snapshot.
+        if ingestor
+            .handle_message(IngestionMessage::TransactionInfo(
+                TransactionInfo::SnapshottingSkipped,
+            ))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
         let reader = DeltaLakeReader::new(self.config.clone());
         reader.read(&tables, ingestor).await
     }
@@ -70,6 +70,7 @@ impl DeltaLakeReader {
                             },
                         },
                         id: None,
+                        source_time: None,
                     })
                     .await
                     .unwrap();
@@ -10,9 +10,9 @@ use dozer_ingestion_connector::{
         },
         models::ingestion_types::{
             default_buffer_size, default_log_batch_size, default_timeout, IngestionMessage,
-            NestedDozerConfig, NestedDozerLogOptions,
+            NestedDozerConfig, NestedDozerLogOptions, TransactionInfo,
         },
-        node::OpIdentifier,
+        node::{OpIdentifier, SourcePosition},
         serde_json,
         tonic::{async_trait, transport::Channel},
         types::{FieldType, Operation, Record, Schema},
@@ -130,8 +130,22 @@ impl Connector for NestedDozerConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
+        // The upstream logs are read as operations, there's no separate snapshot.
+        if ingestor
+            .handle_message(IngestionMessage::TransactionInfo(
+                TransactionInfo::SnapshottingSkipped,
+            ))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
+        let last_checkpoint = last_checkpoint
+            .as_ref()
+            .map(OpIdentifier::try_from)
+            .transpose()?;
         let mut joinset = JoinSet::new();
         let (sender, mut receiver) = channel(100);
 
@@ -260,7 +274,8 @@ async fn read_table(
             .send(IngestionMessage::OperationEvent {
                 table_index,
                 op,
-                id: Some(OpIdentifier::new(0, op_and_pos.pos)),
+                id: Some(OpIdentifier::new(0, op_and_pos.pos).into()),
+                source_time: None,
             })
             .await;
     }
@@ -3,14 +3,14 @@ use std::{str::FromStr, sync::Arc};
 
 use super::helper;
 use super::sender::{run, EthDetails};
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
+use dozer_ingestion_connector::dozer_types::node::SourcePosition;
 use dozer_ingestion_connector::utils::TableNotFound;
 use dozer_ingestion_connector::{
     async_trait,
     dozer_types::{
         errors::internal::BoxedError,
         log::warn,
-        models::ingestion_types::{EthFilter, EthLogConfig},
+        models::ingestion_types::{EthFilter, EthLogConfig, IngestionMessage, TransactionInfo},
         serde_json,
         types::FieldType,
     },
@@ -242,8 +242,18 @@ impl Connector for EthLogConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
+        // Logs are replicated block by block, there's no snapshot.
+        if ingestor
+            .handle_message(IngestionMessage::TransactionInfo(
+                TransactionInfo::SnapshottingSkipped,
+            ))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
         // Start a new thread that interfaces with ETH node
         let wss_url = self.config.wss_url.to_owned();
         let filter = self.config.filter.to_owned().unwrap_or_default();
@@ -223,6 +223,7 @@ async fn process_log(details: Arc<EthDetails<'_>>, msg: Log) {
                     table_index,
                     op,
                     id: None,
+                    source_time: None,
                 })
                 .await
                 .is_err()
@@ -246,6 +247,7 @@ async fn process_log(details: Arc<EthDetails<'_>>, msg: Log) {
                     table_index,
                     op,
                     id: None,
+                    source_time: None,
                 })
                 .await;
         } else {
@@ -4,7 +4,9 @@ use dozer_ingestion_connector::{
     dozer_types::{
         errors::internal::BoxedError,
         log::info,
-        models::ingestion_types::{EthContract, EthFilter, EthLogConfig, IngestionMessage},
+        models::ingestion_types::{
+            EthContract, EthFilter, EthLogConfig, IngestionMessage, TransactionInfo,
+        },
         types::Operation,
     },
     test_util::spawn_connector,
@@ -90,6 +92,12 @@ pub async fn run_eth_sample(
 
     let (connector, tables) = get_eth_tables(wss_url, contract.clone()).await.unwrap();
     let (mut iterator, _) = spawn_connector(runtime, connector, tables);
+    assert_eq!(
+        iterator.next_timeout(Duration::from_millis(400)).await,
+        Some(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingSkipped
+        ))
+    );
 
     let mut msgs = vec![];
     while let Some(IngestionMessage::OperationEvent {
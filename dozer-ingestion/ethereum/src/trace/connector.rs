@@ -3,8 +3,10 @@ use dozer_ingestion_connector::{
     dozer_types::{
         errors::internal::BoxedError,
         log::{error, info, warn},
-        models::ingestion_types::{default_batch_size, EthTraceConfig, IngestionMessage},
-        node::OpIdentifier,
+        models::ingestion_types::{
+            default_batch_size, EthTraceConfig, IngestionMessage, TransactionInfo,
+        },
+        node::SourcePosition,
         types::FieldType,
     },
     utils::TableNotFound,
@@ -106,8 +108,18 @@ impl Connector for EthTraceConnector {
         &mut self,
         ingestor: &Ingestor,
         _tables: Vec<TableInfo>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
+        // Traces are replicated block by block, there's no snapshot.
+        if ingestor
+            .handle_message(IngestionMessage::TransactionInfo(
+                TransactionInfo::SnapshottingSkipped,
+            ))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
         let config = self.config.clone();
         let conn_name = self.conn_name.clone();
         run(ingestor, config, conn_name).await
@@ -161,6 +173,7 @@ pub async fn run(
                                     table_index: 0, // We have only one table
                                     op,
                                     id: None,
+                                    source_time: None,
                                 })
                                 .await
                                 .is_err()
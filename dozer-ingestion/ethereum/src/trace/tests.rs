@@ -3,7 +3,7 @@ use std::{env, time::Duration};
 use dozer_ingestion_connector::{
     dozer_types::{
         log::info,
-        models::ingestion_types::{EthTraceConfig, IngestionMessage},
+        models::ingestion_types::{EthTraceConfig, IngestionMessage, TransactionInfo},
         types::{Field, Operation},
     },
     test_util::{create_test_runtime, spawn_connector},
@@ -67,6 +67,12 @@ fn test_trace_iterator() {
     let (mut iterator, _) = spawn_connector(runtime.clone(), connector, tables);
 
     runtime.block_on(async move {
+        assert_eq!(
+            iterator.next_timeout(Duration::from_millis(1000)).await,
+            Some(IngestionMessage::TransactionInfo(
+                TransactionInfo::SnapshottingSkipped
+            ))
+        );
         if let Some(IngestionMessage::OperationEvent { op, .. }) =
             iterator.next_timeout(Duration::from_millis(1000)).await
         {
@@ -116,6 +116,7 @@ pub async fn handle_message(
                 table_index,
                 op,
                 id: None,
+                source_time: None,
             })
             .await
             .is_err()
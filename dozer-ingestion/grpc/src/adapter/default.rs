@@ -89,6 +89,7 @@ pub async fn handle_message(
             table_index,
             op,
             id: None,
+            source_time: None,
         })
         .await;
     let _ = ingestor
@@ -111,7 +112,7 @@ fn map_record(rec: Vec<grpc_types::types::Value>, schema: &Schema) -> Result<Rec
     }
 
     for (idx, v) in rec.into_iter().enumerate() {
-        let typ = schema.fields[idx].typ;
+        let typ = &schema.fields[idx].typ;
 
         let val = v.value.map(|value| match (value, typ) {
             (
@@ -187,7 +188,7 @@ fn map_record(rec: Vec<grpc_types::types::Value>, schema: &Schema) -> Result<Rec
             (a, b) => Err(Error::FieldTypeMismatch {
                 index: idx,
                 value: a,
-                field_type: b,
+                field_type: b.clone(),
             }),
         });
         values.push(val.unwrap_or(Ok(dozer_types::types::Field::Null))?);
@@ -4,7 +4,7 @@ use crate::Error;
 
 use super::adapter::{GrpcIngestor, IngestAdapter};
 use super::ingest::IngestorServiceImpl;
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
+use dozer_ingestion_connector::dozer_types::node::SourcePosition;
 use dozer_ingestion_connector::schema_parser::SchemaParser;
 use dozer_ingestion_connector::utils::TableNotFound;
 use dozer_ingestion_connector::{
@@ -13,7 +13,9 @@ use dozer_ingestion_connector::{
         errors::internal::BoxedError,
         grpc_types::ingest::ingest_service_server::IngestServiceServer,
         log::{info, warn},
-        models::ingestion_types::{default_ingest_host, default_ingest_port, GrpcConfig},
+        models::ingestion_types::{
+            default_ingest_host, default_ingest_port, GrpcConfig, IngestionMessage, TransactionInfo,
+        },
         tonic::transport::Server,
         tracing::Level,
     },
@@ -201,8 +203,18 @@ where
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
+        // Operations are only received once the server is up, there's no snapshot.
+        if ingestor
+            .handle_message(IngestionMessage::TransactionInfo(
+                TransactionInfo::SnapshottingSkipped,
+            ))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
         self.serve(ingestor, tables).await.map_err(Into::into)
     }
 }
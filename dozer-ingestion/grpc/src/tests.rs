@@ -12,7 +12,7 @@ use dozer_ingestion_connector::dozer_types::{
     },
     json_types::json as dozer_json,
     models::ingestion_types::IngestionMessage,
-    models::ingestion_types::{ConfigSchemas, GrpcConfig},
+    models::ingestion_types::{ConfigSchemas, GrpcConfig, TransactionInfo},
     serde_json,
     serde_json::json,
     serde_json::Value,
@@ -45,7 +45,13 @@ fn ingest_grpc<T: IngestAdapter>(
         },
     );
 
-    let (iterator, _) = spawn_connector_all_tables(runtime.clone(), grpc_connector);
+    let (mut iterator, _) = spawn_connector_all_tables(runtime.clone(), grpc_connector);
+    assert_eq!(
+        iterator.next(),
+        Some(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingSkipped
+        ))
+    );
 
     let retries = 10;
     let url = format!("http://0.0.0.0:{port}");
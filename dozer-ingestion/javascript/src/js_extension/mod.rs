@@ -1,22 +1,28 @@
 use std::{future::Future, sync::Arc};
 
 use dozer_deno::deno_runtime::{
-    deno_core::{self, anyhow::Error, extension, op2, ModuleSpecifier},
+    deno_core::{
+        self,
+        anyhow::{bail, Context as _, Error},
+        extension, op2, Extension, ModuleSpecifier,
+    },
     permissions::PermissionsContainer,
     worker::{MainWorker, WorkerOptions},
 };
 use dozer_ingestion_connector::{
     dozer_types::{
-        errors::{internal::BoxedError, types::DeserializationError},
-        json_types::serde_json_to_json_value,
+        errors::internal::BoxedError,
+        json_types::{serde_json_to_json_value, JsonValue},
+        json_value_to_field,
         models::ingestion_types::{IngestionMessage, TransactionInfo},
+        node::SourcePosition,
         serde::{Deserialize, Serialize},
         serde_json,
         thiserror::{self, Error},
-        types::{Field, Operation, Record},
+        types::{Field, FieldDefinition, Operation, Record},
     },
     tokio::{runtime::Runtime, task::LocalSet},
-    Ingestor,
+    IngestionConfig, Ingestor,
 };
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -33,13 +39,107 @@ pub enum MsgType {
 #[serde(crate = "dozer_ingestion_connector::dozer_types::serde")]
 pub struct JsMessage {
     typ: MsgType,
+    /// The table of an operation. Can be omitted if only one table is ingested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    table: Option<String>,
+    #[serde(default)]
     old_val: serde_json::Value,
+    #[serde(default)]
     new_val: serde_json::Value,
+    /// Where to resume after this message. Dozer checkpoints it and passes it back to the module on restart.
+    /// Cursors must grow as the module ingests, so that sinks checkpointed at different cursors restart from the oldest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cursor: Option<serde_json::Value>,
+}
+
+/// How the records of a table are read from messages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordFormat {
+    /// The value is the only field, as JSON.
+    Json,
+    /// The value is an object keyed by column name.
+    Columns(Vec<FieldDefinition>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngestedTable {
+    pub name: String,
+    pub format: RecordFormat,
+}
+
+/// The state of the `ingest` op. Table indexes are positions in `tables`.
+#[derive(Debug, Clone)]
+pub struct JsIngestor {
+    ingestor: Ingestor,
+    tables: Arc<Vec<IngestedTable>>,
+}
+
+impl JsIngestor {
+    pub fn new(ingestor: Ingestor, tables: Vec<IngestedTable>) -> Self {
+        Self {
+            ingestor,
+            tables: Arc::new(tables),
+        }
+    }
+
+    /// Returns `None` if the operation belongs to a table that is not ingested.
+    fn map_operation(&self, msg: JsMessage) -> Result<Option<(usize, Operation)>, Error> {
+        let table_index = match &msg.table {
+            Some(name) => match self.tables.iter().position(|table| &table.name == name) {
+                Some(index) => index,
+                None => return Ok(None),
+            },
+            None if self.tables.len() == 1 => 0,
+            None => bail!(
+                "{:?} message must name its table, because {} tables are ingested",
+                msg.typ,
+                self.tables.len()
+            ),
+        };
+
+        let format = &self.tables[table_index].format;
+        let op = match msg.typ {
+            MsgType::Insert => Operation::Insert {
+                new: format.record(msg.new_val)?,
+            },
+            MsgType::Delete => Operation::Delete {
+                old: format.record(msg.old_val)?,
+            },
+            MsgType::Update => Operation::Update {
+                old: format.record(msg.old_val)?,
+                new: format.record(msg.new_val)?,
+            },
+            MsgType::SnapshottingStarted | MsgType::SnapshottingDone => unreachable!(),
+        };
+        Ok(Some((table_index, op)))
+    }
+}
+
+impl RecordFormat {
+    fn record(&self, value: serde_json::Value) -> Result<Record, Error> {
+        let values = match self {
+            RecordFormat::Json => vec![Field::Json(serde_json_to_json_value(value)?)],
+            RecordFormat::Columns(fields) => {
+                let serde_json::Value::Object(mut object) = value else {
+                    bail!("expected an object keyed by column name, got {value}");
+                };
+                fields
+                    .iter()
+                    .map(|field| {
+                        let value = object.remove(&field.name).unwrap_or_default();
+                        json_value_to_field(value, field.typ.clone(), field.nullable)
+                            .with_context(|| format!("column {}", field.name))
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+        };
+        Ok(Record::new(values))
+    }
 }
 
 #[op2(async)]
 fn ingest(
-    #[state] ingestor: &Ingestor,
+    #[state] ingestor: &JsIngestor,
     #[serde] val: JsMessage,
 ) -> impl Future<Output = Result<(), Error>> {
     send(ingestor.clone(), val)
@@ -48,15 +148,16 @@ fn ingest(
 extension!(
     dozer_extension,
     ops = [ingest],
-    options = { ingestor: Ingestor },
+    options = { ingestor: JsIngestor },
     state = |state, options| {
         state.put(options.ingestor);
     },
 );
 
+/// Runs a module that ingests from its top-level code.
 pub struct JsExtension {
     runtime: Arc<Runtime>,
-    ingestor: Ingestor,
+    ingestor: JsIngestor,
     module_specifier: ModuleSpecifier,
 }
 
@@ -69,7 +170,7 @@ pub enum JsExtensionError {
 impl JsExtension {
     pub fn new(
         runtime: Arc<Runtime>,
-        ingestor: Ingestor,
+        ingestor: JsIngestor,
         js_path: String,
     ) -> Result<Self, JsExtensionError> {
         let path = std::fs::canonicalize(js_path.clone())
@@ -111,61 +212,63 @@ impl JsExtension {
     }
 }
 
-async fn send(ingestor: Ingestor, val: JsMessage) -> Result<(), Error> {
+/// Calls the default export of a module with the cursor to resume from, and waits for it to finish ingesting.
+pub async fn run_module(
+    ingestor: JsIngestor,
+    module: String,
+    cursor: JsonValue,
+) -> Result<(), BoxedError> {
+    let (mut runtime, functions) = dozer_deno::Runtime::with_exports(
+        vec![(module, "default".to_string())],
+        vec![move || dozer_extension::init_ops(ingestor)],
+    )
+    .await?;
+    runtime.call_function(functions[0], vec![cursor]).await?;
+    Ok(())
+}
+
+/// Provides the `ingest` op but drops all messages, so a module can be evaluated without ingesting.
+pub fn discarding_extension() -> Extension {
+    let (ingestor, _) = Ingestor::initialize_channel(IngestionConfig::default());
+    dozer_extension::init_ops(JsIngestor::new(ingestor, vec![]))
+}
+
+async fn send(ingestor: JsIngestor, val: JsMessage) -> Result<(), Error> {
+    let id = val
+        .cursor
+        .as_ref()
+        .map(SourcePosition::encode)
+        .transpose()?;
     let msg = match val.typ {
         MsgType::SnapshottingStarted => {
             IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingStarted)
         }
         MsgType::SnapshottingDone => {
-            IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingDone { id: None })
+            IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingDone { id: id.clone() })
         }
         MsgType::Insert | MsgType::Delete | MsgType::Update => {
-            let op = map_operation(val)?;
+            let Some((table_index, op)) = ingestor.map_operation(val)? else {
+                return Ok(());
+            };
             IngestionMessage::OperationEvent {
-                table_index: 0,
+                table_index,
                 op,
-                id: None,
+                id: id.clone(),
+                source_time: None,
             }
         }
     };
 
     // Ignore if the receiver is closed.
-    let _ = ingestor.handle_message(msg).await;
+    let _ = ingestor.ingestor.handle_message(msg).await;
     let _ = ingestor
+        .ingestor
         .handle_message(IngestionMessage::TransactionInfo(TransactionInfo::Commit {
-            id: None,
+            id,
         }))
         .await;
     Ok(())
 }
 
-fn map_operation(msg: JsMessage) -> Result<Operation, DeserializationError> {
-    Ok(match msg.typ {
-        MsgType::Insert => Operation::Insert {
-            new: Record {
-                values: vec![Field::Json(serde_json_to_json_value(msg.new_val)?)],
-                lifetime: None,
-            },
-        },
-        MsgType::Delete => Operation::Delete {
-            old: Record {
-                values: vec![Field::Json(serde_json_to_json_value(msg.old_val)?)],
-                lifetime: None,
-            },
-        },
-        MsgType::Update => Operation::Update {
-            old: Record {
-                values: vec![Field::Json(serde_json_to_json_value(msg.old_val)?)],
-                lifetime: None,
-            },
-            new: Record {
-                values: vec![Field::Json(serde_json_to_json_value(msg.new_val)?)],
-                lifetime: None,
-            },
-        },
-        _ => unreachable!(),
-    })
-}
-
 #[cfg(test)]
 mod tests;
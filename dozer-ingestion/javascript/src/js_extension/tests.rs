@@ -1,9 +1,17 @@
 use std::time::Duration;
 
 use camino::Utf8Path;
-use dozer_ingestion_connector::{test_util::create_test_runtime, IngestionConfig, Ingestor};
+use dozer_ingestion_connector::{
+    dozer_types::{
+        json_types::json,
+        serde_json,
+        types::{Field, FieldDefinition, FieldType, Operation, Record, SourceDefinition},
+    },
+    test_util::create_test_runtime,
+    IngestionConfig, Ingestor,
+};
 
-use super::JsExtension;
+use super::{IngestedTable, JsExtension, JsIngestor, JsMessage, RecordFormat};
 
 #[test]
 #[ignore = "this test fails if it runs together with tests in `dozer-deno`. Not sure why."]
@@ -11,6 +19,13 @@ fn test_deno() {
     let js_path = Utf8Path::new(env!("CARGO_MANIFEST_DIR")).join("./src/js_extension/ingest.js");
 
     let (ingestor, mut iterator) = Ingestor::initialize_channel(IngestionConfig::default());
+    let ingestor = JsIngestor::new(
+        ingestor,
+        vec![IngestedTable {
+            name: "json_records".to_string(),
+            format: RecordFormat::Json,
+        }],
+    );
 
     let runtime = create_test_runtime();
     let ext = JsExtension::new(runtime.clone(), ingestor, js_path.into()).unwrap();
@@ -29,3 +44,109 @@ fn test_deno() {
         }
     });
 }
+
+fn users_table() -> IngestedTable {
+    let field = |name: &str, typ, nullable| {
+        FieldDefinition::new(name.to_string(), typ, nullable, SourceDefinition::Dynamic)
+    };
+    IngestedTable {
+        name: "users".to_string(),
+        format: RecordFormat::Columns(vec![
+            field("id", FieldType::Int, false),
+            field("name", FieldType::String, true),
+        ]),
+    }
+}
+
+fn js_ingestor(tables: Vec<IngestedTable>) -> JsIngestor {
+    let (ingestor, _) = Ingestor::initialize_channel(IngestionConfig::default());
+    JsIngestor::new(ingestor, tables)
+}
+
+fn message(value: serde_json::Value) -> JsMessage {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn test_map_operation() {
+    let ingestor = js_ingestor(vec![
+        IngestedTable {
+            name: "events".to_string(),
+            format: RecordFormat::Json,
+        },
+        users_table(),
+    ]);
+
+    let (table_index, op) = ingestor
+        .map_operation(message(serde_json::json!({
+            "typ": "Update",
+            "table": "users",
+            "old_val": { "id": 1, "name": "alice" },
+            "new_val": { "id": 1 },
+        })))
+        .unwrap()
+        .unwrap();
+    assert_eq!(table_index, 1);
+    assert_eq!(
+        op,
+        Operation::Update {
+            old: Record::new(vec![Field::Int(1), Field::String("alice".to_string())]),
+            new: Record::new(vec![Field::Int(1), Field::Null]),
+        }
+    );
+
+    let (table_index, op) = ingestor
+        .map_operation(message(serde_json::json!({
+            "typ": "Insert",
+            "table": "events",
+            "new_val": { "kind": "click" },
+            "cursor": 42,
+        })))
+        .unwrap()
+        .unwrap();
+    assert_eq!(table_index, 0);
+    assert_eq!(
+        op,
+        Operation::Insert {
+            new: Record::new(vec![Field::Json(json!({ "kind": "click" }))]),
+        }
+    );
+
+    // Tables that are not ingested are skipped.
+    assert!(ingestor
+        .map_operation(message(serde_json::json!({
+            "typ": "Delete",
+            "table": "orders",
+            "old_val": { "id": 1 },
+        })))
+        .unwrap()
+        .is_none());
+
+    // The table must be named when more than one is ingested.
+    assert!(ingestor
+        .map_operation(message(serde_json::json!({
+            "typ": "Insert",
+            "new_val": { "id": 1 },
+        })))
+        .is_err());
+}
+
+#[test]
+fn test_map_operation_type_errors() {
+    let ingestor = js_ingestor(vec![users_table()]);
+
+    // A non-nullable column is missing.
+    assert!(ingestor
+        .map_operation(message(serde_json::json!({
+            "typ": "Insert",
+            "new_val": { "name": "alice" },
+        })))
+        .is_err());
+    // The value is not an object.
+    assert!(ingestor
+        .map_operation(message(serde_json::json!({
+            "typ": "Insert",
+            "new_val": [1, "alice"],
+        })))
+        .is_err());
+}
@@ -1,77 +1,135 @@
-use std::sync::Arc;
+use std::{cmp::Ordering, sync::Arc};
 
 use dozer_ingestion_connector::{
     async_trait,
     dozer_types::{
         errors::internal::BoxedError,
+        json_types::{serde_json_to_json_value, JsonValue},
         models::ingestion_types::{default_bootstrap_path, JavaScriptConfig},
-        node::OpIdentifier,
-        types::{FieldDefinition, FieldType, Schema, SourceDefinition},
+        node::SourcePosition,
+        serde_json,
+        types::FieldType,
     },
     tokio::runtime::Runtime,
-    CdcType, Connector, Ingestor, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+    utils::TableNotFound,
+    Connector, Ingestor, SourceSchemaResult, TableIdentifier, TableInfo,
 };
-use js_extension::JsExtension;
+use js_extension::{IngestedTable, JsExtension, JsIngestor, RecordFormat};
+use tables::{load_tables, TableDeclaration};
 
+/// Ingests with a JavaScript module.
+///
+/// The module exports `tables`, a function returning the declarations of the tables it ingests,
+/// and a default async function that ingests by calling `Deno.core.ops.ingest(message)`.
+/// The default export is passed the `cursor` of the last checkpointed message, or `null`.
+/// Cursors must grow as the module ingests: numbers compare numerically, strings lexicographically,
+/// and arrays element by element. Other cursors can't be ordered, so sinks can't restart from different ones.
+///
+/// Modules without a `tables` export ingest into a single `json_records` table from their top-level code.
 #[derive(Debug)]
 pub struct JavaScriptConnector {
     runtime: Arc<Runtime>,
     config: JavaScriptConfig,
+    /// `None` until the module is loaded.
+    module_kind: Option<ModuleKind>,
+}
+
+#[derive(Debug, Clone)]
+enum ModuleKind {
+    Tables(Vec<TableDeclaration>),
+    Legacy,
 }
 
 #[async_trait]
 impl Connector for JavaScriptConnector {
-    // We will return one field, named "value", of type Json to
-    // give maximum flexibility to the user.
     fn types_mapping() -> Vec<(String, Option<FieldType>)>
     where
         Self: Sized,
     {
-        vec![(String::from("value"), Some(FieldType::Json))]
+        [
+            "uint",
+            "u128",
+            "int",
+            "i128",
+            "float",
+            "decimal",
+            "boolean",
+            "string",
+            "text",
+            "binary",
+            "timestamp",
+            "date",
+            "json",
+            "point",
+            "duration",
+        ]
+        .into_iter()
+        .map(|name| (name.to_string(), FieldType::try_from(name).ok()))
+        .collect()
     }
 
     async fn validate_connection(&mut self) -> Result<(), BoxedError> {
-        Ok(())
+        self.tables().await.map(|_| ())
     }
 
     async fn list_tables(&mut self) -> Result<Vec<TableIdentifier>, BoxedError> {
-        Ok(vec![TableIdentifier {
-            schema: None,
-            name: "json_records".to_string(),
-        }])
+        Ok(self
+            .tables()
+            .await?
+            .into_iter()
+            .map(|table| TableIdentifier::from_table_name(table.name))
+            .collect())
     }
 
-    async fn validate_tables(&mut self, _tables: &[TableIdentifier]) -> Result<(), BoxedError> {
+    async fn validate_tables(&mut self, tables: &[TableIdentifier]) -> Result<(), BoxedError> {
+        let declarations = self.tables().await?;
+        for table in tables {
+            if table.schema.is_some()
+                || !declarations
+                    .iter()
+                    .any(|declaration| declaration.name == table.name)
+            {
+                return Err(TableNotFound {
+                    schema: table.schema.clone(),
+                    name: table.name.clone(),
+                }
+                .into());
+            }
+        }
         Ok(())
     }
 
     async fn list_columns(
         &mut self,
-        _tables: Vec<TableIdentifier>,
+        tables: Vec<TableIdentifier>,
     ) -> Result<Vec<TableInfo>, BoxedError> {
-        Ok(vec![TableInfo {
-            schema: None,
-            name: "json_records".to_string(),
-            column_names: vec!["value".to_string()],
-        }])
+        let declarations = self.tables().await?;
+        tables
+            .into_iter()
+            .map(|table| {
+                let declaration = find_table(&declarations, &table.schema, &table.name)?;
+                Ok(TableInfo {
+                    schema: table.schema,
+                    name: table.name,
+                    column_names: declaration.column_names(),
+                })
+            })
+            .collect()
     }
 
     async fn get_schemas(
         &mut self,
-        _table_infos: &[TableInfo],
+        table_infos: &[TableInfo],
     ) -> Result<Vec<SourceSchemaResult>, BoxedError> {
-        Ok(vec![Ok(SourceSchema {
-            schema: Schema {
-                fields: vec![FieldDefinition {
-                    name: "value".to_string(),
-                    typ: FieldType::Json,
-                    nullable: false,
-                    source: SourceDefinition::Dynamic,
-                }],
-                primary_index: vec![],
-            },
-            cdc_type: CdcType::Nothing,
-        })])
+        let declarations = self.tables().await?;
+        Ok(table_infos
+            .iter()
+            .map(|table| {
+                find_table(&declarations, &table.schema, &table.name)?
+                    .schema(&table.column_names)
+                    .map_err(Into::into)
+            })
+            .collect())
     }
 
     async fn serialize_state(&self) -> Result<Vec<u8>, BoxedError> {
@@ -81,24 +139,173 @@ impl Connector for JavaScriptConnector {
     async fn start(
         &mut self,
         ingestor: &Ingestor,
-        _tables: Vec<TableInfo>,
-        _last_checkpoint: Option<OpIdentifier>,
+        tables: Vec<TableInfo>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
-        let js_path = self
-            .config
-            .bootstrap_path
-            .clone()
-            .unwrap_or_else(default_bootstrap_path);
-        let ingestor = ingestor.clone();
-        let ext = JsExtension::new(self.runtime.clone(), ingestor, js_path)?;
-        ext.run().await
+        let module_kind = self.module_kind().await?;
+        let declarations = self.tables().await?;
+        let ingested_tables = tables
+            .iter()
+            .map(|table| {
+                let declaration = find_table(&declarations, &table.schema, &table.name)?;
+                let format = match module_kind {
+                    ModuleKind::Tables(_) => {
+                        RecordFormat::Columns(declaration.fields(&table.column_names)?)
+                    }
+                    ModuleKind::Legacy => RecordFormat::Json,
+                };
+                Ok(IngestedTable {
+                    name: table.name.clone(),
+                    format,
+                })
+            })
+            .collect::<Result<Vec<_>, BoxedError>>()?;
+        let ingestor = JsIngestor::new(ingestor.clone(), ingested_tables);
+
+        match module_kind {
+            ModuleKind::Tables(_) => {
+                let cursor = match last_checkpoint {
+                    Some(position) => {
+                        serde_json_to_json_value(position.decode::<serde_json::Value>()?)?
+                    }
+                    None => JsonValue::NULL,
+                };
+                js_extension::run_module(ingestor, self.module_path(), cursor).await
+            }
+            ModuleKind::Legacy => {
+                let ext = JsExtension::new(self.runtime.clone(), ingestor, self.module_path())?;
+                ext.run().await
+            }
+        }
+    }
+
+    fn compare_positions(&self, a: &SourcePosition, b: &SourcePosition) -> Option<Ordering> {
+        let a = a.decode::<serde_json::Value>().ok()?;
+        let b = b.decode::<serde_json::Value>().ok()?;
+        compare_cursors(&a, &b)
     }
 }
 
 impl JavaScriptConnector {
     pub fn new(runtime: Arc<Runtime>, config: JavaScriptConfig) -> Self {
-        Self { runtime, config }
+        Self {
+            runtime,
+            config,
+            module_kind: None,
+        }
+    }
+
+    fn module_path(&self) -> String {
+        self.config
+            .bootstrap_path
+            .clone()
+            .unwrap_or_else(default_bootstrap_path)
+    }
+
+    async fn module_kind(&mut self) -> Result<ModuleKind, BoxedError> {
+        if let Some(kind) = &self.module_kind {
+            return Ok(kind.clone());
+        }
+
+        let kind = match load_tables(self.module_path(), js_extension::discarding_extension).await?
+        {
+            Some(tables) => ModuleKind::Tables(tables),
+            None => ModuleKind::Legacy,
+        };
+        self.module_kind = Some(kind.clone());
+        Ok(kind)
+    }
+
+    async fn tables(&mut self) -> Result<Vec<TableDeclaration>, BoxedError> {
+        Ok(match self.module_kind().await? {
+            ModuleKind::Tables(tables) => tables,
+            ModuleKind::Legacy => vec![TableDeclaration::legacy()],
+        })
+    }
+}
+
+fn find_table<'a>(
+    declarations: &'a [TableDeclaration],
+    schema: &Option<String>,
+    name: &str,
+) -> Result<&'a TableDeclaration, TableNotFound> {
+    declarations
+        .iter()
+        .find(|declaration| schema.is_none() && declaration.name == name)
+        .ok_or_else(|| TableNotFound {
+            schema: schema.clone(),
+            name: name.to_string(),
+        })
+}
+
+/// Orders cursors as documented on [`JavaScriptConnector`].
+fn compare_cursors(a: &serde_json::Value, b: &serde_json::Value) -> Option<Ordering> {
+    use serde_json::Value;
+
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => {
+            if let (Some(a), Some(b)) = (a.as_i64(), b.as_i64()) {
+                Some(a.cmp(&b))
+            } else if let (Some(a), Some(b)) = (a.as_u64(), b.as_u64()) {
+                Some(a.cmp(&b))
+            } else {
+                a.as_f64()?.partial_cmp(&b.as_f64()?)
+            }
+        }
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Array(a), Value::Array(b)) => {
+            for (a, b) in a.iter().zip(b) {
+                match compare_cursors(a, b)? {
+                    Ordering::Equal => continue,
+                    ordering => return Some(ordering),
+                }
+            }
+            Some(a.len().cmp(&b.len()))
+        }
+        (a, b) if a == b => Some(Ordering::Equal),
+        _ => None,
     }
 }
 
 mod js_extension;
+mod tables;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compare_cursors() {
+        assert_eq!(compare_cursors(&json!(2), &json!(10)), Some(Ordering::Less));
+        assert_eq!(
+            compare_cursors(&json!(-1), &json!(u64::MAX)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare_cursors(&json!(1.5), &json!(1)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare_cursors(&json!("b"), &json!("a")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare_cursors(&json!([1, "b"]), &json!([1, "c"])),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare_cursors(&json!([1]), &json!([1, 0])),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            compare_cursors(&json!({"page": 1}), &json!({"page": 1})),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            compare_cursors(&json!({"page": 1}), &json!({"page": 2})),
+            None
+        );
+        assert_eq!(compare_cursors(&json!(1), &json!("1")), None);
+    }
+}
@@ -0,0 +1,217 @@
+use dozer_deno::deno_runtime::deno_core::{error::AnyError, Extension};
+use dozer_ingestion_connector::{
+    dozer_types::{
+        json_types::json_value_to_serde_json,
+        serde::Deserialize,
+        serde_json,
+        thiserror::{self, Error},
+        types::{FieldDefinition, FieldType, Schema, SourceDefinition},
+    },
+    CdcType, SourceSchema,
+};
+
+/// A table declared by the `tables` export of the module.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(crate = "dozer_ingestion_connector::dozer_types::serde")]
+pub struct TableDeclaration {
+    pub name: String,
+    pub columns: Vec<ColumnDeclaration>,
+    #[serde(default)]
+    pub primary_key: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(crate = "dozer_ingestion_connector::dozer_types::serde")]
+pub struct ColumnDeclaration {
+    pub name: String,
+    /// A Dozer type name, e.g. `int`, `string` or `timestamp`.
+    #[serde(rename = "type")]
+    pub typ: String,
+    #[serde(default)]
+    pub nullable: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum TablesError {
+    #[error(transparent)]
+    Runtime(#[from] dozer_deno::RuntimeError),
+    #[error("failed to call `tables` export: {0}")]
+    Call(#[source] AnyError),
+    #[error("invalid table declarations: {0}")]
+    Invalid(#[source] serde_json::Error),
+    #[error("column {column} of table {table} has unsupported type: {message}")]
+    UnsupportedType {
+        table: String,
+        column: String,
+        message: String,
+    },
+    #[error("column {column} not found in table {table}")]
+    ColumnNotFound { table: String, column: String },
+}
+
+/// Name of the table exposed by modules without a `tables` export.
+pub const LEGACY_TABLE_NAME: &str = "json_records";
+
+impl TableDeclaration {
+    /// Modules without a `tables` export ingest into a single table, holding each value as JSON.
+    pub fn legacy() -> Self {
+        Self {
+            name: LEGACY_TABLE_NAME.to_string(),
+            columns: vec![ColumnDeclaration {
+                name: "value".to_string(),
+                typ: "json".to_string(),
+                nullable: false,
+            }],
+            primary_key: vec![],
+        }
+    }
+
+    pub fn column_names(&self) -> Vec<String> {
+        self.columns
+            .iter()
+            .map(|column| column.name.clone())
+            .collect()
+    }
+
+    /// Returns definitions of `column_names` in that order, or of all columns if it's empty.
+    pub fn fields(&self, column_names: &[String]) -> Result<Vec<FieldDefinition>, TablesError> {
+        let columns = if column_names.is_empty() {
+            self.columns.iter().collect::<Vec<_>>()
+        } else {
+            column_names
+                .iter()
+                .map(|name| self.column(name))
+                .collect::<Result<_, _>>()?
+        };
+
+        columns
+            .into_iter()
+            .map(|column| {
+                let typ = FieldType::try_from(column.typ.as_str()).map_err(|message| {
+                    TablesError::UnsupportedType {
+                        table: self.name.clone(),
+                        column: column.name.clone(),
+                        message,
+                    }
+                })?;
+                Ok(FieldDefinition {
+                    name: column.name.clone(),
+                    typ,
+                    nullable: column.nullable,
+                    source: SourceDefinition::Dynamic,
+                })
+            })
+            .collect()
+    }
+
+    pub fn schema(&self, column_names: &[String]) -> Result<SourceSchema, TablesError> {
+        let fields = self.fields(column_names)?;
+        let primary_index = self
+            .primary_key
+            .iter()
+            .map(|key| {
+                fields
+                    .iter()
+                    .position(|field| &field.name == key)
+                    .ok_or_else(|| TablesError::ColumnNotFound {
+                        table: self.name.clone(),
+                        column: key.clone(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Without a primary key, updates and deletes cannot be applied.
+        let cdc_type = if primary_index.is_empty() {
+            CdcType::Nothing
+        } else {
+            CdcType::FullChanges
+        };
+
+        Ok(SourceSchema {
+            schema: Schema {
+                fields,
+                primary_index,
+            },
+            cdc_type,
+        })
+    }
+
+    fn column(&self, name: &str) -> Result<&ColumnDeclaration, TablesError> {
+        self.columns
+            .iter()
+            .find(|column| column.name == name)
+            .ok_or_else(|| TablesError::ColumnNotFound {
+                table: self.name.clone(),
+                column: name.to_string(),
+            })
+    }
+}
+
+/// Loads the module and calls its `tables` export. Returns `None` if the module doesn't have one.
+///
+/// `extension` provides the ops the module may reference when it's evaluated.
+pub async fn load_tables(
+    module: String,
+    extension: impl FnOnce() -> Extension + Send + 'static,
+) -> Result<Option<Vec<TableDeclaration>>, TablesError> {
+    let (mut runtime, functions) = match dozer_deno::Runtime::with_exports(
+        vec![(module, "tables".to_string())],
+        vec![extension],
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(dozer_deno::RuntimeError::ModuleNoExport(_, _)) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let tables = runtime
+        .call_function(functions[0], vec![])
+        .await
+        .map_err(TablesError::Call)?;
+    serde_json::from_value(json_value_to_serde_json(&tables))
+        .map(Some)
+        .map_err(TablesError::Invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_ingestion_connector::dozer_types::serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_schema() {
+        let declaration: TableDeclaration = serde_json::from_value(json!({
+            "name": "users",
+            "columns": [
+                { "name": "id", "type": "int" },
+                { "name": "name", "type": "string", "nullable": true },
+                { "name": "created_at", "type": "timestamp" },
+            ],
+            "primary_key": ["id"],
+        }))
+        .unwrap();
+
+        let schema = declaration.schema(&[]).unwrap();
+        assert_eq!(schema.cdc_type, CdcType::FullChanges);
+        assert_eq!(schema.schema.primary_index, vec![0]);
+        assert_eq!(schema.schema.fields[1].typ, FieldType::String);
+        assert!(schema.schema.fields[1].nullable);
+
+        // Selected columns are reordered, and the primary key follows them.
+        let schema = declaration
+            .schema(&["name".to_string(), "id".to_string()])
+            .unwrap();
+        assert_eq!(schema.schema.primary_index, vec![1]);
+
+        // The primary key column must be selected.
+        assert!(matches!(
+            declaration.schema(&["name".to_string()]),
+            Err(TablesError::ColumnNotFound { .. })
+        ));
+
+        assert_eq!(
+            TableDeclaration::legacy().schema(&[]).unwrap().cdc_type,
+            CdcType::Nothing
+        );
+    }
+}
@@ -1,7 +1,9 @@
 use dozer_ingestion_connector::async_trait;
 use dozer_ingestion_connector::dozer_types::errors::internal::BoxedError;
-use dozer_ingestion_connector::dozer_types::models::ingestion_types::KafkaConfig;
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
+use dozer_ingestion_connector::dozer_types::models::ingestion_types::{
+    IngestionMessage, KafkaConfig, TransactionInfo,
+};
+use dozer_ingestion_connector::dozer_types::node::SourcePosition;
 use dozer_ingestion_connector::dozer_types::types::FieldType;
 use dozer_ingestion_connector::Connector;
 use dozer_ingestion_connector::Ingestor;
@@ -135,8 +137,18 @@ impl Connector for KafkaConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
+        // Topics are consumed from their committed offsets, there's no snapshot.
+        if ingestor
+            .handle_message(IngestionMessage::TransactionInfo(
+                TransactionInfo::SnapshottingSkipped,
+            ))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
         let broker = self.config.broker.to_owned();
         run(
             broker,
@@ -144,6 +156,7 @@ impl Connector for KafkaConnector {
             last_checkpoint,
             ingestor,
             &self.config.schema_registry_url,
+            self.config.upsert,
         )
         .await
         .map_err(Into::into)
@@ -153,9 +166,10 @@ impl Connector for KafkaConnector {
 async fn run(
     broker: String,
     tables: Vec<TableInfo>,
-    last_checkpoint: Option<OpIdentifier>,
+    last_checkpoint: Option<SourcePosition>,
     ingestor: &Ingestor,
     schema_registry_url: &Option<String>,
+    upsert: bool,
 ) -> Result<(), KafkaError> {
     let mut client_config = ClientConfig::new();
     client_config
@@ -163,7 +177,7 @@ async fn run(
         .set("group.id", "dozer")
         .set("enable.auto.commit", "true");
 
-    let consumer = StreamConsumerBasic::default();
+    let consumer = StreamConsumerBasic::new(upsert);
     consumer
         .run(
             client_config,
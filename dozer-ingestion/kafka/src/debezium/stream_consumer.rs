@@ -4,7 +4,7 @@ use crate::stream_consumer::StreamConsumer;
 use crate::stream_consumer_helper::{is_network_failure, OffsetsMap, StreamConsumerHelper};
 use crate::{KafkaError, KafkaStreamError};
 
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
+use dozer_ingestion_connector::dozer_types::node::SourcePosition;
 use dozer_ingestion_connector::TableInfo;
 use dozer_ingestion_connector::{
     async_trait,
@@ -91,7 +91,7 @@ impl StreamConsumer for DebeziumStreamConsumer {
         client_config: ClientConfig,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
         _schema_registry_url: &Option<String>,
     ) -> Result<(), KafkaError> {
         assert!(last_checkpoint.is_none());
@@ -110,6 +110,7 @@ impl StreamConsumer for DebeziumStreamConsumer {
                 ))?,
             };
             StreamConsumerHelper::update_offsets(&mut offsets, &m);
+            let source_time = StreamConsumerHelper::message_time(&m);
 
             if let (Some(message), Some(key)) = (m.payload(), m.key()) {
                 let mut value_struct: DebeziumMessage = serde_json::from_str(
@@ -153,6 +154,7 @@ impl StreamConsumer for DebeziumStreamConsumer {
                                     },
                                 },
                                 id: None,
+                                source_time,
                             })
                             .await
                             .is_err()
@@ -175,6 +177,7 @@ impl StreamConsumer for DebeziumStreamConsumer {
                                     },
                                 },
                                 id: None,
+                                source_time,
                             })
                             .await
                             .is_err()
@@ -197,6 +200,7 @@ impl StreamConsumer for DebeziumStreamConsumer {
                                     },
                                 },
                                 id: None,
+                                source_time,
                             })
                             .await
                             .is_err()
@@ -16,6 +16,7 @@ pub mod stream_consumer_basic;
 mod stream_consumer_helper;
 #[cfg(any(test, feature = "debezium_bench"))]
 pub mod test_utils;
+mod upsert;
 
 #[derive(Error, Debug)]
 pub enum KafkaError {
@@ -1,7 +1,7 @@
 use crate::KafkaError;
 
 use dozer_ingestion_connector::{
-    async_trait, dozer_types::node::OpIdentifier, Ingestor, TableInfo,
+    async_trait, dozer_types::node::SourcePosition, Ingestor, TableInfo,
 };
 use rdkafka::ClientConfig;
 
@@ -12,7 +12,7 @@ pub trait StreamConsumer {
         client_config: ClientConfig,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
         schema_registry_url: &Option<String>,
     ) -> Result<(), KafkaError>;
 }
@@ -1,20 +1,27 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use dozer_ingestion_connector::{
     async_trait,
     dozer_types::{
         models::ingestion_types::IngestionMessage,
-        node::OpIdentifier,
+        node::SourcePosition,
         serde::{Deserialize, Serialize},
         serde_json::{self, Value},
         types::{Field, Operation, Record},
     },
-    Ingestor, TableInfo,
+    tokio, Ingestor, SourceSchema, TableInfo,
+};
+use rdkafka::{
+    consumer::{BaseConsumer, Consumer},
+    message::BorrowedMessage,
+    ClientConfig, Message, Offset, TopicPartitionList,
 };
-use rdkafka::{ClientConfig, Message};
 
+use crate::debezium::stream_consumer::DebeziumSchemaStruct;
 use crate::schema_registry_basic::SchemaRegistryBasic;
 use crate::stream_consumer::StreamConsumer;
+use crate::upsert::UpsertState;
 use crate::{debezium::mapper::convert_value_to_schema, KafkaError};
 use crate::{no_schema_registry_basic::NoSchemaRegistryBasic, KafkaStreamError};
 
@@ -74,7 +81,15 @@ pub struct Payload {
 }
 
 #[derive(Default)]
-pub struct StreamConsumerBasic {}
+pub struct StreamConsumerBasic {
+    upsert: bool,
+}
+
+impl StreamConsumerBasic {
+    pub fn new(upsert: bool) -> Self {
+        Self { upsert }
+    }
+}
 
 #[async_trait]
 impl StreamConsumer for StreamConsumerBasic {
@@ -83,13 +98,13 @@ impl StreamConsumer for StreamConsumerBasic {
         client_config: ClientConfig,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
         schema_registry_url: &Option<String>,
     ) -> Result<(), KafkaError> {
         assert!(last_checkpoint.is_none());
         let topics: Vec<String> = tables.iter().map(|t| t.name.clone()).collect();
 
-        let mut schemas = HashMap::new();
+        let mut schemas: Schemas = HashMap::new();
         for (table_index, table) in tables.into_iter().enumerate() {
             let schema = if let Some(url) = schema_registry_url {
                 SchemaRegistryBasic::get_single_schema(&table.name, url).await?
@@ -100,6 +115,20 @@ impl StreamConsumer for StreamConsumerBasic {
             schemas.insert(table.name.clone(), (table_index, schema));
         }
 
+        let (mut upsert_state, schemas) = if self.upsert {
+            let client_config = client_config.clone();
+            let topics = topics.clone();
+            let schema_registry_url = schema_registry_url.clone();
+            // The replay polls synchronously.
+            let replay = tokio::task::spawn_blocking(move || {
+                let state =
+                    rebuild_upsert_state(&client_config, &topics, &schemas, &schema_registry_url)?;
+                Ok::<_, KafkaError>((state, schemas))
+            });
+            replay.await.unwrap()? // Propagate panics.
+        } else {
+            (UpsertState::default(), schemas)
+        };
         let topics: Vec<&str> = topics.iter().map(|t| t.as_str()).collect();
         let mut con = StreamConsumerHelper::start(&client_config, &topics).await?;
 
@@ -113,60 +142,38 @@ impl StreamConsumer for StreamConsumerBasic {
                 let m = result
                     .map_err(|e| KafkaError::KafkaStreamError(KafkaStreamError::PollingError(e)))?;
                 StreamConsumerHelper::update_offsets(&mut offsets, &m);
+                let source_time = StreamConsumerHelper::message_time(&m);
                 match schemas.get(m.topic()) {
                     None => return Err(KafkaError::TopicNotDefined),
                     Some((table_index, (schema, fields_map))) => {
-                        if let (Some(message), Some(key)) = (m.payload(), m.key()) {
-                            let new = match schema_registry_url {
-                                None => {
-                                    let value = std::str::from_utf8(message)
-                                        .map_err(KafkaError::BytesConvertError)?;
-                                    let key = std::str::from_utf8(key)
-                                        .map_err(KafkaError::BytesConvertError)?;
-
-                                    vec![
-                                        Field::String(key.to_string()),
-                                        Field::String(value.to_string()),
-                                    ]
-                                }
-                                Some(_) => {
-                                    let value_struct: Value = serde_json::from_str(
-                                        std::str::from_utf8(message)
-                                            .map_err(KafkaError::BytesConvertError)?,
-                                    )
-                                    .map_err(KafkaError::JsonDecodeError)?;
-                                    let _key_struct: Value = serde_json::from_str(
-                                        std::str::from_utf8(key)
-                                            .map_err(KafkaError::BytesConvertError)?,
-                                    )
-                                    .map_err(KafkaError::JsonDecodeError)?;
-
-                                    convert_value_to_schema(
-                                        value_struct,
-                                        &schema.schema,
-                                        fields_map,
-                                    )
-                                    .map_err(KafkaError::KafkaSchemaError)?
-                                }
-                            };
-
-                            if ingestor
-                                .handle_message(IngestionMessage::OperationEvent {
-                                    table_index: *table_index,
-                                    op: Operation::Insert {
-                                        new: Record {
-                                            values: new,
-                                            lifetime: None,
-                                        },
-                                    },
-                                    id: None,
-                                })
-                                .await
-                                .is_err()
-                            {
-                                // If receiving side is closed, we should stop the stream
-                                return Ok(());
-                            }
+                        let Some(key) = m.key() else {
+                            continue;
+                        };
+                        let new = message_values(&m, key, schema_registry_url, schema, fields_map)?;
+
+                        let op = if self.upsert {
+                            upsert_state.apply(*table_index, key, new)
+                        } else {
+                            new.map(|new| Operation::Insert {
+                                new: Record::new(new),
+                            })
+                        };
+                        let Some(op) = op else {
+                            continue;
+                        };
+
+                        if ingestor
+                            .handle_message(IngestionMessage::OperationEvent {
+                                table_index: *table_index,
+                                op,
+                                id: None,
+                                source_time,
+                            })
+                            .await
+                            .is_err()
+                        {
+                            // If receiving side is closed, we should stop the stream
+                            return Ok(());
                         }
                     }
                 }
@@ -174,3 +181,131 @@ impl StreamConsumer for StreamConsumerBasic {
         }
     }
 }
+
+/// key: topic, value: (table index, (schema, schema registry fields))
+type Schemas = HashMap<String, (usize, (SourceSchema, HashMap<String, DebeziumSchemaStruct>))>;
+
+/// Decodes a message's values, `None` for tombstones.
+fn message_values(
+    m: &BorrowedMessage<'_>,
+    key: &[u8],
+    schema_registry_url: &Option<String>,
+    schema: &SourceSchema,
+    fields_map: &HashMap<String, DebeziumSchemaStruct>,
+) -> Result<Option<Vec<Field>>, KafkaError> {
+    let Some(message) = m.payload() else {
+        return Ok(None);
+    };
+    let values = match schema_registry_url {
+        None => {
+            let value = std::str::from_utf8(message).map_err(KafkaError::BytesConvertError)?;
+            let key = std::str::from_utf8(key).map_err(KafkaError::BytesConvertError)?;
+
+            vec![
+                Field::String(key.to_string()),
+                Field::String(value.to_string()),
+            ]
+        }
+        Some(_) => {
+            let value_struct: Value = serde_json::from_str(
+                std::str::from_utf8(message).map_err(KafkaError::BytesConvertError)?,
+            )
+            .map_err(KafkaError::JsonDecodeError)?;
+            let _key_struct: Value = serde_json::from_str(
+                std::str::from_utf8(key).map_err(KafkaError::BytesConvertError)?,
+            )
+            .map_err(KafkaError::JsonDecodeError)?;
+
+            convert_value_to_schema(value_struct, &schema.schema, fields_map)
+                .map_err(KafkaError::KafkaSchemaError)?
+        }
+    };
+    Ok(Some(values))
+}
+
+/// Replays the topics up to the offsets the consumer group resumes from, without emitting anything.
+///
+/// Keys delivered by a previous run are then known again, so their next messages become updates
+/// and deletes instead of duplicate inserts. Nothing is committed while replaying.
+fn rebuild_upsert_state(
+    client_config: &ClientConfig,
+    topics: &[String],
+    schemas: &Schemas,
+    schema_registry_url: &Option<String>,
+) -> Result<UpsertState, KafkaError> {
+    let mut state = UpsertState::default();
+    let topic_names: Vec<&str> = topics.iter().map(|t| t.as_str()).collect();
+    let committed = StreamConsumerHelper::committed_offsets(client_config, &topic_names)?;
+    // Partition EOF errors only name the partition, so topics are replayed one at a time.
+    for topic in topics {
+        let pending = committed
+            .iter()
+            .filter(|((committed_topic, _), _)| committed_topic == topic)
+            .map(|((_, partition), end)| (*partition, *end))
+            .collect();
+        replay_topic(
+            client_config,
+            topic,
+            pending,
+            schemas,
+            schema_registry_url,
+            &mut state,
+        )?;
+    }
+    Ok(state)
+}
+
+/// Replays `topic` up to the offsets in `pending`, by partition.
+fn replay_topic(
+    client_config: &ClientConfig,
+    topic: &str,
+    mut pending: HashMap<i32, i64>,
+    schemas: &Schemas,
+    schema_registry_url: &Option<String>,
+    state: &mut UpsertState,
+) -> Result<(), KafkaError> {
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let mut partitions = TopicPartitionList::new();
+    for partition in pending.keys() {
+        partitions.add_partition_offset(topic, *partition, Offset::Beginning)?;
+    }
+    let con: BaseConsumer = client_config
+        .clone()
+        .set("enable.auto.commit", "false")
+        .set("enable.partition.eof", "true")
+        .create()?;
+    con.assign(&partitions)?;
+
+    const POLL_TIMEOUT: Duration = Duration::from_secs(1);
+    while !pending.is_empty() {
+        let m = match con.poll(POLL_TIMEOUT) {
+            None => continue,
+            // Transaction markers and compaction can leave no record at or past the last offset to replay.
+            Some(Err(rdkafka::error::KafkaError::PartitionEOF(partition))) => {
+                pending.remove(&partition);
+                continue;
+            }
+            Some(result) => result
+                .map_err(|e| KafkaError::KafkaStreamError(KafkaStreamError::PollingError(e)))?,
+        };
+        let Some(&end) = pending.get(&m.partition()) else {
+            continue;
+        };
+        // Compaction may have removed the record just before `end`, so anything past it also ends the replay.
+        if m.offset() < end {
+            if let (Some((table_index, (schema, fields_map))), Some(key)) =
+                (schemas.get(m.topic()), m.key())
+            {
+                let new = message_values(&m, key, schema_registry_url, schema, fields_map)?;
+                state.apply(*table_index, key, new);
+            }
+        }
+        if m.offset() + 1 >= end {
+            pending.remove(&m.partition());
+        }
+    }
+    Ok(())
+}
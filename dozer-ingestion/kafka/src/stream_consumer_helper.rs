@@ -3,9 +3,10 @@ use rdkafka::{
     consumer::{BaseConsumer, Consumer},
     message::BorrowedMessage,
     util::Timeout,
-    ClientConfig, Message, Offset,
+    ClientConfig, Message, Offset, TopicPartitionList,
 };
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 
 use crate::KafkaError;
 
@@ -36,6 +37,49 @@ impl StreamConsumerHelper {
         );
     }
 
+    /// The offsets the consumer group resumes from, keyed by topic and partition.
+    ///
+    /// Partitions without a committed offset, or with nothing left to read before it, are left out.
+    pub fn committed_offsets(
+        client_config: &ClientConfig,
+        topics: &[&str],
+    ) -> Result<HashMap<(String, i32), i64>, KafkaError> {
+        let con: BaseConsumer = client_config.create()?;
+        let timeout = Timeout::After(Duration::from_secs(60));
+
+        let mut partitions = TopicPartitionList::new();
+        for topic in topics {
+            let metadata = con.fetch_metadata(Some(*topic), timeout)?;
+            for partition in metadata
+                .topics()
+                .iter()
+                .flat_map(|topic| topic.partitions())
+            {
+                partitions.add_partition(topic, partition.id());
+            }
+        }
+
+        let mut offsets = HashMap::new();
+        for element in con.committed_offsets(partitions, timeout)?.elements() {
+            let Offset::Offset(committed) = element.offset() else {
+                continue;
+            };
+            let (low, high) =
+                con.fetch_watermarks(element.topic(), element.partition(), timeout)?;
+            let end = committed.min(high);
+            if end > low {
+                offsets.insert((element.topic().to_string(), element.partition()), end);
+            }
+        }
+        Ok(offsets)
+    }
+
+    /// The message's create or log append time, whichever the topic is configured with.
+    pub fn message_time(message: &BorrowedMessage<'_>) -> Option<SystemTime> {
+        let millis = message.timestamp().to_millis()?;
+        Some(SystemTime::UNIX_EPOCH + Duration::from_millis(u64::try_from(millis).ok()?))
+    }
+
     async fn resume_impl(
         client_config: &ClientConfig,
         topics: &[&str],
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use dozer_ingestion_connector::dozer_types::types::{Field, Operation, Record};
+
+/// Keeps the latest value of every message key, so a compacted topic can be turned into a changelog.
+///
+/// Holds one entry per live key, the same rows the compacted topic retains; tombstones free theirs.
+/// It is not checkpointed but rebuilt on start by replaying the topics up to the committed offsets.
+#[derive(Debug, Default)]
+pub struct UpsertState {
+    /// key: (table index, message key), value: latest record values
+    records: HashMap<(usize, Vec<u8>), Vec<Field>>,
+}
+
+impl UpsertState {
+    /// Maps a Kafka message to an `Operation`.
+    ///
+    /// `new` is `None` for tombstones. A tombstone for an unknown key produces no operation.
+    pub fn apply(
+        &mut self,
+        table_index: usize,
+        key: &[u8],
+        new: Option<Vec<Field>>,
+    ) -> Option<Operation> {
+        let state_key = (table_index, key.to_vec());
+        match new {
+            Some(new) => match self.records.insert(state_key, new.clone()) {
+                Some(old) => Some(Operation::Update {
+                    old: Record::new(old),
+                    new: Record::new(new),
+                }),
+                None => Some(Operation::Insert {
+                    new: Record::new(new),
+                }),
+            },
+            None => self
+                .records
+                .remove(&state_key)
+                .map(|old| Operation::Delete {
+                    old: Record::new(old),
+                }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(key: &str, value: &str) -> Vec<Field> {
+        vec![
+            Field::String(key.to_string()),
+            Field::String(value.to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_upsert_state() {
+        let mut state = UpsertState::default();
+
+        assert_eq!(
+            state.apply(0, b"a", Some(values("a", "1"))),
+            Some(Operation::Insert {
+                new: Record::new(values("a", "1"))
+            })
+        );
+        assert_eq!(
+            state.apply(0, b"a", Some(values("a", "2"))),
+            Some(Operation::Update {
+                old: Record::new(values("a", "1")),
+                new: Record::new(values("a", "2"))
+            })
+        );
+        // Same key on another topic is a different row.
+        assert_eq!(
+            state.apply(1, b"a", Some(values("a", "3"))),
+            Some(Operation::Insert {
+                new: Record::new(values("a", "3"))
+            })
+        );
+        assert_eq!(
+            state.apply(0, b"a", None),
+            Some(Operation::Delete {
+                old: Record::new(values("a", "2"))
+            })
+        );
+        assert_eq!(state.apply(0, b"a", None), None);
+        assert_eq!(state.apply(0, b"b", None), None);
+    }
+}
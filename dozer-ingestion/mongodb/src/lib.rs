@@ -8,7 +8,7 @@ use dozer_ingestion_connector::{
         errors::{internal::BoxedError, types::DeserializationError},
         json_types::{serde_json_to_json_value, JsonValue},
         models::ingestion_types::{IngestionMessage, TransactionInfo},
-        node::OpIdentifier,
+        node::SourcePosition,
         thiserror::{self, Error},
         types::{Field, FieldDefinition, FieldType, Operation, Record, SourceDefinition},
     },
@@ -597,7 +597,7 @@ impl Connector for MongodbConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         // Snapshot: find
         //
@@ -634,6 +634,7 @@ impl Connector for MongodbConnector {
                         table_index,
                         op,
                         id: None,
+                        source_time: None,
                     })
                     .await
                     .is_err()
@@ -683,6 +684,7 @@ impl Connector for MongodbConnector {
                         table_index,
                         op,
                         id: None,
+                        source_time: None,
                     })
                     .await
                     .is_err()
@@ -36,6 +36,7 @@ use mysql_common::{
 use std::{
     collections::{HashMap, HashSet},
     ops::Deref,
+    time::{Duration, SystemTime},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -403,7 +404,7 @@ impl BinlogIngestor<'_, '_, '_> {
                                                                     Err(BreakingSchemaChange::ColumnDataTypeChanged{
                                                                         table_name: table.to_string(),
                                                                         column_name: column.to_string(),
-                                                                        old_data_type: column.typ,
+                                                                        old_data_type: column.typ.clone(),
                                                                         new_column_name: new_type,
                                                                     })?
                                                                 }
@@ -486,7 +487,10 @@ impl BinlogIngestor<'_, '_, '_> {
 
                         let table = table_cache.get_table_details(table_index).unwrap();
 
-                        self.handle_rows_event(&rows_event, &table, tme).await?;
+                        let event_time = SystemTime::UNIX_EPOCH
+                            + Duration::from_secs(binlog_event.header().timestamp().into());
+                        self.handle_rows_event(&rows_event, &table, tme, event_time)
+                            .await?;
                     }
                 }
 
@@ -504,6 +508,7 @@ impl BinlogIngestor<'_, '_, '_> {
         rows_event: &BinlogRowsEvent<'_>,
         table: &TableDetails<'_>,
         tme: &TableMapEvent<'a>,
+        event_time: SystemTime,
     ) -> Result<(), MySQLConnectorError> {
         for op in self.make_rows_operations(rows_event, table, tme) {
             if self
@@ -512,6 +517,7 @@ impl BinlogIngestor<'_, '_, '_> {
                     table_index: table.def.table_index,
                     op: op?,
                     id: Some(encode_state(&self.next_position)),
+                    source_time: Some(event_time),
                 })
                 .await
                 .is_err()
@@ -15,7 +15,7 @@ use dozer_ingestion_connector::{
         log::info,
         models::ingestion_types::IngestionMessage,
         models::ingestion_types::TransactionInfo,
-        node::OpIdentifier,
+        node::SourcePosition,
         types::{FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition},
     },
     utils::TableNotFound,
@@ -199,12 +199,23 @@ impl Connector for MySQLConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         self.replicate(ingestor, tables, last_checkpoint)
             .await
             .map_err(Into::into)
     }
+
+    async fn sample(
+        &mut self,
+        tables: Vec<TableInfo>,
+        limit: usize,
+    ) -> Result<Option<Vec<Vec<Record>>>, BoxedError> {
+        self.sample_tables(&tables, limit)
+            .await
+            .map(Some)
+            .map_err(Into::into)
+    }
 }
 
 impl MySQLConnector {
@@ -218,11 +229,53 @@ impl MySQLConnector {
         SchemaHelper::new(&self.conn_url, &self.conn_pool)
     }
 
+    /// Reads up to `limit` rows of each table without locking it, unlike the snapshot.
+    async fn sample_tables(
+        &self,
+        table_infos: &[TableInfo],
+        limit: usize,
+    ) -> Result<Vec<Vec<Record>>, MySQLConnectorError> {
+        let table_definitions = self
+            .schema_helper()
+            .get_table_definitions(table_infos)
+            .await?;
+        let mut conn = self.connect().await?;
+        let mut samples = vec![];
+        for td in &table_definitions {
+            let mut rows = conn.exec_iter(
+                format!(
+                    "SELECT {} from {} LIMIT {limit}",
+                    td.columns
+                        .iter()
+                        .map(|ColumnDefinition { name, .. }| escape_identifier(name))
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    qualify_table_name(Some(&td.database_name), &td.table_name)
+                ),
+                vec![],
+            );
+
+            let field_types: Vec<FieldType> = td
+                .columns
+                .iter()
+                .map(|ColumnDefinition { typ, .. }| typ.clone())
+                .collect();
+
+            let mut records = vec![];
+            while let Some(result) = rows.next().await {
+                let row = result.map_err(MySQLConnectorError::QueryResultError)?;
+                records.push(Record::new(row.into_fields(&field_types)?));
+            }
+            samples.push(records);
+        }
+        Ok(samples)
+    }
+
     async fn replicate(
         &self,
         ingestor: &Ingestor,
         table_infos: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), MySQLConnectorError> {
         let mut table_definitions = self
             .schema_helper()
@@ -240,6 +293,7 @@ impl MySQLConnector {
             .await?;
 
         let binlog_position = last_checkpoint
+            .as_ref()
             .map(crate::binlog::BinlogPosition::try_from)
             .transpose()?;
 
@@ -304,6 +358,18 @@ impl MySQLConnector {
 
         let mut conn = self.connect().await?;
 
+        // Replication resumes from the checkpoint, there's no snapshot.
+        if binlog_position.is_some()
+            && ingestor
+                .handle_message(IngestionMessage::TransactionInfo(
+                    TransactionInfo::SnapshottingSkipped,
+                ))
+                .await
+                .is_err()
+        {
+            return Err(MySQLConnectorError::SnapshotIngestionMessageError);
+        }
+
         let mut snapshot_started = false;
         for (table_index, td) in table_definitions.iter().enumerate() {
             let position = match &binlog_position {
@@ -353,6 +419,20 @@ impl MySQLConnector {
                         continue;
                     }
 
+                    if ingestor
+                        .handle_message(IngestionMessage::TransactionInfo(
+                            TransactionInfo::SnapshotSize {
+                                table_index,
+                                rows: row_count,
+                            },
+                        ))
+                        .await
+                        .is_err()
+                    {
+                        // If receiving end is closed, we should stop the replication
+                        break;
+                    }
+
                     let mut rows = conn.exec_iter(
                         format!(
                             "SELECT {} from {}",
@@ -369,7 +449,7 @@ impl MySQLConnector {
                     let field_types: Vec<FieldType> = td
                         .columns
                         .iter()
-                        .map(|ColumnDefinition { typ, .. }| *typ)
+                        .map(|ColumnDefinition { typ, .. }| typ.clone())
                         .collect();
 
                     while let Some(result) = rows.next().await {
@@ -383,6 +463,7 @@ impl MySQLConnector {
                                 table_index,
                                 op,
                                 id: None,
+                                source_time: None,
                             })
                             .await
                             .is_err()
@@ -533,11 +614,14 @@ mod tests {
         )
         .await;
 
-        // We are not checking state.
+        // We are not checking state or event times.
         for actual in actual_ingestion_messages.iter_mut() {
             match actual {
-                IngestionMessage::OperationEvent { id, .. } => {
+                IngestionMessage::OperationEvent {
+                    id, source_time, ..
+                } => {
                     *id = None;
+                    *source_time = None;
                 }
                 IngestionMessage::TransactionInfo(TransactionInfo::Commit { id }) => {
                     *id = None;
@@ -611,6 +695,10 @@ mod tests {
 
         let expected_ingestion_messages = vec![
             IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingStarted),
+            IngestionMessage::TransactionInfo(TransactionInfo::SnapshotSize {
+                table_index: 0,
+                rows: 3,
+            }),
             IngestionMessage::OperationEvent {
                 table_index: 0,
                 op: Insert {
@@ -621,6 +709,7 @@ mod tests {
                     ]),
                 },
                 id: None,
+                source_time: None,
             },
             IngestionMessage::OperationEvent {
                 table_index: 0,
@@ -632,6 +721,7 @@ mod tests {
                     ]),
                 },
                 id: None,
+                source_time: None,
             },
             IngestionMessage::OperationEvent {
                 table_index: 0,
@@ -643,6 +733,7 @@ mod tests {
                     ]),
                 },
                 id: None,
+                source_time: None,
             },
             IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingDone { id: None }),
         ];
@@ -709,6 +800,7 @@ mod tests {
                     new: Record::new(vec![Field::Int(4), Field::Float(4.0.into())]),
                 },
                 id: None,
+                source_time: None,
             },
             IngestionMessage::OperationEvent {
                 table_index: 1,
@@ -716,6 +808,7 @@ mod tests {
                     new: Record::new(vec![Field::Int(1), Field::Json(true.into())]),
                 },
                 id: None,
+                source_time: None,
             },
             IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingDone { id: None }),
         ];
@@ -735,6 +828,7 @@ mod tests {
                     new: Record::new(vec![Field::Int(4), Field::Float(5.0.into())]),
                 },
                 id: None,
+                source_time: None,
             },
             IngestionMessage::TransactionInfo(TransactionInfo::Commit { id: None }),
         ];
@@ -753,6 +847,7 @@ mod tests {
                     old: Record::new(vec![Field::Int(4), Field::Float(5.0.into())]),
                 },
                 id: None,
+                source_time: None,
             },
             IngestionMessage::TransactionInfo(TransactionInfo::Commit { id: None }),
         ];
@@ -1,5 +1,6 @@
 use dozer_ingestion_connector::dozer_types::{
     chrono::{DateTime, NaiveDate, NaiveDateTime, Offset, Utc},
+    helper::json_value_to_nested_field,
     json_types::{serde_json_to_json_value, JsonValue},
     rust_decimal::Decimal,
     serde_json,
@@ -222,6 +223,9 @@ impl<'a> IntoField<'a> for Value {
                     from_value_opt::<Duration>(value)?,
                     TimeUnit::Microseconds,
                 )),
+                FieldType::List(_) | FieldType::Struct(_) => {
+                    json_value_to_nested_field(from_value_opt::<serde_json::Value>(value)?)
+                }
             }
         };
 
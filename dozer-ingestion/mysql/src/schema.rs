@@ -331,8 +331,8 @@ impl SchemaHelper<'_> {
                     Err(BreakingSchemaChange::ColumnDataTypeChanged {
                         table_name: old.to_string(),
                         column_name: old_column.to_string(),
-                        old_data_type: old_column.typ,
-                        new_column_name: new_column.typ,
+                        old_data_type: old_column.typ.clone(),
+                        new_column_name: new_column.typ.clone(),
                     })?
                 }
             }
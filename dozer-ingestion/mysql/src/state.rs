@@ -1,21 +1,23 @@
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
+use dozer_ingestion_connector::dozer_types::node::{OpIdentifier, SourcePosition};
 
 use crate::binlog::BinlogPosition;
 use crate::MysqlStateError;
 
-pub fn encode_state(pos: &BinlogPosition) -> OpIdentifier {
+pub fn encode_state(pos: &BinlogPosition) -> SourcePosition {
     let lsn = (pos.binlog_id << 32) | pos.position;
 
     OpIdentifier {
         txid: lsn,
         seq_in_tx: 0,
     }
+    .into()
 }
 
-impl TryFrom<OpIdentifier> for BinlogPosition {
+impl TryFrom<&SourcePosition> for BinlogPosition {
     type Error = MysqlStateError;
 
-    fn try_from(state: OpIdentifier) -> Result<Self, Self::Error> {
+    fn try_from(state: &SourcePosition) -> Result<Self, Self::Error> {
+        let state = OpIdentifier::from_bytes(state.as_bytes().try_into()?);
         let binlog_id = state.txid >> 32;
         let position = state.txid & 0x00000000ffffffff;
 
@@ -37,7 +39,7 @@ mod tests {
         };
 
         let state = encode_state(&pos);
-        let pos2 = BinlogPosition::try_from(state).unwrap();
+        let pos2 = BinlogPosition::try_from(&state).unwrap();
 
         assert_eq!(pos, pos2);
     }
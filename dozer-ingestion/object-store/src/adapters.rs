@@ -1,12 +1,15 @@
 use dozer_ingestion_connector::dozer_types::models::ingestion_types::{
-    LocalStorage, S3Storage, Table, TableConfig,
+    AzureStorage, GcsStorage, LocalStorage, S3Storage, Table,
 };
 use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::azure::{MicrosoftAzure, MicrosoftAzureBuilder};
+use object_store::gcp::{GoogleCloudStorage, GoogleCloudStorageBuilder};
 use object_store::local::LocalFileSystem;
 use object_store::{BackoffConfig, ObjectStore, RetryConfig};
 use std::fmt::Debug;
 use url::Url;
 
+use crate::table::TableConfig;
 use crate::{ObjectStoreConnectorError, ObjectStoreObjectError};
 
 pub trait DozerObjectStore: Clone + Send + Sync + Debug + 'static {
@@ -49,6 +52,14 @@ pub struct DozerObjectStoreParams<T: ObjectStore> {
     pub aws_secret_access_key: Option<String>,
 }
 
+fn retry_config() -> RetryConfig {
+    RetryConfig {
+        backoff: BackoffConfig::default(),
+        max_retries: usize::max_value(),
+        retry_timeout: std::time::Duration::from_secs(u64::MAX),
+    }
+}
+
 impl DozerObjectStore for S3Storage {
     type ObjectStore = AmazonS3;
 
@@ -58,24 +69,29 @@ impl DozerObjectStore for S3Storage {
     ) -> Result<DozerObjectStoreParams<Self::ObjectStore>, ObjectStoreConnectorError> {
         let details = &self.details;
 
-        let retry_config = RetryConfig {
-            backoff: BackoffConfig::default(),
-            max_retries: usize::max_value(),
-            retry_timeout: std::time::Duration::from_secs(u64::MAX),
-        };
-
-        let object_store = AmazonS3Builder::new()
+        // Credentials that are not configured are taken from the environment.
+        let mut builder = AmazonS3Builder::from_env()
             .with_bucket_name(&details.bucket_name)
             .with_region(&details.region)
-            .with_access_key_id(&details.access_key_id)
-            .with_secret_access_key(&details.secret_access_key)
-            .with_retry(retry_config)
-            .build()?;
-
-        let folder = match &table.config {
-            TableConfig::CSV(csv_config) => csv_config.path.clone(),
-            TableConfig::Parquet(parquet_config) => parquet_config.path.clone(),
-        };
+            .with_virtual_hosted_style_request(details.virtual_hosted_style)
+            .with_retry(retry_config());
+        if let Some(access_key_id) = &details.access_key_id {
+            builder = builder.with_access_key_id(access_key_id);
+        }
+        if let Some(secret_access_key) = &details.secret_access_key {
+            builder = builder.with_secret_access_key(secret_access_key);
+        }
+        if let Some(session_token) = &details.session_token {
+            builder = builder.with_token(session_token);
+        }
+        if let Some(endpoint) = &details.endpoint {
+            builder = builder
+                .with_endpoint(endpoint)
+                .with_allow_http(endpoint.starts_with("http://"));
+        }
+        let object_store = builder.build()?;
+
+        let folder = table.config.path().to_string();
 
         Ok(DozerObjectStoreParams {
             url: Url::parse(&format!("s3://{}", details.bucket_name)).expect("Must be valid url"),
@@ -85,8 +101,88 @@ impl DozerObjectStore for S3Storage {
             data_fusion_table: table.clone(),
 
             aws_region: Some(details.region.clone()),
-            aws_access_key_id: Some(details.access_key_id.clone()),
-            aws_secret_access_key: Some(details.secret_access_key.clone()),
+            aws_access_key_id: details.access_key_id.clone(),
+            aws_secret_access_key: details.secret_access_key.clone(),
+        })
+    }
+
+    fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+}
+
+impl DozerObjectStore for GcsStorage {
+    type ObjectStore = GoogleCloudStorage;
+
+    fn store_params(
+        &self,
+        table: &Table,
+    ) -> Result<DozerObjectStoreParams<Self::ObjectStore>, ObjectStoreConnectorError> {
+        let details = &self.details;
+
+        let mut builder = GoogleCloudStorageBuilder::from_env()
+            .with_bucket_name(&details.bucket_name)
+            .with_retry(retry_config());
+        if let Some(service_account_path) = &details.service_account_path {
+            builder = builder.with_service_account_path(service_account_path);
+        }
+        if let Some(service_account_key) = &details.service_account_key {
+            builder = builder.with_service_account_key(service_account_key);
+        }
+        let object_store = builder.build()?;
+
+        let folder = table.config.path().to_string();
+
+        Ok(DozerObjectStoreParams {
+            url: Url::parse(&format!("gs://{}", details.bucket_name)).expect("Must be valid url"),
+            object_store,
+            table_path: format!("gs://{}/{folder}/", details.bucket_name),
+            folder,
+            data_fusion_table: table.clone(),
+
+            aws_region: None,
+            aws_access_key_id: None,
+            aws_secret_access_key: None,
+        })
+    }
+
+    fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+}
+
+impl DozerObjectStore for AzureStorage {
+    type ObjectStore = MicrosoftAzure;
+
+    fn store_params(
+        &self,
+        table: &Table,
+    ) -> Result<DozerObjectStoreParams<Self::ObjectStore>, ObjectStoreConnectorError> {
+        let details = &self.details;
+
+        let mut builder = MicrosoftAzureBuilder::from_env()
+            .with_account(&details.account_name)
+            .with_container_name(&details.container_name)
+            .with_use_emulator(details.use_emulator)
+            .with_retry(retry_config());
+        if let Some(access_key) = &details.access_key {
+            builder = builder.with_access_key(access_key);
+        }
+        let object_store = builder.build()?;
+
+        let folder = table.config.path().to_string();
+
+        Ok(DozerObjectStoreParams {
+            url: Url::parse(&format!("az://{}", details.container_name))
+                .expect("Must be valid url"),
+            object_store,
+            table_path: format!("az://{}/{folder}/", details.container_name),
+            folder,
+            data_fusion_table: table.clone(),
+
+            aws_region: None,
+            aws_access_key_id: None,
+            aws_secret_access_key: None,
         })
     }
 
@@ -106,10 +202,7 @@ impl DozerObjectStore for LocalStorage {
 
         let object_store = LocalFileSystem::new_with_prefix(path)?;
 
-        let folder = match &table.config {
-            TableConfig::CSV(csv_config) => csv_config.path.clone(),
-            TableConfig::Parquet(parquet_config) => parquet_config.path.clone(),
-        };
+        let folder = table.config.path().to_string();
 
         Ok(DozerObjectStoreParams {
             url: Url::parse(&format!("local://{}", path)).expect("Must be valid url"),
@@ -1,13 +1,17 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
 use dozer_ingestion_connector::dozer_types::errors::internal::BoxedError;
 use dozer_ingestion_connector::dozer_types::log::error;
 use dozer_ingestion_connector::dozer_types::models::ingestion_types::{
     IngestionMessage, TransactionInfo,
 };
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
-use dozer_ingestion_connector::dozer_types::types::FieldType;
-use dozer_ingestion_connector::futures::future::try_join_all;
-use dozer_ingestion_connector::tokio::sync::mpsc::channel;
-use dozer_ingestion_connector::tokio::task::JoinSet;
+use dozer_ingestion_connector::dozer_types::node::SourcePosition;
+use dozer_ingestion_connector::dozer_types::serde::{Deserialize, Serialize};
+use dozer_ingestion_connector::dozer_types::types::{FieldType, Operation, Record};
+use dozer_ingestion_connector::tokio::sync::mpsc::{channel, Sender};
+use dozer_ingestion_connector::tokio::sync::watch;
 use dozer_ingestion_connector::utils::{ListOrFilterColumns, TableNotFound};
 use dozer_ingestion_connector::{
     async_trait, tokio, Connector, Ingestor, SourceSchemaResult, TableIdentifier, TableInfo,
@@ -15,6 +19,7 @@ use dozer_ingestion_connector::{
 
 use crate::adapters::DozerObjectStore;
 use crate::table::ObjectStoreTable;
+use crate::table_watcher::FileInfo;
 use crate::{schema_mapper, ObjectStoreConnectorError};
 
 use super::connection::validator::validate_connection;
@@ -103,9 +108,12 @@ impl<T: DozerObjectStore> Connector for ObjectStoreConnector<T> {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
-        assert!(last_checkpoint.is_none());
+        let last_checkpoint = last_checkpoint
+            .as_ref()
+            .map(SourcePosition::decode::<ObjectStorePosition>)
+            .transpose()?;
         let (sender, mut receiver) =
             channel::<Result<Option<IngestionMessage>, ObjectStoreConnectorError>>(100); // todo: increase buffer siz
         let ingestor_clone = ingestor.clone();
@@ -131,98 +139,232 @@ impl<T: DozerObjectStore> Connector for ObjectStoreConnector<T> {
             Ok::<_, BoxedError>(())
         });
 
-        // sender sending out message for pipeline
-        sender
-            .send(Ok(Some(IngestionMessage::TransactionInfo(
-                TransactionInfo::SnapshottingStarted,
-            ))))
-            .await
-            .unwrap();
-
-        let mut handles = vec![];
-
-        for (table_index, table_info) in tables.iter().enumerate() {
-            let table_info = TableInfo {
-                schema: table_info.schema.clone(),
-                name: table_info.name.clone(),
-                column_names: table_info.column_names.clone(),
-            };
-
-            let mut found = false;
-            for table_config in self.config.tables() {
-                if table_info.name == table_config.name {
-                    let table = ObjectStoreTable::new(
-                        table_config.config.clone(),
-                        self.config.clone(),
-                        Default::default(),
-                    );
-                    let table_info = table_info.clone();
-                    let sender = sender.clone();
-                    handles.push(tokio::spawn(async move {
-                        table
-                            .snapshot(table_index, &table_info, sender)
-                            .await
-                            .unwrap()
-                    }));
-                    found = true;
-                    break;
-                }
-            }
-
-            if !found {
+        let mut object_store_tables = vec![];
+        for (table_index, table_info) in tables.into_iter().enumerate() {
+            if !self
+                .config
+                .tables()
+                .iter()
+                .any(|table| table.name == table_info.name)
+            {
                 return Err(TableNotFound {
                     schema: table_info.schema,
                     name: table_info.name,
                 }
                 .into());
             }
+            object_store_tables.push(ObjectStoreTable::new(
+                &self.config,
+                table_index,
+                table_info,
+            )?);
         }
 
-        let updated_state = try_join_all(handles).await.unwrap();
-
-        sender
-            .send(Ok(Some(IngestionMessage::TransactionInfo(
-                TransactionInfo::SnapshottingDone { id: None },
-            ))))
-            .await
-            .unwrap();
-        sender
-            .send(Ok(Some(IngestionMessage::TransactionInfo(
-                TransactionInfo::Commit { id: None },
-            ))))
-            .await
-            .unwrap();
-
-        let mut joinset = JoinSet::new();
+        ingest(
+            object_store_tables,
+            sender,
+            ingestor.checkpointed_position(),
+            last_checkpoint,
+        )
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn sample(
+        &mut self,
+        tables: Vec<TableInfo>,
+        limit: usize,
+    ) -> Result<Option<Vec<Vec<Record>>>, BoxedError> {
+        let mut samples = vec![];
         for (table_index, table_info) in tables.into_iter().enumerate() {
-            let table_info = TableInfo {
-                schema: table_info.schema,
-                name: table_info.name,
-                column_names: table_info.column_names,
-            };
+            let mut table = ObjectStoreTable::new(&self.config, table_index, table_info)?;
+            samples.push(sample(&mut table, limit).await?);
+        }
+        Ok(Some(samples))
+    }
 
-            for table in self.config.tables() {
-                if table_info.name == table.name {
-                    let (state, schema) = updated_state[table_index].clone();
-                    let table =
-                        ObjectStoreTable::new(table.config.clone(), self.config.clone(), state);
-                    let sender = sender.clone();
-                    joinset.spawn(async move {
-                        table
-                            .watch(table_index, &table_info, sender, schema.as_ref())
-                            .await
-                    });
-                    break;
+    fn compare_positions(&self, a: &SourcePosition, b: &SourcePosition) -> Option<Ordering> {
+        let a = a.decode::<ObjectStorePosition>().ok()?;
+        let b = b.decode::<ObjectStorePosition>().ok()?;
+        Some(a.seq.cmp(&b.seq))
+    }
+}
+
+/// Reads files of `table` until `limit` records are read. Unlike `ingest`, files are never deleted or archived.
+async fn sample<O: DozerObjectStore>(
+    table: &mut ObjectStoreTable<O>,
+    limit: usize,
+) -> Result<Vec<Record>, ObjectStoreConnectorError> {
+    let files = table.list_new_files().await?;
+    let (sender, mut receiver) = channel(100);
+    let read = async move {
+        for file in &files {
+            if sender.is_closed() {
+                break;
+            }
+            table.read(file, sender.clone()).await?;
+        }
+        Ok::<_, ObjectStoreConnectorError>(())
+    };
+    let collect = async move {
+        let mut records = vec![];
+        while records.len() < limit {
+            match receiver.recv().await {
+                Some(Ok(Some(IngestionMessage::OperationEvent { op, .. }))) => match op {
+                    Operation::Insert { new } => records.push(new),
+                    Operation::BatchInsert { new } => records.extend(new),
+                    Operation::Update { .. } | Operation::Delete { .. } => {}
+                },
+                Some(Ok(_)) => {}
+                Some(Err(ObjectStoreConnectorError::TableReaderError(e))) => error!("{e}"),
+                Some(Err(e)) => return Err(e),
+                None => break,
+            }
+        }
+        records.truncate(limit);
+        Ok(records)
+    };
+    let (read, records) = tokio::join!(read, collect);
+    read?;
+    records
+}
+
+/// Where ingestion resumes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "dozer_ingestion_connector::dozer_types::serde")]
+pub struct ObjectStorePosition {
+    /// Number of files ingested, which orders positions.
+    pub seq: u64,
+    /// Locations of the ingested files by table name, until they're deleted or archived.
+    pub files: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ObjectStorePosition {
+    fn contains(&self, table: &str, file: &FileInfo) -> bool {
+        let location: &str = file.location.as_ref();
+        self.files
+            .get(table)
+            .map_or(false, |files| files.contains(location))
+    }
+
+    fn insert(&mut self, table: &str, file: &FileInfo) {
+        self.seq += 1;
+        self.files
+            .entry(table.to_string())
+            .or_default()
+            .insert(file.location.to_string());
+    }
+
+    fn remove(&mut self, table: &str, file: &FileInfo) {
+        let location: &str = file.location.as_ref();
+        if let Some(files) = self.files.get_mut(table) {
+            files.remove(location);
+            if files.is_empty() {
+                self.files.remove(table);
+            }
+        }
+    }
+}
+
+/// Reads new files of all tables in last modified order, checking for new files every second.
+///
+/// The position lists the ingested files, so files added late are still read however old they are.
+/// After the snapshot, a commit is sent after every file, so ingestion can resume after the last committed file.
+///
+/// A commit is only durable once the pipeline checkpoints it, so files are deleted or archived when `checkpointed`
+/// covers them, or when ingestion resumes from a position that covers them.
+async fn ingest<O: DozerObjectStore>(
+    mut tables: Vec<ObjectStoreTable<O>>,
+    sender: Sender<Result<Option<IngestionMessage>, ObjectStoreConnectorError>>,
+    mut checkpointed: watch::Receiver<Option<SourcePosition>>,
+    last_checkpoint: Option<ObjectStorePosition>,
+) -> Result<(), ObjectStoreConnectorError> {
+    let send = |message| {
+        let sender = sender.clone();
+        async move {
+            sender
+                .send(Ok(Some(IngestionMessage::TransactionInfo(message))))
+                .await
+                .map_err(|_| ObjectStoreConnectorError::SendError)
+        }
+    };
+
+    let mut snapshotting = last_checkpoint.is_none();
+    let mut position = last_checkpoint.unwrap_or_default();
+    // Ingested files to delete or archive once they're checkpointed, with their table indexes.
+    let mut uncheckpointed: Vec<(usize, FileInfo)> = vec![];
+
+    if snapshotting {
+        send(TransactionInfo::SnapshottingStarted).await?;
+    } else {
+        send(TransactionInfo::SnapshottingSkipped).await?;
+    }
+
+    loop {
+        let mut files = vec![];
+        for (table_index, table) in tables.iter_mut().enumerate() {
+            for file in table.list_new_files().await? {
+                files.push((table_index, file));
+            }
+        }
+        files.sort_by(|(table_a, a), (table_b, b)| {
+            (a.last_modified, table_a, &a.name).cmp(&(b.last_modified, table_b, &b.name))
+        });
+
+        for (table_index, file) in files {
+            let table = &mut tables[table_index];
+            if position.contains(table.name(), &file) {
+                // Ingested before ingestion resumed from a position covering it, so it won't be read again.
+                if table.removes_ingested_files() {
+                    table.after_ingestion(&file).await?;
+                    position.remove(table.name(), &file);
                 }
+                continue;
+            }
+
+            table.read(&file, sender.clone()).await?;
+            position.insert(table.name(), &file);
+            if table.removes_ingested_files() {
+                uncheckpointed.push((table_index, file));
+            }
+            if !snapshotting {
+                send(TransactionInfo::Commit {
+                    id: Some(SourcePosition::encode(&position)?),
+                })
+                .await?;
             }
         }
-        while let Some(result) = joinset.join_next().await {
-            // Unwrap to propagate a panic in a task, then return
-            // short-circuit on any errors in connectors. The JoinSet
-            // will abort all other tasks when it is dropped
-            result.unwrap()?;
+
+        if snapshotting {
+            let id = Some(SourcePosition::encode(&position)?);
+            send(TransactionInfo::SnapshottingDone { id: id.clone() }).await?;
+            send(TransactionInfo::Commit { id }).await?;
+            snapshotting = false;
         }
-        Ok(())
+
+        // The sender is dropped if the pipeline doesn't checkpoint.
+        if checkpointed.has_changed().unwrap_or(false) {
+            let checkpointed_position = checkpointed
+                .borrow_and_update()
+                .as_ref()
+                .map(SourcePosition::decode::<ObjectStorePosition>)
+                .transpose()?;
+            if let Some(checkpointed_position) = checkpointed_position {
+                let (covered, rest): (Vec<_>, Vec<_>) =
+                    uncheckpointed.into_iter().partition(|(table_index, file)| {
+                        checkpointed_position.contains(tables[*table_index].name(), file)
+                    });
+                uncheckpointed = rest;
+                for (table_index, file) in covered {
+                    let table = &mut tables[table_index];
+                    table.after_ingestion(&file).await?;
+                    position.remove(table.name(), &file);
+                }
+            }
+        }
+
+        const WATCHER_INTERVAL: Duration = Duration::from_secs(1);
+        tokio::time::sleep(WATCHER_INTERVAL).await;
     }
 }
 
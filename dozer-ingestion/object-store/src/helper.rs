@@ -1,5 +1,8 @@
 use datafusion::datasource::{
-    file_format::{csv::CsvFormat, parquet::ParquetFormat},
+    file_format::{
+        avro::AvroFormat, csv::CsvFormat, file_compression_type::FileCompressionType,
+        json::JsonFormat, parquet::ParquetFormat,
+    },
     listing::ListingOptions,
 };
 use dozer_ingestion_connector::dozer_types::models::ingestion_types::{Table, TableConfig};
@@ -12,7 +15,8 @@ pub fn map_listing_options(
 ) -> Result<ListingOptions, ObjectStoreObjectError> {
     match &data_fusion_table.config {
         TableConfig::CSV(csv) => {
-            let format = CsvFormat::default();
+            let format = CsvFormat::default()
+                .with_file_compression_type(file_compression_type(&csv.extension));
             Ok(ListingOptions::new(Arc::new(format)).with_file_extension(csv.extension.clone()))
         }
         TableConfig::Parquet(parquet) => {
@@ -22,22 +26,46 @@ pub fn map_listing_options(
                     .with_file_extension(parquet.extension.clone()),
             )
         }
+        TableConfig::Json(json) => {
+            let format = JsonFormat::default()
+                .with_file_compression_type(file_compression_type(&json.extension));
+            Ok(ListingOptions::new(Arc::new(format)).with_file_extension(json.extension.clone()))
+        }
+        TableConfig::Avro(avro) => {
+            let format = AvroFormat;
+            Ok(ListingOptions::new(Arc::new(format)).with_file_extension(avro.extension.clone()))
+        }
     }
 }
 
-pub fn is_marker_file_exist(marker_files: Vec<FileInfo>, info: &FileInfo) -> bool {
-    for marker_file in marker_files {
-        let marker_file_name = match marker_file.name.rsplit_once('.') {
-            None => "",
-            Some(n) => n.0,
-        };
-        let file_name = match info.name.rsplit_once('.') {
-            None => "",
-            Some(n) => n.0,
-        };
-        if !file_name.is_empty() && marker_file_name == file_name {
-            return true;
-        }
+/// Infers the compression of text based formats from the file extension, e.g. `.json.gz`.
+fn file_compression_type(extension: &str) -> FileCompressionType {
+    match extension.rsplit('.').next() {
+        Some("gz") => FileCompressionType::GZIP,
+        Some("bz2") => FileCompressionType::BZIP2,
+        Some("xz") => FileCompressionType::XZ,
+        Some("zst") => FileCompressionType::ZSTD,
+        _ => FileCompressionType::UNCOMPRESSED,
     }
-    false
+}
+
+/// Finds the marker of a data file, which has the same name with `marker_extension` in place of `extension`.
+pub fn find_marker_file<'a>(
+    marker_files: &'a [FileInfo],
+    info: &FileInfo,
+    extension: &str,
+    marker_extension: &str,
+) -> Option<&'a FileInfo> {
+    let file_name = strip_extension(&info.name, extension)?;
+    marker_files
+        .iter()
+        .find(|marker_file| strip_extension(&marker_file.name, marker_extension) == Some(file_name))
+}
+
+/// Strips `extension`, which may or may not start with a dot, from `name`. `extension` can have several parts, like `json.gz`.
+fn strip_extension<'a>(name: &'a str, extension: &str) -> Option<&'a str> {
+    let stem = name
+        .strip_suffix(extension.trim_start_matches('.'))?
+        .strip_suffix('.')?;
+    (!stem.is_empty()).then_some(stem)
 }
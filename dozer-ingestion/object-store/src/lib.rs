@@ -1,6 +1,7 @@
 use datafusion::{datasource::listing::ListingTableUrl, error::DataFusionError};
 use dozer_ingestion_connector::dozer_types::{
     arrow_types::errors::FromArrowError,
+    errors::types::{DeserializationError, SerializationError},
     thiserror::{self, Error},
 };
 
@@ -41,6 +42,12 @@ pub enum ObjectStoreConnectorError {
 
     #[error("Failed to receive message on data read channel")]
     RecvError,
+
+    #[error("Failed to encode position: {0}")]
+    EncodePosition(#[from] SerializationError),
+
+    #[error("Failed to decode position: {0}")]
+    DecodePosition(#[from] DeserializationError),
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -1,16 +1,14 @@
 use datafusion::arrow::datatypes::SchemaRef;
-use datafusion::datasource::file_format::csv::CsvFormat;
-use datafusion::datasource::file_format::parquet::ParquetFormat;
 use datafusion::datasource::listing::{ListingOptions, ListingTableUrl};
 use datafusion::prelude::SessionContext;
 use dozer_ingestion_connector::dozer_types::log::error;
-use dozer_ingestion_connector::dozer_types::models::ingestion_types::TableConfig;
 use dozer_ingestion_connector::dozer_types::types::Schema;
 use dozer_ingestion_connector::utils::ListOrFilterColumns;
 use dozer_ingestion_connector::{CdcType, SourceSchema, SourceSchemaResult};
 use std::sync::Arc;
 
 use crate::adapters::DozerObjectStore;
+use crate::helper::map_listing_options;
 use crate::schema_helper::map_schema_to_dozer;
 use crate::{ObjectStoreConnectorError, ObjectStoreObjectError, ObjectStoreSchemaError};
 
@@ -52,21 +50,9 @@ async fn get_table_schema(
 ) -> SourceSchemaResult {
     let params = &config.table_params(&table.name)?;
 
-    match &params.data_fusion_table.config {
-        TableConfig::CSV(table_config) => {
-            let format = CsvFormat::default();
-            let listing_options = ListingOptions::new(Arc::new(format))
-                .with_file_extension(table_config.extension.clone());
-            get_object_schema(table, config, listing_options).await
-        }
-        TableConfig::Parquet(table_config) => {
-            let format = ParquetFormat::default();
-            let listing_options = ListingOptions::new(Arc::new(format))
-                .with_file_extension(table_config.extension.clone());
-
-            get_object_schema(table, config, listing_options).await
-        }
-    }
+    let listing_options = map_listing_options(&params.data_fusion_table)
+        .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?;
+    get_object_schema(table, config, listing_options).await
 }
 
 async fn get_object_schema(
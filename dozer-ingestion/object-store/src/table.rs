@@ -1,14 +1,20 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc};
 
-use datafusion::{common::DFSchema, datasource::listing::ListingTableUrl, prelude::SessionContext};
+use datafusion::{
+    common::DFSchema,
+    datasource::listing::{ListingOptions, ListingTableUrl},
+    prelude::SessionContext,
+};
 use dozer_ingestion_connector::{
     dozer_types::{
-        chrono::{DateTime, Utc},
         log::info,
-        models::ingestion_types::{self, CsvConfig, IngestionMessage, ParquetConfig},
+        models::ingestion_types::{
+            self, AfterIngestion, AvroConfig, CsvConfig, IngestionMessage, JsonConfig,
+            ParquetConfig,
+        },
     },
     futures::StreamExt,
-    tokio::{self, sync::mpsc::Sender},
+    tokio::sync::mpsc::Sender,
     TableInfo,
 };
 use object_store::path::Path;
@@ -16,7 +22,7 @@ use object_store::ObjectStore;
 
 use crate::{
     adapters::DozerObjectStore,
-    helper::{is_marker_file_exist, map_listing_options},
+    helper::{find_marker_file, map_listing_options},
     table_reader,
     table_watcher::FileInfo,
     ObjectStoreConnectorError, ObjectStoreObjectError,
@@ -28,29 +34,30 @@ pub trait TableConfig {
     fn marker_extension(&self) -> Option<&str>;
 }
 
-pub struct ObjectStoreTable<C: TableConfig, O: DozerObjectStore> {
-    table_config: C,
-    store: O,
-    update_state: HashMap<Path, DateTime<Utc>>,
+pub struct ObjectStoreTable<O: DozerObjectStore> {
+    table_index: usize,
+    table_info: TableInfo,
+    table_config: ingestion_types::TableConfig,
+    after_ingestion: Option<AfterIngestion>,
+    store: Arc<O::ObjectStore>,
+    folder: String,
+    base_path: String,
+    ctx: SessionContext,
+    listing_options: ListingOptions,
+    /// Files and markers that have been listed as ready, with their last modified time in milliseconds since epoch.
+    update_state: HashMap<Path, i64>,
+    /// Schema of the first file read, all other files must match it.
+    schema: Option<DFSchema>,
 }
 
-impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
-    pub fn new(table_config: C, store: O, update_state: HashMap<Path, DateTime<Utc>>) -> Self {
-        Self {
-            table_config,
-            store,
-            update_state,
-        }
-    }
-
-    pub async fn snapshot(
-        &self,
+impl<O: DozerObjectStore> ObjectStoreTable<O> {
+    pub fn new(
+        store: &O,
         table_index: usize,
-        table_info: &TableInfo,
-        sender: Sender<Result<Option<IngestionMessage>, ObjectStoreConnectorError>>,
-    ) -> Result<(HashMap<Path, DateTime<Utc>>, Option<DFSchema>), ObjectStoreConnectorError> {
-        let params = self.store.table_params(&table_info.name)?;
-        let store = Arc::new(params.object_store);
+        table_info: TableInfo,
+    ) -> Result<Self, ObjectStoreConnectorError> {
+        let params = store.table_params(&table_info.name)?;
+        let object_store = Arc::new(params.object_store);
 
         let listing_options = map_listing_options(&params.data_fusion_table)
             .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?;
@@ -58,281 +65,201 @@ impl<C: TableConfig, O: DozerObjectStore> ObjectStoreTable<C, O> {
         let ctx = SessionContext::new();
 
         ctx.runtime_env()
-            .register_object_store(&params.url, store.clone());
-
-        // Get the table state after snapshot
-        let mut update_state = self.update_state.clone();
+            .register_object_store(&params.url, object_store.clone());
+
+        Ok(Self {
+            table_index,
+            table_info,
+            table_config: params.data_fusion_table.config,
+            after_ingestion: params.data_fusion_table.after_ingestion,
+            store: object_store,
+            folder: params.folder,
+            base_path: params.table_path,
+            ctx,
+            listing_options,
+            update_state: HashMap::new(),
+            schema: None,
+        })
+    }
 
+    /// Lists files that have been added since the last call and are ready to be read, i.e. their marker exists if the table has a marker extension.
+    ///
+    /// A file waiting for its marker is considered modified when the marker is.
+    pub async fn list_new_files(&mut self) -> Result<Vec<FileInfo>, ObjectStoreConnectorError> {
         // List objects in the S3 bucket with the specified prefix
-        let mut stream = store
-            .list(Some(&Path::from(params.folder.clone())))
-            .await
-            .unwrap();
+        let mut stream = self
+            .store
+            .list(Some(&Path::from(self.folder.clone())))
+            .await?;
 
         let mut new_files = vec![];
         let mut new_marker_files = vec![];
 
         while let Some(item) = stream.next().await {
             // Check if any objects have been added or modified
-            let object = item.unwrap();
+            let object = item?;
 
-            if let Some(last_modified) = update_state.get_mut(&object.location) {
+            if let Some(last_modified) = self.update_state.get(&object.location) {
                 // Scenario 1: Update on existing file
-                if *last_modified < object.last_modified {
+                if *last_modified < object.last_modified.timestamp_millis() {
                     info!(
                         "Source Object has been modified: {:?}, {:?}",
                         object.location, object.last_modified
                     );
                 }
-            } else {
+                continue;
+            }
+
+            let file_path = object.location.to_string();
+            // Skip the source folder
+            if file_path == self.folder {
+                continue;
+            }
+
+            let is_marker = if file_path.ends_with(self.table_config.extension()) {
                 // Scenario 2: New file added
                 info!(
                     "Source Object has been added: {:?}, {:?}",
                     object.location, object.last_modified
                 );
+                false
+            } else if self
+                .table_config
+                .marker_extension()
+                .map_or(false, |marker_extension| {
+                    file_path.ends_with(marker_extension)
+                })
+            {
+                // Scenario 3: New marker file added
+                info!(
+                    "Source Object Marker has been added: {:?}, {:?}",
+                    object.location, object.last_modified
+                );
+                true
+            } else {
+                // Skip files that do not match the extension nor marker extension
+                continue;
+            };
 
-                let file_path = object.location.to_string();
-                // Skip the source folder
-                if file_path == params.folder {
-                    continue;
-                }
-
-                if file_path.ends_with(self.table_config.extension()) {
-                    // Scenario 2: New file added
-                    info!(
-                        "Source Object has been added: {:?}, {:?}",
-                        object.location, object.last_modified
-                    );
-
-                    // Remove base folder from relative path
-                    let path = std::path::Path::new(&file_path);
-                    let new_path = path
-                        .strip_prefix(path.components().next().unwrap())
-                        .unwrap();
-                    let new_path_str = new_path.to_str().unwrap();
-
-                    new_files.push(FileInfo {
-                        name: params.table_path.clone() + new_path_str,
-                        last_modified: object.last_modified.timestamp(),
-                    });
-                    if self.table_config.marker_extension().is_none() {
-                        update_state.insert(object.location, object.last_modified);
-                    }
-                } else if let Some(marker_extension) = self.table_config.marker_extension() {
-                    if file_path.ends_with(marker_extension) {
-                        // Scenario 3: New marker file added
-                        info!(
-                            "Source Object Marker has been added: {:?}, {:?}",
-                            object.location, object.last_modified
-                        );
-
-                        // Remove base folder from relative path
-                        let path = std::path::Path::new(&file_path);
-                        let new_path = path
-                            .strip_prefix(path.components().next().unwrap())
-                            .unwrap();
-                        let new_path_str = new_path.to_str().unwrap();
-
-                        new_marker_files.push(FileInfo {
-                            name: params.table_path.clone() + new_path_str,
-                            last_modified: object.last_modified.timestamp(),
-                        });
-
-                        update_state.insert(object.location, object.last_modified);
-                    } else {
-                        continue;
-                    }
-                } else {
-                    // Skip files that do not match the extension nor marker extension
-                    continue;
-                }
+            // Remove base folder from relative path
+            let path = std::path::Path::new(&file_path);
+            let new_path = path
+                .strip_prefix(path.components().next().unwrap())
+                .unwrap();
+            let new_path_str = new_path.to_str().unwrap();
+
+            let file = FileInfo {
+                name: self.base_path.clone() + new_path_str,
+                location: object.location.clone(),
+                last_modified: object.last_modified.timestamp_millis(),
+                marker: None,
+            };
+            if is_marker {
+                new_marker_files.push(file);
+            } else {
+                new_files.push(file);
             }
         }
 
-        let mut schema = None;
-        new_files.sort();
-        for file in &new_files {
-            let marker_file_exist = is_marker_file_exist(new_marker_files.clone(), file);
-            if !marker_file_exist && self.table_config.marker_extension().is_some() {
-                continue;
-            } else {
-                let file_path = ListingTableUrl::parse(&file.name)
-                    .map_err(|e| {
-                        ObjectStoreConnectorError::DataFusionStorageObjectError(
-                            ObjectStoreObjectError::ListingPathParsingError(file.name.clone(), e),
-                        )
-                    })
-                    .unwrap();
-
-                let result = table_reader::read(
-                    table_index,
-                    ctx.clone(),
-                    file_path,
-                    listing_options.clone(),
-                    table_info,
-                    sender.clone(),
-                    schema.as_ref(),
-                )
-                .await;
-                match result {
-                    Ok(s) => {
-                        schema = Some(s);
-                    }
-                    Err(e) => sender.send(Err(e)).await.unwrap(),
-                }
+        let Some(marker_extension) = self.table_config.marker_extension() else {
+            for file in &new_files {
+                self.update_state
+                    .insert(file.location.clone(), file.last_modified);
             }
+            return Ok(new_files);
+        };
+        // Files and markers are only recorded once they're matched, so either can be added first.
+        let mut ready_files = vec![];
+        for mut file in new_files {
+            let Some(marker_file) = find_marker_file(
+                &new_marker_files,
+                &file,
+                self.table_config.extension(),
+                marker_extension,
+            ) else {
+                continue;
+            };
+            self.update_state
+                .insert(file.location.clone(), file.last_modified);
+            self.update_state
+                .insert(marker_file.location.clone(), marker_file.last_modified);
+            file.last_modified = file.last_modified.max(marker_file.last_modified);
+            file.marker = Some(marker_file.location.clone());
+            ready_files.push(file);
         }
-
-        Ok((update_state, schema))
+        Ok(ready_files)
     }
 
-    pub async fn watch(
-        &self,
-        table_index: usize,
-        table_info: &TableInfo,
+    /// Reads all records of `file`. Read errors of a single file are sent to `sender` instead of stopping ingestion.
+    pub async fn read(
+        &mut self,
+        file: &FileInfo,
         sender: Sender<Result<Option<IngestionMessage>, ObjectStoreConnectorError>>,
-        schema: Option<&DFSchema>,
     ) -> Result<(), ObjectStoreConnectorError> {
-        let params = self.store.table_params(&table_info.name)?;
-        let store = Arc::new(params.object_store);
-
-        let source_folder = params.folder.to_string();
-        let base_path = params.table_path;
-
-        let listing_options = map_listing_options(&params.data_fusion_table)
-            .map_err(ObjectStoreConnectorError::DataFusionStorageObjectError)?;
-
-        let ctx = SessionContext::new();
-
-        ctx.runtime_env()
-            .register_object_store(&params.url, store.clone());
-
-        // Get the table state after snapshot
-        let mut update_state = self.update_state.clone();
-
-        loop {
-            // List objects in the S3 bucket with the specified prefix
-            let mut stream = store
-                .list(Some(&Path::from(source_folder.to_owned())))
+        let file_path = ListingTableUrl::parse(&file.name).map_err(|e| {
+            ObjectStoreConnectorError::DataFusionStorageObjectError(
+                ObjectStoreObjectError::ListingPathParsingError(file.name.clone(), e),
+            )
+        })?;
+
+        let result = table_reader::read(
+            self.table_index,
+            self.ctx.clone(),
+            file_path,
+            self.listing_options.clone(),
+            &self.table_info,
+            sender.clone(),
+            self.schema.as_ref(),
+        )
+        .await;
+        match result {
+            Ok(schema) => {
+                self.schema = Some(schema);
+                Ok(())
+            }
+            Err(e) => sender
+                .send(Err(e))
                 .await
-                .unwrap();
+                .map_err(|_| ObjectStoreConnectorError::SendError),
+        }
+    }
 
-            let mut new_files = vec![];
-            let mut new_marker_files = vec![];
-
-            while let Some(item) = stream.next().await {
-                // Check if any objects have been added or modified
-                let object = item.unwrap();
-
-                if let Some(last_modified) = update_state.get_mut(&object.location) {
-                    // Scenario 1: Update on existing file
-                    if *last_modified < object.last_modified {
-                        info!(
-                            "Source Object has been modified: {:?}, {:?}",
-                            object.location, object.last_modified
-                        );
-                    }
-                } else {
-                    // Scenario 2: New file added
-                    info!(
-                        "Source Object has been added: {:?}, {:?}",
-                        object.location, object.last_modified
-                    );
+    pub fn name(&self) -> &str {
+        &self.table_info.name
+    }
 
-                    let file_path = object.location.to_string();
-                    // Skip the source folder
-                    if file_path == source_folder {
-                        continue;
-                    }
-
-                    if file_path.ends_with(self.table_config.extension()) {
-                        // Scenario 2: New file added
-                        info!(
-                            "Source Object has been added: {:?}, {:?}",
-                            object.location, object.last_modified
-                        );
-
-                        // Remove base folder from relative path
-                        let path = std::path::Path::new(&file_path);
-                        let new_path = path
-                            .strip_prefix(path.components().next().unwrap())
-                            .unwrap();
-                        let new_path_str = new_path.to_str().unwrap();
-
-                        new_files.push(FileInfo {
-                            name: base_path.clone() + new_path_str,
-                            last_modified: object.last_modified.timestamp(),
-                        });
-                        if self.table_config.marker_extension().is_none() {
-                            update_state.insert(object.location, object.last_modified);
-                        }
-                    } else if let Some(marker_extension) = self.table_config.marker_extension() {
-                        if file_path.ends_with(marker_extension) {
-                            // Scenario 3: New marker file added
-                            info!(
-                                "Source Object Marker has been added: {:?}, {:?}",
-                                object.location, object.last_modified
-                            );
-
-                            // Remove base folder from relative path
-                            let path = std::path::Path::new(&file_path);
-                            let new_path = path
-                                .strip_prefix(path.components().next().unwrap())
-                                .unwrap();
-                            let new_path_str = new_path.to_str().unwrap();
-
-                            new_marker_files.push(FileInfo {
-                                name: base_path.clone() + new_path_str,
-                                last_modified: object.last_modified.timestamp(),
-                            });
-                            update_state.insert(object.location, object.last_modified);
-                        } else {
-                            continue;
-                        }
-                    } else {
-                        // Skip files that do not match the extension nor marker extension
-                        continue;
-                    }
-                }
-            }
+    /// Whether ingested files are deleted or archived.
+    pub fn removes_ingested_files(&self) -> bool {
+        self.after_ingestion.is_some()
+    }
 
-            new_files.sort();
-            for file in &new_files {
-                let marker_file_exist = is_marker_file_exist(new_marker_files.clone(), file);
-                if !marker_file_exist && self.table_config.marker_extension().is_some() {
-                    continue;
-                } else {
-                    let file_path = ListingTableUrl::parse(&file.name)
-                        .map_err(|e| {
-                            ObjectStoreConnectorError::DataFusionStorageObjectError(
-                                ObjectStoreObjectError::ListingPathParsingError(
-                                    file.name.clone(),
-                                    e,
-                                ),
-                            )
-                        })
-                        .unwrap();
-
-                    let result = table_reader::read(
-                        table_index,
-                        ctx.clone(),
-                        file_path,
-                        listing_options.clone(),
-                        table_info,
-                        sender.clone(),
-                        schema,
-                    )
-                    .await;
-                    if let Err(e) = result {
-                        sender.send(Err(e)).await.unwrap();
-                    }
+    /// Deletes or archives `file` if the table is configured to, and its marker file if any.
+    ///
+    /// They're forgotten, so a file added later at the same location is ingested as a new one.
+    pub async fn after_ingestion(
+        &mut self,
+        file: &FileInfo,
+    ) -> Result<(), ObjectStoreConnectorError> {
+        let Some(after_ingestion) = &self.after_ingestion else {
+            return Ok(());
+        };
+
+        let locations = std::iter::once(&file.location).chain(file.marker.as_ref());
+        for location in locations {
+            match after_ingestion {
+                AfterIngestion::Delete => {
+                    info!("Deleting ingested object {location}");
+                    self.store.delete(location).await?;
+                }
+                AfterIngestion::Archive { path } => {
+                    let to = Path::from(format!("{}/{location}", path.trim_end_matches('/')));
+                    info!("Archiving ingested object {location} to {to}");
+                    self.store.rename(location, &to).await?;
                 }
             }
-
-            // Wait for 10 seconds before checking again
-            const WATCHER_INTERVAL: Duration = Duration::from_secs(1);
-            tokio::time::sleep(WATCHER_INTERVAL).await;
+            self.update_state.remove(location);
         }
+        Ok(())
     }
 }
 
@@ -364,11 +291,41 @@ impl TableConfig for ParquetConfig {
     }
 }
 
+impl TableConfig for JsonConfig {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn extension(&self) -> &str {
+        &self.extension
+    }
+
+    fn marker_extension(&self) -> Option<&str> {
+        self.marker_extension.as_deref()
+    }
+}
+
+impl TableConfig for AvroConfig {
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn extension(&self) -> &str {
+        &self.extension
+    }
+
+    fn marker_extension(&self) -> Option<&str> {
+        self.marker_extension.as_deref()
+    }
+}
+
 impl TableConfig for ingestion_types::TableConfig {
     fn path(&self) -> &str {
         match self {
             ingestion_types::TableConfig::CSV(csv_config) => csv_config.path(),
             ingestion_types::TableConfig::Parquet(parquet_config) => parquet_config.path(),
+            ingestion_types::TableConfig::Json(json_config) => json_config.path(),
+            ingestion_types::TableConfig::Avro(avro_config) => avro_config.path(),
         }
     }
 
@@ -376,6 +333,8 @@ impl TableConfig for ingestion_types::TableConfig {
         match self {
             ingestion_types::TableConfig::CSV(csv_config) => csv_config.extension(),
             ingestion_types::TableConfig::Parquet(parquet_config) => parquet_config.extension(),
+            ingestion_types::TableConfig::Json(json_config) => json_config.extension(),
+            ingestion_types::TableConfig::Avro(avro_config) => avro_config.extension(),
         }
     }
 
@@ -385,6 +344,8 @@ impl TableConfig for ingestion_types::TableConfig {
             ingestion_types::TableConfig::Parquet(parquet_config) => {
                 parquet_config.marker_extension()
             }
+            ingestion_types::TableConfig::Json(json_config) => json_config.marker_extension(),
+            ingestion_types::TableConfig::Avro(avro_config) => avro_config.marker_extension(),
         }
     }
 }
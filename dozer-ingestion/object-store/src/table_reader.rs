@@ -115,6 +115,7 @@ pub async fn read(
                     table_index,
                     op: evt,
                     id: None,
+                    source_time: None,
                 })))
                 .await
                 .is_err()
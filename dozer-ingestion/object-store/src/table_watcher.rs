@@ -10,12 +10,18 @@ use dozer_ingestion_connector::{
     TableInfo,
 };
 
+use object_store::path::Path;
+
 use crate::ObjectStoreConnectorError;
 
 #[derive(Debug, Eq, Clone)]
 pub struct FileInfo {
     pub name: String,
+    pub location: Path,
+    /// Milliseconds since epoch.
     pub last_modified: i64,
+    /// Location of the marker file, if the table has a marker extension.
+    pub marker: Option<Path>,
 }
 
 impl Ord for FileInfo {
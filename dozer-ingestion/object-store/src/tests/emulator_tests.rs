@@ -0,0 +1,131 @@
+//! Connector tests against local emulators, run by the unit workflow with `--ignored`.
+//! Each emulator must have a `dozer-test` bucket or container.
+
+use dozer_ingestion_connector::{
+    dozer_types::{
+        models::ingestion_types::{
+            AzureDetails, AzureStorage, CsvConfig, GcsDetails, GcsStorage, IngestionMessage,
+            S3Details, S3Storage, Table, TableConfig, TransactionInfo,
+        },
+        types::{Field, Operation},
+    },
+    test_util::{create_test_runtime, spawn_connector_all_tables},
+};
+use object_store::{path::Path, ObjectStore};
+
+use crate::{adapters::DozerObjectStore, connector::ObjectStoreConnector};
+
+const BUCKET: &str = "dozer-test";
+const TABLE: &str = "all_types_csv";
+
+fn tables() -> Vec<Table> {
+    vec![Table {
+        config: TableConfig::CSV(CsvConfig {
+            extension: "csv".to_string(),
+            path: TABLE.to_string(),
+            marker_extension: None,
+        }),
+        name: TABLE.to_string(),
+        after_ingestion: None,
+    }]
+}
+
+/// Uploads the local CSV samples to the store and reads them back through the connector.
+fn test_csv_read<T: DozerObjectStore>(config: T) {
+    let runtime = create_test_runtime();
+    let object_store = config.table_params(TABLE).unwrap().object_store;
+    runtime.block_on(async {
+        for file in ["sample.csv", "new_sample.csv"] {
+            let data = std::fs::read(format!("src/tests/files/{TABLE}/{file}")).unwrap();
+            object_store
+                .put(&Path::from(format!("{TABLE}/{file}")), data.into())
+                .await
+                .unwrap();
+        }
+    });
+
+    let (mut iterator, _) =
+        spawn_connector_all_tables(runtime.clone(), ObjectStoreConnector::new(config));
+
+    assert!(matches!(
+        iterator.next(),
+        Some(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingStarted
+        ))
+    ));
+    // no. of rows in the two files
+    for _ in 0..20 {
+        let Some(IngestionMessage::OperationEvent {
+            op: Operation::Insert { new },
+            ..
+        }) = iterator.next()
+        else {
+            panic!("Unexpected message");
+        };
+        crate::test_type_conversion!(new.values, 0, Field::Int(_));
+        crate::test_type_conversion!(new.values, 1, Field::String(_));
+    }
+    assert!(matches!(
+        iterator.next(),
+        Some(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingDone { .. }
+        ))
+    ));
+}
+
+/// Needs MinIO on `127.0.0.1:9000` with the default `minioadmin` credentials.
+#[test]
+#[ignore]
+fn test_emulator_minio() {
+    test_csv_read(S3Storage {
+        details: S3Details {
+            access_key_id: Some("minioadmin".to_string()),
+            secret_access_key: Some("minioadmin".to_string()),
+            session_token: None,
+            region: "us-east-1".to_string(),
+            bucket_name: BUCKET.to_string(),
+            endpoint: Some("http://127.0.0.1:9000".to_string()),
+            virtual_hosted_style: false,
+        },
+        tables: tables(),
+    });
+}
+
+/// Needs Azurite's blob service on `127.0.0.1:10000`, its default.
+#[test]
+#[ignore]
+fn test_emulator_azurite() {
+    test_csv_read(AzureStorage {
+        details: AzureDetails {
+            account_name: "devstoreaccount1".to_string(),
+            container_name: BUCKET.to_string(),
+            access_key: None,
+            use_emulator: true,
+        },
+        tables: tables(),
+    });
+}
+
+/// Needs fake-gcs-server on `127.0.0.1:4443`, started with `-scheme http`.
+#[test]
+#[ignore]
+fn test_emulator_fake_gcs_server() {
+    test_csv_read(GcsStorage {
+        details: GcsDetails {
+            bucket_name: BUCKET.to_string(),
+            service_account_path: None,
+            // The key points the client to the emulator and turns off OAuth.
+            service_account_key: Some(
+                r#"{
+                    "gcs_base_url": "http://127.0.0.1:4443",
+                    "disable_oauth": true,
+                    "client_email": "",
+                    "private_key": "",
+                    "private_key_id": ""
+                }"#
+                .to_string(),
+            ),
+        },
+        tables: tables(),
+    });
+}
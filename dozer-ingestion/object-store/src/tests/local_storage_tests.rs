@@ -1,13 +1,24 @@
+use std::time::{Duration, UNIX_EPOCH};
+
 use dozer_ingestion_connector::{
     dozer_types::{
-        models::ingestion_types::{IngestionMessage, TransactionInfo},
+        models::ingestion_types::{
+            AfterIngestion, IngestionMessage, JsonConfig, LocalDetails, LocalStorage, Table,
+            TableConfig, TransactionInfo,
+        },
+        node::SourcePosition,
         types::{Field, FieldType, Operation},
     },
-    test_util::create_runtime_and_spawn_connector_all_tables,
-    tokio, Connector,
+    test_util::{create_runtime_and_spawn_connector_all_tables, create_test_runtime},
+    tokio::{self, sync::watch},
+    Connector, IngestionConfig, IngestionIterator, Ingestor,
 };
+use tempdir::TempDir;
 
-use crate::{connector::ObjectStoreConnector, tests::test_utils::get_local_storage_config};
+use crate::{
+    connector::{ObjectStoreConnector, ObjectStorePosition},
+    tests::test_utils::get_local_storage_config,
+};
 
 #[macro_export]
 macro_rules! test_type_conversion {
@@ -370,3 +381,330 @@ fn test_csv_read_no_marker() {
         panic!("Unexpected message");
     }
 }
+
+async fn assert_sample_schema(typ: &str) {
+    let local_storage = get_local_storage_config(typ, "");
+
+    let mut connector = ObjectStoreConnector::new(local_storage);
+    let (_, schemas) = connector.list_all_schemas().await.unwrap();
+    let schema = schemas.first().unwrap();
+
+    let fields = schema.schema.fields.clone();
+    assert_eq!(fields.first().unwrap().typ, FieldType::Int);
+    assert_eq!(fields.get(1).unwrap().typ, FieldType::String);
+    assert_eq!(fields.get(2).unwrap().typ, FieldType::Float);
+    assert_eq!(fields.get(3).unwrap().typ, FieldType::Boolean);
+}
+
+#[tokio::test]
+async fn test_get_schema_of_json() {
+    assert_sample_schema("json").await;
+}
+
+#[tokio::test]
+async fn test_get_schema_of_compressed_json() {
+    assert_sample_schema("json.gz").await;
+}
+
+#[tokio::test]
+async fn test_get_schema_of_avro() {
+    assert_sample_schema("avro").await;
+}
+
+fn assert_sample_read(typ: &str) {
+    let local_storage = get_local_storage_config(typ, "");
+
+    let connector = ObjectStoreConnector::new(local_storage);
+
+    let (mut iterator, _) = create_runtime_and_spawn_connector_all_tables(connector);
+
+    let row = iterator.next();
+    if let Some(IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingStarted)) = row {
+    } else {
+        panic!("Unexpected message");
+    }
+
+    for i in 1..=10 {
+        let row = iterator.next();
+        if let Some(IngestionMessage::OperationEvent {
+            op: Operation::Insert { new },
+            ..
+        }) = row
+        {
+            let values = new.values;
+
+            assert_eq!(values[0], Field::Int(i));
+            test_type_conversion!(values, 1, Field::String(_));
+            test_type_conversion!(values, 2, Field::Float(_));
+            test_type_conversion!(values, 3, Field::Boolean(_));
+        } else {
+            panic!("Unexpected message");
+        }
+    }
+
+    let row = iterator.next();
+    if let Some(IngestionMessage::TransactionInfo(TransactionInfo::SnapshottingDone { .. })) = row {
+    } else {
+        panic!("Unexpected message");
+    }
+}
+
+#[test]
+fn test_json_read() {
+    assert_sample_read("json");
+}
+
+#[test]
+fn test_compressed_json_read() {
+    assert_sample_read("json.gz");
+}
+
+#[test]
+fn test_avro_read() {
+    assert_sample_read("avro");
+}
+
+fn temp_json_storage(after_ingestion: Option<AfterIngestion>) -> (TempDir, LocalStorage) {
+    let temp_dir = TempDir::new("object_store").unwrap();
+    std::fs::create_dir(temp_dir.path().join("events")).unwrap();
+    let local_storage = LocalStorage {
+        details: LocalDetails {
+            path: temp_dir.path().to_str().unwrap().to_string(),
+        },
+        tables: vec![Table {
+            config: TableConfig::Json(JsonConfig {
+                path: "events".to_string(),
+                extension: "json".to_string(),
+                marker_extension: None,
+            }),
+            name: "events".to_string(),
+            after_ingestion,
+        }],
+    };
+    (temp_dir, local_storage)
+}
+
+fn add_sample_json(dir: &TempDir, name: &str, modified_secs: u64) {
+    let path = dir.path().join("events").join(name);
+    std::fs::copy("src/tests/files/all_types_json/sample.json", &path).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(UNIX_EPOCH + Duration::from_secs(modified_secs))
+        .unwrap();
+}
+
+/// Spawns ingestion from `last_checkpoint`. Positions sent to the returned sender are reported to the connector as checkpointed.
+fn spawn_from_checkpoint(
+    local_storage: LocalStorage,
+    last_checkpoint: Option<ObjectStorePosition>,
+) -> (IngestionIterator, watch::Sender<Option<SourcePosition>>) {
+    let runtime = create_test_runtime();
+    let mut connector = ObjectStoreConnector::new(local_storage);
+    let tables = runtime.block_on(async {
+        let tables = connector.list_tables().await.unwrap();
+        connector.list_columns(tables).await.unwrap()
+    });
+    let (checkpointed_sender, checkpointed) = watch::channel(None);
+    let (ingestor, iterator) = Ingestor::initialize_channel(
+        IngestionConfig::default().with_checkpointed_position(checkpointed),
+    );
+    let last_checkpoint =
+        last_checkpoint.map(|position| SourcePosition::encode(&position).unwrap());
+    runtime.clone().spawn_blocking(move || {
+        runtime
+            .block_on(connector.start(&ingestor, tables, last_checkpoint))
+            .unwrap()
+    });
+    (iterator, checkpointed_sender)
+}
+
+/// A position of the `events` table of `temp_json_storage`.
+fn position(seq: u64, files: &[&str]) -> ObjectStorePosition {
+    let mut position = ObjectStorePosition {
+        seq,
+        ..Default::default()
+    };
+    if !files.is_empty() {
+        position.files.insert(
+            "events".to_string(),
+            files.iter().map(|file| format!("events/{file}")).collect(),
+        );
+    }
+    position
+}
+
+fn commit(position: ObjectStorePosition) -> Option<IngestionMessage> {
+    Some(IngestionMessage::TransactionInfo(TransactionInfo::Commit {
+        id: Some(SourcePosition::encode(&position).unwrap()),
+    }))
+}
+
+fn assert_sample_rows(iterator: &mut IngestionIterator) {
+    for i in 1..=10 {
+        match iterator.next() {
+            Some(IngestionMessage::OperationEvent {
+                op: Operation::Insert { new },
+                ..
+            }) => assert_eq!(new.values[0], Field::Int(i)),
+            other => panic!("Unexpected message {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_resume_from_checkpoint() {
+    let (temp_dir, local_storage) = temp_json_storage(None);
+    add_sample_json(&temp_dir, "a.json", 1000);
+    add_sample_json(&temp_dir, "b.json", 2000);
+    // Added after `b.json` was ingested, but modified before it.
+    add_sample_json(&temp_dir, "c.json", 1500);
+
+    let (mut iterator, _checkpointed) =
+        spawn_from_checkpoint(local_storage, Some(position(2, &["a.json", "b.json"])));
+
+    assert_eq!(
+        iterator.next(),
+        Some(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingSkipped
+        ))
+    );
+    assert_sample_rows(&mut iterator);
+    assert_eq!(
+        iterator.next(),
+        commit(position(3, &["a.json", "b.json", "c.json"]))
+    );
+
+    add_sample_json(&temp_dir, "d.json", 500);
+    assert_sample_rows(&mut iterator);
+    assert_eq!(
+        iterator.next(),
+        commit(position(4, &["a.json", "b.json", "c.json", "d.json"]))
+    );
+}
+
+#[test]
+fn test_snapshot_checkpoint() {
+    let (temp_dir, local_storage) = temp_json_storage(None);
+    add_sample_json(&temp_dir, "a.json", 1000);
+
+    let (mut iterator, _checkpointed) = spawn_from_checkpoint(local_storage, None);
+
+    assert_eq!(
+        iterator.next(),
+        Some(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingStarted
+        ))
+    );
+    assert_sample_rows(&mut iterator);
+    let id = Some(SourcePosition::encode(&position(1, &["a.json"])).unwrap());
+    assert_eq!(
+        iterator.next(),
+        Some(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingDone { id: id.clone() }
+        ))
+    );
+    assert_eq!(
+        iterator.next(),
+        Some(IngestionMessage::TransactionInfo(TransactionInfo::Commit {
+            id
+        }))
+    );
+}
+
+/// Spawns ingestion from a checkpoint covering `a.json`, waits until `b.json`, which it doesn't cover, is committed,
+/// and reports that commit as checkpointed.
+fn ingest_and_checkpoint(temp_dir: &TempDir, local_storage: LocalStorage) {
+    add_sample_json(temp_dir, "a.json", 1000);
+    add_sample_json(temp_dir, "b.json", 2000);
+
+    let (mut iterator, checkpointed) =
+        spawn_from_checkpoint(local_storage, Some(position(1, &["a.json"])));
+
+    assert_eq!(
+        iterator.next(),
+        Some(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingSkipped
+        ))
+    );
+    assert_sample_rows(&mut iterator);
+    // `a.json` was removed when ingestion resumed, so it's no longer listed.
+    assert_eq!(iterator.next(), commit(position(2, &["b.json"])));
+
+    assert!(!temp_dir.path().join("events/a.json").exists());
+    // Not checkpointed yet, so it must be kept in case the pipeline fails before checkpointing it.
+    assert!(temp_dir.path().join("events/b.json").exists());
+
+    checkpointed
+        .send(Some(
+            SourcePosition::encode(&position(2, &["b.json"])).unwrap(),
+        ))
+        .unwrap();
+    for _ in 0..50 {
+        if !temp_dir.path().join("events/b.json").exists() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    panic!("b.json was not removed after it was checkpointed");
+}
+
+#[test]
+fn test_delete_after_ingestion() {
+    let (temp_dir, local_storage) = temp_json_storage(Some(AfterIngestion::Delete));
+
+    ingest_and_checkpoint(&temp_dir, local_storage);
+
+    assert!(!temp_dir.path().join("archive").exists());
+}
+
+#[test]
+fn test_archive_after_ingestion() {
+    let (temp_dir, local_storage) = temp_json_storage(Some(AfterIngestion::Archive {
+        path: "archive".to_string(),
+    }));
+
+    ingest_and_checkpoint(&temp_dir, local_storage);
+
+    assert!(temp_dir.path().join("archive/events/a.json").exists());
+    assert!(temp_dir.path().join("archive/events/b.json").exists());
+}
+
+#[test]
+fn test_compressed_json_read_marker() {
+    let temp_dir = TempDir::new("object_store").unwrap();
+    let events = temp_dir.path().join("events");
+    std::fs::create_dir(&events).unwrap();
+    std::fs::copy(
+        "src/tests/files/all_types_json_gz/sample.json.gz",
+        events.join("a.json.gz"),
+    )
+    .unwrap();
+    std::fs::write(events.join("a.marker"), "").unwrap();
+    let local_storage = LocalStorage {
+        details: LocalDetails {
+            path: temp_dir.path().to_str().unwrap().to_string(),
+        },
+        tables: vec![Table {
+            config: TableConfig::Json(JsonConfig {
+                path: "events".to_string(),
+                extension: "json.gz".to_string(),
+                marker_extension: Some(".marker".to_string()),
+            }),
+            name: "events".to_string(),
+            after_ingestion: None,
+        }],
+    };
+
+    let (mut iterator, _) = spawn_from_checkpoint(local_storage, None);
+
+    // `a.marker` marks `a.json.gz`, although the data file has two extensions.
+    assert_eq!(
+        iterator.next(),
+        Some(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingStarted
+        ))
+    );
+    assert_sample_rows(&mut iterator);
+}
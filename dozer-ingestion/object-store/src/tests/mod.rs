@@ -1,2 +1,3 @@
+mod emulator_tests;
 mod local_storage_tests;
 mod test_utils;
@@ -1,5 +1,6 @@
 use dozer_ingestion_connector::dozer_types::models::ingestion_types::{
-    CsvConfig, LocalDetails, LocalStorage, ParquetConfig, Table, TableConfig,
+    AvroConfig, CsvConfig, JsonConfig, LocalDetails, LocalStorage, ParquetConfig, Table,
+    TableConfig,
 };
 use std::path::PathBuf;
 
@@ -18,6 +19,7 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         marker_extension: None,
                     }),
                     name: format!("all_types_{typ}"),
+                    after_ingestion: None,
                 }],
             },
             &_ => LocalStorage {
@@ -31,6 +33,7 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         marker_extension: Some(String::from(".marker")),
                     }),
                     name: format!("{prefix}_{typ}"),
+                    after_ingestion: None,
                 }],
             },
         },
@@ -46,6 +49,7 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         marker_extension: None,
                     }),
                     name: format!("all_types_{typ}"),
+                    after_ingestion: None,
                 }],
             },
             &_ => LocalStorage {
@@ -59,9 +63,35 @@ pub fn get_local_storage_config(typ: &str, prefix: &str) -> LocalStorage {
                         marker_extension: Some(String::from(".marker")),
                     }),
                     name: format!("{prefix}_{typ}"),
+                    after_ingestion: None,
                 }],
             },
         },
+        "json" | "json.gz" | "avro" => {
+            let name = format!("all_types_{}", typ.replace('.', "_"));
+            let config = match typ {
+                "avro" => TableConfig::Avro(AvroConfig {
+                    extension: typ.to_string(),
+                    path: name.clone(),
+                    marker_extension: None,
+                }),
+                _ => TableConfig::Json(JsonConfig {
+                    extension: typ.to_string(),
+                    path: name.clone(),
+                    marker_extension: None,
+                }),
+            };
+            LocalStorage {
+                details: LocalDetails {
+                    path: p.to_str().unwrap().to_string(),
+                },
+                tables: vec![Table {
+                    config,
+                    name,
+                    after_ingestion: None,
+                }],
+            }
+        }
         other => panic!("Unsupported type: {}", other),
     }
 }
@@ -15,7 +15,7 @@ use oracle::Row;
 
 use super::{join::Column, Error};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct MappedColumn {
     pub typ: FieldType,
     pub nullable: bool,
@@ -88,7 +88,7 @@ pub fn map_row(schema: &Schema, row: Row) -> Result<Record, Error> {
 }
 
 fn map_field(index: usize, field: &FieldDefinition, row: &Row) -> Result<Field, Error> {
-    Ok(match (field.typ, field.nullable) {
+    Ok(match (&field.typ, field.nullable) {
         (FieldType::Float, true) => row
             .get::<_, Option<f64>>(index)?
             .map_or(Field::Null, |value| Field::Float(OrderedFloat(value))),
@@ -192,7 +192,7 @@ pub fn decide_schema(
         match &column.map_result {
             Ok(column) => fields.push(FieldDefinition {
                 name: column_name.clone(),
-                typ: column.typ,
+                typ: column.typ.clone(),
                 nullable: column.nullable,
                 source: SourceDefinition::Table {
                     connection: connection.to_string(),
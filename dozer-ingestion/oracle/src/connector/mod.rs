@@ -272,6 +272,7 @@ impl Connector {
                                 new: std::mem::take(&mut batch),
                             },
                             id: None,
+                            source_time: None,
                         })
                         .is_err()
                 {
@@ -285,6 +286,7 @@ impl Connector {
                         table_index,
                         op: Operation::BatchInsert { new: batch },
                         id: None,
+                        source_time: None,
                     })
                     .is_err()
             {
@@ -413,7 +415,7 @@ impl Connector {
                             if ingestor
                                 .blocking_handle_message(IngestionMessage::TransactionInfo(
                                     TransactionInfo::Commit {
-                                        id: Some(OpIdentifier::new(checkpoint, 0)),
+                                        id: Some(OpIdentifier::new(checkpoint, 0).into()),
                                     },
                                 ))
                                 .is_err()
@@ -426,6 +428,7 @@ impl Connector {
                                 table_index,
                                 op,
                                 id: None,
+                                source_time: None,
                             });
                         }
                     }
@@ -17,7 +17,7 @@ pub fn map_row(mut row: HashMap<String, ParsedValue>, schema: &Schema) -> Result
         let value = row
             .remove(&field.name)
             .ok_or_else(|| Error::FieldNotFound(field.name.clone()))?;
-        values.push(map_value(value, field.typ, field.nullable, &field.name)?);
+        values.push(map_value(value, &field.typ, field.nullable, &field.name)?);
     }
 
     Ok(Record::new(values))
@@ -25,7 +25,7 @@ pub fn map_row(mut row: HashMap<String, ParsedValue>, schema: &Schema) -> Result
 
 fn map_value(
     value: ParsedValue,
-    typ: FieldType,
+    typ: &FieldType,
     nullable: bool,
     name: &str,
 ) -> Result<Field, Error> {
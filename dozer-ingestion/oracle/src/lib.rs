@@ -4,7 +4,7 @@ use dozer_ingestion_connector::{
         errors::internal::BoxedError,
         log::info,
         models::ingestion_types::{IngestionMessage, OracleConfig, TransactionInfo},
-        node::OpIdentifier,
+        node::{OpIdentifier, SourcePosition},
         types::FieldType,
     },
     tokio, Connector, Ingestor, SourceSchemaResult, TableIdentifier, TableInfo,
@@ -150,9 +150,23 @@ impl Connector for OracleConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
+        let last_checkpoint = last_checkpoint
+            .as_ref()
+            .map(OpIdentifier::try_from)
+            .transpose()?;
         let checkpoint = if let Some(last_checkpoint) = last_checkpoint {
+            // Replication resumes from the checkpoint, there's no snapshot.
+            if ingestor
+                .handle_message(IngestionMessage::TransactionInfo(
+                    TransactionInfo::SnapshottingSkipped,
+                ))
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
             last_checkpoint.txid
         } else {
             info!("No checkpoint passed, starting snapshotting");
@@ -0,0 +1,303 @@
+use dozer_ingestion_connector::dozer_types::{
+    node::OpIdentifier,
+    types::{Field, FieldType, Operation, Schema},
+};
+
+/// Chunk numbers are clamped to 48 bits, so a snapshot position fits in `OpIdentifier::seq_in_tx`.
+pub const CHUNK_MIN: i64 = -(1 << 47);
+pub const CHUNK_MAX: i64 = (1 << 47) - 1;
+const CHUNK_MASK: u64 = (1 << 48) - 1;
+/// Set in `seq_in_tx` of replication positions while tables added to the replication are snapshotted.
+const INCREMENTAL_FLAG: u64 = 1 << 63;
+
+/// How a table is split into chunks during the snapshot.
+///
+/// Tables with a single integer primary key are split into ranges of `chunk_size` keys, aligned to multiples of `chunk_size`,
+/// so chunk numbers don't depend on the data and stay valid after a restart. Other tables are read as one chunk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableChunking {
+    /// Index of the primary key in the table's fields, and its column name.
+    primary_key: Option<(usize, String)>,
+    chunk_size: i64,
+}
+
+impl TableChunking {
+    pub fn new(schema: &Schema, chunk_size: u64) -> Self {
+        let primary_key = match schema.primary_index.as_slice() {
+            [index] if schema.fields[*index].typ == FieldType::Int => {
+                Some((*index, schema.fields[*index].name.clone()))
+            }
+            _ => None,
+        };
+        Self {
+            primary_key,
+            chunk_size: chunk_size.clamp(1, i64::MAX as u64) as i64,
+        }
+    }
+
+    pub fn primary_key_column(&self) -> Option<&str> {
+        self.primary_key.as_ref().map(|(_, name)| name.as_str())
+    }
+
+    /// Key range of a chunk, as inclusive start and exclusive end. `None` is unbounded.
+    pub fn key_range(&self, chunk: i64) -> (Option<i64>, Option<i64>) {
+        let start = if chunk == CHUNK_MIN {
+            None
+        } else {
+            chunk.checked_mul(self.chunk_size)
+        };
+        let end = if chunk == CHUNK_MAX {
+            None
+        } else {
+            (chunk + 1).checked_mul(self.chunk_size)
+        };
+        (start, end)
+    }
+
+    pub fn chunk_of_key(&self, key: i64) -> i64 {
+        key.div_euclid(self.chunk_size).clamp(CHUNK_MIN, CHUNK_MAX)
+    }
+
+    /// Chunk of a replicated record, which has the same fields as the snapshot.
+    pub fn chunk_of_record(&self, values: &[Field]) -> i64 {
+        match &self.primary_key {
+            Some((index, _)) => match values.get(*index) {
+                Some(Field::Int(key)) => self.chunk_of_key(*key),
+                _ => 0,
+            },
+            None => 0,
+        }
+    }
+}
+
+/// Position in a snapshot: all tables before `table_index` are done, and `table_index` is done up to and including `chunk`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SnapshotPosition {
+    pub table_index: usize,
+    pub chunk: i64,
+}
+
+impl SnapshotPosition {
+    /// Encodes the position in `seq_in_tx`, which is always 0 for replication positions.
+    /// `lsn` is the replication slot's consistent point.
+    pub fn to_op_id(self, lsn: u64) -> OpIdentifier {
+        OpIdentifier::new(lsn, self.encode())
+    }
+
+    /// Returns `None` if `id` is a replication position.
+    pub fn from_op_id(id: OpIdentifier) -> Option<Self> {
+        if id.seq_in_tx == 0 || id.seq_in_tx & INCREMENTAL_FLAG != 0 {
+            return None;
+        }
+        Some(Self::decode(id.seq_in_tx))
+    }
+
+    /// Encodes the position of a snapshot of tables added to the replication, where `chunk` is the next chunk to read.
+    /// `lsn` is the replicated transaction's lsn.
+    pub fn to_incremental_op_id(self, lsn: u64) -> OpIdentifier {
+        OpIdentifier::new(lsn, INCREMENTAL_FLAG | self.encode())
+    }
+
+    /// Returns `None` if no tables were being added to the replication at `id`.
+    pub fn from_incremental_op_id(id: OpIdentifier) -> Option<Self> {
+        (id.seq_in_tx & INCREMENTAL_FLAG != 0)
+            .then(|| Self::decode(id.seq_in_tx & !INCREMENTAL_FLAG))
+    }
+
+    fn encode(self) -> u64 {
+        let table = (self.table_index as u64 + 1) << 48;
+        let chunk = (self.chunk - CHUNK_MIN) as u64 & CHUNK_MASK;
+        table | chunk
+    }
+
+    fn decode(seq_in_tx: u64) -> Self {
+        Self {
+            table_index: ((seq_in_tx >> 48) - 1) as usize,
+            chunk: (seq_in_tx & CHUNK_MASK) as i64 + CHUNK_MIN,
+        }
+    }
+}
+
+/// Filters replicated changes after a snapshot has been resumed.
+///
+/// Chunks up to `position` were read before the restart, at the replication slot's consistent point.
+/// Chunks after it were read at `resume_lsn`, so they already contain changes of transactions committed before `resume_lsn`.
+#[derive(Debug, Clone)]
+pub struct ResumedSnapshotFilter {
+    pub position: SnapshotPosition,
+    pub resume_lsn: u64,
+    pub tables: Vec<TableChunking>,
+}
+
+impl ResumedSnapshotFilter {
+    /// Whether changes of a transaction need filtering.
+    pub fn applies_to(&self, commit_lsn: u64) -> bool {
+        commit_lsn < self.resume_lsn
+    }
+
+    /// Maps an operation of a transaction committed before `resume_lsn` to what the snapshot doesn't contain yet.
+    pub fn filter(&self, table_index: usize, op: Operation) -> Option<Operation> {
+        let is_read_before = |values: &[Field]| {
+            let chunk = self.tables[table_index].chunk_of_record(values);
+            SnapshotPosition { table_index, chunk } <= self.position
+        };
+        match op {
+            Operation::Insert { ref new } => is_read_before(&new.values).then_some(op),
+            Operation::Delete { ref old } => is_read_before(&old.values).then_some(op),
+            Operation::Update { old, new } => {
+                match (is_read_before(&old.values), is_read_before(&new.values)) {
+                    (true, true) => Some(Operation::Update { old, new }),
+                    // The key moved to a chunk that was read after the change.
+                    (true, false) => Some(Operation::Delete { old }),
+                    // The key moved from a chunk that was read after the change.
+                    (false, true) => Some(Operation::Insert { new }),
+                    (false, false) => None,
+                }
+            }
+            Operation::BatchInsert { new } => {
+                let new = new
+                    .into_iter()
+                    .filter(|record| is_read_before(&record.values))
+                    .collect::<Vec<_>>();
+                (!new.is_empty()).then_some(Operation::BatchInsert { new })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_ingestion_connector::dozer_types::types::{
+        FieldDefinition, Record, SourceDefinition,
+    };
+
+    use super::*;
+
+    fn schema(typ: FieldType) -> Schema {
+        let mut schema = Schema::new();
+        schema
+            .field(
+                FieldDefinition::new("id".to_string(), typ, false, SourceDefinition::Dynamic),
+                true,
+            )
+            .field(
+                FieldDefinition::new(
+                    "name".to_string(),
+                    FieldType::String,
+                    true,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            );
+        schema
+    }
+
+    fn record(id: i64) -> Record {
+        Record::new(vec![Field::Int(id), Field::Null])
+    }
+
+    #[test]
+    fn test_chunks() {
+        let chunking = TableChunking::new(&schema(FieldType::Int), 10);
+        assert_eq!(chunking.primary_key_column(), Some("id"));
+        assert_eq!(chunking.chunk_of_key(-5), -1);
+        assert_eq!(chunking.chunk_of_key(25), 2);
+        assert_eq!(chunking.key_range(-1), (Some(-10), Some(0)));
+        assert_eq!(chunking.key_range(2), (Some(20), Some(30)));
+
+        let chunking = TableChunking::new(&schema(FieldType::Int), 1);
+        assert_eq!(chunking.chunk_of_key(i64::MIN), CHUNK_MIN);
+        assert_eq!(chunking.chunk_of_key(i64::MAX), CHUNK_MAX);
+        assert_eq!(chunking.key_range(CHUNK_MIN), (None, Some(CHUNK_MIN + 1)));
+        assert_eq!(chunking.key_range(CHUNK_MAX), (Some(CHUNK_MAX), None));
+
+        let chunking = TableChunking::new(&schema(FieldType::String), 10);
+        assert_eq!(chunking.primary_key_column(), None);
+    }
+
+    #[test]
+    fn test_snapshot_position_op_id() {
+        for position in [
+            SnapshotPosition {
+                table_index: 0,
+                chunk: 0,
+            },
+            SnapshotPosition {
+                table_index: 3,
+                chunk: CHUNK_MIN,
+            },
+            SnapshotPosition {
+                table_index: 32766,
+                chunk: CHUNK_MAX,
+            },
+        ] {
+            let id = position.to_op_id(42);
+            assert_eq!(id.txid, 42);
+            assert_ne!(id.seq_in_tx, 0);
+            assert_eq!(SnapshotPosition::from_op_id(id), Some(position));
+            assert_eq!(SnapshotPosition::from_incremental_op_id(id), None);
+
+            let id = position.to_incremental_op_id(42);
+            assert_eq!(SnapshotPosition::from_incremental_op_id(id), Some(position));
+            assert_eq!(SnapshotPosition::from_op_id(id), None);
+        }
+        let id = OpIdentifier::new(42, 0);
+        assert_eq!(SnapshotPosition::from_op_id(id), None);
+        assert_eq!(SnapshotPosition::from_incremental_op_id(id), None);
+    }
+
+    #[test]
+    fn test_resumed_snapshot_filter() {
+        let filter = ResumedSnapshotFilter {
+            position: SnapshotPosition {
+                table_index: 0,
+                chunk: 1,
+            },
+            resume_lsn: 100,
+            tables: vec![
+                TableChunking::new(&schema(FieldType::Int), 10),
+                TableChunking::new(&schema(FieldType::String), 10),
+            ],
+        };
+        assert!(filter.applies_to(99));
+        assert!(!filter.applies_to(100));
+
+        let insert = |id| Operation::Insert { new: record(id) };
+        assert_eq!(filter.filter(0, insert(19)), Some(insert(19)));
+        assert_eq!(filter.filter(0, insert(20)), None);
+        // Tables after the position haven't been read before the restart.
+        assert_eq!(filter.filter(1, insert(0)), None);
+
+        assert_eq!(
+            filter.filter(
+                0,
+                Operation::Update {
+                    old: record(5),
+                    new: record(25)
+                }
+            ),
+            Some(Operation::Delete { old: record(5) })
+        );
+        assert_eq!(
+            filter.filter(
+                0,
+                Operation::Update {
+                    old: record(25),
+                    new: record(5)
+                }
+            ),
+            Some(Operation::Insert { new: record(5) })
+        );
+        assert_eq!(
+            filter.filter(
+                0,
+                Operation::BatchInsert {
+                    new: vec![record(1), record(21)]
+                }
+            ),
+            Some(Operation::BatchInsert {
+                new: vec![record(1)]
+            })
+        );
+    }
+}
@@ -99,6 +99,19 @@ impl Client {
         Ok(Box::pin(row_stream))
     }
 
+    /// Unlike `query_raw`, runs the query on this connection, so it sees the open transaction.
+    /// The stream can't be resumed after a network failure, because the transaction is lost with the connection.
+    pub async fn query_raw_in_transaction(
+        &mut self,
+        statement: &Statement,
+    ) -> Result<BoxStream<'static, Result<Row, tokio_postgres::Error>>, tokio_postgres::Error> {
+        let row_stream = self
+            .inner
+            .query_raw(statement, Vec::<String>::new())
+            .await?;
+        Ok(Box::pin(row_stream))
+    }
+
     pub async fn copy_both_simple<T>(
         &mut self,
         query: &str,
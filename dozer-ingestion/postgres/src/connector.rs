@@ -1,7 +1,10 @@
-use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
+use dozer_ingestion_connector::dozer_types::node::{OpIdentifier, SourcePosition};
 use dozer_ingestion_connector::{
     async_trait,
-    dozer_types::{errors::internal::BoxedError, types::FieldType},
+    dozer_types::{
+        errors::internal::BoxedError,
+        types::{FieldType, Record},
+    },
     utils::ListOrFilterColumns,
     Connector, Ingestor, SourceSchemaResult, TableIdentifier, TableInfo,
 };
@@ -15,6 +18,8 @@ use crate::{
     connection::validator::validate_connection,
     iterator::PostgresIterator,
     schema::helper::{SchemaHelper, DEFAULT_SCHEMA_NAME},
+    snapshotter::PostgresSnapshotter,
+    watermark::{create_watermark_table, WatermarkTable},
     PostgresConnectorError,
 };
 
@@ -27,6 +32,10 @@ pub struct PostgresConfig {
     pub config: Config,
     pub schema: Option<String>,
     pub batch_size: usize,
+    pub snapshot_chunk_size: u64,
+    pub snapshot_parallelism: usize,
+    /// Set if tables added to a running replication are snapshotted.
+    pub watermark_table: Option<WatermarkTable>,
 }
 
 #[derive(Debug)]
@@ -38,6 +47,9 @@ pub struct PostgresConnector {
     schema_helper: SchemaHelper,
     pub schema: Option<String>,
     batch_size: usize,
+    snapshot_chunk_size: u64,
+    snapshot_parallelism: usize,
+    watermark_table: Option<WatermarkTable>,
 }
 
 #[derive(Debug)]
@@ -74,6 +86,9 @@ impl PostgresConnector {
             schema_helper: helper,
             schema: config.schema,
             batch_size: config.batch_size,
+            snapshot_chunk_size: config.snapshot_chunk_size,
+            snapshot_parallelism: config.snapshot_parallelism,
+            watermark_table: config.watermark_table,
         })
     }
 }
@@ -171,15 +186,28 @@ impl Connector for PostgresConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
-        let lsn = last_checkpoint.map(|checkpoint| checkpoint.txid.into());
+        let last_checkpoint = last_checkpoint
+            .as_ref()
+            .map(OpIdentifier::try_from)
+            .transpose()?;
+        if last_checkpoint.is_none() {
+            if let Some(watermark_table) = &self.watermark_table {
+                let mut client = helper::connect(self.conn_config.clone()).await?;
+                create_watermark_table(&mut client, watermark_table).await?;
+            }
 
-        if lsn.is_none() {
             let client = helper::connect(self.replication_conn_config.clone()).await?;
             let table_identifiers = tables
                 .iter()
                 .map(|table| TableIdentifier::new(table.schema.clone(), table.name.clone()))
+                .chain(self.watermark_table.iter().map(|watermark_table| {
+                    TableIdentifier::new(
+                        Some(watermark_table.schema.clone()),
+                        watermark_table.name.clone(),
+                    )
+                }))
                 .collect::<Vec<_>>();
             create_publication(client, &self.name, Some(&table_identifiers)).await?;
         }
@@ -202,8 +230,34 @@ impl Connector for PostgresConnector {
             self.conn_config.clone(),
             self.schema.clone(),
             self.batch_size,
+            self.snapshot_chunk_size,
+            self.snapshot_parallelism,
+            self.watermark_table.clone(),
         );
-        iterator.start(lsn).await.map_err(Into::into)
+        iterator.start(last_checkpoint).await.map_err(Into::into)
+    }
+
+    async fn sample(
+        &mut self,
+        tables: Vec<TableInfo>,
+        limit: usize,
+    ) -> Result<Option<Vec<Vec<Record>>>, BoxedError> {
+        let tables = tables
+            .into_iter()
+            .map(|table| ListOrFilterColumns {
+                schema: table.schema,
+                name: table.name,
+                columns: Some(table.column_names),
+            })
+            .collect::<Vec<_>>();
+        let samples = PostgresSnapshotter::sample_tables(
+            self.conn_config.clone(),
+            self.schema.clone(),
+            &tables,
+            limit,
+        )
+        .await?;
+        Ok(Some(samples))
     }
 }
 
@@ -11,14 +11,23 @@ use dozer_ingestion_connector::dozer_types::{
 use postgres_types::{FromSql, Type, WasNull};
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::Decimal;
+use std::collections::HashMap;
 use std::error::Error;
 use std::num::ParseIntError;
 
 use dozer_ingestion_connector::dozer_types::chrono::{LocalResult, NaiveTime};
 use std::vec;
-use tokio_postgres::{Column, Row};
+use tokio_postgres::{Column, Row, Statement};
 use uuid::Uuid;
 
+use crate::connection::client::Client;
+use crate::schema::helper::get_money_format;
+use crate::type_conversion::{
+    base_type, decode_inet, decode_interval, decode_macaddr, decode_time, decode_timetz,
+    hstore_to_json, is_enum, is_hstore, parse_array, parse_bool_element, parse_decimal_element,
+    parse_float_element, parse_hstore, parse_int_element, parse_interval, parse_text_element,
+    parse_time, parse_timetz, MoneyFormat, Raw, Unwrapped,
+};
 use crate::DateConversionError::{AmbiguousTimeResult, InvalidDate, InvalidTime};
 use crate::{
     xlog_mapper::TableColumn, DateConversionError, PostgresConnectorError, PostgresSchemaError,
@@ -95,11 +104,13 @@ fn convert_date_with_timezone(date: String) -> Result<DateTime<FixedOffset>, Dat
     }
 }
 
+/// `money` is the server's `money` format.
 pub fn postgres_type_to_field(
     value: Option<&Bytes>,
     column: &TableColumn,
+    money: &MoneyFormat,
 ) -> Result<Field, PostgresSchemaError> {
-    let column_type = column.r#type.clone();
+    let column_type = base_type(&column.r#type).clone();
     value.map_or(Ok(Field::Null), |v| match column_type {
         Type::INT2 | Type::INT4 | Type::INT8 => Ok(Field::Int(
             String::from_utf8(v.to_vec()).unwrap().parse().unwrap(),
@@ -172,33 +183,129 @@ pub fn postgres_type_to_field(
                 .parse::<DozerPoint>()
                 .map_err(|_| PostgresSchemaError::PointParseError)?,
         )),
+        Type::TIME => Ok(Field::Duration(DozerDuration(
+            parse_time(&String::from_utf8(v.to_vec())?)?,
+            TimeUnit::Microseconds,
+        ))),
+        Type::TIMETZ => Ok(Field::String(parse_timetz(&String::from_utf8(
+            v.to_vec(),
+        )?)?)),
+        Type::INTERVAL => Ok(Field::String(parse_interval(&String::from_utf8(
+            v.to_vec(),
+        )?)?)),
+        Type::INET | Type::CIDR | Type::MACADDR | Type::MACADDR8 => {
+            Ok(Field::String(String::from_utf8(v.to_vec())?))
+        }
+        Type::MONEY => Ok(Field::Decimal(
+            money.parse(&String::from_utf8(v.to_vec())?)?,
+        )),
+        Type::INT2_ARRAY | Type::INT4_ARRAY | Type::INT8_ARRAY => {
+            array_to_field(v, parse_int_element, |value| {
+                value.as_i64().map_or(Field::Null, Field::Int)
+            })
+        }
+        Type::FLOAT4_ARRAY | Type::FLOAT8_ARRAY => {
+            array_to_field(v, parse_float_element, |value| {
+                value.as_f64().map_or(Field::Null, Field::from)
+            })
+        }
+        Type::NUMERIC_ARRAY => array_to_field(v, parse_decimal_element, |value| {
+            value
+                .as_str()
+                .and_then(|text| text.parse().ok())
+                .map_or(Field::Null, Field::Decimal)
+        }),
+        Type::BOOL_ARRAY => array_to_field(v, parse_bool_element, |value| {
+            value.as_bool().map_or(Field::Null, Field::Boolean)
+        }),
+        Type::TEXT_ARRAY | Type::CHAR_ARRAY | Type::VARCHAR_ARRAY | Type::BPCHAR_ARRAY => {
+            array_to_field(v, parse_text_element, |value| match value {
+                serde_json::Value::String(text) => Field::String(text),
+                _ => Field::Null,
+            })
+        }
+        _ if is_enum(&column_type) => Ok(Field::String(String::from_utf8(v.to_vec())?)),
+        _ if is_hstore(&column_type) => {
+            json_to_field(parse_hstore(&String::from_utf8(v.to_vec())?)?)
+        }
         _ => Err(PostgresSchemaError::ColumnTypeNotSupported(
             column_type.name().to_string(),
         )),
     })
 }
 
+fn array_to_field(
+    value: &Bytes,
+    parse_element: fn(&str) -> Result<serde_json::Value, PostgresSchemaError>,
+    to_field: fn(serde_json::Value) -> Field,
+) -> Result<Field, PostgresSchemaError> {
+    let array = parse_array(&String::from_utf8(value.to_vec())?, parse_element)?;
+    let mut elements = vec![];
+    flatten_parsed_array(array, to_field, &mut elements);
+    Ok(Field::List(elements))
+}
+
+/// Multidimensional arrays are flattened in row-major order, as `unnest` does,
+/// so that every element has the array's element type.
+fn flatten_parsed_array(
+    value: serde_json::Value,
+    to_field: fn(serde_json::Value) -> Field,
+    elements: &mut Vec<Field>,
+) {
+    match value {
+        serde_json::Value::Array(values) => {
+            for value in values {
+                flatten_parsed_array(value, to_field, elements);
+            }
+        }
+        serde_json::Value::Null => elements.push(Field::Null),
+        element => elements.push(to_field(element)),
+    }
+}
+
+fn json_to_field(value: serde_json::Value) -> Result<Field, PostgresSchemaError> {
+    serde_json_to_json_value(value)
+        .map(Field::Json)
+        .map_err(|e| PostgresSchemaError::TypeError(TypeError::DeserializationError(e)))
+}
+
 pub fn postgres_type_to_dozer_type(column_type: Type) -> Result<FieldType, PostgresSchemaError> {
-    match column_type {
-        Type::BOOL => Ok(FieldType::Boolean),
-        Type::INT2 | Type::INT4 | Type::INT8 => Ok(FieldType::Int),
-        Type::CHAR | Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::UUID | Type::ANYENUM => {
-            Ok(FieldType::String)
+    match base_type(&column_type) {
+        &Type::BOOL => Ok(FieldType::Boolean),
+        &Type::INT2 | &Type::INT4 | &Type::INT8 => Ok(FieldType::Int),
+        &Type::CHAR
+        | &Type::TEXT
+        | &Type::VARCHAR
+        | &Type::BPCHAR
+        | &Type::UUID
+        | &Type::ANYENUM
+        | &Type::TIMETZ
+        | &Type::INTERVAL
+        | &Type::INET
+        | &Type::CIDR
+        | &Type::MACADDR
+        | &Type::MACADDR8 => Ok(FieldType::String),
+        &Type::FLOAT4 | &Type::FLOAT8 => Ok(FieldType::Float),
+        &Type::BYTEA => Ok(FieldType::Binary),
+        &Type::TIMESTAMP | &Type::TIMESTAMPTZ => Ok(FieldType::Timestamp),
+        &Type::NUMERIC | &Type::MONEY => Ok(FieldType::Decimal),
+        &Type::JSONB | &Type::JSON | &Type::JSONB_ARRAY | &Type::JSON_ARRAY => Ok(FieldType::Json),
+        &Type::TEXT_ARRAY | &Type::CHAR_ARRAY | &Type::VARCHAR_ARRAY | &Type::BPCHAR_ARRAY => {
+            Ok(FieldType::List(Box::new(FieldType::String)))
+        }
+        &Type::INT2_ARRAY | &Type::INT4_ARRAY | &Type::INT8_ARRAY => {
+            Ok(FieldType::List(Box::new(FieldType::Int)))
         }
-        Type::FLOAT4 | Type::FLOAT8 => Ok(FieldType::Float),
-        Type::BYTEA => Ok(FieldType::Binary),
-        Type::TIMESTAMP | Type::TIMESTAMPTZ => Ok(FieldType::Timestamp),
-        Type::NUMERIC => Ok(FieldType::Decimal),
-        Type::JSONB
-        | Type::JSON
-        | Type::JSONB_ARRAY
-        | Type::JSON_ARRAY
-        | Type::TEXT_ARRAY
-        | Type::CHAR_ARRAY
-        | Type::VARCHAR_ARRAY
-        | Type::BPCHAR_ARRAY => Ok(FieldType::Json),
-        Type::DATE => Ok(FieldType::Date),
-        Type::POINT => Ok(FieldType::Point),
+        &Type::FLOAT4_ARRAY | &Type::FLOAT8_ARRAY => {
+            Ok(FieldType::List(Box::new(FieldType::Float)))
+        }
+        &Type::NUMERIC_ARRAY => Ok(FieldType::List(Box::new(FieldType::Decimal))),
+        &Type::BOOL_ARRAY => Ok(FieldType::List(Box::new(FieldType::Boolean))),
+        &Type::DATE => Ok(FieldType::Date),
+        &Type::POINT => Ok(FieldType::Point),
+        &Type::TIME => Ok(FieldType::Duration),
+        typ if is_enum(typ) => Ok(FieldType::String),
+        typ if is_hstore(typ) => Ok(FieldType::Json),
         _ => Err(PostgresSchemaError::ColumnTypeNotSupported(
             column_type.name().to_string(),
         )),
@@ -220,7 +327,22 @@ fn handle_error(e: tokio_postgres::error::Error) -> Result<Field, PostgresSchema
 macro_rules! conversion_fn {
     ($name:ident, $typ:expr) => {
         fn $name(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
-            row.try_get(idx).map($typ).or_else(handle_error)
+            row.try_get(idx)
+                .map(|Unwrapped(v)| ($typ)(v))
+                .or_else(handle_error)
+        }
+    };
+}
+
+/// Conversion of types that `FromSql` doesn't decode.
+macro_rules! raw_conversion_fn {
+    ($name:ident, $decode:expr) => {
+        fn $name(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
+            match row.try_get::<_, Option<Raw>>(idx) {
+                Ok(Some(Raw(raw))) => ($decode)(raw),
+                Ok(None) => Ok(Field::Null),
+                Err(e) => handle_error(e),
+            }
         }
     };
 }
@@ -242,7 +364,7 @@ fn convert_int<'a, T: Into<i64> + FromSql<'a>>(
     idx: usize,
 ) -> Result<Field, PostgresSchemaError> {
     row.try_get(idx)
-        .map(|v: T| Field::Int(v.into()))
+        .map(|Unwrapped(v): Unwrapped<T>| Field::Int(v.into()))
         .or_else(handle_error)
 }
 
@@ -260,16 +382,16 @@ conversion_fn!(convert_float, |v: f32| Field::Float(OrderedFloat(v.into())));
 conversion_fn!(convert_double, |v| Field::Float(OrderedFloat(v)));
 
 fn convert_json(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
-    let value: Result<serde_json::Value, _> = row.try_get(idx);
-    value.map_or_else(handle_error, |val| {
+    let value: Result<Unwrapped<serde_json::Value>, _> = row.try_get(idx);
+    value.map_or_else(handle_error, |Unwrapped(val)| {
         Ok(Field::Json(serde_json_to_json_value(val).map_err(|e| {
             PostgresSchemaError::TypeError(TypeError::DeserializationError(e))
         })?))
     })
 }
 fn convert_jsonarray(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
-    let value: Result<Vec<serde_json::Value>, _> = row.try_get(idx);
-    value.map_or_else(handle_error, |val| {
+    let value: Result<Unwrapped<Vec<serde_json::Value>>, _> = row.try_get(idx);
+    value.map_or_else(handle_error, |Unwrapped(val)| {
         let field = val
             .into_iter()
             .map(serde_json_to_json_value)
@@ -279,19 +401,122 @@ fn convert_jsonarray(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError
     })
 }
 fn convert_textarray(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
-    let value: Result<Vec<String>, _> = row.try_get(idx);
-    value.map_or_else(handle_error, |val| Ok(Field::Json(val.into())))
+    convert_array::<String>(row, idx)
+}
+
+fn convert_array<'a, T: FromSql<'a> + Into<Field>>(
+    row: &'a Row,
+    idx: usize,
+) -> Result<Field, PostgresSchemaError> {
+    let value: Result<Unwrapped<Vec<Option<T>>>, _> = row.try_get(idx);
+    value.map_or_else(handle_error, |Unwrapped(val)| {
+        Ok(Field::List(
+            val.into_iter()
+                .map(|element| element.map_or(Field::Null, Into::into))
+                .collect(),
+        ))
+    })
+}
+
+fn convert_int2array(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
+    convert_array::<i16>(row, idx)
+}
+fn convert_int4array(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
+    convert_array::<i32>(row, idx)
+}
+fn convert_int8array(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
+    convert_array::<i64>(row, idx)
+}
+fn convert_float4array(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
+    convert_array::<f32>(row, idx)
+}
+fn convert_float8array(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
+    convert_array::<f64>(row, idx)
+}
+fn convert_boolarray(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
+    convert_array::<bool>(row, idx)
+}
+fn convert_numericarray(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
+    convert_array::<Decimal>(row, idx)
+}
+
+fn convert_hstore(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
+    let value: Result<Unwrapped<HashMap<String, Option<String>>>, _> = row.try_get(idx);
+    value.map_or_else(handle_error, |Unwrapped(val)| {
+        json_to_field(hstore_to_json(val))
+    })
 }
 
 fn convert_uuid(row: &Row, idx: usize) -> Result<Field, PostgresSchemaError> {
-    let value: Result<Uuid, _> = row.try_get(idx);
-    value.map_or_else(handle_error, |val| Ok(Field::from(val.to_string())))
+    let value: Result<Unwrapped<Uuid>, _> = row.try_get(idx);
+    value.map_or_else(handle_error, |Unwrapped(val)| {
+        Ok(Field::from(val.to_string()))
+    })
+}
+
+fn utf8_to_string(raw: &[u8]) -> Result<String, PostgresSchemaError> {
+    String::from_utf8(raw.to_vec()).map_err(PostgresSchemaError::StringParseError)
 }
 
-type ConversionFn = fn(&Row, usize) -> Result<Field, PostgresSchemaError>;
+raw_conversion_fn!(convert_enum, |raw| utf8_to_string(raw).map(Field::String));
+raw_conversion_fn!(convert_time, |raw| decode_time(raw)
+    .map(|v| Field::Duration(DozerDuration(v, TimeUnit::Microseconds))));
+raw_conversion_fn!(convert_timetz, |raw| decode_timetz(raw).map(Field::String));
+raw_conversion_fn!(convert_interval, |raw| decode_interval(raw)
+    .map(Field::String));
+raw_conversion_fn!(convert_inet, |raw| decode_inet(raw, false)
+    .map(Field::String));
+raw_conversion_fn!(convert_cidr, |raw| decode_inet(raw, true)
+    .map(Field::String));
+raw_conversion_fn!(convert_macaddr, |raw| decode_macaddr(raw)
+    .map(Field::String));
+
+pub type ConversionFn =
+    Box<dyn Fn(&Row, usize) -> Result<Field, PostgresSchemaError> + Send + Sync>;
+
+/// The conversions of the columns of `statement`. The server's `money` format is only looked up if it's needed.
+pub async fn get_conversions(
+    client: &mut Client,
+    statement: &Statement,
+) -> Result<Vec<ConversionFn>, PostgresConnectorError> {
+    let columns = statement.columns();
+    let money = if columns
+        .iter()
+        .any(|column| base_type(column.type_()) == &Type::MONEY)
+    {
+        get_money_format(client).await?
+    } else {
+        MoneyFormat::default()
+    };
+    Ok(columns
+        .iter()
+        .map(|column| get_conversion_fn(column.type_(), &money))
+        .collect::<Result<_, _>>()?)
+}
+
+/// `money` is the server's `money` format.
+pub fn get_conversion_fn(
+    col_type: &Type,
+    money: &MoneyFormat,
+) -> Result<ConversionFn, PostgresSchemaError> {
+    if base_type(col_type) == &Type::MONEY {
+        let money = money.clone();
+        return Ok(Box::new(move |row: &Row, idx: usize| {
+            match row.try_get::<_, Option<Raw>>(idx) {
+                Ok(Some(Raw(raw))) => money.decode(raw).map(Field::Decimal),
+                Ok(None) => Ok(Field::Null),
+                Err(e) => handle_error(e),
+            }
+        }));
+    }
+    get_plain_conversion_fn(col_type).map(|convert| Box::new(convert) as ConversionFn)
+}
 
-pub fn get_conversion_fn(col_type: &Type) -> Result<ConversionFn, PostgresSchemaError> {
-    match col_type {
+/// Conversions that don't depend on the server's settings.
+fn get_plain_conversion_fn(
+    col_type: &Type,
+) -> Result<fn(&Row, usize) -> Result<Field, PostgresSchemaError>, PostgresSchemaError> {
+    match base_type(col_type) {
         &Type::BOOL => Ok(convert_bool),
         &Type::INT2 => Ok(convert_int2),
         &Type::INT4 => Ok(convert_int4),
@@ -314,6 +539,21 @@ pub fn get_conversion_fn(col_type: &Type) -> Result<ConversionFn, PostgresSchema
         &Type::POINT => Ok(convert_point),
         // &Type::UUID => convert_row_value_to_field!(row, idx, Uuid),
         &Type::UUID => Ok(convert_uuid),
+        &Type::TIME => Ok(convert_time),
+        &Type::TIMETZ => Ok(convert_timetz),
+        &Type::INTERVAL => Ok(convert_interval),
+        &Type::INET => Ok(convert_inet),
+        &Type::CIDR => Ok(convert_cidr),
+        &Type::MACADDR | &Type::MACADDR8 => Ok(convert_macaddr),
+        &Type::INT2_ARRAY => Ok(convert_int2array),
+        &Type::INT4_ARRAY => Ok(convert_int4array),
+        &Type::INT8_ARRAY => Ok(convert_int8array),
+        &Type::FLOAT4_ARRAY => Ok(convert_float4array),
+        &Type::FLOAT8_ARRAY => Ok(convert_float8array),
+        &Type::NUMERIC_ARRAY => Ok(convert_numericarray),
+        &Type::BOOL_ARRAY => Ok(convert_boolarray),
+        typ if is_enum(typ) => Ok(convert_enum),
+        typ if is_hstore(typ) => Ok(convert_hstore),
         _ => {
             if col_type.schema() == "pg_catalog" {
                 Err(PostgresSchemaError::ColumnTypeNotSupported(
@@ -369,6 +609,7 @@ pub fn convert_column_to_field(column: &Column) -> Result<FieldDefinition, Postg
 mod tests {
     use super::*;
     use dozer_ingestion_connector::dozer_types::{chrono::NaiveDate, json_types::json};
+    use postgres_types::Kind;
 
     #[macro_export]
     macro_rules! test_conversion {
@@ -381,6 +622,7 @@ mod tests {
                     r#type: $b,
                     column_index: 0,
                 },
+                &MoneyFormat::default(),
             );
             assert_eq!(value.unwrap(), $c);
         };
@@ -501,6 +743,93 @@ mod tests {
             Type::POINT,
             Field::Point(DozerPoint::from((1.234, 2.456)))
         );
+
+        test_conversion!(
+            "04:05:06.5",
+            Type::TIME,
+            Field::Duration(DozerDuration(
+                std::time::Duration::from_millis(14_706_500),
+                TimeUnit::Microseconds
+            ))
+        );
+        test_conversion!(
+            "04:05:06+02",
+            Type::TIMETZ,
+            Field::String("04:05:06+02".to_string())
+        );
+        test_conversion!(
+            "-1 days +01:00:00",
+            Type::INTERVAL,
+            Field::String("-1 days +01:00:00".to_string())
+        );
+        test_conversion!(
+            "192.168.0.0/24",
+            Type::CIDR,
+            Field::String("192.168.0.0/24".to_string())
+        );
+        test_conversion!(
+            "$1,000.50",
+            Type::MONEY,
+            Field::Decimal(Decimal::new(100_050, 2))
+        );
+        test_conversion!(
+            "{1,NULL,3}",
+            Type::INT4_ARRAY,
+            Field::List(vec![Field::Int(1), Field::Null, Field::Int(3)])
+        );
+        test_conversion!(
+            "{1.5,NULL,0.1000000000000000000001}",
+            Type::NUMERIC_ARRAY,
+            Field::List(vec![
+                Field::Decimal(Decimal::new(15, 1)),
+                Field::Null,
+                Field::Decimal("0.1000000000000000000001".parse().unwrap())
+            ])
+        );
+        test_conversion!(
+            "{{1,2},{3,NULL}}",
+            Type::INT8_ARRAY,
+            Field::List(vec![
+                Field::Int(1),
+                Field::Int(2),
+                Field::Int(3),
+                Field::Null
+            ])
+        );
+        test_conversion!(
+            "{a,\"b c\"}",
+            Type::TEXT_ARRAY,
+            Field::List(vec![
+                Field::String("a".to_string()),
+                Field::String("b c".to_string())
+            ])
+        );
+
+        let domain = Type::new(
+            "positive".to_string(),
+            100_000,
+            Kind::Domain(Type::INT4),
+            "public".to_string(),
+        );
+        test_conversion!("5", domain, Field::Int(5));
+        let enum_type = Type::new(
+            "mood".to_string(),
+            100_001,
+            Kind::Enum(vec![]),
+            "public".to_string(),
+        );
+        test_conversion!("happy", enum_type, Field::String("happy".to_string()));
+        let hstore = Type::new(
+            "hstore".to_string(),
+            100_002,
+            Kind::Simple,
+            "public".to_string(),
+        );
+        test_conversion!(
+            "\"a\"=>\"1\", \"b\"=>NULL",
+            hstore,
+            Field::Json(json!({"a": "1", "b": null}))
+        );
     }
 
     #[test]
@@ -520,6 +849,48 @@ mod tests {
         test_type_mapping!(Type::JSON_ARRAY, FieldType::Json);
         test_type_mapping!(Type::BOOL, FieldType::Boolean);
         test_type_mapping!(Type::POINT, FieldType::Point);
+        test_type_mapping!(Type::TIME, FieldType::Duration);
+        test_type_mapping!(Type::TIMETZ, FieldType::String);
+        test_type_mapping!(Type::INTERVAL, FieldType::String);
+        test_type_mapping!(Type::INT8_ARRAY, FieldType::List(Box::new(FieldType::Int)));
+        test_type_mapping!(
+            Type::NUMERIC_ARRAY,
+            FieldType::List(Box::new(FieldType::Decimal))
+        );
+        test_type_mapping!(
+            Type::TEXT_ARRAY,
+            FieldType::List(Box::new(FieldType::String))
+        );
+        test_type_mapping!(Type::INET, FieldType::String);
+        test_type_mapping!(Type::MACADDR, FieldType::String);
+        test_type_mapping!(Type::MONEY, FieldType::Decimal);
+        test_type_mapping!(
+            Type::new(
+                "positive".to_string(),
+                100_000,
+                Kind::Domain(Type::INT4),
+                "public".to_string()
+            ),
+            FieldType::Int
+        );
+        test_type_mapping!(
+            Type::new(
+                "mood".to_string(),
+                100_001,
+                Kind::Enum(vec![]),
+                "public".to_string()
+            ),
+            FieldType::String
+        );
+        test_type_mapping!(
+            Type::new(
+                "hstore".to_string(),
+                100_002,
+                Kind::Simple,
+                "public".to_string()
+            ),
+            FieldType::Json
+        );
     }
 
     #[test]
@@ -532,6 +903,7 @@ mod tests {
                 r#type: Type::VARCHAR,
                 column_index: 0,
             },
+            &MoneyFormat::default(),
         );
         assert_eq!(value.unwrap(), Field::Null);
     }
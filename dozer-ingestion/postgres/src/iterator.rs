@@ -1,19 +1,25 @@
 use std::str::FromStr;
 use std::sync::Arc;
 
-use dozer_ingestion_connector::dozer_types::log::debug;
+use dozer_ingestion_connector::dozer_types::log::{debug, warn};
+use dozer_ingestion_connector::dozer_types::models::ingestion_types::{
+    IngestionMessage, TransactionInfo,
+};
+use dozer_ingestion_connector::dozer_types::node::OpIdentifier;
 use dozer_ingestion_connector::utils::ListOrFilterColumns;
 use dozer_ingestion_connector::Ingestor;
 use postgres_types::PgLsn;
 
+use crate::chunk::{ResumedSnapshotFilter, SnapshotPosition, TableChunking};
 use crate::connection::helper;
 use crate::connector::REPLICATION_SLOT_PREFIX;
 use crate::replication_slot_helper::ReplicationSlotHelper;
 use crate::replicator::CDCHandler;
-use crate::snapshotter::PostgresSnapshotter;
+use crate::snapshotter::{PostgresSnapshotter, SnapshotOptions};
+use crate::watermark::{self, IncrementalSnapshot, IncrementalTable, WatermarkTable};
 use crate::PostgresConnectorError;
 
-use super::schema::helper::PostgresTableInfo;
+use super::schema::helper::{PostgresTableInfo, SchemaHelper};
 
 pub struct Details {
     name: String,
@@ -24,6 +30,9 @@ pub struct Details {
     conn_config: tokio_postgres::Config,
     schema: Option<String>,
     batch_size: usize,
+    snapshot_chunk_size: u64,
+    snapshot_parallelism: usize,
+    watermark_table: Option<WatermarkTable>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -50,6 +59,9 @@ impl<'a> PostgresIterator<'a> {
         conn_config: tokio_postgres::Config,
         schema: Option<String>,
         batch_size: usize,
+        snapshot_chunk_size: u64,
+        snapshot_parallelism: usize,
+        watermark_table: Option<WatermarkTable>,
     ) -> Self {
         let details = Arc::new(Details {
             name,
@@ -60,13 +72,19 @@ impl<'a> PostgresIterator<'a> {
             conn_config,
             schema,
             batch_size,
+            snapshot_chunk_size,
+            snapshot_parallelism,
+            watermark_table,
         });
         PostgresIterator { details, ingestor }
     }
 }
 
 impl<'a> PostgresIterator<'a> {
-    pub async fn start(self, lsn: Option<PgLsn>) -> Result<(), PostgresConnectorError> {
+    pub async fn start(
+        self,
+        last_checkpoint: Option<OpIdentifier>,
+    ) -> Result<(), PostgresConnectorError> {
         let state = ReplicationState::Pending;
         let details = self.details.clone();
 
@@ -74,7 +92,10 @@ impl<'a> PostgresIterator<'a> {
             details,
             ingestor: self.ingestor,
             state,
-            lsn,
+            lsn: last_checkpoint.map(|checkpoint| checkpoint.txid.into()),
+            snapshot_position: last_checkpoint.and_then(SnapshotPosition::from_op_id),
+            incremental_position: last_checkpoint
+                .and_then(SnapshotPosition::from_incremental_op_id),
         };
         stream_inner.start().await
     }
@@ -83,6 +104,10 @@ impl<'a> PostgresIterator<'a> {
 pub struct PostgresIteratorHandler<'a> {
     pub details: Arc<Details>,
     pub lsn: Option<PgLsn>,
+    /// Set if the connector restarted during the snapshot.
+    pub snapshot_position: Option<SnapshotPosition>,
+    /// Set if the connector restarted while snapshotting tables added to the replication.
+    pub incremental_position: Option<SnapshotPosition>,
     pub state: ReplicationState,
     pub ingestor: &'a Ingestor,
 }
@@ -91,14 +116,17 @@ impl<'a> PostgresIteratorHandler<'a> {
     /*
      Replication involves 3 states
         1) Pending
-        - Initialize a replication slot.
+        - Initialize a replication slot, exporting its snapshot.
         - Initialize snapshots
 
         2) SnapshotInProgress
-        - Sync initial snapshots of specified tables
+        - Sync initial snapshots of specified tables in chunks
+        - Commit every chunk with slot lsn and chunk position
         - Commit with lsn
+        - On restart, sync remaining chunks in a new snapshot and filter the replicated changes it contains
 
         3) Replicating
+        - Snapshot tables added since the last run between watermarks, without pausing replication, if enabled
         - Replicate CDC events using lsn
     */
     pub async fn start(&mut self) -> Result<(), PostgresConnectorError> {
@@ -107,7 +135,6 @@ impl<'a> PostgresIteratorHandler<'a> {
         let mut client = helper::connect(replication_conn_config).await?;
 
         // TODO: Handle cases:
-        // - When snapshot replication is not completed
         // - When there is gap between available lsn (in case when slot dropped and new created) and last lsn
         // - When publication tables changes
 
@@ -119,6 +146,7 @@ impl<'a> PostgresIteratorHandler<'a> {
         )
         .await?;
 
+        let mut snapshot_filter = None;
         if self.lsn.is_none() {
             debug!("\nCreating Slot....");
             let slot_exist =
@@ -132,63 +160,285 @@ impl<'a> PostgresIteratorHandler<'a> {
                     .map_err(PostgresConnectorError::InvalidQueryError)?;
             }
 
-            client
-                .simple_query("BEGIN READ ONLY ISOLATION LEVEL REPEATABLE READ;")
-                .await
-                .map_err(|_e| {
-                    debug!("failed to begin txn for replication");
-                    PostgresConnectorError::BeginReplication
-                })?;
-
-            let replication_slot_lsn =
-                ReplicationSlotHelper::create_replication_slot(&mut client, &details.slot_name)
-                    .await?;
-            if let Some(lsn) = replication_slot_lsn {
-                self.lsn = PgLsn::from_str(&lsn).map_or_else(
-                    |_| Err(PostgresConnectorError::LsnParseError(lsn.to_string())),
-                    |lsn| Ok(Some(lsn)),
-                )?;
-            } else {
-                return Err(PostgresConnectorError::LsnNotReturnedFromReplicationSlot);
-            }
+            let (lsn, snapshot_name) =
+                ReplicationSlotHelper::create_replication_slot_with_exported_snapshot(
+                    &mut client,
+                    &details.slot_name,
+                    false,
+                )
+                .await?;
+            let lsn = parse_lsn(&lsn)?;
+            self.lsn = Some(lsn);
 
             self.state = ReplicationState::SnapshotInProgress;
 
             /* #####################        SnapshotInProgress         ###################### */
             debug!("\nInitializing snapshots...");
 
-            let snapshotter = PostgresSnapshotter {
-                conn_config: details.conn_config.to_owned(),
-                ingestor: self.ingestor,
-                schema: details.schema.clone(),
-                batch_size: details.batch_size,
-            };
-            let tables = details
-                .tables
-                .iter()
-                .map(|table_info| ListOrFilterColumns {
-                    name: table_info.name.clone(),
-                    columns: Some(table_info.columns.clone()),
-                    schema: Some(table_info.schema.clone()),
-                })
-                .collect::<Vec<_>>();
-            snapshotter.sync_tables(&tables).await?;
+            self.sync_tables(SnapshotOptions {
+                snapshot_name: Some(snapshot_name),
+                resume_after: None,
+                checkpoint_lsn: Some(lsn.into()),
+            })
+            .await?;
 
             debug!("\nInitialized with tables: {:?}", details.tables);
 
-            client.simple_query("COMMIT;").await.map_err(|_e| {
-                debug!("failed to commit txn for replication");
-                PostgresConnectorError::CommitReplication
-            })?;
+            // The exported snapshot is no longer needed.
+            drop(client);
+
+            self.mark_snapshotted(lsn.into()).await?;
+
+            // Snapshot is done, replication starts from the slot's consistent point.
+            if self
+                .ingestor
+                .handle_message(IngestionMessage::TransactionInfo(TransactionInfo::Commit {
+                    id: Some(OpIdentifier::new(lsn.into(), 0).into()),
+                }))
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+        } else if let Some(position) = self.snapshot_position {
+            debug!("\nResuming snapshots after {:?}...", position);
+            self.state = ReplicationState::SnapshotInProgress;
+
+            // Remaining chunks are read at a later point than the chunks read before the restart.
+            // A temporary slot gives that point, and the replicated changes before it are filtered.
+            let (resume_lsn, snapshot_name) =
+                ReplicationSlotHelper::create_replication_slot_with_exported_snapshot(
+                    &mut client,
+                    &format!("{}_resume", details.slot_name),
+                    true,
+                )
+                .await?;
+            let resume_lsn = parse_lsn(&resume_lsn)?;
+
+            // Chunks can't be committed, because the filter doesn't survive a restart.
+            let tables = self
+                .sync_tables(SnapshotOptions {
+                    snapshot_name: Some(snapshot_name),
+                    resume_after: Some(position),
+                    checkpoint_lsn: None,
+                })
+                .await?;
+
+            // Drops the temporary slot.
+            drop(client);
+
+            let lsn = self.lsn.ok_or(PostgresConnectorError::LSNNotStoredError)?;
+            self.mark_snapshotted(lsn.into()).await?;
+
+            snapshot_filter = Some(ResumedSnapshotFilter {
+                position,
+                resume_lsn: resume_lsn.into(),
+                tables,
+            });
+        } else if self
+            .ingestor
+            .handle_message(IngestionMessage::TransactionInfo(
+                TransactionInfo::SnapshottingSkipped,
+            ))
+            .await
+            .is_err()
+        {
+            return Ok(());
         }
 
         self.state = ReplicationState::Replicating;
 
         /*  ####################        Replicating         ######################  */
-        self.replicate().await
+        let (watermark_relation_id, incremental_snapshot) =
+            self.prepare_incremental_snapshot().await?;
+        self.replicate(snapshot_filter, watermark_relation_id, incremental_snapshot)
+            .await
     }
 
-    async fn replicate(&self) -> Result<(), PostgresConnectorError> {
+    /// Records that all tables were snapshotted by the initial snapshot, which starts at `lsn`.
+    async fn mark_snapshotted(&self, lsn: u64) -> Result<(), PostgresConnectorError> {
+        let Some(watermark_table) = &self.details.watermark_table else {
+            return Ok(());
+        };
+        let mut client = helper::connect(self.details.conn_config.clone()).await?;
+        let tables = self
+            .details
+            .tables
+            .iter()
+            .map(|table| (table.schema.clone(), table.name.clone()))
+            .collect::<Vec<_>>();
+        watermark::mark_snapshotted(
+            &mut client,
+            watermark_table,
+            &self.details.slot_name,
+            &tables,
+            lsn,
+        )
+        .await
+    }
+
+    /// Finds the tables added since the last run, and adds them to the publication.
+    ///
+    /// Returns the watermark table's relation id, and the snapshot of the added tables if there are any.
+    /// Both are `None` if incremental snapshots are disabled, in which case added tables are not replicated.
+    async fn prepare_incremental_snapshot(
+        &self,
+    ) -> Result<(Option<u32>, Option<IncrementalSnapshot>), PostgresConnectorError> {
+        let details = &self.details;
+        let lsn: u64 = self
+            .lsn
+            .ok_or(PostgresConnectorError::LSNNotStoredError)?
+            .into();
+        let mut client = helper::connect(details.conn_config.clone()).await?;
+        let publication_tables =
+            watermark::publication_tables(&mut client, &details.publication_name).await?;
+
+        let Some(watermark_table) = &details.watermark_table else {
+            for table in &details.tables {
+                if !publication_tables.contains(&(table.schema.clone(), table.name.clone())) {
+                    warn!(
+                        "Table {}.{} was added after replication started and is not replicated. Enable `incremental_snapshot` to snapshot it.",
+                        table.schema, table.name
+                    );
+                }
+            }
+            return Ok((None, None));
+        };
+        let relation_id = watermark::create_watermark_table(&mut client, watermark_table).await?;
+
+        let snapshotted_tables = match watermark::snapshotted_tables(
+            &mut client,
+            watermark_table,
+            &details.slot_name,
+            lsn,
+        )
+        .await?
+        {
+            Some(tables) => tables,
+            None => {
+                // Slots created by earlier versions snapshotted the tables in the publication.
+                let tables = details
+                    .tables
+                    .iter()
+                    .map(|table| (table.schema.clone(), table.name.clone()))
+                    .filter(|table| publication_tables.contains(table))
+                    .collect::<Vec<_>>();
+                watermark::mark_snapshotted(
+                    &mut client,
+                    watermark_table,
+                    &details.slot_name,
+                    &tables,
+                    lsn,
+                )
+                .await?;
+                tables.into_iter().collect()
+            }
+        };
+
+        let mut pending = vec![];
+        for (table_index, table) in details.tables.iter().enumerate() {
+            let key = (table.schema.clone(), table.name.clone());
+            if !snapshotted_tables.contains(&key) {
+                pending.push((table_index, table));
+            }
+        }
+        for (schema, name) in pending
+            .iter()
+            .map(|(_, table)| (table.schema.clone(), table.name.clone()))
+            .chain(std::iter::once((
+                watermark_table.schema.clone(),
+                watermark_table.name.clone(),
+            )))
+        {
+            if !publication_tables.contains(&(schema.clone(), name.clone())) {
+                watermark::add_table_to_publication(
+                    &mut client,
+                    &details.publication_name,
+                    &schema,
+                    &name,
+                )
+                .await?;
+            }
+        }
+
+        if pending.is_empty() {
+            return Ok((Some(relation_id), None));
+        }
+        debug!("\nSnapshotting added tables: {:?}", pending);
+
+        let schema_helper = SchemaHelper::new(details.conn_config.clone(), details.schema.clone());
+        let schemas = schema_helper
+            .get_schemas(
+                &pending
+                    .iter()
+                    .map(|(_, table)| ListOrFilterColumns {
+                        name: table.name.clone(),
+                        columns: Some(table.columns.clone()),
+                        schema: Some(table.schema.clone()),
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .await?;
+        let mut tables = vec![];
+        for ((table_index, table), schema) in pending.into_iter().zip(schemas) {
+            let schema = schema?.schema;
+            if schema.primary_index.is_empty() {
+                return Err(PostgresConnectorError::AddedTableWithoutPrimaryKey(
+                    table.schema.clone(),
+                    table.name.clone(),
+                ));
+            }
+            tables.push(IncrementalTable {
+                table_index,
+                schema_name: table.schema.clone(),
+                table_name: table.name.clone(),
+                chunking: TableChunking::new(&schema, details.snapshot_chunk_size),
+                schema,
+            });
+        }
+
+        let snapshot = IncrementalSnapshot::new(
+            client,
+            watermark_table.clone(),
+            details.slot_name.clone(),
+            details.batch_size,
+            tables,
+            self.incremental_position,
+        );
+        Ok((Some(relation_id), Some(snapshot)))
+    }
+
+    async fn sync_tables(
+        &self,
+        options: SnapshotOptions,
+    ) -> Result<Vec<TableChunking>, PostgresConnectorError> {
+        let snapshotter = PostgresSnapshotter {
+            conn_config: self.details.conn_config.to_owned(),
+            ingestor: self.ingestor,
+            schema: self.details.schema.clone(),
+            batch_size: self.details.batch_size,
+            chunk_size: self.details.snapshot_chunk_size,
+            parallelism: self.details.snapshot_parallelism,
+        };
+        let tables = self
+            .details
+            .tables
+            .iter()
+            .map(|table_info| ListOrFilterColumns {
+                name: table_info.name.clone(),
+                columns: Some(table_info.columns.clone()),
+                schema: Some(table_info.schema.clone()),
+            })
+            .collect::<Vec<_>>();
+        snapshotter.sync_tables(&tables, &options).await
+    }
+
+    async fn replicate(
+        &self,
+        snapshot_filter: Option<ResumedSnapshotFilter>,
+        watermark_relation_id: Option<u32>,
+        incremental_snapshot: Option<IncrementalSnapshot>,
+    ) -> Result<(), PostgresConnectorError> {
         let lsn = self
             .lsn
             .as_ref()
@@ -206,8 +456,16 @@ impl<'a> PostgresIteratorHandler<'a> {
             publication_name,
             slot_name,
             last_commit_lsn: 0,
+            commit_time: None,
             name: self.details.name.clone(),
+            snapshot_filter,
+            incremental_snapshot,
+            watermark_relation_id,
         };
         replicator.start(tables).await
     }
 }
+
+fn parse_lsn(lsn: &str) -> Result<PgLsn, PostgresConnectorError> {
+    PgLsn::from_str(lsn).map_err(|_| PostgresConnectorError::LsnParseError(lsn.to_string()))
+}
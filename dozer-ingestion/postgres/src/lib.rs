@@ -8,6 +8,7 @@ use dozer_ingestion_connector::dozer_types::{
 };
 use tokio_postgres::config::SslMode;
 
+pub mod chunk;
 pub mod connection;
 pub mod connector;
 pub mod helper;
@@ -16,10 +17,12 @@ mod replication_slot_helper;
 pub mod replicator;
 mod schema;
 pub mod snapshotter;
+mod type_conversion;
 #[cfg(test)]
 pub mod test_utils;
 #[cfg(test)]
 pub mod tests;
+pub mod watermark;
 pub mod xlog_mapper;
 
 pub use tokio_postgres;
@@ -122,6 +125,9 @@ pub enum PostgresConnectorError {
     #[error("Relation not found in replication: {0}")]
     RelationNotFound(#[source] std::io::Error),
 
+    #[error("Table \"{0}\".\"{1}\" is added to a running replication, so it needs a primary key")]
+    AddedTableWithoutPrimaryKey(String, String),
+
     #[error("Failed to send message on snapshot read channel")]
     SnapshotReadError,
 
@@ -22,6 +22,7 @@ impl ReplicationSlotHelper {
         res
     }
 
+    #[cfg(test)]
     pub async fn create_replication_slot(
         client: &mut Client,
         slot_name: &str,
@@ -44,6 +45,37 @@ impl ReplicationSlotHelper {
         }
     }
 
+    /// Creates a replication slot and exports its snapshot, which other connections can import with `SET TRANSACTION SNAPSHOT`.
+    /// The snapshot stays valid until the next command on `client`.
+    ///
+    /// Returns the consistent point and the snapshot name.
+    pub async fn create_replication_slot_with_exported_snapshot(
+        client: &mut Client,
+        slot_name: &str,
+        temporary: bool,
+    ) -> Result<(String, String), PostgresConnectorError> {
+        let temporary = if temporary { "TEMPORARY " } else { "" };
+        let create_replication_slot_query = format!(
+            r#"CREATE_REPLICATION_SLOT {slot_name:?} {temporary}LOGICAL "pgoutput" EXPORT_SNAPSHOT"#
+        );
+
+        let slot_query_row = client
+            .simple_query(&create_replication_slot_query)
+            .await
+            .map_err(|e| {
+                debug!("failed to create replication slot {}", slot_name);
+                PostgresConnectorError::CreateSlotError(slot_name.to_string(), e)
+            })?;
+
+        let SimpleQueryMessage::Row(row) = &slot_query_row[0] else {
+            return Err(PostgresConnectorError::UnexpectedQueryMessageError);
+        };
+        match (row.get("consistent_point"), row.get("snapshot_name")) {
+            (Some(lsn), Some(snapshot_name)) => Ok((lsn.to_string(), snapshot_name.to_string())),
+            _ => Err(PostgresConnectorError::LsnNotReturnedFromReplicationSlot),
+        }
+    }
+
     pub async fn replication_slot_exists(
         client: &mut Client,
         slot_name: &str,
@@ -16,8 +16,11 @@ use tokio_postgres::Error;
 use std::pin::Pin;
 use std::time::SystemTime;
 
+use crate::chunk::ResumedSnapshotFilter;
 use crate::connection::client::Client;
 use crate::connection::helper::{self, is_network_failure};
+use crate::schema::helper::{get_custom_types, get_money_format};
+use crate::watermark::IncrementalSnapshot;
 use crate::xlog_mapper::XlogMapper;
 use crate::PostgresConnectorError;
 
@@ -36,6 +39,14 @@ pub struct CDCHandler<'a> {
     pub begin_lsn: Lsn,
     pub offset_lsn: Lsn,
     pub last_commit_lsn: Lsn,
+    /// When the transaction being replicated was committed.
+    pub commit_time: Option<SystemTime>,
+
+    /// Set after a resumed snapshot, until replication reaches the point where the snapshot resumed.
+    pub snapshot_filter: Option<ResumedSnapshotFilter>,
+    /// Set while tables added to the replication are snapshotted.
+    pub incremental_snapshot: Option<IncrementalSnapshot>,
+    pub watermark_relation_id: Option<u32>,
 }
 
 impl<'a> CDCHandler<'a> {
@@ -44,7 +55,9 @@ impl<'a> CDCHandler<'a> {
         tables: Vec<PostgresTableInfo>,
     ) -> Result<(), PostgresConnectorError> {
         let replication_conn_config = self.replication_conn_config.clone();
-        let client = helper::connect(replication_conn_config).await?;
+        let mut client = helper::connect(replication_conn_config).await?;
+        let custom_types = get_custom_types(&mut client).await?;
+        let money_format = get_money_format(&mut client).await?;
 
         info!(
             "[{}] Starting Replication: {:?}, {:?}",
@@ -74,7 +87,16 @@ impl<'a> CDCHandler<'a> {
                 (table_info.relation_id, (table_index, table_info.columns))
             })
             .collect();
-        let mut mapper = XlogMapper::new(tables_columns);
+        let mut mapper = XlogMapper::new(tables_columns)
+            .with_custom_types(custom_types)
+            .with_money_format(money_format);
+        if let Some(relation_id) = self.watermark_relation_id {
+            mapper = mapper.with_watermark_relation_id(relation_id);
+        }
+
+        if let Some(snapshot) = &mut self.incremental_snapshot {
+            snapshot.start(self.ingestor, lsn.into()).await?;
+        }
 
         loop {
             let message = stream.next().await;
@@ -117,13 +139,28 @@ impl<'a> CDCHandler<'a> {
 
                 match message {
                     Some(MappedReplicationMessage::Commit(lsn)) => {
-                        self.last_commit_lsn = lsn;
+                        // Restarting from a filtered transaction would lose the filter,
+                        // so neither the checkpoint nor the slot may move past it.
+                        let id = if self.snapshot_filter.is_some() {
+                            None
+                        } else {
+                            self.last_commit_lsn = lsn;
+                            Some(
+                                match self
+                                    .incremental_snapshot
+                                    .as_ref()
+                                    .and_then(IncrementalSnapshot::position)
+                                {
+                                    Some(position) => position.to_incremental_op_id(self.begin_lsn),
+                                    None => OpIdentifier::new(self.begin_lsn, 0),
+                                }
+                                .into(),
+                            )
+                        };
                         if self
                             .ingestor
                             .handle_message(IngestionMessage::TransactionInfo(
-                                TransactionInfo::Commit {
-                                    id: Some(OpIdentifier::new(self.begin_lsn, 0)),
-                                },
+                                TransactionInfo::Commit { id },
                             ))
                             .await
                             .is_err()
@@ -131,17 +168,37 @@ impl<'a> CDCHandler<'a> {
                             return Ok(());
                         }
                     }
-                    Some(MappedReplicationMessage::Begin) => {
+                    Some(MappedReplicationMessage::Begin(commit_lsn, commit_time)) => {
                         self.begin_lsn = lsn;
+                        self.commit_time = Some(commit_time);
+                        if self
+                            .snapshot_filter
+                            .as_ref()
+                            .is_some_and(|filter| !filter.applies_to(commit_lsn))
+                        {
+                            self.snapshot_filter = None;
+                        }
                     }
                     Some(MappedReplicationMessage::Operation { table_index, op }) => {
+                        let op = match &self.snapshot_filter {
+                            Some(filter) => filter.filter(table_index, op),
+                            None => Some(op),
+                        };
+                        let op = match (op, &mut self.incremental_snapshot) {
+                            (Some(op), Some(snapshot)) => snapshot.filter(table_index, op),
+                            (op, _) => op,
+                        };
+                        let Some(op) = op else {
+                            return Ok(());
+                        };
                         if self.begin_lsn != self.offset_lsn
                             && self
                                 .ingestor
                                 .handle_message(IngestionMessage::OperationEvent {
                                     table_index,
                                     op,
-                                    id: Some(OpIdentifier::new(self.begin_lsn, 0)),
+                                    id: Some(OpIdentifier::new(self.begin_lsn, 0).into()),
+                                    source_time: self.commit_time,
                                 })
                                 .await
                                 .is_err()
@@ -150,6 +207,16 @@ impl<'a> CDCHandler<'a> {
                             return Ok(());
                         }
                     }
+                    Some(MappedReplicationMessage::Watermark(watermark)) => {
+                        if let Some(snapshot) = &mut self.incremental_snapshot {
+                            snapshot
+                                .on_watermark(&watermark, self.begin_lsn, self.ingestor)
+                                .await?;
+                            if snapshot.is_done() {
+                                self.incremental_snapshot = None;
+                            }
+                        }
+                    }
                     None => {}
                 }
 
@@ -1,16 +1,18 @@
 use std::collections::HashMap;
 
 use dozer_ingestion_connector::{
-    dozer_types::types::{FieldDefinition, FieldType, Schema, SourceDefinition},
+    dozer_types::types::{FieldDefinition, Schema, SourceDefinition},
     utils::ListOrFilterColumns,
     CdcType, SourceSchema,
 };
 use postgres_types::Type;
-use tokio_postgres::Row;
+use tokio_postgres::{Row, SimpleQueryMessage};
 
 use crate::{
-    connection::helper, helper::postgres_type_to_dozer_type, PostgresConnectorError,
-    PostgresSchemaError,
+    connection::{client::Client, helper},
+    helper::postgres_type_to_dozer_type,
+    type_conversion::{custom_type, MoneyFormat},
+    PostgresConnectorError, PostgresSchemaError,
 };
 
 use super::sorter::sort_schemas;
@@ -328,17 +330,26 @@ impl SchemaHelper {
         let is_column_used_in_index: bool = row.get(3);
         let replication_type_int: i8 = row.get(5);
         let type_oid: u32 = row.get(6);
-
-        // TODO: workaround - in case of custom enum
-        let typ = if type_oid == 28862 {
-            FieldType::String
-        } else {
-            let oid_typ = Type::from_oid(type_oid);
-            oid_typ.map_or_else(
-                || Err(PostgresSchemaError::InvalidColumnType(column_name.clone())),
-                postgres_type_to_dozer_type,
-            )?
+        let type_kind: Option<String> = row.get(9);
+        let type_name: Option<String> = row.get(10);
+
+        // Domains are already mapped to their base type by `information_schema`.
+        let typ = match Type::from_oid(type_oid) {
+            Some(typ) => typ,
+            None => {
+                let type_name = type_name
+                    .ok_or_else(|| PostgresSchemaError::InvalidColumnType(column_name.clone()))?;
+                custom_type(
+                    type_oid,
+                    &type_name,
+                    type_kind.as_deref().unwrap_or_default(),
+                    "",
+                    None,
+                )
+                .ok_or(PostgresSchemaError::CustomTypeNotSupported(type_name))?
+            }
         };
+        let typ = postgres_type_to_dozer_type(typ)?;
 
         let replication_type =
             String::from_utf8(vec![replication_type_int as u8]).map_err(|_e| {
@@ -357,6 +368,85 @@ impl SchemaHelper {
 
 pub const DEFAULT_SCHEMA_NAME: &str = "public";
 
+/// Returns how the server formats `money`, which depends on its `lc_monetary` setting.
+pub async fn get_money_format(client: &mut Client) -> Result<MoneyFormat, PostgresConnectorError> {
+    let messages = client
+        .simple_query("SELECT scale('0'::money::numeric), '1'::money::text, '-1'::money::text")
+        .await
+        .map_err(PostgresConnectorError::InvalidQueryError)?;
+
+    messages
+        .iter()
+        .find_map(|message| match message {
+            SimpleQueryMessage::Row(row) => Some(MoneyFormat::new(
+                row.get(0)?.parse().ok()?,
+                row.get(1)?,
+                row.get(2)?,
+            )),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            PostgresConnectorError::PostgresSchemaError(PostgresSchemaError::ValueConversionError(
+                "failed to read the money format".to_string(),
+            ))
+        })
+}
+
+/// Returns the enums, domains and `hstore` types of the database by oid, which replication messages refer to.
+pub async fn get_custom_types(
+    client: &mut Client,
+) -> Result<HashMap<u32, Type>, PostgresConnectorError> {
+    let messages = client
+        .simple_query(
+            "SELECT t.oid, t.typname, t.typtype, t.typbasetype, n.nspname
+            FROM pg_type t JOIN pg_namespace n ON n.oid = t.typnamespace
+            WHERE t.typtype IN ('e', 'd') OR t.typname = 'hstore'",
+        )
+        .await
+        .map_err(PostgresConnectorError::InvalidQueryError)?;
+
+    let mut rows = messages
+        .iter()
+        .filter_map(|message| match message {
+            SimpleQueryMessage::Row(row) => Some(row),
+            _ => None,
+        })
+        .filter_map(|row| {
+            Some((
+                row.get(0)?.parse::<u32>().ok()?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?.parse::<u32>().ok()?,
+                row.get(4)?,
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    // Domains can be based on other domains, so they are resolved after their base type.
+    let mut types = HashMap::new();
+    loop {
+        let count = rows.len();
+        rows.retain(|(oid, name, typtype, base_oid, schema)| {
+            let base = if *typtype == "d" {
+                match Type::from_oid(*base_oid).or_else(|| types.get(base_oid).cloned()) {
+                    Some(base) => Some(base),
+                    None => return true,
+                }
+            } else {
+                None
+            };
+            if let Some(typ) = custom_type(*oid, name, typtype, schema, base) {
+                types.insert(*oid, typ);
+            }
+            false
+        });
+        if rows.is_empty() || rows.len() == count {
+            break;
+        }
+    }
+    Ok(types)
+}
+
 fn find_table(
     table_columns_map: &HashMap<SchemaTableIdentifier, (u32, Vec<String>)>,
     schema_name: Option<&str>,
@@ -393,12 +483,15 @@ SELECT table_info.table_name,
        pc.relreplident,
        pt.oid                                                           AS type_oid,
        t.table_type,
-       t.table_schema
+       t.table_schema,
+       pt.typtype::text                                                 AS type_kind,
+       pt.typname::text                                                 AS type_name
 FROM information_schema.columns table_info
          LEFT JOIN information_schema.tables t ON t.table_name = table_info.table_name AND t.table_schema = table_info.table_schema
          LEFT JOIN pg_namespace ns ON t.table_schema = ns.nspname
          LEFT JOIN pg_class pc ON t.table_name = pc.relname AND ns.oid = pc.relnamespace
-         LEFT JOIN pg_type pt ON table_info.udt_name = pt.typname
+         LEFT JOIN pg_namespace tns ON table_info.udt_schema = tns.nspname
+         LEFT JOIN pg_type pt ON table_info.udt_name = pt.typname AND tns.oid = pt.typnamespace
          LEFT JOIN pg_index pi ON pc.oid = pi.indrelid AND
                                   ((pi.indisreplident = true AND pc.relreplident = 'i') OR (pi.indisprimary AND pc.relreplident = 'd'))
          LEFT JOIN pg_attribute pa ON
@@ -1,21 +1,28 @@
+use std::sync::Arc;
+
 use dozer_ingestion_connector::{
     dozer_types::{
         models::ingestion_types::{IngestionMessage, TransactionInfo},
-        types::{Operation, Schema},
+        types::{Operation, Record, Schema},
     },
     futures::StreamExt,
     tokio::{
         self,
-        sync::mpsc::{channel, Sender},
-        task::JoinSet,
+        sync::{
+            mpsc::{channel, Receiver, Sender},
+            Semaphore,
+        },
     },
     utils::ListOrFilterColumns,
     Ingestor, SourceSchema,
 };
 
 use crate::{
-    connection::helper as connection_helper, helper::get_conversion_fn,
-    schema::helper::SchemaHelper, PostgresConnectorError,
+    chunk::{SnapshotPosition, TableChunking},
+    connection::{client::Client, helper as connection_helper},
+    helper::get_conversions,
+    schema::helper::{SchemaHelper, DEFAULT_SCHEMA_NAME},
+    PostgresConnectorError,
 };
 
 use super::helper;
@@ -25,8 +32,51 @@ pub struct PostgresSnapshotter<'a> {
     pub ingestor: &'a Ingestor,
     pub schema: Option<String>,
     pub batch_size: usize,
+    pub chunk_size: u64,
+    pub parallelism: usize,
+}
+
+/// Where the snapshot is read from and how its progress is reported.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotOptions {
+    /// Snapshot exported by a replication slot. Chunks are read in it, so they are consistent with the slot.
+    pub snapshot_name: Option<String>,
+    /// Chunks up to and including this position were read before.
+    pub resume_after: Option<SnapshotPosition>,
+    /// Consistent point of the replication slot. If set, every chunk is committed with its position,
+    /// so the snapshot can be resumed from the last committed chunk.
+    pub checkpoint_lsn: Option<u64>,
+}
+
+fn quoted_table_name(table: &ListOrFilterColumns) -> String {
+    format!(
+        r#""{}"."{}""#,
+        table.schema.as_deref().unwrap_or(DEFAULT_SCHEMA_NAME),
+        table.name
+    )
 }
 
+/// Selects the columns of `schema` from the whole table.
+fn select_query(table: &ListOrFilterColumns, schema: &Schema) -> String {
+    let column_str: Vec<String> = schema
+        .fields
+        .iter()
+        .map(|f| format!("\"{0}\"", f.name))
+        .collect();
+    format!(
+        "select {} from {}",
+        column_str.join(","),
+        quoted_table_name(table)
+    )
+}
+
+struct Chunk {
+    position: SnapshotPosition,
+    query: String,
+}
+
+type ChunkReceiver = Receiver<Result<Operation, PostgresConnectorError>>;
+
 impl<'a> PostgresSnapshotter<'a> {
     pub async fn get_tables(
         &self,
@@ -36,40 +86,190 @@ impl<'a> PostgresSnapshotter<'a> {
         helper.get_schemas(tables).await
     }
 
-    pub async fn sync_table(
-        schema: Schema,
-        schema_name: String,
-        table_name: String,
-        table_index: usize,
+    async fn connect(
         conn_config: tokio_postgres::Config,
-        batch_size: usize,
-        sender: Sender<Result<(usize, Operation), PostgresConnectorError>>,
-    ) -> Result<(), PostgresConnectorError> {
-        let mut client_plain = connection_helper::connect(conn_config).await?;
+        snapshot_name: Option<&str>,
+        query: Option<&str>,
+    ) -> Result<(Client, Option<tokio_postgres::Statement>), PostgresConnectorError> {
+        let mut client = connection_helper::connect(conn_config).await?;
+        // Prepare before the transaction begins, because importing a snapshot must be its first statement.
+        let statement = match query {
+            Some(query) => Some(
+                client
+                    .prepare(query)
+                    .await
+                    .map_err(PostgresConnectorError::InvalidQueryError)?,
+            ),
+            None => None,
+        };
+        if let Some(snapshot_name) = snapshot_name {
+            client
+                .simple_query(&format!(
+                    "BEGIN ISOLATION LEVEL REPEATABLE READ READ ONLY; SET TRANSACTION SNAPSHOT '{snapshot_name}';"
+                ))
+                .await
+                .map_err(|e| PostgresConnectorError::SyncWithSnapshotError(e.to_string()))?;
+        }
+        Ok((client, statement))
+    }
 
-        let column_str: Vec<String> = schema
-            .fields
-            .iter()
-            .map(|f| format!("\"{0}\"", f.name))
-            .collect();
+    /// Splits the tables into chunks, skipping the empty key ranges of chunked tables.
+    async fn plan_chunks(
+        &self,
+        tables: &[ListOrFilterColumns],
+        schemas: &[Schema],
+        chunkings: &[TableChunking],
+        options: &SnapshotOptions,
+    ) -> Result<Vec<Chunk>, PostgresConnectorError> {
+        let (mut client, _) = Self::connect(
+            self.conn_config.clone(),
+            options.snapshot_name.as_deref(),
+            None,
+        )
+        .await?;
+
+        let mut chunks = vec![];
+        for (table_index, ((table, schema), chunking)) in
+            tables.iter().zip(schemas).zip(chunkings).enumerate()
+        {
+            let table_name = quoted_table_name(table);
+            let query = select_query(table, schema);
+
+            let Some(column) = chunking.primary_key_column() else {
+                chunks.push(Chunk {
+                    position: SnapshotPosition {
+                        table_index,
+                        chunk: 0,
+                    },
+                    query,
+                });
+                continue;
+            };
+
+            let mut next_key = None;
+            loop {
+                let condition = next_key
+                    .map(|key| format!(r#" where "{column}" >= {key}"#))
+                    .unwrap_or_default();
+                let row = client
+                    .query_one(
+                        &format!(r#"select min("{column}")::bigint from {table_name}{condition}"#),
+                        &[],
+                    )
+                    .await
+                    .map_err(PostgresConnectorError::InvalidQueryError)?;
+                let Some(key) = row.get::<_, Option<i64>>(0) else {
+                    break;
+                };
+
+                let chunk = chunking.chunk_of_key(key);
+                let (start, end) = chunking.key_range(chunk);
+                let conditions = start
+                    .map(|start| format!(r#""{column}" >= {start}"#))
+                    .into_iter()
+                    .chain(end.map(|end| format!(r#""{column}" < {end}"#)))
+                    .collect::<Vec<_>>();
+                let query = if conditions.is_empty() {
+                    query.clone()
+                } else {
+                    format!("{query} where {}", conditions.join(" and "))
+                };
+                chunks.push(Chunk {
+                    position: SnapshotPosition { table_index, chunk },
+                    query,
+                });
+
+                match end {
+                    Some(end) => next_key = Some(end),
+                    None => break,
+                }
+            }
+        }
 
-        let column_str = column_str.join(",");
-        let query = format!(r#"select {column_str} from "{schema_name}"."{table_name}""#);
-        let stmt = client_plain
-            .prepare(&query)
-            .await
-            .map_err(PostgresConnectorError::InvalidQueryError)?;
-        let columns = stmt.columns();
-        let conversions: Vec<_> = columns
-            .iter()
-            .map(|col| get_conversion_fn(col.type_()))
+        if let Some(resume_after) = options.resume_after {
+            chunks.retain(|chunk| chunk.position > resume_after);
+        }
+        Ok(chunks)
+    }
+
+    /// Reads up to `limit` rows of each table with plain queries, so unlike `sync_tables` it needs no replication slot.
+    pub async fn sample_tables(
+        conn_config: tokio_postgres::Config,
+        schema: Option<String>,
+        tables: &[ListOrFilterColumns],
+        limit: usize,
+    ) -> Result<Vec<Vec<Record>>, PostgresConnectorError> {
+        let helper = SchemaHelper::new(conn_config.clone(), schema);
+        let schemas = helper
+            .get_schemas(tables)
+            .await?
+            .into_iter()
+            .map(|schema| schema.map(|schema| schema.schema))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let empty_vec: Vec<String> = Vec::new();
-        let row_stream = client_plain
-            .query_raw(query, empty_vec)
-            .await
-            .map_err(PostgresConnectorError::InvalidQueryError)?;
+        let mut client = connection_helper::connect(conn_config).await?;
+        let mut samples = vec![];
+        for (table, schema) in tables.iter().zip(&schemas) {
+            let query = format!("{} limit {limit}", select_query(table, schema));
+            let statement = client
+                .prepare(&query)
+                .await
+                .map_err(PostgresConnectorError::InvalidQueryError)?;
+            let conversions = get_conversions(&mut client, &statement).await?;
+            let rows = client
+                .query(&statement, &[])
+                .await
+                .map_err(PostgresConnectorError::InvalidQueryError)?;
+            samples.push(
+                rows.iter()
+                    .map(|row| helper::map_row_to_record(row, &conversions))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(PostgresConnectorError::PostgresSchemaError)?,
+            );
+        }
+        Ok(samples)
+    }
+
+    /// Row counts of the tables estimated from the planner statistics. Tables that were never analyzed are estimated as 0 rows.
+    async fn estimate_rows(
+        &self,
+        tables: &[ListOrFilterColumns],
+    ) -> Result<Vec<u64>, PostgresConnectorError> {
+        let mut client = connection_helper::connect(self.conn_config.clone()).await?;
+        let mut rows = vec![];
+        for table in tables {
+            let table_name = quoted_table_name(table);
+            let row = client
+                .query_one(
+                    "select reltuples::bigint from pg_class where oid = $1::text::regclass",
+                    &[&table_name],
+                )
+                .await
+                .map_err(PostgresConnectorError::InvalidQueryError)?;
+            rows.push(row.get::<_, i64>(0).max(0) as u64);
+        }
+        Ok(rows)
+    }
+
+    async fn sync_chunk(
+        query: String,
+        conn_config: tokio_postgres::Config,
+        snapshot_name: Option<String>,
+        batch_size: usize,
+        sender: Sender<Result<Operation, PostgresConnectorError>>,
+    ) -> Result<(), PostgresConnectorError> {
+        let (mut client, statement) =
+            Self::connect(conn_config, snapshot_name.as_deref(), Some(&query)).await?;
+        let statement = statement.expect("query is prepared");
+        let conversions = get_conversions(&mut client, &statement).await?;
+
+        let row_stream = if snapshot_name.is_some() {
+            client.query_raw_in_transaction(&statement).await
+        } else {
+            let empty_vec: Vec<String> = Vec::new();
+            client.query_raw(query, empty_vec).await
+        }
+        .map_err(PostgresConnectorError::InvalidQueryError)?;
         tokio::pin!(row_stream);
 
         let mut batch = Vec::with_capacity(batch_size);
@@ -82,9 +282,7 @@ impl<'a> PostgresSnapshotter<'a> {
                     batch.push(record);
 
                     if batch.len() == batch_size {
-                        let Ok(_) = sender
-                            .send(Ok((table_index, Operation::BatchInsert { new: batch })))
-                            .await
+                        let Ok(_) = sender.send(Ok(Operation::BatchInsert { new: batch })).await
                         else {
                             // If we can't send, the parent task has quit. There is
                             // no use in going on, but if there was an error, it was
@@ -99,50 +297,82 @@ impl<'a> PostgresSnapshotter<'a> {
         }
 
         if !batch.is_empty() {
-            let _ = sender
-                .send(Ok((table_index, Operation::BatchInsert { new: batch })))
-                .await;
+            let _ = sender.send(Ok(Operation::BatchInsert { new: batch })).await;
         }
 
         Ok(())
     }
 
+    /// Starts reading up to `parallelism` chunks at a time. The receivers are returned in chunk order.
+    fn spawn_chunk_readers(
+        &self,
+        chunks: Vec<Chunk>,
+        snapshot_name: Option<String>,
+    ) -> Receiver<(SnapshotPosition, ChunkReceiver)> {
+        let parallelism = self.parallelism.max(1);
+        let (tx, rx) = channel(parallelism);
+        let semaphore = Arc::new(Semaphore::new(parallelism));
+        let conn_config = self.conn_config.clone();
+        let batch_size = self.batch_size;
+
+        tokio::spawn(async move {
+            for chunk in chunks {
+                // Permits are acquired in chunk order, so the chunk that is being ingested always has one.
+                let Ok(permit) = semaphore.clone().acquire_owned().await else {
+                    return;
+                };
+                let (sender, receiver) = channel(16);
+                if tx.send((chunk.position, receiver)).await.is_err() {
+                    // The snapshot has stopped.
+                    return;
+                }
+
+                let conn_config = conn_config.clone();
+                let snapshot_name = snapshot_name.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = Self::sync_chunk(
+                        chunk.query,
+                        conn_config,
+                        snapshot_name,
+                        batch_size,
+                        sender.clone(),
+                    )
+                    .await
+                    {
+                        let _ = sender.send(Err(e)).await;
+                    }
+                    drop(permit);
+                });
+            }
+        });
+
+        rx
+    }
+
+    /// Reads the tables in chunks, and ingests the chunks in order.
+    ///
+    /// Returns how the tables are chunked.
     pub async fn sync_tables(
         &self,
         tables: &[ListOrFilterColumns],
-    ) -> Result<(), PostgresConnectorError> {
-        let schemas = self.get_tables(tables).await?;
-
-        let (tx, mut rx) = channel(16);
-
-        let mut joinset = JoinSet::new();
-        for (table_index, (schema, table)) in schemas.into_iter().zip(tables).enumerate() {
-            let schema = schema?;
-            let schema = schema.schema;
-            let schema_name = table.schema.clone().unwrap_or("public".to_string());
-            let table_name = table.name.clone();
-            let conn_config = self.conn_config.clone();
-            let batch_size = self.batch_size;
-            let sender = tx.clone();
-            joinset.spawn(async move {
-                if let Err(e) = Self::sync_table(
-                    schema,
-                    schema_name,
-                    table_name,
-                    table_index,
-                    conn_config,
-                    batch_size,
-                    sender.clone(),
-                )
-                .await
-                {
-                    sender.send(Err(e)).await.unwrap();
-                }
-            });
-        }
-        // Make sure the last sender is dropped so receiving on the channel doesn't
-        // deadlock
-        drop(tx);
+        options: &SnapshotOptions,
+    ) -> Result<Vec<TableChunking>, PostgresConnectorError> {
+        let schemas = self
+            .get_tables(tables)
+            .await?
+            .into_iter()
+            .map(|schema| schema.map(|schema| schema.schema))
+            .collect::<Result<Vec<_>, _>>()?;
+        let chunkings = schemas
+            .iter()
+            .map(|schema| TableChunking::new(schema, self.chunk_size))
+            .collect::<Vec<_>>();
+
+        let chunks = self
+            .plan_chunks(tables, &schemas, &chunkings, options)
+            .await?;
+        let estimated_rows = self.estimate_rows(tables).await?;
+        let mut chunk_receivers = self.spawn_chunk_readers(chunks, options.snapshot_name.clone());
 
         if self
             .ingestor
@@ -153,23 +383,53 @@ impl<'a> PostgresSnapshotter<'a> {
             .is_err()
         {
             // If receiving side is closed, we can stop
-            return Ok(());
+            return Ok(chunkings);
+        }
+        for (table_index, rows) in estimated_rows.into_iter().enumerate() {
+            if self
+                .ingestor
+                .handle_message(IngestionMessage::TransactionInfo(
+                    TransactionInfo::SnapshotSize { table_index, rows },
+                ))
+                .await
+                .is_err()
+            {
+                // If receiving side is closed, we can stop
+                return Ok(chunkings);
+            }
         }
 
-        while let Some(message) = rx.recv().await {
-            let (table_index, evt) = message?;
+        while let Some((position, mut receiver)) = chunk_receivers.recv().await {
+            while let Some(message) = receiver.recv().await {
+                let op = message?;
+                if self
+                    .ingestor
+                    .handle_message(IngestionMessage::OperationEvent {
+                        table_index: position.table_index,
+                        op,
+                        id: None,
+                        source_time: None,
+                    })
+                    .await
+                    .is_err()
+                {
+                    // If receiving side is closed, we can stop
+                    return Ok(chunkings);
+                }
+            }
+
             if self
                 .ingestor
-                .handle_message(IngestionMessage::OperationEvent {
-                    table_index,
-                    op: evt,
-                    id: None,
-                })
+                .handle_message(IngestionMessage::TransactionInfo(TransactionInfo::Commit {
+                    id: options
+                        .checkpoint_lsn
+                        .map(|lsn| position.to_op_id(lsn).into()),
+                }))
                 .await
                 .is_err()
             {
                 // If receiving side is closed, we can stop
-                return Ok(());
+                return Ok(chunkings);
             }
         }
 
@@ -182,12 +442,11 @@ impl<'a> PostgresSnapshotter<'a> {
             .is_err()
         {
             // If receiving side is closed, we can stop
-            return Ok(());
+            return Ok(chunkings);
         }
 
-        // All tasks in the joinset should have finished (because they have dropped their senders)
-        // Otherwise, they will be aborted when the joinset is dropped
-        Ok(())
+        // Chunk readers stop when their receivers are dropped.
+        Ok(chunkings)
     }
 }
 
@@ -204,7 +463,7 @@ mod tests {
         tests::client::TestPostgresClient,
     };
 
-    use super::PostgresSnapshotter;
+    use super::{PostgresSnapshotter, SnapshotOptions};
 
     #[tokio::test]
     #[ignore]
@@ -236,9 +495,14 @@ mod tests {
             ingestor: &ingestor,
             schema: None,
             batch_size: 1000,
+            chunk_size: 100_000,
+            parallelism: 4,
         };
 
-        snapshotter.sync_tables(&input_tables).await.unwrap();
+        snapshotter
+            .sync_tables(&input_tables, &SnapshotOptions::default())
+            .await
+            .unwrap();
 
         let mut i = 0;
         while i < 2 {
@@ -284,9 +548,13 @@ mod tests {
             ingestor: &ingestor,
             schema: None,
             batch_size: 1000,
+            chunk_size: 100_000,
+            parallelism: 4,
         };
 
-        let actual = snapshotter.sync_tables(&input_tables).await;
+        let actual = snapshotter
+            .sync_tables(&input_tables, &SnapshotOptions::default())
+            .await;
 
         assert!(actual.is_err());
     }
@@ -316,9 +584,13 @@ mod tests {
             ingestor: &ingestor,
             schema: None,
             batch_size: 1000,
+            chunk_size: 100_000,
+            parallelism: 4,
         };
 
-        let actual = snapshotter.sync_tables(&input_tables).await;
+        let actual = snapshotter
+            .sync_tables(&input_tables, &SnapshotOptions::default())
+            .await;
 
         assert!(actual.is_err());
     }
@@ -28,6 +28,9 @@ mod tests {
             config: conn_config.clone(),
             schema: None,
             batch_size: 1000,
+            snapshot_chunk_size: 100_000,
+            snapshot_parallelism: 4,
+            watermark_table: None,
         };
 
         let connector = PostgresConnector::new(postgres_config, None).unwrap();
@@ -83,6 +86,9 @@ mod tests {
             config: conn_config.clone(),
             schema: None,
             batch_size: 1000,
+            snapshot_chunk_size: 100_000,
+            snapshot_parallelism: 4,
+            watermark_table: None,
         };
 
         let connector = PostgresConnector::new(postgres_config, None).unwrap();
@@ -0,0 +1,708 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::iter::Peekable;
+use std::net::IpAddr;
+use std::str::Chars;
+use std::time::Duration;
+
+use dozer_ingestion_connector::dozer_types::{rust_decimal::Decimal, serde_json::Value};
+use postgres_types::{FromSql, Kind, Type};
+
+use crate::PostgresSchemaError;
+
+type BoxedError = Box<dyn Error + Sync + Send>;
+
+/// Domains are encoded as their base type.
+pub fn base_type(ty: &Type) -> &Type {
+    match ty.kind() {
+        Kind::Domain(base) => base_type(base),
+        _ => ty,
+    }
+}
+
+pub fn is_enum(ty: &Type) -> bool {
+    matches!(ty.kind(), Kind::Enum(_))
+}
+
+/// `hstore` is defined by an extension, so it has no fixed oid.
+pub fn is_hstore(ty: &Type) -> bool {
+    ty.name() == "hstore" && matches!(ty.kind(), Kind::Simple)
+}
+
+/// Resolves a type that is not built in, from its `pg_type` row.
+///
+/// `base` is the resolved base type of domains.
+pub fn custom_type(
+    oid: u32,
+    name: &str,
+    typtype: &str,
+    schema: &str,
+    base: Option<Type>,
+) -> Option<Type> {
+    let kind = match (typtype, base) {
+        ("e", _) => Kind::Enum(vec![]),
+        ("d", Some(base)) => Kind::Domain(base),
+        ("b", _) if name == "hstore" => Kind::Simple,
+        _ => return None,
+    };
+    Some(Type::new(name.to_string(), oid, kind, schema.to_string()))
+}
+
+/// Decodes a domain's values as its base type.
+pub struct Unwrapped<T>(pub T);
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for Unwrapped<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxedError> {
+        T::from_sql(base_type(ty), raw).map(Unwrapped)
+    }
+
+    fn from_sql_null(ty: &Type) -> Result<Self, BoxedError> {
+        T::from_sql_null(base_type(ty)).map(Unwrapped)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        T::accepts(base_type(ty))
+    }
+}
+
+/// Binary value of any type.
+pub struct Raw<'a>(pub &'a [u8]);
+
+impl<'a> FromSql<'a> for Raw<'a> {
+    fn from_sql(_ty: &Type, raw: &'a [u8]) -> Result<Self, BoxedError> {
+        Ok(Raw(raw))
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+}
+
+fn conversion_error(typ: &str, value: impl std::fmt::Debug) -> PostgresSchemaError {
+    PostgresSchemaError::ValueConversionError(format!("invalid {typ} value {value:?}"))
+}
+
+fn read_i64(raw: &[u8], typ: &str) -> Result<i64, PostgresSchemaError> {
+    raw.get(0..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(i64::from_be_bytes)
+        .ok_or_else(|| conversion_error(typ, raw))
+}
+
+fn read_i32(raw: &[u8], typ: &str) -> Result<i32, PostgresSchemaError> {
+    raw.get(0..4)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(i32::from_be_bytes)
+        .ok_or_else(|| conversion_error(typ, raw))
+}
+
+fn micros_to_duration(micros: i128, typ: &str) -> Result<Duration, PostgresSchemaError> {
+    let micros = u64::try_from(micros).map_err(|_| conversion_error(typ, micros))?;
+    Ok(Duration::from_micros(micros))
+}
+
+/// `time` is the time since midnight.
+pub fn decode_time(raw: &[u8]) -> Result<Duration, PostgresSchemaError> {
+    micros_to_duration(read_i64(raw, "time")?.into(), "time")
+}
+
+pub fn parse_time(value: &str) -> Result<Duration, PostgresSchemaError> {
+    micros_to_duration(parse_time_micros(value)?.into(), "time")
+}
+
+/// `timetz` keeps its offset, so it is formatted as Postgres does.
+pub fn decode_timetz(raw: &[u8]) -> Result<String, PostgresSchemaError> {
+    let micros = read_i64(raw, "timetz")?;
+    // The offset is stored in seconds west of UTC.
+    let offset = -read_i32(raw.get(8..).unwrap_or_default(), "timetz")?;
+    Ok(format_timetz(micros, offset))
+}
+
+pub fn parse_timetz(value: &str) -> Result<String, PostgresSchemaError> {
+    let position = value
+        .rfind(['+', '-'])
+        .ok_or_else(|| conversion_error("timetz", value))?;
+    let (time, offset) = value.split_at(position);
+    let micros = parse_time_micros(time)?;
+
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let mut seconds = 0;
+    for (part, unit) in offset[1..].split(':').zip([3600, 60, 1]) {
+        let part: i32 = part
+            .parse()
+            .map_err(|_| conversion_error("timetz", value))?;
+        seconds += part * unit;
+    }
+    Ok(format_timetz(micros, sign * seconds))
+}
+
+fn parse_time_micros(value: &str) -> Result<i64, PostgresSchemaError> {
+    let error = || conversion_error("time", value);
+    let (time, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let mut parts = time.split(':');
+    let mut micros = 0;
+    for unit in [3600, 60, 1] {
+        let part: i64 = parts
+            .next()
+            .and_then(|part| part.parse().ok())
+            .ok_or_else(error)?;
+        micros += part * unit * 1_000_000;
+    }
+    if parts.next().is_some() || fraction.len() > 6 {
+        return Err(error());
+    }
+    if !fraction.is_empty() {
+        let fraction: i64 = format!("{fraction:0<6}").parse().map_err(|_| error())?;
+        micros += fraction;
+    }
+    Ok(micros)
+}
+
+/// Formats as `HH:MM:SS[.ffffff]+HH[:MM[:SS]]`, where `offset` is in seconds east of UTC.
+fn format_timetz(micros: i64, offset: i32) -> String {
+    let seconds = micros / 1_000_000;
+    let mut result = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    let fraction = micros % 1_000_000;
+    if fraction != 0 {
+        result += format!(".{fraction:06}").trim_end_matches('0');
+    }
+
+    result.push(if offset < 0 { '-' } else { '+' });
+    let offset = offset.abs();
+    result += &format!("{:02}", offset / 3600);
+    if offset % 3600 != 0 {
+        result += &format!(":{:02}", offset / 60 % 60);
+    }
+    if offset % 60 != 0 {
+        result += &format!(":{:02}", offset % 60);
+    }
+    result
+}
+
+/// An `interval`, which keeps months, days and time apart, each with its own sign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Interval {
+    months: i64,
+    days: i64,
+    micros: i64,
+}
+
+impl Display for Interval {
+    /// Formats in the `postgres` interval style, for example `-1 years -2 mons +3 days 04:05:06.7`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Like Postgres, a positive part following a negative one gets a `+`.
+        let mut is_zero = true;
+        let mut is_before = false;
+        for (value, unit) in [
+            (self.months / 12, "year"),
+            (self.months % 12, "mon"),
+            (self.days, "day"),
+        ] {
+            if value == 0 {
+                continue;
+            }
+            let separator = if is_zero { "" } else { " " };
+            let sign = if is_before && value > 0 { "+" } else { "" };
+            let plural = if value != 1 { "s" } else { "" };
+            write!(f, "{separator}{sign}{value} {unit}{plural}")?;
+            is_before = value < 0;
+            is_zero = false;
+        }
+
+        if is_zero || self.micros != 0 {
+            let separator = if is_zero { "" } else { " " };
+            let sign = if self.micros < 0 {
+                "-"
+            } else if is_before {
+                "+"
+            } else {
+                ""
+            };
+            let micros = self.micros.unsigned_abs();
+            let seconds = micros / 1_000_000;
+            write!(
+                f,
+                "{separator}{sign}{:02}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )?;
+            let fraction = micros % 1_000_000;
+            if fraction != 0 {
+                f.write_str(format!(".{fraction:06}").trim_end_matches('0'))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Intervals can be negative, so they are formatted as strings rather than converted to a duration.
+pub fn decode_interval(raw: &[u8]) -> Result<String, PostgresSchemaError> {
+    let micros = read_i64(raw, "interval")?;
+    let days = read_i32(raw.get(8..).unwrap_or_default(), "interval")?;
+    let months = read_i32(raw.get(12..).unwrap_or_default(), "interval")?;
+    Ok(Interval {
+        months: months.into(),
+        days: days.into(),
+        micros,
+    }
+    .to_string())
+}
+
+/// Parses the `postgres` interval style, which is the default, for example `1 year 2 mons -3 days 04:05:06.7`.
+///
+/// The result is formatted like `decode_interval` does.
+pub fn parse_interval(value: &str) -> Result<String, PostgresSchemaError> {
+    let error = || conversion_error("interval", value);
+    let mut interval = Interval::default();
+    let mut tokens = value.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if token.contains(':') {
+            let (sign, time) = match token.strip_prefix('-') {
+                Some(time) => (-1, time),
+                None => (1, token.trim_start_matches('+')),
+            };
+            interval.micros += sign * parse_time_micros(time)?;
+            continue;
+        }
+
+        let amount: i64 = token.parse().map_err(|_| error())?;
+        match tokens.next().ok_or_else(error)? {
+            "year" | "years" => interval.months += amount * 12,
+            "mon" | "mons" => interval.months += amount,
+            "day" | "days" => interval.days += amount,
+            _ => return Err(error()),
+        }
+    }
+    Ok(interval.to_string())
+}
+
+/// How the server formats `money`, which depends on its `lc_monetary` setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoneyFormat {
+    /// The number of fractional digits. `money` is stored as an integer count of the smallest fraction.
+    scale: u32,
+    /// Characters that only appear in negative amounts, like the `-` of `-$1.00` or the parentheses of `($1.00)`.
+    negative_markers: Vec<char>,
+}
+
+impl MoneyFormat {
+    /// `positive` and `negative` are how the server formats `1` and `-1`.
+    pub fn new(scale: u32, positive: &str, negative: &str) -> Self {
+        let negative_markers = negative
+            .chars()
+            .filter(|c| !c.is_ascii_digit() && !positive.contains(*c))
+            .collect();
+        Self {
+            scale,
+            negative_markers,
+        }
+    }
+
+    pub fn decode(&self, raw: &[u8]) -> Result<Decimal, PostgresSchemaError> {
+        Ok(Decimal::new(read_i64(raw, "money")?, self.scale))
+    }
+
+    /// Parses a formatted amount, such as `-$1,234.56`, `($1,234.56)` or `1.234,56 €`.
+    ///
+    /// All fractional digits are printed, so the digits alone are the amount in units of the smallest fraction.
+    pub fn parse(&self, value: &str) -> Result<Decimal, PostgresSchemaError> {
+        let digits = value
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>();
+        let units: i128 = digits
+            .parse()
+            .map_err(|_| conversion_error("money", value))?;
+        let is_negative = value.chars().any(|c| self.negative_markers.contains(&c));
+        let units = if is_negative { -units } else { units };
+        Decimal::try_from_i128_with_scale(units, self.scale)
+            .map_err(|_| conversion_error("money", value))
+    }
+}
+
+impl Default for MoneyFormat {
+    /// The format of the `C` and `en_US` locales.
+    fn default() -> Self {
+        Self::new(2, "$1.00", "-$1.00")
+    }
+}
+
+/// Formats as Postgres does: `inet` omits the netmask of a single address, `cidr` always has it.
+pub fn decode_inet(raw: &[u8], is_cidr: bool) -> Result<String, PostgresSchemaError> {
+    let inet =
+        postgres_protocol::types::inet_from_sql(raw).map_err(|_| conversion_error("inet", raw))?;
+    let max_netmask = match inet.addr() {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    };
+    Ok(if is_cidr || inet.netmask() != max_netmask {
+        format!("{}/{}", inet.addr(), inet.netmask())
+    } else {
+        inet.addr().to_string()
+    })
+}
+
+/// Decodes `macaddr` and `macaddr8`.
+pub fn decode_macaddr(raw: &[u8]) -> Result<String, PostgresSchemaError> {
+    if raw.len() != 6 && raw.len() != 8 {
+        return Err(conversion_error("macaddr", raw));
+    }
+    Ok(raw
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(":"))
+}
+
+/// Parses an array literal such as `{1,NULL,"a b"}`, including nested arrays of multidimensional arrays.
+///
+/// `parse_element` is called with the unquoted text of non null elements.
+pub fn parse_array(
+    value: &str,
+    parse_element: fn(&str) -> Result<Value, PostgresSchemaError>,
+) -> Result<Value, PostgresSchemaError> {
+    // Arrays with lower bounds other than 1 are prefixed with their dimensions, for example `[0:1]={1,2}`.
+    let literal = match value.split_once('=') {
+        Some((dimensions, literal)) if dimensions.starts_with('[') => literal,
+        _ => value,
+    };
+    let mut chars = literal.chars().peekable();
+    let array = parse_array_level(&mut chars, parse_element)
+        .ok_or_else(|| conversion_error("array", value))??;
+    if chars.next().is_some() {
+        return Err(conversion_error("array", value));
+    }
+    Ok(array)
+}
+
+fn parse_array_level(
+    chars: &mut Peekable<Chars>,
+    parse_element: fn(&str) -> Result<Value, PostgresSchemaError>,
+) -> Option<Result<Value, PostgresSchemaError>> {
+    if chars.next()? != '{' {
+        return None;
+    }
+    let mut elements = vec![];
+    if chars.peek() == Some(&'}') {
+        chars.next();
+        return Some(Ok(Value::Array(elements)));
+    }
+
+    loop {
+        let element = match chars.peek()? {
+            '{' => match parse_array_level(chars, parse_element)? {
+                Ok(element) => element,
+                Err(e) => return Some(Err(e)),
+            },
+            '"' => {
+                chars.next();
+                let text = read_quoted(chars)?;
+                match parse_element(&text) {
+                    Ok(element) => element,
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+            _ => {
+                let mut text = String::new();
+                while let Some(c) = chars.peek() {
+                    if *c == ',' || *c == '}' {
+                        break;
+                    }
+                    text.push(chars.next()?);
+                }
+                if text == "NULL" {
+                    Value::Null
+                } else {
+                    match parse_element(&text) {
+                        Ok(element) => element,
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+            }
+        };
+        elements.push(element);
+
+        match chars.next()? {
+            ',' => continue,
+            '}' => return Some(Ok(Value::Array(elements))),
+            _ => return None,
+        }
+    }
+}
+
+pub fn parse_int_element(text: &str) -> Result<Value, PostgresSchemaError> {
+    text.parse::<i64>()
+        .map(Value::from)
+        .map_err(|_| conversion_error("integer", text))
+}
+
+pub fn parse_float_element(text: &str) -> Result<Value, PostgresSchemaError> {
+    text.parse::<f64>()
+        .map(Value::from)
+        .map_err(|_| conversion_error("float", text))
+}
+
+/// `numeric` elements are kept as text, so they are converted to decimals without going through floats.
+pub fn parse_decimal_element(text: &str) -> Result<Value, PostgresSchemaError> {
+    text.parse::<Decimal>()
+        .map(|_| Value::String(text.to_string()))
+        .map_err(|_| conversion_error("numeric", text))
+}
+
+pub fn parse_bool_element(text: &str) -> Result<Value, PostgresSchemaError> {
+    match text {
+        "t" => Ok(Value::Bool(true)),
+        "f" => Ok(Value::Bool(false)),
+        _ => Err(conversion_error("boolean", text)),
+    }
+}
+
+pub fn parse_text_element(text: &str) -> Result<Value, PostgresSchemaError> {
+    Ok(Value::String(text.to_string()))
+}
+
+pub fn hstore_to_json(map: HashMap<String, Option<String>>) -> Value {
+    Value::Object(
+        map.into_iter()
+            .map(|(key, value)| (key, value.map_or(Value::Null, Value::String)))
+            .collect(),
+    )
+}
+
+/// Reads a quoted string after its opening quote.
+fn read_quoted(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut text = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(text),
+            '\\' => text.push(chars.next()?),
+            c => text.push(c),
+        }
+    }
+}
+
+/// Parses the text format of `hstore`, for example `"a"=>"1", "b"=>NULL`.
+pub fn parse_hstore(value: &str) -> Result<Value, PostgresSchemaError> {
+    let error = || conversion_error("hstore", value);
+    let mut map = HashMap::new();
+    let mut chars = value.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        match chars.next() {
+            None => break,
+            Some('"') => {}
+            Some(_) => return Err(error()),
+        }
+        let key = read_quoted(&mut chars).ok_or_else(error)?;
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.next() != Some('=') || chars.next() != Some('>') {
+            return Err(error());
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let value = if chars.next_if_eq(&'"').is_some() {
+            Some(read_quoted(&mut chars).ok_or_else(error)?)
+        } else {
+            let text = chars.by_ref().take_while(|c| *c != ',').collect::<String>();
+            if text.trim() != "NULL" {
+                return Err(error());
+            }
+            None
+        };
+        map.insert(key, value);
+    }
+    Ok(hstore_to_json(map))
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_ingestion_connector::dozer_types::serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_time() {
+        assert_eq!(
+            parse_time("04:05:06.789").unwrap(),
+            Duration::from_micros(14_706_789_000)
+        );
+        assert_eq!(
+            decode_time(&14_706_789_000i64.to_be_bytes()).unwrap(),
+            Duration::from_micros(14_706_789_000)
+        );
+        assert!(parse_time("04:05").is_err());
+    }
+
+    #[test]
+    fn test_timetz() {
+        let mut raw = 14_706_789_000i64.to_be_bytes().to_vec();
+        raw.extend((-19_800i32).to_be_bytes());
+        assert_eq!(decode_timetz(&raw).unwrap(), "04:05:06.789+05:30");
+        assert_eq!(
+            parse_timetz("04:05:06.789+05:30").unwrap(),
+            "04:05:06.789+05:30"
+        );
+        assert_eq!(parse_timetz("23:00:00-08").unwrap(), "23:00:00-08");
+    }
+
+    fn raw_interval(micros: i64, days: i32, months: i32) -> Vec<u8> {
+        let mut raw = micros.to_be_bytes().to_vec();
+        raw.extend(days.to_be_bytes());
+        raw.extend(months.to_be_bytes());
+        raw
+    }
+
+    #[test]
+    fn test_interval() {
+        let expected = "1 year 2 mons 3 days 04:05:06.7";
+        assert_eq!(parse_interval(expected).unwrap(), expected);
+        assert_eq!(
+            decode_interval(&raw_interval(14_706_700_000, 3, 14)).unwrap(),
+            expected
+        );
+
+        assert_eq!(
+            parse_interval("1 day -01:00:00").unwrap(),
+            "1 day -01:00:00"
+        );
+        assert_eq!(parse_interval("00:00:00").unwrap(), "00:00:00");
+        assert_eq!(decode_interval(&raw_interval(0, 0, 0)).unwrap(), "00:00:00");
+        assert_eq!(
+            decode_interval(&raw_interval(360_000_000_000, 0, 0)).unwrap(),
+            "100:00:00"
+        );
+        assert!(parse_interval("1 fortnight").is_err());
+    }
+
+    #[test]
+    fn test_negative_interval() {
+        assert_eq!(parse_interval("-1 days").unwrap(), "-1 days");
+        assert_eq!(
+            decode_interval(&raw_interval(-3_600_000_000, -1, 0)).unwrap(),
+            "-1 days -01:00:00"
+        );
+        assert_eq!(
+            decode_interval(&raw_interval(1_500_000, 3, -14)).unwrap(),
+            "-1 years -2 mons +3 days 00:00:01.5"
+        );
+        assert_eq!(
+            parse_interval("-1 years -2 mons +3 days 00:00:01.5").unwrap(),
+            "-1 years -2 mons +3 days 00:00:01.5"
+        );
+        assert_eq!(
+            decode_interval(&raw_interval(-500_000, 0, 0)).unwrap(),
+            "-00:00:00.5"
+        );
+    }
+
+    #[test]
+    fn test_money() {
+        let dollars = MoneyFormat::default();
+        assert_eq!(
+            dollars.parse("$1,234.56").unwrap(),
+            Decimal::new(123_456, 2)
+        );
+        assert_eq!(dollars.parse("-$1.50").unwrap(), Decimal::new(-150, 2));
+        assert_eq!(
+            dollars.decode(&123_456i64.to_be_bytes()).unwrap(),
+            Decimal::new(123_456, 2)
+        );
+
+        let accounting = MoneyFormat::new(2, "$1.00", "($1.00)");
+        assert_eq!(accounting.parse("($1.50)").unwrap(), Decimal::new(-150, 2));
+        assert_eq!(accounting.parse("$1.50").unwrap(), Decimal::new(150, 2));
+
+        let yen = MoneyFormat::new(0, "￥1", "-￥1");
+        assert_eq!(yen.parse("￥1,234").unwrap(), Decimal::new(1_234, 0));
+        assert_eq!(
+            yen.decode(&1_234i64.to_be_bytes()).unwrap(),
+            Decimal::new(1_234, 0)
+        );
+
+        let euros = MoneyFormat::new(2, "1,00 €", "-1,00 €");
+        assert_eq!(euros.parse("1.234,56 €").unwrap(), Decimal::new(123_456, 2));
+        assert_eq!(euros.parse("-0,01 €").unwrap(), Decimal::new(-1, 2));
+
+        assert_eq!(
+            dollars.parse("-$92,233,720,368,547,758.08").unwrap(),
+            Decimal::new(i64::MIN, 2)
+        );
+        assert!(dollars.parse("$").is_err());
+    }
+
+    #[test]
+    fn test_inet_and_macaddr() {
+        // family, netmask, is_cidr, address length, address
+        let raw = [2, 32, 0, 4, 192, 168, 0, 1];
+        assert_eq!(decode_inet(&raw, false).unwrap(), "192.168.0.1");
+        assert_eq!(decode_inet(&raw, true).unwrap(), "192.168.0.1/32");
+        let raw = [2, 16, 1, 4, 10, 1, 0, 0];
+        assert_eq!(decode_inet(&raw, true).unwrap(), "10.1.0.0/16");
+
+        assert_eq!(
+            decode_macaddr(&[8, 0, 0x2b, 1, 2, 3]).unwrap(),
+            "08:00:2b:01:02:03"
+        );
+        assert!(decode_macaddr(&[8, 0]).is_err());
+    }
+
+    #[test]
+    fn test_array() {
+        assert_eq!(
+            parse_array("{1,NULL,-3}", parse_int_element).unwrap(),
+            json!([1, null, -3])
+        );
+        assert_eq!(
+            parse_array("{{1.5,2},{3,4}}", parse_float_element).unwrap(),
+            json!([[1.5, 2.0], [3.0, 4.0]])
+        );
+        assert_eq!(
+            parse_array(r#"{a,"b,c","d\"e",NULL,"NULL"}"#, parse_text_element).unwrap(),
+            json!(["a", "b,c", "d\"e", null, "NULL"])
+        );
+        assert_eq!(
+            parse_array("[0:1]={t,f}", parse_bool_element).unwrap(),
+            json!([true, false])
+        );
+        assert_eq!(parse_array("{}", parse_int_element).unwrap(), json!([]));
+        assert_eq!(
+            parse_array("{1.10,NULL,-3}", parse_decimal_element).unwrap(),
+            json!(["1.10", null, "-3"])
+        );
+        assert!(parse_array("{1,a}", parse_int_element).is_err());
+        assert!(parse_array("{1,2", parse_int_element).is_err());
+    }
+
+    #[test]
+    fn test_hstore() {
+        assert_eq!(
+            parse_hstore(r#""a"=>"1", "b c"=>NULL, "d"=>"e\"f""#).unwrap(),
+            json!({"a": "1", "b c": null, "d": "e\"f"})
+        );
+        assert_eq!(parse_hstore("").unwrap(), json!({}));
+        assert!(parse_hstore(r#""a"=>1"#).is_err());
+    }
+
+    #[test]
+    fn test_custom_type() {
+        let enum_type = custom_type(100, "mood", "e", "public", None).unwrap();
+        assert!(is_enum(&enum_type));
+
+        let domain = custom_type(101, "positive", "d", "public", Some(Type::INT4)).unwrap();
+        assert_eq!(base_type(&domain), &Type::INT4);
+
+        let hstore = custom_type(102, "hstore", "b", "public", None).unwrap();
+        assert!(is_hstore(&hstore));
+
+        assert!(custom_type(103, "composite", "c", "public", None).is_none());
+    }
+}
@@ -0,0 +1,772 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::RangeInclusive;
+
+use dozer_ingestion_connector::{
+    dozer_types::{
+        models::ingestion_types::{IngestionMessage, TransactionInfo},
+        node::OpIdentifier,
+        types::{Field, Operation, Record, Schema},
+    },
+    Ingestor,
+};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+use crate::{
+    chunk::{SnapshotPosition, TableChunking, CHUNK_MAX, CHUNK_MIN},
+    connection::client::Client,
+    helper::{get_conversions, map_row_to_record},
+    schema::helper::DEFAULT_SCHEMA_NAME,
+    PostgresConnectorError,
+};
+
+pub const DEFAULT_WATERMARK_TABLE: &str = "dozer_watermarks";
+/// Index of the `watermark` column in the watermark table.
+pub const WATERMARK_COLUMN_INDEX: usize = 3;
+
+/// Table in the source database, which the connector writes watermarks to, and records snapshotted tables in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatermarkTable {
+    pub schema: String,
+    pub name: String,
+}
+
+impl WatermarkTable {
+    pub fn new(schema: Option<String>, name: Option<String>) -> Self {
+        Self {
+            schema: schema.unwrap_or_else(|| DEFAULT_SCHEMA_NAME.to_string()),
+            name: name.unwrap_or_else(|| DEFAULT_WATERMARK_TABLE.to_string()),
+        }
+    }
+
+    fn quoted_name(&self) -> String {
+        format!(r#""{}"."{}""#, self.schema, self.name)
+    }
+}
+
+/// Creates the watermark table if it doesn't exist, and returns its relation id.
+pub async fn create_watermark_table(
+    client: &mut Client,
+    table: &WatermarkTable,
+) -> Result<u32, PostgresConnectorError> {
+    let table_name = table.quoted_name();
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {table_name} (
+                slot_name text NOT NULL,
+                table_schema text NOT NULL,
+                table_name text NOT NULL,
+                watermark text,
+                snapshotted_lsn bigint,
+                PRIMARY KEY (slot_name, table_schema, table_name)
+            )"
+        ))
+        .await
+        .map_err(PostgresConnectorError::InvalidQueryError)?;
+
+    let row = client
+        .query_one(&format!("SELECT '{table_name}'::regclass::oid"), &[])
+        .await
+        .map_err(PostgresConnectorError::InvalidQueryError)?;
+    Ok(row.get(0))
+}
+
+/// Returns the tables that were snapshotted at or before `lsn`.
+///
+/// Returns `None` if no table was recorded for the slot, which is the case for slots created by earlier versions.
+pub async fn snapshotted_tables(
+    client: &mut Client,
+    watermark_table: &WatermarkTable,
+    slot_name: &str,
+    lsn: u64,
+) -> Result<Option<HashSet<(String, String)>>, PostgresConnectorError> {
+    let rows = client
+        .query(
+            &format!(
+                "SELECT table_schema, table_name, snapshotted_lsn FROM {} WHERE slot_name = $1",
+                watermark_table.quoted_name()
+            ),
+            &[&slot_name],
+        )
+        .await
+        .map_err(PostgresConnectorError::InvalidQueryError)?;
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        rows.iter()
+            .filter(|row| {
+                row.get::<_, Option<i64>>(2)
+                    .is_some_and(|snapshotted_lsn| snapshotted_lsn as u64 <= lsn)
+            })
+            .map(|row| (row.get(0), row.get(1)))
+            .collect(),
+    ))
+}
+
+/// Records that `tables` were snapshotted, and are up to date from `lsn` on.
+pub async fn mark_snapshotted(
+    client: &mut Client,
+    watermark_table: &WatermarkTable,
+    slot_name: &str,
+    tables: &[(String, String)],
+    lsn: u64,
+) -> Result<(), PostgresConnectorError> {
+    let query = format!(
+        "INSERT INTO {} (slot_name, table_schema, table_name, snapshotted_lsn) VALUES ($1, $2, $3, $4)
+        ON CONFLICT (slot_name, table_schema, table_name)
+        DO UPDATE SET watermark = NULL, snapshotted_lsn = excluded.snapshotted_lsn",
+        watermark_table.quoted_name()
+    );
+    for (schema, name) in tables {
+        client
+            .query(&query, &[&slot_name, schema, name, &(lsn as i64)])
+            .await
+            .map_err(PostgresConnectorError::InvalidQueryError)?;
+    }
+    Ok(())
+}
+
+async fn write_watermark(
+    client: &mut Client,
+    watermark_table: &WatermarkTable,
+    slot_name: &str,
+    table: &IncrementalTable,
+) -> Result<String, PostgresConnectorError> {
+    let watermark: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect();
+    client
+        .query(
+            &format!(
+                "INSERT INTO {} (slot_name, table_schema, table_name, watermark) VALUES ($1, $2, $3, $4)
+                ON CONFLICT (slot_name, table_schema, table_name)
+                DO UPDATE SET watermark = excluded.watermark",
+                watermark_table.quoted_name()
+            ),
+            &[
+                &slot_name,
+                &table.schema_name,
+                &table.table_name,
+                &watermark,
+            ],
+        )
+        .await
+        .map_err(PostgresConnectorError::InvalidQueryError)?;
+    Ok(watermark)
+}
+
+pub async fn publication_tables(
+    client: &mut Client,
+    publication_name: &str,
+) -> Result<HashSet<(String, String)>, PostgresConnectorError> {
+    let rows = client
+        .query(
+            "SELECT schemaname::text, tablename::text FROM pg_publication_tables WHERE pubname = $1",
+            &[&publication_name],
+        )
+        .await
+        .map_err(PostgresConnectorError::InvalidQueryError)?;
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+}
+
+pub async fn add_table_to_publication(
+    client: &mut Client,
+    publication_name: &str,
+    schema: &str,
+    name: &str,
+) -> Result<(), PostgresConnectorError> {
+    client
+        .batch_execute(&format!(
+            r#"ALTER PUBLICATION {publication_name} ADD TABLE "{schema}"."{name}""#
+        ))
+        .await
+        .map_err(PostgresConnectorError::CreatePublicationError)
+}
+
+/// A table added to a running replication.
+#[derive(Debug, Clone)]
+pub struct IncrementalTable {
+    pub table_index: usize,
+    pub schema_name: String,
+    pub table_name: String,
+    pub schema: Schema,
+    pub chunking: TableChunking,
+}
+
+impl IncrementalTable {
+    fn key(&self, record: &Record) -> Vec<Field> {
+        self.schema
+            .primary_index
+            .iter()
+            .map(|index| record.values.get(*index).cloned().unwrap_or(Field::Null))
+            .collect()
+    }
+
+    fn query(&self, chunks: &RangeInclusive<i64>) -> String {
+        let column_str = self
+            .schema
+            .fields
+            .iter()
+            .map(|f| format!("\"{0}\"", f.name))
+            .collect::<Vec<_>>()
+            .join(",");
+        let query = format!(
+            r#"select {column_str} from "{}"."{}""#,
+            self.schema_name, self.table_name
+        );
+        let Some(column) = self.chunking.primary_key_column() else {
+            return query;
+        };
+
+        let (start, _) = self.chunking.key_range(*chunks.start());
+        let (_, end) = self.chunking.key_range(*chunks.end());
+        let conditions = start
+            .map(|start| format!(r#""{column}" >= {start}"#))
+            .into_iter()
+            .chain(end.map(|end| format!(r#""{column}" < {end}"#)))
+            .collect::<Vec<_>>();
+        if conditions.is_empty() {
+            query
+        } else {
+            format!("{query} where {}", conditions.join(" and "))
+        }
+    }
+}
+
+/// Chunks read between a low and a high watermark.
+#[derive(Debug)]
+struct Window {
+    low: String,
+    high: String,
+    chunks: RangeInclusive<i64>,
+    rows: Vec<Record>,
+    /// Whether the low watermark has been replicated.
+    is_open: bool,
+    /// Latest values of keys changed in the window, `None` if deleted.
+    changes: HashMap<Vec<Field>, Option<Record>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyState {
+    Snapshotted,
+    InWindow,
+    NotSnapshotted,
+}
+
+/// Which tables and chunks have been snapshotted, and the changes in the open window.
+#[derive(Debug)]
+struct Progress {
+    /// Tables to snapshot, the first one is being snapshotted.
+    tables: VecDeque<IncrementalTable>,
+    next_chunk: i64,
+    window: Option<Window>,
+}
+
+impl Progress {
+    fn filter(&mut self, table_index: usize, op: Operation) -> Option<Operation> {
+        let Some(table) = self.tables.front() else {
+            return Some(op);
+        };
+        if table.table_index != table_index {
+            // Tables waiting for their snapshot are read entirely.
+            let is_waiting = self
+                .tables
+                .iter()
+                .any(|table| table.table_index == table_index);
+            return (!is_waiting).then_some(op);
+        }
+
+        match op {
+            Operation::Insert { new } => match self.key_state(&new) {
+                KeyState::Snapshotted => Some(Operation::Insert { new }),
+                KeyState::InWindow => {
+                    self.record_change(&new, Some(new.clone()));
+                    None
+                }
+                KeyState::NotSnapshotted => None,
+            },
+            Operation::Delete { old } => match self.key_state(&old) {
+                KeyState::Snapshotted => Some(Operation::Delete { old }),
+                KeyState::InWindow => {
+                    self.record_change(&old, None);
+                    None
+                }
+                KeyState::NotSnapshotted => None,
+            },
+            Operation::Update { old, new } => {
+                let old_state = self.key_state(&old);
+                let new_state = self.key_state(&new);
+                if old_state == KeyState::InWindow {
+                    self.record_change(&old, None);
+                }
+                if new_state == KeyState::InWindow {
+                    self.record_change(&new, Some(new.clone()));
+                }
+                match (
+                    old_state == KeyState::Snapshotted,
+                    new_state == KeyState::Snapshotted,
+                ) {
+                    (true, true) => Some(Operation::Update { old, new }),
+                    (true, false) => Some(Operation::Delete { old }),
+                    (false, true) => Some(Operation::Insert { new }),
+                    (false, false) => None,
+                }
+            }
+            Operation::BatchInsert { new } => {
+                let mut snapshotted = vec![];
+                for record in new {
+                    match self.key_state(&record) {
+                        KeyState::Snapshotted => snapshotted.push(record),
+                        KeyState::InWindow => self.record_change(&record, Some(record.clone())),
+                        KeyState::NotSnapshotted => {}
+                    }
+                }
+                (!snapshotted.is_empty()).then_some(Operation::BatchInsert { new: snapshotted })
+            }
+        }
+    }
+
+    /// Opens the window on its low watermark. On its high watermark, closes it and returns the table index and rows to ingest.
+    fn on_watermark(&mut self, watermark: &str) -> Option<(usize, Vec<Record>)> {
+        let window = self.window.as_mut()?;
+        if watermark == window.low {
+            window.is_open = true;
+            return None;
+        }
+        if watermark != window.high {
+            // Written before a restart.
+            return None;
+        }
+
+        let Window {
+            chunks,
+            rows,
+            mut changes,
+            ..
+        } = self.window.take()?;
+        let table = self.tables.front()?;
+        let mut rows = rows
+            .into_iter()
+            .filter(|row| !changes.contains_key(&table.key(row)))
+            .collect::<Vec<_>>();
+        rows.extend(changes.drain().filter_map(|(_, record)| record));
+
+        self.next_chunk = chunks.end().saturating_add(1);
+        Some((table.table_index, rows))
+    }
+
+    fn key_state(&self, record: &Record) -> KeyState {
+        let table = self.tables.front().expect("called for the current table");
+        let chunk = table.chunking.chunk_of_record(&record.values);
+        if chunk < self.next_chunk {
+            return KeyState::Snapshotted;
+        }
+        match &self.window {
+            Some(window) if window.is_open && window.chunks.contains(&chunk) => KeyState::InWindow,
+            _ => KeyState::NotSnapshotted,
+        }
+    }
+
+    fn record_change(&mut self, record: &Record, value: Option<Record>) {
+        let table = self.tables.front().expect("called for the current table");
+        let key = table.key(record);
+        if let Some(window) = &mut self.window {
+            window.changes.insert(key, value);
+        }
+    }
+}
+
+/// Snapshots tables added to a running replication without pausing it, as described in
+/// [DBLog](https://arxiv.org/abs/2010.12597).
+///
+/// Chunks are read between a low and a high watermark, which are written to the watermark table and replicated.
+/// A chunk may or may not contain changes of its keys replicated between the watermarks, so their latest values replace
+/// the chunk's rows when the high watermark is replicated. Changes of chunks that haven't been read are dropped,
+/// because reading the chunk includes them.
+#[derive(Debug)]
+pub struct IncrementalSnapshot {
+    client: Client,
+    watermark_table: WatermarkTable,
+    slot_name: String,
+    batch_size: usize,
+    progress: Progress,
+}
+
+impl IncrementalSnapshot {
+    /// `position` is where the first table's snapshot was interrupted.
+    pub fn new(
+        client: Client,
+        watermark_table: WatermarkTable,
+        slot_name: String,
+        batch_size: usize,
+        tables: Vec<IncrementalTable>,
+        position: Option<SnapshotPosition>,
+    ) -> Self {
+        let next_chunk = match (tables.first(), position) {
+            (Some(table), Some(position)) if table.table_index == position.table_index => {
+                position.chunk
+            }
+            _ => CHUNK_MIN,
+        };
+        Self {
+            client,
+            watermark_table,
+            slot_name,
+            batch_size: batch_size.max(1),
+            progress: Progress {
+                tables: tables.into(),
+                next_chunk,
+                window: None,
+            },
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.progress.tables.is_empty()
+    }
+
+    /// Position to resume from, if replication restarts after the current transaction.
+    pub fn position(&self) -> Option<SnapshotPosition> {
+        self.progress.tables.front().map(|table| SnapshotPosition {
+            table_index: table.table_index,
+            chunk: self.progress.next_chunk,
+        })
+    }
+
+    /// Starts reading the first chunk. `lsn` is where replication starts.
+    pub async fn start(
+        &mut self,
+        ingestor: &Ingestor,
+        lsn: u64,
+    ) -> Result<(), PostgresConnectorError> {
+        if self.is_done() {
+            return Ok(());
+        }
+        if ingestor
+            .handle_message(IngestionMessage::TransactionInfo(
+                TransactionInfo::SnapshottingStarted,
+            ))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
+        self.read_next_chunk(ingestor, lsn).await
+    }
+
+    /// Maps a replicated operation to what the snapshot doesn't contain.
+    pub fn filter(&mut self, table_index: usize, op: Operation) -> Option<Operation> {
+        self.progress.filter(table_index, op)
+    }
+
+    /// Handles a replicated watermark. `lsn` is the lsn of the transaction that wrote it.
+    pub async fn on_watermark(
+        &mut self,
+        watermark: &str,
+        lsn: u64,
+        ingestor: &Ingestor,
+    ) -> Result<(), PostgresConnectorError> {
+        let Some((table_index, rows)) = self.progress.on_watermark(watermark) else {
+            return Ok(());
+        };
+
+        let mut rows = rows.into_iter().peekable();
+        while rows.peek().is_some() {
+            let new = rows.by_ref().take(self.batch_size).collect();
+            if ingestor
+                .handle_message(IngestionMessage::OperationEvent {
+                    table_index,
+                    op: Operation::BatchInsert { new },
+                    id: Some(OpIdentifier::new(lsn, 0).into()),
+                    source_time: None,
+                })
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+        }
+
+        self.read_next_chunk(ingestor, lsn).await
+    }
+
+    /// Reads the next chunk between watermarks, moving on to the next table when the current one is done.
+    /// Finished tables are recorded as snapshotted at `lsn`.
+    async fn read_next_chunk(
+        &mut self,
+        ingestor: &Ingestor,
+        lsn: u64,
+    ) -> Result<(), PostgresConnectorError> {
+        while let Some(table) = self.progress.tables.front().cloned() {
+            if let Some(chunks) = self.next_chunks(&table).await? {
+                let low = write_watermark(
+                    &mut self.client,
+                    &self.watermark_table,
+                    &self.slot_name,
+                    &table,
+                )
+                .await?;
+                let rows = self.read_chunks(&table, &chunks).await?;
+                let high = write_watermark(
+                    &mut self.client,
+                    &self.watermark_table,
+                    &self.slot_name,
+                    &table,
+                )
+                .await?;
+                self.progress.window = Some(Window {
+                    low,
+                    high,
+                    chunks,
+                    rows,
+                    is_open: false,
+                    changes: HashMap::new(),
+                });
+                return Ok(());
+            }
+
+            mark_snapshotted(
+                &mut self.client,
+                &self.watermark_table,
+                &self.slot_name,
+                &[(table.schema_name, table.table_name)],
+                lsn,
+            )
+            .await?;
+            self.progress.tables.pop_front();
+            self.progress.next_chunk = CHUNK_MIN;
+
+            if ingestor
+                .handle_message(IngestionMessage::TransactionInfo(
+                    TransactionInfo::SnapshottingDone { id: None },
+                ))
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+            if !self.is_done()
+                && ingestor
+                    .handle_message(IngestionMessage::TransactionInfo(
+                        TransactionInfo::SnapshottingStarted,
+                    ))
+                    .await
+                    .is_err()
+            {
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+
+    /// Chunks from `next_chunk` to the next non-empty one, or `None` if the table is done.
+    async fn next_chunks(
+        &mut self,
+        table: &IncrementalTable,
+    ) -> Result<Option<RangeInclusive<i64>>, PostgresConnectorError> {
+        let next_chunk = self.progress.next_chunk;
+        let Some(column) = table.chunking.primary_key_column() else {
+            // The table is read as chunk 0.
+            return Ok((next_chunk <= 0).then_some(next_chunk..=0));
+        };
+        if next_chunk > CHUNK_MAX {
+            return Ok(None);
+        }
+
+        let condition = table
+            .chunking
+            .key_range(next_chunk)
+            .0
+            .map(|start| format!(r#" where "{column}" >= {start}"#))
+            .unwrap_or_default();
+        let row = self
+            .client
+            .query_one(
+                &format!(
+                    r#"select min("{column}")::bigint from "{}"."{}"{condition}"#,
+                    table.schema_name, table.table_name
+                ),
+                &[],
+            )
+            .await
+            .map_err(PostgresConnectorError::InvalidQueryError)?;
+        Ok(row
+            .get::<_, Option<i64>>(0)
+            .map(|key| next_chunk..=table.chunking.chunk_of_key(key)))
+    }
+
+    async fn read_chunks(
+        &mut self,
+        table: &IncrementalTable,
+        chunks: &RangeInclusive<i64>,
+    ) -> Result<Vec<Record>, PostgresConnectorError> {
+        let statement = self
+            .client
+            .prepare(&table.query(chunks))
+            .await
+            .map_err(PostgresConnectorError::InvalidQueryError)?;
+        let conversions = get_conversions(&mut self.client, &statement).await?;
+        self.client
+            .query(&statement, &[])
+            .await
+            .map_err(PostgresConnectorError::InvalidQueryError)?
+            .iter()
+            .map(|row| {
+                map_row_to_record(row, &conversions)
+                    .map_err(PostgresConnectorError::PostgresSchemaError)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_ingestion_connector::dozer_types::types::{
+        FieldDefinition, FieldType, SourceDefinition,
+    };
+
+    use super::*;
+
+    fn table(table_index: usize) -> IncrementalTable {
+        let mut schema = Schema::new();
+        schema
+            .field(
+                FieldDefinition::new(
+                    "id".to_string(),
+                    FieldType::Int,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                true,
+            )
+            .field(
+                FieldDefinition::new(
+                    "name".to_string(),
+                    FieldType::String,
+                    true,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            );
+        let chunking = TableChunking::new(&schema, 10);
+        IncrementalTable {
+            table_index,
+            schema_name: "public".to_string(),
+            table_name: format!("table_{table_index}"),
+            schema,
+            chunking,
+        }
+    }
+
+    fn record(id: i64, name: &str) -> Record {
+        Record::new(vec![Field::Int(id), Field::String(name.to_string())])
+    }
+
+    fn progress() -> Progress {
+        Progress {
+            tables: vec![table(1), table(2)].into(),
+            next_chunk: 1,
+            window: Some(Window {
+                low: "low".to_string(),
+                high: "high".to_string(),
+                chunks: 1..=2,
+                rows: vec![record(10, "a"), record(15, "b"), record(20, "c")],
+                is_open: false,
+                changes: HashMap::new(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_filter_tables() {
+        let mut progress = progress();
+        let insert = |id| Operation::Insert {
+            new: record(id, "x"),
+        };
+        // Tables that are not added are replicated.
+        assert_eq!(progress.filter(0, insert(0)), Some(insert(0)));
+        // Tables waiting for their snapshot are not.
+        assert_eq!(progress.filter(2, insert(0)), None);
+        // Snapshotted chunks are replicated, the others are not.
+        assert_eq!(progress.filter(1, insert(5)), Some(insert(5)));
+        assert_eq!(progress.filter(1, insert(10)), None);
+        assert_eq!(progress.filter(1, insert(30)), None);
+    }
+
+    #[test]
+    fn test_window() {
+        let mut progress = progress();
+        // Changes before the low watermark are in the chunk.
+        assert_eq!(
+            progress.filter(
+                1,
+                Operation::Update {
+                    old: record(10, "a0"),
+                    new: record(10, "a"),
+                }
+            ),
+            None
+        );
+        assert_eq!(progress.on_watermark("low"), None);
+
+        // Changes in the window replace the chunk's rows.
+        assert_eq!(
+            progress.filter(
+                1,
+                Operation::Update {
+                    old: record(15, "b"),
+                    new: record(15, "b1"),
+                }
+            ),
+            None
+        );
+        assert_eq!(
+            progress.filter(
+                1,
+                Operation::Delete {
+                    old: record(20, "c"),
+                }
+            ),
+            None
+        );
+        assert_eq!(
+            progress.filter(
+                1,
+                Operation::Insert {
+                    new: record(25, "d"),
+                }
+            ),
+            None
+        );
+        // A key moving out of the window is deleted from it.
+        assert_eq!(
+            progress.filter(
+                1,
+                Operation::Update {
+                    old: record(10, "a"),
+                    new: record(5, "a"),
+                }
+            ),
+            Some(Operation::Insert {
+                new: record(5, "a")
+            })
+        );
+
+        assert_eq!(progress.on_watermark("stale"), None);
+        let (table_index, mut rows) = progress.on_watermark("high").unwrap();
+        assert_eq!(table_index, 1);
+        rows.sort_by_key(|row| row.values[0].clone());
+        assert_eq!(rows, vec![record(15, "b1"), record(25, "d")]);
+
+        // Chunks in the window are snapshotted after the high watermark.
+        assert_eq!(progress.next_chunk, 3);
+        let insert = Operation::Insert {
+            new: record(29, "e"),
+        };
+        assert_eq!(progress.filter(1, insert.clone()), Some(insert));
+    }
+}
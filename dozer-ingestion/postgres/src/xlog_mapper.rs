@@ -9,9 +9,12 @@ use postgres_protocol::Lsn;
 use postgres_types::Type;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 
 use crate::{
     helper::{self, postgres_type_to_dozer_type},
+    type_conversion::MoneyFormat,
+    watermark::WATERMARK_COLUMN_INDEX,
     PostgresConnectorError, PostgresSchemaError,
 };
 
@@ -31,9 +34,21 @@ pub struct TableColumn {
 
 #[derive(Debug, Clone)]
 pub enum MappedReplicationMessage {
-    Begin,
+    Begin(Lsn, SystemTime),
     Commit(Lsn),
     Operation { table_index: usize, op: Operation },
+    Watermark(String),
+}
+
+/// Converts a replication message timestamp, which counts microseconds since 2000-01-01.
+fn postgres_time(micros: i64) -> SystemTime {
+    const POSTGRES_EPOCH_SECS: u64 = 946_684_800;
+    let postgres_epoch = SystemTime::UNIX_EPOCH + Duration::from_secs(POSTGRES_EPOCH_SECS);
+    if micros >= 0 {
+        postgres_epoch + Duration::from_micros(micros as u64)
+    } else {
+        postgres_epoch - Duration::from_micros(micros.unsigned_abs())
+    }
 }
 
 #[derive(Debug, Default)]
@@ -42,6 +57,12 @@ pub struct XlogMapper {
     relations_map: HashMap<u32, Table>,
     /// Relation id to (table index, column names).
     tables_columns: HashMap<u32, (usize, Vec<String>)>,
+    /// Relation id of the watermark table.
+    watermark_relation_id: Option<u32>,
+    /// Enums, domains and `hstore` by oid.
+    custom_types: HashMap<u32, Type>,
+    /// How the server formats `money`.
+    money_format: MoneyFormat,
 }
 
 impl XlogMapper {
@@ -49,9 +70,27 @@ impl XlogMapper {
         XlogMapper {
             relations_map: HashMap::<u32, Table>::new(),
             tables_columns,
+            watermark_relation_id: None,
+            custom_types: HashMap::new(),
+            money_format: MoneyFormat::default(),
         }
     }
 
+    pub fn with_custom_types(mut self, custom_types: HashMap<u32, Type>) -> Self {
+        self.custom_types = custom_types;
+        self
+    }
+
+    pub fn with_money_format(mut self, money_format: MoneyFormat) -> Self {
+        self.money_format = money_format;
+        self
+    }
+
+    pub fn with_watermark_relation_id(mut self, relation_id: u32) -> Self {
+        self.watermark_relation_id = Some(relation_id);
+        self
+    }
+
     pub fn handle_message(
         &mut self,
         message: XLogDataBody<LogicalReplicationMessage>,
@@ -63,8 +102,17 @@ impl XlogMapper {
             Commit(commit) => {
                 return Ok(Some(MappedReplicationMessage::Commit(commit.end_lsn())));
             }
-            Begin(_begin) => {
-                return Ok(Some(MappedReplicationMessage::Begin));
+            Begin(begin) => {
+                return Ok(Some(MappedReplicationMessage::Begin(
+                    begin.final_lsn(),
+                    postgres_time(begin.timestamp()),
+                )));
+            }
+            Insert(insert) if Some(insert.rel_id()) == self.watermark_relation_id => {
+                return Ok(Self::convert_watermark(insert.tuple().tuple_data()));
+            }
+            Update(update) if Some(update.rel_id()) == self.watermark_relation_id => {
+                return Ok(Self::convert_watermark(update.new_tuple().tuple_data()));
             }
             Insert(insert) => {
                 let Some(table_columns) = self.tables_columns.get(&insert.rel_id()) else {
@@ -75,7 +123,7 @@ impl XlogMapper {
                 let table = self.relations_map.get(&insert.rel_id()).unwrap();
                 let new_values = insert.tuple().tuple_data();
 
-                let values = Self::convert_values_to_fields(table, new_values, false)?;
+                let values = self.convert_values_to_fields(table, new_values, false)?;
 
                 let event = Operation::Insert {
                     new: Record::new(values),
@@ -95,8 +143,8 @@ impl XlogMapper {
                 let table = self.relations_map.get(&update.rel_id()).unwrap();
                 let new_values = update.new_tuple().tuple_data();
 
-                let values = Self::convert_values_to_fields(table, new_values, false)?;
-                let old_values = Self::convert_old_value_to_fields(table, update)?;
+                let values = self.convert_values_to_fields(table, new_values, false)?;
+                let old_values = self.convert_old_value_to_fields(table, update)?;
 
                 let event = Operation::Update {
                     old: Record::new(old_values),
@@ -118,7 +166,7 @@ impl XlogMapper {
                 let table = self.relations_map.get(&delete.rel_id()).unwrap();
                 let key_values = delete.key_tuple().unwrap().tuple_data();
 
-                let values = Self::convert_values_to_fields(table, key_values, true)?;
+                let values = self.convert_values_to_fields(table, key_values, true)?;
 
                 let event = Operation::Delete {
                     old: Record::new(values),
@@ -159,15 +207,10 @@ impl XlogMapper {
                 continue;
             }
 
-            // TODO: workaround - in case of custom enum
             let type_oid = column.type_id() as u32;
-            let typ = if type_oid == 28862 {
-                Type::VARCHAR
-            } else {
-                Type::from_oid(type_oid).ok_or_else(|| {
-                    PostgresSchemaError::InvalidColumnType(column_name.to_string())
-                })?
-            };
+            let typ = Type::from_oid(type_oid)
+                .or_else(|| self.custom_types.get(&type_oid).cloned())
+                .ok_or_else(|| PostgresSchemaError::InvalidColumnType(column_name.to_string()))?;
 
             columns.push(TableColumn {
                 name: column_name.to_string(),
@@ -225,7 +268,17 @@ impl XlogMapper {
         Ok(())
     }
 
+    fn convert_watermark(values: &[TupleData]) -> Option<MappedReplicationMessage> {
+        match values.get(WATERMARK_COLUMN_INDEX) {
+            Some(TupleData::Text(text)) => String::from_utf8(text.to_vec())
+                .ok()
+                .map(MappedReplicationMessage::Watermark),
+            _ => None,
+        }
+    }
+
     fn convert_values_to_fields(
+        &self,
         table: &Table,
         new_values: &[TupleData],
         only_key: bool,
@@ -237,12 +290,12 @@ impl XlogMapper {
                 let value = new_values.get(column.column_index).unwrap();
                 match value {
                     TupleData::Null => values.push(
-                        helper::postgres_type_to_field(None, column)
+                        helper::postgres_type_to_field(None, column, &self.money_format)
                             .map_err(PostgresConnectorError::PostgresSchemaError)?,
                     ),
                     TupleData::UnchangedToast => {}
                     TupleData::Text(text) => values.push(
-                        helper::postgres_type_to_field(Some(text), column)
+                        helper::postgres_type_to_field(Some(text), column, &self.money_format)
                             .map_err(PostgresConnectorError::PostgresSchemaError)?,
                     ),
                 }
@@ -255,14 +308,15 @@ impl XlogMapper {
     }
 
     fn convert_old_value_to_fields(
+        &self,
         table: &Table,
         update: &UpdateBody,
     ) -> Result<Vec<Field>, PostgresConnectorError> {
         match table.replica_identity {
             ReplicaIdentity::Default | ReplicaIdentity::Full | ReplicaIdentity::Index => {
                 update.key_tuple().map_or_else(
-                    || Self::convert_values_to_fields(table, update.new_tuple().tuple_data(), true),
-                    |key_tuple| Self::convert_values_to_fields(table, key_tuple.tuple_data(), true),
+                    || self.convert_values_to_fields(table, update.new_tuple().tuple_data(), true),
+                    |key_tuple| self.convert_values_to_fields(table, key_tuple.tuple_data(), true),
                 )
             }
             ReplicaIdentity::Nothing => Ok(vec![]),
@@ -3,8 +3,10 @@ use dozer_ingestion_connector::{
     dozer_types::{
         errors::internal::BoxedError,
         log::{info, warn},
-        models::ingestion_types::{default_snowflake_poll_interval, SnowflakeConfig},
-        node::OpIdentifier,
+        models::ingestion_types::{
+            default_snowflake_poll_interval, IngestionMessage, SnowflakeConfig, TransactionInfo,
+        },
+        node::{OpIdentifier, SourcePosition},
         types::FieldType,
     },
     tokio, Connector, Ingestor, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
@@ -123,8 +125,22 @@ impl Connector for SnowflakeConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        last_checkpoint: Option<OpIdentifier>,
+        last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
+        // Tables are read through their streams, there's no separate snapshot.
+        if ingestor
+            .handle_message(IngestionMessage::TransactionInfo(
+                TransactionInfo::SnapshottingSkipped,
+            ))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
+        let last_checkpoint = last_checkpoint
+            .as_ref()
+            .map(OpIdentifier::try_from)
+            .transpose()?;
         spawn_blocking({
             let name = self.name.clone();
             let config = self.config.clone();
@@ -126,7 +126,8 @@ impl StreamConsumer {
                     .blocking_handle_message(IngestionMessage::OperationEvent {
                         table_index,
                         op,
-                        id: Some(OpIdentifier::new(iteration, idx as u64)),
+                        id: Some(OpIdentifier::new(iteration, idx as u64).into()),
+                        source_time: None,
                     })
                     .is_err()
                 {
@@ -136,7 +137,7 @@ impl StreamConsumer {
                 if ingestor
                     .blocking_handle_message(IngestionMessage::TransactionInfo(
                         TransactionInfo::Commit {
-                            id: Some(OpIdentifier::new(iteration, idx as u64)),
+                            id: Some(OpIdentifier::new(iteration, idx as u64).into()),
                         },
                     ))
                     .is_err()
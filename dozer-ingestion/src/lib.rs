@@ -27,6 +27,7 @@ use dozer_ingestion_oracle::OracleConnector;
 use dozer_ingestion_postgres::{
     connection::helper::map_connection_config,
     connector::{PostgresConfig, PostgresConnector},
+    watermark::WatermarkTable,
 };
 #[cfg(feature = "snowflake")]
 use dozer_ingestion_snowflake::connector::SnowflakeConnector;
@@ -38,6 +39,8 @@ pub mod errors;
 pub use dozer_ingestion_connector::*;
 
 const DEFAULT_POSTGRES_SNAPSHOT_BATCH_SIZE: u32 = 100_000;
+const DEFAULT_POSTGRES_SNAPSHOT_CHUNK_SIZE: u64 = 1_000_000;
+const DEFAULT_POSTGRES_SNAPSHOT_PARALLELISM: u32 = 4;
 
 pub fn get_connector(
     runtime: Arc<Runtime>,
@@ -53,6 +56,16 @@ pub fn get_connector(
                 config,
                 schema: c.schema,
                 batch_size: c.batch_size.unwrap_or(DEFAULT_POSTGRES_SNAPSHOT_BATCH_SIZE) as usize,
+                snapshot_chunk_size: c
+                    .snapshot_chunk_size
+                    .unwrap_or(DEFAULT_POSTGRES_SNAPSHOT_CHUNK_SIZE),
+                snapshot_parallelism: c
+                    .snapshot_parallelism
+                    .unwrap_or(DEFAULT_POSTGRES_SNAPSHOT_PARALLELISM)
+                    as usize,
+                watermark_table: c.incremental_snapshot.map(|snapshot| {
+                    WatermarkTable::new(snapshot.watermark_schema, snapshot.watermark_table)
+                }),
             };
 
             if let Some(dbname) = postgres_config.config.get_dbname() {
@@ -114,6 +127,12 @@ pub fn get_connector(
         ConnectionConfig::LocalStorage(object_store_config) => {
             Ok(Box::new(ObjectStoreConnector::new(object_store_config)))
         }
+        ConnectionConfig::GcsStorage(object_store_config) => {
+            Ok(Box::new(ObjectStoreConnector::new(object_store_config)))
+        }
+        ConnectionConfig::AzureStorage(object_store_config) => {
+            Ok(Box::new(ObjectStoreConnector::new(object_store_config)))
+        }
         ConnectionConfig::DeltaLake(delta_lake_config) => {
             Ok(Box::new(DeltaLakeConnector::new(delta_lake_config)))
         }
@@ -161,6 +180,8 @@ pub fn get_connector_info_table(connection: &Connection) -> Option<Table> {
         ConnectionConfig::Kafka(config) => Some(config.convert_to_table()),
         ConnectionConfig::S3Storage(config) => Some(config.convert_to_table()),
         ConnectionConfig::LocalStorage(config) => Some(config.convert_to_table()),
+        ConnectionConfig::GcsStorage(config) => Some(config.convert_to_table()),
+        ConnectionConfig::AzureStorage(config) => Some(config.convert_to_table()),
         _ => None,
     }
 }
@@ -309,6 +309,8 @@ fn assert_record_matches_schema(record: &Record, schema: &Schema, only_match_pk:
             FieldType::Json => assert!(value.as_json().is_some()),
             FieldType::Point => assert!(value.as_point().is_some()),
             FieldType::Duration => assert!(value.as_duration().is_some()),
+            FieldType::List(_) => assert!(value.as_list().is_some()),
+            FieldType::Struct(_) => assert!(value.as_struct().is_some()),
         }
     }
 }
@@ -273,7 +273,7 @@ pub fn record_batch_with_all_supported_data_types() -> arrow::record_batch::Reco
         .expect("BUG in record_batch_with_all_supported_data_types")
 }
 
-fn field_type_to_arrow(field_type: FieldType) -> Option<arrow::datatypes::DataType> {
+fn field_type_to_arrow(field_type: &FieldType) -> Option<arrow::datatypes::DataType> {
     match field_type {
         FieldType::UInt => Some(arrow::datatypes::DataType::UInt64),
         FieldType::U128 => None,
@@ -295,11 +295,12 @@ fn field_type_to_arrow(field_type: FieldType) -> Option<arrow::datatypes::DataTy
         FieldType::Duration => Some(arrow::datatypes::DataType::Duration(
             arrow::datatypes::TimeUnit::Nanosecond,
         )),
+        FieldType::List(_) | FieldType::Struct(_) => None,
     }
 }
 
 fn field_definition_to_arrow(field_definition: FieldDefinition) -> Option<arrow::datatypes::Field> {
-    field_type_to_arrow(field_definition.typ).map(|data_type| {
+    field_type_to_arrow(&field_definition.typ).map(|data_type| {
         arrow::datatypes::Field::new(field_definition.name, data_type, field_definition.nullable)
     })
 }
@@ -314,7 +315,7 @@ pub fn schema_to_arrow(fields: Vec<FieldDefinition>) -> (arrow::datatypes::Schem
 
     let fields = fields
         .into_iter()
-        .filter_map(|field| field_type_to_arrow(field.typ).map(|_| field))
+        .filter_map(|field| field_type_to_arrow(&field.typ).map(|_| field))
         .collect();
 
     (arrow_schema, (fields, vec![]))
@@ -323,7 +324,7 @@ pub fn schema_to_arrow(fields: Vec<FieldDefinition>) -> (arrow::datatypes::Schem
 fn fields_to_arrow<'a, F: IntoIterator<Item = &'a Field>>(
     fields: F,
     count: usize,
-    field_type: FieldType,
+    field_type: &FieldType,
 ) -> Arc<dyn arrow::array::Array> {
     match field_type {
         FieldType::UInt => {
@@ -443,6 +444,7 @@ fn fields_to_arrow<'a, F: IntoIterator<Item = &'a Field>>(
             Arc::new(builder.finish())
         }
         FieldType::Point => panic!("Point not supported"),
+        FieldType::List(_) | FieldType::Struct(_) => panic!("Nested types not supported"),
         FieldType::Duration => {
             let mut builder = arrow::array::DurationNanosecondArray::builder(count);
             for field in fields {
@@ -463,9 +465,9 @@ pub fn records_to_arrow(
 ) -> arrow::record_batch::RecordBatch {
     let mut columns = vec![];
     for (index, field) in fields.iter().enumerate() {
-        if field_type_to_arrow(field.typ).is_some() {
+        if field_type_to_arrow(&field.typ).is_some() {
             let fields = records.iter().map(|record| &record[index]);
-            let column = fields_to_arrow(fields, records.len(), field.typ);
+            let column = fields_to_arrow(fields, records.len(), &field.typ);
             columns.push(column);
         }
     }
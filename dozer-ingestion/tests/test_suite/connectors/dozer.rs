@@ -208,6 +208,7 @@ async fn create_nested_dozer_server(
             connection: "ingest".to_owned(),
             schema: None,
             refresh_config: Default::default(),
+            rate_limit: None,
         }],
         sinks: vec![Endpoint {
             table_name: table_name.clone(),
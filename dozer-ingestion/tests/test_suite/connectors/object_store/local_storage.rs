@@ -98,6 +98,7 @@ fn create_connector(
                 marker_extension: None,
             }),
             name: table_name,
+            after_ingestion: None,
         }],
     };
     let connector = ObjectStoreConnector::new(local_storage);
@@ -134,6 +134,9 @@ async fn create_postgres_server() -> (Client, PostgresConnectorTest, PostgresCon
             config: config.clone(),
             schema: None,
             batch_size: 1000,
+            snapshot_chunk_size: 100_000,
+            snapshot_parallelism: 4,
+            watermark_table: None,
         },
         None,
     )
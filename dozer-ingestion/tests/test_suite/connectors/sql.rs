@@ -8,6 +8,9 @@ use crate::test_suite::{records::Operation, FieldsAndPk};
 pub fn create_table_with_all_supported_data_types(table_name: &str) -> String {
     format!(
         r#"
+        CREATE EXTENSION IF NOT EXISTS hstore;
+        CREATE TYPE mood AS ENUM ('sad', 'happy');
+        CREATE DOMAIN positive AS INT4 CHECK (VALUE > 0);
         CREATE TABLE {table_name} (
             boolean BOOLEAN NOT NULL,
             boolean_null BOOLEAN, 
@@ -50,7 +53,31 @@ pub fn create_table_with_all_supported_data_types(table_name: &str) -> String {
             point POINT NOT NULL,
             point_null POINT,
             uuid UUID NOT NULL,
-            uuid_null UUID
+            uuid_null UUID,
+            time TIME NOT NULL,
+            time_null TIME,
+            timetz TIMETZ NOT NULL,
+            timetz_null TIMETZ,
+            interval INTERVAL NOT NULL,
+            interval_null INTERVAL,
+            int4_array INT4[] NOT NULL,
+            int4_array_null INT4[],
+            numeric_array NUMERIC[] NOT NULL,
+            numeric_array_null NUMERIC[],
+            inet INET NOT NULL,
+            inet_null INET,
+            cidr CIDR NOT NULL,
+            cidr_null CIDR,
+            macaddr MACADDR NOT NULL,
+            macaddr_null MACADDR,
+            money MONEY NOT NULL,
+            money_null MONEY,
+            hstore HSTORE NOT NULL,
+            hstore_null HSTORE,
+            mood MOOD NOT NULL,
+            mood_null MOOD,
+            positive POSITIVE NOT NULL,
+            positive_null POSITIVE
         );
         INSERT INTO {table_name} VALUES (
             false,
@@ -94,7 +121,31 @@ pub fn create_table_with_all_supported_data_types(table_name: &str) -> String {
             '(0,0)',
             '(0,0)',
             'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::UUID,
-            'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::UUID
+            'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::UUID,
+            '00:00:00',
+            '00:00:00',
+            '00:00:00+00',
+            '00:00:00+00',
+            '0 seconds',
+            '0 seconds',
+            ARRAY[]::int4[],
+            ARRAY[]::int4[],
+            ARRAY[]::numeric[],
+            ARRAY[]::numeric[],
+            '0.0.0.0',
+            '0.0.0.0',
+            '0.0.0.0/32',
+            '0.0.0.0/32',
+            '00:00:00:00:00:00',
+            '00:00:00:00:00:00',
+            0,
+            0,
+            '',
+            '',
+            'sad',
+            'sad',
+            1,
+            1
         );
         INSERT INTO {table_name} VALUES (
             true,
@@ -138,6 +189,30 @@ pub fn create_table_with_all_supported_data_types(table_name: &str) -> String {
             '(1,1)',
             null,
             'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::UUID,
+            null,
+            '04:05:06.789',
+            null,
+            '04:05:06.789+05:30',
+            null,
+            '1 year 2 mons 3 days 04:05:06',
+            null,
+            ARRAY[1, NULL, 3]::int4[],
+            null,
+            ARRAY[1.5, 2.25]::numeric[],
+            null,
+            '192.168.0.1/24',
+            null,
+            '192.168.0.0/24',
+            null,
+            '08:00:2b:01:02:03',
+            null,
+            1234.56,
+            null,
+            'a => 1, b => NULL',
+            null,
+            'happy',
+            null,
+            5,
             null
         );
         "#,
@@ -153,7 +228,7 @@ pub fn create_schema(name: &str) -> String {
     )
 }
 
-fn field_type_to_sql(field_type: FieldType) -> Option<String> {
+fn field_type_to_sql(field_type: &FieldType) -> Option<String> {
     match field_type {
         FieldType::UInt => None,
         FieldType::U128 => None,
@@ -169,12 +244,13 @@ fn field_type_to_sql(field_type: FieldType) -> Option<String> {
         FieldType::Date => Some("DATE".to_string()),
         FieldType::Json => Some("JSONB".to_string()),
         FieldType::Point => Some("POINT".to_string()),
-        FieldType::Duration => Some("DURATION".to_string()),
+        FieldType::Duration => Some("INTERVAL".to_string()),
+        FieldType::List(_) | FieldType::Struct(_) => None,
     }
 }
 
 fn field_definition_to_sql(field_definition: &FieldDefinition) -> Option<String> {
-    let field_type = field_type_to_sql(field_definition.typ)?;
+    let field_type = field_type_to_sql(&field_definition.typ)?;
     let nullable = if field_definition.nullable {
         ""
     } else {
@@ -240,8 +316,9 @@ fn field_to_sql(field: &Field) -> String {
         Field::Date(d) => format!("'{}'", d),
         Field::Json(b) => format!("'{}'::jsonb", json_to_string(b)),
         Field::Point(p) => format!("'({},{})'", p.0.x(), p.0.y()),
-        Field::Duration(_) => field.to_string(),
+        Field::Duration(d) => format!("'{} microseconds'::interval", d.0.as_micros()),
         Field::Null => "NULL".to_string(),
+        Field::List(_) | Field::Struct(_) => panic!("Nested types not supported"),
     }
 }
 
@@ -253,7 +330,7 @@ pub fn insert_record(
 ) -> String {
     let mut values_sql = vec![];
     for (field, value) in fields.iter().zip(record.iter()) {
-        if field_type_to_sql(field.typ).is_none() {
+        if field_type_to_sql(&field.typ).is_none() {
             continue;
         }
         values_sql.push(field_to_sql(value));
@@ -280,7 +357,7 @@ fn update_record(
     for (index, ((old_field, new_field), field_definition)) in
         old.iter().zip(new).zip(fields).enumerate()
     {
-        if field_type_to_sql(field_definition.typ).is_none() {
+        if field_type_to_sql(&field_definition.typ).is_none() {
             continue;
         }
 
@@ -319,7 +396,7 @@ fn delete_record(
 ) -> String {
     let mut where_ = vec![];
     for (index, (field, field_definition)) in record.iter().zip(fields).enumerate() {
-        if field_type_to_sql(field_definition.typ).is_none() {
+        if field_type_to_sql(&field_definition.typ).is_none() {
             continue;
         }
 
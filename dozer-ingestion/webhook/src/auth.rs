@@ -0,0 +1,220 @@
+use actix_web::{http::header::HeaderMap, http::header::AUTHORIZATION};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use dozer_ingestion_connector::dozer_types::{
+    chrono::Utc,
+    models::ingestion_types::WebhookAuth,
+    serde_json,
+    thiserror::{self, Error},
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AuthError {
+    #[error("missing header {0}")]
+    MissingHeader(String),
+    #[error("invalid secret")]
+    InvalidSecret,
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("malformed token")]
+    MalformedToken,
+    #[error("unsupported token algorithm {0}")]
+    UnsupportedAlgorithm(String),
+    #[error("token expired")]
+    TokenExpired,
+}
+
+pub fn verify(auth: &WebhookAuth, headers: &HeaderMap, body: &[u8]) -> Result<(), AuthError> {
+    match auth {
+        WebhookAuth::SharedSecret { header, secret } => {
+            let value = get_header(headers, header)?;
+            if constant_time_eq(value.as_bytes(), secret.as_bytes()) {
+                Ok(())
+            } else {
+                Err(AuthError::InvalidSecret)
+            }
+        }
+        WebhookAuth::HmacSha256 { header, secret } => {
+            let value = get_header(headers, header)?;
+            let value = value.strip_prefix("sha256=").unwrap_or(value);
+            let signature = hex::decode(value).map_err(|_| AuthError::InvalidSignature)?;
+            let mut mac = new_mac(secret);
+            mac.update(body);
+            mac.verify_slice(&signature)
+                .map_err(|_| AuthError::InvalidSignature)
+        }
+        WebhookAuth::BearerJwt { secret } => {
+            let value = get_header(headers, AUTHORIZATION.as_str())?;
+            let token = value
+                .strip_prefix("Bearer ")
+                .ok_or(AuthError::MalformedToken)?;
+            verify_jwt(token, secret)
+        }
+    }
+}
+
+fn get_header<'a>(headers: &'a HeaderMap, name: &str) -> Result<&'a str, AuthError> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| AuthError::MissingHeader(name.to_string()))
+}
+
+fn new_mac(secret: &str) -> HmacSha256 {
+    HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size")
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn verify_jwt(token: &str, secret: &str) -> Result<(), AuthError> {
+    let mut parts = token.split('.');
+    let (Some(header), Some(payload), Some(signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(AuthError::MalformedToken);
+    };
+
+    let signed = &token[..header.len() + 1 + payload.len()];
+
+    let header: serde_json::Value = decode_json_part(header)?;
+    let algorithm = header["alg"].as_str().unwrap_or_default();
+    if algorithm != "HS256" {
+        return Err(AuthError::UnsupportedAlgorithm(algorithm.to_string()));
+    }
+
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| AuthError::MalformedToken)?;
+    let mut mac = new_mac(secret);
+    mac.update(signed.as_bytes());
+    mac.verify_slice(&signature)
+        .map_err(|_| AuthError::InvalidSignature)?;
+
+    let payload: serde_json::Value = decode_json_part(payload)?;
+    if let Some(exp) = payload.get("exp") {
+        let exp = exp.as_i64().ok_or(AuthError::MalformedToken)?;
+        if exp < Utc::now().timestamp() {
+            return Err(AuthError::TokenExpired);
+        }
+    }
+    Ok(())
+}
+
+fn decode_json_part(part: &str) -> Result<serde_json::Value, AuthError> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(part)
+        .map_err(|_| AuthError::MalformedToken)?;
+    serde_json::from_slice(&bytes).map_err(|_| AuthError::MalformedToken)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::header::{HeaderName, HeaderValue};
+
+    use super::*;
+
+    fn headers(name: &str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            HeaderName::from_bytes(name.as_bytes()).unwrap(),
+            HeaderValue::from_str(value).unwrap(),
+        );
+        headers
+    }
+
+    fn sign(secret: &str, data: &[u8]) -> Vec<u8> {
+        let mut mac = new_mac(secret);
+        mac.update(data);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn jwt(secret: &str, alg: &str, payload: serde_json::Value) -> String {
+        let header = URL_SAFE_NO_PAD.encode(serde_json::json!({ "alg": alg }).to_string());
+        let payload = URL_SAFE_NO_PAD.encode(payload.to_string());
+        let signed = format!("{header}.{payload}");
+        let signature = URL_SAFE_NO_PAD.encode(sign(secret, signed.as_bytes()));
+        format!("{signed}.{signature}")
+    }
+
+    #[test]
+    fn test_shared_secret() {
+        let auth = WebhookAuth::SharedSecret {
+            header: "x-api-key".to_string(),
+            secret: "secret".to_string(),
+        };
+        assert_eq!(verify(&auth, &headers("x-api-key", "secret"), b""), Ok(()));
+        assert_eq!(
+            verify(&auth, &headers("x-api-key", "wrong"), b""),
+            Err(AuthError::InvalidSecret)
+        );
+        assert_eq!(
+            verify(&auth, &HeaderMap::new(), b""),
+            Err(AuthError::MissingHeader("x-api-key".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_hmac_signature() {
+        let auth = WebhookAuth::HmacSha256 {
+            header: "x-hub-signature-256".to_string(),
+            secret: "secret".to_string(),
+        };
+        let body = br#"{"users": []}"#;
+        let signature = hex::encode(sign("secret", body));
+        assert_eq!(
+            verify(&auth, &headers("x-hub-signature-256", &signature), body),
+            Ok(())
+        );
+        assert_eq!(
+            verify(
+                &auth,
+                &headers("x-hub-signature-256", &format!("sha256={signature}")),
+                body
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            verify(&auth, &headers("x-hub-signature-256", &signature), b"{}"),
+            Err(AuthError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_bearer_jwt() {
+        let auth = WebhookAuth::BearerJwt {
+            secret: "secret".to_string(),
+        };
+        let bearer = |token: String| headers("authorization", &format!("Bearer {token}"));
+
+        let valid = jwt("secret", "HS256", serde_json::json!({ "sub": "dozer" }));
+        assert_eq!(verify(&auth, &bearer(valid), b""), Ok(()));
+
+        let wrong_secret = jwt("other", "HS256", serde_json::json!({}));
+        assert_eq!(
+            verify(&auth, &bearer(wrong_secret), b""),
+            Err(AuthError::InvalidSignature)
+        );
+
+        let expired = jwt("secret", "HS256", serde_json::json!({ "exp": 1 }));
+        assert_eq!(
+            verify(&auth, &bearer(expired), b""),
+            Err(AuthError::TokenExpired)
+        );
+
+        let none_alg = jwt("secret", "none", serde_json::json!({}));
+        assert_eq!(
+            verify(&auth, &bearer(none_alg), b""),
+            Err(AuthError::UnsupportedAlgorithm("none".to_string()))
+        );
+
+        assert_eq!(
+            verify(&auth, &bearer("abc".to_string()), b""),
+            Err(AuthError::MalformedToken)
+        );
+    }
+}
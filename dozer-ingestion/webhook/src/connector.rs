@@ -2,11 +2,13 @@ use crate::{server::WebhookServer, util::extract_source_schema, Error};
 use dozer_ingestion_connector::{
     async_trait,
     dozer_types::{
-        self, errors::internal::BoxedError, models::ingestion_types::WebhookConfig,
-        node::OpIdentifier,
+        self,
+        errors::internal::BoxedError,
+        models::ingestion_types::{IngestionMessage, TransactionInfo, WebhookConfig, WebhookVerb},
+        node::SourcePosition,
     },
     utils::TableNotFound,
-    Connector, Ingestor, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
+    CdcType, Connector, Ingestor, SourceSchema, SourceSchemaResult, TableIdentifier, TableInfo,
 };
 use std::{collections::HashMap, sync::Arc, vec};
 
@@ -25,7 +27,11 @@ impl WebhookConnector {
         let config = &self.config;
         for endpoint in &config.endpoints {
             let schemas = extract_source_schema(endpoint.schema.clone());
-            for (key, value) in schemas {
+            for (key, mut value) in schemas {
+                // PUT only knows the primary key of the old record.
+                if endpoint.verbs.contains(&WebhookVerb::PUT) {
+                    value.cdc_type = CdcType::OnlyPK;
+                }
                 result.insert(key, value);
             }
         }
@@ -145,8 +151,18 @@ impl Connector for WebhookConnector {
         &mut self,
         ingestor: &Ingestor,
         tables: Vec<TableInfo>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
+        // Requests are only received once the server is up, there's no snapshot.
+        if ingestor
+            .handle_message(IngestionMessage::TransactionInfo(
+                TransactionInfo::SnapshottingSkipped,
+            ))
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
         let config = self.config.clone();
         let server = WebhookServer::new(config);
         server
@@ -1,9 +1,11 @@
 use std::{net::AddrParseError, path::PathBuf};
 
 use dozer_ingestion_connector::dozer_types::{
+    errors::types::TypeError,
     serde_json,
     thiserror::{self, Error},
 };
+mod auth;
 pub mod connector;
 mod server;
 #[cfg(test)]
@@ -22,6 +24,8 @@ pub enum Error {
     SchemaNotFound(String),
     #[error("field {0} not found in schema")]
     FieldNotFound(String),
+    #[error("type error: {0}")]
+    Type(#[from] TypeError),
     #[error("actix web start error: {0}")]
     ActixWebStartError(#[from] std::io::Error),
 }
@@ -1,14 +1,16 @@
 use crate::{
+    auth,
     util::{extract_source_schema, map_record},
     Error,
 };
 use actix_web::{
-    web::{self, Data},
-    App, HttpRequest, HttpServer, Responder,
+    http::header::CONTENT_TYPE,
+    web::{self, Bytes, Data},
+    App, HttpRequest, HttpResponse, HttpServer,
 };
 use dozer_ingestion_connector::{
     dozer_types::{
-        models::ingestion_types::{IngestionMessage, WebhookConfig, WebhookVerb},
+        models::ingestion_types::{IngestionMessage, WebhookAuth, WebhookConfig, WebhookVerb},
         serde_json,
         types::{Operation, Record},
     },
@@ -16,6 +18,8 @@ use dozer_ingestion_connector::{
 };
 use std::{collections::HashMap, sync::Arc};
 
+const NDJSON_CONTENT_TYPES: [&str; 2] = ["application/x-ndjson", "application/jsonl"];
+
 pub(crate) struct WebhookServer {
     config: WebhookConfig,
 }
@@ -42,14 +46,15 @@ impl WebhookServer {
                 let mut app_resource = web::resource(endpoint_data.path)
                     .app_data(web::Data::new(Arc::clone(&ingestor)))
                     .app_data(web::Data::new(source_schema_dict))
-                    .app_data(web::Data::new(tables));
+                    .app_data(web::Data::new(tables))
+                    .app_data(web::Data::new(config.auth.clone()));
                 for verb in &endpoint.verbs {
                     app_resource = match verb {
                         WebhookVerb::POST => app_resource.route(web::post().to(Self::post_handler)),
+                        WebhookVerb::PUT => app_resource.route(web::put().to(Self::put_handler)),
                         WebhookVerb::DELETE => {
                             app_resource.route(web::delete().to(Self::delete_handler))
                         }
-                        _ => app_resource.route(web::route().to(Self::other_handler)),
                     };
                 }
                 app = app.service(app_resource);
@@ -67,15 +72,14 @@ impl WebhookServer {
         server.await.map_err(Into::into)
     }
 
+    /// Maps one JSON document of shape `{"<table>": <record or array of records>}` to operations.
     fn common_handler(
-        tables: Data<Vec<TableInfo>>,
-        schema_dict: Data<HashMap<String, SourceSchema>>,
-        info: web::Json<serde_json::Value>,
-    ) -> actix_web::Result<Vec<(usize, Vec<Record>)>, actix_web::error::Error> {
-        let source_schema_dict = schema_dict.get_ref();
-        let info = &info.into_inner();
-
-        let mut result: Vec<(usize, Vec<Record>)> = vec![];
+        verb: &WebhookVerb,
+        tables: &[TableInfo],
+        source_schema_dict: &HashMap<String, SourceSchema>,
+        info: serde_json::Value,
+    ) -> actix_web::Result<Vec<(usize, Vec<Operation>)>, actix_web::error::Error> {
+        let mut result: Vec<(usize, Vec<Operation>)> = vec![];
         if let serde_json::Value::Object(object) = info {
             for (schema_name, values) in object.iter() {
                 let schema = match source_schema_dict.get(schema_name) {
@@ -106,7 +110,7 @@ impl WebhookServer {
                     .iter()
                     .position(|table| table.name.as_str() == schema_name)
                     .ok_or_else(|| actix_web::error::ErrorBadRequest("Invalid table name"))?;
-                result.push((table_idx, records));
+                result.push((table_idx, Self::to_operations(verb, schema, records)?));
             }
         } else {
             return Err(actix_web::error::ErrorBadRequest("Invalid JSON"));
@@ -114,77 +118,200 @@ impl WebhookServer {
         Ok(result)
     }
 
-    async fn post_handler(
-        ingestor: Data<Arc<Ingestor>>,
-        schema_dict: Data<HashMap<String, SourceSchema>>,
-        tables: Data<Vec<TableInfo>>,
-        info: web::Json<serde_json::Value>,
-    ) -> actix_web::Result<impl Responder> {
-        let ingestor = ingestor.get_ref();
-        let records = Self::common_handler(tables, schema_dict, info)?;
-
-        for (table_idx, records) in records {
-            let op: IngestionMessage = if records.len() == 1 {
-                IngestionMessage::OperationEvent {
-                    table_index: table_idx,
-                    op: Operation::Insert {
-                        new: records[0].clone(),
-                    },
-                    id: None,
+    fn to_operations(
+        verb: &WebhookVerb,
+        schema: &SourceSchema,
+        mut records: Vec<Record>,
+    ) -> actix_web::Result<Vec<Operation>, actix_web::error::Error> {
+        Ok(match verb {
+            WebhookVerb::POST => {
+                if records.len() == 1 {
+                    vec![Operation::Insert {
+                        new: records.remove(0),
+                    }]
+                } else {
+                    vec![Operation::BatchInsert { new: records }]
                 }
-            } else {
-                IngestionMessage::OperationEvent {
-                    table_index: table_idx,
-                    op: Operation::BatchInsert { new: records },
-                    id: None,
+            }
+            WebhookVerb::PUT => {
+                let primary_index = &schema.schema.primary_index;
+                if primary_index.is_empty() {
+                    return Err(actix_web::error::ErrorBadRequest(
+                        "PUT requires a primary key in the table schema",
+                    ));
                 }
-            };
-            ingestor
-                .handle_message(op)
-                .await
-                .map_err(|e| actix_web::error::ErrorInternalServerError(format!("Error: {}", e)))?;
-        }
+                // Only the primary key of the old record is known.
+                records
+                    .into_iter()
+                    .map(|new| {
+                        let mut old = Record::nulls(new.values.len());
+                        for &index in primary_index {
+                            old.values[index] = new.values[index].clone();
+                        }
+                        Operation::Update { old, new }
+                    })
+                    .collect()
+            }
+            WebhookVerb::DELETE => records
+                .into_iter()
+                .map(|old| Operation::Delete { old })
+                .collect(),
+        })
+    }
 
-        let json_response = serde_json::json!({
-            "status": "ok"
-        });
+    fn is_ndjson(req: &HttpRequest) -> bool {
+        req.headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map_or(false, |value| {
+                NDJSON_CONTENT_TYPES
+                    .iter()
+                    .any(|content_type| value.starts_with(content_type))
+            })
+    }
 
-        Ok(web::Json(json_response))
+    /// Splits the body into JSON documents, paired with their 1-based line numbers.
+    /// NDJSON bodies have one document per non-empty line.
+    fn parse_body(
+        is_ndjson: bool,
+        body: &Bytes,
+    ) -> Vec<(usize, Result<serde_json::Value, String>)> {
+        if is_ndjson {
+            body.split(|byte| *byte == b'\n')
+                .enumerate()
+                .filter(|(_, line)| !line.iter().all(u8::is_ascii_whitespace))
+                .map(|(index, line)| {
+                    (
+                        index + 1,
+                        serde_json::from_slice(line).map_err(|e| e.to_string()),
+                    )
+                })
+                .collect()
+        } else {
+            vec![(1, serde_json::from_slice(body).map_err(|e| e.to_string()))]
+        }
     }
 
-    async fn delete_handler(
+    async fn handle(
+        verb: WebhookVerb,
+        req: HttpRequest,
+        body: Bytes,
         ingestor: Data<Arc<Ingestor>>,
         schema_dict: Data<HashMap<String, SourceSchema>>,
         tables: Data<Vec<TableInfo>>,
-        info: web::Json<serde_json::Value>,
-    ) -> actix_web::Result<impl Responder> {
+        auth: Data<Option<WebhookAuth>>,
+    ) -> actix_web::Result<HttpResponse> {
+        if let Some(auth) = auth.get_ref() {
+            auth::verify(auth, req.headers(), &body)
+                .map_err(|e| actix_web::error::ErrorUnauthorized(e.to_string()))?;
+        }
+
         let ingestor = ingestor.get_ref();
-        let records = Self::common_handler(tables, schema_dict, info)?;
-        for (table_idx, records) in records {
-            for record in records {
-                let op: IngestionMessage = IngestionMessage::OperationEvent {
-                    table_index: table_idx,
-                    op: Operation::Delete { old: record },
-                    id: None,
-                };
-                ingestor.handle_message(op).await.map_err(|e| {
-                    actix_web::error::ErrorInternalServerError(format!("Error: {}", e))
-                })?;
+        let is_batch = Self::is_ndjson(&req);
+        let documents = Self::parse_body(is_batch, &body);
+
+        let mut errors = vec![];
+        for (line, document) in documents {
+            let operations = document
+                .map_err(actix_web::error::ErrorBadRequest)
+                .and_then(|document| Self::common_handler(&verb, &tables, &schema_dict, document));
+            let operations = match operations {
+                Ok(operations) => operations,
+                // A single document fails the whole request, a batch reports errors per record.
+                Err(e) if !is_batch => return Err(e),
+                Err(e) => {
+                    errors.push(serde_json::json!({
+                        "line": line,
+                        "error": e.to_string(),
+                    }));
+                    continue;
+                }
+            };
+
+            for (table_idx, operations) in operations {
+                for op in operations {
+                    let op: IngestionMessage = IngestionMessage::OperationEvent {
+                        table_index: table_idx,
+                        op,
+                        id: None,
+                        source_time: None,
+                    };
+                    ingestor.handle_message(op).await.map_err(|e| {
+                        actix_web::error::ErrorInternalServerError(format!("Error: {}", e))
+                    })?;
+                }
             }
         }
 
-        let json_response = serde_json::json!({
-            "status": "ok"
-        });
+        if errors.is_empty() {
+            Ok(HttpResponse::Ok().json(serde_json::json!({
+                "status": "ok"
+            })))
+        } else {
+            Ok(HttpResponse::UnprocessableEntity().json(serde_json::json!({
+                "status": "partial",
+                "errors": errors,
+            })))
+        }
+    }
+
+    async fn post_handler(
+        req: HttpRequest,
+        body: Bytes,
+        ingestor: Data<Arc<Ingestor>>,
+        schema_dict: Data<HashMap<String, SourceSchema>>,
+        tables: Data<Vec<TableInfo>>,
+        auth: Data<Option<WebhookAuth>>,
+    ) -> actix_web::Result<HttpResponse> {
+        Self::handle(
+            WebhookVerb::POST,
+            req,
+            body,
+            ingestor,
+            schema_dict,
+            tables,
+            auth,
+        )
+        .await
+    }
 
-        Ok(web::Json(json_response))
+    async fn put_handler(
+        req: HttpRequest,
+        body: Bytes,
+        ingestor: Data<Arc<Ingestor>>,
+        schema_dict: Data<HashMap<String, SourceSchema>>,
+        tables: Data<Vec<TableInfo>>,
+        auth: Data<Option<WebhookAuth>>,
+    ) -> actix_web::Result<HttpResponse> {
+        Self::handle(
+            WebhookVerb::PUT,
+            req,
+            body,
+            ingestor,
+            schema_dict,
+            tables,
+            auth,
+        )
+        .await
     }
-    async fn other_handler(req: HttpRequest) -> actix_web::Result<impl Responder> {
-        // get VERB from request
-        let verb = req.method().as_str();
-        let json_response = serde_json::json!({
-            "status": format!("{} not supported", verb)
-        });
-        Ok(web::Json(json_response))
+
+    async fn delete_handler(
+        req: HttpRequest,
+        body: Bytes,
+        ingestor: Data<Arc<Ingestor>>,
+        schema_dict: Data<HashMap<String, SourceSchema>>,
+        tables: Data<Vec<TableInfo>>,
+        auth: Data<Option<WebhookAuth>>,
+    ) -> actix_web::Result<HttpResponse> {
+        Self::handle(
+            WebhookVerb::DELETE,
+            req,
+            body,
+            ingestor,
+            schema_dict,
+            tables,
+            auth,
+        )
+        .await
     }
 }
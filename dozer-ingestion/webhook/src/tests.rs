@@ -3,24 +3,19 @@ use dozer_ingestion_connector::{
     dozer_types::{
         json_types::json_from_str,
         models::ingestion_types::{
-            IngestionMessage, WebhookConfig, WebhookConfigSchemas, WebhookEndpoint, WebhookVerb,
+            IngestionMessage, TransactionInfo, WebhookAuth, WebhookConfig, WebhookConfigSchemas,
+            WebhookEndpoint, WebhookVerb,
         },
         serde_json::{self, json},
-        types::{Field, Record},
+        types::{Field, Operation, Record},
     },
     test_util::{create_test_runtime, spawn_connector_all_tables},
     tokio::runtime::Runtime,
-    IngestionIterator,
+    CdcType, Connector, IngestionIterator, TableInfo,
 };
 use std::sync::Arc;
 
-fn ingest_webhook(
-    runtime: Arc<Runtime>,
-    port: u32,
-) -> (
-    IngestionIterator,
-    dozer_ingestion_connector::futures::future::AbortHandle,
-) {
+fn webhook_connector(port: u32, auth: Option<WebhookAuth>) -> WebhookConnector {
     let user_schema = r#"
         {
             "users": {
@@ -41,7 +36,8 @@ fn ingest_webhook(
                     "typ": "Json",
                     "nullable": true
                   }
-                ]
+                ],
+                "primary_index": [0]
               }
             }
           }
@@ -71,7 +67,7 @@ fn ingest_webhook(
             }
           }
           "#;
-    let webhook_connector = WebhookConnector::new(WebhookConfig {
+    WebhookConnector::new(WebhookConfig {
         port: Some(port),
         host: None,
         endpoints: vec![
@@ -82,19 +78,56 @@ fn ingest_webhook(
             },
             WebhookEndpoint {
                 path: "/users".to_string(),
-                verbs: vec![WebhookVerb::POST, WebhookVerb::DELETE],
+                verbs: vec![WebhookVerb::POST, WebhookVerb::PUT, WebhookVerb::DELETE],
                 schema: WebhookConfigSchemas::Inline(user_schema.to_string()),
             },
         ],
-    });
-    spawn_connector_all_tables(runtime.clone(), webhook_connector)
+        auth,
+    })
+}
+
+fn ingest_webhook(
+    runtime: Arc<Runtime>,
+    port: u32,
+    auth: Option<WebhookAuth>,
+) -> (
+    IngestionIterator,
+    dozer_ingestion_connector::futures::future::AbortHandle,
+) {
+    let (mut iterator, abort_handle) =
+        spawn_connector_all_tables(runtime, webhook_connector(port, auth));
+    assert_eq!(
+        iterator.next(),
+        Some(IngestionMessage::TransactionInfo(
+            TransactionInfo::SnapshottingSkipped
+        ))
+    );
+    (iterator, abort_handle)
+}
+
+#[test]
+fn webhook_cdc_type() {
+    let runtime = create_test_runtime();
+    let mut connector = webhook_connector(58888, None);
+    let tables = ["customers", "users"]
+        .into_iter()
+        .map(|name| TableInfo {
+            schema: None,
+            name: name.to_string(),
+            column_names: vec![],
+        })
+        .collect::<Vec<_>>();
+    let schemas = runtime.block_on(connector.get_schemas(&tables)).unwrap();
+    // Only the endpoint accepting PUT sends partial old records.
+    assert_eq!(schemas[0].as_ref().unwrap().cdc_type, CdcType::Nothing);
+    assert_eq!(schemas[1].as_ref().unwrap().cdc_type, CdcType::OnlyPK);
 }
 
 #[test]
 fn ingest_webhook_batch_insert() {
     let runtime = create_test_runtime();
     let port = 58883;
-    let result: (IngestionIterator, _) = ingest_webhook(runtime.clone(), port);
+    let result: (IngestionIterator, _) = ingest_webhook(runtime.clone(), port, None);
     // call http request to webhook endpoint
     let client = reqwest::blocking::Client::new();
     let post_value = json!({
@@ -152,6 +185,7 @@ fn ingest_webhook_batch_insert() {
         table_index: _,
         op,
         id: _,
+        source_time: _,
     } = msg
     {
         assert_eq!(
@@ -169,7 +203,7 @@ fn ingest_webhook_batch_insert() {
 fn ingest_webhook_delete() {
     let runtime = create_test_runtime();
     let port = 58884;
-    let result: (IngestionIterator, _) = ingest_webhook(runtime.clone(), port);
+    let result: (IngestionIterator, _) = ingest_webhook(runtime.clone(), port, None);
     // call http request to webhook endpoint
     let client = reqwest::blocking::Client::new();
     let delete_value = json!({
@@ -209,6 +243,7 @@ fn ingest_webhook_delete() {
         table_index: _,
         op,
         id: _,
+        source_time: _,
     } = msg
     {
         if let dozer_ingestion_connector::dozer_types::types::Operation::Delete { old } = op {
@@ -220,3 +255,109 @@ fn ingest_webhook_delete() {
         panic!("Expected operation event");
     }
 }
+
+#[test]
+fn ingest_webhook_update() {
+    let runtime = create_test_runtime();
+    let port = 58885;
+    let result: (IngestionIterator, _) = ingest_webhook(runtime.clone(), port, None);
+    let client = reqwest::blocking::Client::new();
+    let put_value = json!({
+        "users": {
+            "id": 1,
+            "name": "John Smith"
+        }
+    });
+    let response = client
+        .put(format!("http://127.0.0.1:{:}/users", port))
+        .json(&put_value)
+        .send()
+        .unwrap();
+    assert!(response.status().is_success());
+
+    let mut iterator = result.0;
+    let msg = iterator.next().unwrap();
+    let IngestionMessage::OperationEvent { op, .. } = msg else {
+        panic!("Expected operation event");
+    };
+    assert_eq!(
+        op,
+        Operation::Update {
+            old: Record::new(vec![Field::Int(1), Field::Null, Field::Null]),
+            new: Record::new(vec![
+                Field::Int(1),
+                Field::String("John Smith".to_string()),
+                Field::Null
+            ]),
+        }
+    );
+}
+
+#[test]
+fn ingest_webhook_ndjson_batch() {
+    let runtime = create_test_runtime();
+    let port = 58886;
+    let result: (IngestionIterator, _) = ingest_webhook(runtime.clone(), port, None);
+    let client = reqwest::blocking::Client::new();
+    let body = [
+        json!({ "users": { "id": 1, "name": "John Doe" } }).to_string(),
+        json!({ "users": { "name": "No Id" } }).to_string(),
+        String::new(),
+        json!({ "users": { "id": 2, "name": "Jane Doe" } }).to_string(),
+    ]
+    .join("\n");
+    let response = client
+        .post(format!("http://127.0.0.1:{:}/users", port))
+        .header("Content-Type", "application/x-ndjson")
+        .body(body)
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+    let response: serde_json::Value = response.json().unwrap();
+    assert_eq!(response["errors"].as_array().unwrap().len(), 1);
+    assert_eq!(response["errors"][0]["line"], json!(2));
+
+    let mut iterator = result.0;
+    for id in [1, 2] {
+        let IngestionMessage::OperationEvent { op, .. } = iterator.next().unwrap() else {
+            panic!("Expected operation event");
+        };
+        let Operation::Insert { new } = op else {
+            panic!("Expected insert operation");
+        };
+        assert_eq!(new.values[0], Field::Int(id));
+    }
+}
+
+#[test]
+fn ingest_webhook_rejects_unauthenticated() {
+    let runtime = create_test_runtime();
+    let port = 58887;
+    let _result: (IngestionIterator, _) = ingest_webhook(
+        runtime.clone(),
+        port,
+        Some(WebhookAuth::SharedSecret {
+            header: "x-api-key".to_string(),
+            secret: "secret".to_string(),
+        }),
+    );
+    let client = reqwest::blocking::Client::new();
+    let post_value = json!({
+        "users": { "id": 1, "name": "John Doe" }
+    });
+
+    let response = client
+        .post(format!("http://127.0.0.1:{:}/users", port))
+        .json(&post_value)
+        .send()
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .post(format!("http://127.0.0.1:{:}/users", port))
+        .header("x-api-key", "secret")
+        .json(&post_value)
+        .send()
+        .unwrap();
+    assert!(response.status().is_success());
+}
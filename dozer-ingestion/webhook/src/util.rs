@@ -2,6 +2,7 @@ use crate::Error;
 use dozer_ingestion_connector::{
     dozer_types::{
         chrono::{self, NaiveDate},
+        helper::json_value_to_field,
         json_types::json_from_str,
         models::ingestion_types::WebhookConfigSchemas,
         ordered_float::OrderedFloat,
@@ -126,6 +127,9 @@ pub fn map_record(
                 FieldType::Duration => {
                     values.push(Field::Null);
                 }
+                typ @ (FieldType::List(_) | FieldType::Struct(_)) => {
+                    values.push(json_value_to_field(value.clone(), typ, true)?);
+                }
             },
             None => {
                 let field = Field::Null;
@@ -0,0 +1,32 @@
+use std::num::NonZeroI32;
+
+use dozer_deno::deno_runtime::deno_core::{error::AnyError, Extension};
+use dozer_types::json_types::JsonValue;
+
+use crate::Error;
+
+/// Calls the default export of a JavaScript module.
+#[derive(Debug)]
+pub struct Worker {
+    runtime: dozer_deno::Runtime,
+    function: NonZeroI32,
+}
+
+impl Worker {
+    pub async fn new(module: String) -> Result<Self, Error> {
+        let (runtime, functions) =
+            dozer_deno::Runtime::new::<fn() -> Extension>(vec![module], vec![]).await?;
+        Ok(Self {
+            runtime,
+            function: functions[0],
+        })
+    }
+
+    /// Calls the handler with `event`. If it returns a promise, waits for it to settle.
+    pub async fn call(&mut self, event: JsonValue) -> Result<(), AnyError> {
+        self.runtime
+            .call_function(self.function, vec![event])
+            .await
+            .map(|_| ())
+    }
+}
@@ -0,0 +1,40 @@
+//! Lambdas call user code with every change written to an endpoint.
+//!
+//! A lambda is a sink attached to the same table as its endpoint, so it sees operations in commit order.
+//! The handler is called from a delivery task, so retries don't hold up the sink.
+//! Delivery is at least once: the lambda checkpoints the position of the last commit whose operations
+//! have all been delivered, and the source restarts from there.
+
+use dozer_deno::deno_runtime::deno_core::error::AnyError;
+use dozer_types::thiserror::{self, Error};
+
+mod javascript;
+mod sink;
+mod state;
+
+pub use sink::{LambdaSinkFactory, RetryOptions};
+pub use state::LambdaState;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("failed to create JavaScript runtime: {0}")]
+    CreateRuntime(#[from] dozer_deno::RuntimeError),
+    #[error("lambda on endpoint {endpoint} failed after {attempts} attempts: {source}")]
+    Handler {
+        endpoint: String,
+        attempts: u32,
+        #[source]
+        source: AnyError,
+    },
+    #[error("lambda on endpoint {0} stopped after a failed delivery, restart to retry")]
+    Stopped(String),
+    #[error("checkpoint storage: {0}")]
+    Storage(#[from] dozer_core::dozer_log::storage::Error),
+    #[error("corrupted lambda checkpoint: {0}")]
+    CorruptedCheckpoint(#[source] bincode::error::DecodeError),
+    #[error("checkpoint queue has quit")]
+    CheckpointQueueQuit,
+}
+
+#[cfg(test)]
+mod tests;
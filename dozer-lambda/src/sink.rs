@@ -0,0 +1,329 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use dozer_core::{
+    dozer_log::storage::Queue,
+    epoch::Epoch,
+    node::{PortHandle, Sink, SinkFactory},
+    tokio::{self, runtime::Runtime, sync::mpsc, task::JoinHandle},
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_types::{
+    errors::internal::BoxedError,
+    json_types::{field_to_json_value, JsonObject, JsonValue},
+    log::warn,
+    node::{SourcePosition, SourceStates},
+    parking_lot::Mutex,
+    tonic::async_trait,
+    types::{Operation, Record, Schema, TableOperation},
+};
+
+use crate::{javascript::Worker, Error, LambdaState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryOptions {
+    /// Number of times a failed call is retried before the lambda stops.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every following one.
+    pub backoff: Duration,
+}
+
+impl Default for RetryOptions {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LambdaSinkFactory {
+    runtime: Arc<Runtime>,
+    endpoint: String,
+    module: String,
+    checkpoint_key: String,
+    state: LambdaState,
+    retry: RetryOptions,
+}
+
+impl LambdaSinkFactory {
+    /// `checkpoint_key` is where the lambda persists its `LambdaState`, and `state` is what was loaded from it.
+    pub fn new(
+        runtime: Arc<Runtime>,
+        endpoint: String,
+        module: String,
+        checkpoint_key: String,
+        state: Option<LambdaState>,
+    ) -> Self {
+        Self {
+            runtime,
+            endpoint,
+            module,
+            checkpoint_key,
+            state: state.unwrap_or_default(),
+            retry: RetryOptions::default(),
+        }
+    }
+
+    pub fn with_retry_options(mut self, retry: RetryOptions) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+#[async_trait]
+impl SinkFactory for LambdaSinkFactory {
+    fn get_input_ports(&self) -> Vec<PortHandle> {
+        vec![DEFAULT_PORT_HANDLE]
+    }
+
+    fn prepare(&self, input_schemas: HashMap<PortHandle, Schema>) -> Result<(), BoxedError> {
+        debug_assert!(input_schemas.len() == 1);
+        Ok(())
+    }
+
+    async fn build(
+        &self,
+        mut input_schemas: HashMap<PortHandle, Schema>,
+    ) -> Result<Box<dyn Sink>, BoxedError> {
+        let schema = input_schemas
+            .remove(&DEFAULT_PORT_HANDLE)
+            .expect("lambda sink has one input port");
+        let worker = Worker::new(self.module.clone()).await?;
+        let position = Arc::new(Mutex::new(self.state.position.clone()));
+        let (sender, receiver) = mpsc::channel(DELIVERY_QUEUE_CAPACITY);
+        let delivery = self.runtime.spawn(deliver(
+            worker,
+            self.endpoint.clone(),
+            self.retry,
+            receiver,
+            position.clone(),
+        ));
+        Ok(Box::new(LambdaSink {
+            runtime: self.runtime.clone(),
+            endpoint: self.endpoint.clone(),
+            field_names: schema.fields.into_iter().map(|field| field.name).collect(),
+            checkpoint_key: self.checkpoint_key.clone(),
+            state: self.state.clone(),
+            sender,
+            delivery: Some(delivery),
+            position,
+            last_position: None,
+        }))
+    }
+
+    fn type_name(&self) -> String {
+        "lambda".to_string()
+    }
+}
+
+/// Number of operations and commits that can wait for delivery before `process` blocks.
+const DELIVERY_QUEUE_CAPACITY: usize = 1024;
+
+#[derive(Debug)]
+enum Delivery {
+    Events(Vec<JsonValue>),
+    /// Everything before this has been delivered once it's received, so the position can be checkpointed.
+    Commit(Option<SourcePosition>),
+}
+
+#[derive(Debug)]
+pub struct LambdaSink {
+    runtime: Arc<Runtime>,
+    endpoint: String,
+    field_names: Vec<String>,
+    checkpoint_key: String,
+    /// Source state, and the position as of the last persist.
+    state: LambdaState,
+    /// Queue of the delivery task, which calls the handler off the sink thread.
+    sender: mpsc::Sender<Delivery>,
+    /// The delivery task, which only returns when a delivery fails. `None` once its error has been reported.
+    delivery: Option<JoinHandle<Error>>,
+    /// Position of the last commit whose operations have all been delivered.
+    position: Arc<Mutex<Option<SourcePosition>>>,
+    /// Position of the last operation queued since the last commit.
+    last_position: Option<SourcePosition>,
+}
+
+impl LambdaSink {
+    fn send(&mut self, delivery: Delivery) -> Result<(), Error> {
+        if self.sender.blocking_send(delivery).is_ok() {
+            return Ok(());
+        }
+        // The delivery task has stopped. Later operations are not delivered and the position stops advancing,
+        // so a restart delivers the failed operation again.
+        match self.delivery.take() {
+            Some(delivery) => Err(self
+                .runtime
+                .block_on(delivery)
+                .unwrap_or_else(|_| Error::Stopped(self.endpoint.clone()))),
+            None => Err(Error::Stopped(self.endpoint.clone())),
+        }
+    }
+}
+
+impl Sink for LambdaSink {
+    fn process(&mut self, op: TableOperation) -> Result<(), BoxedError> {
+        let events = op_to_events(&self.endpoint, &self.field_names, op.op);
+        self.send(Delivery::Events(events))?;
+        if op.id.is_some() {
+            self.last_position = op.id;
+        }
+        Ok(())
+    }
+
+    fn commit(&mut self, epoch_details: &Epoch) -> Result<(), BoxedError> {
+        let position = self
+            .last_position
+            .take()
+            .or_else(|| single_source_position(&epoch_details.common_info.source_states));
+        // A failed delivery is reported by the next `process`, the position just stops advancing.
+        let _ = self.send(Delivery::Commit(position));
+        Ok(())
+    }
+
+    fn persist(&mut self, _epoch: &Epoch, queue: &Queue) -> Result<(), BoxedError> {
+        self.state.position = self.position.lock().clone();
+        queue
+            .upload_object(self.checkpoint_key.clone(), self.state.encode())
+            .map_err(|_| Error::CheckpointQueueQuit)?;
+        Ok(())
+    }
+
+    fn on_source_snapshotting_started(
+        &mut self,
+        _connection_name: String,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn on_source_snapshotting_done(
+        &mut self,
+        _connection_name: String,
+        _id: Option<SourcePosition>,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    fn set_source_state(&mut self, source_state: &[u8]) -> Result<(), BoxedError> {
+        self.state.source_state = Some(source_state.to_vec());
+        Ok(())
+    }
+
+    fn get_source_state(&mut self) -> Result<Option<Vec<u8>>, BoxedError> {
+        Ok(self.state.source_state.clone())
+    }
+
+    /// The delivered position can lag behind the other sinks of the same source.
+    /// The source then restarts from the oldest position, see `SourceFactory::compare_positions`,
+    /// and the other sinks skip what they have already written.
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
+        Ok(self.position.lock().clone())
+    }
+}
+
+/// Delivers queued events in order, recording the position of every commit reached.
+/// Returns the error of the first delivery that fails after all retries.
+async fn deliver(
+    mut worker: Worker,
+    endpoint: String,
+    retry: RetryOptions,
+    mut receiver: mpsc::Receiver<Delivery>,
+    position: Arc<Mutex<Option<SourcePosition>>>,
+) -> Error {
+    while let Some(delivery) = receiver.recv().await {
+        match delivery {
+            Delivery::Events(events) => {
+                for event in events {
+                    if let Err(e) = deliver_event(&mut worker, &endpoint, retry, event).await {
+                        return e;
+                    }
+                }
+            }
+            Delivery::Commit(Some(commit_position)) => *position.lock() = Some(commit_position),
+            Delivery::Commit(None) => (),
+        }
+    }
+    Error::Stopped(endpoint)
+}
+
+async fn deliver_event(
+    worker: &mut Worker,
+    endpoint: &str,
+    retry: RetryOptions,
+    event: JsonValue,
+) -> Result<(), Error> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match worker.call(event.clone()).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempts <= retry.max_retries => {
+                let delay = retry
+                    .backoff
+                    .saturating_mul(2u32.saturating_pow(attempts - 1));
+                warn!(
+                    "Lambda on endpoint {endpoint} failed (attempt {attempts}), retrying in {delay:?}: {e}"
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(source) => {
+                return Err(Error::Handler {
+                    endpoint: endpoint.to_string(),
+                    attempts,
+                    source,
+                })
+            }
+        }
+    }
+}
+
+/// Operations coming out of SQL don't carry positions. If the pipeline has a single source,
+/// the epoch's position of that source covers everything queued before the commit.
+fn single_source_position(source_states: &SourceStates) -> Option<SourcePosition> {
+    let mut states = source_states.values();
+    match (states.next(), states.next()) {
+        (Some(state), None) => state.position(),
+        _ => None,
+    }
+}
+
+/// Converts an operation to the events passed to the handler, one per record.
+///
+/// An event looks like `{ endpoint, type: "insert" | "update" | "delete", old, new }`,
+/// with records as objects keyed by field name, and `null` for the absent record.
+pub(crate) fn op_to_events(
+    endpoint: &str,
+    field_names: &[String],
+    op: Operation,
+) -> Vec<JsonValue> {
+    let event = |typ: &str, old: Option<Record>, new: Option<Record>| {
+        let mut event = JsonObject::new();
+        event.insert("endpoint", endpoint);
+        event.insert("type", typ);
+        event.insert("old", record_to_json(field_names, old));
+        event.insert("new", record_to_json(field_names, new));
+        JsonValue::from(event)
+    };
+
+    match op {
+        Operation::Insert { new } => vec![event("insert", None, Some(new))],
+        Operation::Update { old, new } => vec![event("update", Some(old), Some(new))],
+        Operation::Delete { old } => vec![event("delete", Some(old), None)],
+        Operation::BatchInsert { new } => new
+            .into_iter()
+            .map(|new| event("insert", None, Some(new)))
+            .collect(),
+    }
+}
+
+fn record_to_json(field_names: &[String], record: Option<Record>) -> JsonValue {
+    let Some(record) = record else {
+        return JsonValue::NULL;
+    };
+    let mut object = JsonObject::new();
+    for (name, value) in field_names.iter().zip(record.values) {
+        object.insert(name.as_str(), field_to_json_value(value));
+    }
+    object.into()
+}
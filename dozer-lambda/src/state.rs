@@ -0,0 +1,34 @@
+use dozer_core::dozer_log::storage::Storage;
+use dozer_types::node::SourcePosition;
+
+use crate::Error;
+
+/// What a lambda has delivered, as of its last commit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct LambdaState {
+    /// Position of the last delivered operation. The source restarts from here.
+    pub position: Option<SourcePosition>,
+    /// Serialized state of the source, see `Sink::set_source_state`.
+    pub source_state: Option<Vec<u8>>,
+}
+
+impl LambdaState {
+    /// Loads the state persisted under `key`, or returns `None` if the lambda has never been checkpointed.
+    pub async fn load(storage: &dyn Storage, key: String) -> Result<Option<Self>, Error> {
+        let objects = storage.list_objects(key.clone(), None).await?.objects;
+        if !objects.iter().any(|object| object.key == key) {
+            return Ok(None);
+        }
+
+        let data = storage.download_object(key).await?;
+        let state = bincode::decode_from_slice(&data, bincode::config::legacy())
+            .map_err(Error::CorruptedCheckpoint)?
+            .0;
+        Ok(Some(state))
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        bincode::encode_to_vec(self, bincode::config::legacy())
+            .expect("encoding to vec must succeed")
+    }
+}
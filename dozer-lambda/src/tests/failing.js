@@ -0,0 +1,3 @@
+export default function (event) {
+    throw new Error(`cannot handle ${event.type}`);
+}
@@ -0,0 +1,9 @@
+let calls = 0;
+
+// Fails every other call, so each event is delivered on its first retry.
+export default function (event) {
+    calls += 1;
+    if (calls % 2 === 1) {
+        throw new Error(`call ${calls} failed`);
+    }
+}
@@ -0,0 +1,161 @@
+use std::{collections::HashMap, sync::Arc, time::Duration, time::SystemTime};
+
+use dozer_core::{
+    epoch::Epoch,
+    node::{Sink, SinkFactory},
+    tokio::runtime::Runtime,
+    DEFAULT_PORT_HANDLE,
+};
+use dozer_types::{
+    json_types::json,
+    node::SourcePosition,
+    types::{
+        Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
+        TableOperation,
+    },
+};
+
+use crate::{sink::op_to_events, LambdaSinkFactory, LambdaState, RetryOptions};
+
+fn field_names() -> Vec<String> {
+    vec!["id".to_string(), "name".to_string()]
+}
+
+fn schema() -> Schema {
+    let mut schema = Schema::default();
+    for (name, typ) in field_names()
+        .into_iter()
+        .zip([FieldType::Int, FieldType::String])
+    {
+        schema.field(
+            FieldDefinition::new(name, typ, false, SourceDefinition::Dynamic),
+            false,
+        );
+    }
+    schema
+}
+
+fn record(id: i64, name: &str) -> Record {
+    Record::new(vec![Field::Int(id), Field::String(name.to_string())])
+}
+
+fn build_sink(runtime: &Arc<Runtime>, module: &str, max_retries: u32) -> Box<dyn Sink> {
+    let factory = LambdaSinkFactory::new(
+        runtime.clone(),
+        "users".to_string(),
+        format!("src/tests/{module}"),
+        "lambdas/users".to_string(),
+        None,
+    )
+    .with_retry_options(RetryOptions {
+        max_retries,
+        backoff: Duration::ZERO,
+    });
+    runtime
+        .block_on(factory.build(HashMap::from([(DEFAULT_PORT_HANDLE, schema())])))
+        .unwrap()
+}
+
+fn insert(id: i64, position: u8) -> TableOperation {
+    TableOperation {
+        id: Some(SourcePosition::new(vec![position])),
+        op: Operation::Insert {
+            new: record(id, "alice"),
+        },
+        port: DEFAULT_PORT_HANDLE,
+    }
+}
+
+fn epoch() -> Epoch {
+    Epoch::new(0, Default::default(), None, None, SystemTime::now())
+}
+
+/// Delivery happens in the background, so give it some time to catch up.
+fn wait_until(mut condition: impl FnMut() -> bool) {
+    for _ in 0..500 {
+        if condition() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    panic!("condition not met in time");
+}
+
+#[test]
+fn test_op_to_events() {
+    let events = op_to_events(
+        "users",
+        &field_names(),
+        Operation::Update {
+            old: record(1, "alice"),
+            new: record(1, "bob"),
+        },
+    );
+    assert_eq!(
+        events,
+        vec![json!({
+            "endpoint": "users",
+            "type": "update",
+            "old": { "id": 1, "name": "alice" },
+            "new": { "id": 1, "name": "bob" },
+        })]
+    );
+
+    let events = op_to_events(
+        "users",
+        &field_names(),
+        Operation::BatchInsert {
+            new: vec![record(1, "alice"), record(2, "bob")],
+        },
+    );
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        events[1],
+        json!({
+            "endpoint": "users",
+            "type": "insert",
+            "old": null,
+            "new": { "id": 2, "name": "bob" },
+        })
+    );
+}
+
+#[test]
+fn test_retry_and_commit_position() {
+    let runtime = Arc::new(Runtime::new().unwrap());
+    let mut sink = build_sink(&runtime, "flaky.js", 1);
+
+    sink.process(insert(1, 1)).unwrap();
+    sink.process(insert(2, 2)).unwrap();
+    // Nothing is checkpointed before a commit.
+    assert_eq!(sink.get_latest_op_id().unwrap(), None);
+
+    sink.commit(&epoch()).unwrap();
+    wait_until(|| sink.get_latest_op_id().unwrap() == Some(SourcePosition::new(vec![2])));
+}
+
+#[test]
+fn test_failed_delivery_stops_position() {
+    let runtime = Arc::new(Runtime::new().unwrap());
+    let mut sink = build_sink(&runtime, "failing.js", 2);
+
+    // Queued, the failure surfaces on a later call.
+    sink.process(insert(1, 1)).unwrap();
+    sink.commit(&epoch()).unwrap();
+    wait_until(|| sink.process(insert(2, 2)).is_err());
+    assert!(sink.process(insert(3, 3)).is_err());
+    assert_eq!(sink.get_latest_op_id().unwrap(), None);
+}
+
+#[test]
+fn test_state_encoding() {
+    let state = LambdaState {
+        position: Some(SourcePosition::new(vec![1, 2, 3])),
+        source_state: Some(vec![4]),
+    };
+    let decoded: LambdaState =
+        bincode::decode_from_slice(&state.encode(), bincode::config::legacy())
+            .unwrap()
+            .0;
+    assert_eq!(decoded, state);
+}
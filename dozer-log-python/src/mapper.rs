@@ -93,6 +93,20 @@ fn map_value(value: Field, py: Python) -> PyResult<Py<PyAny>> {
         Field::Point(v) => map_point(v, py),
         Field::Duration(_) => Ok(value.to_string().to_object(py)),
         Field::Null => Ok(py.None()),
+        Field::List(values) => {
+            let list = PyList::empty(py);
+            for value in values {
+                list.append(map_value(value, py)?)?;
+            }
+            Ok(list.to_object(py))
+        }
+        Field::Struct(fields) => {
+            let dict = PyDict::new(py);
+            for (name, value) in fields {
+                dict.set_item(name, map_value(value, py)?)?;
+            }
+            Ok(dict.to_object(py))
+        }
     }
 }
 
@@ -1,3 +1,5 @@
+use std::time::SystemTime;
+
 use async_stream::try_stream;
 use camino::{Utf8Path, Utf8PathBuf};
 use dozer_types::{
@@ -101,6 +103,7 @@ impl CheckpointedLogReader {
 pub struct RecordStoreSliceMeta {
     pub key: String,
     pub epoch_id: u64,
+    pub last_modified: SystemTime,
     pub processor_prefix: Utf8PathBuf,
 }
 
@@ -128,6 +131,7 @@ pub fn list_record_store_slices<'a>(
                 yield RecordStoreSliceMeta {
                     key: object.key,
                     epoch_id,
+                    last_modified: object.last_modified,
                     processor_prefix,
                 };
             }
@@ -1,7 +1,7 @@
 use crossbeam_channel::{bounded, Receiver, Sender};
-use dozer_types::json_types::{DestructuredJsonRef, JsonValue};
+use dozer_types::json_types::{field_to_json_value, DestructuredJsonRef, JsonValue};
 use dozer_types::models::connection::AerospikeConnection;
-use dozer_types::node::OpIdentifier;
+use dozer_types::node::SourcePosition;
 use std::alloc::{handle_alloc_error, Layout};
 use std::ffi::{c_char, c_void, CStr, CString, NulError};
 use std::fmt::Display;
@@ -282,7 +282,7 @@ impl SinkFactory for AerospikeSinkFactory {
             0 => return Err(AerospikeSinkError::NoPrimaryKey.into()),
             _ => return Err(AerospikeSinkError::CompositePrimaryKey.into()),
         };
-        match schema.fields[primary_index].typ {
+        match &schema.fields[primary_index].typ {
             // These are definitely OK as the primary key
             dozer_types::types::FieldType::UInt
             | dozer_types::types::FieldType::U128
@@ -303,8 +303,10 @@ impl SinkFactory for AerospikeSinkFactory {
             typ @ (dozer_types::types::FieldType::Float|
             dozer_types::types::FieldType::Boolean |
             dozer_types::types::FieldType::Json |
+            dozer_types::types::FieldType::List(_) |
+            dozer_types::types::FieldType::Struct(_) |
             dozer_types::types::FieldType::Point ) =>  {
-                    return Err(Box::new(AerospikeSinkError::UnsupportedPrimaryKeyType(typ)));
+                    return Err(Box::new(AerospikeSinkError::UnsupportedPrimaryKeyType(typ.clone())));
                 }
         }
         for field in &schema.fields {
@@ -356,6 +358,10 @@ impl SinkFactory for AerospikeSinkFactory {
     fn type_name(&self) -> String {
         "aerospike".to_string()
     }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
 }
 
 // A wrapper type responsible for cleaning up a key. This doesn't own an as_key
@@ -680,7 +686,12 @@ impl AerospikeSinkWorker {
                     allocated_strings,
                 ),
                 Field::Null => unreachable!("Primary key cannot be null"),
-                Field::Boolean(_) | Field::Json(_) | Field::Point(_) | Field::Float(_) => {
+                Field::Boolean(_)
+                | Field::Json(_)
+                | Field::Point(_)
+                | Field::Float(_)
+                | Field::List(_)
+                | Field::Struct(_) => {
                     unreachable!("Unsupported primary key type. If this is reached, it means this record does not conform to the schema.")
                 }
             };
@@ -795,6 +806,11 @@ impl AerospikeSinkWorker {
                     let value = convert_json(v)?;
                     as_record_set(record, name, value);
                 }
+                // Lists and structs are written as Aerospike lists and maps.
+                nested @ (Field::List(_) | Field::Struct(_)) => {
+                    let value = convert_json(&field_to_json_value(nested.clone()))?;
+                    as_record_set(record, name, value);
+                }
             }
         }
         Ok(())
@@ -902,6 +918,10 @@ impl AerospikeSinkWorker {
                 Field::Json(v) => {
                     as_operations_add_write(ops, name, convert_json(v)?);
                 }
+                nested @ (Field::List(_) | Field::Struct(_)) => {
+                    let value = convert_json(&field_to_json_value(nested.clone()))?;
+                    as_operations_add_write(ops, name, value);
+                }
             }
         }
         Ok(())
@@ -913,7 +933,7 @@ impl AerospikeSinkWorker {
                 for rec in new.into_iter() {
                     self.process_impl(TableOperation {
                         op: Operation::Insert { new: rec },
-                        id: op.id,
+                        id: op.id.clone(),
                         port: op.port,
                     })?;
                 }
@@ -1183,7 +1203,7 @@ impl Sink for AerospikeSink {
     fn on_source_snapshotting_done(
         &mut self,
         connection_name: String,
-        _id: Option<OpIdentifier>,
+        _id: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         if let Some(started_instant) = self.snapshotting_started_instant.remove(&connection_name) {
             info!(
@@ -1208,7 +1228,7 @@ impl Sink for AerospikeSink {
         Ok(None)
     }
 
-    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
         Ok(None)
     }
 }
@@ -89,7 +89,8 @@ impl ClickhouseDDL {
             FieldType::Decimal => "Decimal",
             FieldType::Timestamp => "DateTime64(3)",
             FieldType::Date => "Date",
-            FieldType::Json => "JSON",
+            // Nested values are stored as JSON, as their element types are not known up front.
+            FieldType::Json | FieldType::List(_) | FieldType::Struct(_) => "JSON",
             FieldType::Point => "Point",
             FieldType::Duration => unimplemented!(),
         };
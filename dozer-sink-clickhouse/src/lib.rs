@@ -14,9 +14,10 @@ use dozer_core::DEFAULT_PORT_HANDLE;
 use dozer_log::storage::Queue;
 use dozer_log::tokio::runtime::Runtime;
 use dozer_types::errors::internal::BoxedError;
+use dozer_types::json_types::field_to_json_value;
 use dozer_types::log::debug;
 use dozer_types::models::endpoint::ClickhouseSinkConfig;
-use dozer_types::node::OpIdentifier;
+use dozer_types::node::SourcePosition;
 use dozer_types::serde::Serialize;
 use dozer_types::tonic::async_trait;
 use dozer_types::types::{
@@ -132,6 +133,9 @@ fn convert_field_to_ff(field: Field, nullable: bool) -> FieldWrapper {
             Field::Point(v) => FieldWrapper::OptionalPoint(Some(v)),
             Field::Duration(v) => FieldWrapper::OptionalDuration(Some(v)),
             Field::Null => FieldWrapper::Null(None),
+            nested @ (Field::List(_) | Field::Struct(_)) => {
+                FieldWrapper::OptionalJson(Some(field_to_json_value(nested)))
+            }
         }
     } else {
         match field {
@@ -151,6 +155,9 @@ fn convert_field_to_ff(field: Field, nullable: bool) -> FieldWrapper {
             Field::Point(v) => FieldWrapper::Point(v),
             Field::Duration(v) => FieldWrapper::Duration(v),
             Field::Null => FieldWrapper::Null(None),
+            nested @ (Field::List(_) | Field::Struct(_)) => {
+                FieldWrapper::Json(field_to_json_value(nested))
+            }
         }
     }
 }
@@ -447,7 +454,7 @@ impl Sink for ClickhouseSink {
     fn on_source_snapshotting_done(
         &mut self,
         _connection_name: String,
-        _id: Option<OpIdentifier>,
+        _id: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         Ok(())
     }
@@ -460,7 +467,7 @@ impl Sink for ClickhouseSink {
         Ok(None)
     }
 
-    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
         Ok(None)
     }
 }
@@ -134,7 +134,7 @@ impl ClickhouseSchema {
                 FieldType::Decimal => "Decimal64",
                 FieldType::Timestamp => "DateTime64(9)",
                 FieldType::Date => "Date",
-                FieldType::Json => "Json",
+                FieldType::Json | FieldType::List(_) | FieldType::Struct(_) => "Json",
                 FieldType::Point => "Point",
                 FieldType::Duration => {
                     return Err(ClickhouseSinkError::TypeNotSupported(
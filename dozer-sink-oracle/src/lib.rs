@@ -25,6 +25,8 @@ use oracle::{
 enum Error {
     #[error("Updating a primary key is not supported. Old: {:?}, new: {:?}", .old, .new)]
     UpdatedPrimaryKey { old: Vec<Field>, new: Vec<Field> },
+    #[error("Column {name} of type {typ} is not supported by the Oracle sink")]
+    UnsupportedFieldType { name: String, typ: FieldType },
 }
 
 #[derive(Debug)]
@@ -124,7 +126,7 @@ impl SinkFactory for OracleSinkFactory {
         let mut column_defs = Vec::with_capacity(schema.fields.len());
         for field in &schema.fields {
             let name = &field.name;
-            let col_type = match field.typ {
+            let col_type = match &field.typ {
                 dozer_types::types::FieldType::UInt => "NUMBER",
                 dozer_types::types::FieldType::U128 => unimplemented!(),
                 dozer_types::types::FieldType::Int => "NUMBER",
@@ -141,6 +143,13 @@ impl SinkFactory for OracleSinkFactory {
                 dozer_types::types::FieldType::Json => unimplemented!(),
                 dozer_types::types::FieldType::Point => unimplemented!("Oracle Point"),
                 dozer_types::types::FieldType::Duration => unimplemented!(),
+                dozer_types::types::FieldType::List(_)
+                | dozer_types::types::FieldType::Struct(_) => {
+                    return Err(Box::new(Error::UnsupportedFieldType {
+                        name: name.clone(),
+                        typ: field.typ.clone(),
+                    }))
+                }
             };
             column_defs.push(format!(
                 "\"{name}\" {col_type}{}",
@@ -162,7 +171,11 @@ impl SinkFactory for OracleSinkFactory {
                 .join(", ")
         );
 
-        let field_types = schema.fields.iter().map(|field| field.typ).collect();
+        let field_types = schema
+            .fields
+            .iter()
+            .map(|field| field.typ.clone())
+            .collect();
         Ok(Box::new(OracleSink {
             conn: connection,
             insert_append,
@@ -178,9 +191,9 @@ impl SinkFactory for OracleSinkFactory {
 }
 
 #[derive(Debug)]
-struct OraField(Field, FieldType);
+struct OraField<'a>(Field, &'a FieldType);
 
-impl ToSql for OraField {
+impl ToSql for OraField<'_> {
     fn oratype(&self, conn: &Connection) -> oracle::Result<oracle::sql_type::OracleType> {
         match &self.0 {
             Field::UInt(v) => v.oratype(conn),
@@ -248,7 +261,7 @@ impl OracleSink {
                 .zip(&self.field_types)
                 .enumerate()
             {
-                batch.set(i + 1, &OraField(field, *typ))?;
+                batch.set(i + 1, &OraField(field, typ))?;
             }
             batch.set(self.field_types.len() + 1, &(params.op_kind as u64))?;
             batch.append_row(&[])?;
@@ -315,7 +328,7 @@ impl Sink for OracleSink {
                     for (i, (field, typ)) in
                         record.values.into_iter().zip(&self.field_types).enumerate()
                     {
-                        batch.set(i + 1, &OraField(field, *typ))?;
+                        batch.set(i + 1, &OraField(field, typ))?;
                     }
                     batch.append_row(&[])?;
                 }
@@ -344,7 +357,7 @@ impl Sink for OracleSink {
     fn on_source_snapshotting_done(
         &mut self,
         _connection_name: String,
-        _id: Option<dozer_types::node::OpIdentifier>,
+        _id: Option<dozer_types::node::SourcePosition>,
     ) -> Result<(), dozer_types::errors::internal::BoxedError> {
         Ok(())
     }
@@ -364,7 +377,7 @@ impl Sink for OracleSink {
 
     fn get_latest_op_id(
         &mut self,
-    ) -> Result<Option<dozer_types::node::OpIdentifier>, dozer_types::errors::internal::BoxedError>
+    ) -> Result<Option<dozer_types::node::SourcePosition>, dozer_types::errors::internal::BoxedError>
     {
         Ok(None)
     }
@@ -0,0 +1,260 @@
+//! Evaluation of an expression over a batch of records.
+//!
+//! Python and ONNX UDFs have a high per-call cost, so they are called once for the whole batch.
+//! The rest of the expression is evaluated record by record, with the UDF results substituted in.
+//! UDFs in branches that are only evaluated for some records, like the results of `CASE`, are called per record.
+
+use dozer_types::types::{Field, Record, Schema};
+
+use crate::conditional::ConditionalExpressionType;
+use crate::error::Error;
+use crate::execution::Expression;
+
+impl Expression {
+    /// Evaluates the expression for every record, returning the results in the same order.
+    pub fn evaluate_batch(
+        &mut self,
+        records: &[Record],
+        schema: &Schema,
+    ) -> Result<Vec<Field>, Error> {
+        // Take the UDFs out of the tree, leaving literals in their place.
+        let mut udfs = vec![];
+        let mut position = 0;
+        self.visit_mut(&mut |expr| {
+            position += 1;
+            if expr.is_batched_udf() {
+                let udf = std::mem::replace(expr, Expression::Literal(Field::Null));
+                udfs.push((position, udf));
+                false
+            } else {
+                true
+            }
+        });
+
+        if udfs.is_empty() {
+            return records
+                .iter()
+                .map(|record| self.evaluate(record, schema))
+                .collect();
+        }
+
+        let result = self.evaluate_with_udfs(&mut udfs, records, schema);
+
+        let mut udfs = udfs.into_iter().peekable();
+        self.replace_at_positions(|position| {
+            udfs.next_if(|(udf_position, _)| *udf_position == position)
+                .map(|(_, udf)| udf)
+        });
+        result
+    }
+
+    fn evaluate_with_udfs(
+        &mut self,
+        udfs: &mut [(usize, Expression)],
+        records: &[Record],
+        schema: &Schema,
+    ) -> Result<Vec<Field>, Error> {
+        let mut udf_results = udfs
+            .iter_mut()
+            .map(|(_, udf)| udf.evaluate_udf_batch(records, schema).map(Vec::into_iter))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        records
+            .iter()
+            .map(|record| {
+                let mut results = udf_results.iter_mut().zip(udfs.iter()).peekable();
+                self.replace_at_positions(|position| {
+                    results
+                        .next_if(|(_, (udf_position, _))| *udf_position == position)
+                        .map(|(result, _)| {
+                            Expression::Literal(
+                                result.next().expect("UDF returns a result per record"),
+                            )
+                        })
+                });
+                self.evaluate(record, schema)
+            })
+            .collect()
+    }
+
+    /// Visits the nodes in pre-order, replacing the ones `replacement` returns an expression for.
+    /// Positions count from 1 and match the ones `evaluate_batch` recorded.
+    fn replace_at_positions(&mut self, mut replacement: impl FnMut(usize) -> Option<Expression>) {
+        let mut position = 0;
+        self.visit_mut(&mut |expr| {
+            position += 1;
+            if let Some(replacement) = replacement(position) {
+                *expr = replacement;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Calls `f` on this node and then on its unconditional children, in pre-order. Children are skipped if `f` returns `false`.
+    fn visit_mut(&mut self, f: &mut impl FnMut(&mut Expression) -> bool) {
+        if !f(self) {
+            return;
+        }
+        for child in self.unconditional_children_mut() {
+            child.visit_mut(f);
+        }
+    }
+
+    /// The children that are evaluated for every record this node is evaluated for.
+    fn unconditional_children_mut(&mut self) -> Vec<&mut Expression> {
+        match self {
+            Expression::Column { .. } | Expression::Literal(_) | Expression::Now { .. } => vec![],
+            Expression::UnaryOperator { arg, .. }
+            | Expression::DateTimeFunction { arg, .. }
+            | Expression::Cast { arg, .. } => vec![arg.as_mut()],
+            Expression::BinaryOperator { left, right, .. } => vec![left.as_mut(), right.as_mut()],
+            // Later arguments are only evaluated while the previous ones are null.
+            Expression::ConditionalExpression {
+                fun: ConditionalExpressionType::Coalesce,
+                args,
+            } => args.first_mut().into_iter().collect(),
+            Expression::ScalarFunction { args, .. }
+            | Expression::GeoFunction { args, .. }
+            | Expression::ConditionalExpression { args, .. }
+            | Expression::AggregateFunction { args, .. }
+            | Expression::Json { args, .. } => args.iter_mut().collect(),
+            #[cfg(feature = "python")]
+            Expression::PythonUDF { args, .. } => args.iter_mut().collect(),
+            #[cfg(feature = "onnx")]
+            Expression::OnnxUDF { args, .. } => args.iter_mut().collect(),
+            Expression::Trim { arg, what, .. } => std::iter::once(arg.as_mut())
+                .chain(what.as_deref_mut())
+                .collect(),
+            Expression::Like { arg, pattern, .. } => vec![arg.as_mut(), pattern.as_mut()],
+            Expression::InList { expr, list, .. } => {
+                std::iter::once(expr.as_mut()).chain(list).collect()
+            }
+            // Later conditions and the results are only evaluated for the records that reach them.
+            Expression::Case { conditions, .. } => conditions.first_mut().into_iter().collect(),
+            // The arguments of JavaScript UDFs are evaluated by the UDF itself.
+            Expression::JavaScriptUdf(_) => vec![],
+            Expression::JavaScriptUdaf(udaf) => udaf.args_mut().iter_mut().collect(),
+        }
+    }
+
+    fn is_batched_udf(&self) -> bool {
+        match self {
+            #[cfg(feature = "python")]
+            Expression::PythonUDF { .. } => true,
+            #[cfg(feature = "onnx")]
+            Expression::OnnxUDF { .. } => true,
+            _ => false,
+        }
+    }
+
+    #[cfg_attr(
+        not(any(feature = "python", feature = "onnx")),
+        allow(unused_variables)
+    )]
+    fn evaluate_udf_batch(
+        &mut self,
+        records: &[Record],
+        schema: &Schema,
+    ) -> Result<Vec<Field>, Error> {
+        match self {
+            #[cfg(feature = "python")]
+            Expression::PythonUDF {
+                name,
+                args,
+                return_type,
+            } => {
+                let columns = evaluate_columns(args, records, schema)?;
+                crate::python_udf::evaluate_py_udf_batch(name, columns, return_type, records.len())
+            }
+            #[cfg(feature = "onnx")]
+            Expression::OnnxUDF { session, args, .. } => {
+                use crate::onnx::{batch, udf::evaluate_onnx_udf};
+                use std::borrow::Borrow;
+
+                let session = session.0.borrow();
+                if batch::supports_batch(session) {
+                    let columns = evaluate_columns(args, records, schema)?;
+                    batch::evaluate_onnx_udf_batch(session, rows(columns, records.len()))
+                } else {
+                    records
+                        .iter()
+                        .map(|record| evaluate_onnx_udf(schema, session, args, record))
+                        .collect()
+                }
+            }
+            _ => unreachable!("not a batched UDF: {self:?}"),
+        }
+    }
+}
+
+/// Evaluates each argument for the whole batch.
+#[cfg(any(feature = "python", feature = "onnx"))]
+fn evaluate_columns(
+    args: &mut [Expression],
+    records: &[Record],
+    schema: &Schema,
+) -> Result<Vec<Vec<Field>>, Error> {
+    args.iter_mut()
+        .map(|arg| arg.evaluate_batch(records, schema))
+        .collect()
+}
+
+#[cfg(feature = "onnx")]
+fn rows(columns: Vec<Vec<Field>>, num_rows: usize) -> Vec<Vec<Field>> {
+    let mut rows = vec![Vec::with_capacity(columns.len()); num_rows];
+    for column in columns {
+        for (row, value) in rows.iter_mut().zip(column) {
+            row.push(value);
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::types::{FieldDefinition, FieldType, SourceDefinition};
+
+    use crate::operator::BinaryOperatorType;
+    use crate::scalar::common::ScalarFunctionType;
+
+    use super::*;
+
+    #[test]
+    fn test_evaluate_batch_matches_evaluate() {
+        let schema = Schema::default()
+            .field(
+                FieldDefinition::new(
+                    "a".to_string(),
+                    FieldType::Int,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone();
+        let mut expression = Expression::BinaryOperator {
+            left: Box::new(Expression::ScalarFunction {
+                fun: ScalarFunctionType::Abs,
+                args: vec![Expression::Column { index: 0 }],
+            }),
+            operator: BinaryOperatorType::Add,
+            right: Box::new(Expression::Literal(Field::Int(1))),
+        };
+        let records = [-2, 0, 3]
+            .into_iter()
+            .map(|value| Record::new(vec![Field::Int(value)]))
+            .collect::<Vec<_>>();
+
+        let expected = records
+            .iter()
+            .map(|record| expression.evaluate(record, &schema).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            expression.evaluate_batch(&records, &schema).unwrap(),
+            expected
+        );
+        assert_eq!(expected, vec![Field::Int(3), Field::Int(1), Field::Int(4)]);
+    }
+}
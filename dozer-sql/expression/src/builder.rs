@@ -184,10 +184,63 @@ impl ExpressionBuilder {
                 )
                 .await
             }
+            SqlExpr::Array(array) => {
+                let mut args = Vec::with_capacity(array.elem.len());
+                for elem in &array.elem {
+                    args.push(
+                        self.parse_sql_expression(parse_aggregations, elem, schema, udfs)
+                            .await?,
+                    );
+                }
+                Ok(ScalarFunction {
+                    fun: ScalarFunctionType::Array,
+                    args,
+                })
+            }
+            SqlExpr::ArrayIndex { obj, indexes } => {
+                self.parse_sql_element_access(parse_aggregations, obj, indexes, schema, udfs)
+                    .await
+            }
+            SqlExpr::MapAccess { column, keys } => {
+                self.parse_sql_element_access(parse_aggregations, column, keys, schema, udfs)
+                    .await
+            }
+            SqlExpr::CompositeAccess { expr, key } => {
+                let arg = self
+                    .parse_sql_expression(parse_aggregations, expr, schema, udfs)
+                    .await?;
+                Ok(ScalarFunction {
+                    fun: ScalarFunctionType::ElementAt,
+                    args: vec![arg, Expression::Literal(Field::String(key.value.clone()))],
+                })
+            }
             _ => Err(Error::UnsupportedExpression(expression.clone())),
         }
     }
 
+    async fn parse_sql_element_access(
+        &mut self,
+        parse_aggregations: bool,
+        container: &Expr,
+        keys: &[Expr],
+        schema: &Schema,
+        udfs: &[UdfConfig],
+    ) -> Result<Expression, Error> {
+        let mut expression = self
+            .parse_sql_expression(parse_aggregations, container, schema, udfs)
+            .await?;
+        for key in keys {
+            let key = self
+                .parse_sql_expression(parse_aggregations, key, schema, udfs)
+                .await?;
+            expression = ScalarFunction {
+                fun: ScalarFunctionType::ElementAt,
+                args: vec![expression, key],
+            };
+        }
+        Ok(expression)
+    }
+
     fn parse_sql_column(ident: &[Ident], schema: &Schema) -> Result<Expression, Error> {
         let (src_field, src_table_or_alias, src_connection) = match ident.len() {
             1 => (&ident[0].value, None, None),
@@ -316,6 +369,11 @@ impl ExpressionBuilder {
             fun: aggr,
             args: arg_expr,
         };
+        Some(self.push_aggregation(measure))
+    }
+
+    /// Adds `measure` to the aggregations if it's not there yet, and returns the column of its result.
+    fn push_aggregation(&mut self, measure: Expression) -> Expression {
         let index = match self
             .aggregations
             .iter()
@@ -328,9 +386,9 @@ impl ExpressionBuilder {
                 self.aggregations.len() - 1
             }
         };
-        Some(Expression::Column {
+        Expression::Column {
             index: self.offset + index,
-        })
+        }
     }
 
     async fn scalar_function_check(
@@ -554,6 +612,21 @@ impl ExpressionBuilder {
                     )
                     .await
                 }
+                UdfType::JavaScriptAggregate(config) => {
+                    if !parse_aggregations {
+                        return Err(Error::UnknownFunction(function_name.clone()));
+                    }
+                    let measure = self
+                        .parse_javascript_udaf(
+                            function_name.clone(),
+                            config,
+                            sql_function,
+                            schema,
+                            udfs,
+                        )
+                        .await?;
+                    Ok(self.push_aggregation(measure))
+                }
             };
         }
 
@@ -805,36 +878,45 @@ impl ExpressionBuilder {
         let expression = self
             .parse_sql_expression(parse_aggregations, expr, schema, udfs)
             .await?;
-        let cast_to = match data_type {
-            DataType::Decimal(_) => CastOperatorType(FieldType::Decimal),
-            DataType::Binary(_) => CastOperatorType(FieldType::Binary),
-            DataType::Float(_) => CastOperatorType(FieldType::Float),
-            DataType::Int(_) => CastOperatorType(FieldType::Int),
-            DataType::Integer(_) => CastOperatorType(FieldType::Int),
-            DataType::UnsignedInt(_) => CastOperatorType(FieldType::UInt),
-            DataType::UnsignedInteger(_) => CastOperatorType(FieldType::UInt),
-            DataType::Boolean => CastOperatorType(FieldType::Boolean),
-            DataType::Date => CastOperatorType(FieldType::Date),
-            DataType::Timestamp(..) => CastOperatorType(FieldType::Timestamp),
-            DataType::Text => CastOperatorType(FieldType::Text),
-            DataType::String => CastOperatorType(FieldType::String),
-            DataType::JSON => CastOperatorType(FieldType::Json),
+        Ok(Expression::Cast {
+            arg: Box::new(expression),
+            typ: CastOperatorType(Self::parse_data_type(data_type)?),
+        })
+    }
+
+    fn parse_data_type(data_type: &DataType) -> Result<FieldType, Error> {
+        Ok(match data_type {
+            DataType::Decimal(_) => FieldType::Decimal,
+            DataType::Binary(_) => FieldType::Binary,
+            DataType::Float(_) => FieldType::Float,
+            DataType::Int(_) => FieldType::Int,
+            DataType::Integer(_) => FieldType::Int,
+            DataType::UnsignedInt(_) => FieldType::UInt,
+            DataType::UnsignedInteger(_) => FieldType::UInt,
+            DataType::Boolean => FieldType::Boolean,
+            DataType::Date => FieldType::Date,
+            DataType::Timestamp(..) => FieldType::Timestamp,
+            DataType::Text => FieldType::Text,
+            DataType::String => FieldType::String,
+            DataType::JSON => FieldType::Json,
+            DataType::Array(Some(element)) => {
+                FieldType::List(Box::new(Self::parse_data_type(element)?))
+            }
+            DataType::Array(None) => FieldType::List(Box::new(FieldType::Json)),
             DataType::Custom(name, ..) => {
                 if name.to_string().to_lowercase() == "uint" {
-                    CastOperatorType(FieldType::UInt)
+                    FieldType::UInt
                 } else if name.to_string().to_lowercase() == "u128" {
-                    CastOperatorType(FieldType::U128)
+                    FieldType::U128
                 } else if name.to_string().to_lowercase() == "i128" {
-                    CastOperatorType(FieldType::I128)
+                    FieldType::I128
+                } else if name.to_string().to_lowercase() == "struct" {
+                    FieldType::Struct(vec![])
                 } else {
                     return Err(Error::UnsupportedDataType(data_type.clone()));
                 }
             }
             _ => Err(Error::UnsupportedDataType(data_type.clone()))?,
-        };
-        Ok(Expression::Cast {
-            arg: Box::new(expression),
-            typ: cast_to,
         })
     }
 
@@ -963,18 +1045,52 @@ impl ExpressionBuilder {
             args.push(arg);
         }
 
-        use crate::javascript::{validate_args, Udf};
-        validate_args(name.clone(), &args, schema)?;
+        use crate::javascript::{parse_arg_types, parse_return_type, validate_args, Udf};
+        let arg_types = parse_arg_types(config.arg_types.as_deref())?;
+        validate_args(name.clone(), &args, arg_types.as_deref(), schema)?;
+        let return_type = parse_return_type(config.return_type.as_deref())?;
         let udf = Udf::new(
             self.runtime.clone(),
             name,
             config.module.clone(),
-            args.remove(0),
+            args,
+            return_type,
         )
         .await?;
         Ok(Expression::JavaScriptUdf(udf))
     }
 
+    async fn parse_javascript_udaf(
+        &mut self,
+        name: String,
+        config: &dozer_types::models::udf_config::JavaScriptConfig,
+        function: &Function,
+        schema: &Schema,
+        udfs: &[UdfConfig],
+    ) -> Result<Expression, Error> {
+        let mut args = vec![];
+        for argument in &function.args {
+            let arg = self
+                .parse_sql_function_arg(false, argument, schema, udfs)
+                .await?;
+            args.push(arg);
+        }
+
+        use crate::javascript::{parse_arg_types, parse_return_type, validate_args, Udaf};
+        let arg_types = parse_arg_types(config.arg_types.as_deref())?;
+        validate_args(name.clone(), &args, arg_types.as_deref(), schema)?;
+        let return_type = parse_return_type(config.return_type.as_deref())?;
+        let udaf = Udaf::new(
+            self.runtime.clone(),
+            name,
+            config.module.clone(),
+            args,
+            return_type,
+        )
+        .await?;
+        Ok(Expression::JavaScriptUdaf(udaf))
+    }
+
     async fn parse_sql_in_list_operator(
         &mut self,
         parse_aggregations: bool,
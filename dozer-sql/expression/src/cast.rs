@@ -33,6 +33,8 @@ impl Display for CastOperatorType {
             FieldType::Json => f.write_str("CAST AS JSON"),
             FieldType::Point => f.write_str("CAST AS POINT"),
             FieldType::Duration => f.write_str("CAST AS DURATION"),
+            FieldType::List(_) => f.write_str("CAST AS LIST"),
+            FieldType::Struct(_) => f.write_str("CAST AS STRUCT"),
         }
     }
 }
@@ -45,7 +47,7 @@ impl CastOperatorType {
         record: &Record,
     ) -> Result<Field, Error> {
         let field = arg.evaluate(record, schema)?;
-        cast_field(&field, self.0)
+        cast_field(&field, &self.0)
     }
 
     pub(crate) fn get_return_type(
@@ -181,8 +183,8 @@ impl CastOperatorType {
                 vec![FieldType::Date, FieldType::Timestamp, FieldType::String],
                 FieldType::Date,
             ),
-            FieldType::Json => (
-                vec![
+            FieldType::Json => {
+                let mut expected = vec![
                     FieldType::Boolean,
                     FieldType::Float,
                     FieldType::Int,
@@ -192,9 +194,14 @@ impl CastOperatorType {
                     FieldType::UInt,
                     FieldType::U128,
                     FieldType::Json,
-                ],
-                FieldType::Json,
-            ),
+                ];
+                // Nested values of any element or field types.
+                let arg_type = arg.get_type(schema)?.return_type;
+                if matches!(arg_type, FieldType::List(_) | FieldType::Struct(_)) {
+                    expected.push(arg_type);
+                }
+                (expected, FieldType::Json)
+            }
             FieldType::Point => (vec![FieldType::Point], FieldType::Point),
             FieldType::Duration => (
                 vec![
@@ -208,6 +215,19 @@ impl CastOperatorType {
                 ],
                 FieldType::Duration,
             ),
+            FieldType::List(_) | FieldType::Struct(_) => {
+                let mut expected = vec![FieldType::String, FieldType::Text, FieldType::Json];
+                // Nested values can be cast to other element or field types.
+                let arg_type = arg.get_type(schema)?.return_type;
+                if matches!(
+                    (&self.0, &arg_type),
+                    (FieldType::List(_), FieldType::List(_))
+                        | (FieldType::Struct(_), FieldType::Struct(_))
+                ) {
+                    expected.push(arg_type);
+                }
+                (expected, self.0.clone())
+            }
         };
 
         let expression_type = validate_arg_type(arg, expected_input_type, schema, self, 0)?;
@@ -220,7 +240,7 @@ impl CastOperatorType {
     }
 }
 
-pub fn cast_field(input: &Field, output_type: FieldType) -> Result<Field, Error> {
+pub fn cast_field(input: &Field, output_type: &FieldType) -> Result<Field, Error> {
     match output_type {
         FieldType::UInt => {
             if let Some(value) = input.to_uint() {
@@ -354,5 +374,43 @@ pub fn cast_field(input: &Field, output_type: FieldType) -> Result<Field, Error>
                 })
             }
         }
+        FieldType::List(element) => match input.to_list() {
+            Some(values) => values
+                .into_iter()
+                .map(|value| cast_element(value, element))
+                .collect::<Result<_, _>>()
+                .map(Field::List),
+            None => Err(Error::InvalidCast {
+                from: input.clone(),
+                to: output_type.clone(),
+            }),
+        },
+        FieldType::Struct(fields) => match input.to_struct() {
+            Some(values) if fields.is_empty() => Ok(Field::Struct(values)),
+            Some(mut values) => fields
+                .iter()
+                .map(|(name, typ)| {
+                    let value = values
+                        .iter()
+                        .position(|(value_name, _)| value_name == name)
+                        .map_or(Field::Null, |index| values.swap_remove(index).1);
+                    cast_element(value, typ).map(|value| (name.clone(), value))
+                })
+                .collect::<Result<_, _>>()
+                .map(Field::Struct),
+            None => Err(Error::InvalidCast {
+                from: input.clone(),
+                to: output_type.clone(),
+            }),
+        },
+    }
+}
+
+/// Null elements stay null, and `Json` elements keep their own types.
+fn cast_element(value: Field, typ: &FieldType) -> Result<Field, Error> {
+    match (value, typ) {
+        (Field::Null, _) => Ok(Field::Null),
+        (value, FieldType::Json) => Ok(value),
+        (value, typ) => cast_field(&value, typ),
     }
 }
@@ -102,6 +102,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Duration(_)
                     | Field::Null => Ok(Field::Null),
@@ -148,6 +150,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -196,6 +200,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -242,6 +248,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -292,6 +300,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
                         right_p,
@@ -332,6 +342,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -404,6 +416,8 @@ macro_rules! define_comparison {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -494,11 +508,9 @@ macro_rules! define_comparison {
                         })?;
                         Ok(Field::Boolean($function(left_val, right_v)))
                     }
-                    Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-                        left_p,
-                        right_p,
-                        $op.to_string(),
-                    )),
+                    Field::Binary(_) | Field::Json(_) | Field::List(_) | Field::Struct(_) => Err(
+                        PipelineError::InvalidTypeComparison(left_p, right_p, $op.to_string()),
+                    ),
                 },
                 Field::Timestamp(left_v) => match right_p {
                     Field::Timestamp(right_v) => Ok(Field::Boolean($function(left_v, right_v))),
@@ -522,6 +534,8 @@ macro_rules! define_comparison {
                     | Field::Decimal(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -552,6 +566,8 @@ macro_rules! define_comparison {
                     | Field::Decimal(_)
                     | Field::Timestamp(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -578,6 +594,8 @@ macro_rules! define_comparison {
                     | Field::Decimal(_)
                     | Field::Timestamp(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Date(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -608,6 +626,8 @@ macro_rules! define_comparison {
                     | Field::Decimal(_)
                     | Field::Timestamp(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Date(_)
                     | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -615,6 +635,24 @@ macro_rules! define_comparison {
                         $op.to_string(),
                     )),
                 },
+                Field::List(ref left_v) => match right_p {
+                    Field::List(ref right_v) => Ok(Field::Boolean($function(left_v, right_v))),
+                    Field::Null => Ok(Field::Null),
+                    _ => Err(PipelineError::InvalidTypeComparison(
+                        left_p.clone(),
+                        right_p,
+                        $op.to_string(),
+                    )),
+                },
+                Field::Struct(ref left_v) => match right_p {
+                    Field::Struct(ref right_v) => Ok(Field::Boolean($function(left_v, right_v))),
+                    Field::Null => Ok(Field::Null),
+                    _ => Err(PipelineError::InvalidTypeComparison(
+                        left_p.clone(),
+                        right_p,
+                        $op.to_string(),
+                    )),
+                },
                 Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
                     left_p,
                     right_p,
@@ -692,6 +730,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_)
             | Field::Null => Ok(Field::Null),
@@ -735,6 +775,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -780,6 +822,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -823,6 +867,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -867,6 +913,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -906,6 +954,8 @@ pub fn evaluate_lt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
@@ -966,6 +1016,8 @@ pub fn evaluate_lt(
                 | Field::Timestamp(_)
                 | Field::Date(_)
                 | Field::Json(_)
+                | Field::List(_)
+                | Field::Struct(_)
                 | Field::Point(_)
                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                     left_p,
@@ -1044,11 +1096,9 @@ pub fn evaluate_lt(
                 })?;
                 Ok(Field::Boolean(left_val < right_v))
             }
-            Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                "<".to_string(),
-            )),
+            Field::Binary(_) | Field::Json(_) | Field::List(_) | Field::Struct(_) => Err(
+                PipelineError::InvalidTypeComparison(left_p, right_p, "<".to_string()),
+            ),
         },
         Field::Timestamp(left_v) => match right_p {
             Field::Timestamp(right_v) => Ok(Field::Boolean(left_v < right_v)),
@@ -1069,6 +1119,8 @@ pub fn evaluate_lt(
             | Field::Decimal(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
@@ -1095,6 +1147,8 @@ pub fn evaluate_lt(
             | Field::Decimal(_)
             | Field::Timestamp(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
@@ -1121,6 +1175,8 @@ pub fn evaluate_lt(
             | Field::Decimal(_)
             | Field::Timestamp(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Date(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
@@ -1147,6 +1203,8 @@ pub fn evaluate_lt(
             | Field::Decimal(_)
             | Field::Timestamp(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Date(_)
             | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
@@ -1154,6 +1212,24 @@ pub fn evaluate_lt(
                 "<".to_string(),
             )),
         },
+        Field::List(ref left_v) => match right_p {
+            Field::List(ref right_v) => Ok(Field::Boolean(left_v < right_v)),
+            Field::Null => Ok(Field::Null),
+            _ => Err(PipelineError::InvalidTypeComparison(
+                left_p.clone(),
+                right_p,
+                "<".to_string(),
+            )),
+        },
+        Field::Struct(ref left_v) => match right_p {
+            Field::Struct(ref right_v) => Ok(Field::Boolean(left_v < right_v)),
+            Field::Null => Ok(Field::Null),
+            _ => Err(PipelineError::InvalidTypeComparison(
+                left_p.clone(),
+                right_p,
+                "<".to_string(),
+            )),
+        },
         Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
             left_p,
             right_p,
@@ -1226,6 +1302,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_)
             | Field::Null => Ok(Field::Null),
@@ -1269,6 +1347,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1314,6 +1394,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1357,6 +1439,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1401,6 +1485,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
                 right_p,
@@ -1440,6 +1526,8 @@ pub fn evaluate_gt(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
@@ -1500,6 +1588,8 @@ pub fn evaluate_gt(
                 | Field::Timestamp(_)
                 | Field::Date(_)
                 | Field::Json(_)
+                | Field::List(_)
+                | Field::Struct(_)
                 | Field::Point(_)
                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                     left_p,
@@ -1578,11 +1668,9 @@ pub fn evaluate_gt(
                 })?;
                 Ok(Field::Boolean(left_val > right_v))
             }
-            Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
-                left_p,
-                right_p,
-                ">".to_string(),
-            )),
+            Field::Binary(_) | Field::Json(_) | Field::List(_) | Field::Struct(_) => Err(
+                PipelineError::InvalidTypeComparison(left_p, right_p, ">".to_string()),
+            ),
         },
         Field::Timestamp(left_v) => match right_p {
             Field::Timestamp(right_v) => Ok(Field::Boolean(left_v > right_v)),
@@ -1603,6 +1691,8 @@ pub fn evaluate_gt(
             | Field::Decimal(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
@@ -1629,6 +1719,8 @@ pub fn evaluate_gt(
             | Field::Decimal(_)
             | Field::Timestamp(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
@@ -1655,6 +1747,8 @@ pub fn evaluate_gt(
             | Field::Decimal(_)
             | Field::Timestamp(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Date(_)
             | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
@@ -1681,6 +1775,8 @@ pub fn evaluate_gt(
             | Field::Decimal(_)
             | Field::Timestamp(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Date(_)
             | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                 left_p,
@@ -1688,6 +1784,24 @@ pub fn evaluate_gt(
                 ">".to_string(),
             )),
         },
+        Field::List(ref left_v) => match right_p {
+            Field::List(ref right_v) => Ok(Field::Boolean(left_v > right_v)),
+            Field::Null => Ok(Field::Null),
+            _ => Err(PipelineError::InvalidTypeComparison(
+                left_p.clone(),
+                right_p,
+                ">".to_string(),
+            )),
+        },
+        Field::Struct(ref left_v) => match right_p {
+            Field::Struct(ref right_v) => Ok(Field::Boolean(left_v > right_v)),
+            Field::Null => Ok(Field::Null),
+            _ => Err(PipelineError::InvalidTypeComparison(
+                left_p.clone(),
+                right_p,
+                ">".to_string(),
+            )),
+        },
         Field::Binary(_) | Field::Json(_) => Err(PipelineError::InvalidTypeComparison(
             left_p,
             right_p,
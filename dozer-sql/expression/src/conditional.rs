@@ -55,7 +55,7 @@ pub(crate) fn validate_coalesce(
         .iter()
         .map(|expr| expr.get_type(schema).unwrap().return_type)
         .collect::<Vec<FieldType>>();
-    let return_type = return_types[0];
+    let return_type = return_types[0].clone();
 
     Ok(ExpressionType::new(
         return_type,
@@ -33,6 +33,8 @@ pub enum Error {
 
     #[error("Aggregate Function {0:?} should not be executed at this point")]
     UnexpectedAggregationExecution(AggregateFunctionType),
+    #[error("JavaScript aggregate function {0} should not be executed at this point")]
+    UnexpectedJavaScriptAggregateExecution(String),
     #[error("literal expression cannot be null")]
     LiteralExpressionIsNull,
     #[error("cannot apply NOT to {0:?}")]
@@ -75,6 +77,14 @@ pub enum Error {
     FailedToCalculateVincentyDistance(
         #[from] dozer_types::geo::vincenty_distance::FailedToConvergeError,
     ),
+    #[error("Invalid geometry for function {function_name}(), index: {argument_index}: {message}")]
+    InvalidGeometry {
+        function_name: String,
+        argument_index: usize,
+        message: String,
+    },
+    #[error("Geometry cannot be converted to GeoJSON: {0}")]
+    InvalidGeometryResult(String),
 
     #[error("Invalid like escape: {0}")]
     InvalidLikeEscape(#[from] like::InvalidEscapeError),
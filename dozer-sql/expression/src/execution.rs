@@ -99,6 +99,7 @@ pub enum Expression {
         args: Vec<Expression>,
     },
     JavaScriptUdf(crate::javascript::Udf),
+    JavaScriptUdaf(crate::javascript::Udaf),
 }
 
 impl Expression {
@@ -278,6 +279,7 @@ impl Expression {
                     + ")"
             }
             Expression::JavaScriptUdf(udf) => udf.to_string(schema),
+            Expression::JavaScriptUdaf(udaf) => udaf.to_string(schema),
         }
     }
 }
@@ -366,6 +368,9 @@ impl Expression {
                 else_result,
             } => evaluate_case(schema, operand, conditions, results, else_result, record),
             Expression::JavaScriptUdf(udf) => udf.evaluate(record, schema),
+            Expression::JavaScriptUdaf(udaf) => Err(Error::UnexpectedJavaScriptAggregateExecution(
+                udaf.function_name().to_string(),
+            )),
         }
     }
 
@@ -387,7 +392,7 @@ impl Expression {
                 let t = schema.fields.get(*index).unwrap();
 
                 Ok(ExpressionType::new(
-                    t.typ,
+                    t.typ.clone(),
                     t.nullable,
                     t.source.clone(),
                     schema.primary_index.contains(index),
@@ -461,7 +466,7 @@ impl Expression {
             }
             #[cfg(feature = "python")]
             Expression::PythonUDF { return_type, .. } => Ok(ExpressionType::new(
-                *return_type,
+                return_type.clone(),
                 false,
                 SourceDefinition::Dynamic,
                 false,
@@ -474,6 +479,7 @@ impl Expression {
                 false,
             )),
             Expression::JavaScriptUdf(udf) => Ok(udf.get_type()),
+            Expression::JavaScriptUdaf(udaf) => Ok(udaf.get_type()),
         }
     }
 
@@ -573,6 +579,12 @@ impl Expression {
                 Ok(())
             }
             Expression::JavaScriptUdf(udf) => udf.serialize(object).map_err(Into::into),
+            Expression::JavaScriptUdaf(udaf) => {
+                for arg in udaf.args() {
+                    arg.serialize_state(object)?;
+                }
+                Ok(())
+            }
         }
     }
 
@@ -671,6 +683,12 @@ impl Expression {
                 Ok(())
             }
             Expression::JavaScriptUdf(udf) => udf.deserialize(cursor).map_err(Into::into),
+            Expression::JavaScriptUdaf(udaf) => {
+                for arg in udaf.args_mut() {
+                    arg.deserialize_state(cursor)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -951,6 +969,8 @@ fn validate_avg(args: &[Expression], schema: &Schema) -> Result<ExpressionType,
         | FieldType::Timestamp
         | FieldType::Binary
         | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_)
         | FieldType::Point => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::Avg.to_string(),
@@ -1004,6 +1024,8 @@ fn validate_max(args: &[Expression], schema: &Schema) -> Result<ExpressionType,
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_)
         | FieldType::Point => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::Max.to_string(),
@@ -1049,6 +1071,8 @@ fn validate_min(args: &[Expression], schema: &Schema) -> Result<ExpressionType,
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_)
         | FieldType::Point => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::Min.to_string(),
@@ -1095,6 +1119,8 @@ fn validate_max_append_only(args: &[Expression], schema: &Schema) -> Result<Expr
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_)
         | FieldType::Point => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::MaxAppendOnly.to_string(),
@@ -1140,6 +1166,8 @@ fn validate_min_append_only(args: &[Expression], schema: &Schema) -> Result<Expr
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_)
         | FieldType::Point => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::MinAppendOnly.to_string(),
@@ -1185,6 +1213,8 @@ fn validate_sum(args: &[Expression], schema: &Schema) -> Result<ExpressionType,
         | FieldType::Timestamp
         | FieldType::Binary
         | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_)
         | FieldType::Point => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::Sum.to_string(),
@@ -1228,6 +1258,8 @@ fn validate_max_value(args: &[Expression], schema: &Schema) -> Result<Expression
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_)
         | FieldType::Point => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::MaxValue.to_string(),
@@ -1274,6 +1306,8 @@ fn validate_min_value(args: &[Expression], schema: &Schema) -> Result<Expression
         | FieldType::Text
         | FieldType::Binary
         | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_)
         | FieldType::Point => {
             return Err(Error::InvalidFunctionArgumentType {
                 function_name: AggregateFunctionType::MinValue.to_string(),
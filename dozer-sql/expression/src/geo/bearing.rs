@@ -0,0 +1,80 @@
+use crate::arg_utils::{extract_point, validate_arg_type, validate_num_arguments};
+use crate::error::Error;
+use dozer_types::geo::Point;
+use dozer_types::types::Record;
+use dozer_types::types::{Field, FieldType, Schema};
+
+use crate::execution::{Expression, ExpressionType};
+use crate::geo::common::GeoFunctionType;
+use crate::geo::geometry::to_point;
+
+pub fn validate_bearing(args: &[Expression], schema: &Schema) -> Result<ExpressionType, Error> {
+    validate_num_arguments(2..3, args.len(), GeoFunctionType::Bearing)?;
+    for (argument_index, arg) in args.iter().enumerate() {
+        validate_arg_type(
+            arg,
+            vec![FieldType::Point],
+            schema,
+            GeoFunctionType::Bearing,
+            argument_index,
+        )?;
+    }
+
+    Ok(ExpressionType::new(
+        FieldType::Float,
+        true,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// `BEARING(from, to)` returns the initial bearing of the great circle path, in degrees clockwise from north.
+pub fn evaluate_bearing(
+    schema: &Schema,
+    args: &mut [Expression],
+    record: &Record,
+) -> Result<Field, Error> {
+    validate_num_arguments(2..3, args.len(), GeoFunctionType::Bearing)?;
+    let f_from = args[0].evaluate(record, schema)?;
+    let f_to = args[1].evaluate(record, schema)?;
+
+    if f_from == Field::Null || f_to == Field::Null {
+        return Ok(Field::Null);
+    }
+
+    let from = extract_point(f_from, GeoFunctionType::Bearing, 0)?;
+    let to = extract_point(f_to, GeoFunctionType::Bearing, 1)?;
+    Ok(Field::Float(
+        initial_bearing(to_point(from), to_point(to)).into(),
+    ))
+}
+
+/// Bearing in degrees, in the range `[0, 360)`.
+pub fn initial_bearing(from: Point<f64>, to: Point<f64>) -> f64 {
+    let (lat_from, lat_to) = (from.y().to_radians(), to.y().to_radians());
+    let delta_lng = (to.x() - from.x()).to_radians();
+    let y = delta_lng.sin() * lat_to.cos();
+    let x = lat_from.cos() * lat_to.sin() - lat_from.sin() * lat_to.cos() * delta_lng.cos();
+    (y.atan2(x).to_degrees() + 360.0) % 360.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_bearing() {
+        let origin = Point::new(0.0, 0.0);
+        let assert_bearing = |to: Point<f64>, expected: f64| {
+            let bearing = initial_bearing(origin, to);
+            assert!(
+                (bearing - expected).abs() < 1e-9,
+                "expected {expected}, got {bearing}"
+            );
+        };
+        assert_bearing(Point::new(0.0, 1.0), 0.0);
+        assert_bearing(Point::new(1.0, 0.0), 90.0);
+        assert_bearing(Point::new(0.0, -1.0), 180.0);
+        assert_bearing(Point::new(-1.0, 0.0), 270.0);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::arg_utils::{extract_float, validate_arg_type, validate_num_arguments};
+use crate::error::Error;
+use dozer_types::geo::{
+    BooleanOps, CoordsIter, Geometry, HaversineDestination, Line, MultiPolygon, Point, Polygon,
+};
+use dozer_types::types::Record;
+use dozer_types::types::{Field, FieldType, Schema};
+
+use crate::execution::{Expression, ExpressionType};
+use crate::geo::bearing::initial_bearing;
+use crate::geo::common::GeoFunctionType;
+use crate::geo::geometry::{extract_geometry, geometry_to_json, GEOMETRY_TYPES};
+
+/// Number of segments approximating the circle around each vertex.
+const CIRCLE_SEGMENTS: usize = 32;
+
+pub fn validate_buffer(args: &[Expression], schema: &Schema) -> Result<ExpressionType, Error> {
+    validate_num_arguments(2..3, args.len(), GeoFunctionType::Buffer)?;
+    validate_arg_type(
+        &args[0],
+        GEOMETRY_TYPES.to_vec(),
+        schema,
+        GeoFunctionType::Buffer,
+        0,
+    )?;
+    validate_arg_type(
+        &args[1],
+        vec![
+            FieldType::Float,
+            FieldType::Int,
+            FieldType::UInt,
+            FieldType::Decimal,
+        ],
+        schema,
+        GeoFunctionType::Buffer,
+        1,
+    )?;
+
+    Ok(ExpressionType::new(
+        FieldType::Json,
+        true,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// `ST_BUFFER(geometry, meters)` returns, as a GeoJSON geometry, the area within `meters` of the geometry.
+///
+/// The area is approximated by the union of the geometry, circles around its vertices and rectangles along its edges.
+pub fn evaluate_buffer(
+    schema: &Schema,
+    args: &mut [Expression],
+    record: &Record,
+) -> Result<Field, Error> {
+    validate_num_arguments(2..3, args.len(), GeoFunctionType::Buffer)?;
+    let f_geometry = args[0].evaluate(record, schema)?;
+    let f_distance = args[1].evaluate(record, schema)?;
+
+    if f_geometry == Field::Null || f_distance == Field::Null {
+        return Ok(Field::Null);
+    }
+
+    let geometry = extract_geometry(f_geometry, GeoFunctionType::Buffer, 0)?;
+    let distance = extract_float(f_distance.clone(), GeoFunctionType::Buffer, 1)?;
+    if !(distance.is_finite() && distance > 0.0) {
+        return Err(Error::InvalidFunctionArgument {
+            function_name: GeoFunctionType::Buffer.to_string(),
+            argument_index: 1,
+            argument: f_distance,
+        });
+    }
+
+    let buffered = buffer(&geometry, distance);
+    Ok(Field::Json(geometry_to_json(&Geometry::MultiPolygon(
+        buffered,
+    ))?))
+}
+
+fn buffer(geometry: &Geometry<f64>, distance: f64) -> MultiPolygon<f64> {
+    let mut parts = vec![];
+    collect_areas(geometry, &mut parts);
+    parts.extend(
+        geometry
+            .coords_iter()
+            .map(|coord| circle(Point::from(coord), distance)),
+    );
+    let mut lines = vec![];
+    collect_lines(geometry, &mut lines);
+    parts.extend(lines.into_iter().map(|line| rectangle(line, distance)));
+
+    parts
+        .into_iter()
+        .fold(MultiPolygon::new(vec![]), |union, part| {
+            union.union(&MultiPolygon::new(vec![part]))
+        })
+}
+
+fn circle(center: Point<f64>, distance: f64) -> Polygon<f64> {
+    let points = (0..CIRCLE_SEGMENTS)
+        .map(|i| {
+            let bearing = 360.0 * i as f64 / CIRCLE_SEGMENTS as f64;
+            center.haversine_destination(bearing, distance)
+        })
+        .collect::<Vec<_>>();
+    Polygon::new(points.into(), vec![])
+}
+
+fn rectangle(line: Line<f64>, distance: f64) -> Polygon<f64> {
+    let (start, end) = line.points();
+    let bearing = initial_bearing(start, end);
+    let left = bearing - 90.0;
+    let right = bearing + 90.0;
+    let points = vec![
+        start.haversine_destination(left, distance),
+        end.haversine_destination(left, distance),
+        end.haversine_destination(right, distance),
+        start.haversine_destination(right, distance),
+    ];
+    Polygon::new(points.into(), vec![])
+}
+
+fn collect_areas(geometry: &Geometry<f64>, areas: &mut Vec<Polygon<f64>>) {
+    match geometry {
+        Geometry::Polygon(polygon) => areas.push(polygon.clone()),
+        Geometry::MultiPolygon(polygons) => areas.extend(polygons.iter().cloned()),
+        Geometry::Rect(rect) => areas.push(rect.to_polygon()),
+        Geometry::Triangle(triangle) => areas.push(triangle.to_polygon()),
+        Geometry::GeometryCollection(collection) => {
+            for geometry in collection {
+                collect_areas(geometry, areas);
+            }
+        }
+        Geometry::Point(_)
+        | Geometry::MultiPoint(_)
+        | Geometry::Line(_)
+        | Geometry::LineString(_)
+        | Geometry::MultiLineString(_) => {}
+    }
+}
+
+fn collect_lines(geometry: &Geometry<f64>, lines: &mut Vec<Line<f64>>) {
+    match geometry {
+        Geometry::Line(line) => lines.push(*line),
+        Geometry::LineString(line_string) => lines.extend(line_string.lines()),
+        Geometry::MultiLineString(line_strings) => {
+            for line_string in line_strings {
+                lines.extend(line_string.lines());
+            }
+        }
+        Geometry::Polygon(polygon) => collect_polygon_lines(polygon, lines),
+        Geometry::MultiPolygon(polygons) => {
+            for polygon in polygons {
+                collect_polygon_lines(polygon, lines);
+            }
+        }
+        Geometry::Rect(rect) => collect_polygon_lines(&rect.to_polygon(), lines),
+        Geometry::Triangle(triangle) => collect_polygon_lines(&triangle.to_polygon(), lines),
+        Geometry::GeometryCollection(collection) => {
+            for geometry in collection {
+                collect_lines(geometry, lines);
+            }
+        }
+        Geometry::Point(_) | Geometry::MultiPoint(_) => {}
+    }
+}
+
+fn collect_polygon_lines(polygon: &Polygon<f64>, lines: &mut Vec<Line<f64>>) {
+    lines.extend(polygon.exterior().lines());
+    for interior in polygon.interiors() {
+        lines.extend(interior.lines());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use dozer_types::geo::{Contains, LineString};
+
+    #[test]
+    fn test_buffer_point() {
+        let center = Point::new(13.4, 52.5);
+        let buffered = buffer(&Geometry::Point(center), 1000.0);
+        assert_eq!(buffered.0.len(), 1);
+        assert!(buffered.contains(&center));
+        assert!(buffered.contains(&center.haversine_destination(45.0, 900.0)));
+        assert!(!buffered.contains(&center.haversine_destination(45.0, 1100.0)));
+    }
+
+    #[test]
+    fn test_buffer_line() {
+        let start = Point::new(13.4, 52.5);
+        let end = start.haversine_destination(90.0, 5000.0);
+        let line = LineString::from(vec![start, end]);
+        let buffered = buffer(&Geometry::LineString(line), 100.0);
+
+        let middle = start.haversine_destination(90.0, 2500.0);
+        assert!(buffered.contains(&middle.haversine_destination(0.0, 90.0)));
+        assert!(!buffered.contains(&middle.haversine_destination(0.0, 110.0)));
+    }
+
+    #[test]
+    fn test_invalid_distance() {
+        let mut args = vec![
+            Expression::Literal(Field::String("POINT(1 2)".to_string())),
+            Expression::Literal(Field::Float((-1.0).into())),
+        ];
+        assert!(evaluate_buffer(&Schema::default(), &mut args, &Record::new(vec![])).is_err());
+    }
+}
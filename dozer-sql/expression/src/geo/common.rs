@@ -1,8 +1,15 @@
 use crate::error::Error;
 use crate::execution::{Expression, ExpressionType};
 
+use crate::geo::bearing::{evaluate_bearing, validate_bearing};
+use crate::geo::buffer::{evaluate_buffer, validate_buffer};
 use crate::geo::distance::{evaluate_distance, validate_distance};
+use crate::geo::index::{
+    evaluate_geohash_decode, evaluate_geohash_encode, evaluate_h3_cell, validate_geohash_decode,
+    validate_geohash_encode, validate_h3_cell,
+};
 use crate::geo::point::{evaluate_point, validate_point};
+use crate::geo::relation::{evaluate_relation, validate_relation};
 use dozer_types::types::Record;
 use dozer_types::types::{Field, Schema};
 use std::fmt::{Display, Formatter};
@@ -11,6 +18,13 @@ use std::fmt::{Display, Formatter};
 pub enum GeoFunctionType {
     Point,
     Distance,
+    Contains,
+    Within,
+    Buffer,
+    Bearing,
+    GeohashEncode,
+    GeohashDecode,
+    H3Cell,
 }
 
 impl Display for GeoFunctionType {
@@ -18,6 +32,13 @@ impl Display for GeoFunctionType {
         match self {
             GeoFunctionType::Point => f.write_str("POINT"),
             GeoFunctionType::Distance => f.write_str("DISTANCE"),
+            GeoFunctionType::Contains => f.write_str("ST_CONTAINS"),
+            GeoFunctionType::Within => f.write_str("ST_WITHIN"),
+            GeoFunctionType::Buffer => f.write_str("ST_BUFFER"),
+            GeoFunctionType::Bearing => f.write_str("BEARING"),
+            GeoFunctionType::GeohashEncode => f.write_str("GEOHASH_ENCODE"),
+            GeoFunctionType::GeohashDecode => f.write_str("GEOHASH_DECODE"),
+            GeoFunctionType::H3Cell => f.write_str("H3_CELL"),
         }
     }
 }
@@ -30,6 +51,14 @@ pub(crate) fn get_geo_function_type(
     match function {
         GeoFunctionType::Point => validate_point(args, schema),
         GeoFunctionType::Distance => validate_distance(args, schema),
+        GeoFunctionType::Contains | GeoFunctionType::Within => {
+            validate_relation(function.clone(), args, schema)
+        }
+        GeoFunctionType::Buffer => validate_buffer(args, schema),
+        GeoFunctionType::Bearing => validate_bearing(args, schema),
+        GeoFunctionType::GeohashEncode => validate_geohash_encode(args, schema),
+        GeoFunctionType::GeohashDecode => validate_geohash_decode(args, schema),
+        GeoFunctionType::H3Cell => validate_h3_cell(args, schema),
     }
 }
 
@@ -38,6 +67,13 @@ impl GeoFunctionType {
         match name {
             "point" => Some(GeoFunctionType::Point),
             "distance" => Some(GeoFunctionType::Distance),
+            "st_contains" => Some(GeoFunctionType::Contains),
+            "st_within" => Some(GeoFunctionType::Within),
+            "st_buffer" => Some(GeoFunctionType::Buffer),
+            "bearing" => Some(GeoFunctionType::Bearing),
+            "geohash_encode" => Some(GeoFunctionType::GeohashEncode),
+            "geohash_decode" => Some(GeoFunctionType::GeohashDecode),
+            "h3_cell" => Some(GeoFunctionType::H3Cell),
             _ => None,
         }
     }
@@ -51,6 +87,14 @@ impl GeoFunctionType {
         match self {
             GeoFunctionType::Point => evaluate_point(schema, args, record),
             GeoFunctionType::Distance => evaluate_distance(schema, args, record),
+            GeoFunctionType::Contains | GeoFunctionType::Within => {
+                evaluate_relation(self.clone(), schema, args, record)
+            }
+            GeoFunctionType::Buffer => evaluate_buffer(schema, args, record),
+            GeoFunctionType::Bearing => evaluate_bearing(schema, args, record),
+            GeoFunctionType::GeohashEncode => evaluate_geohash_encode(schema, args, record),
+            GeoFunctionType::GeohashDecode => evaluate_geohash_decode(schema, args, record),
+            GeoFunctionType::H3Cell => evaluate_h3_cell(schema, args, record),
         }
     }
 }
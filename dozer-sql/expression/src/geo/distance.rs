@@ -52,7 +52,7 @@ pub(crate) fn validate_distance(
                     function_name: GeoFunctionType::Distance.to_string(),
                     argument_index,
                     actual: return_type,
-                    expected: vec![*expected_arg_type],
+                    expected: vec![expected_arg_type.clone()],
                 });
             }
         }
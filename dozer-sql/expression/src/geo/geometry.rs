@@ -0,0 +1,84 @@
+use std::fmt::Display;
+
+use dozer_types::geo::{Geometry, Point};
+use dozer_types::json_types::{json_value_to_serde_json, serde_json_to_json_value, JsonValue};
+use dozer_types::types::{DozerPoint, Field, FieldType};
+use geojson::GeoJson;
+use wkt::TryFromWkt;
+
+use crate::error::Error;
+
+/// Types that can be passed where a geometry is expected.
+///
+/// Strings hold WKT, or GeoJSON if they start with `{`. JSON values hold GeoJSON geometries or features.
+pub const GEOMETRY_TYPES: &[FieldType] = &[
+    FieldType::Point,
+    FieldType::String,
+    FieldType::Text,
+    FieldType::Json,
+];
+
+pub fn extract_geometry(
+    field: Field,
+    function_name: impl Display,
+    argument_index: usize,
+) -> Result<Geometry<f64>, Error> {
+    let invalid = |message: String| Error::InvalidGeometry {
+        function_name: function_name.to_string(),
+        argument_index,
+        message,
+    };
+
+    match field {
+        Field::Point(point) => Ok(Geometry::Point(to_point(point))),
+        Field::String(text) | Field::Text(text) => {
+            if text.trim_start().starts_with('{') {
+                let geojson = text
+                    .parse::<GeoJson>()
+                    .map_err(|e| invalid(e.to_string()))?;
+                geojson_to_geometry(geojson).map_err(invalid)
+            } else {
+                Geometry::try_from_wkt_str(&text).map_err(|e| invalid(e.to_string()))
+            }
+        }
+        Field::Json(value) => {
+            let geojson = GeoJson::from_json_value(json_value_to_serde_json(&value))
+                .map_err(|e| invalid(e.to_string()))?;
+            geojson_to_geometry(geojson).map_err(invalid)
+        }
+        field => Err(Error::InvalidFunctionArgument {
+            function_name: function_name.to_string(),
+            argument_index,
+            argument: field,
+        }),
+    }
+}
+
+fn geojson_to_geometry(geojson: GeoJson) -> Result<Geometry<f64>, String> {
+    let geometry = match geojson {
+        GeoJson::Geometry(geometry) => geometry,
+        GeoJson::Feature(feature) => feature
+            .geometry
+            .ok_or_else(|| "feature has no geometry".to_string())?,
+        GeoJson::FeatureCollection(_) => {
+            return Err("expected a geometry or a feature, got a feature collection".to_string())
+        }
+    };
+    Geometry::try_from(geometry).map_err(|e| e.to_string())
+}
+
+/// Converts a geometry to a GeoJSON geometry object.
+pub fn geometry_to_json(geometry: &Geometry<f64>) -> Result<JsonValue, Error> {
+    let geometry = geojson::Geometry::new(geojson::Value::from(geometry));
+    let value = dozer_types::serde_json::to_value(geometry)
+        .expect("GeoJSON geometry can always be serialized");
+    serde_json_to_json_value(value).map_err(|e| Error::InvalidGeometryResult(e.to_string()))
+}
+
+pub fn to_point(point: DozerPoint) -> Point<f64> {
+    Point::new(point.0.x().0, point.0.y().0)
+}
+
+pub fn from_point(point: Point<f64>) -> DozerPoint {
+    DozerPoint::from((point.x(), point.y()))
+}
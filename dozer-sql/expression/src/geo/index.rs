@@ -0,0 +1,240 @@
+use crate::arg_utils::{extract_point, extract_uint, validate_arg_type, validate_num_arguments};
+use crate::error::Error;
+use dozer_types::types::Record;
+use dozer_types::types::{Field, FieldType, Schema};
+use h3o::{LatLng, Resolution};
+
+use crate::execution::{Expression, ExpressionType};
+use crate::geo::common::GeoFunctionType;
+use crate::geo::geometry::{from_point, to_point};
+
+const DEFAULT_GEOHASH_PRECISION: u64 = 12;
+
+const UINT_TYPES: &[FieldType] = &[FieldType::UInt, FieldType::Int];
+
+pub fn validate_geohash_encode(
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    validate_num_arguments(1..3, args.len(), GeoFunctionType::GeohashEncode)?;
+    validate_arg_type(
+        &args[0],
+        vec![FieldType::Point],
+        schema,
+        GeoFunctionType::GeohashEncode,
+        0,
+    )?;
+    if let Some(precision) = args.get(1) {
+        validate_arg_type(
+            precision,
+            UINT_TYPES.to_vec(),
+            schema,
+            GeoFunctionType::GeohashEncode,
+            1,
+        )?;
+    }
+    Ok(ExpressionType::new(
+        FieldType::String,
+        true,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// `GEOHASH_ENCODE(point[, precision])` returns the geohash of the point, 12 characters long by default.
+pub fn evaluate_geohash_encode(
+    schema: &Schema,
+    args: &mut [Expression],
+    record: &Record,
+) -> Result<Field, Error> {
+    validate_num_arguments(1..3, args.len(), GeoFunctionType::GeohashEncode)?;
+    let f_point = args[0].evaluate(record, schema)?;
+    let f_precision = match args.get_mut(1) {
+        Some(arg) => arg.evaluate(record, schema)?,
+        None => Field::UInt(DEFAULT_GEOHASH_PRECISION),
+    };
+
+    if f_point == Field::Null || f_precision == Field::Null {
+        return Ok(Field::Null);
+    }
+
+    let point = to_point(extract_point(f_point, GeoFunctionType::GeohashEncode, 0)?);
+    let precision = extract_uint(f_precision.clone(), GeoFunctionType::GeohashEncode, 1)?;
+    let hash = geohash::encode(point.into(), precision as usize).map_err(|_| {
+        Error::InvalidFunctionArgument {
+            function_name: GeoFunctionType::GeohashEncode.to_string(),
+            argument_index: 1,
+            argument: f_precision,
+        }
+    })?;
+    Ok(Field::String(hash))
+}
+
+pub fn validate_geohash_decode(
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    validate_num_arguments(1..2, args.len(), GeoFunctionType::GeohashDecode)?;
+    validate_arg_type(
+        &args[0],
+        vec![FieldType::String, FieldType::Text],
+        schema,
+        GeoFunctionType::GeohashDecode,
+        0,
+    )?;
+    Ok(ExpressionType::new(
+        FieldType::Point,
+        true,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// `GEOHASH_DECODE(hash)` returns the center of the geohash cell.
+pub fn evaluate_geohash_decode(
+    schema: &Schema,
+    args: &mut [Expression],
+    record: &Record,
+) -> Result<Field, Error> {
+    validate_num_arguments(1..2, args.len(), GeoFunctionType::GeohashDecode)?;
+    let f_hash = args[0].evaluate(record, schema)?;
+    let hash = match &f_hash {
+        Field::Null => return Ok(Field::Null),
+        Field::String(hash) | Field::Text(hash) => hash,
+        _ => {
+            return Err(Error::InvalidFunctionArgument {
+                function_name: GeoFunctionType::GeohashDecode.to_string(),
+                argument_index: 0,
+                argument: f_hash.clone(),
+            })
+        }
+    };
+
+    let (center, _, _) = geohash::decode(hash).map_err(|_| Error::InvalidFunctionArgument {
+        function_name: GeoFunctionType::GeohashDecode.to_string(),
+        argument_index: 0,
+        argument: f_hash.clone(),
+    })?;
+    Ok(Field::Point(from_point(center.into())))
+}
+
+pub fn validate_h3_cell(args: &[Expression], schema: &Schema) -> Result<ExpressionType, Error> {
+    validate_num_arguments(2..3, args.len(), GeoFunctionType::H3Cell)?;
+    validate_arg_type(
+        &args[0],
+        vec![FieldType::Point],
+        schema,
+        GeoFunctionType::H3Cell,
+        0,
+    )?;
+    validate_arg_type(
+        &args[1],
+        UINT_TYPES.to_vec(),
+        schema,
+        GeoFunctionType::H3Cell,
+        1,
+    )?;
+    Ok(ExpressionType::new(
+        FieldType::String,
+        true,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// `H3_CELL(point, resolution)` returns the index of the H3 cell containing the point, as a hex string.
+pub fn evaluate_h3_cell(
+    schema: &Schema,
+    args: &mut [Expression],
+    record: &Record,
+) -> Result<Field, Error> {
+    validate_num_arguments(2..3, args.len(), GeoFunctionType::H3Cell)?;
+    let f_point = args[0].evaluate(record, schema)?;
+    let f_resolution = args[1].evaluate(record, schema)?;
+
+    if f_point == Field::Null || f_resolution == Field::Null {
+        return Ok(Field::Null);
+    }
+
+    let invalid = |argument_index, argument| Error::InvalidFunctionArgument {
+        function_name: GeoFunctionType::H3Cell.to_string(),
+        argument_index,
+        argument,
+    };
+    let point = to_point(extract_point(f_point.clone(), GeoFunctionType::H3Cell, 0)?);
+    let resolution = extract_uint(f_resolution.clone(), GeoFunctionType::H3Cell, 1)?;
+    let resolution = u8::try_from(resolution)
+        .ok()
+        .and_then(|resolution| Resolution::try_from(resolution).ok())
+        .ok_or_else(|| invalid(1, f_resolution))?;
+    let lat_lng = LatLng::new(point.y(), point.x()).map_err(|_| invalid(0, f_point))?;
+    Ok(Field::String(lat_lng.to_cell(resolution).to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use dozer_types::types::DozerPoint;
+    use Expression::Literal;
+
+    fn evaluate(
+        evaluate: fn(&Schema, &mut [Expression], &Record) -> Result<Field, Error>,
+        args: Vec<Field>,
+    ) -> Result<Field, Error> {
+        let mut args = args.into_iter().map(Literal).collect::<Vec<_>>();
+        evaluate(&Schema::default(), &mut args, &Record::new(vec![]))
+    }
+
+    #[test]
+    fn test_geohash() {
+        let point = Field::Point(DozerPoint::from((-5.6, 42.6)));
+        assert_eq!(
+            evaluate(evaluate_geohash_encode, vec![point.clone(), Field::UInt(5)]).unwrap(),
+            Field::String("ezs42".to_string())
+        );
+        assert_eq!(
+            evaluate(evaluate_geohash_encode, vec![point]).unwrap(),
+            Field::String("ezs42e44yx96".to_string())
+        );
+
+        let Field::Point(center) = evaluate(
+            evaluate_geohash_decode,
+            vec![Field::String("ezs42".to_string())],
+        )
+        .unwrap() else {
+            panic!("expected a point");
+        };
+        let center = to_point(center);
+        assert!((center.x() - -5.603).abs() < 0.01);
+        assert!((center.y() - 42.605).abs() < 0.01);
+
+        assert!(evaluate(
+            evaluate_geohash_decode,
+            vec![Field::String("not a geohash!".to_string())]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_h3_cell() {
+        let point = Field::Point(DozerPoint::from((-122.4194, 37.7749)));
+        let cell = evaluate(evaluate_h3_cell, vec![point.clone(), Field::UInt(9)]).unwrap();
+        let Field::String(cell) = cell else {
+            panic!("expected a string");
+        };
+        assert_eq!(cell.len(), 15);
+        // Nearby points share a coarse cell.
+        let nearby = Field::Point(DozerPoint::from((-122.4195, 37.7750)));
+        assert_eq!(
+            evaluate(evaluate_h3_cell, vec![point, Field::UInt(5)]).unwrap(),
+            evaluate(evaluate_h3_cell, vec![nearby, Field::UInt(5)]).unwrap()
+        );
+
+        assert!(evaluate(
+            evaluate_h3_cell,
+            vec![Field::Point(DozerPoint::from((0.0, 0.0))), Field::UInt(16)]
+        )
+        .is_err());
+    }
+}
@@ -1,3 +1,8 @@
+pub mod bearing;
+pub mod buffer;
 pub mod common;
 pub mod distance;
+pub mod geometry;
+pub mod index;
 pub mod point;
+pub mod relation;
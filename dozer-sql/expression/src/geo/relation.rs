@@ -0,0 +1,144 @@
+use crate::arg_utils::{validate_arg_type, validate_num_arguments};
+use crate::error::Error;
+use dozer_types::geo::Contains;
+use dozer_types::types::Record;
+use dozer_types::types::{Field, FieldType, Schema};
+
+use crate::execution::{Expression, ExpressionType};
+use crate::geo::common::GeoFunctionType;
+use crate::geo::geometry::{extract_geometry, GEOMETRY_TYPES};
+
+pub fn validate_relation(
+    function: GeoFunctionType,
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    validate_num_arguments(2..3, args.len(), &function)?;
+    for (argument_index, arg) in args.iter().enumerate() {
+        validate_arg_type(
+            arg,
+            GEOMETRY_TYPES.to_vec(),
+            schema,
+            &function,
+            argument_index,
+        )?;
+    }
+
+    Ok(ExpressionType::new(
+        FieldType::Boolean,
+        true,
+        dozer_types::types::SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// `ST_CONTAINS(a, b)` is true if no point of `b` lies outside `a`, and they share an interior point.
+/// `ST_WITHIN(a, b)` is `ST_CONTAINS(b, a)`.
+pub fn evaluate_relation(
+    function: GeoFunctionType,
+    schema: &Schema,
+    args: &mut [Expression],
+    record: &Record,
+) -> Result<Field, Error> {
+    validate_num_arguments(2..3, args.len(), &function)?;
+    let f_a = args[0].evaluate(record, schema)?;
+    let f_b = args[1].evaluate(record, schema)?;
+
+    if f_a == Field::Null || f_b == Field::Null {
+        return Ok(Field::Null);
+    }
+
+    let a = extract_geometry(f_a, &function, 0)?;
+    let b = extract_geometry(f_b, &function, 1)?;
+    let result = match function {
+        GeoFunctionType::Within => b.contains(&a),
+        _ => a.contains(&b),
+    };
+    Ok(Field::Boolean(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use dozer_types::json_types::json;
+    use dozer_types::types::DozerPoint;
+    use Expression::Literal;
+
+    const SQUARE: &str = "POLYGON((0 0, 10 0, 10 10, 0 10, 0 0))";
+
+    fn evaluate(function: GeoFunctionType, a: Field, b: Field) -> Field {
+        let mut args = vec![Literal(a), Literal(b)];
+        evaluate_relation(
+            function,
+            &Schema::default(),
+            &mut args,
+            &Record::new(vec![]),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_contains() {
+        let square = Field::String(SQUARE.to_string());
+        let inside = Field::Point(DozerPoint::from((5.0, 5.0)));
+        let outside = Field::Point(DozerPoint::from((15.0, 5.0)));
+
+        assert_eq!(
+            evaluate(GeoFunctionType::Contains, square.clone(), inside.clone()),
+            Field::Boolean(true)
+        );
+        assert_eq!(
+            evaluate(GeoFunctionType::Contains, square.clone(), outside.clone()),
+            Field::Boolean(false)
+        );
+        assert_eq!(
+            evaluate(GeoFunctionType::Within, inside, square.clone()),
+            Field::Boolean(true)
+        );
+        assert_eq!(
+            evaluate(GeoFunctionType::Within, outside, square.clone()),
+            Field::Boolean(false)
+        );
+        assert_eq!(
+            evaluate(GeoFunctionType::Contains, square, Field::Null),
+            Field::Null
+        );
+    }
+
+    #[test]
+    fn test_geojson_geometries() {
+        let square = Field::Json(json!({
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]]],
+        }));
+        let line = Field::Text(
+            r#"{"type": "Feature", "properties": {}, "geometry": {"type": "LineString", "coordinates": [[1.0, 1.0], [2.0, 2.0]]}}"#
+                .to_string(),
+        );
+        assert_eq!(
+            evaluate(GeoFunctionType::Contains, square, line),
+            Field::Boolean(true)
+        );
+    }
+
+    #[test]
+    fn test_invalid_geometry() {
+        let mut args = vec![
+            Literal(Field::String("POLYGON((0 0, 10 0".to_string())),
+            Literal(Field::Point(DozerPoint::from((5.0, 5.0)))),
+        ];
+        assert!(matches!(
+            evaluate_relation(
+                GeoFunctionType::Contains,
+                &Schema::default(),
+                &mut args,
+                &Record::new(vec![]),
+            ),
+            Err(Error::InvalidGeometry {
+                argument_index: 0,
+                ..
+            })
+        ));
+    }
+}
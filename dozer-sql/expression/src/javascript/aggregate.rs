@@ -0,0 +1,120 @@
+use std::{num::NonZeroI32, sync::Arc};
+
+use dozer_deno::deno_runtime::deno_core::Extension;
+use dozer_types::{
+    json_types::{field_to_json_value, JsonValue},
+    types::{Field, FieldType, Schema, SourceDefinition},
+};
+use tokio::{runtime::Runtime, sync::Mutex};
+
+use crate::execution::{Expression, ExpressionType};
+
+use super::evaluate::{call_function, format_call, json_to_field, Error};
+
+/// A JavaScript aggregate function.
+///
+/// The module exports `init() -> state`, `accumulate(state, ...args) -> state`, `retract(state, ...args) -> state`
+/// and `result(state) -> value`. The state must be JSON, so the aggregation processor can checkpoint it.
+#[derive(Debug, Clone)]
+pub struct Udaf {
+    function_name: String,
+    args: Vec<Expression>,
+    return_type: FieldType,
+    tokio_runtime: Arc<Runtime>,
+    deno_runtime: Arc<Mutex<dozer_deno::Runtime>>,
+    functions: [NonZeroI32; 4],
+}
+
+impl PartialEq for Udaf {
+    fn eq(&self, other: &Self) -> bool {
+        self.function_name == other.function_name && self.args == other.args
+    }
+}
+
+const INIT: usize = 0;
+const ACCUMULATE: usize = 1;
+const RETRACT: usize = 2;
+const RESULT: usize = 3;
+
+impl Udaf {
+    pub async fn new(
+        tokio_runtime: Arc<Runtime>,
+        function_name: String,
+        module: String,
+        args: Vec<Expression>,
+        return_type: FieldType,
+    ) -> Result<Self, Error> {
+        let exports = ["init", "accumulate", "retract", "result"]
+            .into_iter()
+            .map(|export| (module.clone(), export.to_string()))
+            .collect();
+        let (deno_runtime, functions) =
+            dozer_deno::Runtime::with_exports::<fn() -> Extension>(exports, vec![]).await?;
+        Ok(Self {
+            function_name,
+            args,
+            return_type,
+            tokio_runtime,
+            deno_runtime: Arc::new(Mutex::new(deno_runtime)),
+            functions: functions
+                .try_into()
+                .expect("runtime returns one id per export"),
+        })
+    }
+
+    pub fn function_name(&self) -> &str {
+        &self.function_name
+    }
+
+    pub fn args(&self) -> &[Expression] {
+        &self.args
+    }
+
+    pub fn args_mut(&mut self) -> &mut [Expression] {
+        &mut self.args
+    }
+
+    pub fn get_type(&self) -> ExpressionType {
+        ExpressionType {
+            return_type: self.return_type.clone(),
+            nullable: true,
+            source: SourceDefinition::Dynamic,
+            is_primary_key: false,
+        }
+    }
+
+    pub fn to_string(&self, schema: &Schema) -> String {
+        format_call(&self.function_name, &self.args, schema)
+    }
+
+    pub fn init(&self) -> Result<JsonValue, Error> {
+        self.call(INIT, vec![])
+    }
+
+    pub fn accumulate(&self, state: JsonValue, args: &[Field]) -> Result<JsonValue, Error> {
+        self.call(ACCUMULATE, with_state(state, args))
+    }
+
+    pub fn retract(&self, state: JsonValue, args: &[Field]) -> Result<JsonValue, Error> {
+        self.call(RETRACT, with_state(state, args))
+    }
+
+    pub fn result(&self, state: JsonValue) -> Result<Field, crate::error::Error> {
+        let result = self.call(RESULT, vec![state])?;
+        json_to_field(result, &self.return_type)
+    }
+
+    fn call(&self, function: usize, args: Vec<JsonValue>) -> Result<JsonValue, Error> {
+        self.tokio_runtime.block_on(call_function(
+            &self.deno_runtime,
+            self.functions[function],
+            args,
+        ))
+    }
+}
+
+fn with_state(state: JsonValue, args: &[Field]) -> Vec<JsonValue> {
+    std::iter::once(state)
+        .chain(args.iter().cloned().map(field_to_json_value))
+        .collect()
+}
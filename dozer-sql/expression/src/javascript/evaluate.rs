@@ -8,18 +8,22 @@ use dozer_core::{
 };
 use dozer_deno::deno_runtime::deno_core::{self, error::AnyError, extension, op2};
 use dozer_types::{
-    json_types::JsonValue,
+    json_types::{field_to_json_value, JsonValue},
     parking_lot, serde_json, thiserror,
     types::{Field, FieldType, Record, Schema, SourceDefinition},
 };
 use tokio::{runtime::Runtime, sync::Mutex};
 
-use crate::execution::{Expression, ExpressionType};
+use crate::{
+    cast::cast_field,
+    execution::{Expression, ExpressionType},
+};
 
 #[derive(Debug, Clone)]
 pub struct Udf {
     function_name: String,
-    arg: Box<Expression>,
+    args: Vec<Expression>,
+    return_type: FieldType,
     tokio_runtime: Arc<Runtime>,
     /// `Arc<Mutex>` to enable `Clone`. Not sure why `Expression` should be `Clone`.
     deno_runtime: Arc<Mutex<dozer_deno::Runtime>>,
@@ -30,7 +34,7 @@ pub struct Udf {
 impl PartialEq for Udf {
     fn eq(&self, other: &Self) -> bool {
         // This is obviously wrong. We have to lift the `PartialEq` constraint.
-        self.function_name == other.function_name && self.arg == other.arg
+        self.function_name == other.function_name && self.args == other.args
     }
 }
 
@@ -46,6 +50,10 @@ pub enum Error {
     Deserialization(#[from] DeserializationError),
     #[error("serde json: {0}")]
     SerdeJson(#[from] serde_json::Error),
+    #[error("unknown return type {0}")]
+    UnknownReturnType(String),
+    #[error("unknown argument type {0}")]
+    UnknownArgumentType(String),
 }
 
 #[op2]
@@ -73,7 +81,8 @@ impl Udf {
         tokio_runtime: Arc<Runtime>,
         function_name: String,
         module: String,
-        arg: Expression,
+        args: Vec<Expression>,
+        return_type: FieldType,
     ) -> Result<Self, Error> {
         let state = Arc::new(parking_lot::Mutex::new(JsonValue::NULL));
         let state_clone = state.clone();
@@ -83,7 +92,8 @@ impl Udf {
         let function = functions[0];
         Ok(Self {
             function_name,
-            arg: Box::new(arg),
+            args,
+            return_type,
             tokio_runtime,
             deno_runtime: Arc::new(Mutex::new(deno_runtime)),
             function,
@@ -93,8 +103,8 @@ impl Udf {
 
     pub fn get_type(&self) -> ExpressionType {
         ExpressionType {
-            return_type: FieldType::Json,
-            nullable: false,
+            return_type: self.return_type.clone(),
+            nullable: true,
             source: SourceDefinition::Dynamic,
             is_primary_key: false,
         }
@@ -106,8 +116,8 @@ impl Udf {
         schema: &Schema,
     ) -> Result<Field, crate::error::Error> {
         self.tokio_runtime.block_on(evaluate_impl(
-            self.function_name.clone(),
-            &mut self.arg,
+            &mut self.args,
+            &self.return_type,
             &self.deno_runtime,
             self.function,
             record,
@@ -116,7 +126,7 @@ impl Udf {
     }
 
     pub fn to_string(&self, schema: &Schema) -> String {
-        format!("{}({})", self.function_name, self.arg.to_string(schema))
+        format_call(&self.function_name, &self.args, schema)
     }
 
     pub fn serialize(&self, object: &mut Object) -> Result<(), Error> {
@@ -133,26 +143,77 @@ impl Udf {
 }
 
 async fn evaluate_impl(
-    function_name: String,
-    arg: &mut Expression,
+    args: &mut [Expression],
+    return_type: &FieldType,
     runtime: &Arc<Mutex<dozer_deno::Runtime>>,
     function: NonZeroI32,
     record: &Record,
     schema: &Schema,
 ) -> Result<Field, crate::error::Error> {
-    let arg = arg.evaluate(record, schema)?;
-    let Field::Json(arg) = arg else {
-        return Err(crate::error::Error::InvalidFunctionArgument {
-            function_name,
-            argument_index: 0,
-            argument: arg,
-        });
-    };
+    let mut values = Vec::with_capacity(args.len());
+    for arg in args {
+        values.push(field_to_json_value(arg.evaluate(record, schema)?));
+    }
+
+    let result = call_function(runtime, function, values).await?;
+    json_to_field(result, return_type)
+}
 
+pub(super) async fn call_function(
+    runtime: &Arc<Mutex<dozer_deno::Runtime>>,
+    function: NonZeroI32,
+    args: Vec<JsonValue>,
+) -> Result<JsonValue, Error> {
     let mut runtime = runtime.lock().await;
-    let result = runtime
-        .call_function(function, vec![arg])
+    runtime
+        .call_function(function, args)
         .await
-        .map_err(Error::Evaluate)?;
-    Ok(Field::Json(result))
+        .map_err(Error::Evaluate)
+}
+
+pub(super) fn parse_return_type(return_type: Option<&str>) -> Result<FieldType, Error> {
+    return_type.map_or(Ok(FieldType::Json), |typ| {
+        FieldType::try_from(typ).map_err(|_| Error::UnknownReturnType(typ.to_string()))
+    })
+}
+
+pub(super) fn parse_arg_types(
+    arg_types: Option<&[String]>,
+) -> Result<Option<Vec<FieldType>>, Error> {
+    arg_types
+        .map(|arg_types| {
+            arg_types
+                .iter()
+                .map(|typ| {
+                    FieldType::try_from(typ.as_str())
+                        .map_err(|_| Error::UnknownArgumentType(typ.clone()))
+                })
+                .collect()
+        })
+        .transpose()
+}
+
+/// Converts a value returned from JavaScript to `return_type`. `null` is always converted to `Field::Null`.
+pub(super) fn json_to_field(
+    value: JsonValue,
+    return_type: &FieldType,
+) -> Result<Field, crate::error::Error> {
+    if value.is_null() {
+        return Ok(Field::Null);
+    }
+    // Casting the JSON would keep the quotes.
+    match (return_type, value.as_string()) {
+        (FieldType::String, Some(string)) => Ok(Field::String(string.as_str().to_string())),
+        (FieldType::Text, Some(string)) => Ok(Field::Text(string.as_str().to_string())),
+        _ => cast_field(&Field::Json(value), return_type),
+    }
+}
+
+pub(super) fn format_call(function_name: &str, args: &[Expression], schema: &Schema) -> String {
+    let args = args
+        .iter()
+        .map(|arg| arg.to_string(schema))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{function_name}({args})")
 }
@@ -1,5 +1,8 @@
+mod aggregate;
 mod evaluate;
 mod validate;
 
+pub use aggregate::Udaf;
+pub(crate) use evaluate::{parse_arg_types, parse_return_type};
 pub use evaluate::{Error, Udf};
-pub use validate::validate_args;
+pub(crate) use validate::validate_args;
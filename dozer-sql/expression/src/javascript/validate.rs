@@ -2,26 +2,36 @@ use dozer_types::types::{FieldType, Schema};
 
 use crate::{error::Error, execution::Expression};
 
+/// Checks the arguments of a call against the declared argument types.
+///
+/// A `json` argument accepts any type, as every value is passed to JavaScript as JSON.
+/// Without declared types, any arguments are accepted.
 pub fn validate_args(
     function_name: String,
     args: &[Expression],
+    arg_types: Option<&[FieldType]>,
     schema: &Schema,
 ) -> Result<(), Error> {
-    if args.len() != 1 {
+    let Some(arg_types) = arg_types else {
+        return Ok(());
+    };
+    if args.len() != arg_types.len() {
         return Err(Error::InvalidNumberOfArguments {
             function_name,
-            expected: 1..2,
+            expected: arg_types.len()..arg_types.len() + 1,
             actual: args.len(),
         });
     }
-    let typ = args[0].get_type(schema)?;
-    if typ.return_type != FieldType::Json {
-        return Err(Error::InvalidFunctionArgumentType {
-            function_name,
-            argument_index: 0,
-            expected: vec![FieldType::Json],
-            actual: typ.return_type,
-        });
+    for (argument_index, (arg, expected)) in args.iter().zip(arg_types).enumerate() {
+        let typ = arg.get_type(schema)?;
+        if *expected != FieldType::Json && typ.return_type != *expected {
+            return Err(Error::InvalidFunctionArgumentType {
+                function_name,
+                argument_index,
+                expected: vec![expected.clone()],
+                actual: typ.return_type,
+            });
+        }
     }
     Ok(())
 }
@@ -1,8 +1,9 @@
 pub mod aggregate;
 mod arg_utils;
+mod batch;
 pub mod builder;
 mod case;
-mod cast;
+pub mod cast;
 mod comparison;
 mod conditional;
 mod datetime;
@@ -16,7 +17,7 @@ mod mathematical;
 pub mod operator;
 pub mod scalar;
 
-mod javascript;
+pub mod javascript;
 #[cfg(feature = "onnx")]
 mod onnx;
 #[cfg(feature = "python")]
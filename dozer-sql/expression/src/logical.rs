@@ -29,6 +29,8 @@ pub fn evaluate_and(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_) => Err(Error::InvalidType(r_field, "AND".to_string())),
         },
@@ -48,6 +50,8 @@ pub fn evaluate_and(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_) => Err(Error::InvalidType(r_field, "AND".to_string())),
         },
@@ -64,6 +68,8 @@ pub fn evaluate_and(
         | Field::Timestamp(_)
         | Field::Date(_)
         | Field::Json(_)
+        | Field::List(_)
+        | Field::Struct(_)
         | Field::Point(_)
         | Field::Duration(_) => Err(Error::InvalidType(l_field, "AND".to_string())),
     }
@@ -94,6 +100,8 @@ pub fn evaluate_or(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_) => Err(Error::InvalidType(r_field, "OR".to_string())),
         },
@@ -113,6 +121,8 @@ pub fn evaluate_or(
             | Field::Timestamp(_)
             | Field::Date(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_) => Err(Error::InvalidType(r_field, "OR".to_string())),
         },
@@ -128,6 +138,8 @@ pub fn evaluate_or(
         | Field::Timestamp(_)
         | Field::Date(_)
         | Field::Json(_)
+        | Field::List(_)
+        | Field::Struct(_)
         | Field::Point(_)
         | Field::Duration(_) => Err(Error::InvalidType(l_field, "OR".to_string())),
     }
@@ -155,6 +167,8 @@ pub fn evaluate_not(
         | Field::Timestamp(_)
         | Field::Date(_)
         | Field::Json(_)
+        | Field::List(_)
+        | Field::Struct(_)
         | Field::Point(_)
         | Field::Duration(_) => Err(Error::InvalidType(value_p, "NOT".to_string())),
     }
@@ -82,6 +82,8 @@ macro_rules! define_math_operator {
                     | Field::Decimal(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Null => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -164,6 +166,8 @@ macro_rules! define_math_operator {
                     | Field::Decimal(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Null => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -369,6 +373,8 @@ macro_rules! define_math_operator {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -616,6 +622,8 @@ macro_rules! define_math_operator {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -841,6 +849,8 @@ macro_rules! define_math_operator {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -1111,6 +1121,8 @@ macro_rules! define_math_operator {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -1352,6 +1364,8 @@ macro_rules! define_math_operator {
                     | Field::Timestamp(_)
                     | Field::Date(_)
                     | Field::Json(_)
+                    | Field::List(_)
+                    | Field::Struct(_)
                     | Field::Point(_)
                     | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                         left_p,
@@ -1483,6 +1497,8 @@ macro_rules! define_math_operator {
                                 | Field::Timestamp(_)
                                 | Field::Date(_)
                                 | Field::Json(_)
+                                | Field::List(_)
+                                | Field::Struct(_)
                                 | Field::Point(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
@@ -1573,6 +1589,8 @@ macro_rules! define_math_operator {
                                 | Field::Timestamp(_)
                                 | Field::Date(_)
                                 | Field::Json(_)
+                                | Field::List(_)
+                                | Field::Struct(_)
                                 | Field::Point(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
@@ -1663,6 +1681,8 @@ macro_rules! define_math_operator {
                                 | Field::Timestamp(_)
                                 | Field::Date(_)
                                 | Field::Json(_)
+                                | Field::List(_)
+                                | Field::Struct(_)
                                 | Field::Point(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
@@ -1736,6 +1756,8 @@ macro_rules! define_math_operator {
                                 | Field::Timestamp(_)
                                 | Field::Date(_)
                                 | Field::Json(_)
+                                | Field::List(_)
+                                | Field::Struct(_)
                                 | Field::Point(_)
                                 | Field::Duration(_) => Err(PipelineError::InvalidTypeComparison(
                                     left_p,
@@ -1759,6 +1781,8 @@ macro_rules! define_math_operator {
                 | Field::Binary(_)
                 | Field::Date(_)
                 | Field::Json(_)
+                | Field::List(_)
+                | Field::Struct(_)
                 | Field::Point(_) => Err(PipelineError::InvalidTypeComparison(
                     left_p,
                     right_p,
@@ -1795,6 +1819,8 @@ pub fn evaluate_plus(
         | Field::Timestamp(_)
         | Field::Date(_)
         | Field::Json(_)
+        | Field::List(_)
+        | Field::Struct(_)
         | Field::Point(_)
         | Field::Duration(_)
         | Field::Null => Err(PipelineError::InvalidType(
@@ -1825,6 +1851,8 @@ pub fn evaluate_minus(
         // | Field::Timestamp(_)
         | Field::Date(_)
         | Field::Json(_)
+        | Field::List(_)
+        | Field::Struct(_)
         | Field::Point(_)
         | Field::Duration(_)
         | Field::Null => Err(PipelineError::InvalidType(
@@ -0,0 +1,182 @@
+use super::error::Error::{OnnxNotSupportedDataTypeErr, OnnxOrtErr, OnnxOutputShapeErr};
+use super::tensor::run_model;
+use crate::error::Error::{self, Onnx};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::Field;
+use half::f16;
+use ndarray::ArrayViewD;
+use ort::tensor::TensorElementDataType;
+use ort::Session;
+
+/// Rows can be stacked into one tensor if the model's input and output have a dynamic leading dimension.
+pub fn supports_batch(session: &Session) -> bool {
+    let mut input_dimensions = session.inputs[0].dimensions();
+    let mut output_dimensions = session.outputs[0].dimensions();
+    matches!(input_dimensions.next(), Some(None)) && matches!(output_dimensions.next(), Some(None))
+}
+
+/// Runs one inference for a batch of rows, each holding the values of the UDF arguments.
+pub fn evaluate_onnx_udf_batch(
+    session: &Session,
+    rows: Vec<Vec<Field>>,
+) -> Result<Vec<Field>, Error> {
+    let num_rows = rows.len();
+    let mut input_shape = vec![num_rows];
+    input_shape.extend(session.inputs[0].dimensions().skip(1).flatten());
+
+    let return_type = session.outputs[0].output_type;
+    let outputs = run_model(session, input_shape, rows.into_iter().flatten())?;
+    let output = &outputs[0];
+
+    match return_type {
+        TensorElementDataType::Float16 => {
+            let tensor = output
+                .try_extract::<f16>()
+                .map_err(|e| Onnx(OnnxOrtErr(e)))?;
+            first_of_rows(&tensor.view(), num_rows, f64::from)
+        }
+        TensorElementDataType::Float32 => {
+            let tensor = output
+                .try_extract::<f32>()
+                .map_err(|e| Onnx(OnnxOrtErr(e)))?;
+            first_of_rows(&tensor.view(), num_rows, f64::from)
+        }
+        TensorElementDataType::Float64 => {
+            let tensor = output
+                .try_extract::<f64>()
+                .map_err(|e| Onnx(OnnxOrtErr(e)))?;
+            first_of_rows(&tensor.view(), num_rows, |v| v)
+        }
+        _ => Err(Onnx(OnnxNotSupportedDataTypeErr(return_type))),
+    }
+}
+
+/// Takes the first output value of every row, like the per-row evaluation does.
+fn first_of_rows<T: Copy>(
+    output: &ArrayViewD<T>,
+    num_rows: usize,
+    to_f64: impl Fn(T) -> f64,
+) -> Result<Vec<Field>, Error> {
+    let output_rows = output.shape().first().copied().unwrap_or_default();
+    if output_rows != num_rows {
+        return Err(Onnx(OnnxOutputShapeErr(num_rows, output_rows)));
+    }
+    Ok(output
+        .outer_iter()
+        .map(|row| match row.iter().next() {
+            Some(v) => Field::Float(OrderedFloat(to_f64(*v))),
+            None => Field::Null,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use dozer_types::types::{FieldDefinition, FieldType, Record, Schema, SourceDefinition};
+    use ndarray::ArrayD;
+    use ort::{Environment, SessionBuilder};
+
+    use crate::execution::Expression;
+    use crate::onnx::error::Error::OnnxInputDataMismatchErr;
+    use crate::onnx::udf::evaluate_onnx_udf;
+
+    use super::*;
+
+    /// A model that returns its `[N, 2]` float input unchanged.
+    fn identity_model() -> Session {
+        let environment = Environment::builder()
+            .with_name("dozer_onnx_test")
+            .build()
+            .unwrap()
+            .into_arc();
+        SessionBuilder::new(&environment)
+            .unwrap()
+            .with_model_from_file(
+                Path::new(env!("CARGO_MANIFEST_DIR")).join("src/onnx/testdata/identity.onnx"),
+            )
+            .unwrap()
+    }
+
+    fn float(value: f64) -> Field {
+        Field::Float(OrderedFloat(value))
+    }
+
+    #[test]
+    fn test_batch_takes_first_value_of_every_row() {
+        let session = identity_model();
+        assert!(supports_batch(&session));
+
+        let rows = vec![
+            vec![float(1.5), float(2.0)],
+            vec![Field::Int(3), Field::UInt(4)],
+            vec![float(-1.0), Field::I128(0)],
+        ];
+        assert_eq!(
+            evaluate_onnx_udf_batch(&session, rows).unwrap(),
+            vec![float(1.5), float(3.0), float(-1.0)]
+        );
+    }
+
+    #[test]
+    fn test_batch_matches_per_row_evaluation() {
+        let session = identity_model();
+        let mut schema = Schema::default();
+        for name in ["a", "b"] {
+            schema.field(
+                FieldDefinition::new(
+                    name.to_string(),
+                    FieldType::Float,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            );
+        }
+        let records = [(0.5, 1.0), (2.0, -3.0), (4.25, 0.0)]
+            .into_iter()
+            .map(|(a, b)| Record::new(vec![float(a), float(b)]))
+            .collect::<Vec<_>>();
+
+        let mut args = vec![
+            Expression::Column { index: 0 },
+            Expression::Column { index: 1 },
+        ];
+        let expected = records
+            .iter()
+            .map(|record| evaluate_onnx_udf(&schema, &session, &mut args, record).unwrap())
+            .collect::<Vec<_>>();
+        let rows = records.into_iter().map(|record| record.values).collect();
+        assert_eq!(evaluate_onnx_udf_batch(&session, rows).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_batch_rejects_mismatched_input() {
+        let session = identity_model();
+        let rows = vec![
+            vec![float(1.0), float(2.0)],
+            vec![Field::String("3".to_string()), float(4.0)],
+        ];
+        assert!(matches!(
+            evaluate_onnx_udf_batch(&session, rows),
+            Err(Onnx(OnnxInputDataMismatchErr(
+                TensorElementDataType::Float32,
+                Field::String(_)
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_first_of_rows_checks_row_count() {
+        let output = ArrayD::from_shape_vec(vec![2, 2], vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+        assert!(matches!(
+            first_of_rows(&output.view(), 3, |v: f64| v),
+            Err(Onnx(OnnxOutputShapeErr(3, 2)))
+        ));
+        assert_eq!(
+            first_of_rows(&output.view(), 2, |v: f64| v).unwrap(),
+            vec![float(1.0), float(3.0)]
+        );
+    }
+}
@@ -1,4 +1,6 @@
+pub mod batch;
 pub mod error;
+mod tensor;
 pub mod udf;
 pub mod utils;
 
@@ -0,0 +1,183 @@
+use super::error::Error::{
+    InputArgumentOverflow, OnnxInputDataMismatchErr, OnnxNotSupportedDataTypeErr, OnnxOrtErr,
+    OnnxShapeErr,
+};
+use crate::error::Error::{self, Onnx};
+use dozer_types::log::warn;
+use dozer_types::types::Field;
+use ndarray::{Array, CowArray};
+use num_traits::FromPrimitive;
+use ort::tensor::{IntoTensorElementDataType, TensorElementDataType};
+use ort::{Session, Value};
+use std::fmt::Debug;
+
+/// Converts `fields` to the element type of the model's input, and runs the model on them as one tensor of `input_shape`.
+pub fn run_model(
+    session: &Session,
+    input_shape: Vec<usize>,
+    fields: impl Iterator<Item = Field>,
+) -> Result<Vec<Value<'static>>, Error> {
+    let input_type = session.inputs[0].input_type;
+    let return_type = session.outputs[0].output_type;
+
+    match input_type {
+        TensorElementDataType::Float32 => {
+            warn!("Precision loss is expected due to conversion to f32");
+            let values = numbers::<f32>(fields, input_type, return_type)?;
+            run(session, input_shape, values)
+        }
+        TensorElementDataType::Float64 => {
+            let values = numbers::<f64>(fields, input_type, return_type)?;
+            run(session, input_shape, values)
+        }
+        TensorElementDataType::Uint8 => {
+            warn!("Precision loss is expected due to conversion to u8");
+            let values = numbers::<u8>(fields, input_type, return_type)?;
+            run(session, input_shape, values)
+        }
+        TensorElementDataType::Uint16 => {
+            warn!("Precision loss is expected due to conversion to u16");
+            let values = numbers::<u16>(fields, input_type, return_type)?;
+            run(session, input_shape, values)
+        }
+        TensorElementDataType::Uint32 => {
+            warn!("Precision loss is expected due to conversion to u32");
+            let values = numbers::<u32>(fields, input_type, return_type)?;
+            run(session, input_shape, values)
+        }
+        TensorElementDataType::Uint64 => {
+            let values = numbers::<u64>(fields, input_type, return_type)?;
+            run(session, input_shape, values)
+        }
+        TensorElementDataType::Int8 => {
+            warn!("Precision loss is expected due to conversion to i8");
+            let values = numbers::<i8>(fields, input_type, return_type)?;
+            run(session, input_shape, values)
+        }
+        TensorElementDataType::Int16 => {
+            warn!("Precision loss is expected due to conversion to i16");
+            let values = numbers::<i16>(fields, input_type, return_type)?;
+            run(session, input_shape, values)
+        }
+        TensorElementDataType::Int32 => {
+            warn!("Precision loss is expected due to conversion to i32");
+            let values = numbers::<i32>(fields, input_type, return_type)?;
+            run(session, input_shape, values)
+        }
+        TensorElementDataType::Int64 => {
+            let values = numbers::<i64>(fields, input_type, return_type)?;
+            run(session, input_shape, values)
+        }
+        TensorElementDataType::String => {
+            let values = fields
+                .map(|field| match field {
+                    Field::String(v) | Field::Text(v) => Ok(v),
+                    field => Err(Onnx(OnnxInputDataMismatchErr(input_type, field))),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            run(session, input_shape, values)
+        }
+        TensorElementDataType::Bool => {
+            let values = fields
+                .map(|field| match field {
+                    Field::Boolean(v) => Ok(v),
+                    field => Err(Onnx(OnnxInputDataMismatchErr(input_type, field))),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            run(session, input_shape, values)
+        }
+        _ => Err(Onnx(OnnxNotSupportedDataTypeErr(input_type))),
+    }
+}
+
+fn numbers<T: FromPrimitive>(
+    fields: impl Iterator<Item = Field>,
+    input_type: TensorElementDataType,
+    return_type: TensorElementDataType,
+) -> Result<Vec<T>, Error> {
+    fields
+        .map(|field| {
+            let number = match &field {
+                Field::Float(v) => T::from_f64(**v),
+                Field::Int(v) => T::from_i64(*v),
+                Field::I128(v) => T::from_i128(*v),
+                Field::UInt(v) => T::from_u64(*v),
+                Field::U128(v) => T::from_u128(*v),
+                _ => return Err(Onnx(OnnxInputDataMismatchErr(input_type, field))),
+            };
+            number.ok_or_else(|| Onnx(InputArgumentOverflow(field, return_type)))
+        })
+        .collect()
+}
+
+fn run<T: IntoTensorElementDataType + Debug + Clone>(
+    session: &Session,
+    input_shape: Vec<usize>,
+    values: Vec<T>,
+) -> Result<Vec<Value<'static>>, Error> {
+    let array = CowArray::from(
+        Array::from_shape_vec(input_shape, values)
+            .map_err(|e| Onnx(OnnxShapeErr(e)))?
+            .into_dyn(),
+    );
+    let input_tensor_values =
+        vec![Value::from_array(session.allocator(), &array).map_err(|e| Onnx(OnnxOrtErr(e)))?];
+    session
+        .run(input_tensor_values)
+        .map_err(|e| Onnx(OnnxOrtErr(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use dozer_types::ordered_float::OrderedFloat;
+
+    use super::*;
+
+    #[test]
+    fn test_numbers_convert_every_numeric_field() {
+        let fields = vec![
+            Field::Float(OrderedFloat(1.0)),
+            Field::Int(2),
+            Field::I128(3),
+            Field::UInt(4),
+            Field::U128(5),
+        ];
+        assert_eq!(
+            numbers::<u8>(
+                fields.into_iter(),
+                TensorElementDataType::Uint8,
+                TensorElementDataType::Float32
+            )
+            .unwrap(),
+            vec![1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_numbers_reject_overflow_and_mismatch() {
+        for field in [Field::Int(256), Field::Int(-1)] {
+            assert!(matches!(
+                numbers::<u8>(
+                    std::iter::once(field),
+                    TensorElementDataType::Uint8,
+                    TensorElementDataType::Float32
+                ),
+                Err(Onnx(InputArgumentOverflow(
+                    _,
+                    TensorElementDataType::Float32
+                )))
+            ));
+        }
+        assert!(matches!(
+            numbers::<f32>(
+                std::iter::once(Field::Boolean(true)),
+                TensorElementDataType::Float32,
+                TensorElementDataType::Float32
+            ),
+            Err(Onnx(OnnxInputDataMismatchErr(
+                TensorElementDataType::Float32,
+                Field::Boolean(true)
+            )))
+        ));
+    }
+}
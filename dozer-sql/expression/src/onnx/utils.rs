@@ -40,7 +40,7 @@ pub fn onnx_input_validation(
                         if def.typ != FieldType::Float {
                             return Err(Onnx(OnnxInputDataTypeMismatchErr(
                                 input.input_type,
-                                def.typ,
+                                def.typ.clone(),
                             )));
                         }
                     }
@@ -51,7 +51,7 @@ pub fn onnx_input_validation(
                         if def.typ != FieldType::UInt && def.typ != FieldType::U128 {
                             return Err(Onnx(OnnxInputDataTypeMismatchErr(
                                 input.input_type,
-                                def.typ,
+                                def.typ.clone(),
                             )));
                         }
                     }
@@ -62,7 +62,7 @@ pub fn onnx_input_validation(
                         if def.typ != FieldType::Int && def.typ != FieldType::I128 {
                             return Err(Onnx(OnnxInputDataTypeMismatchErr(
                                 input.input_type,
-                                def.typ,
+                                def.typ.clone(),
                             )));
                         }
                     }
@@ -70,7 +70,7 @@ pub fn onnx_input_validation(
                         if def.typ != FieldType::String && def.typ != FieldType::Text {
                             return Err(Onnx(OnnxInputDataTypeMismatchErr(
                                 input.input_type,
-                                def.typ,
+                                def.typ.clone(),
                             )));
                         }
                     }
@@ -78,7 +78,7 @@ pub fn onnx_input_validation(
                         if def.typ != FieldType::Boolean {
                             return Err(Onnx(OnnxInputDataTypeMismatchErr(
                                 input.input_type,
-                                def.typ,
+                                def.typ.clone(),
                             )));
                         }
                     }
@@ -1,7 +1,7 @@
 use crate::execution::Expression;
 use dozer_types::ordered_float::OrderedFloat;
-use dozer_types::pyo3::types::PyTuple;
-use dozer_types::pyo3::Python;
+use dozer_types::pyo3::types::{PyList, PyTuple};
+use dozer_types::pyo3::{PyAny, Python};
 use dozer_types::thiserror::{self, Error};
 use dozer_types::types::Record;
 use dozer_types::types::{Field, FieldType, Schema};
@@ -9,6 +9,8 @@ use std::env;
 use std::path::PathBuf;
 
 const MODULE_NAME: &str = "python_udf";
+/// Functions with this attribute set are called with whole columns.
+const VECTORIZED_ATTR: &str = "vectorized";
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -24,6 +26,8 @@ pub enum Error {
     UnsupportedReturnType(FieldType),
     #[error("Failed to parse return type: {0}")]
     FailedToParseReturnType(String),
+    #[error("Vectorized function returned {actual} results for {expected} rows")]
+    BatchSizeMismatch { expected: usize, actual: usize },
 }
 
 pub fn evaluate_py_udf(
@@ -38,43 +42,251 @@ pub fn evaluate_py_udf(
         .map(|arg| arg.evaluate(record, schema))
         .collect::<Result<Vec<_>, crate::error::Error>>()?;
 
+    let env_path = set_python_executable()?;
+
+    Python::with_gil(|py| -> Result<Field, Error> {
+        let function = import_function(py, &env_path, name)?;
+
+        let args = PyTuple::new(py, values);
+        let res = function.call1(args)?;
+
+        extract_field(res, return_type)
+    })
+    .map_err(Into::into)
+}
+
+/// Evaluates the UDF for a batch of rows, acquiring the GIL once.
+///
+/// `columns` holds the values of each argument for all rows. If the function has a truthy `vectorized` attribute,
+/// it's called once with a list per argument and must return a list with a result per row.
+/// Otherwise it's called once per row.
+pub fn evaluate_py_udf_batch(
+    name: &str,
+    columns: Vec<Vec<Field>>,
+    return_type: &FieldType,
+    num_rows: usize,
+) -> Result<Vec<Field>, crate::error::Error> {
+    let env_path = set_python_executable()?;
+
+    Python::with_gil(|py| -> Result<Vec<Field>, Error> {
+        let function = import_function(py, &env_path, name)?;
+
+        if is_vectorized(function)? {
+            let args = PyTuple::new(
+                py,
+                columns.into_iter().map(|column| PyList::new(py, column)),
+            );
+            let results = function.call1(args)?.extract::<Vec<&PyAny>>()?;
+            if results.len() != num_rows {
+                return Err(Error::BatchSizeMismatch {
+                    expected: num_rows,
+                    actual: results.len(),
+                });
+            }
+            results
+                .into_iter()
+                .map(|res| extract_field(res, return_type))
+                .collect()
+        } else {
+            (0..num_rows)
+                .map(|row| {
+                    let args = PyTuple::new(py, columns.iter().map(|column| column[row].clone()));
+                    extract_field(function.call1(args)?, return_type)
+                })
+                .collect()
+        }
+    })
+    .map_err(Into::into)
+}
+
+/// Points `PYTHON_SYS_EXECUTABLE` to the Python interpreter of the virtual environment and returns its path.
+fn set_python_executable() -> Result<String, Error> {
     // Get the path of the Python interpreter in your virtual environment
     let env_path = env::var("VIRTUAL_ENV").map_err(|_| Error::MissingVirtualEnv)?;
     let py_path = format!("{env_path}/bin/python");
     // Set the `PYTHON_SYS_EXECUTABLE` environment variable
     env::set_var("PYTHON_SYS_EXECUTABLE", py_path);
+    Ok(env_path)
+}
 
-    Python::with_gil(|py| -> Result<Field, Error> {
-        // Get the directory containing the module
-        let module_dir = PathBuf::from(env_path);
-        // Import the `sys` module and append the module directory to the system path
-        let sys = py.import("sys")?;
-        let path = sys.getattr("path")?;
-        path.call_method1("append", (module_dir.to_string_lossy(),))?;
+fn import_function<'py>(py: Python<'py>, env_path: &str, name: &str) -> Result<&'py PyAny, Error> {
+    // Get the directory containing the module
+    let module_dir = PathBuf::from(env_path);
+    // Import the `sys` module and append the module directory to the system path
+    let sys = py.import("sys")?;
+    let path = sys.getattr("path")?;
+    path.call_method1("append", (module_dir.to_string_lossy(),))?;
 
-        let module = py.import(MODULE_NAME)?;
-        let function = module.getattr(name)?;
+    let module = py.import(MODULE_NAME)?;
+    Ok(module.getattr(name)?)
+}
 
-        let args = PyTuple::new(py, values);
-        let res = function.call1(args)?;
+fn is_vectorized(function: &PyAny) -> Result<bool, Error> {
+    Ok(function.hasattr(VECTORIZED_ATTR)? && function.getattr(VECTORIZED_ATTR)?.is_true()?)
+}
 
-        Ok(match return_type {
-            FieldType::UInt => Field::UInt(res.extract::<u64>()?),
-            FieldType::U128 => Field::U128(res.extract::<u128>()?),
-            FieldType::Int => Field::Int(res.extract::<i64>()?),
-            FieldType::I128 => Field::I128(res.extract::<i128>()?),
-            FieldType::Float => Field::Float(OrderedFloat::from(res.extract::<f64>()?)),
-            FieldType::Boolean => Field::Boolean(res.extract::<bool>()?),
-            FieldType::String => Field::String(res.extract::<String>()?),
-            FieldType::Text => Field::Text(res.extract::<String>()?),
-            FieldType::Binary => Field::Binary(res.extract::<Vec<u8>>()?),
-            FieldType::Decimal
-            | FieldType::Date
-            | FieldType::Timestamp
-            | FieldType::Point
-            | FieldType::Duration
-            | FieldType::Json => return Err(Error::UnsupportedReturnType(*return_type)),
-        })
+fn extract_field(res: &PyAny, return_type: &FieldType) -> Result<Field, Error> {
+    Ok(match return_type {
+        FieldType::UInt => Field::UInt(res.extract::<u64>()?),
+        FieldType::U128 => Field::U128(res.extract::<u128>()?),
+        FieldType::Int => Field::Int(res.extract::<i64>()?),
+        FieldType::I128 => Field::I128(res.extract::<i128>()?),
+        FieldType::Float => Field::Float(OrderedFloat::from(res.extract::<f64>()?)),
+        FieldType::Boolean => Field::Boolean(res.extract::<bool>()?),
+        FieldType::String => Field::String(res.extract::<String>()?),
+        FieldType::Text => Field::Text(res.extract::<String>()?),
+        FieldType::Binary => Field::Binary(res.extract::<Vec<u8>>()?),
+        FieldType::Decimal
+        | FieldType::Date
+        | FieldType::Timestamp
+        | FieldType::Point
+        | FieldType::Duration
+        | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_) => return Err(Error::UnsupportedReturnType(return_type.clone())),
     })
-    .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+
+    use dozer_types::types::{FieldDefinition, SourceDefinition};
+
+    use crate::operator::BinaryOperatorType;
+
+    use super::*;
+
+    const MODULE: &str = r#"
+calls = {}
+
+def count(name):
+    calls[name] = calls.get(name, 0) + 1
+
+def calls_of(name):
+    return calls.get(name, 0)
+
+def add_one(a):
+    count("add_one")
+    return a + 1
+
+def add_vectorized(a, b):
+    count("add_vectorized")
+    return [x + y for x, y in zip(a, b)]
+add_vectorized.vectorized = True
+
+def too_few(a):
+    return a[1:]
+too_few.vectorized = True
+
+def reciprocal(a):
+    return 1.0 / a
+"#;
+
+    /// Writes the test module to a directory and points `VIRTUAL_ENV` to it, which puts it on the Python path.
+    fn setup() {
+        static SETUP: Once = Once::new();
+        SETUP.call_once(|| {
+            let dir = env::temp_dir().join("dozer_python_udf_test");
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join(format!("{MODULE_NAME}.py")), MODULE).unwrap();
+            env::set_var("VIRTUAL_ENV", dir);
+        });
+    }
+
+    fn calls_of(name: &str) -> Field {
+        evaluate_py_udf_batch(
+            "calls_of",
+            vec![vec![Field::String(name.to_string())]],
+            &FieldType::Int,
+            1,
+        )
+        .unwrap()
+        .remove(0)
+    }
+
+    fn ints(values: &[i64]) -> Vec<Field> {
+        values.iter().copied().map(Field::Int).collect()
+    }
+
+    #[test]
+    fn test_batch_calls_function_per_row() {
+        setup();
+        let results =
+            evaluate_py_udf_batch("add_one", vec![ints(&[1, 2, 3])], &FieldType::Int, 3).unwrap();
+        assert_eq!(results, ints(&[2, 3, 4]));
+        assert_eq!(calls_of("add_one"), Field::Int(3));
+    }
+
+    #[test]
+    fn test_batch_calls_vectorized_function_once() {
+        setup();
+        let results = evaluate_py_udf_batch(
+            "add_vectorized",
+            vec![ints(&[1, 2, 3]), ints(&[10, 20, 30])],
+            &FieldType::Int,
+            3,
+        )
+        .unwrap();
+        assert_eq!(results, ints(&[11, 22, 33]));
+        assert_eq!(calls_of("add_vectorized"), Field::Int(1));
+    }
+
+    #[test]
+    fn test_batch_rejects_wrong_number_of_results() {
+        setup();
+        let result = evaluate_py_udf_batch("too_few", vec![ints(&[1, 2, 3])], &FieldType::Int, 3);
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::PythonUdf(Error::BatchSizeMismatch {
+                expected: 3,
+                actual: 2
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_batch_does_not_call_udf_in_unevaluated_branch() {
+        setup();
+        let schema = Schema::default()
+            .field(
+                FieldDefinition::new(
+                    "a".to_string(),
+                    FieldType::Float,
+                    false,
+                    SourceDefinition::Dynamic,
+                ),
+                false,
+            )
+            .clone();
+        // CASE WHEN a > 0 THEN reciprocal(a) ELSE 0.0 END
+        let mut expression = Expression::Case {
+            operand: None,
+            conditions: vec![Expression::BinaryOperator {
+                left: Box::new(Expression::Column { index: 0 }),
+                operator: BinaryOperatorType::Gt,
+                right: Box::new(Expression::Literal(Field::Float(OrderedFloat(0.0)))),
+            }],
+            results: vec![Expression::PythonUDF {
+                name: "reciprocal".to_string(),
+                args: vec![Expression::Column { index: 0 }],
+                return_type: FieldType::Float,
+            }],
+            else_result: Some(Box::new(Expression::Literal(Field::Float(OrderedFloat(
+                0.0,
+            ))))),
+        };
+        let records = [2.0, 0.0, 4.0]
+            .into_iter()
+            .map(|value| Record::new(vec![Field::Float(OrderedFloat(value))]))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            expression.evaluate_batch(&records, &schema).unwrap(),
+            [0.5, 0.0, 0.25]
+                .into_iter()
+                .map(|value| Field::Float(OrderedFloat(value)))
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -1,6 +1,9 @@
 use crate::arg_utils::{validate_num_arguments, validate_one_argument, validate_two_arguments};
 use crate::error::Error;
 use crate::execution::{Expression, ExpressionType};
+use crate::scalar::nested::{
+    evaluate_array, evaluate_cardinality, evaluate_element_at, validate_array, validate_element_at,
+};
 use crate::scalar::number::{evaluate_abs, evaluate_round};
 use crate::scalar::string::{
     evaluate_concat, evaluate_length, evaluate_to_char, evaluate_ucase, validate_concat,
@@ -18,6 +21,9 @@ pub enum ScalarFunctionType {
     Concat,
     Length,
     ToChar,
+    Array,
+    ElementAt,
+    Cardinality,
 }
 
 impl Display for ScalarFunctionType {
@@ -29,6 +35,9 @@ impl Display for ScalarFunctionType {
             ScalarFunctionType::Concat => f.write_str("CONCAT"),
             ScalarFunctionType::Length => f.write_str("LENGTH"),
             ScalarFunctionType::ToChar => f.write_str("TO_CHAR"),
+            ScalarFunctionType::Array => f.write_str("ARRAY"),
+            ScalarFunctionType::ElementAt => f.write_str("ELEMENT_AT"),
+            ScalarFunctionType::Cardinality => f.write_str("CARDINALITY"),
         }
     }
 }
@@ -73,6 +82,17 @@ pub(crate) fn get_scalar_function_type(
                 Ok(validate_two_arguments(args, schema, ScalarFunctionType::ToChar)?.0)
             }
         }
+        ScalarFunctionType::Array => validate_array(args, schema),
+        ScalarFunctionType::ElementAt => validate_element_at(args, schema),
+        ScalarFunctionType::Cardinality => {
+            validate_num_arguments(1..2, args.len(), ScalarFunctionType::Cardinality)?;
+            Ok(ExpressionType::new(
+                FieldType::UInt,
+                true,
+                dozer_types::types::SourceDefinition::Dynamic,
+                false,
+            ))
+        }
     }
 }
 
@@ -85,6 +105,9 @@ impl ScalarFunctionType {
             "concat" => Some(ScalarFunctionType::Concat),
             "length" => Some(ScalarFunctionType::Length),
             "to_char" => Some(ScalarFunctionType::ToChar),
+            "array" => Some(ScalarFunctionType::Array),
+            "element_at" => Some(ScalarFunctionType::ElementAt),
+            "cardinality" | "array_length" => Some(ScalarFunctionType::Cardinality),
             _ => None,
         }
     }
@@ -119,6 +142,16 @@ impl ScalarFunctionType {
                 let (arg0, arg1) = args.split_at_mut(1);
                 evaluate_to_char(schema, &mut arg0[0], &mut arg1[0], record)
             }
+            ScalarFunctionType::Array => evaluate_array(schema, args, record),
+            ScalarFunctionType::ElementAt => {
+                validate_num_arguments(2..3, args.len(), ScalarFunctionType::ElementAt)?;
+                let (arg0, arg1) = args.split_at_mut(1);
+                evaluate_element_at(schema, &mut arg0[0], &mut arg1[0], record)
+            }
+            ScalarFunctionType::Cardinality => {
+                validate_num_arguments(1..2, args.len(), ScalarFunctionType::Cardinality)?;
+                evaluate_cardinality(schema, &mut args[0], record)
+            }
         }
     }
 }
@@ -1,3 +1,4 @@
 pub mod common;
+pub mod nested;
 pub mod number;
 pub mod string;
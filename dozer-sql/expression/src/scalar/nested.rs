@@ -0,0 +1,166 @@
+use crate::arg_utils::validate_num_arguments;
+use crate::error::Error;
+use crate::execution::{Expression, ExpressionType};
+use crate::scalar::common::ScalarFunctionType;
+
+use dozer_types::json_types::{field_to_json_value, JsonValue};
+use dozer_types::types::{Field, FieldType, Schema};
+use dozer_types::types::{Record, SourceDefinition};
+
+/// The elements of an array share the type of its non-null arguments.
+/// Arguments of different types make an untyped list.
+pub(crate) fn validate_array(
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    let mut element_type: Option<FieldType> = None;
+    for arg in args {
+        if let Expression::Literal(Field::Null) = arg {
+            continue;
+        }
+        let arg_type = arg.get_type(schema)?.return_type;
+        element_type = match element_type {
+            None => Some(arg_type),
+            Some(typ) if typ == arg_type => Some(typ),
+            Some(_) => Some(FieldType::Json),
+        };
+    }
+    Ok(ExpressionType::new(
+        FieldType::List(Box::new(element_type.unwrap_or(FieldType::Json))),
+        false,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+pub(crate) fn validate_element_at(
+    args: &[Expression],
+    schema: &Schema,
+) -> Result<ExpressionType, Error> {
+    validate_num_arguments(2..3, args.len(), ScalarFunctionType::ElementAt)?;
+    Ok(ExpressionType::new(
+        element_type(&args[0], &args[1], schema)?,
+        true,
+        SourceDefinition::Dynamic,
+        false,
+    ))
+}
+
+/// The type of the element addressed by `key`. A struct field is only typed when it is named by a literal.
+fn element_type(
+    container: &Expression,
+    key: &Expression,
+    schema: &Schema,
+) -> Result<FieldType, Error> {
+    Ok(match (container.get_type(schema)?.return_type, key) {
+        (FieldType::List(element), _) => *element,
+        (
+            FieldType::Struct(fields),
+            Expression::Literal(Field::String(name) | Field::Text(name)),
+        ) => fields
+            .into_iter()
+            .find(|(field_name, _)| field_name == name)
+            .map_or(FieldType::Json, |(_, typ)| typ),
+        _ => FieldType::Json,
+    })
+}
+
+pub(crate) fn evaluate_array(
+    schema: &Schema,
+    args: &mut [Expression],
+    record: &Record,
+) -> Result<Field, Error> {
+    let mut values = Vec::with_capacity(args.len());
+    for arg in args {
+        values.push(arg.evaluate(record, schema)?);
+    }
+    Ok(Field::List(values))
+}
+
+/// Elements of lists are addressed with 1-based indexes, fields of structs by name.
+/// Elements without a declared type are returned as JSON.
+pub(crate) fn evaluate_element_at(
+    schema: &Schema,
+    arg0: &mut Expression,
+    arg1: &mut Expression,
+    record: &Record,
+) -> Result<Field, Error> {
+    let container = arg0.evaluate(record, schema)?;
+    let key = arg1.evaluate(record, schema)?;
+
+    let element = match (&container, &key) {
+        (Field::Null, _) | (_, Field::Null) => None,
+        (Field::List(values), _) => {
+            list_position(&key)?.and_then(|position| values.get(position).cloned())
+        }
+        (Field::Struct(fields), Field::String(name) | Field::Text(name)) => fields
+            .iter()
+            .find(|(field_name, _)| field_name == name)
+            .map(|(_, value)| value.clone()),
+        (Field::Json(json), _) => {
+            return Ok(json_element_at(json, &key)?
+                .map(|value| Field::Json(value.clone()))
+                .unwrap_or(Field::Null))
+        }
+        _ => {
+            return Err(Error::InvalidFunctionArgument {
+                function_name: ScalarFunctionType::ElementAt.to_string(),
+                argument_index: 0,
+                argument: container,
+            })
+        }
+    };
+
+    Ok(match element {
+        None | Some(Field::Null) => Field::Null,
+        Some(element) => match element_type(arg0, arg1, schema)? {
+            FieldType::Json => Field::Json(field_to_json_value(element)),
+            _ => element,
+        },
+    })
+}
+
+pub(crate) fn evaluate_cardinality(
+    schema: &Schema,
+    arg: &mut Expression,
+    record: &Record,
+) -> Result<Field, Error> {
+    match arg.evaluate(record, schema)? {
+        Field::Null => Ok(Field::Null),
+        Field::List(values) => Ok(Field::UInt(values.len() as u64)),
+        Field::Struct(fields) => Ok(Field::UInt(fields.len() as u64)),
+        Field::Json(json) => match (json.as_array(), json.as_object()) {
+            (Some(array), _) => Ok(Field::UInt(array.len() as u64)),
+            (_, Some(object)) => Ok(Field::UInt(object.len() as u64)),
+            _ => Ok(Field::Null),
+        },
+        other => Err(Error::InvalidFunctionArgument {
+            function_name: ScalarFunctionType::Cardinality.to_string(),
+            argument_index: 0,
+            argument: other,
+        }),
+    }
+}
+
+fn json_element_at<'a>(json: &'a JsonValue, key: &Field) -> Result<Option<&'a JsonValue>, Error> {
+    if let Some(array) = json.as_array() {
+        return Ok(list_position(key)?.and_then(|position| array.get(position)));
+    }
+    match (json.as_object(), key) {
+        (Some(object), Field::String(name) | Field::Text(name)) => Ok(object.get(name.as_str())),
+        _ => Ok(None),
+    }
+}
+
+fn list_position(key: &Field) -> Result<Option<usize>, Error> {
+    let index = match key {
+        Field::UInt(_) | Field::Int(_) | Field::U128(_) | Field::I128(_) => key.to_int(),
+        _ => None,
+    }
+    .ok_or_else(|| Error::InvalidFunctionArgument {
+        function_name: ScalarFunctionType::ElementAt.to_string(),
+        argument_index: 1,
+        argument: key.clone(),
+    })?;
+    Ok(usize::try_from(index).ok().and_then(|i| i.checked_sub(1)))
+}
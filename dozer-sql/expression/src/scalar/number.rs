@@ -26,6 +26,8 @@ pub(crate) fn evaluate_abs(
         | Field::Timestamp(_)
         | Field::Binary(_)
         | Field::Json(_)
+        | Field::List(_)
+        | Field::Struct(_)
         | Field::Point(_)
         | Field::Duration(_)
         | Field::Null => Err(Error::InvalidFunctionArgument {
@@ -68,6 +70,8 @@ pub(crate) fn evaluate_round(
             | Field::Timestamp(_)
             | Field::Binary(_)
             | Field::Json(_)
+            | Field::List(_)
+            | Field::Struct(_)
             | Field::Point(_)
             | Field::Duration(_)
             | Field::Null => {} // Truncate value to 0 decimals
@@ -90,6 +94,8 @@ pub(crate) fn evaluate_round(
         | Field::Timestamp(_)
         | Field::Binary(_)
         | Field::Json(_)
+        | Field::List(_)
+        | Field::Struct(_)
         | Field::Point(_)
         | Field::Duration(_) => Err(Error::InvalidFunctionArgument {
             function_name: ScalarFunctionType::Round.to_string(),
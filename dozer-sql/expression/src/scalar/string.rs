@@ -44,6 +44,8 @@ pub fn evaluate_ucase(
         | FieldType::Timestamp
         | FieldType::Binary
         | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_)
         | FieldType::Point
         | FieldType::Duration => Field::Text(ret),
     })
@@ -103,6 +105,8 @@ pub fn evaluate_concat(
         | FieldType::Timestamp
         | FieldType::Binary
         | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_)
         | FieldType::Point
         | FieldType::Duration => Field::String(res_str),
     })
@@ -184,6 +188,8 @@ pub fn evaluate_trim(
         | FieldType::Timestamp
         | FieldType::Binary
         | FieldType::Json
+        | FieldType::List(_)
+        | FieldType::Struct(_)
         | FieldType::Point
         | FieldType::Duration => Field::Text(retval),
     })
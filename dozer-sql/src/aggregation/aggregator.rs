@@ -2,6 +2,7 @@
 
 use crate::aggregation::avg::AvgAggregator;
 use crate::aggregation::count::CountAggregator;
+use crate::aggregation::javascript::JavaScriptAggregator;
 use crate::aggregation::max::MaxAggregator;
 use crate::aggregation::min::MinAggregator;
 use crate::aggregation::sum::SumAggregator;
@@ -16,6 +17,7 @@ use std::collections::BTreeMap;
 
 use dozer_sql_expression::aggregate::AggregateFunctionType;
 use dozer_sql_expression::execution::Expression;
+use dozer_sql_expression::javascript::Udaf;
 
 use crate::aggregation::max_append_only::MaxAppendOnlyAggregator;
 use crate::aggregation::max_value::MaxValueAggregator;
@@ -46,9 +48,10 @@ pub enum AggregatorEnum {
     MaxValueAggregator,
     SumAggregator,
     CountAggregator,
+    JavaScriptAggregator,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AggregatorType {
     Avg,
     Count,
@@ -59,6 +62,7 @@ pub enum AggregatorType {
     MinAppendOnly,
     MinValue,
     Sum,
+    JavaScript(Udaf),
 }
 
 #[derive(Debug, bincode::Encode, bincode::Decode)]
@@ -178,7 +182,10 @@ impl OrderedAggregatorState {
         Ok(())
     }
 
-    pub(crate) fn new(function_type: AggregateFunctionType, field_type: FieldType) -> Option<Self> {
+    pub(crate) fn new(
+        function_type: AggregateFunctionType,
+        field_type: &FieldType,
+    ) -> Option<Self> {
         let inner = match field_type {
             FieldType::UInt => OrderedAggregatorStateInner::UInt(Default::default()),
             FieldType::U128 => OrderedAggregatorStateInner::U128(Default::default()),
@@ -250,11 +257,12 @@ impl Display for AggregatorType {
             AggregatorType::MinAppendOnly => f.write_str("min_append_only"),
             AggregatorType::MinValue => f.write_str("min_value"),
             AggregatorType::Sum => f.write_str("sum"),
+            AggregatorType::JavaScript(udaf) => f.write_str(udaf.function_name()),
         }
     }
 }
 
-pub fn get_aggregator_from_aggregator_type(typ: AggregatorType) -> AggregatorEnum {
+pub fn get_aggregator_from_aggregator_type(typ: &AggregatorType) -> AggregatorEnum {
     match typ {
         AggregatorType::Avg => AvgAggregator::new().into(),
         AggregatorType::Count => CountAggregator::new().into(),
@@ -265,6 +273,7 @@ pub fn get_aggregator_from_aggregator_type(typ: AggregatorType) -> AggregatorEnu
         AggregatorType::MinAppendOnly => MinAppendOnlyAggregator::new().into(),
         AggregatorType::MinValue => MinValueAggregator::new().into(),
         AggregatorType::Sum => SumAggregator::new().into(),
+        AggregatorType::JavaScript(udaf) => JavaScriptAggregator::new(udaf.clone()).into(),
     }
 }
 
@@ -405,6 +414,10 @@ pub fn get_aggregator_type_from_aggregation_expression(
                 .clone()],
             AggregatorType::Count,
         )),
+        Expression::JavaScriptUdaf(udaf) => Ok((
+            udaf.args().to_vec(),
+            AggregatorType::JavaScript(udaf.clone()),
+        )),
         _ => Err(PipelineError::InvalidFunction(e.to_string(schema))),
     }
 }
@@ -53,7 +53,7 @@ impl Aggregator for AvgAggregator {
             old,
             &mut self.current_state,
             &mut self.current_count,
-            self.return_type,
+            &self.return_type,
             true,
         )
     }
@@ -64,7 +64,7 @@ impl Aggregator for AvgAggregator {
             new,
             &mut self.current_state,
             &mut self.current_count,
-            self.return_type,
+            &self.return_type,
             false,
         )
     }
@@ -74,7 +74,7 @@ fn get_average(
     field: &[Field],
     current_sum: &mut SumState,
     current_count: &mut u64,
-    return_type: Option<FieldType>,
+    return_type: &Option<FieldType>,
     decr: bool,
 ) -> Result<Field, PipelineError> {
     let sum = get_sum(field, current_sum, return_type, decr)?;
@@ -154,6 +154,8 @@ fn get_average(
             | FieldType::Timestamp
             | FieldType::Binary
             | FieldType::Json
+            | FieldType::List(_)
+            | FieldType::Struct(_)
             | FieldType::Point => Err(PipelineError::InvalidReturnType(format!(
                 "Not supported return type {typ} for {Avg}"
             ))),
@@ -34,16 +34,16 @@ impl Aggregator for CountAggregator {
 
     fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
         self.current_state -= old.len() as u64;
-        get_count(self.current_state, self.return_type)
+        get_count(self.current_state, &self.return_type)
     }
 
     fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
         self.current_state += new.len() as u64;
-        get_count(self.current_state, self.return_type)
+        get_count(self.current_state, &self.return_type)
     }
 }
 
-fn get_count(count: u64, return_type: Option<FieldType>) -> Result<Field, PipelineError> {
+fn get_count(count: u64, return_type: &Option<FieldType>) -> Result<Field, PipelineError> {
     match return_type {
         Some(typ) => match typ {
             FieldType::UInt => Ok(Field::UInt(count)),
@@ -64,6 +64,8 @@ fn get_count(count: u64, return_type: Option<FieldType>) -> Result<Field, Pipeli
             | FieldType::Timestamp
             | FieldType::Binary
             | FieldType::Json
+            | FieldType::List(_)
+            | FieldType::Struct(_)
             | FieldType::Point => Err(PipelineError::InvalidReturnType(format!(
                 "Not supported return type {typ} for {Count}"
             ))),
@@ -2,15 +2,16 @@ use crate::planner::projection::CommonPlanner;
 use crate::projection::processor::ProjectionProcessor;
 use crate::{aggregation::processor::AggregationProcessor, errors::PipelineError};
 use dozer_core::{
-    node::{PortHandle, Processor, ProcessorFactory},
+    node::{Partitioner, PortHandle, Processor, ProcessorFactory},
     DEFAULT_PORT_HANDLE,
 };
+use dozer_sql_expression::execution::Expression;
 use dozer_sql_expression::sqlparser::ast::Select;
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::models::udf_config::UdfConfig;
 use dozer_types::parking_lot::Mutex;
 use dozer_types::tonic::async_trait;
-use dozer_types::types::Schema;
+use dozer_types::types::{Field, Record, Schema};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
@@ -133,6 +134,52 @@ impl ProcessorFactory for AggregationProcessorFactory {
     fn id(&self) -> String {
         self.id.clone()
     }
+
+    fn definition(&self) -> Option<String> {
+        Some(format!(
+            "{} (probabilistic optimizations: {})",
+            self.projection, self.enable_probabilistic_optimizations
+        ))
+    }
+
+    async fn partitioner(
+        &self,
+        input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<Option<Box<dyn Partitioner>>, BoxedError> {
+        let input_schema = input_schemas
+            .get(&DEFAULT_PORT_HANDLE)
+            .ok_or(PipelineError::InvalidPortHandle(DEFAULT_PORT_HANDLE))?;
+
+        let planner = self.get_planner(input_schema.clone()).await?;
+        if planner.groupby.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Box::new(GroupByPartitioner {
+            dimensions: planner.groupby,
+            input_schema: input_schema.clone(),
+        })))
+    }
+}
+
+/// Partitions input by the GROUP BY key, so every group is aggregated by one instance.
+#[derive(Debug)]
+struct GroupByPartitioner {
+    dimensions: Vec<Expression>,
+    input_schema: Schema,
+}
+
+impl Partitioner for GroupByPartitioner {
+    fn partition_key(
+        &mut self,
+        _port: PortHandle,
+        record: &Record,
+    ) -> Result<Vec<Field>, BoxedError> {
+        let mut key = Vec::with_capacity(self.dimensions.len());
+        for dimension in self.dimensions.iter_mut() {
+            key.push(dimension.evaluate(record, &self.input_schema)?);
+        }
+        Ok(key)
+    }
 }
 
 fn is_projection(planner: &CommonPlanner) -> bool {
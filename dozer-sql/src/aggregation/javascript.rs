@@ -0,0 +1,103 @@
+use crate::aggregation::aggregator::Aggregator;
+use crate::errors::PipelineError;
+use dozer_sql_expression::error::Error;
+use dozer_sql_expression::javascript::Udaf;
+use dozer_types::json_types::JsonValue;
+use dozer_types::types::{Field, FieldType};
+
+/// Calls a JavaScript aggregate function. Only the JSON state is checkpointed,
+/// the function has to be attached again after decoding.
+#[derive(Debug)]
+pub struct JavaScriptAggregator {
+    udaf: Option<Udaf>,
+    /// `None` until the first value is accumulated.
+    state: Option<JsonValue>,
+}
+
+impl JavaScriptAggregator {
+    pub fn new(udaf: Udaf) -> Self {
+        Self {
+            udaf: Some(udaf),
+            state: None,
+        }
+    }
+
+    pub fn attach(&mut self, udaf: Udaf) {
+        self.udaf = Some(udaf);
+    }
+
+    fn udaf(&self) -> &Udaf {
+        self.udaf
+            .as_ref()
+            .expect("aggregate function must be attached after decoding")
+    }
+
+    fn take_state(&mut self) -> Result<JsonValue, PipelineError> {
+        match self.state.take() {
+            Some(state) => Ok(state),
+            None => self.udaf().init().map_err(to_pipeline_error),
+        }
+    }
+
+    fn result(&self) -> Result<Field, PipelineError> {
+        let state = self.state.clone().unwrap_or(JsonValue::NULL);
+        Ok(self.udaf().result(state)?)
+    }
+}
+
+fn to_pipeline_error(e: dozer_sql_expression::javascript::Error) -> PipelineError {
+    Error::JavaScript(e).into()
+}
+
+impl Aggregator for JavaScriptAggregator {
+    fn init(&mut self, _return_type: FieldType) {}
+
+    fn update(&mut self, old: &[Field], new: &[Field]) -> Result<Field, PipelineError> {
+        self.delete(old)?;
+        self.insert(new)
+    }
+
+    fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
+        let state = self.take_state()?;
+        self.state = Some(self.udaf().retract(state, old).map_err(to_pipeline_error)?);
+        self.result()
+    }
+
+    fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
+        let state = self.take_state()?;
+        self.state = Some(
+            self.udaf()
+                .accumulate(state, new)
+                .map_err(to_pipeline_error)?,
+        );
+        self.result()
+    }
+}
+
+impl bincode::Encode for JavaScriptAggregator {
+    fn encode<E: bincode::enc::Encoder>(
+        &self,
+        encoder: &mut E,
+    ) -> Result<(), bincode::error::EncodeError> {
+        self.state.clone().map(Field::Json).encode(encoder)
+    }
+}
+
+impl bincode::Decode for JavaScriptAggregator {
+    fn decode<D: bincode::de::Decoder>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        let state = match Option::<Field>::decode(decoder)? {
+            Some(Field::Json(state)) => Some(state),
+            Some(other) => {
+                return Err(bincode::error::DecodeError::OtherString(format!(
+                    "expected JSON aggregate state, got {other}"
+                )))
+            }
+            None => None,
+        };
+        Ok(Self { udaf: None, state })
+    }
+}
+
+bincode::impl_borrow_decode!(JavaScriptAggregator);
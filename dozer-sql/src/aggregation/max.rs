@@ -22,8 +22,8 @@ impl MaxAggregator {
 
 impl Aggregator for MaxAggregator {
     fn init(&mut self, return_type: FieldType) {
+        self.current_state = OrderedAggregatorState::new(Max, &return_type);
         self.return_type = Some(return_type);
-        self.current_state = OrderedAggregatorState::new(Max, return_type);
     }
 
     fn update(&mut self, old: &[Field], new: &[Field]) -> Result<Field, PipelineError> {
@@ -53,6 +53,7 @@ impl MaxAggregator {
         self.current_state.as_mut().ok_or_else(|| {
             match self
                 .return_type
+                .as_ref()
                 .expect("MaxAggregator processor not initialized")
             {
                 typ @ (FieldType::Boolean
@@ -60,6 +61,8 @@ impl MaxAggregator {
                 | FieldType::Text
                 | FieldType::Binary
                 | FieldType::Json
+                | FieldType::List(_)
+                | FieldType::Struct(_)
                 | FieldType::Point) => PipelineError::InvalidReturnType(format!(
                     "Not supported return type {typ} for {Max}"
                 )),
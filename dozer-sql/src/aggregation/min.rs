@@ -23,7 +23,7 @@ impl MinAggregator {
 
 impl Aggregator for MinAggregator {
     fn init(&mut self, return_type: FieldType) {
-        self.current_state = OrderedAggregatorState::new(AggregateFunctionType::Min, return_type);
+        self.current_state = OrderedAggregatorState::new(AggregateFunctionType::Min, &return_type);
         self.return_type = Some(return_type);
     }
 
@@ -54,6 +54,7 @@ impl MinAggregator {
         self.current_state.as_mut().ok_or_else(|| {
             match self
                 .return_type
+                .as_ref()
                 .expect("MinAggregator processor not initialized")
             {
                 typ @ (FieldType::Boolean
@@ -61,6 +62,8 @@ impl MinAggregator {
                 | FieldType::Text
                 | FieldType::Binary
                 | FieldType::Json
+                | FieldType::List(_)
+                | FieldType::Struct(_)
                 | FieldType::Point) => PipelineError::InvalidReturnType(format!(
                     "Not supported return type {typ} for {Min}"
                 )),
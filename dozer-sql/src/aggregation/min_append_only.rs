@@ -53,7 +53,7 @@ impl Aggregator for MinAppendOnlyAggregator {
             if val == &Field::Null {
                 continue;
             }
-            match self.return_type {
+            match self.return_type.clone() {
                 Some(typ) => match typ {
                     FieldType::UInt => {
                         let new_val = calculate_err_field!(val.to_uint(), MinAppendOnly, val);
@@ -160,6 +160,8 @@ impl Aggregator for MinAppendOnlyAggregator {
                     | FieldType::Text
                     | FieldType::Binary
                     | FieldType::Json
+                    | FieldType::List(_)
+                    | FieldType::Struct(_)
                     | FieldType::Point => {
                         return Err(PipelineError::InvalidReturnType(format!(
                             "Not supported return type {typ} for {MinAppendOnly}"
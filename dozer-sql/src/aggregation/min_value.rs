@@ -42,7 +42,7 @@ impl Aggregator for MinValueAggregator {
             &mut self.current_state,
             &mut self.return_state,
         )?;
-        get_min_value(&self.current_state, &self.return_state, self.return_type)
+        get_min_value(&self.current_state, &self.return_state, &self.return_type)
     }
 
     fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
@@ -53,14 +53,14 @@ impl Aggregator for MinValueAggregator {
             &mut self.current_state,
             &mut self.return_state,
         )?;
-        get_min_value(&self.current_state, &self.return_state, self.return_type)
+        get_min_value(&self.current_state, &self.return_state, &self.return_type)
     }
 }
 
 fn get_min_value(
     field_map: &BTreeMap<Field, u64>,
     return_map: &BTreeMap<Field, Vec<Field>>,
-    return_type: Option<FieldType>,
+    return_type: &Option<FieldType>,
 ) -> Result<Field, PipelineError> {
     if field_map.is_empty() {
         Ok(Field::Null)
@@ -2,6 +2,7 @@ pub mod aggregator;
 pub mod avg;
 pub mod count;
 pub mod factory;
+pub mod javascript;
 pub mod max;
 pub mod max_value;
 pub mod min;
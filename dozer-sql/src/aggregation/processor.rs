@@ -33,8 +33,8 @@ impl AggregationState {
     pub fn new(types: &[AggregatorType], ret_types: &[FieldType]) -> Self {
         let mut states: Vec<AggregatorEnum> = Vec::new();
         for (idx, typ) in types.iter().enumerate() {
-            let mut aggr = get_aggregator_from_aggregator_type(*typ);
-            aggr.init(ret_types[idx]);
+            let mut aggr = get_aggregator_from_aggregator_type(typ);
+            aggr.init(ret_types[idx].clone());
             states.push(aggr);
         }
 
@@ -44,6 +44,17 @@ impl AggregationState {
             values: None,
         }
     }
+
+    /// Attaches the functions of aggregators that only checkpoint their state.
+    fn attach(&mut self, types: &[AggregatorType]) {
+        for (state, typ) in self.states.iter_mut().zip(types) {
+            if let (AggregatorEnum::JavaScriptAggregator(aggr), AggregatorType::JavaScript(udaf)) =
+                (state, typ)
+            {
+                aggr.attach(udaf.clone());
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -101,7 +112,11 @@ impl AggregationProcessor {
         let states = if let Some(data) = checkpoint_data {
             let mut cursor = Cursor::new(&data);
             let data = deserialize_vec_u8(&mut cursor)?;
-            let state = bincode::decode_from_slice(data, bincode::config::legacy())?.0;
+            let mut state: HashMap<RecordKey, AggregationState> =
+                bincode::decode_from_slice(data, bincode::config::legacy())?.0;
+            for state in state.values_mut() {
+                state.attach(&aggr_types);
+            }
             for dimension in &mut dimensions {
                 dimension.deserialize_state(&mut cursor)?;
             }
@@ -54,18 +54,18 @@ impl Aggregator for SumAggregator {
     }
 
     fn delete(&mut self, old: &[Field]) -> Result<Field, PipelineError> {
-        get_sum(old, &mut self.current_state, self.return_type, true)
+        get_sum(old, &mut self.current_state, &self.return_type, true)
     }
 
     fn insert(&mut self, new: &[Field]) -> Result<Field, PipelineError> {
-        get_sum(new, &mut self.current_state, self.return_type, false)
+        get_sum(new, &mut self.current_state, &self.return_type, false)
     }
 }
 
 pub fn get_sum(
     fields: &[Field],
     current_state: &mut SumState,
-    return_type: Option<FieldType>,
+    return_type: &Option<FieldType>,
     decr: bool,
 ) -> Result<Field, PipelineError> {
     match return_type {
@@ -178,6 +178,8 @@ pub fn get_sum(
             | FieldType::Timestamp
             | FieldType::Binary
             | FieldType::Json
+            | FieldType::List(_)
+            | FieldType::Struct(_)
             | FieldType::Point => Err(PipelineError::InvalidReturnType(format!(
                 "Not supported return type {typ} for {Sum}"
             ))),
@@ -0,0 +1,117 @@
+use crate::aggregation::aggregator::{
+    get_aggregator_from_aggregator_type, Aggregator, AggregatorEnum, AggregatorType,
+};
+use crate::aggregation::tests::aggregation_tests_utils::{
+    delete_field, init_input_schema, init_processor_with_udfs, insert_exp, insert_field,
+    update_exp, FIELD_100_FLOAT, FIELD_200_FLOAT, FIELD_50_FLOAT, ITALY,
+};
+use crate::errors::PipelineError;
+use crate::output;
+use crate::tests::utils::create_test_runtime;
+use dozer_core::DEFAULT_PORT_HANDLE;
+use dozer_sql_expression::error::Error;
+use dozer_sql_expression::execution::Expression;
+use dozer_sql_expression::javascript::Udaf;
+
+use dozer_types::models::udf_config::{JavaScriptConfig, UdfConfig, UdfType};
+use dozer_types::ordered_float::OrderedFloat;
+use dozer_types::types::{Field, FieldType};
+use std::collections::HashMap;
+
+const MODULE: &str = "src/aggregation/tests/sum_product.js";
+
+fn udfs() -> [UdfConfig; 1] {
+    [UdfConfig {
+        name: "sum_product".to_string(),
+        config: UdfType::JavaScriptAggregate(JavaScriptConfig {
+            module: MODULE.to_string(),
+            arg_types: Some(vec!["float".to_string(), "int".to_string()]),
+            return_type: Some("float".to_string()),
+        }),
+    }]
+}
+
+#[test]
+fn test_javascript_aggregation() {
+    let schema = init_input_schema(FieldType::Float, "SUM_PRODUCT");
+    let udfs = udfs();
+    let mut processor = init_processor_with_udfs(
+        "SELECT Country, sum_product(Salary, 2) FROM Users GROUP BY Country",
+        HashMap::from([(DEFAULT_PORT_HANDLE, schema)]),
+        &udfs,
+    )
+    .unwrap();
+
+    let out = output!(processor, insert_field(ITALY, FIELD_50_FLOAT));
+    assert_eq!(out, vec![insert_exp(ITALY, FIELD_100_FLOAT)]);
+
+    let out = output!(processor, insert_field(ITALY, FIELD_50_FLOAT));
+    assert_eq!(
+        out,
+        vec![update_exp(ITALY, ITALY, FIELD_100_FLOAT, FIELD_200_FLOAT)]
+    );
+
+    let out = output!(processor, delete_field(ITALY, FIELD_50_FLOAT));
+    assert_eq!(
+        out,
+        vec![update_exp(ITALY, ITALY, FIELD_200_FLOAT, FIELD_100_FLOAT)]
+    );
+}
+
+#[test]
+fn test_javascript_aggregation_validates_args() {
+    let schema = init_input_schema(FieldType::Float, "SUM_PRODUCT");
+    for sql in [
+        "SELECT Country, sum_product(Salary) FROM Users GROUP BY Country",
+        "SELECT Country, sum_product(Salary, Country) FROM Users GROUP BY Country",
+    ] {
+        assert!(matches!(
+            init_processor_with_udfs(
+                sql,
+                HashMap::from([(DEFAULT_PORT_HANDLE, schema.clone())]),
+                &udfs(),
+            ),
+            Err(PipelineError::Expression(
+                Error::InvalidNumberOfArguments { .. } | Error::InvalidFunctionArgumentType { .. }
+            ))
+        ));
+    }
+}
+
+#[test]
+fn test_javascript_aggregator_checkpoint() {
+    let runtime = create_test_runtime();
+    let udaf = runtime
+        .block_on(Udaf::new(
+            runtime.clone(),
+            "sum_product".to_string(),
+            MODULE.to_string(),
+            vec![
+                Expression::Column { index: 0 },
+                Expression::Column { index: 1 },
+            ],
+            FieldType::Float,
+        ))
+        .unwrap();
+    let typ = AggregatorType::JavaScript(udaf);
+
+    let mut aggregator = get_aggregator_from_aggregator_type(&typ);
+    aggregator
+        .insert(&[Field::Float(OrderedFloat(3.0)), Field::Int(4)])
+        .unwrap();
+
+    let config = bincode::config::legacy();
+    let bytes = bincode::encode_to_vec(&aggregator, config).unwrap();
+    let mut aggregator: AggregatorEnum = bincode::decode_from_slice(&bytes, config).unwrap().0;
+    let (AggregatorEnum::JavaScriptAggregator(inner), AggregatorType::JavaScript(udaf)) =
+        (&mut aggregator, &typ)
+    else {
+        panic!("expected JavaScript aggregator");
+    };
+    inner.attach(udaf.clone());
+
+    assert_eq!(
+        aggregator.insert(&[Field::Int(1), Field::Int(2)]).unwrap(),
+        Field::Float(OrderedFloat(14.0))
+    );
+}
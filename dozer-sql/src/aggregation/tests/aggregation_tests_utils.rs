@@ -1,4 +1,5 @@
 use dozer_core::{node::PortHandle, DEFAULT_PORT_HANDLE};
+use dozer_types::models::udf_config::UdfConfig;
 use dozer_types::types::{
     DozerDuration, Field, FieldDefinition, FieldType, Operation, Record, Schema, SourceDefinition,
     TimeUnit, DATE_FORMAT,
@@ -18,18 +19,24 @@ use std::ops::Div;
 pub(crate) fn init_processor(
     sql: &str,
     input_schemas: HashMap<PortHandle, Schema>,
+) -> Result<AggregationProcessor, PipelineError> {
+    init_processor_with_udfs(sql, input_schemas, &[])
+}
+
+pub(crate) fn init_processor_with_udfs(
+    sql: &str,
+    input_schemas: HashMap<PortHandle, Schema>,
+    udfs: &[UdfConfig],
 ) -> Result<AggregationProcessor, PipelineError> {
     let input_schema = input_schemas
         .get(&DEFAULT_PORT_HANDLE)
         .unwrap_or_else(|| panic!("Error getting Input Schema"));
 
     let runtime = create_test_runtime();
-    let mut projection_planner = CommonPlanner::new(input_schema.clone(), &[], runtime.clone());
+    let mut projection_planner = CommonPlanner::new(input_schema.clone(), udfs, runtime.clone());
     let statement = get_select(sql).unwrap();
 
-    runtime
-        .block_on(projection_planner.plan(*statement))
-        .unwrap();
+    runtime.block_on(projection_planner.plan(*statement))?;
 
     let processor = AggregationProcessor::new(
         "".to_string(),
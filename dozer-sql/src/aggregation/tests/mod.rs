@@ -5,6 +5,8 @@ mod aggregation_count_tests;
 #[cfg(test)]
 mod aggregation_having_tests;
 #[cfg(test)]
+mod aggregation_javascript_tests;
+#[cfg(test)]
 mod aggregation_max_tests;
 #[cfg(test)]
 mod aggregation_max_value_tests;
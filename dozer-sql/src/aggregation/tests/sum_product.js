@@ -0,0 +1,15 @@
+export function init() {
+    return { sum: 0 };
+}
+
+export function accumulate(state, a, b) {
+    return { sum: state.sum + a * b };
+}
+
+export function retract(state, a, b) {
+    return { sum: state.sum - a * b };
+}
+
+export function result(state) {
+    return state.sum;
+}
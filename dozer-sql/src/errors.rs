@@ -315,4 +315,10 @@ pub enum TableOperatorError {
 
     #[error("TTL input must evaluate to timestamp, but it evaluates to {0}")]
     InvalidTtlInputType(Field),
+
+    #[error("UNNEST input must evaluate to a list, but it evaluates to {0}")]
+    InvalidUnnestInputType(Field),
+
+    #[error("Invalid element type '{0}' specified in the Table Operator {1}")]
+    InvalidElementType(String, String),
 }
@@ -8,6 +8,7 @@ mod execution;
 mod expression_builder_test;
 mod in_list;
 mod json_functions;
+mod nested;
 mod number;
 mod point;
 mod string;
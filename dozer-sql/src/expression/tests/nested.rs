@@ -0,0 +1,137 @@
+use crate::expression::tests::test_common::run_fct;
+use dozer_types::json_types::json;
+use dozer_types::types::{Field, FieldDefinition, FieldType, Schema, SourceDefinition};
+
+fn nested_schema() -> Schema {
+    Schema::default()
+        .field(
+            FieldDefinition::new(
+                String::from("items"),
+                FieldType::List(Box::new(FieldType::Json)),
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("customer"),
+                FieldType::Struct(vec![
+                    ("name".to_string(), FieldType::String),
+                    ("age".to_string(), FieldType::UInt),
+                ]),
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .field(
+            FieldDefinition::new(
+                String::from("scores"),
+                FieldType::List(Box::new(FieldType::Int)),
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .clone()
+}
+
+fn nested_values() -> Vec<Field> {
+    vec![
+        Field::List(vec![
+            Field::Int(10),
+            Field::List(vec![Field::String("a".to_string())]),
+        ]),
+        Field::Struct(vec![
+            ("name".to_string(), Field::String("Alice".to_string())),
+            ("age".to_string(), Field::UInt(30)),
+        ]),
+        Field::List(vec![Field::Int(1), Field::Int(2)]),
+    ]
+}
+
+#[test]
+fn test_array_constructor() {
+    let f = run_fct(
+        "SELECT ARRAY[1, 'a', NULL] FROM users",
+        nested_schema(),
+        nested_values(),
+    );
+    assert_eq!(
+        f,
+        Field::List(vec![
+            Field::Int(1),
+            Field::String("a".to_string()),
+            Field::Null
+        ])
+    );
+}
+
+#[test]
+fn test_list_element_access() {
+    let f = run_fct(
+        "SELECT items[1] FROM users",
+        nested_schema(),
+        nested_values(),
+    );
+    assert_eq!(f, Field::Json(json!(10)));
+
+    let f = run_fct(
+        "SELECT items[2][1] FROM users",
+        nested_schema(),
+        nested_values(),
+    );
+    assert_eq!(f, Field::Json(json!("a")));
+
+    let f = run_fct(
+        "SELECT items[3] FROM users",
+        nested_schema(),
+        nested_values(),
+    );
+    assert_eq!(f, Field::Null);
+
+    let f = run_fct(
+        "SELECT scores[2] FROM users",
+        nested_schema(),
+        nested_values(),
+    );
+    assert_eq!(f, Field::Int(2));
+}
+
+#[test]
+fn test_struct_element_access() {
+    let f = run_fct(
+        "SELECT customer['name'] FROM users",
+        nested_schema(),
+        nested_values(),
+    );
+    assert_eq!(f, Field::String("Alice".to_string()));
+
+    let f = run_fct(
+        "SELECT ELEMENT_AT(customer, 'missing') FROM users",
+        nested_schema(),
+        nested_values(),
+    );
+    assert_eq!(f, Field::Null);
+}
+
+#[test]
+fn test_cardinality() {
+    let f = run_fct(
+        "SELECT CARDINALITY(items) FROM users",
+        nested_schema(),
+        nested_values(),
+    );
+    assert_eq!(f, Field::UInt(2));
+}
+
+#[test]
+fn test_cast_element() {
+    let f = run_fct(
+        "SELECT CAST(items[1] AS INT) FROM users",
+        nested_schema(),
+        nested_values(),
+    );
+    assert_eq!(f, Field::Int(10));
+}
@@ -12,7 +12,7 @@ use crate::{
     builder::{get_from_source, QueryContext},
     errors::PipelineError,
     product::table::factory::TableProcessorFactory,
-    table_operator::factory::{get_source_name, TableOperatorProcessorFactory},
+    table_operator::factory::{get_source_name, is_unnest_operator, TableOperatorProcessorFactory},
     window::factory::WindowProcessorFactory,
 };
 
@@ -145,7 +145,7 @@ pub fn insert_table_operator_processor_to_pipeline(
 
     let mut input_nodes = vec![];
 
-    if operator.name.to_uppercase() == "TTL" {
+    if operator.name.to_uppercase() == "TTL" || is_unnest_operator(&operator.name) {
         let mut entry_points = vec![];
 
         let processor_name = generate_name("TOP", operator, query_context);
@@ -359,12 +359,40 @@ pub fn is_table_operator(
         }
         TableFactor::Derived { .. } => Ok(None),
         TableFactor::TableFunction { .. } => Err(PipelineError::UnsupportedTableFunction),
-        TableFactor::UNNEST { .. } => Err(PipelineError::UnsupportedUnnest),
+        TableFactor::UNNEST { array_expr, .. } => unnest_operator_descriptor(array_expr),
         TableFactor::NestedJoin { .. } => Err(PipelineError::UnsupportedNestedJoin),
         TableFactor::Pivot { .. } => Err(PipelineError::UnsupportedPivot),
     }
 }
 
+/// `UNNEST(source.column)` reads the list column of a source, and is planned
+/// as `UNNEST(source, column)`.
+fn unnest_operator_descriptor(
+    array_expr: &Expr,
+) -> Result<Option<TableOperatorDescriptor>, PipelineError> {
+    let Expr::CompoundIdentifier(ident) = array_expr else {
+        return Err(PipelineError::UnsupportedUnnest);
+    };
+    let Some((column, source)) = ident.split_last() else {
+        return Err(PipelineError::UnsupportedUnnest);
+    };
+    let source = if source.len() == 1 {
+        Expr::Identifier(source[0].clone())
+    } else {
+        Expr::CompoundIdentifier(source.to_vec())
+    };
+
+    Ok(Some(TableOperatorDescriptor {
+        name: "UNNEST".to_string(),
+        args: vec![
+            TableOperatorArg::Argument(FunctionArg::Unnamed(FunctionArgExpr::Expr(source))),
+            TableOperatorArg::Argument(FunctionArg::Unnamed(FunctionArgExpr::Expr(
+                Expr::Identifier(column.clone()),
+            ))),
+        ],
+    }))
+}
+
 pub fn is_an_entry_point(name: &str, query_context: &QueryContext, pipeline_idx: usize) -> bool {
     if query_context
         .pipeline_map
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use dozer_core::{
-    node::{PortHandle, Processor, ProcessorFactory},
+    node::{Partitioner, PortHandle, Processor, ProcessorFactory},
     DEFAULT_PORT_HANDLE,
 };
 use dozer_sql_expression::{
@@ -15,7 +15,7 @@ use dozer_sql_expression::{
 use dozer_types::{
     errors::internal::BoxedError,
     tonic::async_trait,
-    types::{FieldDefinition, Schema},
+    types::{Field, FieldDefinition, Record, Schema},
 };
 
 use crate::errors::JoinError;
@@ -57,15 +57,120 @@ impl JoinProcessorFactory {
     }
 }
 
+impl JoinProcessorFactory {
+    fn get_input_schemas(
+        &self,
+        input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<(Schema, Schema), PipelineError> {
+        let mut left_schema = input_schemas
+            .get(&LEFT_JOIN_PORT)
+            .ok_or(PipelineError::InternalError(
+                "Invalid Product".to_string().into(),
+            ))?
+            .clone();
+        if let Some(left_table_name) = &self.left {
+            left_schema = extend_schema_source_def(&left_schema, left_table_name);
+        }
+
+        let mut right_schema = input_schemas
+            .get(&RIGHT_JOIN_PORT)
+            .ok_or(PipelineError::InternalError(
+                "Invalid Product".to_string().into(),
+            ))?
+            .clone();
+        if let Some(right_table_name) = &self.right {
+            right_schema = extend_schema_source_def(&right_schema, right_table_name);
+        }
+
+        Ok((left_schema, right_schema))
+    }
+
+    fn get_join_key_indexes(
+        &self,
+        left_schema: &Schema,
+        right_schema: &Schema,
+    ) -> Result<(Vec<usize>, Vec<usize>), PipelineError> {
+        let join_constraint = match &self.join_operator {
+            SqlJoinOperator::Inner(constraint)
+            | SqlJoinOperator::LeftOuter(constraint)
+            | SqlJoinOperator::RightOuter(constraint) => constraint,
+            _ => return Err(PipelineError::JoinError(JoinError::UnsupportedJoinType)),
+        };
+
+        let expression = match join_constraint {
+            SqlJoinConstraint::On(expression) => expression,
+            _ => {
+                return Err(PipelineError::JoinError(
+                    JoinError::UnsupportedJoinConstraintType,
+                ))
+            }
+        };
+
+        Ok(parse_join_constraint(
+            expression,
+            left_schema,
+            right_schema,
+        )?)
+    }
+}
+
+/// Partitions both sides of a join by their join key, so matching records meet in the same instance.
+#[derive(Debug)]
+struct JoinPartitioner {
+    left_join_key_indexes: Vec<usize>,
+    right_join_key_indexes: Vec<usize>,
+}
+
+impl Partitioner for JoinPartitioner {
+    fn partition_key(
+        &mut self,
+        port: PortHandle,
+        record: &Record,
+    ) -> Result<Vec<Field>, BoxedError> {
+        let key_indexes = match port {
+            LEFT_JOIN_PORT => &self.left_join_key_indexes,
+            RIGHT_JOIN_PORT => &self.right_join_key_indexes,
+            _ => return Err(PipelineError::InvalidPortHandle(port).into()),
+        };
+        Ok(key_indexes
+            .iter()
+            .map(|index| record.values[*index].clone())
+            .collect())
+    }
+}
+
 #[async_trait]
 impl ProcessorFactory for JoinProcessorFactory {
     fn id(&self) -> String {
         self.id.clone()
     }
 
+    fn definition(&self) -> Option<String> {
+        Some(format!(
+            "{:?} {:?} {:?} (probabilistic optimizations: {})",
+            self.left, self.join_operator, self.right, self.enable_probabilistic_optimizations
+        ))
+    }
+
     fn type_name(&self) -> String {
         "Join".to_string()
     }
+
+    async fn partitioner(
+        &self,
+        input_schemas: &HashMap<PortHandle, Schema>,
+    ) -> Result<Option<Box<dyn Partitioner>>, BoxedError> {
+        let (left_schema, right_schema) = self.get_input_schemas(input_schemas)?;
+        let (left_join_key_indexes, right_join_key_indexes) =
+            self.get_join_key_indexes(&left_schema, &right_schema)?;
+        if left_join_key_indexes.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Box::new(JoinPartitioner {
+            left_join_key_indexes,
+            right_join_key_indexes,
+        })))
+    }
     fn get_input_ports(&self) -> Vec<PortHandle> {
         vec![LEFT_JOIN_PORT, RIGHT_JOIN_PORT]
     }
@@ -79,27 +184,7 @@ impl ProcessorFactory for JoinProcessorFactory {
         _output_port: &PortHandle,
         input_schemas: &HashMap<PortHandle, Schema>,
     ) -> Result<Schema, BoxedError> {
-        let mut left_schema = input_schemas
-            .get(&LEFT_JOIN_PORT)
-            .ok_or(PipelineError::InternalError(
-                "Invalid Product".to_string().into(),
-            ))?
-            .clone();
-
-        if let Some(left_table_name) = &self.left {
-            left_schema = extend_schema_source_def(&left_schema, left_table_name);
-        }
-
-        let mut right_schema = input_schemas
-            .get(&RIGHT_JOIN_PORT)
-            .ok_or(PipelineError::InternalError(
-                "Invalid Product".to_string().into(),
-            ))?
-            .clone();
-
-        if let Some(right_table_name) = &self.right {
-            right_schema = extend_schema_source_def(&right_schema, right_table_name);
-        }
+        let (left_schema, right_schema) = self.get_input_schemas(input_schemas)?;
 
         let output_schema = append_schema(&left_schema, &right_schema);
 
@@ -112,44 +197,16 @@ impl ProcessorFactory for JoinProcessorFactory {
         _output_schemas: HashMap<PortHandle, dozer_types::types::Schema>,
         checkpoint_data: Option<Vec<u8>>,
     ) -> Result<Box<dyn Processor>, BoxedError> {
-        let (join_type, join_constraint) = match &self.join_operator {
-            SqlJoinOperator::Inner(constraint) => (JoinType::Inner, constraint),
-            SqlJoinOperator::LeftOuter(constraint) => (JoinType::LeftOuter, constraint),
-            SqlJoinOperator::RightOuter(constraint) => (JoinType::RightOuter, constraint),
+        let join_type = match &self.join_operator {
+            SqlJoinOperator::Inner(_) => JoinType::Inner,
+            SqlJoinOperator::LeftOuter(_) => JoinType::LeftOuter,
+            SqlJoinOperator::RightOuter(_) => JoinType::RightOuter,
             _ => return Err(PipelineError::JoinError(JoinError::UnsupportedJoinType).into()),
         };
 
-        let expression = match join_constraint {
-            SqlJoinConstraint::On(expression) => expression,
-            _ => {
-                return Err(
-                    PipelineError::JoinError(JoinError::UnsupportedJoinConstraintType).into(),
-                )
-            }
-        };
-
-        let mut left_schema = input_schemas
-            .get(&LEFT_JOIN_PORT)
-            .ok_or(PipelineError::InternalError(
-                "Invalid Product".to_string().into(),
-            ))?
-            .clone();
-        if let Some(left_table_name) = &self.left {
-            left_schema = extend_schema_source_def(&left_schema, left_table_name);
-        }
-
-        let mut right_schema = input_schemas
-            .get(&RIGHT_JOIN_PORT)
-            .ok_or(PipelineError::InternalError(
-                "Invalid Product".to_string().into(),
-            ))?
-            .clone();
-        if let Some(right_table_name) = &self.right {
-            right_schema = extend_schema_source_def(&right_schema, right_table_name);
-        }
-
+        let (left_schema, right_schema) = self.get_input_schemas(&input_schemas)?;
         let (left_join_key_indexes, right_join_key_indexes) =
-            parse_join_constraint(expression, &left_schema, &right_schema)?;
+            self.get_join_key_indexes(&left_schema, &right_schema)?;
 
         let join_operator = JoinOperator::new(
             join_type,
@@ -43,6 +43,13 @@ impl ProcessorFactory for SetProcessorFactory {
         self.id.clone()
     }
 
+    fn definition(&self) -> Option<String> {
+        Some(format!(
+            "{} (probabilistic optimizations: {})",
+            self.set_quantifier, self.enable_probabilistic_optimizations
+        ))
+    }
+
     fn type_name(&self) -> String {
         "Set".to_string()
     }
@@ -103,7 +110,7 @@ fn validate_set_operation_input_schemas(
         }
         output_fields.push(FieldDefinition::new(
             left.name.clone(),
-            left.typ,
+            left.typ.clone(),
             left.nullable,
             SourceDefinition::Dynamic,
         ));
@@ -33,6 +33,10 @@ impl ProcessorFactory for TableProcessorFactory {
         self.id.clone()
     }
 
+    fn definition(&self) -> Option<String> {
+        Some(self.relation.to_string())
+    }
+
     fn type_name(&self) -> String {
         "Table".to_string()
     }
@@ -92,8 +96,12 @@ pub fn get_name_or_alias(relation: &TableFactor) -> Result<NameOrAlias, Pipeline
         TableFactor::TableFunction { .. } => Err(PipelineError::ProductError(
             ProductError::UnsupportedTableFunction,
         )),
-        TableFactor::UNNEST { .. } => {
-            Err(PipelineError::ProductError(ProductError::UnsupportedUnnest))
+        TableFactor::UNNEST { alias, .. } => {
+            if let Some(table_alias) = alias {
+                let alias = table_alias.name.value.clone();
+                return Ok(NameOrAlias("dozer_unnest".to_string(), Some(alias)));
+            }
+            Ok(NameOrAlias("dozer_unnest".to_string(), None))
         }
         TableFactor::NestedJoin { alias, .. } => {
             if let Some(table_alias) = alias {
@@ -54,6 +54,15 @@ impl ProcessorFactory for ProjectionProcessorFactory {
         "Projection".to_string()
     }
 
+    fn definition(&self) -> Option<String> {
+        let select = self
+            .select
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        Some(select.join(", "))
+    }
+
     fn get_input_ports(&self) -> Vec<PortHandle> {
         vec![DEFAULT_PORT_HANDLE]
     }
@@ -59,6 +59,27 @@ impl ProjectionProcessor {
         Ok(output_record)
     }
 
+    /// Evaluates each expression once for the whole batch, so UDFs can process it in one call.
+    fn batch_insert(&mut self, records: Vec<Record>) -> Result<Vec<Record>, PipelineError> {
+        let mut output_records = records
+            .iter()
+            .map(|record| {
+                let mut output_record = Record::new(Vec::with_capacity(self.expressions.len()));
+                output_record.set_lifetime(record.lifetime.to_owned());
+                output_record
+            })
+            .collect::<Vec<_>>();
+
+        for expr in &mut self.expressions {
+            let results = expr.evaluate_batch(&records, &self.input_schema)?;
+            for (output_record, result) in output_records.iter_mut().zip(results) {
+                output_record.values.push(result);
+            }
+        }
+
+        Ok(output_records)
+    }
+
     fn update(&mut self, old: &Record, new: &Record) -> Result<Operation, PipelineError> {
         let mut old_results = vec![];
         let mut new_results = vec![];
@@ -91,13 +112,9 @@ impl Processor for ProjectionProcessor {
                 new: self.insert(new)?,
             },
             Operation::Update { ref old, ref new } => self.update(old, new)?,
-            Operation::BatchInsert { new } => {
-                let records = new
-                    .iter()
-                    .map(|record| self.insert(record))
-                    .collect::<Result<Vec<_>, _>>()?;
-                Operation::BatchInsert { new: records }
-            }
+            Operation::BatchInsert { new } => Operation::BatchInsert {
+                new: self.batch_insert(new)?,
+            },
         };
         fw.send(TableOperation {
             id: op.id,
@@ -43,6 +43,9 @@ impl ProcessorFactory for SelectionProcessorFactory {
     fn id(&self) -> String {
         self.id.clone()
     }
+    fn definition(&self) -> Option<String> {
+        Some(self.statement.to_string())
+    }
     fn type_name(&self) -> String {
         "Selection".to_string()
     }
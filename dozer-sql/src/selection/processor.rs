@@ -36,6 +36,18 @@ impl SelectionProcessor {
     fn filter(&mut self, record: &Record) -> Result<bool, PipelineError> {
         Ok(self.expression.evaluate(record, &self.input_schema)? == Field::Boolean(true))
     }
+
+    /// Keeps the records fulfilling the condition, evaluating it once for the whole batch.
+    fn filter_batch(&mut self, records: Vec<Record>) -> Result<Vec<Record>, PipelineError> {
+        let results = self
+            .expression
+            .evaluate_batch(&records, &self.input_schema)?;
+        Ok(records
+            .into_iter()
+            .zip(results)
+            .filter_map(|(record, result)| (result == Field::Boolean(true)).then_some(record))
+            .collect())
+    }
 }
 
 impl Processor for SelectionProcessor {
@@ -95,14 +107,7 @@ impl Processor for SelectionProcessor {
                 }
             }
             Operation::BatchInsert { new } => {
-                let records = new
-                    .into_iter()
-                    .filter_map(|record| {
-                        self.filter(&record)
-                            .map(|fulfilled| if fulfilled { Some(record) } else { None })
-                            .transpose()
-                    })
-                    .collect::<Result<Vec<_>, _>>()?;
+                let records = self.filter_batch(new)?;
                 if !records.is_empty() {
                     fw.send(TableOperation {
                         id: op.id,
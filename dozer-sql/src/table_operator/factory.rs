@@ -9,7 +9,10 @@ use dozer_sql_expression::{
     execution::Expression,
     sqlparser::ast::{Expr, FunctionArg, FunctionArgExpr, Value},
 };
-use dozer_types::{errors::internal::BoxedError, types::Schema};
+use dozer_types::{
+    errors::internal::BoxedError,
+    types::{FieldType, Schema},
+};
 use dozer_types::{models::udf_config::UdfConfig, tonic::async_trait};
 use tokio::runtime::Runtime;
 
@@ -22,6 +25,7 @@ use super::{
     lifetime::LifetimeTableOperator,
     operator::{TableOperator, TableOperatorType},
     processor::TableOperatorProcessor,
+    unnest::UnnestTableOperator,
 };
 
 const _SOURCE_TABLE_ARGUMENT: usize = 0;
@@ -58,6 +62,10 @@ impl ProcessorFactory for TableOperatorProcessorFactory {
         self.id.clone()
     }
 
+    fn definition(&self) -> Option<String> {
+        Some(format!("{:?}", self.table))
+    }
+
     fn type_name(&self) -> String {
         self.name.clone()
     }
@@ -142,6 +150,10 @@ pub(crate) async fn operator_from_descriptor(
     if &descriptor.name.to_uppercase() == "TTL" {
         let operator = lifetime_from_descriptor(descriptor, schema, udfs, runtime).await?;
 
+        Ok(Some(operator.into()))
+    } else if is_unnest_operator(&descriptor.name) {
+        let operator = unnest_from_descriptor(descriptor, schema, udfs, runtime).await?;
+
         Ok(Some(operator.into()))
     } else {
         Err(PipelineError::InternalError(descriptor.name.clone().into()))
@@ -201,6 +213,78 @@ async fn lifetime_from_descriptor(
     Ok(operator)
 }
 
+pub(crate) fn is_unnest_operator(name: &str) -> bool {
+    matches!(name.to_uppercase().as_str(), "UNNEST" | "FLATTEN")
+}
+
+async fn unnest_from_descriptor(
+    descriptor: &TableOperatorDescriptor,
+    schema: &Schema,
+    udfs: &[UdfConfig],
+    runtime: Arc<Runtime>,
+) -> Result<UnnestTableOperator, TableOperatorError> {
+    let table_expression_arg =
+        descriptor
+            .args
+            .get(1)
+            .ok_or(TableOperatorError::MissingArgument(
+                descriptor.name.to_owned(),
+            ))?;
+
+    let expression_arg = if let TableOperatorArg::Argument(argument) = table_expression_arg {
+        argument
+    } else {
+        return Err(TableOperatorError::InvalidReference(
+            descriptor.name.to_owned(),
+            format!("{:?}", table_expression_arg),
+        ));
+    };
+
+    let expression = get_expression(
+        descriptor.name.to_owned(),
+        expression_arg,
+        schema,
+        udfs,
+        runtime,
+    )
+    .await?;
+
+    let element_type = match descriptor.args.get(2) {
+        Some(TableOperatorArg::Argument(argument)) => {
+            Some(get_element_type(descriptor.name.to_owned(), argument)?)
+        }
+        Some(other) => {
+            return Err(TableOperatorError::InvalidElementType(
+                format!("{:?}", other),
+                descriptor.name.to_owned(),
+            ))
+        }
+        None => None,
+    };
+
+    Ok(UnnestTableOperator::new(
+        descriptor.name.to_uppercase(),
+        expression,
+        element_type,
+    ))
+}
+
+fn get_element_type(
+    function_name: String,
+    type_arg: &FunctionArg,
+) -> Result<FieldType, TableOperatorError> {
+    match type_arg {
+        FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Value(
+            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s),
+        ))) => FieldType::try_from(s.as_str())
+            .map_err(|_| TableOperatorError::InvalidElementType(s.to_owned(), function_name)),
+        _ => Err(TableOperatorError::InvalidElementType(
+            type_arg.to_string(),
+            function_name,
+        )),
+    }
+}
+
 fn get_interval(
     function_name: String,
     interval_arg: &FunctionArg,
@@ -3,3 +3,4 @@ mod lifetime;
 mod operator;
 mod processor;
 mod tests;
+mod unnest;
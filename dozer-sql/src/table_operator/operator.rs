@@ -1,4 +1,5 @@
 use crate::table_operator::lifetime::LifetimeTableOperator;
+use crate::table_operator::unnest::UnnestTableOperator;
 use dozer_types::types::{Record, Schema};
 use enum_dispatch::enum_dispatch;
 
@@ -19,4 +20,5 @@ pub trait TableOperator: Send + Sync {
 #[derive(Debug)]
 pub enum TableOperatorType {
     LifetimeTableOperator,
+    UnnestTableOperator,
 }
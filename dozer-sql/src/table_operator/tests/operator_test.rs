@@ -3,10 +3,13 @@ use std::time::Duration;
 use dozer_sql_expression::execution::Expression;
 use dozer_types::{
     chrono::DateTime,
+    json_types::json,
     types::{Field, FieldDefinition, FieldType, Lifetime, Record, Schema, SourceDefinition},
 };
 
-use crate::table_operator::{lifetime::LifetimeTableOperator, operator::TableOperator};
+use crate::table_operator::{
+    lifetime::LifetimeTableOperator, operator::TableOperator, unnest::UnnestTableOperator,
+};
 
 #[test]
 fn test_lifetime() {
@@ -59,3 +62,100 @@ fn test_lifetime() {
 
     assert_eq!(lifetime_record, &expected_record);
 }
+
+#[test]
+fn test_unnest() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                "id".to_string(),
+                FieldType::Int,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            true,
+        )
+        .field(
+            FieldDefinition::new(
+                "items".to_string(),
+                FieldType::List(Box::new(FieldType::Json)),
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned();
+
+    let mut table_operator =
+        UnnestTableOperator::new("UNNEST".to_string(), Expression::Column { index: 1 }, None);
+
+    let output_schema = table_operator.get_output_schema(&schema).unwrap();
+    assert_eq!(output_schema.fields.len(), 4);
+    assert_eq!(output_schema.fields[2].typ, FieldType::Json);
+    assert_eq!(output_schema.fields[3].typ, FieldType::UInt);
+    assert_eq!(output_schema.primary_index, vec![0, 3]);
+
+    let record = Record::new(vec![
+        Field::Int(1),
+        Field::List(vec![Field::String("a".to_string()), Field::Int(2)]),
+    ]);
+    let result = table_operator.execute(&record, &schema).unwrap();
+    assert_eq!(
+        result,
+        vec![
+            Record::new(vec![
+                Field::Int(1),
+                record.values[1].clone(),
+                Field::Json(json!("a")),
+                Field::UInt(0),
+            ]),
+            Record::new(vec![
+                Field::Int(1),
+                record.values[1].clone(),
+                Field::Json(json!(2)),
+                Field::UInt(1),
+            ]),
+        ]
+    );
+
+    let empty = Record::new(vec![Field::Int(2), Field::Null]);
+    assert!(table_operator.execute(&empty, &schema).unwrap().is_empty());
+}
+
+#[test]
+fn test_unnest_typed_elements() {
+    let schema = Schema::default()
+        .field(
+            FieldDefinition::new(
+                "items".to_string(),
+                FieldType::List(Box::new(FieldType::UInt)),
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        )
+        .to_owned();
+
+    let mut table_operator = UnnestTableOperator::new(
+        "FLATTEN".to_string(),
+        Expression::Column { index: 0 },
+        Some(FieldType::Int),
+    );
+
+    let record = Record::new(vec![Field::List(vec![Field::UInt(7), Field::Null])]);
+    let result = table_operator.execute(&record, &schema).unwrap();
+    assert_eq!(result[0].values[1], Field::Int(7));
+    assert_eq!(result[1].values[1], Field::Null);
+    assert!(table_operator
+        .get_output_schema(&schema)
+        .unwrap()
+        .primary_index
+        .is_empty());
+
+    let mut table_operator =
+        UnnestTableOperator::new("UNNEST".to_string(), Expression::Column { index: 0 }, None);
+    let output_schema = table_operator.get_output_schema(&schema).unwrap();
+    assert_eq!(output_schema.fields[1].typ, FieldType::UInt);
+    let result = table_operator.execute(&record, &schema).unwrap();
+    assert_eq!(result[0].values[1], Field::UInt(7));
+}
@@ -0,0 +1,118 @@
+use dozer_sql_expression::{cast::cast_field, execution::Expression};
+use dozer_types::{
+    json_types::field_to_json_value,
+    types::{Field, FieldDefinition, FieldType, Record, Schema, SourceDefinition},
+};
+
+use crate::errors::TableOperatorError;
+
+use super::operator::TableOperator;
+
+const UNNEST_VALUE_COLUMN: &str = "value";
+const UNNEST_INDEX_COLUMN: &str = "index";
+
+/// Emits one record per element of a list, appending the element and its
+/// 0-based position to the columns of the source record.
+#[derive(Debug)]
+pub struct UnnestTableOperator {
+    name: String,
+    expression: Expression,
+    /// Elements take the list's element type when no element type is declared.
+    element_type: Option<FieldType>,
+}
+
+impl UnnestTableOperator {
+    pub fn new(name: String, expression: Expression, element_type: Option<FieldType>) -> Self {
+        Self {
+            name,
+            expression,
+            element_type,
+        }
+    }
+
+    fn value_type(&self, schema: &Schema) -> Result<FieldType, TableOperatorError> {
+        if let Some(typ) = &self.element_type {
+            return Ok(typ.clone());
+        }
+        let list_type = self
+            .expression
+            .get_type(schema)
+            .map_err(|err| TableOperatorError::InternalError(Box::new(err)))?
+            .return_type;
+        Ok(match list_type {
+            FieldType::List(element) => *element,
+            _ => FieldType::Json,
+        })
+    }
+}
+
+fn convert_element(element: Field, typ: &FieldType) -> Result<Field, TableOperatorError> {
+    match (element, typ) {
+        (Field::Null, _) => Ok(Field::Null),
+        (element, FieldType::Json) => Ok(Field::Json(field_to_json_value(element))),
+        (element, typ) => cast_field(&element, typ)
+            .map_err(|err| TableOperatorError::InternalError(Box::new(err))),
+    }
+}
+
+impl TableOperator for UnnestTableOperator {
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn execute(
+        &mut self,
+        record: &Record,
+        schema: &Schema,
+    ) -> Result<Vec<Record>, TableOperatorError> {
+        let elements = match self
+            .expression
+            .evaluate(record, schema)
+            .map_err(|err| TableOperatorError::InternalError(Box::new(err)))?
+        {
+            Field::Null => vec![],
+            Field::List(elements) => elements,
+            other @ Field::Json(_) => other
+                .to_list()
+                .ok_or(TableOperatorError::InvalidUnnestInputType(other))?,
+            other => return Err(TableOperatorError::InvalidUnnestInputType(other)),
+        };
+
+        let value_type = self.value_type(schema)?;
+        let mut records = Vec::with_capacity(elements.len());
+        for (index, element) in elements.into_iter().enumerate() {
+            let mut values = Vec::with_capacity(record.values.len() + 2);
+            values.extend_from_slice(&record.values);
+            values.push(convert_element(element, &value_type)?);
+            values.push(Field::UInt(index as u64));
+            records.push(Record {
+                values,
+                lifetime: record.lifetime.clone(),
+            });
+        }
+        Ok(records)
+    }
+
+    fn get_output_schema(&self, schema: &Schema) -> Result<Schema, TableOperatorError> {
+        let mut output_schema = schema.clone();
+        output_schema.field(
+            FieldDefinition::new(
+                UNNEST_VALUE_COLUMN.to_string(),
+                self.value_type(schema)?,
+                true,
+                SourceDefinition::Dynamic,
+            ),
+            false,
+        );
+        output_schema.field(
+            FieldDefinition::new(
+                UNNEST_INDEX_COLUMN.to_string(),
+                FieldType::UInt,
+                false,
+                SourceDefinition::Dynamic,
+            ),
+            !schema.primary_index.is_empty(),
+        );
+        Ok(output_schema)
+    }
+}
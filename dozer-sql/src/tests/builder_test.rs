@@ -12,7 +12,7 @@ use dozer_types::chrono::DateTime;
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::log::debug;
 use dozer_types::models::ingestion_types::IngestionMessage;
-use dozer_types::node::OpIdentifier;
+use dozer_types::node::SourcePosition;
 use dozer_types::ordered_float::OrderedFloat;
 use dozer_types::tonic::async_trait;
 use dozer_types::types::{
@@ -112,7 +112,7 @@ impl Source for TestSource {
     async fn start(
         &mut self,
         sender: Sender<(PortHandle, IngestionMessage)>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         for _ in 0..10 {
             sender
@@ -131,6 +131,7 @@ impl Source for TestSource {
                             ]),
                         },
                         id: None,
+                        source_time: None,
                     },
                 ))
                 .await
@@ -200,7 +201,7 @@ impl Sink for TestSink {
     fn on_source_snapshotting_done(
         &mut self,
         _connection_name: String,
-        _id: Option<OpIdentifier>,
+        _id: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         Ok(())
     }
@@ -213,7 +214,7 @@ impl Sink for TestSink {
         Ok(None)
     }
 
-    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
         Ok(None)
     }
 }
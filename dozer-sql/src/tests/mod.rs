@@ -1,2 +1,3 @@
 mod builder_test;
+mod partition_test;
 pub mod utils;
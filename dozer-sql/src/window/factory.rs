@@ -31,6 +31,10 @@ impl ProcessorFactory for WindowProcessorFactory {
         self.id.clone()
     }
 
+    fn definition(&self) -> Option<String> {
+        Some(format!("{:?}", self.table))
+    }
+
     fn type_name(&self) -> String {
         "Window".to_string()
     }
@@ -204,7 +204,7 @@ fn get_record_from_json(data: String, schema: &Schema) -> Record {
     };
 
     for field_definition in schema.fields.iter() {
-        let field_type = field_definition.typ;
+        let field_type = &field_definition.typ;
         let field_name = &field_definition.name;
         let json_value = root.get(field_name).unwrap();
 
@@ -216,7 +216,7 @@ fn get_record_from_json(data: String, schema: &Schema) -> Record {
             | FieldType::String
             | FieldType::Text => json_value_to_field(
                 json_value.clone(),
-                field_definition.typ,
+                field_definition.typ.clone(),
                 field_definition.nullable,
             )
             .unwrap(),
@@ -19,7 +19,7 @@ use dozer_sql::builder::statement_to_pipeline;
 
 use dozer_types::errors::internal::BoxedError;
 use dozer_types::models::ingestion_types::IngestionMessage;
-use dozer_types::node::OpIdentifier;
+use dozer_types::node::SourcePosition;
 use dozer_types::types::{Operation, Record, Schema, SourceDefinition, TableOperation};
 use std::collections::HashMap;
 use std::future::pending;
@@ -129,7 +129,7 @@ impl Source for TestSource {
     async fn start(
         &mut self,
         sender: tokio::sync::mpsc::Sender<(PortHandle, IngestionMessage)>,
-        _last_checkpoint: Option<OpIdentifier>,
+        _last_checkpoint: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         while let Ok(Some((schema_name, op))) = self.receiver.recv() {
             let port = self.name_to_port.get(&schema_name).expect("port not found");
@@ -140,6 +140,7 @@ impl Source for TestSource {
                         table_index: 0,
                         op,
                         id: None,
+                        source_time: None,
                     },
                 ))
                 .await
@@ -271,7 +272,7 @@ impl Sink for TestSink {
     fn on_source_snapshotting_done(
         &mut self,
         _connection_name: String,
-        _id: Option<OpIdentifier>,
+        _id: Option<SourcePosition>,
     ) -> Result<(), BoxedError> {
         Ok(())
     }
@@ -284,7 +285,7 @@ impl Sink for TestSink {
         Ok(None)
     }
 
-    fn get_latest_op_id(&mut self) -> Result<Option<OpIdentifier>, BoxedError> {
+    fn get_latest_op_id(&mut self) -> Result<Option<SourcePosition>, BoxedError> {
         Ok(None)
     }
 }
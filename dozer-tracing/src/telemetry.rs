@@ -1,9 +1,11 @@
+use std::io::{self, Write};
 use std::time::Duration;
 
 use dozer_types::log::{debug, error};
 use dozer_types::models::telemetry::{
     default_sample_ratio, DozerTelemetryConfig, TelemetryConfig, TelemetryTraceConfig, XRayConfig,
 };
+use dozer_types::redact::redact;
 use dozer_types::tracing::{self, Metadata, Subscriber};
 use metrics_exporter_prometheus::PrometheusBuilder;
 use opentelemetry::global;
@@ -101,6 +103,7 @@ fn create_subscriber(
     subscriber
         .with(
             fmt::Layer::default()
+                .with_writer(|| RedactingWriter(io::stdout()))
                 .without_time()
                 .with_target(!stdout_is_tty)
                 .with_ansi(stdout_is_tty)
@@ -110,6 +113,25 @@ fn create_subscriber(
         .with(layers.1)
 }
 
+/// Redacts the secrets in every log line. The fmt layer writes each line with a single call.
+struct RedactingWriter<W>(W);
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match std::str::from_utf8(buf) {
+            Ok(text) => {
+                self.0.write_all(redact(text).as_bytes())?;
+                Ok(buf.len())
+            }
+            Err(_) => self.0.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
 fn get_xray_tracer<S>(
     app_name: &str,
     config: &XRayConfig,
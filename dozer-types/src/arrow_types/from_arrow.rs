@@ -6,6 +6,8 @@ use super::errors::FromArrowError::FieldTypeNotSupported;
 use super::errors::FromArrowError::TimeConversionError;
 use super::to_arrow;
 use crate::arrow_types::to_arrow::DOZER_SCHEMA_KEY;
+use crate::errors::types::DeserializationError;
+use crate::helper::json_value_to_nested_field;
 use crate::json_types::json_from_str;
 use crate::types::{
     Field as DozerField, FieldDefinition, FieldType, Record, Schema as DozerSchema, Schema,
@@ -162,6 +164,115 @@ fn make_json(column: &ArrayRef, row: usize) -> Result<DozerField, FromArrowError
     }
 }
 
+/// Reads a value that was written as JSON by `to_arrow`, inferring the types of nested values.
+fn make_nested_from_json(column: &ArrayRef, row: usize) -> Result<DozerField, FromArrowError> {
+    let array = column.as_any().downcast_ref::<array::StringArray>();
+
+    if let Some(r) = array {
+        if r.is_null(row) {
+            Ok(DozerField::Null)
+        } else {
+            let value = serde_json::from_str(r.value(row)).map_err(DeserializationError::Json)?;
+            Ok(json_value_to_nested_field(value))
+        }
+    } else {
+        Ok(DozerField::Null)
+    }
+}
+
+/// The schema of a nested column's values: a list's elements keep its name, a struct's fields their own.
+fn nested_schema(schema: &Schema, column_name: &str) -> Schema {
+    let fields = match schema
+        .fields
+        .iter()
+        .find(|fd| fd.name == column_name)
+        .map(|fd| &fd.typ)
+    {
+        Some(FieldType::List(element)) => vec![(column_name.to_string(), (**element).clone())],
+        Some(FieldType::Struct(fields)) => fields.clone(),
+        _ => vec![],
+    };
+
+    let mut nested = Schema::default();
+    for (name, typ) in fields {
+        nested.field(
+            FieldDefinition::new(name, typ, true, SourceDefinition::Dynamic),
+            false,
+        );
+    }
+    nested
+}
+
+fn map_nested_value(
+    column: &ArrayRef,
+    row: usize,
+    column_name: &str,
+    schema: &Schema,
+) -> Result<DozerField, FromArrowError> {
+    // Untyped values were written as JSON, and keep the types inferred from it.
+    let untyped = schema
+        .fields
+        .iter()
+        .any(|fd| fd.name == column_name && fd.typ == FieldType::Json);
+    if untyped && column.data_type() == &DataType::Utf8 {
+        make_nested_from_json(column, row)
+    } else {
+        map_value_to_dozer_field(column, row, column_name, schema)
+    }
+}
+
+fn make_list(
+    values: ArrayRef,
+    column_name: &str,
+    schema: &Schema,
+) -> Result<DozerField, FromArrowError> {
+    let schema = nested_schema(schema, column_name);
+    (0..values.len())
+        .map(|i| map_nested_value(&values, i, column_name, &schema))
+        .collect::<Result<_, _>>()
+        .map(DozerField::List)
+}
+
+fn make_struct(
+    column: &ArrayRef,
+    row: usize,
+    column_name: &str,
+    schema: &Schema,
+) -> Result<DozerField, FromArrowError> {
+    let Some(r) = column.as_any().downcast_ref::<array::StructArray>() else {
+        return Ok(DozerField::Null);
+    };
+    if r.is_null(row) {
+        return Ok(DozerField::Null);
+    }
+
+    let schema = nested_schema(schema, column_name);
+    r.column_names()
+        .into_iter()
+        .zip(r.columns())
+        .map(|(name, values)| {
+            map_nested_value(values, row, name, &schema).map(|value| (name.to_string(), value))
+        })
+        .collect::<Result<_, _>>()
+        .map(DozerField::Struct)
+}
+
+macro_rules! make_list {
+    ($array_type:ty, $column: ident, $row: ident, $column_name: ident, $schema: ident) => {{
+        let array = $column.as_any().downcast_ref::<$array_type>();
+
+        if let Some(r) = array {
+            if r.is_null($row) {
+                Ok(DozerField::Null)
+            } else {
+                make_list(r.value($row), $column_name, $schema)
+            }
+        } else {
+            Ok(DozerField::Null)
+        }
+    }};
+}
+
 pub fn map_schema_to_dozer(
     schema: &arrow::datatypes::Schema,
 ) -> Result<DozerSchema, FromArrowError> {
@@ -220,10 +331,21 @@ pub fn map_arrow_to_dozer_type(dt: &DataType) -> Result<FieldType, FromArrowErro
         }
         DataType::Utf8 => Ok(FieldType::String),
         DataType::LargeUtf8 => Ok(FieldType::Text),
-        // DataType::List(_) => {}
-        // DataType::FixedSizeList(_, _) => {}
-        // DataType::LargeList(_) => {}
-        // DataType::Struct(_) => {}
+        DataType::List(element)
+        | DataType::FixedSizeList(element, _)
+        | DataType::LargeList(element) => Ok(FieldType::List(Box::new(map_arrow_to_dozer_type(
+            element.data_type(),
+        )?))),
+        DataType::Struct(fields) => fields
+            .iter()
+            .map(|field| {
+                Ok::<_, FromArrowError>((
+                    field.name().clone(),
+                    map_arrow_to_dozer_type(field.data_type())?,
+                ))
+            })
+            .collect::<Result<_, _>>()
+            .map(FieldType::Struct),
         // DataType::Union(_, _, _) => {}
         // DataType::Dictionary(_, _) => {}
         // DataType::Decimal128(_, _) => {}
@@ -293,18 +415,28 @@ pub fn map_value_to_dozer_field(
         DataType::LargeBinary => make_binary!(array::LargeBinaryArray, column, row),
         DataType::Utf8 => {
             for fd in schema.fields.clone().into_iter() {
-                if fd.name == *column_name && fd.typ == FieldType::Json {
-                    return make_json(column, row);
+                if fd.name == *column_name {
+                    match fd.typ {
+                        FieldType::Json => return make_json(column, row),
+                        FieldType::List(_) | FieldType::Struct(_) => {
+                            return make_nested_from_json(column, row)
+                        }
+                        _ => {}
+                    }
                 }
             }
             Ok(make_from::<array::StringArray>(column, row))
         }
         DataType::LargeUtf8 => make_text!(array::LargeStringArray, column, row),
         // DataType::Interval(TimeUnit::) => make_from!(array::BooleanArray, x, x0),
-        // DataType::List(_) => {}
-        // DataType::FixedSizeList(_, _) => {}
-        // DataType::LargeList(_) => {}
-        // DataType::Struct(_) => {}
+        DataType::List(_) => make_list!(array::ListArray, column, row, column_name, schema),
+        DataType::FixedSizeList(_, _) => {
+            make_list!(array::FixedSizeListArray, column, row, column_name, schema)
+        }
+        DataType::LargeList(_) => {
+            make_list!(array::LargeListArray, column, row, column_name, schema)
+        }
+        DataType::Struct(_) => make_struct(column, row, column_name, schema),
         // DataType::Union(_, _, _) => {}
         // DataType::Dictionary(_, _) => {}
         // DataType::Decimal128(_, _) => {}
@@ -330,7 +462,7 @@ pub fn map_record_batch_to_dozer_records(
     let dozer_schema = map_schema_to_dozer(&batch_schema)?;
     let mut sort_fields = vec![];
     for x in schema.fields.iter() {
-        let dt = to_arrow::map_field_type(x.typ);
+        let dt = to_arrow::map_field_type(&x.typ);
         sort_fields.push(SortField::new(dt));
     }
     let num_rows = batch.num_rows();
@@ -132,3 +132,110 @@ fn roundtrip_record_to_record_batch() {
 
     assert_eq!(original_schema, arrow_field_test_cases_schema());
 }
+
+#[test]
+fn can_convert_nested_arrow_values() {
+    use crate::arrow_types::from_arrow::{map_arrow_to_dozer_type, map_value_to_dozer_field};
+    use crate::types::Field;
+    use arrow::array::{ArrayRef, Int64Array, ListArray, StringArray, StructArray};
+    use arrow::datatypes::{DataType, Int64Type};
+    use std::sync::Arc;
+
+    let list: ArrayRef = Arc::new(ListArray::from_iter_primitive::<Int64Type, _, _>(vec![
+        Some(vec![Some(1), None, Some(3)]),
+        None,
+    ]));
+    assert_eq!(
+        map_arrow_to_dozer_type(list.data_type()).unwrap(),
+        FieldType::List(Box::new(FieldType::Int))
+    );
+    let schema = DozerSchema::default();
+    assert_eq!(
+        map_value_to_dozer_field(&list, 0, "list", &schema).unwrap(),
+        Field::List(vec![Field::Int(1), Field::Null, Field::Int(3)])
+    );
+    assert_eq!(
+        map_value_to_dozer_field(&list, 1, "list", &schema).unwrap(),
+        Field::Null
+    );
+
+    let structs: ArrayRef = Arc::new(StructArray::from(vec![
+        (
+            Arc::new(arrow_types::Field::new("id", DataType::Int64, false)),
+            Arc::new(Int64Array::from(vec![7])) as ArrayRef,
+        ),
+        (
+            Arc::new(arrow_types::Field::new("name", DataType::Utf8, false)),
+            Arc::new(StringArray::from(vec!["dozer"])) as ArrayRef,
+        ),
+    ]));
+    assert_eq!(
+        map_arrow_to_dozer_type(structs.data_type()).unwrap(),
+        FieldType::Struct(vec![
+            ("id".to_string(), FieldType::Int),
+            ("name".to_string(), FieldType::String),
+        ])
+    );
+    assert_eq!(
+        map_value_to_dozer_field(&structs, 0, "struct", &schema).unwrap(),
+        Field::Struct(vec![
+            ("id".to_string(), Field::Int(7)),
+            ("name".to_string(), Field::String("dozer".to_string())),
+        ])
+    );
+}
+
+#[test]
+fn roundtrip_nested_record_to_record_batch() {
+    use crate::arrow_types::from_arrow::map_record_batch_to_dozer_records;
+    use crate::arrow_types::to_arrow::{map_field_type, map_record_to_arrow};
+    use crate::types::{Field, Record};
+    use arrow::datatypes::DataType;
+
+    let list = FieldType::List(Box::new(FieldType::UInt));
+    let untyped_list = FieldType::List(Box::new(FieldType::Json));
+    let structs = FieldType::Struct(vec![(
+        "inner".to_string(),
+        FieldType::List(Box::new(FieldType::Boolean)),
+    )]);
+    let untyped_struct = FieldType::Struct(vec![]);
+    assert!(matches!(map_field_type(&list), DataType::List(_)));
+    assert!(matches!(map_field_type(&structs), DataType::Struct(_)));
+    assert_eq!(map_field_type(&untyped_struct), DataType::Utf8);
+
+    let mut schema = DozerSchema::default();
+    for (name, typ) in [
+        ("list", list),
+        ("untyped_list", untyped_list),
+        ("struct", structs),
+        ("untyped_struct", untyped_struct),
+    ] {
+        schema.field(
+            FieldDefinition::new(name.to_string(), typ, true, SourceDefinition::Dynamic),
+            false,
+        );
+    }
+    let records = vec![
+        Record::new(vec![
+            Field::List(vec![Field::UInt(1), Field::Null]),
+            Field::List(vec![Field::UInt(1), Field::String("a".to_string())]),
+            Field::Struct(vec![(
+                "inner".to_string(),
+                Field::List(vec![Field::Boolean(true), Field::Null]),
+            )]),
+            Field::Struct(vec![("a".to_string(), Field::Int(-1))]),
+        ]),
+        Record::new(vec![
+            Field::List(vec![]),
+            Field::Null,
+            Field::Struct(vec![("inner".to_string(), Field::Null)]),
+            Field::Null,
+        ]),
+    ];
+
+    for record in records {
+        let batch = map_record_to_arrow(record.clone(), &schema).unwrap();
+        let result = map_record_batch_to_dozer_records(batch, &schema).unwrap();
+        assert_eq!(result, vec![record]);
+    }
+}
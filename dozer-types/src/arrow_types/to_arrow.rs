@@ -1,7 +1,9 @@
+use crate::json_types::field_to_json_value;
 use crate::types::{Field, FieldDefinition, FieldType, Record, Schema};
-use arrow::datatypes::{self as arrow_types, DataType};
+use arrow::datatypes::{self as arrow_types, DataType, Fields};
 use arrow::{
     array::{self as arrow_array, ArrayRef},
+    buffer::{OffsetBuffer, ScalarBuffer},
     datatypes::i256,
     record_batch::RecordBatch,
 };
@@ -41,114 +43,166 @@ pub fn map_record_to_arrow(
 
     for (idx, f) in rec.values.iter().enumerate() {
         let fd = schema.fields.get(idx).unwrap();
-        let column = match (f, fd.typ) {
-            (Field::UInt(v), FieldType::UInt) => {
-                Arc::new(arrow_array::UInt64Array::from_iter_values([*v])) as ArrayRef
-            }
-            (Field::Null, FieldType::UInt) => {
-                Arc::new(arrow_array::UInt64Array::from(vec![None as Option<u64>])) as ArrayRef
-            }
-            (Field::Int(v), FieldType::Int) => {
-                Arc::new(arrow_array::Int64Array::from_iter_values([*v])) as ArrayRef
-            }
-            (Field::Null, FieldType::Int) => {
-                Arc::new(arrow_array::Int64Array::from(vec![None as Option<i64>])) as ArrayRef
-            }
-            (Field::Float(v), FieldType::Float) => {
-                Arc::new(arrow_array::Float64Array::from_iter_values([**v])) as ArrayRef
-            }
-            (Field::Null, FieldType::Float) => {
-                Arc::new(arrow_array::Float64Array::from(vec![None as Option<f64>])) as ArrayRef
-            }
-            (Field::Boolean(v), FieldType::Boolean) => {
-                Arc::new(arrow_array::BooleanArray::from(vec![*v])) as ArrayRef
-            }
-            (Field::Null, FieldType::Boolean) => {
-                Arc::new(arrow_array::BooleanArray::from(vec![None as Option<bool>])) as ArrayRef
-            }
-            (Field::String(v), FieldType::String) => {
-                Arc::new(arrow_array::StringArray::from_iter_values([v])) as ArrayRef
-            }
-            (Field::Null, FieldType::String) => {
-                Arc::new(arrow_array::StringArray::from(vec![None as Option<String>])) as ArrayRef
-            }
-            (Field::Text(v), FieldType::Text) => {
-                Arc::new(arrow_array::LargeStringArray::from_iter_values([v])) as ArrayRef
-            }
-            (Field::Null, FieldType::Text) => Arc::new(arrow_array::LargeStringArray::from(vec![
-                None as Option<String>,
-            ])) as ArrayRef,
-            (Field::Decimal(v), FieldType::Decimal) => arrow_cast::cast(
-                &arrow_array::Decimal128Array::from(vec![v.mantissa()])
-                    .with_precision_and_scale(DECIMAL128_MAX_PRECISION, v.scale() as i8)?,
-                &DataType::Decimal256(DECIMAL256_MAX_PRECISION, DECIMAL128_MAX_SCALE),
-            )?,
-            (Field::Null, FieldType::Decimal) => Arc::new(arrow_array::Decimal256Array::from(vec![
-                None as Option<i256>,
-            ])) as ArrayRef,
-            (Field::Timestamp(v), FieldType::Timestamp) => {
-                Arc::new(arrow_array::TimestampNanosecondArray::from_iter_values([{
-                    v.timestamp_nanos_opt().expect(
-                        "value can not be represented in a timestamp with nanosecond precision.",
-                    )
-                }])) as ArrayRef
-            }
-            (Field::Null, FieldType::Timestamp) => {
-                Arc::new(arrow_array::TimestampNanosecondArray::from(vec![
-                    None as Option<i64>,
-                ])) as ArrayRef
-            }
-            (Field::Date(v), FieldType::Date) => {
-                let d = v.and_hms_milli_opt(0, 0, 0, 0).unwrap();
-                Arc::new(arrow_array::Date64Array::from_iter_values([
-                    d.timestamp_millis()
-                ])) as ArrayRef
-            }
-            (Field::Null, FieldType::Date) => {
-                Arc::new(arrow_array::Date64Array::from(vec![None as Option<i64>])) as ArrayRef
-            }
-            (Field::Binary(v), FieldType::Binary) => {
-                Arc::new(arrow_array::BinaryArray::from_iter_values([v])) as ArrayRef
-            }
-            (Field::Json(v), FieldType::Json) => Arc::new(
-                arrow_array::StringArray::from_iter_values([format!("{v:?}")]),
-            ) as ArrayRef,
-            (Field::Null, FieldType::Json) => {
-                Arc::new(arrow_array::StringArray::from(vec![None as Option<String>])) as ArrayRef
-            }
-            (Field::Point(v), FieldType::Point) => {
-                Arc::new(arrow_array::BinaryArray::from_iter_values([v.to_bytes()])) as ArrayRef
-            }
-            (Field::Null, FieldType::Point) => {
-                Arc::new(arrow_array::BinaryArray::from_opt_vec(vec![
-                    None as Option<&[u8]>,
-                ])) as ArrayRef
-            }
-            (Field::Duration(d), FieldType::Duration) => {
-                Arc::new(arrow_array::DurationNanosecondArray::from_iter_values([
-                    d.0.as_nanos() as i64,
-                ])) as ArrayRef
-            }
-            (Field::Null, FieldType::Duration) => {
-                Arc::new(arrow_array::BinaryArray::from_opt_vec(vec![
-                    None as Option<&[u8]>,
-                ])) as ArrayRef
-            }
-            (a, b) => Err(arrow::error::ArrowError::InvalidArgumentError(format!(
-                "Invalid field type {b:?} for the field: {a:?}",
-            )))?,
-        };
-        columns.push(column);
+        columns.push(map_field_to_arrow(f, &fd.typ)?);
     }
 
     let schema = map_to_arrow_schema(schema).unwrap();
     RecordBatch::try_new(Arc::new(schema), columns)
 }
 
+// Maps a Dozer Field to an Arrow array of length 1
+fn map_field_to_arrow(f: &Field, typ: &FieldType) -> Result<ArrayRef, arrow::error::ArrowError> {
+    let column = match (f, typ) {
+        (Field::UInt(v), FieldType::UInt) => {
+            Arc::new(arrow_array::UInt64Array::from_iter_values([*v])) as ArrayRef
+        }
+        (Field::Null, FieldType::UInt) => {
+            Arc::new(arrow_array::UInt64Array::from(vec![None as Option<u64>])) as ArrayRef
+        }
+        (Field::Int(v), FieldType::Int) => {
+            Arc::new(arrow_array::Int64Array::from_iter_values([*v])) as ArrayRef
+        }
+        (Field::Null, FieldType::Int) => {
+            Arc::new(arrow_array::Int64Array::from(vec![None as Option<i64>])) as ArrayRef
+        }
+        (Field::Float(v), FieldType::Float) => {
+            Arc::new(arrow_array::Float64Array::from_iter_values([**v])) as ArrayRef
+        }
+        (Field::Null, FieldType::Float) => {
+            Arc::new(arrow_array::Float64Array::from(vec![None as Option<f64>])) as ArrayRef
+        }
+        (Field::Boolean(v), FieldType::Boolean) => {
+            Arc::new(arrow_array::BooleanArray::from(vec![*v])) as ArrayRef
+        }
+        (Field::Null, FieldType::Boolean) => {
+            Arc::new(arrow_array::BooleanArray::from(vec![None as Option<bool>])) as ArrayRef
+        }
+        (Field::String(v), FieldType::String) => {
+            Arc::new(arrow_array::StringArray::from_iter_values([v])) as ArrayRef
+        }
+        (Field::Null, FieldType::String) => {
+            Arc::new(arrow_array::StringArray::from(vec![None as Option<String>])) as ArrayRef
+        }
+        (Field::Text(v), FieldType::Text) => {
+            Arc::new(arrow_array::LargeStringArray::from_iter_values([v])) as ArrayRef
+        }
+        (Field::Null, FieldType::Text) => Arc::new(arrow_array::LargeStringArray::from(vec![
+            None as Option<String>,
+        ])) as ArrayRef,
+        (Field::Decimal(v), FieldType::Decimal) => arrow_cast::cast(
+            &arrow_array::Decimal128Array::from(vec![v.mantissa()])
+                .with_precision_and_scale(DECIMAL128_MAX_PRECISION, v.scale() as i8)?,
+            &DataType::Decimal256(DECIMAL256_MAX_PRECISION, DECIMAL128_MAX_SCALE),
+        )?,
+        (Field::Null, FieldType::Decimal) => Arc::new(arrow_array::Decimal256Array::from(vec![
+            None as Option<i256>,
+        ])) as ArrayRef,
+        (Field::Timestamp(v), FieldType::Timestamp) => {
+            Arc::new(arrow_array::TimestampNanosecondArray::from_iter_values([{
+                v.timestamp_nanos_opt().expect(
+                    "value can not be represented in a timestamp with nanosecond precision.",
+                )
+            }])) as ArrayRef
+        }
+        (Field::Null, FieldType::Timestamp) => {
+            Arc::new(arrow_array::TimestampNanosecondArray::from(vec![
+                None as Option<i64>,
+            ])) as ArrayRef
+        }
+        (Field::Date(v), FieldType::Date) => {
+            let d = v.and_hms_milli_opt(0, 0, 0, 0).unwrap();
+            Arc::new(arrow_array::Date64Array::from_iter_values([
+                d.timestamp_millis()
+            ])) as ArrayRef
+        }
+        (Field::Null, FieldType::Date) => {
+            Arc::new(arrow_array::Date64Array::from(vec![None as Option<i64>])) as ArrayRef
+        }
+        (Field::Binary(v), FieldType::Binary) => {
+            Arc::new(arrow_array::BinaryArray::from_iter_values([v])) as ArrayRef
+        }
+        (Field::Json(v), FieldType::Json) => {
+            Arc::new(arrow_array::StringArray::from_iter_values([format!(
+                "{v:?}"
+            )])) as ArrayRef
+        }
+        (Field::Null, FieldType::Json) => {
+            Arc::new(arrow_array::StringArray::from(vec![None as Option<String>])) as ArrayRef
+        }
+        (Field::Point(v), FieldType::Point) => {
+            Arc::new(arrow_array::BinaryArray::from_iter_values([v.to_bytes()])) as ArrayRef
+        }
+        (Field::Null, FieldType::Point) => Arc::new(arrow_array::BinaryArray::from_opt_vec(vec![
+            None as Option<&[u8]>,
+        ])) as ArrayRef,
+        (Field::Duration(d), FieldType::Duration) => {
+            Arc::new(arrow_array::DurationNanosecondArray::from_iter_values([
+                d.0.as_nanos() as i64,
+            ])) as ArrayRef
+        }
+        (Field::Null, FieldType::Duration) => {
+            Arc::new(arrow_array::DurationNanosecondArray::from(vec![
+                None as Option<i64>,
+            ])) as ArrayRef
+        }
+        (Field::List(fields), FieldType::List(element)) => {
+            let values = fields
+                .iter()
+                .map(|field| map_field_to_arrow(field, element))
+                .collect::<Result<Vec<_>, _>>()?;
+            let values = if values.is_empty() {
+                arrow_array::new_empty_array(&map_field_type(element))
+            } else {
+                let values = values
+                    .iter()
+                    .map(|array| array.as_ref())
+                    .collect::<Vec<&dyn arrow_array::Array>>();
+                arrow::compute::concat(&values)?
+            };
+            Arc::new(arrow_array::ListArray::try_new(
+                Arc::new(list_item(element)),
+                OffsetBuffer::new(ScalarBuffer::from(vec![0, fields.len() as i32])),
+                values,
+                None,
+            )?) as ArrayRef
+        }
+        (Field::Struct(fields), FieldType::Struct(types)) if !types.is_empty() => {
+            let columns = types
+                .iter()
+                .map(
+                    |(name, typ)| match fields.iter().find(|(field_name, _)| field_name == name) {
+                        Some((_, field)) => map_field_to_arrow(field, typ),
+                        None => map_field_to_arrow(&Field::Null, typ),
+                    },
+                )
+                .collect::<Result<Vec<_>, _>>()?;
+            Arc::new(arrow_array::StructArray::try_new(
+                struct_fields(types),
+                columns,
+                None,
+            )?) as ArrayRef
+        }
+        (Field::Null, FieldType::List(_) | FieldType::Struct(_)) => {
+            arrow_array::new_null_array(&map_field_type(typ), 1)
+        }
+        // Elements of untyped lists, and structs without declared fields.
+        (f, FieldType::Json | FieldType::Struct(_)) => {
+            Arc::new(arrow_array::StringArray::from_iter_values([
+                serde_json::to_string(&field_to_json_value(f.clone()))
+                    .expect("JSON can always be serialized"),
+            ])) as ArrayRef
+        }
+        (a, b) => Err(arrow::error::ArrowError::InvalidArgumentError(format!(
+            "Invalid field type {b:?} for the field: {a:?}",
+        )))?,
+    };
+    Ok(column)
+}
+
 // Maps the dozer field type to the arrow data type
 // Optionally takes a metadata map to add additional metadata to the field
 
-pub fn map_field_type(typ: FieldType) -> DataType {
+pub fn map_field_type(typ: &FieldType) -> DataType {
     match typ {
         FieldType::UInt => DataType::UInt64,
         FieldType::U128 => DataType::Utf8,
@@ -165,12 +219,27 @@ pub fn map_field_type(typ: FieldType) -> DataType {
         FieldType::Json => DataType::Utf8,
         FieldType::Point => DataType::Binary,
         FieldType::Duration => DataType::Duration(TimeUnit::Nanosecond),
+        FieldType::List(element) => DataType::List(Arc::new(list_item(element))),
+        // Without declared fields, structs are written as JSON.
+        FieldType::Struct(fields) if fields.is_empty() => DataType::Utf8,
+        FieldType::Struct(fields) => DataType::Struct(struct_fields(fields)),
     }
 }
 
+fn list_item(element: &FieldType) -> arrow_types::Field {
+    arrow_types::Field::new("item", map_field_type(element), true)
+}
+
+fn struct_fields(fields: &[(String, FieldType)]) -> Fields {
+    fields
+        .iter()
+        .map(|(name, typ)| arrow_types::Field::new(name, map_field_type(typ), true))
+        .collect()
+}
+
 impl From<FieldDefinition> for arrow_types::Field {
     fn from(f: FieldDefinition) -> Self {
-        let dt = map_field_type(f.typ);
+        let dt = map_field_type(&f.typ);
         arrow_types::Field::new(f.name, dt, f.nullable)
     }
 }
@@ -135,10 +135,78 @@ pub fn json_value_to_field(
                     .into(),
             )),
         },
+        FieldType::List(element) => match value {
+            Value::Array(values) => {
+                return values
+                    .into_iter()
+                    .map(|value| json_value_to_element(value, &element))
+                    .collect::<Result<_, _>>()
+                    .map(Field::List)
+            }
+            _ => Err(DeserializationError::Custom(
+                "Json value type does not match field type"
+                    .to_string()
+                    .into(),
+            )),
+        },
+        FieldType::Struct(fields) => match value {
+            Value::Object(mut map) if !fields.is_empty() => {
+                return fields
+                    .into_iter()
+                    .map(|(name, typ)| {
+                        let value = map.remove(&name).unwrap_or(Value::Null);
+                        json_value_to_element(value, &typ).map(|field| (name, field))
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(Field::Struct)
+            }
+            Value::Object(_) => Ok(json_value_to_nested_field(value)),
+            _ => Err(DeserializationError::Custom(
+                "Json value type does not match field type"
+                    .to_string()
+                    .into(),
+            )),
+        },
     }
     .map_err(TypeError::DeserializationError)
 }
 
+/// Elements of nested values are nullable, and `Json` elements keep their inferred types.
+fn json_value_to_element(value: Value, typ: &FieldType) -> Result<Field, TypeError> {
+    match typ {
+        FieldType::Json => Ok(json_value_to_nested_field(value)),
+        typ => json_value_to_field(value, typ.clone(), true),
+    }
+}
+
+/// Converts a JSON value to a `Field`, inferring the type of every element.
+///
+/// Arrays become `Field::List` and objects become `Field::Struct`.
+pub fn json_value_to_nested_field(value: Value) -> Field {
+    match value {
+        Value::Null => Field::Null,
+        Value::Bool(b) => Field::Boolean(b),
+        Value::Number(n) => {
+            if let Some(n) = n.as_u64() {
+                Field::UInt(n)
+            } else if let Some(n) = n.as_i64() {
+                Field::Int(n)
+            } else {
+                Field::Float(OrderedFloat(n.as_f64().unwrap_or(f64::NAN)))
+            }
+        }
+        Value::String(s) => Field::String(s),
+        Value::Array(values) => {
+            Field::List(values.into_iter().map(json_value_to_nested_field).collect())
+        }
+        Value::Object(map) => Field::Struct(
+            map.into_iter()
+                .map(|(name, value)| (name, json_value_to_nested_field(value)))
+                .collect(),
+        ),
+    }
+}
+
 impl Field {
     pub fn from_str(value: &str, typ: FieldType, nullable: bool) -> Result<Field, TypeError> {
         match typ {
@@ -301,6 +369,20 @@ impl Field {
                     value.parse::<DozerDuration>().map(Field::Duration)
                 }
             }
+            FieldType::List(_) | FieldType::Struct(_) => {
+                if nullable && (value.is_empty() || value == "null") {
+                    Ok(Field::Null)
+                } else {
+                    match serde_json::from_str(value) {
+                        Ok(json) => json_value_to_field(json, typ, nullable),
+                        Err(_) => Err(TypeError::InvalidFieldValue {
+                            field_type: typ,
+                            nullable,
+                            value: value.to_string(),
+                        }),
+                    }
+                }
+            }
         }
     }
 }
@@ -127,10 +127,20 @@ pub fn field_to_json_value(field: Field) -> JsonValue {
         Field::Point(point) => convert_x_y_to_object(point.0.x_y()),
         Field::Duration(d) => convert_duration_to_object(d),
         Field::Null => JsonValue::NULL,
+        Field::List(fields) => fields
+            .into_iter()
+            .map(field_to_json_value)
+            .collect::<IArray>()
+            .into(),
+        Field::Struct(fields) => fields
+            .into_iter()
+            .map(|(name, field)| (name, field_to_json_value(field)))
+            .collect::<IObject>()
+            .into(),
     }
 }
 
-fn json_value_to_serde_json(value: &JsonValue) -> Value {
+pub fn json_value_to_serde_json(value: &JsonValue) -> Value {
     // Note that while this cannot fail, the other way might, as our internal JSON
     // representation does not support `inf`, `-inf` and NaN
     ijson::from_value(value).expect("Json to Json conversion should never fail")
@@ -359,6 +369,24 @@ mod tests {
                     TimeUnit::Nanoseconds,
                 )),
             ),
+            (
+                FieldType::List(Box::new(FieldType::Int)),
+                Field::List(vec![Field::Int(-1), Field::Null]),
+            ),
+            (
+                FieldType::List(Box::new(FieldType::Json)),
+                Field::List(vec![Field::UInt(1), Field::String("a".to_string())]),
+            ),
+            (
+                FieldType::Struct(vec![
+                    ("a".to_string(), FieldType::String),
+                    ("b".to_string(), FieldType::Decimal),
+                ]),
+                Field::Struct(vec![
+                    ("a".to_string(), Field::String("x".to_string())),
+                    ("b".to_string(), Field::Null),
+                ]),
+            ),
         ];
         for (field_type, field) in fields {
             test_field_conversion(field_type, field);
@@ -6,6 +6,7 @@ pub mod helper;
 pub mod json_types;
 pub mod models;
 pub mod node;
+pub mod redact;
 #[cfg(test)]
 mod tests;
 pub mod types;
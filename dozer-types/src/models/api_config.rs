@@ -21,6 +21,10 @@ pub struct ApiConfig {
     #[serde(default, skip_serializing_if = "equal_default")]
     pub pgwire: PgWireOptions,
 
+    #[serde(default, skip_serializing_if = "equal_default")]
+    /// The `/health`, `/ready`, `/status` and `/metrics` endpoints served by `dozer run`
+    pub health: HealthOptions,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     // max records to be returned from the endpoints
     pub default_max_num_records: Option<usize>,
@@ -87,6 +91,23 @@ pub struct PgWireOptions {
     pub enabled: Option<bool>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
+pub struct HealthOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// How far behind the sources a sink may be for `/ready` to succeed; Default: 60
+    pub max_sink_lag_seconds: Option<u64>,
+}
+
 pub fn default_app_grpc_port() -> u32 {
     50053
 }
@@ -103,6 +124,14 @@ pub fn default_rest_port() -> u16 {
     8080
 }
 
+pub fn default_health_port() -> u16 {
+    8081
+}
+
+pub fn default_max_sink_lag_seconds() -> u64 {
+    60
+}
+
 pub fn default_sql_port() -> u32 {
     50054
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -41,6 +43,18 @@ pub struct AppConfig {
     #[serde(default, skip_serializing_if = "equal_default")]
     /// The record store to use for the processors.
     pub record_store: RecordStore,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// How many instances each JOIN and GROUP BY processor runs as. Their input is partitioned by the join or group key.
+    pub parallelism: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    /// Parallelism of individual processors by processor id, overriding `parallelism`.
+    pub processor_parallelism: HashMap<String, u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Bytes of ingested records that may wait to enter the pipeline, across all sources. Connectors pause when it's used up. Unlimited by default.
+    pub memory_budget: Option<u64>,
 }
 
 #[derive(Debug, JsonSchema, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -89,6 +103,10 @@ pub fn default_max_num_records_before_persist() -> u64 {
     100_000
 }
 
+pub fn default_parallelism() -> u32 {
+    1
+}
+
 pub fn default_max_interval_before_persist_in_seconds() -> u64 {
     60
 }
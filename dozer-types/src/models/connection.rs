@@ -1,8 +1,9 @@
 use crate::models::ingestion_types::{
-    ConfigSchemas, DeltaLakeConfig, EthConfig, GrpcConfig, JavaScriptConfig, KafkaConfig,
-    LocalStorage, MongodbConfig, MySQLConfig, NestedDozerConfig, S3Storage, SnowflakeConfig,
-    WebhookConfig, SECRET,
+    AzureStorage, ConfigSchemas, DeltaLakeConfig, EthConfig, GcsStorage, GrpcConfig,
+    JavaScriptConfig, KafkaConfig, LocalStorage, MongodbConfig, MySQLConfig, NestedDozerConfig,
+    S3Storage, SnowflakeConfig, WebhookConfig, SECRET,
 };
+use crate::redact::redact_table;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
@@ -64,6 +65,31 @@ pub struct PostgresConfig {
     /// The snapshot batch size
     #[serde(skip_serializing_if = "Option::is_none")]
     pub batch_size: Option<u32>,
+
+    /// The number of primary key values in a snapshot chunk (default: 1000000). Tables with a single integer primary key are read in chunks, which are checkpointed so an interrupted snapshot resumes from the last completed chunk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_chunk_size: Option<u64>,
+
+    /// The number of snapshot chunks read in parallel (default: 4)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_parallelism: Option<u32>,
+
+    /// Snapshot tables added to the configuration of a running replication, without pausing it (default: disabled). The connector creates a watermark table in the source database and adds it to the publication.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub incremental_snapshot: Option<PostgresIncrementalSnapshotConfig>,
+}
+
+/// Configuration of the watermark table used to snapshot tables added to a running Postgres replication
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PostgresIncrementalSnapshotConfig {
+    /// The schema of the watermark table (default: public)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watermark_schema: Option<String>,
+
+    /// The name of the watermark table (default: dozer_watermarks)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watermark_table: Option<String>,
 }
 
 impl SchemaExample for PostgresConfig {
@@ -92,14 +118,16 @@ pub struct PostgresConfigReplenished {
 
 impl PostgresConfigReplenished {
     pub fn convert_to_table(&self) -> Table {
-        table!(
+        let mut table = table!(
             ["user", self.user],
             ["password", SECRET],
             ["host", self.host],
             ["port", self.port],
             ["database", self.database],
             ["sslmode", format!("{:?}", self.sslmode)]
-        )
+        );
+        redact_table(&mut table);
+        table
     }
 }
 
@@ -270,6 +298,12 @@ pub enum ConnectionConfig {
     /// In yaml, present as tag: `!ObjectStore`
     LocalStorage(LocalStorage),
 
+    /// In yaml, present as tag: `!GcsStorage`
+    GcsStorage(GcsStorage),
+
+    /// In yaml, present as tag: `!AzureStorage`
+    AzureStorage(AzureStorage),
+
     /// In yaml, present as tag" `!DeltaLake`
     DeltaLake(DeltaLakeConfig),
 
@@ -302,6 +336,8 @@ impl ConnectionConfig {
             ConnectionConfig::Kafka(_) => "kafka".to_string(),
             ConnectionConfig::S3Storage(_) => "s3storage".to_string(),
             ConnectionConfig::LocalStorage(_) => "localstorage".to_string(),
+            ConnectionConfig::GcsStorage(_) => "gcsstorage".to_string(),
+            ConnectionConfig::AzureStorage(_) => "azurestorage".to_string(),
             ConnectionConfig::DeltaLake(_) => "deltalake".to_string(),
             ConnectionConfig::MongoDB(_) => "mongodb".to_string(),
             ConnectionConfig::MySQL(_) => "mysql".to_string(),
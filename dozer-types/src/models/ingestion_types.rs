@@ -1,13 +1,17 @@
 use prettytable::Table as PrettyTable;
 use schemars::JsonSchema;
-use std::{fmt::Debug, time::Duration};
+use std::{
+    fmt::Debug,
+    time::{Duration, SystemTime},
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
     helper::{deserialize_duration_secs_f64, f64_schema, serialize_duration_secs_f64},
     models::connection::SchemaExample,
-    node::OpIdentifier,
+    node::SourcePosition,
+    redact::redact_table,
     types::Operation,
 };
 
@@ -24,8 +28,10 @@ pub enum IngestionMessage {
         table_index: usize,
         /// The CDC event.
         op: Operation,
-        /// If this connector supports restarting from a specific CDC event, it should provide a `OpIdentifier`.
-        id: Option<OpIdentifier>,
+        /// If this connector supports restarting from a specific CDC event, it should provide a `SourcePosition`.
+        id: Option<SourcePosition>,
+        /// When the source committed the event, if the connector knows. Replication lag is measured from it.
+        source_time: Option<SystemTime>,
     },
     TransactionInfo(TransactionInfo),
 }
@@ -33,14 +39,19 @@ pub enum IngestionMessage {
 #[derive(Clone, Debug, PartialEq)]
 pub enum TransactionInfo {
     Commit {
-        /// If this connector supports restarting from after this commit, it should provide a `OpIdentifier`.
-        id: Option<OpIdentifier>,
+        /// If this connector supports restarting from after this commit, it should provide a `SourcePosition`.
+        id: Option<SourcePosition>,
     },
     /// A connector uses this message kind to notify Dozer that a initial snapshot of the source tables is started
     SnapshottingStarted,
     /// A connector uses this message kind to notify Dozer that a initial snapshot of the source tables is done,
     /// and the data is up-to-date until next CDC event.
-    SnapshottingDone { id: Option<OpIdentifier> },
+    SnapshottingDone { id: Option<SourcePosition> },
+    /// A connector that starts without a snapshot, e.g. when it resumes replication from a checkpoint,
+    /// uses this message kind to notify Dozer that it is replicating before any change arrives.
+    SnapshottingSkipped,
+    /// A connector may estimate how many rows the snapshot of a table will send, so Dozer can report the snapshot's progress.
+    SnapshotSize { table_index: usize, rows: u64 },
 }
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema, Default)]
@@ -152,6 +163,7 @@ impl EthConfig {
                 table.add_row(row!("trace", format!("{trace:?}")));
             }
         }
+        redact_table(&mut table);
         table
     }
 }
@@ -172,19 +184,27 @@ pub struct KafkaConfig {
     pub broker: String,
 
     pub schema_registry_url: Option<String>,
+
+    /// Treat every topic as a compacted changelog: the message key is the primary key,
+    /// later messages update the previous value and null-value tombstones delete it.
+    #[serde(default, skip_serializing_if = "equal_default")]
+    pub upsert: bool,
 }
 
 impl KafkaConfig {
     pub fn convert_to_table(&self) -> PrettyTable {
-        table!(
+        let mut table = table!(
             ["broker", self.broker],
             [
                 "schema registry url",
                 self.schema_registry_url
                     .as_ref()
                     .map_or("--------", |url| url)
-            ]
-        )
+            ],
+            ["upsert", self.upsert]
+        );
+        redact_table(&mut table);
+        table
     }
 }
 
@@ -222,7 +242,7 @@ pub struct SnowflakeConfig {
 
 impl SnowflakeConfig {
     pub fn convert_to_table(&self) -> PrettyTable {
-        table!(
+        let mut table = table!(
             ["server", self.server],
             ["port", self.port],
             ["user", self.user],
@@ -241,7 +261,9 @@ impl SnowflakeConfig {
                         .as_secs_f64()
                 )
             ]
-        )
+        );
+        redact_table(&mut table);
+        table
     }
 }
 
@@ -258,12 +280,14 @@ pub struct DataFusionConfig {
 
 impl DataFusionConfig {
     pub fn convert_to_table(&self) -> PrettyTable {
-        table!(
+        let mut table = table!(
             ["access_key_id", SECRET],
             ["secret_access_key", SECRET],
             ["region", self.region],
             ["bucket_name", self.bucket_name]
-        )
+        );
+        redact_table(&mut table);
+        table
     }
 }
 
@@ -272,14 +296,30 @@ pub struct Table {
     pub config: TableConfig,
 
     pub name: String,
+
+    /// What to do with a file once it has been ingested. Files are kept by default.
+    /// This happens once the pipeline has checkpointed the file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_ingestion: Option<AfterIngestion>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+pub enum AfterIngestion {
+    /// Delete the file.
+    Delete,
+    /// Move the file to `path`, keeping its location relative to the storage root. `path` must be outside the table's path.
+    Archive { path: String },
 }
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
 pub enum TableConfig {
     CSV(CsvConfig),
     Parquet(ParquetConfig),
+    Json(JsonConfig),
+    Avro(AvroConfig),
 }
 
+/// Extensions ending with `.gz`, `.bz2`, `.xz` or `.zst` are decompressed.
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
 pub struct CsvConfig {
     pub path: String,
@@ -300,15 +340,50 @@ pub struct ParquetConfig {
     pub marker_extension: Option<String>,
 }
 
+/// Newline delimited JSON files. Extensions ending with `.gz`, `.bz2`, `.xz` or `.zst` are decompressed.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+pub struct JsonConfig {
+    pub path: String,
+
+    pub extension: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker_extension: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+pub struct AvroConfig {
+    pub path: String,
+
+    pub extension: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marker_extension: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
 pub struct S3Details {
-    pub access_key_id: String,
+    /// If not set, credentials are read from the environment (`AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, `AWS_SESSION_TOKEN`), a web identity token or the instance metadata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_key_id: Option<String>,
 
-    pub secret_access_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_access_key: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
 
     pub region: String,
 
     pub bucket_name: String,
+
+    /// Endpoint of an S3 compatible store, e.g. `http://localhost:9000` for MinIO.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Send virtual hosted style requests (`<bucket_name>.<endpoint>`) instead of path style requests (`<endpoint>/<bucket_name>`).
+    #[serde(default, skip_serializing_if = "equal_default")]
+    pub virtual_hosted_style: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
@@ -321,21 +396,38 @@ pub struct S3Storage {
 
 impl S3Storage {
     pub fn convert_to_table(&self) -> PrettyTable {
-        table!(
-            ["access_key_id", SECRET],
-            ["secret_access_key", SECRET],
-            ["region", self.details.region],
-            ["bucket_name", self.details.bucket_name]
-        )
+        let mut table = table!();
+        if self.details.access_key_id.is_some() {
+            table.add_row(row!["access_key_id", SECRET]);
+        }
+        if self.details.secret_access_key.is_some() {
+            table.add_row(row!["secret_access_key", SECRET]);
+        }
+        if self.details.session_token.is_some() {
+            table.add_row(row!["session_token", SECRET]);
+        }
+        table.add_row(row!["region", self.details.region]);
+        table.add_row(row!["bucket_name", self.details.bucket_name]);
+        if let Some(endpoint) = &self.details.endpoint {
+            table.add_row(row!["endpoint", endpoint]);
+        }
+        if self.details.virtual_hosted_style {
+            table.add_row(row!["virtual_hosted_style", true]);
+        }
+        redact_table(&mut table);
+        table
     }
 }
 impl SchemaExample for S3Storage {
     fn example() -> Self {
         let s3_details = S3Details {
-            access_key_id: "".to_owned(),
-            secret_access_key: "".to_owned(),
+            access_key_id: Some("".to_owned()),
+            secret_access_key: Some("".to_owned()),
+            session_token: None,
             region: "".to_owned(),
             bucket_name: "".to_owned(),
+            endpoint: None,
+            virtual_hosted_style: false,
         };
         Self {
             details: s3_details,
@@ -346,6 +438,125 @@ impl SchemaExample for S3Storage {
                     marker_extension: None,
                 }),
                 name: "table_name".to_owned(),
+                after_ingestion: None,
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+pub struct GcsDetails {
+    pub bucket_name: String,
+
+    /// Path to a service account key file. If neither the path nor the key is set, credentials are read from the environment (`GOOGLE_SERVICE_ACCOUNT`, `GOOGLE_APPLICATION_CREDENTIALS`) or the instance metadata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_account_path: Option<String>,
+
+    /// Content of a service account key file. A `gcs_base_url` in the key points the connector to an emulator.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_account_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+#[schemars(example = "Self::example")]
+pub struct GcsStorage {
+    pub details: GcsDetails,
+
+    pub tables: Vec<Table>,
+}
+
+impl GcsStorage {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        let mut table = table!(["bucket_name", self.details.bucket_name]);
+        if let Some(service_account_path) = &self.details.service_account_path {
+            table.add_row(row!["service_account_path", service_account_path]);
+        }
+        if self.details.service_account_key.is_some() {
+            table.add_row(row!["service_account_key", SECRET]);
+        }
+        redact_table(&mut table);
+        table
+    }
+}
+
+impl SchemaExample for GcsStorage {
+    fn example() -> Self {
+        Self {
+            details: GcsDetails {
+                bucket_name: "".to_owned(),
+                service_account_path: Some("path/to/service_account.json".to_owned()),
+                service_account_key: None,
+            },
+            tables: vec![Table {
+                config: TableConfig::CSV(CsvConfig {
+                    path: "path/to/file".to_owned(),
+                    extension: ".csv".to_owned(),
+                    marker_extension: None,
+                }),
+                name: "table_name".to_owned(),
+                after_ingestion: None,
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+pub struct AzureDetails {
+    pub account_name: String,
+
+    pub container_name: String,
+
+    /// If not set, credentials are read from the environment (`AZURE_STORAGE_ACCESS_KEY`, `AZURE_CLIENT_ID`, ...) or a managed identity.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_key: Option<String>,
+
+    /// Connect to a local Azurite emulator, at `AZURITE_BLOB_STORAGE_URL` or `http://127.0.0.1:10000`.
+    #[serde(default, skip_serializing_if = "equal_default")]
+    pub use_emulator: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+#[schemars(example = "Self::example")]
+pub struct AzureStorage {
+    pub details: AzureDetails,
+
+    pub tables: Vec<Table>,
+}
+
+impl AzureStorage {
+    pub fn convert_to_table(&self) -> PrettyTable {
+        let mut table = table!(
+            ["account_name", self.details.account_name],
+            ["container_name", self.details.container_name]
+        );
+        if self.details.access_key.is_some() {
+            table.add_row(row!["access_key", SECRET]);
+        }
+        if self.details.use_emulator {
+            table.add_row(row!["use_emulator", true]);
+        }
+        redact_table(&mut table);
+        table
+    }
+}
+
+impl SchemaExample for AzureStorage {
+    fn example() -> Self {
+        Self {
+            details: AzureDetails {
+                account_name: "".to_owned(),
+                container_name: "".to_owned(),
+                access_key: Some("".to_owned()),
+                use_emulator: false,
+            },
+            tables: vec![Table {
+                config: TableConfig::CSV(CsvConfig {
+                    path: "path/to/file".to_owned(),
+                    extension: ".csv".to_owned(),
+                    marker_extension: None,
+                }),
+                name: "table_name".to_owned(),
+                after_ingestion: None,
             }],
         }
     }
@@ -367,7 +578,9 @@ pub struct LocalStorage {
 
 impl LocalStorage {
     pub fn convert_to_table(&self) -> PrettyTable {
-        table!(["path", self.details.path])
+        let mut table = table!(["path", self.details.path]);
+        redact_table(&mut table);
+        table
     }
 }
 
@@ -475,6 +688,7 @@ impl SchemaExample for KafkaConfig {
         Self {
             broker: "".to_owned(),
             schema_registry_url: Some("".to_owned()),
+            upsert: false,
         }
     }
 }
@@ -503,6 +717,7 @@ impl SchemaExample for LocalStorage {
                     marker_extension: None,
                 }),
                 name: "table_name".to_owned(),
+                after_ingestion: None,
             }],
         }
     }
@@ -563,7 +778,24 @@ pub struct WebhookConfig {
     pub port: Option<u32>,
 
     pub endpoints: Vec<WebhookEndpoint>,
+
+    /// Authentication required on every endpoint. If not set, requests are not authenticated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<WebhookAuth>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
+pub enum WebhookAuth {
+    /// Requests must carry `secret` as the value of `header`.
+    SharedSecret { header: String, secret: String },
+    /// Requests must carry the hex encoded HMAC-SHA256 of the request body, keyed with `secret`, in `header`.
+    /// A `sha256=` prefix, as sent by GitHub, is accepted.
+    HmacSha256 { header: String, secret: String },
+    /// Requests must carry a HS256 JWT signed with `secret` as `Authorization: Bearer <token>`.
+    /// The `exp` claim is checked if present.
+    BearerJwt { secret: String },
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Hash, JsonSchema)]
 #[schemars(example = "Self::example")]
 pub struct WebhookEndpoint {
@@ -575,7 +807,7 @@ pub struct WebhookEndpoint {
 #[schemars(example = "Self::example")]
 pub enum WebhookVerb {
     POST,   // insert
-    PUT,    // update
+    PUT,    // update by primary key
     DELETE, // delete
 }
 
@@ -591,6 +823,7 @@ impl SchemaExample for WebhookConfig {
             host: Some("localhost".to_owned()),
             port: Some(50059),
             endpoints: vec![WebhookEndpoint::example()],
+            auth: None,
         }
     }
 }
@@ -26,6 +26,22 @@ pub struct Source {
     #[serde(default, skip_serializing_if = "equal_default")]
     /// setting for how to refresh the data; Default: RealTime
     pub refresh_config: RefreshConfig,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// maximum rate at which records of this source enter the pipeline; the whole connection, not just this source, pauses while it's exceeded
+    pub rate_limit: Option<RateLimit>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Default, Eq, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// records per second
+    pub max_rows_per_sec: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// bytes per second, estimated from the records' encoded size
+    pub max_bytes_per_sec: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Clone, Default)]
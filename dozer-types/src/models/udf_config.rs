@@ -16,6 +16,8 @@ pub struct UdfConfig {
 pub enum UdfType {
     Onnx(OnnxConfig),
     JavaScript(JavaScriptConfig),
+    /// module exports `init`, `accumulate`, `retract` and `result` functions
+    JavaScriptAggregate(JavaScriptConfig),
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Clone)]
@@ -30,4 +32,10 @@ pub struct OnnxConfig {
 pub struct JavaScriptConfig {
     /// path to the module file
     pub module: String,
+    /// types of the arguments, e.g. `[float, int]`, checked when the SQL is planned; Default: not checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arg_types: Option<Vec<String>>,
+    /// type the returned JSON is cast to, e.g. `int` or `string`; Default: json
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<String>,
 }
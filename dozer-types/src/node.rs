@@ -1,10 +1,13 @@
-use serde::{self, Deserialize, Serialize};
+use serde::{self, de::DeserializeOwned, Deserialize, Serialize};
 
 use std::{
     collections::HashMap,
     fmt::{Display, Formatter},
     str::from_utf8,
 };
+
+use crate::errors::types::{DeserializationError, SerializationError};
+
 #[derive(
     Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, bincode::Encode, bincode::Decode,
 )]
@@ -104,6 +107,67 @@ impl OpIdentifier {
     }
 }
 
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+    bincode::Encode,
+    bincode::Decode,
+)]
+/// A position in a source, which its connector can restart from.
+///
+/// The connector defines the encoding, for example a resume token or offsets of multiple partitions,
+/// so positions can only be compared for equality.
+pub struct SourcePosition(Vec<u8>);
+
+impl SourcePosition {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+
+    /// Encodes a connector defined position as JSON.
+    pub fn encode<T: Serialize>(position: &T) -> Result<Self, SerializationError> {
+        Ok(Self(serde_json::to_vec(position)?))
+    }
+
+    /// Decodes a position encoded by `encode`.
+    pub fn decode<T: DeserializeOwned>(&self) -> Result<T, DeserializationError> {
+        Ok(serde_json::from_slice(&self.0)?)
+    }
+}
+
+impl From<OpIdentifier> for SourcePosition {
+    fn from(id: OpIdentifier) -> Self {
+        Self(id.to_bytes().to_vec())
+    }
+}
+
+impl TryFrom<&SourcePosition> for OpIdentifier {
+    type Error = DeserializationError;
+
+    fn try_from(position: &SourcePosition) -> Result<Self, Self::Error> {
+        position
+            .0
+            .as_slice()
+            .try_into()
+            .map(OpIdentifier::from_bytes)
+            .map_err(|_| DeserializationError::BadDataLength)
+    }
+}
+
 #[derive(
     Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, bincode::Encode, bincode::Decode,
 )]
@@ -113,8 +177,21 @@ pub enum SourceState {
     NotStarted,
     /// This source has some data ingested, and it can't be restarted.
     NonRestartable,
-    /// This source has some data ingested, and it can be restarted if it's given the op id.
-    Restartable(OpIdentifier),
+    /// Written by earlier versions, whose positions were always op ids. Kept for decoding their checkpoints.
+    RestartableFromOpId(OpIdentifier),
+    /// This source has some data ingested, and it can be restarted if it's given the position.
+    Restartable(SourcePosition),
+}
+
+impl SourceState {
+    /// The position to restart from, if the source is restartable.
+    pub fn position(&self) -> Option<SourcePosition> {
+        match self {
+            SourceState::NotStarted | SourceState::NonRestartable => None,
+            SourceState::RestartableFromOpId(id) => Some((*id).into()),
+            SourceState::Restartable(position) => Some(position.clone()),
+        }
+    }
 }
 
 /// Map from a `Source` node's handle to it state.
@@ -122,6 +199,23 @@ pub enum SourceState {
 /// This uniquely identifies the state of the Dozer pipeline.
 pub type SourceStates = HashMap<NodeHandle, SourceState>;
 
+#[test]
+fn test_source_position() {
+    let id = OpIdentifier::new(1, 2);
+    let position = SourcePosition::from(id);
+    assert_eq!(OpIdentifier::try_from(&position).unwrap(), id);
+    assert!(OpIdentifier::try_from(&SourcePosition::new(vec![1, 2])).is_err());
+
+    let offsets = HashMap::from([(0_i32, 10_i64), (1, 20)]);
+    let position = SourcePosition::encode(&offsets).unwrap();
+    assert_eq!(position.decode::<HashMap<i32, i64>>().unwrap(), offsets);
+
+    assert_eq!(
+        SourceState::RestartableFromOpId(id).position(),
+        SourceState::Restartable(id.into()).position()
+    );
+}
+
 #[test]
 fn test_handle_to_from_bytes() {
     let original = NodeHandle::new(Some(10), 100.to_string());
@@ -1,6 +1,8 @@
 use crate::errors::types::DeserializationError;
+use crate::helper::json_value_to_nested_field;
 use crate::json_types::{
-    json_cmp, json_from_bytes, json_from_str, json_to_bytes, json_to_bytes_size, JsonValue,
+    json_cmp, json_from_bytes, json_from_str, json_to_bytes, json_to_bytes_size,
+    json_value_to_serde_json, JsonValue,
 };
 use crate::types::{
     DozerDuration, DozerPoint, FieldDefinition, Schema, SourceDefinition, TimeUnit,
@@ -37,6 +39,10 @@ pub enum Field {
     Point(DozerPoint),
     Duration(DozerDuration),
     Null,
+    /// Ordered values, each carrying its own type.
+    List(Vec<Field>),
+    /// Named values in declaration order.
+    Struct(Vec<(String, Field)>),
 }
 
 impl bincode::Decode for Field {
@@ -75,9 +81,11 @@ impl bincode::Decode for Field {
             13 => Ok(Field::Point(DozerPoint::decode(decoder)?)),
             14 => Ok(Field::Duration(DozerDuration::decode(decoder)?)),
             15 => Ok(Field::Null),
+            16 => Ok(Field::List(Vec::decode(decoder)?)),
+            17 => Ok(Field::Struct(Vec::decode(decoder)?)),
             other => Err(bincode::error::DecodeError::UnexpectedVariant {
                 type_name: "Field",
-                allowed: &bincode::error::AllowedEnumVariants::Range { min: 0, max: 15 },
+                allowed: &bincode::error::AllowedEnumVariants::Range { min: 0, max: 17 },
                 found: other,
             }),
         }
@@ -120,9 +128,11 @@ impl<'de> bincode::BorrowDecode<'de> for Field {
             13 => Ok(Field::Point(DozerPoint::borrow_decode(decoder)?)),
             14 => Ok(Field::Duration(DozerDuration::borrow_decode(decoder)?)),
             15 => Ok(Field::Null),
+            16 => Ok(Field::List(Vec::borrow_decode(decoder)?)),
+            17 => Ok(Field::Struct(Vec::borrow_decode(decoder)?)),
             other => Err(bincode::error::DecodeError::UnexpectedVariant {
                 type_name: "Field",
-                allowed: &bincode::error::AllowedEnumVariants::Range { min: 0, max: 15 },
+                allowed: &bincode::error::AllowedEnumVariants::Range { min: 0, max: 17 },
                 found: other,
             }),
         }
@@ -156,6 +166,8 @@ impl bincode::Encode for Field {
             Field::Point(v) => v.encode(encoder),
             Field::Duration(v) => v.encode(encoder),
             Field::Null => Ok(()),
+            Field::List(v) => v.encode(encoder),
+            Field::Struct(v) => v.encode(encoder),
         }
     }
 }
@@ -178,6 +190,8 @@ impl Ord for Field {
             (Self::Json(l), Self::Json(r)) => json_cmp(l, r),
             (Self::Point(l), Self::Point(r)) => l.cmp(r),
             (Self::Duration(l), Self::Duration(r)) => l.cmp(r),
+            (Self::List(l), Self::List(r)) => l.cmp(r),
+            (Self::Struct(l), Self::Struct(r)) => l.cmp(r),
             (Self::Null, Self::Null) => std::cmp::Ordering::Equal,
             (Self::Null, _) => std::cmp::Ordering::Greater,
             (_, Self::Null) => std::cmp::Ordering::Less,
@@ -269,6 +283,11 @@ impl Field {
             Field::Point(_p) => 16,
             Field::Duration(_) => 17,
             Field::Null => 0,
+            Field::List(fields) => fields.iter().map(|f| 4 + f.encoding_len()).sum(),
+            Field::Struct(fields) => fields
+                .iter()
+                .map(|(name, f)| 4 + name.len() + 4 + f.encoding_len())
+                .sum(),
         }
     }
 
@@ -290,6 +309,21 @@ impl Field {
             Field::Point(p) => Cow::Owned(p.to_bytes().into()),
             Field::Duration(d) => Cow::Owned(d.to_bytes().into()),
             Field::Null => Cow::Owned([].into()),
+            Field::List(fields) => {
+                let mut result = Vec::with_capacity(self.data_encoding_len());
+                for field in fields {
+                    write_length_prefixed(&mut result, &field.encode());
+                }
+                Cow::Owned(result)
+            }
+            Field::Struct(fields) => {
+                let mut result = Vec::with_capacity(self.data_encoding_len());
+                for (name, field) in fields {
+                    write_length_prefixed(&mut result, name.as_bytes());
+                    write_length_prefixed(&mut result, &field.encode());
+                }
+                Cow::Owned(result)
+            }
         }
     }
 
@@ -370,6 +404,24 @@ impl Field {
                 DozerDuration::from_bytes(val).map_err(|_| DeserializationError::BadDataLength)?,
             )),
             15 => Ok(Field::Null),
+            16 => {
+                let mut val = val;
+                let mut fields = vec![];
+                while !val.is_empty() {
+                    fields.push(Field::decode(read_length_prefixed(&mut val)?)?);
+                }
+                Ok(Field::List(fields))
+            }
+            17 => {
+                let mut val = val;
+                let mut fields = vec![];
+                while !val.is_empty() {
+                    let name = std::str::from_utf8(read_length_prefixed(&mut val)?)?;
+                    let field = Field::decode(read_length_prefixed(&mut val)?)?;
+                    fields.push((name.to_string(), field));
+                }
+                Ok(Field::Struct(fields))
+            }
             other => Err(DeserializationError::UnrecognisedFieldType(other)),
         }
     }
@@ -392,6 +444,8 @@ impl Field {
             Field::Point(_) => 13,
             Field::Duration(_) => 14,
             Field::Null => 15,
+            Field::List(_) => 16,
+            Field::Struct(_) => 17,
         }
     }
 
@@ -413,6 +467,13 @@ impl Field {
             Field::Point(_) => Some(FieldType::Point),
             Field::Duration(_) => Some(FieldType::Duration),
             Field::Null => None,
+            Field::List(fields) => Some(FieldType::List(Box::new(element_type(fields)))),
+            Field::Struct(fields) => Some(FieldType::Struct(
+                fields
+                    .iter()
+                    .map(|(name, field)| (name.clone(), field.ty().unwrap_or(FieldType::Json)))
+                    .collect(),
+            )),
         }
     }
 
@@ -557,6 +618,24 @@ impl Field {
         }
     }
 
+    pub fn as_list(&self) -> Option<&[Field]> {
+        match self {
+            Field::List(fields) => Some(fields),
+            _ => None,
+        }
+    }
+
+    pub fn as_struct(&self) -> Option<&[(String, Field)]> {
+        match self {
+            Field::Struct(fields) => Some(fields),
+            _ => None,
+        }
+    }
+
+    pub fn is_nested(&self) -> bool {
+        matches!(self, Field::List(_) | Field::Struct(_))
+    }
+
     pub fn as_null(&self) -> Option<()> {
         match self {
             Field::Null => Some(()),
@@ -747,6 +826,16 @@ impl Field {
             Field::String(s) => json_from_str(s.as_str()).ok(),
             Field::Text(t) => Some(t.into()),
             Field::Null => Some(JsonValue::NULL),
+            Field::List(fields) => fields
+                .iter()
+                .map(Field::to_json)
+                .collect::<Option<ijson::IArray>>()
+                .map(Into::into),
+            Field::Struct(fields) => fields
+                .iter()
+                .map(|(name, field)| Some((name.as_str(), field.to_json()?)))
+                .collect::<Option<ijson::IObject>>()
+                .map(Into::into),
             _ => None,
         }
     }
@@ -758,6 +847,36 @@ impl Field {
         }
     }
 
+    pub fn to_list(&self) -> Option<Vec<Field>> {
+        match self {
+            Field::List(fields) => Some(fields.clone()),
+            _ => match self.to_nested()? {
+                Field::List(fields) => Some(fields),
+                _ => None,
+            },
+        }
+    }
+
+    pub fn to_struct(&self) -> Option<Vec<(String, Field)>> {
+        match self {
+            Field::Struct(fields) => Some(fields.clone()),
+            _ => match self.to_nested()? {
+                Field::Struct(fields) => Some(fields),
+                _ => None,
+            },
+        }
+    }
+
+    /// Converts JSON arrays and objects, or strings holding them, to nested fields.
+    fn to_nested(&self) -> Option<Field> {
+        let value = match self {
+            Field::Json(json) => json_value_to_serde_json(json),
+            Field::String(s) | Field::Text(s) => serde_json::from_str(s).ok()?,
+            _ => return None,
+        };
+        Some(json_value_to_nested_field(value))
+    }
+
     pub fn to_duration(&self) -> Option<DozerDuration> {
         match self {
             Field::UInt(d) => Some(DozerDuration(
@@ -794,6 +913,33 @@ impl Field {
     }
 }
 
+/// The type shared by all non-null elements, or `Json` if there is none.
+fn element_type(fields: &[Field]) -> FieldType {
+    let mut types = fields.iter().filter_map(Field::ty);
+    match types.next() {
+        Some(first) if types.all(|typ| typ == first) => first,
+        _ => FieldType::Json,
+    }
+}
+
+fn write_length_prefixed(buf: &mut Vec<u8>, data: &[u8]) {
+    buf.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    buf.extend_from_slice(data);
+}
+
+fn read_length_prefixed<'a>(buf: &mut &'a [u8]) -> Result<&'a [u8], DeserializationError> {
+    let (len, rest) = buf
+        .split_first_chunk::<4>()
+        .ok_or(DeserializationError::BadDataLength)?;
+    let len = u32::from_be_bytes(*len) as usize;
+    if rest.len() < len {
+        return Err(DeserializationError::BadDataLength);
+    }
+    let (data, rest) = rest.split_at(len);
+    *buf = rest;
+    Ok(data)
+}
+
 impl Display for Field {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -821,13 +967,32 @@ impl Display for Field {
             }
             Field::Duration(d) => write!(f, "{:?}", d.0),
             Field::Null => write!(f, ""),
+            Field::List(fields) => {
+                f.write_str("[")?;
+                for (i, field) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{field}")?;
+                }
+                f.write_str("]")
+            }
+            Field::Struct(fields) => {
+                f.write_str("{")?;
+                for (i, (name, field)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{name}: {field}")?;
+                }
+                f.write_str("}")
+            }
         }
     }
 }
 
 #[derive(
     Clone,
-    Copy,
     Serialize,
     Deserialize,
     Debug,
@@ -874,13 +1039,26 @@ pub enum FieldType {
     Point,
     /// Duration up to nanoseconds.
     Duration,
+    /// A list of values of the element type. Elements of mixed or unknown types are `Json`.
+    List(Box<FieldType>),
+    /// Named values of the given types, in declaration order.
+    /// Without any declared fields, a struct may have any fields.
+    Struct(Vec<(String, FieldType)>),
 }
 
 impl TryFrom<&str> for FieldType {
     type Error = String;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let res = match value.to_lowercase().as_str() {
+        let lowercase = value.to_lowercase();
+        if let Some(element) = lowercase
+            .strip_prefix("list<")
+            .and_then(|element| element.strip_suffix('>'))
+        {
+            return FieldType::try_from(element).map(|element| FieldType::List(Box::new(element)));
+        }
+
+        let res = match lowercase.as_str() {
             "uint" => FieldType::UInt,
             "u128" => FieldType::U128,
             "int" => FieldType::Int,
@@ -899,6 +1077,8 @@ impl TryFrom<&str> for FieldType {
             "jsonb_array" => FieldType::Json,
             "point" => FieldType::Point,
             "duration" => FieldType::Duration,
+            "list" | "array" => FieldType::List(Box::new(FieldType::Json)),
+            "struct" => FieldType::Struct(vec![]),
             _ => return Err(format!("Unsupported '{value}' type")),
         };
 
@@ -924,6 +1104,17 @@ impl Display for FieldType {
             FieldType::Json => f.write_str("json"),
             FieldType::Point => f.write_str("point"),
             FieldType::Duration => f.write_str("duration"),
+            FieldType::List(element) => write!(f, "list of {element}"),
+            FieldType::Struct(fields) => {
+                f.write_str("struct {")?;
+                for (i, (name, typ)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{name}: {typ}")?;
+                }
+                f.write_str("}")
+            }
         }
     }
 }
@@ -965,12 +1156,25 @@ pub fn field_test_cases() -> impl Iterator<Item = Field> {
             .into(),
         ),
         Field::Null,
+        Field::List(vec![]),
+        Field::List(vec![
+            Field::UInt(1),
+            Field::Null,
+            Field::String("1".to_string()),
+        ]),
+        Field::Struct(vec![]),
+        Field::Struct(vec![
+            ("a".to_string(), Field::Int(1)),
+            ("b".to_string(), Field::List(vec![Field::Boolean(true)])),
+        ]),
     ]
     .into_iter()
 }
 
 pub fn arrow_field_test_cases() -> impl Iterator<Item = Field> {
-    field_test_cases().filter(|case| !case.is_u128() && !case.is_i128() && !case.is_decimal())
+    field_test_cases().filter(|case| {
+        !case.is_u128() && !case.is_i128() && !case.is_decimal() && !case.is_nested()
+    })
 }
 
 pub fn arrow_field_test_cases_schema() -> Schema {
@@ -1194,6 +1398,14 @@ impl pyo3::ToPyObject for Field {
             Field::Point(_val) => todo!(),
             Field::Duration(_d) => todo!(),
             Field::Null => unreachable!(),
+            Field::List(fields) => pyo3::types::PyList::new(py, fields).to_object(py),
+            Field::Struct(fields) => {
+                let dict = pyo3::types::PyDict::new(py);
+                for (name, field) in fields {
+                    dict.set_item(name, field).unwrap();
+                }
+                dict.to_object(py)
+            }
         }
     }
 }
@@ -8,7 +8,7 @@ use std::hash::Hash;
 use std::str::FromStr;
 
 use crate::errors::types::TypeError;
-use crate::node::OpIdentifier;
+use crate::node::SourcePosition;
 use prettytable::{Cell, Row, Table};
 use serde::{self, Deserialize, Serialize};
 
@@ -299,7 +299,7 @@ pub type PortHandle = u16;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, bincode::Encode, bincode::Decode)]
 pub struct TableOperation {
-    pub id: Option<OpIdentifier>,
+    pub id: Option<SourcePosition>,
     pub op: Operation,
     /// For outputting an operation, node should fill the output port.
     /// For received operation, the port is the input port.